@@ -4,8 +4,8 @@ use libfuzzer_sys::fuzz_target;
 use std::collections::HashMap;
 
 use arbitrary::Arbitrary;
-use ranking::{bm25_score, score_claim, RankSignals};
-use schema::Claim;
+use ranking::{RankSignals, bm25_score, score_claim};
+use schema::claim_builder;
 
 #[derive(Arbitrary, Debug)]
 struct FuzzInput {
@@ -18,29 +18,12 @@ struct FuzzInput {
 }
 
 fn sanitize_f32(value: f32) -> f32 {
-    if value.is_finite() {
-        value
-    } else {
-        0.0
-    }
+    if value.is_finite() { value } else { 0.0 }
 }
 
 fuzz_target!(|input: FuzzInput| {
     let confidence = sanitize_f32(input.confidence).clamp(0.0, 1.0);
-    let claim = Claim {
-        claim_id: "c1".into(),
-        tenant_id: "t1".into(),
-        canonical_text: input.claim_text,
-        confidence,
-        event_time_unix: None,
-        entities: vec![],
-        embedding_ids: vec![],
-        claim_type: None,
-        valid_from: None,
-        valid_to: None,
-        created_at: None,
-        updated_at: None,
-    };
+    let claim = claim_builder("c1", "t1", &input.claim_text, confidence);
     let signals = RankSignals {
         supports: input.supports,
         contradicts: input.contradicts,
@@ -53,8 +36,5 @@ fuzz_target!(|input: FuzzInput| {
     );
 
     let bm = bm25_score(&input.query, &[], &HashMap::new(), 0, 1.0);
-    assert!(
-        bm.is_finite(),
-        "bm25_score produced non-finite score: {bm}"
-    );
+    assert!(bm.is_finite(), "bm25_score produced non-finite score: {bm}");
 });
@@ -8,10 +8,15 @@
 //! `verify_hs256_token_for_tenant`, `encode_hs256_token`,
 //! `encode_hs256_token_with_kid`, `sha256_hex`) is preserved so service-layer
 //! consumers in `services/*/transport/authz.rs` continue to work unchanged.
+//! `verify_hs256_token_scopes_for_tenant` verifies the same way and also
+//! returns the token's scopes, which retrieval uses as the caller's claim
+//! visibility labels.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header,
+};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 
@@ -66,6 +71,19 @@ pub fn verify_hs256_token_for_tenant(
     config: &JwtValidationConfig,
     now_unix_secs: u64,
 ) -> Result<(), JwtValidationError> {
+    verify_hs256_token_scopes_for_tenant(token, tenant_id, config, now_unix_secs).map(|_| ())
+}
+
+/// [`verify_hs256_token_for_tenant`], returning the token's scopes from
+/// its `scope` and `scopes` claims, each a space-separated string or an
+/// array of strings, deduplicated and sorted. A token without either
+/// verifies with no scopes.
+pub fn verify_hs256_token_scopes_for_tenant(
+    token: &str,
+    tenant_id: &str,
+    config: &JwtValidationConfig,
+    now_unix_secs: u64,
+) -> Result<Vec<String>, JwtValidationError> {
     let header = decode_header(token).map_err(map_jwt_error)?;
     if header.alg != Algorithm::HS256 {
         return Err(JwtValidationError::UnsupportedAlgorithm);
@@ -99,7 +117,8 @@ pub fn verify_hs256_token_for_tenant(
         match decode::<Value>(token, &key, &validation) {
             Ok(data) => {
                 check_time_bounds(&data.claims, config, now_unix_secs)?;
-                return check_tenant_allowlist(&data.claims, tenant_id);
+                check_tenant_allowlist(&data.claims, tenant_id)?;
+                return extract_scopes(&data.claims);
             }
             Err(e) => {
                 let mapped = map_jwt_error(e);
@@ -230,6 +249,32 @@ fn extract_tenants(claims: &Value) -> Result<HashSet<String>, JwtValidationError
     Ok(tenants)
 }
 
+fn extract_scopes(claims: &Value) -> Result<Vec<String>, JwtValidationError> {
+    let obj = claims.as_object().ok_or(JwtValidationError::InvalidJson)?;
+    let mut scopes = BTreeSet::new();
+    for key in ["scope", "scopes"] {
+        match obj.get(key) {
+            None => {}
+            Some(Value::String(raw)) => {
+                scopes.extend(raw.split_whitespace().map(ToOwned::to_owned));
+            }
+            Some(Value::Array(items)) => {
+                for item in items {
+                    let Value::String(raw) = item else {
+                        return Err(JwtValidationError::InvalidClaimType(key));
+                    };
+                    let trimmed = raw.trim();
+                    if !trimmed.is_empty() {
+                        scopes.insert(trimmed.to_string());
+                    }
+                }
+            }
+            Some(_) => return Err(JwtValidationError::InvalidClaimType(key)),
+        }
+    }
+    Ok(scopes.into_iter().collect())
+}
+
 fn map_jwt_error(err: jsonwebtoken::errors::Error) -> JwtValidationError {
     use jsonwebtoken::errors::ErrorKind;
     match err.kind() {
@@ -351,7 +396,10 @@ mod tests {
         .unwrap();
         let result =
             verify_hs256_token_for_tenant(&token, "tenant-a", &sample_config(), 1_000_000_000);
-        assert!(result.is_ok(), "expected Ok on the good token, got {result:?}");
+        assert!(
+            result.is_ok(),
+            "expected Ok on the good token, got {result:?}"
+        );
         let mut parts = token
             .split('.')
             .map(ToString::to_string)
@@ -455,7 +503,10 @@ mod tests {
         )
         .unwrap();
         let result = verify_hs256_token_for_tenant(&token, "tenant-b", &sample_config(), 1_000);
-        assert!(result.is_ok(), "expected Ok via tenants array, got {result:?}");
+        assert!(
+            result.is_ok(),
+            "expected Ok via tenants array, got {result:?}"
+        );
     }
 
     #[test]
@@ -469,6 +520,43 @@ mod tests {
         assert!(result.is_ok(), "expected Ok via wildcard, got {result:?}");
     }
 
+    #[test]
+    fn verify_hs256_token_scopes_merges_scope_and_scopes_claims() {
+        let token = encode_hs256_token(
+            r#"{"tenant_id":"tenant-a","iss":"dash","aud":"ingestion","exp":4102444800,"scope":"team:finance  read","scopes":["team:legal","read"]}"#,
+            "secret",
+        )
+        .unwrap();
+        let scopes =
+            verify_hs256_token_scopes_for_tenant(&token, "tenant-a", &sample_config(), 1_000);
+        assert_eq!(
+            scopes,
+            Ok(vec![
+                "read".to_string(),
+                "team:finance".to_string(),
+                "team:legal".to_string()
+            ])
+        );
+
+        let unscoped = encode_hs256_token(
+            r#"{"tenant_id":"tenant-a","iss":"dash","aud":"ingestion","exp":4102444800}"#,
+            "secret",
+        )
+        .unwrap();
+        let scopes =
+            verify_hs256_token_scopes_for_tenant(&unscoped, "tenant-a", &sample_config(), 1_000);
+        assert_eq!(scopes, Ok(Vec::new()));
+
+        let malformed = encode_hs256_token(
+            r#"{"tenant_id":"tenant-a","iss":"dash","aud":"ingestion","exp":4102444800,"scopes":[1]}"#,
+            "secret",
+        )
+        .unwrap();
+        let scopes =
+            verify_hs256_token_scopes_for_tenant(&malformed, "tenant-a", &sample_config(), 1_000);
+        assert_eq!(scopes, Err(JwtValidationError::InvalidClaimType("scopes")));
+    }
+
     #[test]
     fn verify_hs256_token_rejects_missing_tenant_claim() {
         let token = encode_hs256_token(
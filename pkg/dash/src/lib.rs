@@ -0,0 +1,71 @@
+//! The supported public API of DASH.
+//!
+//! The workspace crates (`schema`, `store`, `ranking`, `embeddings`, and
+//! the rest) are free to reshape their public items whenever the services
+//! need them to. This crate re-exports the subset applications can build
+//! on, and that subset follows semver: a release that removes or changes
+//! anything reachable from here bumps the major version, whatever happened
+//! to the crate underneath. Items reached only through the underlying
+//! crates carry no such promise.
+//!
+//! A re-exported type brings its whole public surface under that
+//! promise, not just its name: every public field, inherent method, and
+//! trait implementation of [`InMemoryStore`], [`FileWal`],
+//! [`DashMemory`], [`RetrievalOptions`], and the rest is covered. A
+//! change to `store` that removes or alters any of them is a breaking
+//! change here too, even if no application is known to call it.
+//!
+//! The data, configuration, and error types re-exported here are
+//! `#[non_exhaustive]`, so a minor release may add a field or variant.
+//! The exceptions are [`Stance`], which is closed, and the [`Claim`],
+//! [`Evidence`], and [`ClaimEdge`] records, which callers have always
+//! built as struct literals; a new field on those is a breaking change.
+//! Build the rest with their constructors and builders, such as
+//! [`RetrievalRequest::new`] and `Default` for the policies and configs,
+//! and give matches a wildcard arm.
+//!
+//! Most applications need only the [`prelude`]:
+//!
+//! ```text
+//! use dash::prelude::*;
+//! ```
+//!
+//! [`DashMemory`] is the quickest start, an embedded memory in one data
+//! directory. [`InMemoryStore`] with a [`FileWal`] is the store the
+//! services run, for applications managing their own persistence and
+//! checkpoints.
+
+/// Version of the API re-exported here. It moves with this crate's
+/// version, and only its major part signals a breaking change.
+pub const API_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// The data model.
+pub use schema::{
+    Chunk, Citation, Claim, ClaimEdge, ClaimEdgeBuilder, ClaimId, ClaimType, Document, EdgeId,
+    Entity, Evidence, EvidenceBuilder, EvidenceId, Relation, RetrievalRequest, RetrievalResult,
+    Source, Stance, StanceMode, TenantId, TrustTier, ValidationConfig, ValidationError,
+    is_language_tag, validate_chunk, validate_claim, validate_claim_with, validate_document,
+    validate_edge, validate_evidence, validate_source,
+};
+
+// Storage, persistence, and retrieval.
+pub use store::{
+    CheckpointPolicy, ClaimPatch, FileWal, InMemoryStore, MetadataFilter, RankingConfig,
+    RetrievalOptions, RetrievalOutcome, StoreError, TextAnalyzer, TokenizerKind,
+    WalCheckpointStats, WalWritePolicy,
+};
+
+// The embedded memory.
+pub use store::{DashMemory, DashMemoryConfig, DashMemoryMaintenance};
+
+// Embedding claims and queries.
+pub use embeddings::{EmbeddingError, EmbeddingProvider, HashEmbeddingProvider};
+
+/// The types nearly every application touches.
+pub mod prelude {
+    pub use crate::{
+        Claim, ClaimEdge, DashMemory, DashMemoryConfig, Entity, Evidence, FileWal, InMemoryStore,
+        Relation, RetrievalOptions, RetrievalRequest, RetrievalResult, Stance, StanceMode,
+        StoreError,
+    };
+}
@@ -0,0 +1,18 @@
+use dash::prelude::*;
+
+#[test]
+fn prelude_is_enough_to_remember_and_recall() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut memory: DashMemory = DashMemory::open(DashMemoryConfig::new(dir.path())).unwrap();
+    let claim_id = memory
+        .remember(&"tenant-a".into(), "Company X acquired Company Y")
+        .unwrap();
+    memory.flush().unwrap();
+
+    let reopened = DashMemory::open_dir(dir.path()).unwrap();
+    let results: Vec<RetrievalResult> =
+        reopened.recall(&"tenant-a".into(), "who acquired company y");
+    assert_eq!(results[0].claim_id, claim_id);
+    assert!(reopened.recall(&"tenant-b".into(), "company y").is_empty());
+    assert_eq!(dash::API_VERSION, env!("CARGO_PKG_VERSION"));
+}
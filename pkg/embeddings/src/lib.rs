@@ -15,6 +15,7 @@
 //! `tokio` dependency is gated behind the `async-runtime` feature and is
 //! reserved for future async wrappers.
 
+use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::net::{Shutdown, TcpStream, ToSocketAddrs};
 use std::sync::{Arc, Mutex};
@@ -24,6 +25,7 @@ use serde::Deserialize;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum EmbeddingError {
     #[error("invalid configuration: {0}")]
     InvalidConfig(String),
@@ -171,8 +173,7 @@ impl EmbeddingProvider for OllamaEmbeddingProvider {
                 "prompt": text,
             })
             .to_string();
-            let (status, response_body) =
-                http_post(&self.endpoint, &body, &[], self.timeout)?;
+            let (status, response_body) = http_post(&self.endpoint, &body, &[], self.timeout)?;
             if !(200..300).contains(&status) {
                 return Err(EmbeddingError::Http {
                     status,
@@ -275,7 +276,11 @@ impl EmbeddingProvider for OpenAIEmbeddingProvider {
                 response.data.len()
             )));
         }
-        Ok(response.data.into_iter().map(|item| item.embedding).collect())
+        Ok(response
+            .data
+            .into_iter()
+            .map(|item| item.embedding)
+            .collect())
     }
 }
 
@@ -302,9 +307,9 @@ struct ParsedUrl {
 }
 
 fn parse_url(url: &str) -> Result<ParsedUrl, EmbeddingError> {
-    let (scheme, rest) = url.split_once("://").ok_or_else(|| {
-        EmbeddingError::InvalidConfig(format!("url missing scheme: {url}"))
-    })?;
+    let (scheme, rest) = url
+        .split_once("://")
+        .ok_or_else(|| EmbeddingError::InvalidConfig(format!("url missing scheme: {url}")))?;
 
     let (authority, path) = match rest.find('/') {
         Some(idx) => (&rest[..idx], &rest[idx..]),
@@ -314,9 +319,9 @@ fn parse_url(url: &str) -> Result<ParsedUrl, EmbeddingError> {
     let (host, port) = match authority.rfind(':') {
         Some(idx) => {
             let port_str = &authority[idx + 1..];
-            let port = port_str.parse::<u16>().map_err(|_| {
-                EmbeddingError::InvalidConfig(format!("invalid port: {port_str}"))
-            })?;
+            let port = port_str
+                .parse::<u16>()
+                .map_err(|_| EmbeddingError::InvalidConfig(format!("invalid port: {port_str}")))?;
             (&authority[..idx], port)
         }
         None => {
@@ -345,19 +350,14 @@ fn http_post(
 
     let addr = (parsed.host.as_str(), parsed.port)
         .to_socket_addrs()
-        .map_err(|e| {
-            EmbeddingError::Io(format!(
-                "resolve {}:{}: {}",
-                parsed.host, parsed.port, e
-            ))
-        })?
+        .map_err(|e| EmbeddingError::Io(format!("resolve {}:{}: {}", parsed.host, parsed.port, e)))?
         .next()
         .ok_or_else(|| {
             EmbeddingError::Io(format!("no address for {}:{}", parsed.host, parsed.port))
         })?;
 
-    let stream = TcpStream::connect_timeout(&addr, timeout)
-        .map_err(|e| map_io_error(e, timeout))?;
+    let stream =
+        TcpStream::connect_timeout(&addr, timeout).map_err(|e| map_io_error(e, timeout))?;
     stream.set_read_timeout(Some(timeout)).ok();
     stream.set_write_timeout(Some(timeout)).ok();
 
@@ -387,9 +387,9 @@ fn http_post(
     let response_str = String::from_utf8(response)
         .map_err(|e| EmbeddingError::Parse(format!("response is not utf-8: {e}")))?;
 
-    let (headers, body) = response_str
-        .split_once("\r\n\r\n")
-        .ok_or_else(|| EmbeddingError::Parse("response missing header/body separator".to_string()))?;
+    let (headers, body) = response_str.split_once("\r\n\r\n").ok_or_else(|| {
+        EmbeddingError::Parse("response missing header/body separator".to_string())
+    })?;
 
     let status = parse_status(headers)?;
     Ok((status, body.to_string()))
@@ -591,6 +591,232 @@ impl<P: EmbeddingProvider> EmbeddingProvider for CircuitBreakerProvider<P> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Query embedding cache
+// ---------------------------------------------------------------------------
+//
+// `QueryEmbeddingCache` keeps recent query embeddings per tenant so that a
+// repeated query is not sent to the provider again. Entries are keyed by the
+// query text with case and whitespace normalized away. Each tenant holds at
+// most `max_entries_per_tenant` entries, evicting the least recently used,
+// and an entry older than `ttl` counts as a miss.
+//
+// A tenant's entries belong to the embedding model version recorded with
+// `set_model_version`. Changing the version drops them, so queries against
+// a re-embedded corpus never reuse vectors from the old model.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryEmbeddingCacheConfig {
+    pub max_entries_per_tenant: usize,
+    pub ttl: Duration,
+}
+
+impl Default for QueryEmbeddingCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries_per_tenant: 1024,
+            ttl: Duration::from_secs(600),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryEmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Misses caused by an entry outliving the TTL.
+    pub expirations: u64,
+    pub evictions: u64,
+    /// Entries dropped because their tenant's model version changed.
+    pub invalidations: u64,
+    pub entries: usize,
+}
+
+#[derive(Debug)]
+pub struct QueryEmbeddingCache {
+    config: QueryEmbeddingCacheConfig,
+    state: Mutex<QueryCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct QueryCacheState {
+    tenants: HashMap<String, TenantQueryCache>,
+    stats: QueryEmbeddingCacheStats,
+}
+
+#[derive(Debug, Default)]
+struct TenantQueryCache {
+    model_version: Option<String>,
+    entries: HashMap<String, CachedQueryEmbedding>,
+    /// Monotonic use counter; the entry with the lowest `last_used` is
+    /// evicted first.
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct CachedQueryEmbedding {
+    vector: Vec<f32>,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+impl QueryEmbeddingCache {
+    pub fn new(config: QueryEmbeddingCacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(QueryCacheState::default()),
+        }
+    }
+
+    pub fn config(&self) -> QueryEmbeddingCacheConfig {
+        self.config
+    }
+
+    /// Record the embedding model version `tenant_id`'s vectors come from.
+    /// Returns `true` when this changed a previously recorded version and
+    /// the tenant's cached embeddings were dropped.
+    pub fn set_model_version(&self, tenant_id: &str, model_version: &str) -> bool {
+        let mut state = self.state.lock().expect("query cache mutex poisoned");
+        let state = &mut *state;
+        let tenant = state.tenants.entry(tenant_id.to_string()).or_default();
+        match tenant.model_version.as_deref() {
+            Some(current) if current == model_version => false,
+            previous => {
+                let changed = previous.is_some();
+                if changed {
+                    state.stats.invalidations += tenant.entries.len() as u64;
+                    tenant.entries.clear();
+                }
+                tenant.model_version = Some(model_version.to_string());
+                changed
+            }
+        }
+    }
+
+    pub fn model_version(&self, tenant_id: &str) -> Option<String> {
+        let state = self.state.lock().expect("query cache mutex poisoned");
+        state.tenants.get(tenant_id)?.model_version.clone()
+    }
+
+    pub fn get(&self, tenant_id: &str, query: &str) -> Option<Vec<f32>> {
+        let key = normalize_query_text(query);
+        let mut state = self.state.lock().expect("query cache mutex poisoned");
+        let state = &mut *state;
+        let Some(tenant) = state.tenants.get_mut(tenant_id) else {
+            state.stats.misses += 1;
+            return None;
+        };
+        let expired = match tenant.entries.get(&key) {
+            None => {
+                state.stats.misses += 1;
+                return None;
+            }
+            Some(entry) => entry.inserted_at.elapsed() >= self.config.ttl,
+        };
+        if expired {
+            tenant.entries.remove(&key);
+            state.stats.misses += 1;
+            state.stats.expirations += 1;
+            return None;
+        }
+        tenant.clock += 1;
+        let entry = tenant.entries.get_mut(&key)?;
+        entry.last_used = tenant.clock;
+        state.stats.hits += 1;
+        Some(entry.vector.clone())
+    }
+
+    pub fn insert(&self, tenant_id: &str, query: &str, vector: Vec<f32>) {
+        if self.config.max_entries_per_tenant == 0 {
+            return;
+        }
+        let key = normalize_query_text(query);
+        let mut state = self.state.lock().expect("query cache mutex poisoned");
+        let state = &mut *state;
+        let tenant = state.tenants.entry(tenant_id.to_string()).or_default();
+        if !tenant.entries.contains_key(&key)
+            && tenant.entries.len() >= self.config.max_entries_per_tenant
+        {
+            let oldest = tenant
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                tenant.entries.remove(&oldest);
+                state.stats.evictions += 1;
+            }
+        }
+        tenant.clock += 1;
+        tenant.entries.insert(
+            key,
+            CachedQueryEmbedding {
+                vector,
+                inserted_at: Instant::now(),
+                last_used: tenant.clock,
+            },
+        );
+    }
+
+    /// The cached embedding of `query`, or embed it with `provider` and
+    /// cache the result.
+    pub fn embed_query(
+        &self,
+        tenant_id: &str,
+        query: &str,
+        provider: &dyn EmbeddingProvider,
+    ) -> Result<Vec<f32>, EmbeddingError> {
+        if let Some(vector) = self.get(tenant_id, query) {
+            return Ok(vector);
+        }
+        let vector = provider
+            .embed(&[query.to_string()])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| EmbeddingError::Parse("provider returned no embedding".to_string()))?;
+        self.insert(tenant_id, query, vector.clone());
+        Ok(vector)
+    }
+
+    /// Drop every cached embedding of `tenant_id`, keeping its model version.
+    pub fn invalidate_tenant(&self, tenant_id: &str) {
+        let mut state = self.state.lock().expect("query cache mutex poisoned");
+        let state = &mut *state;
+        if let Some(tenant) = state.tenants.get_mut(tenant_id) {
+            state.stats.invalidations += tenant.entries.len() as u64;
+            tenant.entries.clear();
+        }
+    }
+
+    pub fn stats(&self) -> QueryEmbeddingCacheStats {
+        let state = self.state.lock().expect("query cache mutex poisoned");
+        QueryEmbeddingCacheStats {
+            entries: state
+                .tenants
+                .values()
+                .map(|tenant| tenant.entries.len())
+                .sum(),
+            ..state.stats
+        }
+    }
+}
+
+impl Default for QueryEmbeddingCache {
+    fn default() -> Self {
+        Self::new(QueryEmbeddingCacheConfig::default())
+    }
+}
+
+/// Cache key for `query`: lowercased, with runs of whitespace collapsed to
+/// one space and the ends trimmed.
+pub fn normalize_query_text(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -683,21 +909,17 @@ mod tests {
 
     #[test]
     fn openai_provider_rejects_empty_api_key() {
-        let err = OpenAIEmbeddingProvider::new(
-            "text-embedding-3-small".to_string(),
-            "".to_string(),
-        )
-        .unwrap_err();
+        let err =
+            OpenAIEmbeddingProvider::new("text-embedding-3-small".to_string(), "".to_string())
+                .unwrap_err();
         match err {
             EmbeddingError::InvalidConfig(_) => {}
             other => panic!("expected InvalidConfig, got {other:?}"),
         }
 
-        let err_whitespace = OpenAIEmbeddingProvider::new(
-            "text-embedding-3-small".to_string(),
-            "   ".to_string(),
-        )
-        .unwrap_err();
+        let err_whitespace =
+            OpenAIEmbeddingProvider::new("text-embedding-3-small".to_string(), "   ".to_string())
+                .unwrap_err();
         match err_whitespace {
             EmbeddingError::InvalidConfig(_) => {}
             other => panic!("expected InvalidConfig, got {other:?}"),
@@ -706,8 +928,7 @@ mod tests {
 
     #[test]
     fn openai_provider_parses_valid_response() {
-        let body_json =
-            r#"{"data":[{"embedding":[0.1,0.2,0.3]},{"embedding":[0.4,0.5,0.6]}]}"#;
+        let body_json = r#"{"data":[{"embedding":[0.1,0.2,0.3]},{"embedding":[0.4,0.5,0.6]}]}"#;
         let response = format!(
             "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
             body_json.len(),
@@ -757,7 +978,11 @@ mod tests {
     }
 
     impl CountingProvider {
-        fn new(name: &'static str, dims: usize, outcomes: Vec<Result<Vec<Vec<f32>>, EmbeddingError>>) -> Self {
+        fn new(
+            name: &'static str,
+            dims: usize,
+            outcomes: Vec<Result<Vec<Vec<f32>>, EmbeddingError>>,
+        ) -> Self {
             Self {
                 name,
                 dims,
@@ -778,7 +1003,8 @@ mod tests {
             self.dims
         }
         fn embed(&self, _texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
-            self.calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             let mut outcomes = self.outcomes.lock().expect("outcomes mutex poisoned");
             if outcomes.is_empty() {
                 panic!("CountingProvider ran out of scripted outcomes");
@@ -883,7 +1109,9 @@ mod tests {
 
         // Now the breaker is open, the third call should be short-circuited
         // and return CircuitOpen without consulting the inner provider.
-        let err3 = wrapped.embed(&["c".to_string()]).expect_err("third short-circuits");
+        let err3 = wrapped
+            .embed(&["c".to_string()])
+            .expect_err("third short-circuits");
         match err3 {
             EmbeddingError::CircuitOpen { .. } => {}
             other => panic!("expected CircuitOpen, got {other:?}"),
@@ -918,9 +1146,7 @@ mod tests {
         std::thread::sleep(Duration::from_millis(40));
         // Probe call: breaker is half-open, allows the call, inner succeeds,
         // breaker closes.
-        let r = wrapped
-            .embed(&["c".to_string()])
-            .expect("probe succeeds");
+        let r = wrapped.embed(&["c".to_string()]).expect("probe succeeds");
         assert_eq!(r, vec![vec![1.0, 0.0, 0.0, 0.0]]);
         assert_eq!(breaker.state(), CircuitState::Closed);
 
@@ -942,4 +1168,58 @@ mod tests {
         assert_eq!(wrapped.dimensions(), 96);
         assert_eq!(wrapped.name(), "hash");
     }
+
+    #[test]
+    fn query_cache_reuses_embeddings_for_normalized_queries() {
+        let provider = CountingProvider::new("counting", 2, vec![Ok(vec![vec![1.0, 0.0]])]);
+        let cache = QueryEmbeddingCache::default();
+        let first = cache
+            .embed_query("tenant-a", "Who founded  Acme?", &provider)
+            .expect("first call embeds");
+        let second = cache
+            .embed_query("tenant-a", "  who founded acme? ", &provider)
+            .expect("second call hits the cache");
+        assert_eq!(first, second);
+        assert_eq!(provider.calls(), 1);
+        // Tenants do not share entries.
+        assert_eq!(cache.get("tenant-b", "who founded acme?"), None);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 1));
+    }
+
+    #[test]
+    fn query_cache_evicts_least_recently_used_and_expires_entries() {
+        let cache = QueryEmbeddingCache::new(QueryEmbeddingCacheConfig {
+            max_entries_per_tenant: 2,
+            ttl: Duration::from_millis(50),
+        });
+        cache.insert("tenant-a", "a", vec![1.0]);
+        cache.insert("tenant-a", "b", vec![2.0]);
+        assert_eq!(cache.get("tenant-a", "a"), Some(vec![1.0]));
+        cache.insert("tenant-a", "c", vec![3.0]);
+        assert_eq!(cache.get("tenant-a", "b"), None);
+        assert_eq!(cache.get("tenant-a", "a"), Some(vec![1.0]));
+        assert_eq!(cache.stats().evictions, 1);
+
+        std::thread::sleep(Duration::from_millis(70));
+        assert_eq!(cache.get("tenant-a", "c"), None);
+        let stats = cache.stats();
+        assert_eq!((stats.expirations, stats.entries), (1, 1));
+    }
+
+    #[test]
+    fn query_cache_drops_tenant_entries_when_model_version_changes() {
+        let cache = QueryEmbeddingCache::default();
+        assert!(!cache.set_model_version("tenant-a", "v1"));
+        cache.insert("tenant-a", "q", vec![1.0]);
+        cache.insert("tenant-b", "q", vec![2.0]);
+        assert!(!cache.set_model_version("tenant-a", "v1"));
+        assert_eq!(cache.get("tenant-a", "q"), Some(vec![1.0]));
+
+        assert!(cache.set_model_version("tenant-a", "v2"));
+        assert_eq!(cache.model_version("tenant-a").as_deref(), Some("v2"));
+        assert_eq!(cache.get("tenant-a", "q"), None);
+        assert_eq!(cache.get("tenant-b", "q"), Some(vec![2.0]));
+        assert_eq!(cache.stats().invalidations, 1);
+    }
 }
@@ -6,6 +6,9 @@ use schema::{ClaimEdge, Relation};
 pub struct EdgeSummary {
     pub supports: usize,
     pub contradicts: usize,
+    /// Claims this one replaces.
+    pub supersedes: usize,
+    pub elaborates: usize,
     pub total_strength: f32,
 }
 
@@ -13,6 +16,8 @@ pub fn summarize_edges(edges: &[ClaimEdge]) -> EdgeSummary {
     let mut summary = EdgeSummary {
         supports: 0,
         contradicts: 0,
+        supersedes: 0,
+        elaborates: 0,
         total_strength: 0.0,
     };
 
@@ -21,6 +26,8 @@ pub fn summarize_edges(edges: &[ClaimEdge]) -> EdgeSummary {
         match edge.relation {
             Relation::Supports => summary.supports += 1,
             Relation::Contradicts => summary.contradicts += 1,
+            Relation::Supersedes => summary.supersedes += 1,
+            Relation::Elaborates => summary.elaborates += 1,
             _ => {}
         }
     }
@@ -60,11 +67,11 @@ pub fn traverse_edges_multi_hop(
             continue;
         }
         for edge in outgoing.get(claim_id.as_str()).into_iter().flatten() {
-            if seen_edges.insert(edge.edge_id.clone()) {
+            if seen_edges.insert(edge.edge_id.to_string()) {
                 out.push((*edge).clone());
             }
-            if visited_nodes.insert(edge.to_claim_id.clone()) {
-                queue.push_back((edge.to_claim_id.clone(), hop + 1));
+            if visited_nodes.insert(edge.to_claim_id.to_string()) {
+                queue.push_back((edge.to_claim_id.to_string(), hop + 1));
             }
         }
     }
@@ -90,6 +97,10 @@ pub struct GraphReasoningConfig {
     pub refine_edge_weight: f32,
     pub depends_on_edge_weight: f32,
     pub duplicate_edge_weight: f32,
+    /// Replacing a claim says nothing about whether it held, so this
+    /// defaults to no influence.
+    pub supersede_edge_weight: f32,
+    pub elaborate_edge_weight: f32,
 }
 
 impl Default for GraphReasoningConfig {
@@ -104,6 +115,8 @@ impl Default for GraphReasoningConfig {
             refine_edge_weight: 0.55,
             depends_on_edge_weight: 0.35,
             duplicate_edge_weight: 0.2,
+            supersede_edge_weight: 0.0,
+            elaborate_edge_weight: 0.45,
         }
     }
 }
@@ -145,8 +158,8 @@ pub fn compute_node_reasoning_with_config(
             .entry(edge.from_claim_id.as_str())
             .or_default()
             .push(edge);
-        nodes.insert(edge.from_claim_id.clone());
-        nodes.insert(edge.to_claim_id.clone());
+        nodes.insert(edge.from_claim_id.to_string());
+        nodes.insert(edge.to_claim_id.to_string());
     }
 
     let incoming_weight = compute_weighted_incoming_signals(
@@ -205,7 +218,7 @@ fn compute_weighted_incoming_signals(
             let relation_weight = relation_weight(&edge.relation, config);
             let weighted = relation_weight * edge.strength * depth_factor;
             incoming_weight
-                .entry(edge.to_claim_id.clone())
+                .entry(edge.to_claim_id.to_string())
                 .and_modify(|value| *value += weighted)
                 .or_insert(weighted);
         }
@@ -235,11 +248,11 @@ fn compute_min_hop_depths(
         for edge in outgoing.get(claim_id.as_str()).into_iter().flatten() {
             let next_depth = depth + 1;
             let should_update = min_depth
-                .get(&edge.to_claim_id)
+                .get(edge.to_claim_id.as_str())
                 .is_none_or(|existing| next_depth < *existing);
             if should_update {
-                min_depth.insert(edge.to_claim_id.clone(), next_depth);
-                queue.push_back((edge.to_claim_id.clone(), next_depth));
+                min_depth.insert(edge.to_claim_id.to_string(), next_depth);
+                queue.push_back((edge.to_claim_id.to_string(), next_depth));
             }
         }
     }
@@ -276,7 +289,7 @@ fn compute_support_path_count(
                 if !matches!(edge.relation, Relation::Supports) {
                     continue;
                 }
-                next.entry(edge.to_claim_id.clone())
+                next.entry(edge.to_claim_id.to_string())
                     .and_modify(|count| *count = count.saturating_add(path_count))
                     .or_insert(path_count);
             }
@@ -318,7 +331,7 @@ fn compute_contradiction_chain_depth(
                 } else {
                     0
                 };
-                next.entry(edge.to_claim_id.clone())
+                next.entry(edge.to_claim_id.to_string())
                     .and_modify(|existing| {
                         if next_depth > *existing {
                             *existing = next_depth;
@@ -350,6 +363,11 @@ fn relation_weight(relation: &Relation, config: GraphReasoningConfig) -> f32 {
         Relation::Refines => config.refine_edge_weight,
         Relation::DependsOn => config.depends_on_edge_weight,
         Relation::Duplicates => config.duplicate_edge_weight,
+        Relation::Supersedes => config.supersede_edge_weight,
+        Relation::Elaborates => config.elaborate_edge_weight,
+        // A relation added after this crate was written carries no weight
+        // until it is given one here.
+        _ => 0.0,
     }
 }
 
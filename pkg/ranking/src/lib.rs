@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use schema::{Claim, tokenize};
 
@@ -8,6 +9,57 @@ pub struct RankSignals {
     pub contradicts: usize,
 }
 
+/// Support and contradiction as weighted sums, where each piece of
+/// evidence counts for its [`RankingConfig::evidence_weight`] instead of
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WeightedSignals {
+    pub supports: f32,
+    pub contradicts: f32,
+}
+
+impl From<RankSignals> for WeightedSignals {
+    fn from(signals: RankSignals) -> Self {
+        Self {
+            supports: signals.supports as f32,
+            contradicts: signals.contradicts as f32,
+        }
+    }
+}
+
+/// How claims are ranked beyond the fixed score weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct RankingConfig {
+    /// Age at which evidence counts half as much toward support and
+    /// contradiction, decaying exponentially. `None` counts all evidence
+    /// fully, whatever its age.
+    pub freshness_half_life: Option<Duration>,
+}
+
+impl RankingConfig {
+    pub fn with_freshness_half_life(mut self, half_life: Duration) -> Self {
+        self.freshness_half_life = Some(half_life);
+        self
+    }
+
+    /// Weight in `(0, 1]` of evidence ingested at `ingested_at_ms` as of
+    /// `now_ms`, both epoch millis. Evidence without an ingest time, or
+    /// stamped in the future, counts fully.
+    pub fn evidence_weight(&self, ingested_at_ms: Option<i64>, now_ms: i64) -> f32 {
+        let (Some(half_life), Some(ingested_at_ms)) = (self.freshness_half_life, ingested_at_ms)
+        else {
+            return 1.0;
+        };
+        let half_life_ms = half_life.as_millis() as f64;
+        let age_ms = now_ms.saturating_sub(ingested_at_ms);
+        if age_ms <= 0 || half_life_ms <= 0.0 {
+            return 1.0;
+        }
+        0.5_f64.powf(age_ms as f64 / half_life_ms) as f32
+    }
+}
+
 pub fn lexical_overlap_score(query: &str, text: &str) -> f32 {
     let query_tokens: Vec<String> = tokenize(query);
     if query_tokens.is_empty() {
@@ -31,12 +83,28 @@ pub fn bm25_score(
     doc_freq: &HashMap<String, usize>,
     total_docs: usize,
     avg_doc_len: f32,
+) -> f32 {
+    bm25_score_tokens(
+        &tokenize(query),
+        doc_tokens,
+        doc_freq,
+        total_docs,
+        avg_doc_len,
+    )
+}
+
+/// [`bm25_score`] for a query already split into tokens, for callers
+/// that analyze text their own way.
+pub fn bm25_score_tokens(
+    query_tokens: &[String],
+    doc_tokens: &[String],
+    doc_freq: &HashMap<String, usize>,
+    total_docs: usize,
+    avg_doc_len: f32,
 ) -> f32 {
     if total_docs == 0 || doc_tokens.is_empty() || avg_doc_len <= f32::EPSILON {
         return 0.0;
     }
-
-    let query_tokens = tokenize(query);
     if query_tokens.is_empty() {
         return 0.0;
     }
@@ -57,7 +125,7 @@ pub fn bm25_score(
             continue;
         }
 
-        let df = doc_freq.get(&token).copied().unwrap_or(0) as f32;
+        let df = doc_freq.get(token).copied().unwrap_or(0) as f32;
         let idf = (((total_docs as f32 - df + 0.5) / (df + 0.5)) + 1.0).ln();
         let denom = term_tf + k1 * (1.0 - b + b * (doc_len / avg_doc_len));
         score += idf * ((term_tf * (k1 + 1.0)) / denom.max(f32::EPSILON));
@@ -65,6 +133,33 @@ pub fn bm25_score(
     score.max(0.0)
 }
 
+/// The weighted terms [`score_claim`] adds up, kept apart so a score can
+/// be explained.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScoreBreakdown {
+    /// Unweighted [`lexical_overlap_score`], in `[0, 1]`.
+    pub lexical_overlap: f32,
+    pub support: f32,
+    /// Subtracted from the score.
+    pub contradiction_penalty: f32,
+    pub quality: f32,
+    pub confidence: f32,
+}
+
+impl ScoreBreakdown {
+    /// The [`score_claim`] score.
+    pub fn total(&self) -> f32 {
+        (self.lexical_overlap * 0.6) + self.support - self.contradiction_penalty
+            + self.quality
+            + self.confidence
+    }
+
+    /// The [`score_claim_with_bm25`] score.
+    pub fn total_with_bm25(&self, bm25: f32) -> f32 {
+        (self.total() * 0.72) + (bm25 * 0.28)
+    }
+}
+
 pub fn score_claim_with_bm25(
     query: &str,
     claim: &Claim,
@@ -72,8 +167,7 @@ pub fn score_claim_with_bm25(
     signals: RankSignals,
     bm25: f32,
 ) -> f32 {
-    let base = score_claim(query, claim, avg_source_quality, signals);
-    (base * 0.72) + (bm25 * 0.28)
+    score_breakdown(query, claim, avg_source_quality, signals).total_with_bm25(bm25)
 }
 
 pub fn score_claim(
@@ -82,13 +176,33 @@ pub fn score_claim(
     avg_source_quality: f32,
     signals: RankSignals,
 ) -> f32 {
-    let semantic = lexical_overlap_score(query, &claim.canonical_text);
-    let support_score = signals.supports as f32 * 0.08;
-    let contradiction_penalty = signals.contradicts as f32 * 0.1;
-    let quality = avg_source_quality * 0.15;
-    let confidence = claim.confidence * 0.25;
+    score_breakdown(query, claim, avg_source_quality, signals).total()
+}
+
+pub fn score_breakdown(
+    query: &str,
+    claim: &Claim,
+    avg_source_quality: f32,
+    signals: RankSignals,
+) -> ScoreBreakdown {
+    score_breakdown_weighted(query, claim, avg_source_quality, signals.into())
+}
 
-    (semantic * 0.6) + support_score - contradiction_penalty + quality + confidence
+/// [`score_breakdown`] with fractional support and contradiction, as
+/// weighted by freshness.
+pub fn score_breakdown_weighted(
+    query: &str,
+    claim: &Claim,
+    avg_source_quality: f32,
+    signals: WeightedSignals,
+) -> ScoreBreakdown {
+    ScoreBreakdown {
+        lexical_overlap: lexical_overlap_score(query, &claim.canonical_text),
+        support: signals.supports * 0.08,
+        contradiction_penalty: signals.contradicts * 0.1,
+        quality: avg_source_quality * 0.15,
+        confidence: claim.confidence * 0.25,
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +232,10 @@ mod tests {
             valid_to: None,
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         };
 
         let with_support = score_claim(
@@ -155,4 +273,17 @@ mod tests {
         let b = bm25_score(query, &doc_b, &df, 2, 4.5);
         assert!(a > b);
     }
+
+    #[test]
+    fn evidence_weight_halves_every_half_life() {
+        let day_ms = 86_400_000;
+        let config = RankingConfig::default().with_freshness_half_life(Duration::from_secs(86_400));
+        let now = 100 * day_ms;
+        assert_eq!(config.evidence_weight(Some(now), now), 1.0);
+        assert!((config.evidence_weight(Some(now - day_ms), now) - 0.5).abs() < 1e-6);
+        assert!((config.evidence_weight(Some(now - 2 * day_ms), now) - 0.25).abs() < 1e-6);
+        assert_eq!(config.evidence_weight(None, now), 1.0);
+        assert_eq!(config.evidence_weight(Some(now + day_ms), now), 1.0);
+        assert_eq!(RankingConfig::default().evidence_weight(Some(0), now), 1.0);
+    }
 }
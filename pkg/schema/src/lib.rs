@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Deliberately exhaustive: evidence either supports a claim,
+/// contradicts it, or neither.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Stance {
@@ -14,24 +16,55 @@ pub enum Stance {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum Relation {
     Supports,
     Contradicts,
     Refines,
     Duplicates,
     DependsOn,
+    /// The source claim replaces the target, such as a corrected figure
+    /// or a later status. Retrieval skips superseded claims by default.
+    Supersedes,
+    /// The source claim adds detail to the target without changing it.
+    Elaborates,
+}
+
+impl Relation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Relation::Supports => "supports",
+            Relation::Contradicts => "contradicts",
+            Relation::Refines => "refines",
+            Relation::Duplicates => "duplicates",
+            Relation::DependsOn => "depends_on",
+            Relation::Supersedes => "supersedes",
+            Relation::Elaborates => "elaborates",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum StanceMode {
     Balanced,
     SupportOnly,
 }
 
+impl StanceMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StanceMode::Balanced => "balanced",
+            StanceMode::SupportOnly => "support_only",
+        }
+    }
+}
+
 /// The kind of claim: factual assertion, opinion, prediction, etc.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum ClaimType {
     Factual,
     Opinion,
@@ -40,6 +73,198 @@ pub enum ClaimType {
     Causal,
 }
 
+impl ClaimType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClaimType::Factual => "factual",
+            ClaimType::Opinion => "opinion",
+            ClaimType::Prediction => "prediction",
+            ClaimType::Temporal => "temporal",
+            ClaimType::Causal => "causal",
+        }
+    }
+}
+
+/// How far a registered [`Source`] is trusted. Each tier caps the
+/// quality ranking gives the source's evidence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum TrustTier {
+    Authoritative,
+    Trusted,
+    #[default]
+    Standard,
+    Untrusted,
+}
+
+impl TrustTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrustTier::Authoritative => "authoritative",
+            TrustTier::Trusted => "trusted",
+            TrustTier::Standard => "standard",
+            TrustTier::Untrusted => "untrusted",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "authoritative" => Some(TrustTier::Authoritative),
+            "trusted" => Some(TrustTier::Trusted),
+            "standard" => Some(TrustTier::Standard),
+            "untrusted" => Some(TrustTier::Untrusted),
+            _ => None,
+        }
+    }
+
+    /// The highest quality a source of this tier is ranked at.
+    pub fn max_quality(self) -> f32 {
+        match self {
+            TrustTier::Authoritative => 1.0,
+            TrustTier::Trusted => 0.9,
+            TrustTier::Standard => 0.7,
+            TrustTier::Untrusted => 0.2,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Identifiers
+// ---------------------------------------------------------------------------
+
+/// Declares a string id newtype. Ids serialize as bare strings, deref
+/// to `str`, and compare equal to plain strings, so they drop into
+/// `&str` parameters and string-keyed maps while keeping one kind of
+/// id from being passed where another is expected.
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::borrow::Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(id: &String) -> Self {
+                Self(id.clone())
+            }
+        }
+
+        impl From<&$name> for $name {
+            fn from(id: &$name) -> Self {
+                id.clone()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+
+        impl PartialEq<$name> for String {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialEq<$name> for &str {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+    };
+}
+
+string_id!(
+    /// Identifies a claim within its tenant.
+    ClaimId
+);
+string_id!(
+    /// Identifies a tenant, the unit of data isolation.
+    TenantId
+);
+string_id!(
+    /// Identifies a piece of evidence.
+    EvidenceId
+);
+string_id!(
+    /// Identifies an edge between two claims.
+    EdgeId
+);
+
 // ---------------------------------------------------------------------------
 // Core domain types — architecture §6
 // ---------------------------------------------------------------------------
@@ -47,14 +272,16 @@ pub enum ClaimType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Claim {
-    pub claim_id: String,
-    pub tenant_id: String,
+    pub claim_id: ClaimId,
+    pub tenant_id: TenantId,
     pub canonical_text: String,
     pub confidence: f32,
     #[serde(default)]
     pub event_time_unix: Option<i64>,
+    /// Entities the claim names. Plain strings deserialize as untyped
+    /// entities, so older payloads still load.
     #[serde(default)]
-    pub entities: Vec<String>,
+    pub entities: Vec<Entity>,
     #[serde(default)]
     pub embedding_ids: Vec<String>,
     /// Architecture §6.1 — optional claim classification.
@@ -72,13 +299,140 @@ pub struct Claim {
     /// Epoch‐millis of the most recent update.
     #[serde(default)]
     pub updated_at: Option<i64>,
+    /// Free-form `(key, value)` labels such as source system or project.
+    /// A key may repeat with different values.
+    #[serde(default)]
+    pub metadata: Vec<(String, String)>,
+    /// Labels such as `team:finance` restricting who may retrieve the
+    /// claim; empty means every caller of the tenant may.
+    #[serde(default)]
+    pub visibility_labels: Vec<String>,
+    /// BCP-47 tag of the language `canonical_text` is written in, such
+    /// as `en` or `pt-BR`.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// The corpus within the tenant the claim belongs to, such as
+    /// `support-tickets` or `contracts`; `None` is the default corpus.
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+impl Claim {
+    /// A claim with every optional field unset; the `with_*` methods
+    /// fill them in.
+    pub fn new(
+        claim_id: impl Into<ClaimId>,
+        tenant_id: impl Into<TenantId>,
+        canonical_text: impl Into<String>,
+        confidence: f32,
+    ) -> Self {
+        Self {
+            claim_id: claim_id.into(),
+            tenant_id: tenant_id.into(),
+            canonical_text: canonical_text.into(),
+            confidence,
+            event_time_unix: None,
+            entities: Vec::new(),
+            embedding_ids: Vec::new(),
+            claim_type: None,
+            valid_from: None,
+            valid_to: None,
+            created_at: None,
+            updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
+        }
+    }
+
+    /// Whether a caller holding `labels` may see the claim: it carries no
+    /// visibility labels, or one of them is in `labels`.
+    pub fn is_visible_to(&self, labels: &[String]) -> bool {
+        self.visibility_labels.is_empty()
+            || labels
+                .iter()
+                .any(|label| self.visibility_labels.contains(label))
+    }
+
+    pub fn with_event_time(mut self, event_time_unix: i64) -> Self {
+        self.event_time_unix = Some(event_time_unix);
+        self
+    }
+
+    pub fn with_entities(mut self, entities: impl IntoIterator<Item = impl Into<Entity>>) -> Self {
+        self.entities = entities.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_embedding_ids(
+        mut self,
+        embedding_ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.embedding_ids = embedding_ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_claim_type(mut self, claim_type: ClaimType) -> Self {
+        self.claim_type = Some(claim_type);
+        self
+    }
+
+    pub fn with_valid_from(mut self, valid_from: i64) -> Self {
+        self.valid_from = Some(valid_from);
+        self
+    }
+
+    pub fn with_valid_to(mut self, valid_to: i64) -> Self {
+        self.valid_to = Some(valid_to);
+        self
+    }
+
+    pub fn with_created_at(mut self, created_at: i64) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    pub fn with_updated_at(mut self, updated_at: i64) -> Self {
+        self.updated_at = Some(updated_at);
+        self
+    }
+
+    pub fn with_metadata(
+        mut self,
+        metadata: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.metadata = metadata
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        self
+    }
+
+    pub fn with_visibility_labels(
+        mut self,
+        labels: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.visibility_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    pub fn with_collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = Some(collection.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Evidence {
-    pub evidence_id: String,
-    pub claim_id: String,
+    pub evidence_id: EvidenceId,
+    pub claim_id: ClaimId,
     pub source_id: String,
     pub stance: Stance,
     pub source_quality: f32,
@@ -97,14 +451,72 @@ pub struct Evidence {
     /// Epoch‐millis when this evidence was first ingested.
     #[serde(default)]
     pub ingested_at: Option<i64>,
+    /// BCP-47 tag of the language of the cited text.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// How strongly the cited text takes its stance, in `0..=1`, so
+    /// "weakly supports" can be told from "supports". `None` is full
+    /// strength.
+    #[serde(default)]
+    pub stance_strength: Option<f32>,
+    /// The cited text states the claim's negation, as in "X did not
+    /// acquire Y", so its stance counts reversed: a negated supporting
+    /// passage denies the claim.
+    #[serde(default)]
+    pub negated: bool,
+}
+
+impl Evidence {
+    /// Evidence with every optional field unset, unvalidated; prefer
+    /// [`EvidenceBuilder`] outside of decoding stored records.
+    pub fn new(
+        evidence_id: impl Into<EvidenceId>,
+        claim_id: impl Into<ClaimId>,
+        source_id: impl Into<String>,
+        stance: Stance,
+        source_quality: f32,
+    ) -> Self {
+        Self {
+            evidence_id: evidence_id.into(),
+            claim_id: claim_id.into(),
+            source_id: source_id.into(),
+            stance,
+            source_quality,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        }
+    }
+
+    /// The stance the evidence takes on the claim: `stance`, reversed
+    /// when `negated`. Neutral evidence stays neutral.
+    pub fn effective_stance(&self) -> Stance {
+        match (&self.stance, self.negated) {
+            (Stance::Supports, true) => Stance::Contradicts,
+            (Stance::Contradicts, true) => Stance::Supports,
+            (stance, _) => stance.clone(),
+        }
+    }
+
+    /// How much the evidence counts toward support or contradiction:
+    /// its stance strength, or 1.
+    pub fn stance_weight(&self) -> f32 {
+        self.stance_strength.unwrap_or(1.0)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct ClaimEdge {
-    pub edge_id: String,
-    pub from_claim_id: String,
-    pub to_claim_id: String,
+    pub edge_id: EdgeId,
+    pub from_claim_id: ClaimId,
+    pub to_claim_id: ClaimId,
     pub relation: Relation,
     pub strength: f32,
     /// Architecture §6.3 — human‐readable reason codes for the edge.
@@ -115,36 +527,283 @@ pub struct ClaimEdge {
     pub created_at: Option<i64>,
 }
 
+impl ClaimEdge {
+    /// An edge with no reason codes or creation time, unvalidated;
+    /// prefer [`ClaimEdgeBuilder`] outside of decoding stored records.
+    pub fn new(
+        edge_id: impl Into<EdgeId>,
+        from_claim_id: impl Into<ClaimId>,
+        to_claim_id: impl Into<ClaimId>,
+        relation: Relation,
+        strength: f32,
+    ) -> Self {
+        Self {
+            edge_id: edge_id.into(),
+            from_claim_id: from_claim_id.into(),
+            to_claim_id: to_claim_id.into(),
+            relation,
+            strength,
+            reason_codes: Vec::new(),
+            created_at: None,
+        }
+    }
+}
+
 /// Named entity extracted from claims — architecture §3.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", from = "EntityWire")]
+#[non_exhaustive]
 pub struct Entity {
     pub name: String,
+    /// Kind of entity, such as `organization` or `person`; empty when
+    /// the extractor did not say.
+    #[serde(default)]
     pub entity_type: String,
+    /// The name the entity is indexed under when set, so spellings of
+    /// one entity share an index entry.
     #[serde(default)]
     pub canonical_name: Option<String>,
 }
 
+impl Entity {
+    /// An untyped entity with no canonical name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entity_type: String::new(),
+            canonical_name: None,
+        }
+    }
+
+    pub fn with_type(mut self, entity_type: impl Into<String>) -> Self {
+        self.entity_type = entity_type.into();
+        self
+    }
+
+    pub fn with_canonical_name(mut self, canonical_name: impl Into<String>) -> Self {
+        self.canonical_name = Some(canonical_name.into());
+        self
+    }
+
+    /// The canonical name when set, otherwise the name.
+    pub fn index_name(&self) -> &str {
+        self.canonical_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl From<&str> for Entity {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Entity {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+/// An entity as it arrives on the wire: a bare name or the full object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EntityWire {
+    Name(String),
+    Structured {
+        name: String,
+        #[serde(default)]
+        entity_type: String,
+        #[serde(default)]
+        canonical_name: Option<String>,
+    },
+}
+
+impl From<EntityWire> for Entity {
+    fn from(wire: EntityWire) -> Self {
+        match wire {
+            EntityWire::Name(name) => Self::new(name),
+            EntityWire::Structured {
+                name,
+                entity_type,
+                canonical_name,
+            } => Self {
+                name,
+                entity_type,
+                canonical_name,
+            },
+        }
+    }
+}
+
+/// A source document evidence is extracted from — architecture §6.2.
+/// Evidence names it by `doc_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Document {
+    pub doc_id: String,
+    pub tenant_id: TenantId,
+    /// Where the document was fetched from, if anywhere.
+    #[serde(default)]
+    pub uri: Option<String>,
+    pub text: String,
+    /// Content hash as computed by the ingester, for spotting changed
+    /// documents.
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// Epoch‐millis when this document was first ingested.
+    #[serde(default)]
+    pub ingested_at: Option<i64>,
+}
+
+impl Document {
+    /// A document with no URI, hash, or ingest time.
+    pub fn new(
+        doc_id: impl Into<String>,
+        tenant_id: impl Into<TenantId>,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            doc_id: doc_id.into(),
+            tenant_id: tenant_id.into(),
+            uri: None,
+            text: text.into(),
+            hash: None,
+            ingested_at: None,
+        }
+    }
+}
+
+/// A passage of a [`Document`], named by evidence's `chunk_id`. Chunk
+/// ids are unique within their document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Chunk {
+    pub chunk_id: String,
+    pub doc_id: String,
+    pub tenant_id: TenantId,
+    #[serde(default)]
+    pub uri: Option<String>,
+    pub text: String,
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// Epoch‐millis when this chunk was first ingested.
+    #[serde(default)]
+    pub ingested_at: Option<i64>,
+}
+
+impl Chunk {
+    /// A chunk with no URI, hash, or ingest time.
+    pub fn new(
+        chunk_id: impl Into<String>,
+        doc_id: impl Into<String>,
+        tenant_id: impl Into<TenantId>,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            chunk_id: chunk_id.into(),
+            doc_id: doc_id.into(),
+            tenant_id: tenant_id.into(),
+            uri: None,
+            text: text.into(),
+            hash: None,
+            ingested_at: None,
+        }
+    }
+}
+
+/// A source evidence cites by `source_id`, registered so its quality is
+/// managed in one place rather than on every piece of evidence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Source {
+    pub source_id: String,
+    pub tenant_id: TenantId,
+    pub name: String,
+    /// Kind of source, such as `news` or `filing`.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Quality of the source's evidence, from 0 to 1, before its trust
+    /// tier caps it.
+    pub default_quality: f32,
+    #[serde(default)]
+    pub trust_tier: TrustTier,
+}
+
+impl Source {
+    /// An uncategorized source in the standard trust tier.
+    pub fn new(
+        source_id: impl Into<String>,
+        tenant_id: impl Into<TenantId>,
+        name: impl Into<String>,
+        default_quality: f32,
+    ) -> Self {
+        Self {
+            source_id: source_id.into(),
+            tenant_id: tenant_id.into(),
+            name: name.into(),
+            category: None,
+            default_quality,
+            trust_tier: TrustTier::default(),
+        }
+    }
+
+    /// The quality ranking gives the source's evidence: its default
+    /// quality, capped by its trust tier.
+    pub fn effective_quality(&self) -> f32 {
+        self.default_quality.min(self.trust_tier.max_quality())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Retrieval request/response types
 // ---------------------------------------------------------------------------
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub struct RetrievalRequest {
-    pub tenant_id: String,
+    pub tenant_id: TenantId,
     pub query: String,
     pub top_k: usize,
     pub stance_mode: StanceMode,
 }
 
+impl RetrievalRequest {
+    /// A balanced request for the `top_k` best matches of `query`.
+    pub fn new(tenant_id: impl Into<TenantId>, query: impl Into<String>, top_k: usize) -> Self {
+        Self {
+            tenant_id: tenant_id.into(),
+            query: query.into(),
+            top_k,
+            stance_mode: StanceMode::Balanced,
+        }
+    }
+
+    pub fn with_stance_mode(mut self, stance_mode: StanceMode) -> Self {
+        self.stance_mode = stance_mode;
+        self
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub struct Citation {
-    pub evidence_id: String,
+    pub evidence_id: EvidenceId,
     pub source_id: String,
+    /// The evidence's effective stance, reversed when it is `negated`.
     pub stance: Stance,
     pub source_quality: f32,
+    /// The cited text states the claim's negation, so `stance` is the
+    /// reverse of the stance recorded on the evidence.
+    #[serde(default)]
+    pub negated: bool,
+    /// How strongly the cited text takes its stance; `None` is full
+    /// strength.
+    #[serde(default)]
+    pub stance_strength: Option<f32>,
     #[serde(default)]
     pub chunk_id: Option<String>,
     #[serde(default)]
@@ -159,25 +818,174 @@ pub struct Citation {
     pub ingested_at: Option<i64>,
 }
 
+impl Citation {
+    /// A citation with no provenance beyond its source.
+    pub fn new(
+        evidence_id: impl Into<EvidenceId>,
+        source_id: impl Into<String>,
+        stance: Stance,
+        source_quality: f32,
+    ) -> Self {
+        Self {
+            evidence_id: evidence_id.into(),
+            source_id: source_id.into(),
+            stance,
+            source_quality,
+            negated: false,
+            stance_strength: None,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+        }
+    }
+}
+
+/// How far a result can be trusted, at a glance: backed by independent
+/// good sources, disputed, or neither.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum CertaintyBand {
+    Corroborated,
+    Contested,
+    #[default]
+    Unverified,
+}
+
+impl CertaintyBand {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CertaintyBand::Corroborated => "corroborated",
+            CertaintyBand::Contested => "contested",
+            CertaintyBand::Unverified => "unverified",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub struct RetrievalResult {
-    pub claim_id: String,
+    pub claim_id: ClaimId,
     pub canonical_text: String,
     pub score: f32,
     pub supports: usize,
     pub contradicts: usize,
     pub citations: Vec<Citation>,
+    #[serde(default)]
+    pub certainty: CertaintyBand,
+}
+
+impl RetrievalResult {
+    /// An unverified result with no stance counts or citations.
+    pub fn new(
+        claim_id: impl Into<ClaimId>,
+        canonical_text: impl Into<String>,
+        score: f32,
+    ) -> Self {
+        Self {
+            claim_id: claim_id.into(),
+            canonical_text: canonical_text.into(),
+            score,
+            supports: 0,
+            contradicts: 0,
+            citations: Vec::new(),
+            certainty: CertaintyBand::default(),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Validation
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
 pub enum ValidationError {
+    #[error("missing field: {0}")]
     MissingField(&'static str),
+    #[error("invalid value: {0}")]
     InvalidRange(&'static str),
+    #[error("{field} is too long: {len} characters, limit {max}")]
+    TooLong {
+        field: &'static str,
+        len: usize,
+        max: usize,
+    },
+    #[error("too many {field}: {count}, limit {max}")]
+    TooMany {
+        field: &'static str,
+        count: usize,
+        max: usize,
+    },
+}
+
+/// Size limits a store enforces on what it ingests, on top of the
+/// checks of [`validate_claim`] and [`validate_evidence`]. The defaults
+/// are generous; [`ValidationConfig::unlimited`] turns them off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidationConfig {
+    /// Longest `canonical_text`, in characters.
+    pub max_text_len: usize,
+    pub max_entities: usize,
+    pub max_embedding_ids: usize,
+    /// Most evidence one ingested bundle may carry.
+    pub max_evidence_per_bundle: usize,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_text_len: 16_384,
+            max_entities: 256,
+            max_embedding_ids: 64,
+            max_evidence_per_bundle: 1_024,
+        }
+    }
+}
+
+impl ValidationConfig {
+    pub fn unlimited() -> Self {
+        Self {
+            max_text_len: usize::MAX,
+            max_entities: usize::MAX,
+            max_embedding_ids: usize::MAX,
+            max_evidence_per_bundle: usize::MAX,
+        }
+    }
+
+    /// Checks `claim` against the limits only; see [`validate_claim_with`].
+    pub fn check_claim(&self, claim: &Claim) -> Result<(), ValidationError> {
+        let len = claim.canonical_text.chars().count();
+        if len > self.max_text_len {
+            return Err(ValidationError::TooLong {
+                field: "canonical_text",
+                len,
+                max: self.max_text_len,
+            });
+        }
+        check_count("entities", claim.entities.len(), self.max_entities)?;
+        check_count(
+            "embedding_ids",
+            claim.embedding_ids.len(),
+            self.max_embedding_ids,
+        )
+    }
+
+    pub fn check_evidence_count(&self, count: usize) -> Result<(), ValidationError> {
+        check_count("evidence", count, self.max_evidence_per_bundle)
+    }
+}
+
+fn check_count(field: &'static str, count: usize, max: usize) -> Result<(), ValidationError> {
+    if count > max {
+        return Err(ValidationError::TooMany { field, count, max });
+    }
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -194,13 +1002,97 @@ pub fn normalize_token(token: &str) -> String {
 }
 
 /// Tokenize text into normalized tokens, ready for indexing or matching.
+/// A phrase slop operator after a closing quote (`"a b"~2`) is dropped
+/// rather than folded into the last token.
 pub fn tokenize(text: &str) -> Vec<String> {
     text.split_whitespace()
+        .map(strip_phrase_slop)
         .map(normalize_token)
         .filter(|t| !t.is_empty())
         .collect()
 }
 
+/// Unicode-aware [`tokenize`]: letters and digits of every script are
+/// kept and lowercased instead of being stripped. Scripts written without
+/// spaces between words (Chinese, Japanese, Korean, Thai, and their
+/// neighbours) are split into overlapping character n-grams of `ngram`
+/// characters, so a query matches inside a longer run of text; a run no
+/// longer than `ngram` is a single token.
+pub fn tokenize_unicode(text: &str, ngram: usize) -> Vec<String> {
+    let ngram = ngram.max(1);
+    let mut tokens = Vec::new();
+    for piece in text.split_whitespace().map(strip_phrase_slop) {
+        let mut word = String::new();
+        let mut run: Vec<char> = Vec::new();
+        for c in piece.chars() {
+            if is_unsegmented_script(c) {
+                flush_word(&mut word, &mut tokens);
+                run.push(c);
+            } else if c.is_alphanumeric() {
+                flush_ngrams(&mut run, ngram, &mut tokens);
+                word.extend(c.to_lowercase());
+            } else {
+                // Punctuation ends a run of unsegmented text but, as in
+                // `tokenize`, is simply dropped from a word.
+                flush_ngrams(&mut run, ngram, &mut tokens);
+            }
+        }
+        flush_word(&mut word, &mut tokens);
+        flush_ngrams(&mut run, ngram, &mut tokens);
+    }
+    tokens
+}
+
+fn flush_word(word: &mut String, tokens: &mut Vec<String>) {
+    if !word.is_empty() {
+        tokens.push(std::mem::take(word));
+    }
+}
+
+fn flush_ngrams(run: &mut Vec<char>, ngram: usize, tokens: &mut Vec<String>) {
+    if run.is_empty() {
+        return;
+    }
+    if run.len() <= ngram {
+        tokens.push(run.iter().collect());
+    } else {
+        tokens.extend(
+            run.windows(ngram)
+                .map(|gram| gram.iter().collect::<String>()),
+        );
+    }
+    run.clear();
+}
+
+/// Scripts whose words are not separated by spaces, or (Hangul) whose
+/// space-separated words carry attached particles, so n-grams are the
+/// practical unit of matching.
+fn is_unsegmented_script(c: char) -> bool {
+    matches!(
+        u32::from(c),
+        0x0E00..=0x0EFF // Thai, Lao
+            | 0x1000..=0x109F // Myanmar
+            | 0x1100..=0x11FF // Hangul Jamo
+            | 0x1780..=0x17FF // Khmer
+            | 0x3040..=0x30FF // Hiragana, Katakana
+            | 0x3130..=0x318F // Hangul compatibility Jamo
+            | 0x31F0..=0x31FF // Katakana phonetic extensions
+            | 0x3400..=0x4DBF // CJK extension A
+            | 0x4E00..=0x9FFF // CJK unified ideographs
+            | 0xAC00..=0xD7AF // Hangul syllables
+            | 0xF900..=0xFAFF // CJK compatibility ideographs
+            | 0xFF66..=0xFF9F // Half-width Katakana
+            | 0x20000..=0x2FA1F // CJK extensions B onward
+    )
+}
+
+fn strip_phrase_slop(raw: &str) -> &str {
+    match raw.rsplit_once("\"~") {
+        Some((head, slop)) if !slop.is_empty() && slop.bytes().all(|b| b.is_ascii_digit()) => head,
+        _ => raw,
+    }
+}
+
 pub fn validate_claim(claim: &Claim) -> Result<(), ValidationError> {
     if claim.claim_id.trim().is_empty() {
         return Err(ValidationError::MissingField("claim_id"));
@@ -215,22 +1107,62 @@ pub fn validate_claim(claim: &Claim) -> Result<(), ValidationError> {
         return Err(ValidationError::InvalidRange("confidence"));
     }
     for entity in &claim.entities {
-        if entity.trim().is_empty() {
+        if entity.name.trim().is_empty() {
             return Err(ValidationError::MissingField("entities[]"));
         }
+        if entity
+            .canonical_name
+            .as_ref()
+            .is_some_and(|canonical_name| canonical_name.trim().is_empty())
+        {
+            return Err(ValidationError::MissingField("entities[].canonical_name"));
+        }
     }
     for embedding_id in &claim.embedding_ids {
         if embedding_id.trim().is_empty() {
             return Err(ValidationError::MissingField("embedding_ids[]"));
         }
     }
+    for (key, _) in &claim.metadata {
+        if key.trim().is_empty() {
+            return Err(ValidationError::MissingField("metadata[].key"));
+        }
+    }
+    // Labels are matched against token scopes, which are separated by
+    // whitespace; `*` is reserved so no label reads as a wildcard.
+    for label in &claim.visibility_labels {
+        if label.trim().is_empty() {
+            return Err(ValidationError::MissingField("visibility_labels[]"));
+        }
+        if label == "*" || label.contains(char::is_whitespace) {
+            return Err(ValidationError::InvalidRange("visibility_labels[]"));
+        }
+    }
     // Validate temporal validity window
     if let (Some(from), Some(to)) = (claim.valid_from, claim.valid_to)
         && from > to
     {
         return Err(ValidationError::InvalidRange("valid_from/valid_to"));
     }
-    Ok(())
+    // Collections are named in comma-separated query parameters.
+    if let Some(collection) = &claim.collection {
+        if collection.trim().is_empty() {
+            return Err(ValidationError::MissingField("collection"));
+        }
+        if collection.contains(|c: char| c == ',' || c.is_whitespace()) {
+            return Err(ValidationError::InvalidRange("collection"));
+        }
+    }
+    validate_language(claim.language.as_deref())
+}
+
+/// [`validate_claim`], then the size limits of `config`.
+pub fn validate_claim_with(
+    claim: &Claim,
+    config: &ValidationConfig,
+) -> Result<(), ValidationError> {
+    validate_claim(claim)?;
+    config.check_claim(claim)
 }
 
 pub fn validate_evidence(evidence: &Evidence) -> Result<(), ValidationError> {
@@ -260,7 +1192,45 @@ pub fn validate_evidence(evidence: &Evidence) -> Result<(), ValidationError> {
         (None, None) => {}
         _ => return Err(ValidationError::InvalidRange("span_range")),
     }
-    Ok(())
+    if evidence
+        .stance_strength
+        .is_some_and(|strength| !(0.0..=1.0).contains(&strength))
+    {
+        return Err(ValidationError::InvalidRange("stance_strength"));
+    }
+    validate_language(evidence.language.as_deref())
+}
+
+fn validate_language(language: Option<&str>) -> Result<(), ValidationError> {
+    match language {
+        Some(tag) if tag.trim().is_empty() => Err(ValidationError::MissingField("language")),
+        Some(tag) if !is_language_tag(tag) => Err(ValidationError::InvalidRange("language")),
+        _ => Ok(()),
+    }
+}
+
+/// Whether `tag` is shaped like a BCP-47 language tag: `-`-separated
+/// subtags of one to eight ASCII letters and digits, led by a language
+/// subtag of two or three letters, five to eight letters, or the `x`
+/// that opens a private-use tag. Subtags are not checked against the
+/// registry.
+pub fn is_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    let primary_ok = primary.chars().all(|c| c.is_ascii_alphabetic())
+        && (matches!(primary.len(), 2 | 3 | 5..=8) || primary.eq_ignore_ascii_case("x"));
+    primary_ok
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+/// The language subtag of a BCP-47 `tag`, lowercased: `pt` for `pt-BR`.
+pub fn primary_language(tag: &str) -> String {
+    tag.split('-')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
 }
 
 pub fn validate_edge(edge: &ClaimEdge) -> Result<(), ValidationError> {
@@ -284,22 +1254,190 @@ pub fn validate_edge(edge: &ClaimEdge) -> Result<(), ValidationError> {
     Ok(())
 }
 
-/// Helper to create a `Claim` with default optional fields.
-/// Used throughout tests to avoid repetitive struct construction.
+pub fn validate_document(document: &Document) -> Result<(), ValidationError> {
+    if document.doc_id.trim().is_empty() {
+        return Err(ValidationError::MissingField("doc_id"));
+    }
+    if document.tenant_id.trim().is_empty() {
+        return Err(ValidationError::MissingField("tenant_id"));
+    }
+    if document
+        .uri
+        .as_ref()
+        .is_some_and(|uri| uri.trim().is_empty())
+    {
+        return Err(ValidationError::MissingField("uri"));
+    }
+    if document
+        .hash
+        .as_ref()
+        .is_some_and(|hash| hash.trim().is_empty())
+    {
+        return Err(ValidationError::MissingField("hash"));
+    }
+    Ok(())
+}
+
+pub fn validate_chunk(chunk: &Chunk) -> Result<(), ValidationError> {
+    if chunk.chunk_id.trim().is_empty() {
+        return Err(ValidationError::MissingField("chunk_id"));
+    }
+    if chunk.doc_id.trim().is_empty() {
+        return Err(ValidationError::MissingField("doc_id"));
+    }
+    if chunk.tenant_id.trim().is_empty() {
+        return Err(ValidationError::MissingField("tenant_id"));
+    }
+    if chunk.uri.as_ref().is_some_and(|uri| uri.trim().is_empty()) {
+        return Err(ValidationError::MissingField("uri"));
+    }
+    if chunk
+        .hash
+        .as_ref()
+        .is_some_and(|hash| hash.trim().is_empty())
+    {
+        return Err(ValidationError::MissingField("hash"));
+    }
+    Ok(())
+}
+
+pub fn validate_source(source: &Source) -> Result<(), ValidationError> {
+    if source.source_id.trim().is_empty() {
+        return Err(ValidationError::MissingField("source_id"));
+    }
+    if source.tenant_id.trim().is_empty() {
+        return Err(ValidationError::MissingField("tenant_id"));
+    }
+    if source.name.trim().is_empty() {
+        return Err(ValidationError::MissingField("name"));
+    }
+    if source
+        .category
+        .as_ref()
+        .is_some_and(|category| category.trim().is_empty())
+    {
+        return Err(ValidationError::MissingField("category"));
+    }
+    if !(0.0..=1.0).contains(&source.default_quality) {
+        return Err(ValidationError::InvalidRange("default_quality"));
+    }
+    Ok(())
+}
+
+/// Helper to create a `Claim` with default optional fields, which the
+/// `with_*` methods on [`Claim`] then fill in. Used throughout tests to
+/// avoid repetitive struct construction.
 pub fn claim_builder(claim_id: &str, tenant_id: &str, text: &str, confidence: f32) -> Claim {
-    Claim {
-        claim_id: claim_id.to_string(),
-        tenant_id: tenant_id.to_string(),
-        canonical_text: text.to_string(),
-        confidence,
-        event_time_unix: None,
-        entities: vec![],
-        embedding_ids: vec![],
-        claim_type: None,
-        valid_from: None,
-        valid_to: None,
-        created_at: None,
-        updated_at: None,
+    Claim::new(claim_id, tenant_id, text, confidence)
+}
+
+/// Builds an [`Evidence`] from its ids. Stance starts neutral, source
+/// quality at 0.5, and every optional field unset; [`Self::build`]
+/// validates the result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvidenceBuilder {
+    evidence: Evidence,
+}
+
+impl EvidenceBuilder {
+    pub fn new(evidence_id: &str, claim_id: &str, source_id: &str) -> Self {
+        Self {
+            evidence: Evidence::new(evidence_id, claim_id, source_id, Stance::Neutral, 0.5),
+        }
+    }
+
+    pub fn with_stance(mut self, stance: Stance) -> Self {
+        self.evidence.stance = stance;
+        self
+    }
+
+    pub fn with_source_quality(mut self, source_quality: f32) -> Self {
+        self.evidence.source_quality = source_quality;
+        self
+    }
+
+    pub fn with_chunk_id(mut self, chunk_id: &str) -> Self {
+        self.evidence.chunk_id = Some(chunk_id.to_string());
+        self
+    }
+
+    /// Byte span of the evidence within its chunk.
+    pub fn with_span(mut self, start: u32, end: u32) -> Self {
+        self.evidence.span_start = Some(start);
+        self.evidence.span_end = Some(end);
+        self
+    }
+
+    pub fn with_doc_id(mut self, doc_id: &str) -> Self {
+        self.evidence.doc_id = Some(doc_id.to_string());
+        self
+    }
+
+    pub fn with_extraction_model(mut self, extraction_model: &str) -> Self {
+        self.evidence.extraction_model = Some(extraction_model.to_string());
+        self
+    }
+
+    pub fn with_ingested_at(mut self, ingested_at: i64) -> Self {
+        self.evidence.ingested_at = Some(ingested_at);
+        self
+    }
+
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.evidence.language = Some(language.to_string());
+        self
+    }
+
+    pub fn with_stance_strength(mut self, stance_strength: f32) -> Self {
+        self.evidence.stance_strength = Some(stance_strength);
+        self
+    }
+
+    /// Marks the cited text as stating the claim's negation.
+    pub fn negated(mut self) -> Self {
+        self.evidence.negated = true;
+        self
+    }
+
+    pub fn build(self) -> Result<Evidence, ValidationError> {
+        validate_evidence(&self.evidence)?;
+        Ok(self.evidence)
+    }
+}
+
+/// Builds a [`ClaimEdge`] from its ids and relation. Strength starts at
+/// 1.0 with no reason codes; [`Self::build`] validates the result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimEdgeBuilder {
+    edge: ClaimEdge,
+}
+
+impl ClaimEdgeBuilder {
+    pub fn new(edge_id: &str, from_claim_id: &str, to_claim_id: &str, relation: Relation) -> Self {
+        Self {
+            edge: ClaimEdge::new(edge_id, from_claim_id, to_claim_id, relation, 1.0),
+        }
+    }
+
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.edge.strength = strength;
+        self
+    }
+
+    /// Appends one reason code; call repeatedly for several.
+    pub fn with_reason_code(mut self, reason_code: &str) -> Self {
+        self.edge.reason_codes.push(reason_code.to_string());
+        self
+    }
+
+    pub fn with_created_at(mut self, created_at: i64) -> Self {
+        self.edge.created_at = Some(created_at);
+        self
+    }
+
+    pub fn build(self) -> Result<ClaimEdge, ValidationError> {
+        validate_edge(&self.edge)?;
+        Ok(self.edge)
     }
 }
 
@@ -330,8 +1468,8 @@ mod tests {
     #[test]
     fn rejects_evidence_with_empty_source() {
         let ev = Evidence {
-            evidence_id: "e1".to_string(),
-            claim_id: "c1".to_string(),
+            evidence_id: "e1".into(),
+            claim_id: "c1".into(),
             source_id: "  ".to_string(),
             stance: Stance::Supports,
             source_quality: 0.5,
@@ -341,6 +1479,9 @@ mod tests {
             doc_id: None,
             extraction_model: None,
             ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
         };
         assert_eq!(
             validate_evidence(&ev),
@@ -351,9 +1492,9 @@ mod tests {
     #[test]
     fn rejects_edge_with_invalid_strength() {
         let edge = ClaimEdge {
-            edge_id: "g1".to_string(),
-            from_claim_id: "c1".to_string(),
-            to_claim_id: "c2".to_string(),
+            edge_id: "g1".into(),
+            from_claim_id: "c1".into(),
+            to_claim_id: "c2".into(),
             relation: Relation::Supports,
             strength: -0.1,
             reason_codes: vec![],
@@ -365,11 +1506,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn builders_reject_what_validation_rejects() {
+        assert_eq!(
+            EvidenceBuilder::new("e1", "c1", "  ")
+                .with_stance(Stance::Supports)
+                .build(),
+            Err(ValidationError::MissingField("source_id"))
+        );
+        assert_eq!(
+            EvidenceBuilder::new("e1", "c1", "src")
+                .with_stance(Stance::Supports)
+                .with_span(20, 10)
+                .build(),
+            Err(ValidationError::InvalidRange("span_range"))
+        );
+        assert_eq!(
+            ClaimEdgeBuilder::new("g1", "c1", "c2", Relation::Supports)
+                .with_strength(-0.1)
+                .build(),
+            Err(ValidationError::InvalidRange("strength"))
+        );
+    }
+
     #[test]
     fn rejects_evidence_with_partial_span() {
         let ev = Evidence {
-            evidence_id: "e1".to_string(),
-            claim_id: "c1".to_string(),
+            evidence_id: "e1".into(),
+            claim_id: "c1".into(),
             source_id: "src".to_string(),
             stance: Stance::Supports,
             source_quality: 0.5,
@@ -379,6 +1543,9 @@ mod tests {
             doc_id: None,
             extraction_model: None,
             ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
         };
         assert_eq!(
             validate_evidence(&ev),
@@ -389,8 +1556,8 @@ mod tests {
     #[test]
     fn rejects_evidence_with_inverted_span() {
         let ev = Evidence {
-            evidence_id: "e1".to_string(),
-            claim_id: "c1".to_string(),
+            evidence_id: "e1".into(),
+            claim_id: "c1".into(),
             source_id: "src".to_string(),
             stance: Stance::Supports,
             source_quality: 0.5,
@@ -400,6 +1567,9 @@ mod tests {
             doc_id: None,
             extraction_model: None,
             ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
         };
         assert_eq!(
             validate_evidence(&ev),
@@ -424,12 +1594,96 @@ mod tests {
         assert_eq!(tokens, vec!["company", "x", "acquired", "companyy"]);
     }
 
+    #[test]
+    fn tokenize_unicode_keeps_non_ascii_and_splits_cjk_into_ngrams() {
+        assert_eq!(tokenize("Café 公司"), vec!["caf"]);
+        assert_eq!(
+            tokenize_unicode("Café Müller-Lüdenscheid", 2),
+            vec!["café", "müllerlüdenscheid"]
+        );
+        assert_eq!(
+            tokenize_unicode("甲公司收购了乙公司。iPhone发布", 2),
+            vec![
+                "甲公", "公司", "司收", "收购", "购了", "了乙", "乙公", "公司", "iphone", "发布"
+            ]
+        );
+        assert_eq!(tokenize_unicode("東京", 3), vec!["東京"]);
+        assert_eq!(
+            tokenize_unicode("\"삼성전자\"~1", 1),
+            vec!["삼", "성", "전", "자"]
+        );
+    }
+
+    #[test]
+    fn tokenize_drops_phrase_slop_operator() {
+        let tokens = tokenize("\"Company X acquired\"~2 rival~3");
+        assert_eq!(tokens, vec!["company", "x", "acquired", "rival3"]);
+    }
+
     #[test]
     fn claim_builder_creates_valid_claim() {
         let claim = claim_builder("c1", "t1", "text", 0.5);
         assert_eq!(validate_claim(&claim), Ok(()));
     }
 
+    #[test]
+    fn evidence_builder_sets_fields_and_validates() {
+        let ev = EvidenceBuilder::new("e1", "c1", "src")
+            .with_stance(Stance::Supports)
+            .with_source_quality(0.9)
+            .with_chunk_id("chunk-1")
+            .with_span(4, 12)
+            .with_doc_id("doc-1")
+            .build()
+            .unwrap();
+        assert_eq!(ev.stance, Stance::Supports);
+        assert_eq!(ev.source_quality, 0.9);
+        assert_eq!(ev.chunk_id.as_deref(), Some("chunk-1"));
+        assert_eq!((ev.span_start, ev.span_end), (Some(4), Some(12)));
+        assert_eq!(ev.extraction_model, None);
+
+        let defaults = EvidenceBuilder::new("e2", "c1", "src").build().unwrap();
+        assert_eq!(defaults.stance, Stance::Neutral);
+        assert_eq!(defaults.source_quality, 0.5);
+        assert_eq!(
+            EvidenceBuilder::new("e3", "c1", "src")
+                .with_span(9, 3)
+                .build(),
+            Err(ValidationError::InvalidRange("span_range"))
+        );
+        assert_eq!(
+            EvidenceBuilder::new("e4", "c1", " ").build(),
+            Err(ValidationError::MissingField("source_id"))
+        );
+    }
+
+    #[test]
+    fn claim_edge_builder_sets_fields_and_validates() {
+        let edge = ClaimEdgeBuilder::new("g1", "c1", "c2", Relation::Refines)
+            .with_strength(0.7)
+            .with_reason_code("same_entity")
+            .with_reason_code("later_date")
+            .with_created_at(1_700_000_000_000)
+            .build()
+            .unwrap();
+        assert_eq!(edge.relation, Relation::Refines);
+        assert_eq!(edge.strength, 0.7);
+        assert_eq!(edge.reason_codes, vec!["same_entity", "later_date"]);
+        assert_eq!(edge.created_at, Some(1_700_000_000_000));
+
+        let defaults = ClaimEdgeBuilder::new("g2", "c1", "c2", Relation::Supports)
+            .build()
+            .unwrap();
+        assert_eq!(defaults.strength, 1.0);
+        assert!(defaults.reason_codes.is_empty());
+        assert_eq!(
+            ClaimEdgeBuilder::new("g3", "c1", "c2", Relation::Supports)
+                .with_strength(1.5)
+                .build(),
+            Err(ValidationError::InvalidRange("strength"))
+        );
+    }
+
     // -----------------------------------------------------------------------
     // serde round-trip tests
     // -----------------------------------------------------------------------
@@ -449,6 +1703,10 @@ mod tests {
             valid_to: Some(200),
             created_at: Some(1_700_000_000_000),
             updated_at: Some(1_700_000_001_000),
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         };
         let json = serde_json::to_string(&original).unwrap();
         let decoded: Claim = serde_json::from_str(&json).unwrap();
@@ -464,7 +1722,7 @@ mod tests {
             "confidence": 0.9
         }"#;
         let claim: Claim = serde_json::from_str(json).unwrap();
-        assert_eq!(claim.entities, Vec::<String>::new());
+        assert_eq!(claim.entities, Vec::<Entity>::new());
         assert_eq!(claim.embedding_ids, Vec::<String>::new());
         assert_eq!(claim.event_time_unix, None);
         assert_eq!(claim.claim_type, None);
@@ -496,6 +1754,10 @@ mod tests {
             serde_json::to_string(&Relation::Duplicates).unwrap(),
             "\"duplicates\""
         );
+        assert_eq!(
+            serde_json::from_str::<Relation>("\"supersedes\"").unwrap(),
+            Relation::Supersedes
+        );
     }
 
     #[test]
@@ -524,6 +1786,9 @@ mod tests {
             doc_id: Some("doc".into()),
             extraction_model: Some("v3".into()),
             ingested_at: Some(1_700_000_000_000),
+            language: None,
+            stance_strength: None,
+            negated: false,
         };
         let json = serde_json::to_string(&original).unwrap();
         let decoded: Evidence = serde_json::from_str(&json).unwrap();
@@ -546,6 +1811,297 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    fn builders_match_struct_literals() {
+        let claim = claim_builder("c1", "t1", "text", 0.85)
+            .with_event_time(1_700_000_000)
+            .with_entities(["X", "Y"])
+            .with_embedding_ids(["emb://1"])
+            .with_claim_type(ClaimType::Temporal)
+            .with_valid_from(100)
+            .with_valid_to(200)
+            .with_created_at(1_700_000_000_000)
+            .with_updated_at(1_700_000_001_000);
+        assert_eq!(
+            claim,
+            Claim {
+                claim_id: "c1".into(),
+                tenant_id: "t1".into(),
+                canonical_text: "text".into(),
+                confidence: 0.85,
+                event_time_unix: Some(1_700_000_000),
+                entities: vec!["X".into(), "Y".into()],
+                embedding_ids: vec!["emb://1".into()],
+                claim_type: Some(ClaimType::Temporal),
+                valid_from: Some(100),
+                valid_to: Some(200),
+                created_at: Some(1_700_000_000_000),
+                updated_at: Some(1_700_000_001_000),
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
+            }
+        );
+
+        let evidence = EvidenceBuilder::new("e1", "c1", "src")
+            .with_stance(Stance::Contradicts)
+            .with_source_quality(0.7)
+            .with_chunk_id("chunk-1")
+            .with_span(0, 100)
+            .with_doc_id("doc")
+            .with_extraction_model("v3")
+            .with_ingested_at(1_700_000_000_000)
+            .build()
+            .unwrap();
+        assert_eq!(
+            evidence,
+            Evidence {
+                evidence_id: "e1".into(),
+                claim_id: "c1".into(),
+                source_id: "src".into(),
+                stance: Stance::Contradicts,
+                source_quality: 0.7,
+                chunk_id: Some("chunk-1".into()),
+                span_start: Some(0),
+                span_end: Some(100),
+                doc_id: Some("doc".into()),
+                extraction_model: Some("v3".into()),
+                ingested_at: Some(1_700_000_000_000),
+                language: None,
+                stance_strength: None,
+                negated: false,
+            }
+        );
+
+        let edge = ClaimEdgeBuilder::new("g1", "c1", "c2", Relation::Refines)
+            .with_strength(0.5)
+            .with_reason_code("contextual")
+            .with_created_at(1_700_000_000_000)
+            .build()
+            .unwrap();
+        assert_eq!(
+            edge,
+            ClaimEdge {
+                edge_id: "g1".into(),
+                from_claim_id: "c1".into(),
+                to_claim_id: "c2".into(),
+                relation: Relation::Refines,
+                strength: 0.5,
+                reason_codes: vec!["contextual".into()],
+                created_at: Some(1_700_000_000_000),
+            }
+        );
+    }
+
+    #[test]
+    fn claim_entities_deserialize_from_names_and_objects() {
+        let entities: Vec<Entity> = serde_json::from_str(
+            r#"["Company X", {"name": "Acme Corp.", "entity_type": "organization",
+                "canonical_name": "Acme"}, {"name": "Q3"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            entities,
+            vec![
+                Entity::new("Company X"),
+                Entity::new("Acme Corp.")
+                    .with_type("organization")
+                    .with_canonical_name("Acme"),
+                Entity::new("Q3"),
+            ]
+        );
+        assert_eq!(entities[0].index_name(), "Company X");
+        assert_eq!(entities[1].index_name(), "Acme");
+        let json = serde_json::to_string(&entities[1]).unwrap();
+        assert_eq!(serde_json::from_str::<Entity>(&json).unwrap(), entities[1]);
+    }
+
+    #[test]
+    fn visibility_labels_validate_and_decide_who_sees_a_claim() {
+        let claim: Claim = serde_json::from_str(
+            r#"{"claim_id": "c1", "tenant_id": "t1", "canonical_text": "Revenue grew",
+                "confidence": 0.8, "visibility_labels": ["team:finance"]}"#,
+        )
+        .unwrap();
+        assert!(validate_claim(&claim).is_ok());
+        assert!(claim.is_visible_to(&["team:finance".into()]));
+        assert!(!claim.is_visible_to(&["*".into()]));
+        assert!(!claim.is_visible_to(&["team:legal".into()]));
+        assert!(!claim.is_visible_to(&[]));
+
+        let unlabeled = Claim {
+            visibility_labels: Vec::new(),
+            ..claim.clone()
+        };
+        assert!(unlabeled.is_visible_to(&[]));
+        for (label, error) in [
+            (" ", ValidationError::MissingField("visibility_labels[]")),
+            ("*", ValidationError::InvalidRange("visibility_labels[]")),
+            (
+                "team finance",
+                ValidationError::InvalidRange("visibility_labels[]"),
+            ),
+        ] {
+            let labeled = Claim {
+                visibility_labels: vec![label.into()],
+                ..claim.clone()
+            };
+            assert_eq!(validate_claim(&labeled), Err(error));
+        }
+    }
+
+    #[test]
+    fn documents_and_chunks_deserialize_with_optional_fields_and_validate() {
+        let document: Document = serde_json::from_str(
+            r#"{"doc_id": "doc-1", "tenant_id": "t1", "text": "Company X acquired Y."}"#,
+        )
+        .unwrap();
+        assert_eq!((document.uri, document.hash), (None, None));
+        let chunk: Chunk = serde_json::from_str(
+            r#"{"chunk_id": "c-1", "doc_id": "doc-1", "tenant_id": "t1", "text": "Company X",
+                "hash": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            validate_chunk(&chunk),
+            Err(ValidationError::MissingField("hash"))
+        );
+        assert!(
+            validate_chunk(&Chunk {
+                hash: None,
+                ..chunk
+            })
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn sources_default_to_standard_trust_which_caps_their_quality() {
+        let source: Source = serde_json::from_str(
+            r#"{"source_id": "src://wire", "tenant_id": "t1", "name": "Wire",
+                "default_quality": 0.95}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            (source.category.as_deref(), source.trust_tier),
+            (None, TrustTier::Standard)
+        );
+        assert!((source.effective_quality() - 0.7).abs() < 1e-6);
+        let authoritative = Source {
+            trust_tier: TrustTier::Authoritative,
+            ..source.clone()
+        };
+        assert!((authoritative.effective_quality() - 0.95).abs() < 1e-6);
+        assert_eq!(TrustTier::parse("untrusted"), Some(TrustTier::Untrusted));
+        assert_eq!(
+            validate_source(&Source {
+                default_quality: 1.5,
+                ..source.clone()
+            }),
+            Err(ValidationError::InvalidRange("default_quality"))
+        );
+        assert_eq!(
+            validate_source(&Source {
+                category: Some(" ".into()),
+                ..source
+            }),
+            Err(ValidationError::MissingField("category"))
+        );
+    }
+
+    #[test]
+    fn negation_reverses_the_effective_stance_and_strength_weights_it() {
+        let denied = EvidenceBuilder::new("e1", "c1", "source://a")
+            .with_stance(Stance::Supports)
+            .negated()
+            .build()
+            .unwrap();
+        assert_eq!(denied.effective_stance(), Stance::Contradicts);
+        assert_eq!(denied.stance_weight(), 1.0);
+        let weak = EvidenceBuilder::new("e2", "c1", "source://a")
+            .with_stance_strength(0.25)
+            .negated()
+            .build()
+            .unwrap();
+        assert_eq!(weak.effective_stance(), Stance::Neutral);
+        assert_eq!(weak.stance_weight(), 0.25);
+        let legacy: Evidence = serde_json::from_value(serde_json::json!({
+            "evidence_id": "e3",
+            "claim_id": "c1",
+            "source_id": "source://a",
+            "stance": "contradicts",
+            "source_quality": 0.5,
+        }))
+        .unwrap();
+        assert_eq!((legacy.stance_strength, legacy.negated), (None, false));
+    }
+
+    #[test]
+    fn validation_config_bounds_claim_sizes_unless_unlimited() {
+        let mut claim = claim_builder("c1", "tenant-a", "text", 0.5);
+        let config = ValidationConfig::default();
+        assert_eq!(validate_claim_with(&claim, &config), Ok(()));
+        claim.embedding_ids = (0..=config.max_embedding_ids)
+            .map(|i| format!("emb-{i}"))
+            .collect();
+        assert_eq!(
+            validate_claim_with(&claim, &config),
+            Err(ValidationError::TooMany {
+                field: "embedding_ids",
+                count: 65,
+                max: 64,
+            })
+        );
+        claim.canonical_text = "é".repeat(config.max_text_len + 1);
+        assert!(matches!(
+            config.check_claim(&claim),
+            Err(ValidationError::TooLong { len: 16_385, .. })
+        ));
+        assert_eq!(
+            validate_claim_with(&claim, &ValidationConfig::unlimited()),
+            Ok(())
+        );
+        assert!(config.check_evidence_count(1_025).is_err());
+    }
+
+    #[test]
+    fn language_tags_must_be_well_formed_bcp47() {
+        for tag in ["en", "pt-BR", "zh-Hant-TW", "x-klingon", "sgn-ase"] {
+            assert!(is_language_tag(tag), "{tag}");
+        }
+        for tag in [
+            "e",
+            "en_US",
+            "deutsch1",
+            "en-",
+            "-en",
+            "fr-toolongsubtag",
+            "12",
+        ] {
+            assert!(!is_language_tag(tag), "{tag}");
+        }
+        assert_eq!(primary_language("PT-br"), "pt");
+
+        let mut claim = claim_builder("c1", "t1", "Company X", 0.9);
+        claim.language = Some(" ".into());
+        assert_eq!(
+            validate_claim(&claim),
+            Err(ValidationError::MissingField("language"))
+        );
+        assert_eq!(
+            EvidenceBuilder::new("e1", "c1", "src")
+                .with_language("en_US")
+                .build(),
+            Err(ValidationError::InvalidRange("language"))
+        );
+        let evidence = EvidenceBuilder::new("e1", "c1", "src")
+            .with_language("en-US")
+            .build()
+            .unwrap();
+        assert_eq!(evidence.language.as_deref(), Some("en-US"));
+    }
+
     #[test]
     fn retrieval_request_serde_uses_snake_case_fields() {
         let req = RetrievalRequest {
@@ -558,4 +2114,28 @@ mod tests {
         assert!(json.contains("\"top_k\""));
         assert!(json.contains("\"stance_mode\":\"support_only\""));
     }
+
+    #[test]
+    fn typed_ids_serialize_as_strings_and_look_up_string_keys() {
+        let claim = claim_builder("c1", "t1", "Company X", 0.9);
+        let json = serde_json::to_value(&claim).unwrap();
+        assert_eq!(json["claim_id"], "c1");
+        assert_eq!(json["tenant_id"], "t1");
+        let parsed: Claim = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.claim_id, ClaimId::from("c1"));
+
+        assert_eq!(claim.claim_id, "c1");
+        assert_eq!("t1", claim.tenant_id);
+        assert_eq!(claim.claim_id.to_string(), "c1");
+        assert_eq!(String::from(claim.tenant_id.clone()), "t1");
+
+        let by_id: std::collections::HashMap<ClaimId, &str> =
+            [(claim.claim_id.clone(), "x")].into_iter().collect();
+        assert_eq!(by_id.get("c1"), Some(&"x"));
+        let edge = ClaimEdgeBuilder::new("g1", "c1", "c2", Relation::Supports)
+            .build()
+            .unwrap();
+        assert_eq!(edge.edge_id.as_str(), "g1");
+        assert!(edge.from_claim_id < edge.to_claim_id);
+    }
 }
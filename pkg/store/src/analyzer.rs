@@ -0,0 +1,249 @@
+//! Per-tenant text analysis for the term index.
+//!
+//! [`schema::tokenize`] lowercases and strips punctuation and anything
+//! outside ASCII; a tenant's [`TextAnalyzer`] can instead keep every
+//! script through [`schema::tokenize_unicode`], splitting Chinese,
+//! Japanese, Korean, and other text written without spaces into character
+//! n-grams. It can also drop English stopwords and reduce tokens to a
+//! light stem, so "acquires" and "acquired" meet at "acquir". The same
+//! analyzer runs over claim text when it is indexed and over queries and
+//! quoted phrases when they are matched, so the two always agree.
+//! Stopwords and stemming are English rules: a claim tagged with another
+//! language is indexed without them, while untagged claims and queries
+//! are treated as English. The choice is a WAL record and part of every snapshot, and changing it
+//! re-indexes the tenant's claims, so a replayed store indexes text
+//! exactly as the live one did.
+
+use schema::{Claim, TenantId, primary_language, tokenize, tokenize_unicode};
+
+use crate::{FileWal, InMemoryStore, StoreError};
+
+/// Common English function words, sorted for binary search.
+const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "be", "been", "but", "by", "did", "do", "does",
+    "for", "from", "had", "has", "have", "he", "her", "his", "if", "in", "into", "is", "it", "its",
+    "of", "on", "or", "she", "so", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "those", "to", "was", "were", "what", "when", "which", "who", "will",
+    "with",
+];
+
+/// Stems shorter than this are left alone, so short words keep enough
+/// letters to stay distinct.
+const MIN_STEM_LEN: usize = 3;
+
+/// Character n-gram size of [`TextAnalyzer::multilingual`]; bigrams are
+/// the usual unit for Chinese and Japanese text.
+pub(crate) const DEFAULT_CJK_NGRAM: usize = 2;
+
+/// Which characters survive tokenization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum TokenizerKind {
+    /// ASCII letters and digits only, through [`schema::tokenize`].
+    #[default]
+    Ascii,
+    /// Letters and digits of every script, with unsegmented scripts split
+    /// into overlapping character n-grams of `ngram` characters.
+    Unicode { ngram: usize },
+}
+
+impl TokenizerKind {
+    pub fn encode(self) -> String {
+        match self {
+            Self::Ascii => "ascii".to_string(),
+            Self::Unicode { ngram } => format!("unicode:{ngram}"),
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "ascii" => Some(Self::Ascii),
+            "unicode" => Some(Self::Unicode {
+                ngram: DEFAULT_CJK_NGRAM,
+            }),
+            other => {
+                let ngram = other.strip_prefix("unicode:")?.parse::<usize>().ok()?;
+                (ngram > 0).then_some(Self::Unicode { ngram })
+            }
+        }
+    }
+}
+
+/// How a tenant's text is split into index terms. The default only
+/// tokenizes ASCII, which is how every tenant was indexed before
+/// analyzers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct TextAnalyzer {
+    pub tokenizer: TokenizerKind,
+    /// Drop common English function words.
+    pub stopwords: bool,
+    /// Strip plural and tense suffixes.
+    pub stemming: bool,
+}
+
+impl TextAnalyzer {
+    /// Stopword removal and stemming together.
+    pub fn english() -> Self {
+        Self {
+            tokenizer: TokenizerKind::Ascii,
+            stopwords: true,
+            stemming: true,
+        }
+    }
+
+    /// [`TextAnalyzer::english`] over every script, with CJK text split
+    /// into bigrams. Stopwords and stemming only touch English tokens.
+    pub fn multilingual() -> Self {
+        Self {
+            tokenizer: TokenizerKind::Unicode {
+                ngram: DEFAULT_CJK_NGRAM,
+            },
+            ..Self::english()
+        }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        self.tokenizer == TokenizerKind::Ascii && !self.stopwords && !self.stemming
+    }
+
+    /// `text`'s index terms, in order.
+    pub fn analyze(&self, text: &str) -> Vec<String> {
+        let mut tokens = match self.tokenizer {
+            TokenizerKind::Ascii => tokenize(text),
+            TokenizerKind::Unicode { ngram } => tokenize_unicode(text, ngram),
+        };
+        if self.stopwords {
+            tokens.retain(|token| !is_stopword(token));
+        }
+        if self.stemming {
+            for token in &mut tokens {
+                stem_in_place(token);
+            }
+        }
+        tokens
+    }
+
+    /// [`Self::analyze`] for text in `language`, a BCP-47 tag: only
+    /// English or untagged text has stopwords dropped and tokens stemmed.
+    pub fn analyze_language(&self, text: &str, language: Option<&str>) -> Vec<String> {
+        let english = language.is_none_or(|tag| primary_language(tag) == "en");
+        Self {
+            stopwords: self.stopwords && english,
+            stemming: self.stemming && english,
+            ..*self
+        }
+        .analyze(text)
+    }
+}
+
+fn is_stopword(token: &str) -> bool {
+    STOPWORDS.binary_search(&token).is_ok()
+}
+
+/// A light suffix stripper: plurals, then `-ing`, `-ed`, and a final
+/// `-e`, so the forms of a regular verb share one stem.
+fn stem_in_place(token: &mut String) {
+    if !token.is_ascii() || token.len() <= MIN_STEM_LEN {
+        return;
+    }
+    let strip = |token: &mut String, suffix: &str, replacement: &str| -> bool {
+        if token.len() >= suffix.len() + MIN_STEM_LEN && token.ends_with(suffix) {
+            token.truncate(token.len() - suffix.len());
+            token.push_str(replacement);
+            true
+        } else {
+            false
+        }
+    };
+
+    if token.ends_with("ies") {
+        strip(token, "ies", "y");
+    } else if token.ends_with("sses") || token.ends_with("xes") || token.ends_with("ches") {
+        strip(token, "es", "");
+    } else if token.ends_with('s') && !token.ends_with("ss") && !token.ends_with("us") {
+        strip(token, "s", "");
+    }
+    if !strip(token, "ing", "") {
+        strip(token, "ed", "");
+    }
+    strip(token, "e", "");
+}
+
+impl InMemoryStore {
+    pub fn text_analyzer(&self, tenant_id: &TenantId) -> TextAnalyzer {
+        self.tenant_text_analyzer(tenant_id)
+    }
+
+    pub(crate) fn tenant_text_analyzer(&self, tenant_id: &str) -> TextAnalyzer {
+        self.text_analyzers
+            .get(tenant_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Analyze `tenant_id`'s text with `analyzer` from now on and
+    /// re-index its claims. Returns the number of claims re-indexed.
+    pub fn set_text_analyzer(&mut self, tenant_id: &TenantId, analyzer: TextAnalyzer) -> usize {
+        self.install_text_analyzer(tenant_id, analyzer)
+    }
+
+    pub fn set_text_analyzer_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        analyzer: TextAnalyzer,
+    ) -> Result<usize, StoreError> {
+        wal.append_text_analyzer(tenant_id, analyzer)?;
+        Ok(self.install_text_analyzer(tenant_id, analyzer))
+    }
+
+    pub(crate) fn install_text_analyzer(
+        &mut self,
+        tenant_id: &str,
+        analyzer: TextAnalyzer,
+    ) -> usize {
+        if self.tenant_text_analyzer(tenant_id) == analyzer {
+            return 0;
+        }
+        if analyzer.is_plain() {
+            self.text_analyzers.remove(tenant_id);
+        } else {
+            self.text_analyzers.insert(tenant_id.to_string(), analyzer);
+        }
+
+        let mut claim_ids: Vec<String> = self
+            .tenant_claim_ids
+            .get(tenant_id)
+            .map(|ids| ids.iter().cloned().collect())
+            .unwrap_or_default();
+        claim_ids.sort_unstable();
+        for claim_id in &claim_ids {
+            let Some(claim) = self.claims.get(claim_id.as_str()) else {
+                continue;
+            };
+            let tokens =
+                analyzer.analyze_language(&claim.canonical_text, claim.language.as_deref());
+            let index = self
+                .inverted_index
+                .entry(tenant_id.to_string())
+                .or_default();
+            match self.claim_tokens.get(claim_id) {
+                Some(previous) => index.remove(claim_id, previous),
+                None => index.purge(claim_id),
+            }
+            index.insert(claim_id, &tokens);
+            self.claim_tokens.insert(claim_id.clone(), tokens);
+        }
+        if !claim_ids.is_empty() {
+            self.bump_index_epoch(tenant_id);
+        }
+        claim_ids.len()
+    }
+
+    /// `claim`'s text analyzed the way its tenant's index is.
+    pub(crate) fn analyze_claim_text(&self, claim: &Claim) -> Vec<String> {
+        self.tenant_text_analyzer(&claim.tenant_id)
+            .analyze_language(&claim.canonical_text, claim.language.as_deref())
+    }
+}
@@ -23,6 +23,11 @@ use std::collections::HashMap;
 /// entry-point only and the search converges in O(log n).
 pub(crate) const ANN_GRAPH_LEVELS: usize = 4;
 
+/// Version of the ANN graph records written into snapshots. Bump it
+/// whenever graph construction changes in a way that makes old graphs
+/// unsuitable; snapshots with another version rebuild on load.
+pub(crate) const ANN_GRAPH_SNAPSHOT_VERSION: u32 = 2;
+
 /// Default maximum neighbors on the base layer (level 0). This
 /// is the recall/speed dial: more neighbors = better recall,
 /// slower search. 12 is a conservative default from the
@@ -44,17 +49,117 @@ pub(crate) const ANN_SEARCH_EXPANSION_MIN_DEFAULT: usize = 64;
 /// top_k values).
 pub(crate) const ANN_SEARCH_EXPANSION_MAX_DEFAULT: usize = 4096;
 
+/// Default exact-search threshold: 0 keeps an index for every tenant.
+pub(crate) const ANN_EXACT_SEARCH_THRESHOLD_DEFAULT: usize = 0;
+
+/// Default level multiplier, about `1 / ln(12)` for the default base
+/// fan-out: roughly one node in 12 reaches level 1, one in 150 level 2.
+pub(crate) const ANN_LEVEL_MULTIPLIER_DEFAULT: f64 = 0.4;
+
+/// Default seed mixed into each graph node's per-claim level hash.
+pub(crate) const ANN_LEVEL_SEED_DEFAULT: u64 = 0;
+
 // ---------------------------------------------------------------------------
 // Tunable configuration
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Which approximate index backs a tenant's vector search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnnIndexKind {
+    /// The HNSW-style neighbor graph. Best recall; memory grows with
+    /// `max_neighbors_*` per vector.
+    #[default]
+    Graph,
+    /// Product quantization: each vector is split into `subspaces`
+    /// chunks, each chunk encoded as one of `centroids` (at most 256)
+    /// codebook entries trained on the tenant's stored vectors. Queries
+    /// are scored with asymmetric distance tables and the best
+    /// candidates are re-ranked on the stored vectors. Trades recall for
+    /// a footprint of `subspaces` bytes per vector.
+    ProductQuantization { subspaces: usize, centroids: usize },
+    /// Inverted file: vectors are clustered into `lists` k-means
+    /// centroids trained on the tenant's stored vectors, and a query
+    /// scores every member of the `probes` lists nearest to it. Recall
+    /// grows with `probes / lists`; memory is one list entry per vector.
+    Ivf { lists: usize, probes: usize },
+}
+
+impl AnnIndexKind {
+    /// `graph`, `pq:<subspaces>:<centroids>` or `ivf:<lists>:<probes>`;
+    /// the form used in WAL records and environment variables.
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Graph => "graph".to_string(),
+            Self::ProductQuantization {
+                subspaces,
+                centroids,
+            } => format!("pq:{subspaces}:{centroids}"),
+            Self::Ivf { lists, probes } => format!("ivf:{lists}:{probes}"),
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().to_ascii_lowercase();
+        if raw == "graph" || raw == "hnsw" {
+            return Some(Self::Graph);
+        }
+        if let Some(params) = raw.strip_prefix("ivf:") {
+            let mut parts = params.split(':');
+            let lists = parts.next()?.parse::<usize>().ok()?;
+            let probes = parts.next()?.parse::<usize>().ok()?;
+            if parts.next().is_some() || lists == 0 || probes == 0 {
+                return None;
+            }
+            return Some(Self::Ivf { lists, probes });
+        }
+        let mut parts = raw.strip_prefix("pq:")?.split(':');
+        let subspaces = parts.next()?.parse::<usize>().ok()?;
+        let centroids = parts.next()?.parse::<usize>().ok()?;
+        if parts.next().is_some() || subspaces == 0 || !(1..=256).contains(&centroids) {
+            return None;
+        }
+        Some(Self::ProductQuantization {
+            subspaces,
+            centroids,
+        })
+    }
+}
+
+/// Per-query overrides of a tenant's [`AnnTuningConfig`], so a caller
+/// can trade latency for recall on one request without retuning the
+/// store. Unset fields fall back to the tenant's tuning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnnSearchOverrides {
+    /// Nodes expanded by the graph search (its `ef_search`) and the size
+    /// of the PQ re-rank pool, replacing the budget derived from
+    /// `top_k` and the tenant's expansion factor and bounds. IVF search
+    /// is sized by its probes and ignores it.
+    pub expansion_budget: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct AnnTuningConfig {
     pub max_neighbors_base: usize,
     pub max_neighbors_upper: usize,
     pub search_expansion_factor: usize,
     pub search_expansion_min: usize,
     pub search_expansion_max: usize,
+    /// The graph parameters above only apply to [`AnnIndexKind::Graph`];
+    /// the expansion budget also sizes the PQ re-rank pool. IVF probes
+    /// are set on the kind itself.
+    pub index_kind: AnnIndexKind,
+    /// Tenants with fewer vectors than this keep no approximate index
+    /// and are searched exactly. The index is built in one pass when the
+    /// tenant reaches the threshold. 0 disables the fallback.
+    pub exact_search_threshold: usize,
+    /// Scales the geometric distribution graph nodes draw their level
+    /// from (HNSW's `mL`): a node reaches level `l` with probability
+    /// `exp(-l / level_multiplier)`. `1 / ln(max_neighbors_base)` is the
+    /// usual choice; 0 keeps every node on the base level.
+    pub level_multiplier: f64,
+    /// Seeds the level draw of each graph node, mixed with its tenant and
+    /// claim ids, so a claim's node gets the same level on every build.
+    pub level_seed: u64,
 }
 
 impl Default for AnnTuningConfig {
@@ -65,6 +170,10 @@ impl Default for AnnTuningConfig {
             search_expansion_factor: ANN_SEARCH_EXPANSION_FACTOR_DEFAULT,
             search_expansion_min: ANN_SEARCH_EXPANSION_MIN_DEFAULT,
             search_expansion_max: ANN_SEARCH_EXPANSION_MAX_DEFAULT,
+            index_kind: AnnIndexKind::Graph,
+            exact_search_threshold: ANN_EXACT_SEARCH_THRESHOLD_DEFAULT,
+            level_multiplier: ANN_LEVEL_MULTIPLIER_DEFAULT,
+            level_seed: ANN_LEVEL_SEED_DEFAULT,
         }
     }
 }
@@ -73,7 +182,7 @@ impl Default for AnnTuningConfig {
 // Tenant-scoped graph
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct TenantAnnGraph {
     pub(crate) entry_point: Option<String>,
     pub(crate) entry_level: usize,
@@ -0,0 +1,132 @@
+//! As-of queries over claim validity windows.
+//!
+//! A claim's `valid_from`/`valid_to` window says when the claim held. An
+//! as-of query keeps the claims whose window covers one timestamp, a
+//! missing bound being open. Claims without any window carry no validity
+//! data and never match, as in the retrieval service's temporal
+//! annotations.
+//!
+//! Each tenant's windowed claims are indexed by window start and by window
+//! end. The claims covering `t` are those starting at or before `t` that
+//! also end at or after it, so a lookup walks both sides in step and
+//! checks the side that runs out first against the other bound. Its cost
+//! follows the smaller side rather than the tenant's size. Retrieval
+//! under [`RetrievalOptions::valid_at`](crate::RetrievalOptions::valid_at)
+//! takes the covering claims as its allowed set.
+
+use std::collections::{BTreeMap, HashSet};
+
+use schema::{Claim, ClaimId, TenantId};
+
+use crate::InMemoryStore;
+
+/// One tenant's windowed claims by start and by end; open bounds are
+/// keyed at `i64::MIN` and `i64::MAX`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ValidityIndex {
+    starts: BTreeMap<i64, HashSet<String>>,
+    ends: BTreeMap<i64, HashSet<String>>,
+}
+
+impl ValidityIndex {
+    fn insert(&mut self, claim_id: &str, (start, end): (i64, i64)) {
+        self.starts
+            .entry(start)
+            .or_default()
+            .insert(claim_id.to_string());
+        self.ends
+            .entry(end)
+            .or_default()
+            .insert(claim_id.to_string());
+    }
+
+    fn remove(&mut self, claim_id: &str, (start, end): (i64, i64)) {
+        for (map, key) in [(&mut self.starts, start), (&mut self.ends, end)] {
+            if let Some(claim_ids) = map.get_mut(&key) {
+                claim_ids.remove(claim_id);
+                if claim_ids.is_empty() {
+                    map.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+}
+
+/// Whether `claim`'s validity window covers `as_of_unix`.
+pub(crate) fn claim_valid_at(claim: &Claim, as_of_unix: i64) -> bool {
+    validity_window(claim).is_some_and(|(start, end)| start <= as_of_unix && as_of_unix <= end)
+}
+
+fn validity_window(claim: &Claim) -> Option<(i64, i64)> {
+    if claim.valid_from.is_none() && claim.valid_to.is_none() {
+        return None;
+    }
+    Some((
+        claim.valid_from.unwrap_or(i64::MIN),
+        claim.valid_to.unwrap_or(i64::MAX),
+    ))
+}
+
+impl InMemoryStore {
+    /// Claims of `tenant_id` whose validity window covers `as_of_unix`.
+    pub fn claim_ids_valid_at(&self, tenant_id: &TenantId, as_of_unix: i64) -> HashSet<ClaimId> {
+        let Some(index) = self.validity_index.get(tenant_id.as_str()) else {
+            return HashSet::new();
+        };
+        let mut started = index
+            .starts
+            .range(..=as_of_unix)
+            .flat_map(|(_, claim_ids)| claim_ids);
+        let mut not_ended = index
+            .ends
+            .range(as_of_unix..)
+            .flat_map(|(_, claim_ids)| claim_ids);
+        let mut started_ids = Vec::new();
+        let mut not_ended_ids = Vec::new();
+        let smaller_side = loop {
+            match started.next() {
+                Some(claim_id) => started_ids.push(claim_id),
+                None => break started_ids,
+            }
+            match not_ended.next() {
+                Some(claim_id) => not_ended_ids.push(claim_id),
+                None => break not_ended_ids,
+            }
+        };
+        smaller_side
+            .into_iter()
+            .filter(|claim_id| {
+                self.claims
+                    .get(claim_id.as_str())
+                    .is_some_and(|claim| claim_valid_at(claim, as_of_unix))
+            })
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    pub(crate) fn index_claim_validity(&mut self, claim: &Claim) {
+        if let Some(window) = validity_window(claim) {
+            self.validity_index
+                .entry(claim.tenant_id.to_string())
+                .or_default()
+                .insert(&claim.claim_id, window);
+        }
+    }
+
+    pub(crate) fn unindex_claim_validity(&mut self, claim: &Claim) {
+        let Some(window) = validity_window(claim) else {
+            return;
+        };
+        let Some(index) = self.validity_index.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        index.remove(&claim.claim_id, window);
+        if index.is_empty() {
+            self.validity_index.remove(claim.tenant_id.as_str());
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Single-file backup archives for a [`FileWal`]'s snapshot + WAL delta.
+//!
+//! A store's durable state is two loosely coupled files: the snapshot
+//! written at the last checkpoint and the WAL delta appended since.
+//! Copying them by hand risks pairing a snapshot with the wrong delta.
+//! [`FileWal::backup_to`] captures both in one archive with a manifest
+//! and per-section checksums, and [`FileWal::restore_from`] verifies the
+//! archive before replacing the live files.
+//!
+//! Archive layout (text, one record per line):
+//!
+//! ```text
+//! DASHBACKUP\t1
+//! M\t<created_unix_ms>\t<snapshot_records>\t<wal_records>\t<snapshot_checksum>\t<wal_checksum>
+//! S\t<snapshot record line>   (repeated)
+//! W\t<wal record line>        (repeated)
+//! ```
+//!
+//! Checksums are FNV-1a 64 over the record lines in order, the same
+//! hash family used for batch-commit fingerprints and segment files.
+
+use std::fs::{OpenOptions, create_dir_all, rename};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::wal::{sibling_tmp_path, snapshot_record_count};
+use crate::{FNV1A_64_OFFSET_BASIS, FileWal, StoreError, fnv1a64_feed, line_to_record};
+
+const BACKUP_HEADER: &str = "DASHBACKUP\t1";
+
+/// Describes the contents of a backup archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupManifest {
+    pub created_unix_ms: u64,
+    pub snapshot_records: usize,
+    pub wal_records: usize,
+    pub snapshot_checksum: u64,
+    pub wal_checksum: u64,
+}
+
+impl FileWal {
+    /// Write the current snapshot and WAL delta to a single archive at
+    /// `path`. Pending appends are flushed first so the archive reflects
+    /// every accepted record. The archive is written to a temporary
+    /// sibling and renamed into place.
+    pub fn backup_to(&mut self, path: impl AsRef<Path>) -> Result<BackupManifest, StoreError> {
+        let export = self.replication_export()?;
+        let manifest = BackupManifest {
+            created_unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0),
+            snapshot_records: snapshot_record_count(&export.snapshot_lines),
+            wal_records: export.wal_lines.len(),
+            snapshot_checksum: lines_checksum(&export.snapshot_lines),
+            wal_checksum: lines_checksum(&export.wal_lines),
+        };
+
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            create_dir_all(parent)?;
+        }
+        let tmp_path = sibling_tmp_path(path);
+        {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&tmp_path)?;
+            writeln!(file, "{BACKUP_HEADER}")?;
+            writeln!(
+                file,
+                "M\t{}\t{}\t{}\t{}\t{}",
+                manifest.created_unix_ms,
+                manifest.snapshot_records,
+                manifest.wal_records,
+                manifest.snapshot_checksum,
+                manifest.wal_checksum
+            )?;
+            for line in &export.snapshot_lines {
+                writeln!(file, "S\t{line}")?;
+            }
+            for line in &export.wal_lines {
+                writeln!(file, "W\t{line}")?;
+            }
+            file.sync_all()?;
+        }
+        rename(tmp_path, path)?;
+        Ok(manifest)
+    }
+
+    /// Replace this WAL's snapshot and delta with the contents of the
+    /// archive at `path`. The archive's counts, checksums, and every
+    /// record line are verified before any live file is touched.
+    ///
+    /// Callers must rebuild their `InMemoryStore` afterwards (for
+    /// example with `InMemoryStore::load_from_wal`).
+    pub fn restore_from(&mut self, path: impl AsRef<Path>) -> Result<BackupManifest, StoreError> {
+        let (manifest, snapshot_lines, wal_lines) = read_backup_archive(path.as_ref())?;
+        self.install_lines_atomic(&snapshot_lines, &wal_lines)?;
+        Ok(manifest)
+    }
+}
+
+/// Read and verify an archive without restoring it.
+pub fn verify_backup(path: impl AsRef<Path>) -> Result<BackupManifest, StoreError> {
+    let (manifest, _, _) = read_backup_archive(path.as_ref())?;
+    Ok(manifest)
+}
+
+fn read_backup_archive(
+    path: &Path,
+) -> Result<(BackupManifest, Vec<String>, Vec<String>), StoreError> {
+    let file = OpenOptions::new().read(true).open(path)?;
+    let mut lines = BufReader::new(file).lines();
+    match lines.next().transpose()? {
+        Some(header) if header == BACKUP_HEADER => {}
+        _ => {
+            return Err(StoreError::Parse(
+                "backup archive has invalid header".to_string(),
+            ));
+        }
+    }
+    let manifest_line = lines
+        .next()
+        .transpose()?
+        .ok_or_else(|| StoreError::Parse("backup archive is missing its manifest".to_string()))?;
+    let manifest = parse_manifest_line(&manifest_line)?;
+
+    let mut snapshot_lines = Vec::new();
+    let mut wal_lines = Vec::new();
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.split_once('\t') {
+            Some(("S", record)) if wal_lines.is_empty() => snapshot_lines.push(record.to_string()),
+            Some(("W", record)) => wal_lines.push(record.to_string()),
+            _ => {
+                return Err(StoreError::Parse(
+                    "backup archive has an invalid record line".to_string(),
+                ));
+            }
+        }
+    }
+
+    let archive_snapshot_records = snapshot_record_count(&snapshot_lines);
+    if archive_snapshot_records != manifest.snapshot_records
+        || wal_lines.len() != manifest.wal_records
+    {
+        return Err(StoreError::Parse(format!(
+            "backup archive record count mismatch: manifest snapshot={} wal={}, archive snapshot={} wal={}",
+            manifest.snapshot_records,
+            manifest.wal_records,
+            archive_snapshot_records,
+            wal_lines.len()
+        )));
+    }
+    if lines_checksum(&snapshot_lines) != manifest.snapshot_checksum {
+        return Err(StoreError::Parse(
+            "backup archive snapshot checksum mismatch".to_string(),
+        ));
+    }
+    if lines_checksum(&wal_lines) != manifest.wal_checksum {
+        return Err(StoreError::Parse(
+            "backup archive wal checksum mismatch".to_string(),
+        ));
+    }
+    for line in snapshot_lines.iter().chain(wal_lines.iter()) {
+        let _ = line_to_record(line)?;
+    }
+    Ok((manifest, snapshot_lines, wal_lines))
+}
+
+fn parse_manifest_line(line: &str) -> Result<BackupManifest, StoreError> {
+    let parts: Vec<&str> = line.split('\t').collect();
+    if parts.len() != 6 || parts[0] != "M" {
+        return Err(StoreError::Parse(
+            "backup manifest has invalid field count".to_string(),
+        ));
+    }
+    let field = |idx: usize, name: &str| {
+        parts[idx]
+            .parse::<u64>()
+            .map_err(|_| StoreError::Parse(format!("backup manifest has invalid {name}")))
+    };
+    Ok(BackupManifest {
+        created_unix_ms: field(1, "created_unix_ms")?,
+        snapshot_records: field(2, "snapshot_records")? as usize,
+        wal_records: field(3, "wal_records")? as usize,
+        snapshot_checksum: field(4, "snapshot_checksum")?,
+        wal_checksum: field(5, "wal_checksum")?,
+    })
+}
+
+fn lines_checksum(lines: &[String]) -> u64 {
+    let mut state = FNV1A_64_OFFSET_BASIS;
+    for line in lines {
+        fnv1a64_feed(&mut state, line.as_bytes());
+        fnv1a64_feed(&mut state, b"\n");
+    }
+    state
+}
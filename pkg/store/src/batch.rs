@@ -0,0 +1,52 @@
+//! Several retrievals in one call.
+//!
+//! Agents often split one turn into a handful of sub-queries against the
+//! same tenant, and frequently repeat a sub-query with a different
+//! `top_k` or stance mode. [`InMemoryStore::retrieve_batch`] analyzes each
+//! distinct `(tenant, query)` once and builds its BM25 context once; every
+//! request using it shares that context between candidate generation and
+//! scoring. Each request is otherwise ranked exactly as
+//! [`InMemoryStore::retrieve`] ranks it, through its tenant's pipeline.
+//!
+//! Requests carry no query vector, so a batch never searches the ANN
+//! graph.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use schema::{RetrievalRequest, RetrievalResult};
+
+use crate::pipeline::PipelineScope;
+use crate::{Bm25Context, InMemoryStore, PipelineConfig};
+
+impl InMemoryStore {
+    /// Answer `requests` in order; the result at each index belongs to
+    /// the request at the same index.
+    pub fn retrieve_batch(&self, requests: &[RetrievalRequest]) -> Vec<Vec<RetrievalResult>> {
+        let default_pipeline = PipelineConfig::default();
+        let mut contexts: HashMap<(&str, &str), Bm25Context> = HashMap::new();
+        requests
+            .iter()
+            .map(|req| {
+                let started = Instant::now();
+                let bm25_context = contexts
+                    .entry((req.tenant_id.as_str(), req.query.as_str()))
+                    .or_insert_with(|| self.bm25_context_for_tenant(&req.tenant_id, &req.query));
+                let pipeline = self
+                    .tenant_pipelines
+                    .get(req.tenant_id.as_str())
+                    .unwrap_or(&default_pipeline);
+                let (hits, _) = self.run_pipeline_with_context(
+                    pipeline,
+                    req,
+                    bm25_context,
+                    PipelineScope::default(),
+                    (None, None),
+                );
+                let results = self.hydrate_hits(hits);
+                self.metrics.record_retrieval(started.elapsed());
+                results
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,74 @@
+//! Bloom filter over a tenant's indexed terms.
+//!
+//! Each [`TenantTermIndex`](crate::postings::TenantTermIndex) keeps one so
+//! a query can tell that none of its tokens occur in the tenant before
+//! probing any posting list. The filter only answers "definitely absent"
+//! or "maybe present"; terms whose last posting is removed stay set until
+//! the filter is next rebuilt, which only costs extra false positives.
+//!
+//! Sizing targets about a 1% false-positive rate: ten bits and seven
+//! probes per term, using double hashing over one 64-bit hash.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+const BITS_PER_TERM: usize = 10;
+const PROBES: u64 = 7;
+const MIN_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TermBloom {
+    bits: Vec<u64>,
+    capacity: usize,
+    inserted: usize,
+}
+
+impl TermBloom {
+    /// An empty filter sized for at least `capacity` terms.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(MIN_CAPACITY);
+        Self {
+            bits: vec![0; (capacity * BITS_PER_TERM).div_ceil(64)],
+            capacity,
+            inserted: 0,
+        }
+    }
+
+    /// A filter holding `terms`, with room for as many again.
+    pub(crate) fn from_terms<'a>(terms: impl ExactSizeIterator<Item = &'a String>) -> Self {
+        let mut bloom = Self::with_capacity(terms.len() * 2);
+        for term in terms {
+            bloom.insert(term);
+        }
+        bloom
+    }
+
+    /// Whether inserting another term would exceed the sized capacity.
+    pub(crate) fn is_full(&self) -> bool {
+        self.inserted >= self.capacity
+    }
+
+    pub(crate) fn insert(&mut self, term: &str) {
+        let bit_count = self.bits.len() as u64 * 64;
+        for bit in probes(term, bit_count) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.inserted += 1;
+    }
+
+    /// `false` only when `term` was never inserted.
+    pub(crate) fn may_contain(&self, term: &str) -> bool {
+        if self.bits.is_empty() {
+            return false;
+        }
+        let bit_count = self.bits.len() as u64 * 64;
+        probes(term, bit_count).all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+}
+
+fn probes(term: &str, bit_count: u64) -> impl Iterator<Item = u64> {
+    let mut hasher = DefaultHasher::new();
+    term.hash(&mut hasher);
+    let hash = hasher.finish();
+    let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+    (0..PROBES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bit_count)
+}
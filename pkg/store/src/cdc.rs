@@ -0,0 +1,230 @@
+//! Change-data-capture (CDC) feed for committed store mutations.
+//!
+//! [`WalEvent`](crate::wal::WalEvent) only carries ids, which is enough for
+//! the segment-cache replay path but not for consumers that want to
+//! mirror claims into another system. A [`ChangeSubscription`] obtained
+//! from `InMemoryStore::subscribe` receives a [`ChangeEvent`] with the
+//! full record payload for every data mutation that was applied to the
+//! in-memory state, in apply order: claims, evidence, edges, dense,
+//! sparse and named vectors, batch commits, claim lifecycle changes,
+//! documents, chunks and sources. Tenant configuration (vector configs,
+//! projections, text analyzers) is not published.
+//!
+//! The feed is built on `std::sync::mpsc` so the store stays free of an
+//! async runtime dependency. Payloads are only cloned when at least one
+//! subscriber is attached, and subscribers that have been dropped are
+//! pruned on the next publish. Each subscription queues a bounded
+//! number of events; a subscriber that falls further behind loses the
+//! newest events, which it sees as a sequence gap and in
+//! [`ChangeSubscription::dropped_events`].
+//!
+//! A store staged for a write that may still be rolled back (see
+//! `InMemoryStore::staged`) holds its changes back until
+//! `InMemoryStore::commit_staged`, so subscribers only see committed
+//! writes.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use schema::{Chunk, Claim, ClaimEdge, ClaimId, Document, Evidence, Source, TenantId};
+
+use crate::{BatchCommitMetadata, SparseVector};
+
+/// Events a subscription queues before newer ones are dropped.
+pub const DEFAULT_CHANGE_FEED_CAPACITY: usize = 4096;
+
+/// Full payload of one committed mutation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeRecord {
+    Claim(Claim),
+    Evidence(Evidence),
+    Edge(ClaimEdge),
+    ClaimVector {
+        claim_id: ClaimId,
+        values: Vec<f32>,
+    },
+    BatchCommit(BatchCommitMetadata),
+    ClaimDelete {
+        tenant_id: TenantId,
+        claim_id: ClaimId,
+    },
+    /// Duplicates folded into a primary claim. The evidence and edges
+    /// moved onto the primary, and the duplicates' deletion, are
+    /// published as their own records just before this one.
+    ClaimMerge {
+        tenant_id: TenantId,
+        primary_claim_id: ClaimId,
+        duplicate_claim_ids: Vec<ClaimId>,
+    },
+    /// A claim taken out of retrieval, or returned to it.
+    ClaimArchive {
+        tenant_id: TenantId,
+        claim_id: ClaimId,
+        archived: bool,
+    },
+    /// A source registered, or re-registered, with its tenant.
+    Source(Source),
+    /// A claim's sparse vector, replacing any it had.
+    SparseVector {
+        claim_id: ClaimId,
+        vector: SparseVector,
+    },
+    /// A claim's vector in a named vector space.
+    NamedClaimVector {
+        claim_id: ClaimId,
+        space: String,
+        values: Vec<f32>,
+    },
+    Document(Document),
+    Chunk(Chunk),
+}
+
+/// One entry in the change feed. `sequence` is assigned by the store
+/// and increases by one per published change, so a consumer can detect
+/// gaps if it resubscribes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub sequence: u64,
+    pub record: ChangeRecord,
+}
+
+/// Receiving half of a change feed subscription. Dropping it detaches
+/// the subscriber.
+pub struct ChangeSubscription {
+    receiver: Receiver<ChangeEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl ChangeSubscription {
+    /// Events dropped because this subscription's queue was full.
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Block until the next change is published. Returns `None` once
+    /// every store handle sharing the feed has been dropped.
+    pub fn recv(&self) -> Option<ChangeEvent> {
+        self.receiver.recv().ok()
+    }
+
+    /// Wait up to `timeout` for the next change.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ChangeEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// Return the next change if one is already queued.
+    pub fn try_recv(&self) -> Option<ChangeEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Drain every change that is currently queued without blocking.
+    pub fn drain(&self) -> Vec<ChangeEvent> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Iterator for ChangeSubscription {
+    type Item = ChangeEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+/// Publisher side, owned by the store. Cloning shares the subscriber
+/// list and sequence counter, matching how cloned stores share their
+/// disk handle. A staged feed shares them too but buffers what it is
+/// given until [`ChangeFeed::commit`].
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ChangeFeed {
+    inner: Arc<ChangeFeedInner>,
+    pending: Option<Arc<Mutex<Vec<ChangeRecord>>>>,
+}
+
+#[derive(Debug, Default)]
+struct ChangeFeedInner {
+    subscribers: Mutex<Vec<Subscriber>>,
+    next_sequence: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    sender: SyncSender<ChangeEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl ChangeFeed {
+    pub(crate) fn subscribe(&self, capacity: usize) -> ChangeSubscription {
+        let (sender, receiver) = mpsc::sync_channel(capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        self.lock_subscribers().push(Subscriber {
+            sender,
+            dropped: Arc::clone(&dropped),
+        });
+        ChangeSubscription { receiver, dropped }
+    }
+
+    pub(crate) fn subscriber_count(&self) -> usize {
+        self.lock_subscribers().len()
+    }
+
+    /// A feed sharing this one's subscribers that holds changes back
+    /// until they are committed.
+    pub(crate) fn staged(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            pending: Some(Arc::default()),
+        }
+    }
+
+    /// Publish the changes `staged` held back, in the order it was
+    /// given them.
+    pub(crate) fn commit(&self, staged: &Self) {
+        let Some(pending) = staged.pending.as_ref() else {
+            return;
+        };
+        let records = std::mem::take(&mut *lock(pending));
+        for record in records {
+            self.publish_with(|| record);
+        }
+    }
+
+    /// Publish a change built lazily by `build`. The closure is not
+    /// invoked when nobody is subscribed.
+    pub(crate) fn publish_with(&self, build: impl FnOnce() -> ChangeRecord) {
+        let mut subscribers = self.lock_subscribers();
+        if subscribers.is_empty() {
+            return;
+        }
+        if let Some(pending) = self.pending.as_ref() {
+            lock(pending).push(build());
+            return;
+        }
+        let event = ChangeEvent {
+            sequence: self.inner.next_sequence.fetch_add(1, Ordering::Relaxed),
+            record: build(),
+        };
+        subscribers.retain(
+            |subscriber| match subscriber.sender.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    subscriber.dropped.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            },
+        );
+    }
+
+    fn lock_subscribers(&self) -> std::sync::MutexGuard<'_, Vec<Subscriber>> {
+        lock(&self.inner.subscribers)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
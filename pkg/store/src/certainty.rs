@@ -0,0 +1,111 @@
+//! Certainty bands: a categorical trust signal per result.
+//!
+//! Agents consuming retrieval rarely want to weigh support counts,
+//! source quality and confidence themselves. Every result carries a
+//! [`CertaintyBand`] derived from them:
+//!
+//! - contested when contradictions make up at least a third of the
+//!   claim's stance signals, whatever supports it;
+//! - corroborated when at least two distinct sources of quality at least
+//!   0.5 support a claim with confidence at least 0.5;
+//! - unverified otherwise.
+//!
+//! Stance signals count evidence and supporting or contradicting edges,
+//! as the result's `supports` and `contradicts` do. A retrieval can keep
+//! only some bands with
+//! [`RetrievalOptions::certainty_bands`](crate::RetrievalOptions::certainty_bands).
+
+use std::collections::HashSet;
+
+use graph::summarize_edges;
+use schema::{CertaintyBand, Claim, ClaimId, Evidence, Stance, TenantId};
+
+use crate::InMemoryStore;
+
+/// Distinct good sources needed to call a claim corroborated.
+const CORROBORATING_SOURCES: usize = 2;
+/// Source quality and claim confidence below which support does not
+/// count towards corroboration.
+const MIN_CORROBORATING_QUALITY: f32 = 0.5;
+
+/// The band of `claim` given all of its `evidence`, rated by `quality`,
+/// and its stance counts including edges.
+pub(crate) fn certainty_band(
+    claim: &Claim,
+    evidence: &[Evidence],
+    quality: impl Fn(&Evidence) -> f32,
+    supports: usize,
+    contradicts: usize,
+) -> CertaintyBand {
+    if contradicts > 0 && contradicts * 3 >= supports + contradicts {
+        return CertaintyBand::Contested;
+    }
+    if claim.confidence < MIN_CORROBORATING_QUALITY {
+        return CertaintyBand::Unverified;
+    }
+    let good_sources: HashSet<&str> = evidence
+        .iter()
+        .filter(|e| {
+            e.effective_stance() == Stance::Supports && quality(e) >= MIN_CORROBORATING_QUALITY
+        })
+        .map(|e| e.source_id.as_str())
+        .collect();
+    if good_sources.len() >= CORROBORATING_SOURCES {
+        CertaintyBand::Corroborated
+    } else {
+        CertaintyBand::Unverified
+    }
+}
+
+impl InMemoryStore {
+    /// The band a retrieval would report for `claim_id`.
+    pub fn claim_certainty_band(&self, claim_id: &ClaimId) -> Option<CertaintyBand> {
+        self.certainty_band_of(claim_id)
+    }
+
+    fn certainty_band_of(&self, claim_id: &str) -> Option<CertaintyBand> {
+        let claim = self.claims.get(claim_id)?;
+        let evidence = self
+            .evidence_by_claim
+            .get(claim_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let edges = summarize_edges(
+            self.edges_by_claim
+                .get(claim_id)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        );
+        let count = |stance: Stance| {
+            evidence
+                .iter()
+                .filter(|e| e.effective_stance() == stance)
+                .count()
+        };
+        Some(certainty_band(
+            claim,
+            evidence,
+            |e| self.evidence_quality(&claim.tenant_id, e),
+            count(Stance::Supports) + edges.supports,
+            count(Stance::Contradicts) + edges.contradicts,
+        ))
+    }
+
+    /// Claims of `tenant_id` in any of `bands`.
+    pub fn claim_ids_in_certainty_bands(
+        &self,
+        tenant_id: &TenantId,
+        bands: &[CertaintyBand],
+    ) -> HashSet<ClaimId> {
+        self.tenant_claim_ids
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|claim_id| {
+                self.certainty_band_of(claim_id)
+                    .is_some_and(|band| bands.contains(&band))
+            })
+            .map(ClaimId::from)
+            .collect()
+    }
+}
@@ -0,0 +1,265 @@
+//! Inspecting and hand-editing single claims.
+//!
+//! These are the curation tools behind the admin API: fetch a claim with
+//! everything attached to it, patch some of its fields, reassign its
+//! entities, or rebuild its index entries. Re-applying a claim normally
+//! drops its vectors along with its old index entries; the edits here
+//! carry the claim's default, named, and sparse vectors over, and the
+//! persistent variants write them to the WAL after the patched claim so
+//! replay ends in the same state.
+
+use schema::{
+    Claim, ClaimEdge, ClaimId, ClaimType, Entity, Evidence, TenantId, validate_claim_with,
+};
+
+use crate::wal::{ClaimVectorRecord, PersistedRecord};
+use crate::{FileWal, InMemoryStore, StoreError};
+
+/// A claim with its evidence, edges, and a summary of its vectors, as
+/// returned by [`InMemoryStore::inspect_claim`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimInspection {
+    pub claim: Claim,
+    pub evidence: Vec<Evidence>,
+    pub edges: Vec<ClaimEdge>,
+    /// Dimension of the claim's default vector, if it has one.
+    pub vector_dimension: Option<usize>,
+    /// Named vector spaces holding a vector for the claim, sorted.
+    pub vector_spaces: Vec<String>,
+    /// Number of terms in the claim's sparse vector, if it has one.
+    pub sparse_term_count: Option<usize>,
+}
+
+/// Fields to change on a claim; `None` leaves a field as it is. For the
+/// optional fields, `Some(None)` clears the value.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ClaimPatch {
+    pub canonical_text: Option<String>,
+    pub confidence: Option<f32>,
+    pub claim_type: Option<Option<ClaimType>>,
+    pub event_time_unix: Option<Option<i64>>,
+    pub valid_from: Option<Option<i64>>,
+    pub valid_to: Option<Option<i64>>,
+    pub updated_at: Option<Option<i64>>,
+    pub entities: Option<Vec<Entity>>,
+    pub embedding_ids: Option<Vec<String>>,
+    pub metadata: Option<Vec<(String, String)>>,
+    pub visibility_labels: Option<Vec<String>>,
+}
+
+impl ClaimPatch {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn apply_to(&self, claim: &Claim) -> Claim {
+        let mut patched = claim.clone();
+        if let Some(canonical_text) = &self.canonical_text {
+            patched.canonical_text = canonical_text.clone();
+        }
+        if let Some(confidence) = self.confidence {
+            patched.confidence = confidence;
+        }
+        if let Some(claim_type) = &self.claim_type {
+            patched.claim_type = claim_type.clone();
+        }
+        if let Some(event_time_unix) = self.event_time_unix {
+            patched.event_time_unix = event_time_unix;
+        }
+        if let Some(valid_from) = self.valid_from {
+            patched.valid_from = valid_from;
+        }
+        if let Some(valid_to) = self.valid_to {
+            patched.valid_to = valid_to;
+        }
+        if let Some(updated_at) = self.updated_at {
+            patched.updated_at = updated_at;
+        }
+        if let Some(entities) = &self.entities {
+            patched.entities = entities.clone();
+        }
+        if let Some(embedding_ids) = &self.embedding_ids {
+            patched.embedding_ids = embedding_ids.clone();
+        }
+        if let Some(metadata) = &self.metadata {
+            patched.metadata = metadata.clone();
+        }
+        if let Some(visibility_labels) = &self.visibility_labels {
+            patched.visibility_labels = visibility_labels.clone();
+        }
+        patched
+    }
+}
+
+impl InMemoryStore {
+    /// `None` when the claim does not exist or belongs to another tenant.
+    pub fn inspect_claim(
+        &self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Option<ClaimInspection> {
+        let claim = self
+            .claims
+            .get(claim_id.as_str())
+            .filter(|claim| claim.tenant_id == *tenant_id)?;
+        let mut vector_spaces: Vec<String> = self
+            .named_vector_spaces
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|(_, store)| store.claim_vectors.get(claim_id).is_some())
+            .map(|(space, _)| space.clone())
+            .collect();
+        vector_spaces.sort_unstable();
+        Some(ClaimInspection {
+            claim: claim.clone(),
+            evidence: self
+                .evidence_by_claim
+                .get(claim_id.as_str())
+                .cloned()
+                .unwrap_or_default(),
+            edges: self.edges_for_claim(claim_id),
+            vector_dimension: self.claim_vectors.get(claim_id).map(|values| values.len()),
+            vector_spaces,
+            sparse_term_count: self
+                .sparse_vectors
+                .get(claim_id.as_str())
+                .map(|vector| vector.len()),
+        })
+    }
+
+    /// Apply `patch` to a claim and return the updated claim. The claim
+    /// keeps its evidence, edges, and vectors.
+    pub fn patch_claim(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+        patch: &ClaimPatch,
+    ) -> Result<Claim, StoreError> {
+        let patched = patch.apply_to(self.tenant_claim(tenant_id, claim_id)?);
+        validate_claim_with(&patched, &self.validation_config)?;
+        let vector_records = self.claim_vector_records(&patched);
+        self.replace_claim(patched.clone(), vector_records)?;
+        Ok(patched)
+    }
+
+    pub fn patch_claim_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+        patch: &ClaimPatch,
+    ) -> Result<Claim, StoreError> {
+        let patched = patch.apply_to(self.tenant_claim(tenant_id, claim_id)?);
+        validate_claim_with(&patched, &self.validation_config)?;
+        let vector_records = self.claim_vector_records(&patched);
+
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_claim(&patched)?;
+        for record in &vector_records {
+            wal.append_record(record)?;
+        }
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+
+        self.replace_claim(patched.clone(), vector_records)?;
+        Ok(patched)
+    }
+
+    /// Replace a claim's entities, for example after merging two spellings
+    /// of the same entity.
+    pub fn reassign_claim_entities(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+        entities: Vec<Entity>,
+    ) -> Result<Claim, StoreError> {
+        let patch = ClaimPatch {
+            entities: Some(entities),
+            ..ClaimPatch::default()
+        };
+        self.patch_claim(tenant_id, claim_id, &patch)
+    }
+
+    pub fn reassign_claim_entities_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+        entities: Vec<Entity>,
+    ) -> Result<Claim, StoreError> {
+        let patch = ClaimPatch {
+            entities: Some(entities),
+            ..ClaimPatch::default()
+        };
+        self.patch_claim_persistent(wal, tenant_id, claim_id, &patch)
+    }
+
+    /// Drop and rebuild a claim's lexical, entity, embedding-id, metadata,
+    /// temporal, and vector index entries from the stored claim. Index
+    /// entries are derived state, so nothing is written to the WAL.
+    pub fn reindex_claim(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<(), StoreError> {
+        let claim = self.tenant_claim(tenant_id, claim_id)?.clone();
+        let vector_records = self.claim_vector_records(&claim);
+        self.remove_claim_indexes(&claim);
+        self.add_claim_indexes(&claim);
+        for record in vector_records {
+            self.apply_persisted_record(record)?;
+        }
+        Ok(())
+    }
+
+    fn tenant_claim(&self, tenant_id: &str, claim_id: &str) -> Result<&Claim, StoreError> {
+        self.claims
+            .get(claim_id)
+            .filter(|claim| claim.tenant_id == tenant_id)
+            .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string()))
+    }
+
+    pub(crate) fn replace_claim(
+        &mut self,
+        claim: Claim,
+        vector_records: Vec<PersistedRecord>,
+    ) -> Result<(), StoreError> {
+        self.apply_claim(claim)?;
+        for record in vector_records {
+            self.apply_persisted_record(record)?;
+        }
+        Ok(())
+    }
+
+    /// Records that restore every vector `claim` has: the default one,
+    /// the named ones, and the sparse one.
+    pub(crate) fn claim_vector_records(&self, claim: &Claim) -> Vec<PersistedRecord> {
+        let claim_id = &claim.claim_id;
+        let mut records = Vec::new();
+        if let Some(values) = self.claim_vectors.get(claim_id) {
+            records.push(PersistedRecord::ClaimVector(ClaimVectorRecord {
+                claim_id: claim_id.to_string(),
+                values: values.into_owned(),
+                space: None,
+            }));
+        }
+        for (space, store) in self
+            .named_vector_spaces
+            .get(claim.tenant_id.as_str())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(values) = store.claim_vectors.get(claim_id) {
+                records.push(PersistedRecord::ClaimVector(ClaimVectorRecord {
+                    claim_id: claim_id.to_string(),
+                    values: values.into_owned(),
+                    space: Some(space.clone()),
+                }));
+            }
+        }
+        self.push_sparse_vector_records(std::slice::from_ref(claim_id), &mut records);
+        records
+    }
+}
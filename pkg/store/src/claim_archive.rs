@@ -0,0 +1,166 @@
+//! Archiving claims without deleting them.
+//!
+//! Deletion drops a claim with its evidence and edges. Archiving keeps
+//! all of it, readable through the usual accessors, and only takes the
+//! claim out of retrieval: every retrieve path skips archived claims
+//! unless [`RetrievalOptions::include_archived`](crate::RetrievalOptions::include_archived)
+//! is set. Unarchiving brings the claim back unchanged.
+//!
+//! The persistent variants write an archive record to the WAL, and
+//! checkpoints write one for every archived claim, so the state survives
+//! compaction.
+
+use std::collections::HashSet;
+
+use schema::{ClaimId, TenantId};
+
+use crate::{ChangeRecord, FileWal, InMemoryStore, StoreError};
+
+impl InMemoryStore {
+    /// Take a claim of `tenant_id` out of retrieval. Returns whether it
+    /// was not archived already.
+    pub fn archive_claim(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<bool, StoreError> {
+        self.set_claim_archived(tenant_id, claim_id, true)
+    }
+
+    /// Return an archived claim to retrieval. Returns whether it was
+    /// archived.
+    pub fn unarchive_claim(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<bool, StoreError> {
+        self.set_claim_archived(tenant_id, claim_id, false)
+    }
+
+    /// [`Self::archive_claim`], recording the change in `wal` first.
+    pub fn archive_claim_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<bool, StoreError> {
+        self.set_claim_archived_persistent(wal, tenant_id, claim_id, true)
+    }
+
+    /// [`Self::unarchive_claim`], recording the change in `wal` first.
+    pub fn unarchive_claim_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<bool, StoreError> {
+        self.set_claim_archived_persistent(wal, tenant_id, claim_id, false)
+    }
+
+    pub fn is_claim_archived(&self, tenant_id: &TenantId, claim_id: &ClaimId) -> bool {
+        self.archived_claims
+            .get(tenant_id.as_str())
+            .is_some_and(|claim_ids| claim_ids.contains(claim_id.as_str()))
+    }
+
+    /// The archived claims of `tenant_id`, sorted.
+    pub fn archived_claim_ids(&self, tenant_id: &TenantId) -> Vec<ClaimId> {
+        let mut claim_ids: Vec<ClaimId> = self
+            .archived_claims
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect();
+        claim_ids.sort_unstable();
+        claim_ids
+    }
+
+    fn set_claim_archived_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+        archived: bool,
+    ) -> Result<bool, StoreError> {
+        if !self.owns_claim(tenant_id, claim_id) {
+            return Err(StoreError::MissingClaim(claim_id.to_string()));
+        }
+        if self.is_claim_archived(tenant_id, claim_id) == archived {
+            return Ok(false);
+        }
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_claim_archive(tenant_id, claim_id, archived)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        Ok(self.apply_claim_archive(tenant_id, claim_id, archived))
+    }
+
+    fn set_claim_archived(
+        &mut self,
+        tenant_id: &str,
+        claim_id: &str,
+        archived: bool,
+    ) -> Result<bool, StoreError> {
+        if !self.owns_claim(tenant_id, claim_id) {
+            return Err(StoreError::MissingClaim(claim_id.to_string()));
+        }
+        Ok(self.apply_claim_archive(tenant_id, claim_id, archived))
+    }
+
+    /// Claims that are gone are skipped, so replaying an archive record
+    /// after the claim's deletion is harmless.
+    pub(crate) fn apply_claim_archive(
+        &mut self,
+        tenant_id: &str,
+        claim_id: &str,
+        archived: bool,
+    ) -> bool {
+        if !self.owns_claim(tenant_id, claim_id) {
+            return false;
+        }
+        let changed = if archived {
+            self.archived_claims
+                .entry(tenant_id.to_string())
+                .or_default()
+                .insert(claim_id.to_string())
+        } else {
+            self.unarchive_claim_entry(tenant_id, claim_id)
+        };
+        if changed {
+            self.bump_index_epoch(tenant_id);
+            self.change_feed
+                .publish_with(|| ChangeRecord::ClaimArchive {
+                    tenant_id: tenant_id.into(),
+                    claim_id: claim_id.into(),
+                    archived,
+                });
+        }
+        changed
+    }
+
+    /// Forget `claim_id`'s archived state, as when it is deleted.
+    pub(crate) fn unarchive_claim_entry(&mut self, tenant_id: &str, claim_id: &str) -> bool {
+        let Some(claim_ids) = self.archived_claims.get_mut(tenant_id) else {
+            return false;
+        };
+        let removed = claim_ids.remove(claim_id);
+        if claim_ids.is_empty() {
+            self.archived_claims.remove(tenant_id);
+        }
+        removed
+    }
+
+    /// Drop the archived claims of `tenant_id` from `candidates`, for the
+    /// retrieve paths that bypass the pipeline's filters stage.
+    pub(crate) fn retain_unarchived(&self, tenant_id: &str, candidates: &mut Vec<String>) {
+        if let Some(archived) = self.archived_claims.get(tenant_id) {
+            candidates.retain(|claim_id| !archived.contains(claim_id));
+        }
+    }
+
+    /// The archived claims of `tenant_id`, or `None` when it has none.
+    pub(crate) fn archived_claim_set(&self, tenant_id: &str) -> Option<&HashSet<String>> {
+        self.archived_claims.get(tenant_id)
+    }
+}
@@ -0,0 +1,106 @@
+//! Removing claims.
+//!
+//! Deleting a claim drops it from every index along with its evidence,
+//! its outgoing edges, and its vectors. Edges from other claims that
+//! point at it are left in place, as they are when an edge names a
+//! claim that was never ingested. The persistent variant writes a
+//! tombstone to the WAL so replay removes the claim again; snapshots
+//! taken afterwards simply no longer contain it.
+
+use schema::{ClaimId, TenantId};
+
+use crate::{ChangeRecord, FileWal, InMemoryStore, StoreError, WalEvent, disk_error};
+
+impl InMemoryStore {
+    /// Delete `claim_id` from `tenant_id`. Returns `false` when the
+    /// tenant holds no such claim.
+    pub fn delete_claim(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<bool, StoreError> {
+        self.apply_claim_delete(tenant_id, claim_id)
+    }
+
+    /// [`Self::delete_claim`], recording a tombstone in `wal` first.
+    /// Nothing is written when the claim does not exist.
+    pub fn delete_claim_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<bool, StoreError> {
+        if !self.owns_claim(tenant_id, claim_id) {
+            return Ok(false);
+        }
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_claim_delete(tenant_id, claim_id)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        self.apply_claim_delete(tenant_id, claim_id)
+    }
+
+    /// Delete every one of `claim_ids` from `tenant_id`, as
+    /// [`Self::delete_claim`] does. Returns how many were deleted.
+    pub fn delete_claims(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_ids: &[ClaimId],
+    ) -> Result<usize, StoreError> {
+        let mut deleted = 0;
+        for claim_id in claim_ids {
+            deleted += usize::from(self.apply_claim_delete(tenant_id, claim_id)?);
+        }
+        Ok(deleted)
+    }
+
+    /// [`Self::delete_claims`], with a tombstone in `wal` for each claim
+    /// deleted.
+    pub fn delete_claims_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        claim_ids: &[ClaimId],
+    ) -> Result<usize, StoreError> {
+        let mut deleted = 0;
+        for claim_id in claim_ids {
+            deleted += usize::from(self.delete_claim_persistent(wal, tenant_id, claim_id)?);
+        }
+        Ok(deleted)
+    }
+
+    pub(crate) fn apply_claim_delete(
+        &mut self,
+        tenant_id: &str,
+        claim_id: &str,
+    ) -> Result<bool, StoreError> {
+        if !self.owns_claim(tenant_id, claim_id) {
+            return Ok(false);
+        }
+        if let Some(disk) = self.disk.as_ref() {
+            disk.delete_claim(tenant_id, claim_id).map_err(disk_error)?;
+        }
+        let Some(claim) = self.claims.remove(claim_id) else {
+            return Ok(false);
+        };
+        self.remove_claim_indexes(&claim);
+        self.unindex_evidence_sources(tenant_id, claim_id);
+        self.unarchive_claim_entry(tenant_id, claim_id);
+        self.evidence_by_claim.remove(claim_id);
+        self.unindex_incoming_edges(claim_id);
+        self.edges_by_claim.remove(claim_id);
+        self.claim_access.forget(claim_id);
+        self.change_feed.publish_with(|| ChangeRecord::ClaimDelete {
+            tenant_id: tenant_id.into(),
+            claim_id: claim_id.into(),
+        });
+        self.wal.push(WalEvent::ClaimDelete(claim_id.to_string()));
+        Ok(true)
+    }
+
+    pub(crate) fn owns_claim(&self, tenant_id: &str, claim_id: &str) -> bool {
+        self.claims
+            .get(claim_id)
+            .is_some_and(|claim| claim.tenant_id == tenant_id)
+    }
+}
@@ -0,0 +1,69 @@
+//! Walking a tenant's claims without copying them all.
+//!
+//! [`InMemoryStore::claims_for_tenant`] clones every claim of the tenant
+//! into one `Vec`, which is fine for a handful and prohibitive for an
+//! export of millions. [`InMemoryStore::iter_claims`] borrows them
+//! instead, and [`InMemoryStore::claims_page`] hands them out a page at
+//! a time in claim id order, for callers that cannot hold a borrow
+//! across requests. A page's cursor is the last claim id it returned, so
+//! claims added or deleted between pages neither shift nor repeat the
+//! rest.
+
+use schema::{Claim, ClaimId, TenantId};
+
+use crate::InMemoryStore;
+
+/// One page of [`InMemoryStore::claims_page`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClaimPage {
+    pub claims: Vec<Claim>,
+    /// Cursor for the next page; `None` after the last.
+    pub next_cursor: Option<ClaimId>,
+}
+
+impl InMemoryStore {
+    /// The claims of `tenant_id`, borrowed, in no particular order.
+    pub fn iter_claims<'a>(&'a self, tenant_id: &TenantId) -> impl Iterator<Item = &'a Claim> + 'a {
+        self.tenant_claim_ids
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter_map(|claim_id| self.claims.get(claim_id.as_str()))
+    }
+
+    /// Up to `limit` claims of `tenant_id` with ids after `cursor`, in
+    /// claim id order. Start with `cursor` unset and pass each page's
+    /// `next_cursor` to get the next.
+    pub fn claims_page(
+        &self,
+        tenant_id: &TenantId,
+        cursor: Option<&ClaimId>,
+        limit: usize,
+    ) -> ClaimPage {
+        let mut claim_ids: Vec<&String> = self
+            .tenant_claim_ids
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|claim_id| cursor.is_none_or(|cursor| claim_id.as_str() > cursor.as_str()))
+            .collect();
+        let has_more = claim_ids.len() > limit;
+        if has_more {
+            claim_ids.select_nth_unstable(limit);
+            claim_ids.truncate(limit);
+        }
+        claim_ids.sort_unstable();
+        let claims: Vec<Claim> = claim_ids
+            .into_iter()
+            .filter_map(|claim_id| self.claims.get(claim_id.as_str()))
+            .cloned()
+            .collect();
+        let next_cursor = has_more
+            .then(|| claims.last().map(|claim| claim.claim_id.clone()))
+            .flatten();
+        ClaimPage {
+            claims,
+            next_cursor,
+        }
+    }
+}
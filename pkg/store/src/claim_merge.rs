@@ -0,0 +1,223 @@
+//! Folding duplicate claims into one.
+//!
+//! Extraction over many documents produces near-identical claims, each
+//! with a share of the evidence. [`InMemoryStore::merge_claims`] keeps a
+//! primary claim and moves everything attached to the duplicates onto
+//! it: their evidence, their outgoing edges, and edges from other claims
+//! that pointed at them. The primary then records a
+//! [`Relation::Duplicates`] edge to each duplicate, and the duplicates
+//! are deleted as by [`InMemoryStore::delete_claim`]. Evidence or edges
+//! already on the primary under the same id are not moved twice, and
+//! edges between a duplicate and the primary are dropped rather than
+//! turned into self-loops.
+//!
+//! The persistent variant writes one merge record to the WAL; replay
+//! repeats the merge from the state before it.
+
+use std::collections::HashSet;
+
+use schema::{ClaimEdge, ClaimId, EdgeId, EvidenceId, Relation, TenantId, ValidationError};
+
+use crate::{ChangeRecord, FileWal, InMemoryStore, StoreError, disk_error};
+
+/// What a merge moved onto the primary claim.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClaimMergeStats {
+    /// Duplicates folded in and deleted, in request order.
+    pub merged_claim_ids: Vec<ClaimId>,
+    pub evidence_moved: usize,
+    /// Outgoing edges of the duplicates now leaving the primary.
+    pub edges_moved: usize,
+    /// Edges from other claims now pointing at the primary.
+    pub incoming_edges_repointed: usize,
+}
+
+impl InMemoryStore {
+    /// Merge `duplicate_ids` into `primary_id`. Every duplicate must be a
+    /// claim of the primary's tenant other than the primary itself.
+    pub fn merge_claims(
+        &mut self,
+        primary_id: &ClaimId,
+        duplicate_ids: &[ClaimId],
+    ) -> Result<ClaimMergeStats, StoreError> {
+        let tenant_id = self.validate_claim_merge(primary_id, duplicate_ids)?;
+        self.apply_claim_merge(&tenant_id, primary_id, duplicate_ids)
+    }
+
+    /// [`Self::merge_claims`], recording the merge in `wal` first.
+    pub fn merge_claims_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        primary_id: &ClaimId,
+        duplicate_ids: &[ClaimId],
+    ) -> Result<ClaimMergeStats, StoreError> {
+        let tenant_id = self.validate_claim_merge(primary_id, duplicate_ids)?;
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_claim_merge(&tenant_id, primary_id, duplicate_ids)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        self.apply_claim_merge(&tenant_id, primary_id, duplicate_ids)
+    }
+
+    /// The primary's tenant, once the merge is known to be valid.
+    fn validate_claim_merge(
+        &self,
+        primary_id: &ClaimId,
+        duplicate_ids: &[ClaimId],
+    ) -> Result<TenantId, StoreError> {
+        let primary = self
+            .claims
+            .get(primary_id)
+            .ok_or_else(|| StoreError::MissingClaim(primary_id.to_string()))?;
+        let invalid =
+            || StoreError::Validation(ValidationError::InvalidRange("merge duplicate ids"));
+        if duplicate_ids.is_empty() {
+            return Err(invalid());
+        }
+        let mut seen = HashSet::new();
+        for duplicate_id in duplicate_ids {
+            if duplicate_id == primary_id || !seen.insert(duplicate_id.as_str()) {
+                return Err(invalid());
+            }
+            if !self.owns_claim(&primary.tenant_id, duplicate_id) {
+                return Err(StoreError::MissingClaim(duplicate_id.to_string()));
+            }
+        }
+        Ok(primary.tenant_id.clone())
+    }
+
+    /// Duplicates that are already gone are skipped, so replaying a merge
+    /// over a snapshot taken after it is harmless.
+    pub(crate) fn apply_claim_merge(
+        &mut self,
+        tenant_id: &str,
+        primary_id: &ClaimId,
+        duplicate_ids: &[ClaimId],
+    ) -> Result<ClaimMergeStats, StoreError> {
+        if !self.owns_claim(tenant_id, primary_id) {
+            return Err(StoreError::MissingClaim(primary_id.to_string()));
+        }
+        let mut stats = ClaimMergeStats::default();
+        for duplicate_id in duplicate_ids {
+            if duplicate_id == primary_id || !self.owns_claim(tenant_id, duplicate_id) {
+                continue;
+            }
+            self.fold_duplicate(tenant_id, primary_id, duplicate_id, &mut stats)?;
+            stats.merged_claim_ids.push(duplicate_id.clone());
+        }
+        self.change_feed.publish_with(|| ChangeRecord::ClaimMerge {
+            tenant_id: tenant_id.into(),
+            primary_claim_id: primary_id.clone(),
+            duplicate_claim_ids: stats.merged_claim_ids.clone(),
+        });
+        Ok(stats)
+    }
+
+    fn fold_duplicate(
+        &mut self,
+        tenant_id: &str,
+        primary_id: &str,
+        duplicate_id: &str,
+        stats: &mut ClaimMergeStats,
+    ) -> Result<(), StoreError> {
+        let primary_evidence_ids: HashSet<EvidenceId> = self
+            .evidence_by_claim
+            .get(primary_id)
+            .into_iter()
+            .flatten()
+            .map(|evidence| evidence.evidence_id.clone())
+            .collect();
+        let evidence = self
+            .evidence_by_claim
+            .get(duplicate_id)
+            .cloned()
+            .unwrap_or_default();
+        for mut evidence in evidence {
+            if primary_evidence_ids.contains(&evidence.evidence_id) {
+                continue;
+            }
+            evidence.claim_id = primary_id.into();
+            self.apply_evidence(evidence)?;
+            stats.evidence_moved += 1;
+        }
+
+        let primary_edge_ids: HashSet<EdgeId> = self
+            .edges_by_claim
+            .get(primary_id)
+            .into_iter()
+            .flatten()
+            .map(|edge| edge.edge_id.clone())
+            .collect();
+        let edges = self
+            .edges_by_claim
+            .get(duplicate_id)
+            .cloned()
+            .unwrap_or_default();
+        for mut edge in edges {
+            if edge.to_claim_id == primary_id || primary_edge_ids.contains(&edge.edge_id) {
+                continue;
+            }
+            edge.from_claim_id = primary_id.into();
+            self.apply_edge(edge)?;
+            stats.edges_moved += 1;
+        }
+
+        stats.incoming_edges_repointed +=
+            self.repoint_incoming_edges(tenant_id, primary_id, duplicate_id)?;
+
+        let mut merge_edge = ClaimEdge::new(
+            format!("merge:{primary_id}:{duplicate_id}"),
+            primary_id,
+            duplicate_id,
+            Relation::Duplicates,
+            1.0,
+        );
+        merge_edge.reason_codes = vec!["merged".to_string()];
+        self.apply_edge(merge_edge)?;
+        self.apply_claim_delete(tenant_id, duplicate_id)?;
+        Ok(())
+    }
+
+    /// Point edges from the tenant's other claims at `primary_id` instead
+    /// of `duplicate_id`; an edge from the primary itself is dropped.
+    fn repoint_incoming_edges(
+        &mut self,
+        tenant_id: &str,
+        primary_id: &str,
+        duplicate_id: &str,
+    ) -> Result<usize, StoreError> {
+        let mut owners: Vec<String> = self
+            .claims_with_edges_into(duplicate_id)
+            .into_iter()
+            .filter(|owner| owner != duplicate_id && self.owns_claim(tenant_id, owner))
+            .collect();
+        owners.sort_unstable();
+        let mut repointed = 0;
+        for owner in owners {
+            let mut edges = self.edges_by_claim[&owner].clone();
+            edges.retain_mut(|edge| {
+                if edge.to_claim_id != duplicate_id {
+                    return true;
+                }
+                if owner == primary_id {
+                    return false;
+                }
+                edge.to_claim_id = primary_id.into();
+                repointed += 1;
+                true
+            });
+            if let Some(disk) = self.disk.as_ref() {
+                disk.put_edge_blob(&owner, &edges).map_err(disk_error)?;
+            }
+            self.unindex_incoming_edges(&owner);
+            for edge in &edges {
+                self.index_incoming_edge(edge);
+            }
+            self.edges_by_claim.insert(owner, edges);
+        }
+        if repointed > 0 {
+            self.bump_index_epoch(tenant_id);
+        }
+        Ok(repointed)
+    }
+}
@@ -0,0 +1,72 @@
+//! Filtering claims on their [`ClaimType`].
+//!
+//! Typed claims are indexed per tenant by type, so a filter such as "only
+//! factual claims" for grounding resolves to its claims without a pass
+//! over the tenant. Claims without a type are not indexed and never match
+//! a type filter. As with metadata filters, retrieval under
+//! [`RetrievalOptions::claim_types`](crate::RetrievalOptions::claim_types)
+//! takes the matching claims as its allowed set.
+
+use std::collections::HashSet;
+
+use schema::{Claim, ClaimId, ClaimType, TenantId};
+
+use crate::InMemoryStore;
+
+impl InMemoryStore {
+    pub fn claim_ids_for_claim_type(
+        &self,
+        tenant_id: &TenantId,
+        claim_type: &ClaimType,
+    ) -> HashSet<ClaimId> {
+        self.claim_type_index
+            .get(tenant_id.as_str())
+            .and_then(|index| index.get(claim_type))
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    /// Claims of `tenant_id` having any of `claim_types`.
+    pub fn claim_ids_for_claim_types(
+        &self,
+        tenant_id: &TenantId,
+        claim_types: &[ClaimType],
+    ) -> HashSet<ClaimId> {
+        claim_types
+            .iter()
+            .flat_map(|claim_type| self.claim_ids_for_claim_type(tenant_id, claim_type))
+            .collect()
+    }
+
+    pub(crate) fn index_claim_type(&mut self, claim: &Claim) {
+        let Some(claim_type) = &claim.claim_type else {
+            return;
+        };
+        self.claim_type_index
+            .entry(claim.tenant_id.to_string())
+            .or_default()
+            .entry(claim_type.clone())
+            .or_default()
+            .insert(claim.claim_id.to_string());
+    }
+
+    pub(crate) fn unindex_claim_type(&mut self, claim: &Claim) {
+        let Some(claim_type) = &claim.claim_type else {
+            return;
+        };
+        let Some(index) = self.claim_type_index.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        if let Some(claim_ids) = index.get_mut(claim_type) {
+            claim_ids.remove(claim.claim_id.as_str());
+            if claim_ids.is_empty() {
+                index.remove(claim_type);
+            }
+        }
+        if index.is_empty() {
+            self.claim_type_index.remove(claim.tenant_id.as_str());
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Filtered change subscriptions for watched claims.
+//!
+//! A raw [`ChangeSubscription`] delivers every mutation, which leaves an
+//! agent to re-derive whether anything it relied on actually changed. A
+//! [`ClaimWatchSubscription`] does that on the subscriber side of the
+//! feed: it tracks the confidence and the support/contradiction counts of
+//! a fixed set of claims and only yields a [`ClaimWatchEvent`] when a
+//! claim's [`StanceBalance`] changes or its confidence crosses one of the
+//! filter's thresholds. Counts follow retrieval: supporting and
+//! contradicting evidence plus the claim's own supports/contradicts
+//! edges.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use schema::{ClaimId, Evidence, Relation, Stance};
+
+use crate::{ChangeEvent, ChangeRecord, ChangeSubscription, InMemoryStore};
+
+/// Which side a claim's evidence and edges come down on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StanceBalance {
+    Supported,
+    /// As much support as contradiction, including neither.
+    Even,
+    Contradicted,
+}
+
+impl StanceBalance {
+    pub fn from_counts(supports: usize, contradicts: usize) -> Self {
+        match supports.cmp(&contradicts) {
+            std::cmp::Ordering::Greater => Self::Supported,
+            std::cmp::Ordering::Equal => Self::Even,
+            std::cmp::Ordering::Less => Self::Contradicted,
+        }
+    }
+}
+
+/// The claims to watch and what to be told about them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClaimWatchFilter {
+    pub claim_ids: Vec<ClaimId>,
+    /// Notify when a claim's [`StanceBalance`] changes.
+    pub balance_changes: bool,
+    /// Notify when a claim's confidence moves from one side of a
+    /// threshold to the other. A confidence equal to the threshold counts
+    /// as above it.
+    pub confidence_thresholds: Vec<f32>,
+}
+
+impl ClaimWatchFilter {
+    /// Watch `claim_ids` for balance changes.
+    pub fn new<I, S>(claim_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<ClaimId>,
+    {
+        Self {
+            claim_ids: claim_ids.into_iter().map(Into::into).collect(),
+            balance_changes: true,
+            confidence_thresholds: Vec::new(),
+        }
+    }
+
+    pub fn with_confidence_threshold(mut self, threshold: f32) -> Self {
+        self.confidence_thresholds.push(threshold);
+        self
+    }
+
+    pub fn without_balance_changes(mut self) -> Self {
+        self.balance_changes = false;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimWatchNotification {
+    BalanceChanged {
+        claim_id: ClaimId,
+        from: StanceBalance,
+        to: StanceBalance,
+        supports: usize,
+        contradicts: usize,
+    },
+    ConfidenceCrossed {
+        claim_id: ClaimId,
+        threshold: f32,
+        previous: f32,
+        current: f32,
+    },
+}
+
+impl ClaimWatchNotification {
+    pub fn claim_id(&self) -> &str {
+        match self {
+            Self::BalanceChanged { claim_id, .. } | Self::ConfidenceCrossed { claim_id, .. } => {
+                claim_id
+            }
+        }
+    }
+}
+
+/// A notification with the sequence of the change that caused it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimWatchEvent {
+    pub sequence: u64,
+    pub notification: ClaimWatchNotification,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WatchedClaim {
+    /// `None` until the claim exists.
+    confidence: Option<f32>,
+    supports: usize,
+    contradicts: usize,
+}
+
+impl WatchedClaim {
+    fn balance(&self) -> StanceBalance {
+        StanceBalance::from_counts(self.supports, self.contradicts)
+    }
+}
+
+/// Receiving half of a claim watch. Dropping it detaches the subscriber.
+pub struct ClaimWatchSubscription {
+    changes: ChangeSubscription,
+    filter: ClaimWatchFilter,
+    watched: HashMap<String, WatchedClaim>,
+    pending: VecDeque<ClaimWatchEvent>,
+}
+
+impl ClaimWatchSubscription {
+    /// Wait up to `timeout` for the next notification.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<ClaimWatchEvent> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let change = self.changes.recv_timeout(remaining)?;
+            self.observe(change);
+        }
+    }
+
+    /// Return the next notification if the changes already queued
+    /// produce one.
+    pub fn try_recv(&mut self) -> Option<ClaimWatchEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let change = self.changes.try_recv()?;
+            self.observe(change);
+        }
+    }
+
+    /// Every notification produced by the changes queued so far.
+    pub fn drain(&mut self) -> Vec<ClaimWatchEvent> {
+        for change in self.changes.drain() {
+            self.observe(change);
+        }
+        self.pending.drain(..).collect()
+    }
+
+    fn observe(&mut self, change: ChangeEvent) {
+        let (claim_id, supports, contradicts) = match &change.record {
+            ChangeRecord::Claim(claim) => {
+                let Some(watched) = self.watched.get_mut(claim.claim_id.as_str()) else {
+                    return;
+                };
+                let previous = watched.confidence.replace(claim.confidence);
+                if let Some(previous) = previous {
+                    for &threshold in &self.filter.confidence_thresholds {
+                        if (previous >= threshold) != (claim.confidence >= threshold) {
+                            self.pending.push_back(ClaimWatchEvent {
+                                sequence: change.sequence,
+                                notification: ClaimWatchNotification::ConfidenceCrossed {
+                                    claim_id: claim.claim_id.clone(),
+                                    threshold,
+                                    previous,
+                                    current: claim.confidence,
+                                },
+                            });
+                        }
+                    }
+                }
+                return;
+            }
+            ChangeRecord::Evidence(evidence) => (
+                evidence.claim_id.as_str(),
+                evidence.effective_stance() == Stance::Supports,
+                evidence.effective_stance() == Stance::Contradicts,
+            ),
+            ChangeRecord::Edge(edge) => (
+                edge.from_claim_id.as_str(),
+                matches!(edge.relation, Relation::Supports),
+                matches!(edge.relation, Relation::Contradicts),
+            ),
+            ChangeRecord::ClaimVector { .. }
+            | ChangeRecord::BatchCommit(_)
+            | ChangeRecord::ClaimDelete { .. }
+            | ChangeRecord::ClaimMerge { .. }
+            | ChangeRecord::ClaimArchive { .. }
+            | ChangeRecord::Source(_)
+            | ChangeRecord::SparseVector { .. }
+            | ChangeRecord::NamedClaimVector { .. }
+            | ChangeRecord::Document(_)
+            | ChangeRecord::Chunk(_) => return,
+        };
+        let Some(watched) = self.watched.get_mut(claim_id) else {
+            return;
+        };
+        let before = watched.balance();
+        watched.supports += usize::from(supports);
+        watched.contradicts += usize::from(contradicts);
+        let after = watched.balance();
+        if self.filter.balance_changes && before != after {
+            self.pending.push_back(ClaimWatchEvent {
+                sequence: change.sequence,
+                notification: ClaimWatchNotification::BalanceChanged {
+                    claim_id: claim_id.into(),
+                    from: before,
+                    to: after,
+                    supports: watched.supports,
+                    contradicts: watched.contradicts,
+                },
+            });
+        }
+    }
+}
+
+impl InMemoryStore {
+    /// Subscribe to notifications about the claims in `filter`. Their
+    /// current state is the baseline, so only later changes notify; a
+    /// watched claim that does not exist yet starts notifying once it is
+    /// ingested.
+    pub fn watch_claims(&self, filter: ClaimWatchFilter) -> ClaimWatchSubscription {
+        let changes = self.subscribe();
+        let watched = filter
+            .claim_ids
+            .iter()
+            .map(|claim_id| (claim_id.to_string(), self.watched_claim_state(claim_id)))
+            .collect();
+        ClaimWatchSubscription {
+            changes,
+            filter,
+            watched,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn watched_claim_state(&self, claim_id: &str) -> WatchedClaim {
+        let evidence = self.evidence_by_claim.get(claim_id).into_iter().flatten();
+        let edges = self.edges_by_claim.get(claim_id).into_iter().flatten();
+        let mut state = WatchedClaim {
+            confidence: self.claims.get(claim_id).map(|claim| claim.confidence),
+            supports: 0,
+            contradicts: 0,
+        };
+        for stance in evidence.map(Evidence::effective_stance) {
+            match stance {
+                Stance::Supports => state.supports += 1,
+                Stance::Contradicts => state.contradicts += 1,
+                Stance::Neutral => {}
+            }
+        }
+        for relation in edges.map(|edge| &edge.relation) {
+            match relation {
+                Relation::Supports => state.supports += 1,
+                Relation::Contradicts => state.contradicts += 1,
+                _ => {}
+            }
+        }
+        state
+    }
+}
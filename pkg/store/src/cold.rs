@@ -0,0 +1,238 @@
+//! Retrieval across resident claims and a cold tier.
+//!
+//! Cold-tier claims are listed in segment manifests but their payloads
+//! (text, evidence, vectors) are not held in the serving store. A query
+//! that only scores resident claims silently misses them. With
+//! [`RetrievalOptions::with_cold_tier`] the resident store is
+//! scored as usual, the cold candidates the caller's prefilter kept are
+//! loaded on demand from a [`ColdClaimSource`] up to a per-query budget,
+//! and both sets are scored with the same signals (including the
+//! resident tenant's BM25 statistics) before being merged.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use schema::{Claim, ClaimEdge, ClaimId, Evidence, RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::{ClaimCandidate, InMemoryStore, RetrievalOptions, StoreError, compare_ranked};
+
+/// Full payload of a claim held outside the serving store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColdClaim {
+    pub claim: Claim,
+    pub evidence: Vec<Evidence>,
+    pub edges: Vec<ClaimEdge>,
+    pub vector: Option<Vec<f32>>,
+}
+
+/// Somewhere cold claim payloads can be fetched from one at a time.
+pub trait ColdClaimSource {
+    /// Load `claim_id` for `tenant_id`, or `Ok(None)` if the source does
+    /// not hold it.
+    fn load_cold_claim(
+        &self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<Option<ColdClaim>, StoreError>;
+}
+
+/// An archived store (for example one restored from a backup or a
+/// tenant export) can serve as the cold tier for a smaller hot store.
+impl ColdClaimSource for InMemoryStore {
+    fn load_cold_claim(
+        &self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<Option<ColdClaim>, StoreError> {
+        let Some(claim) = self.claims.get(claim_id) else {
+            return Ok(None);
+        };
+        if claim.tenant_id != *tenant_id {
+            return Ok(None);
+        }
+        Ok(Some(ColdClaim {
+            claim: claim.clone(),
+            evidence: self
+                .evidence_by_claim
+                .get(claim_id.as_str())
+                .cloned()
+                .unwrap_or_default(),
+            edges: self.edges_for_claim(claim_id),
+            vector: self.claim_vectors.get(claim_id).map(Cow::into_owned),
+        }))
+    }
+}
+
+/// Cold candidates to score next to the resident claims, set with
+/// [`RetrievalOptions::with_cold_tier`].
+#[derive(Clone, Copy)]
+pub struct ColdTier<'a> {
+    pub candidate_ids: &'a HashSet<ClaimId>,
+    pub source: &'a (dyn ColdClaimSource + Sync),
+    /// Most payloads loaded from `source` for one query.
+    pub max_loads: usize,
+}
+
+impl fmt::Debug for ColdTier<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColdTier")
+            .field("candidate_ids", &self.candidate_ids)
+            .field("max_loads", &self.max_loads)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Equal when both name the same candidates, budget and source object.
+impl PartialEq for ColdTier<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.candidate_ids == other.candidate_ids
+            && std::ptr::addr_eq(self.source, other.source)
+            && self.max_loads == other.max_loads
+    }
+}
+
+/// What the cold tier contributed to a retrieval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColdTierCounts {
+    /// Cold candidates whose payload was loaded and scored.
+    pub loaded: usize,
+    /// Cold candidates skipped because the load budget ran out.
+    pub skipped_budget: usize,
+    /// Cold candidates the source did not hold or that belonged to a
+    /// different tenant.
+    pub missing: usize,
+    /// Cold candidates whose load failed; they are left out of the
+    /// results rather than failing the query.
+    pub load_errors: usize,
+}
+
+/// Merged results plus what the cold tier contributed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TieredRetrieval {
+    pub results: Vec<RetrievalResult>,
+    /// Cold candidates whose payload was loaded and scored.
+    pub cold_loaded: usize,
+    /// Cold candidates skipped because the load budget ran out.
+    pub cold_skipped_budget: usize,
+    /// Cold candidates the source did not hold or that belonged to a
+    /// different tenant.
+    pub cold_missing: usize,
+    /// Cold candidates whose load failed; they are left out of the
+    /// results rather than failing the query.
+    pub cold_load_errors: usize,
+}
+
+impl InMemoryStore {
+    /// Retrieve from resident claims and from `cold_candidate_ids`,
+    /// loading at most `max_cold_loads` payloads from `source`.
+    #[deprecated(note = "use `retrieve_with_outcome` with `RetrievalOptions::with_cold_tier`")]
+    pub fn retrieve_with_cold_tier(
+        &self,
+        req: &RetrievalRequest,
+        query_vector: Option<&[f32]>,
+        cold_candidate_ids: &HashSet<ClaimId>,
+        source: &dyn ColdClaimSource,
+        max_cold_loads: usize,
+    ) -> TieredRetrieval {
+        let mut results = self.retrieve_with(
+            req,
+            &RetrievalOptions::new().with_query_vector(query_vector),
+        );
+        let counts = self.merge_cold_tier(
+            req,
+            query_vector,
+            (cold_candidate_ids, source, max_cold_loads),
+            &mut results,
+        );
+        TieredRetrieval {
+            results,
+            cold_loaded: counts.loaded,
+            cold_skipped_budget: counts.skipped_budget,
+            cold_missing: counts.missing,
+            cold_load_errors: counts.load_errors,
+        }
+    }
+
+    /// Score the cold candidates that are not resident, loading at most
+    /// `max_loads` from `source`, and merge them into the resident
+    /// `results`, keeping the best `top_k`. Cold candidates are loaded in
+    /// claim id order so the budget cuts the same ids on every run.
+    pub(crate) fn merge_cold_tier(
+        &self,
+        req: &RetrievalRequest,
+        query_vector: Option<&[f32]>,
+        (candidate_ids, source, max_loads): (&HashSet<ClaimId>, &dyn ColdClaimSource, usize),
+        results: &mut Vec<RetrievalResult>,
+    ) -> ColdTierCounts {
+        let mut counts = ColdTierCounts::default();
+        let mut cold_ids: Vec<&ClaimId> = candidate_ids
+            .iter()
+            .filter(|claim_id| !self.claims.contains_key(claim_id.as_str()))
+            .collect();
+        cold_ids.sort_unstable();
+        if cold_ids.len() > max_loads {
+            counts.skipped_budget = cold_ids.len() - max_loads;
+            cold_ids.truncate(max_loads);
+        }
+
+        let bm25_context = self.bm25_context_for_tenant(&req.tenant_id, &req.query);
+        let metric = self.tenant_distance_metric(&req.tenant_id);
+        let mut cold_confidence: HashMap<ClaimId, f32> = HashMap::new();
+        for claim_id in cold_ids {
+            let cold = match source.load_cold_claim(&req.tenant_id, claim_id) {
+                Ok(Some(cold)) if cold.claim.tenant_id == req.tenant_id => cold,
+                Ok(_) => {
+                    counts.missing += 1;
+                    continue;
+                }
+                Err(_) => {
+                    counts.load_errors += 1;
+                    continue;
+                }
+            };
+            counts.loaded += 1;
+
+            let dense_similarity = match (query_vector, cold.vector.as_deref()) {
+                (Some(query), Some(vector)) => metric
+                    .similarity(query, vector)
+                    .map(|score| metric.normalize_similarity(score))
+                    .unwrap_or(0.0),
+                _ => 0.0,
+            };
+            let tokens = self.analyze_claim_text(&cold.claim);
+            let scored = self.score_claim_candidate(
+                req,
+                query_vector.is_some(),
+                &bm25_context,
+                ClaimCandidate {
+                    claim: &cold.claim,
+                    evidence: &cold.evidence,
+                    edges: &cold.edges,
+                    tokens: Some(&tokens),
+                    dense_similarity,
+                    sparse_similarity: 0.0,
+                },
+            );
+            if let Some(result) = scored {
+                cold_confidence.insert(result.claim_id.clone(), cold.claim.confidence);
+                results.push(result);
+            }
+        }
+
+        let confidence = |claim_id: &str| {
+            cold_confidence
+                .get(claim_id)
+                .copied()
+                .unwrap_or_else(|| self.claim_confidence(claim_id))
+        };
+        results.sort_by(|a, b| {
+            compare_ranked(
+                (a.score, confidence(&a.claim_id), &a.claim_id),
+                (b.score, confidence(&b.claim_id), &b.claim_id),
+            )
+        });
+        results.truncate(req.top_k);
+        counts
+    }
+}
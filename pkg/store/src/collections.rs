@@ -0,0 +1,86 @@
+//! Collections: separate corpora within one tenant.
+//!
+//! A claim may name the [`Claim::collection`] it belongs to, such as
+//! `support-tickets` or `contracts`. Claims are indexed per tenant by
+//! collection, so retrieval can target one or more collections without a
+//! pass over the tenant; as with claim type filters, the matching claims
+//! become its allowed set. Claims without a collection belong to the
+//! tenant's default corpus and match no collection filter.
+
+use std::collections::HashSet;
+
+use schema::{Claim, ClaimId, TenantId};
+
+use crate::InMemoryStore;
+
+impl InMemoryStore {
+    pub fn claim_ids_in_collection(
+        &self,
+        tenant_id: &TenantId,
+        collection: &str,
+    ) -> HashSet<ClaimId> {
+        self.collection_index
+            .get(tenant_id.as_str())
+            .and_then(|index| index.get(collection))
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    /// Claims of `tenant_id` in any of `collections`.
+    pub fn claim_ids_in_collections(
+        &self,
+        tenant_id: &TenantId,
+        collections: &[String],
+    ) -> HashSet<ClaimId> {
+        collections
+            .iter()
+            .flat_map(|collection| self.claim_ids_in_collection(tenant_id, collection))
+            .collect()
+    }
+
+    /// The collections of `tenant_id` with how many claims each holds,
+    /// by name.
+    pub fn collections(&self, tenant_id: &TenantId) -> Vec<(String, usize)> {
+        let mut collections: Vec<(String, usize)> = self
+            .collection_index
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .map(|(collection, claim_ids)| (collection.clone(), claim_ids.len()))
+            .collect();
+        collections.sort();
+        collections
+    }
+
+    pub(crate) fn index_claim_collection(&mut self, claim: &Claim) {
+        let Some(collection) = &claim.collection else {
+            return;
+        };
+        self.collection_index
+            .entry(claim.tenant_id.to_string())
+            .or_default()
+            .entry(collection.clone())
+            .or_default()
+            .insert(claim.claim_id.to_string());
+    }
+
+    pub(crate) fn unindex_claim_collection(&mut self, claim: &Claim) {
+        let Some(collection) = &claim.collection else {
+            return;
+        };
+        let Some(index) = self.collection_index.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        if let Some(claim_ids) = index.get_mut(collection) {
+            claim_ids.remove(claim.claim_id.as_str());
+            if claim_ids.is_empty() {
+                index.remove(collection);
+            }
+        }
+        if index.is_empty() {
+            self.collection_index.remove(claim.tenant_id.as_str());
+        }
+    }
+}
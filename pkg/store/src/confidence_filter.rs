@@ -0,0 +1,113 @@
+//! Filtering claims on their confidence.
+//!
+//! Each tenant's claims are kept in a confidence-sorted index, so a
+//! [`ConfidenceRange`] resolves to its claims with one range scan instead
+//! of a pass over the tenant. Retrieval under
+//! [`RetrievalOptions::confidence`](crate::RetrievalOptions::confidence)
+//! takes the resolved claims as its allowed set, which drops out-of-range
+//! claims during candidate generation, before anything is scored.
+
+use std::collections::HashSet;
+use std::ops::Bound;
+
+use schema::{Claim, ClaimId, TenantId};
+
+use crate::InMemoryStore;
+
+/// Inclusive bounds on claim confidence; a missing bound is open.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConfidenceRange {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl ConfidenceRange {
+    pub fn new(min: Option<f32>, max: Option<f32>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn at_least(min: f32) -> Self {
+        Self::new(Some(min), None)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    pub fn contains(&self, confidence: f32) -> bool {
+        self.min.is_none_or(|min| confidence >= min) && self.max.is_none_or(|max| confidence <= max)
+    }
+}
+
+impl InMemoryStore {
+    /// Claims of `tenant_id` whose confidence lies in `range`. An
+    /// unbounded range matches every claim of the tenant, and an inverted
+    /// one matches none.
+    pub fn claim_ids_in_confidence_range(
+        &self,
+        tenant_id: &TenantId,
+        range: ConfidenceRange,
+    ) -> HashSet<ClaimId> {
+        let Some(index) = self.confidence_index.get(tenant_id.as_str()) else {
+            return HashSet::new();
+        };
+        if range.min.is_some_and(|min| min > 1.0) || range.max.is_some_and(|max| max < 0.0) {
+            return HashSet::new();
+        }
+        let lower = match range.min {
+            Some(min) => Bound::Included(confidence_key(min)),
+            None => Bound::Unbounded,
+        };
+        let upper = match range.max {
+            Some(max) => Bound::Included(confidence_key(max)),
+            None => Bound::Unbounded,
+        };
+        if let (Bound::Included(lower), Bound::Included(upper)) = (lower, upper)
+            && lower > upper
+        {
+            return HashSet::new();
+        }
+        index
+            .range((lower, upper))
+            .flat_map(|(_, claim_ids)| claim_ids.iter().map(ClaimId::from))
+            .collect()
+    }
+
+    pub(crate) fn index_claim_confidence(&mut self, claim: &Claim) {
+        self.confidence_index
+            .entry(claim.tenant_id.to_string())
+            .or_default()
+            .entry(confidence_key(claim.confidence))
+            .or_default()
+            .insert(claim.claim_id.to_string());
+    }
+
+    pub(crate) fn unindex_claim_confidence(&mut self, claim: &Claim) {
+        let Some(index) = self.confidence_index.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        let key = confidence_key(claim.confidence);
+        if let Some(claim_ids) = index.get_mut(&key) {
+            claim_ids.remove(claim.claim_id.as_str());
+            if claim_ids.is_empty() {
+                index.remove(&key);
+            }
+        }
+        if index.is_empty() {
+            self.confidence_index.remove(claim.tenant_id.as_str());
+        }
+    }
+}
+
+/// Order-preserving key for a confidence. Stored confidences are
+/// validated to `0.0..=1.0`, where the IEEE bit patterns sort like the
+/// values once `-0.0` is folded into `0.0`; query bounds outside that
+/// interval are clamped into it.
+fn confidence_key(confidence: f32) -> u32 {
+    let clamped = if confidence.is_nan() {
+        0.0
+    } else {
+        confidence.clamp(0.0, 1.0)
+    };
+    if clamped == 0.0 { 0 } else { clamped.to_bits() }
+}
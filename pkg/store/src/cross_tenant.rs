@@ -0,0 +1,73 @@
+//! Privileged search across every tenant.
+//!
+//! Every other retrieval is confined to the request's tenant. Operator
+//! tooling and abuse investigations sometimes need to find a claim
+//! without knowing whose it is, so [`InMemoryStore::retrieve_all_tenants`]
+//! runs a request against each tenant in turn and tags every result with
+//! the tenant it came from. The path is off unless the store's
+//! cross-tenant capability is switched on with
+//! [`InMemoryStore::set_cross_tenant_search_enabled`]; a store serving
+//! tenant traffic should leave it off.
+
+use schema::{RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::{InMemoryStore, RetrievalOptions, StoreError};
+
+/// A result of [`InMemoryStore::retrieve_all_tenants`] and the tenant
+/// owning its claim.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenantRetrievalResult {
+    pub tenant_id: TenantId,
+    pub result: RetrievalResult,
+}
+
+impl InMemoryStore {
+    pub fn cross_tenant_search_enabled(&self) -> bool {
+        self.cross_tenant_search_enabled
+    }
+
+    /// Allow or forbid [`Self::retrieve_all_tenants`].
+    pub fn set_cross_tenant_search_enabled(&mut self, enabled: bool) {
+        self.cross_tenant_search_enabled = enabled;
+    }
+
+    /// The best `req.top_k` results over every tenant, ignoring
+    /// `req.tenant_id`. Each tenant ranks against its own corpus and the
+    /// raw scores are merged, so scores are comparable only roughly; ties
+    /// go to the lower tenant and then claim id. Fails with
+    /// [`StoreError::Forbidden`] unless cross-tenant search is enabled.
+    pub fn retrieve_all_tenants(
+        &self,
+        req: &RetrievalRequest,
+    ) -> Result<Vec<TenantRetrievalResult>, StoreError> {
+        if !self.cross_tenant_search_enabled {
+            return Err(StoreError::Forbidden(
+                "cross-tenant search is not enabled on this store".to_string(),
+            ));
+        }
+        let options = RetrievalOptions::new();
+        let mut merged: Vec<TenantRetrievalResult> = self
+            .tenant_ids()
+            .into_iter()
+            .flat_map(|tenant_id| {
+                let mut tenant_req = req.clone();
+                tenant_req.tenant_id = tenant_id.clone();
+                self.retrieve_with(&tenant_req, &options)
+                    .into_iter()
+                    .map(move |result| TenantRetrievalResult {
+                        tenant_id: tenant_id.clone(),
+                        result,
+                    })
+            })
+            .collect();
+        merged.sort_by(|a, b| {
+            b.result
+                .score
+                .total_cmp(&a.result.score)
+                .then_with(|| a.tenant_id.cmp(&b.tenant_id))
+                .then_with(|| a.result.claim_id.cmp(&b.result.claim_id))
+        });
+        merged.truncate(req.top_k);
+        Ok(merged)
+    }
+}
@@ -15,8 +15,8 @@
 //!
 //! All methods return `Result<_, String>` (not `Result<_, StoreError>`)
 //! so that the disk module has zero coupling to the in-memory store's
-//! error type. The in-memory store maps the disk's `String` errors
-//! into its own `StoreError::Io` variant at the call site.
+//! error type. The in-memory store wraps the disk's `String` errors in
+//! an `io::Error` for its `StoreError::Io` variant at the call site.
 
 use std::path::Path;
 
@@ -28,11 +28,13 @@ use crate::{BatchCommitMetadata, InMemoryStore, StoreIndexStats};
 const TABLE_CLAIMS: TableDefinition<&str, &[u8]> = TableDefinition::new("dash_claims");
 const TABLE_EVIDENCE: TableDefinition<&str, &[u8]> = TableDefinition::new("dash_evidence");
 const TABLE_EDGES: TableDefinition<&str, &[u8]> = TableDefinition::new("dash_edges");
-const TABLE_CLAIM_VECTORS: TableDefinition<&str, &[u8]> = TableDefinition::new("dash_claim_vectors");
+const TABLE_CLAIM_VECTORS: TableDefinition<&str, &[u8]> =
+    TableDefinition::new("dash_claim_vectors");
 const TABLE_TENANT_DIMS: TableDefinition<&str, u64> = TableDefinition::new("dash_tenant_dims");
 const TABLE_TENANT_CLAIMS_SET: TableDefinition<(&str, &str), ()> =
     TableDefinition::new("dash_tenant_claims_set");
-const TABLE_BATCH_COMMITS: TableDefinition<&str, &[u8]> = TableDefinition::new("dash_batch_commits");
+const TABLE_BATCH_COMMITS: TableDefinition<&str, &[u8]> =
+    TableDefinition::new("dash_batch_commits");
 const TABLE_STATS: TableDefinition<&str, &[u8]> = TableDefinition::new("dash_stats");
 const TABLE_HWM: TableDefinition<&str, u64> = TableDefinition::new("dash_hwm");
 
@@ -69,10 +71,7 @@ fn map_bincode_err(ctx: &str, e: bincode::Error) -> String {
     format!("bincode {ctx}: {e}")
 }
 
-fn read_bytes<V: serde::de::DeserializeOwned>(
-    bytes: Vec<u8>,
-    ctx: &str,
-) -> Result<V, String> {
+fn read_bytes<V: serde::de::DeserializeOwned>(bytes: Vec<u8>, ctx: &str) -> Result<V, String> {
     bincode::deserialize(&bytes).map_err(|e| map_bincode_err(ctx, e))
 }
 
@@ -124,7 +123,9 @@ impl DiskBackedStore {
     pub fn set_high_water_mark(&self, hwm: u64) -> Result<(), String> {
         let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
         {
-            let mut table = txn.open_table(TABLE_HWM).map_err(|e| err("open hwm table", e))?;
+            let mut table = txn
+                .open_table(TABLE_HWM)
+                .map_err(|e| err("open hwm table", e))?;
             table
                 .insert(HWM_KEY, hwm)
                 .map_err(|e| err("write hwm", e))?;
@@ -139,7 +140,9 @@ impl DiskBackedStore {
         let bytes = bincode::serialize(claim).map_err(|e| map_bincode_err("serialize claim", e))?;
         let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
         {
-            let mut table = txn.open_table(TABLE_CLAIMS).map_err(|e| err("open claims", e))?;
+            let mut table = txn
+                .open_table(TABLE_CLAIMS)
+                .map_err(|e| err("open claims", e))?;
             table
                 .insert(claim.claim_id.as_str(), bytes.as_slice())
                 .map_err(|e| err("write claim", e))?;
@@ -170,11 +173,13 @@ impl DiskBackedStore {
     /// Persist the full evidence blob for a claim. Replaces any prior
     /// evidence list for the same `claim_id` atomically.
     pub fn put_evidence_blob(&self, claim_id: &str, evidence: &[Evidence]) -> Result<(), String> {
-        let bytes = bincode::serialize(evidence)
-            .map_err(|e| map_bincode_err("serialize evidence", e))?;
+        let bytes =
+            bincode::serialize(evidence).map_err(|e| map_bincode_err("serialize evidence", e))?;
         let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
         {
-            let mut table = txn.open_table(TABLE_EVIDENCE).map_err(|e| err("open evidence", e))?;
+            let mut table = txn
+                .open_table(TABLE_EVIDENCE)
+                .map_err(|e| err("open evidence", e))?;
             table
                 .insert(claim_id, bytes.as_slice())
                 .map_err(|e| err("write evidence", e))?;
@@ -206,11 +211,12 @@ impl DiskBackedStore {
     /// Persist the full edge blob for a source claim. Replaces any
     /// prior edge list for the same `from` atomically.
     pub fn put_edge_blob(&self, from: &str, edges: &[ClaimEdge]) -> Result<(), String> {
-        let bytes = bincode::serialize(edges)
-            .map_err(|e| map_bincode_err("serialize edges", e))?;
+        let bytes = bincode::serialize(edges).map_err(|e| map_bincode_err("serialize edges", e))?;
         let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
         {
-            let mut table = txn.open_table(TABLE_EDGES).map_err(|e| err("open edges", e))?;
+            let mut table = txn
+                .open_table(TABLE_EDGES)
+                .map_err(|e| err("open edges", e))?;
             table
                 .insert(from, bytes.as_slice())
                 .map_err(|e| err("write edges", e))?;
@@ -242,8 +248,8 @@ impl DiskBackedStore {
     /// Persist an embedding vector for a claim. Replaces any prior
     /// vector for the same `claim_id` atomically.
     pub fn put_vector(&self, claim_id: &str, vector: &[f32]) -> Result<(), String> {
-        let bytes = bincode::serialize(vector)
-            .map_err(|e| map_bincode_err("serialize vector", e))?;
+        let bytes =
+            bincode::serialize(vector).map_err(|e| map_bincode_err("serialize vector", e))?;
         let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
         {
             let mut table = txn
@@ -280,8 +286,8 @@ impl DiskBackedStore {
     /// Persist batch-commit metadata. Replaces any prior entry with
     /// the same `commit_id` atomically.
     pub fn put_batch_commit(&self, commit: &BatchCommitMetadata) -> Result<(), String> {
-        let bytes = bincode::serialize(commit)
-            .map_err(|e| map_bincode_err("serialize batch_commit", e))?;
+        let bytes =
+            bincode::serialize(commit).map_err(|e| map_bincode_err("serialize batch_commit", e))?;
         let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
         {
             let mut table = txn
@@ -357,7 +363,39 @@ impl DiskBackedStore {
                 .insert(key, ())
                 .map_err(|e| err("write tenant_claims_set", e))?;
         }
-        txn.commit().map_err(|e| err("commit tenant_claims_set", e))?;
+        txn.commit()
+            .map_err(|e| err("commit tenant_claims_set", e))?;
+        Ok(())
+    }
+
+    /// Remove `claim` and everything keyed by its id — evidence, outgoing
+    /// edges, vector, and its entry in `tenant`'s claim set — in one
+    /// transaction. Missing rows are not an error.
+    pub fn delete_claim(&self, tenant: &str, claim: &str) -> Result<(), String> {
+        let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
+        {
+            for (table, ctx) in [
+                (TABLE_CLAIMS, "claims"),
+                (TABLE_EVIDENCE, "evidence"),
+                (TABLE_EDGES, "edges"),
+                (TABLE_CLAIM_VECTORS, "claim_vectors"),
+            ] {
+                let mut table = txn
+                    .open_table(table)
+                    .map_err(|e| err(&format!("open {ctx}"), e))?;
+                table
+                    .remove(claim)
+                    .map_err(|e| err(&format!("delete {ctx}"), e))?;
+            }
+            let mut table = txn
+                .open_table(TABLE_TENANT_CLAIMS_SET)
+                .map_err(|e| err("open tenant_claims_set", e))?;
+            let key: (&str, &str) = (tenant, claim);
+            table
+                .remove(key)
+                .map_err(|e| err("delete tenant_claims_set", e))?;
+        }
+        txn.commit().map_err(|e| err("commit claim delete", e))?;
         Ok(())
     }
 
@@ -394,9 +432,7 @@ impl DiskBackedStore {
         // "prefix matches first element" range, so this is the
         // most portable approach for the small N of typical
         // tenant/claim sets.
-        let iter = table
-            .iter()
-            .map_err(|e| err("iter tenant_claims_set", e))?;
+        let iter = table.iter().map_err(|e| err("iter tenant_claims_set", e))?;
         for entry in iter {
             let entry = entry.map_err(|e| err("scan tenant_claims_set", e))?;
             let key = entry.0.value();
@@ -410,11 +446,12 @@ impl DiskBackedStore {
 
     /// Persist the index stats singleton.
     pub fn set_stats(&self, stats: &StoreIndexStats) -> Result<(), String> {
-        let bytes = bincode::serialize(stats)
-            .map_err(|e| map_bincode_err("serialize stats", e))?;
+        let bytes = bincode::serialize(stats).map_err(|e| map_bincode_err("serialize stats", e))?;
         let txn = self.db.begin_write().map_err(|e| err("begin_write", e))?;
         {
-            let mut table = txn.open_table(TABLE_STATS).map_err(|e| err("open stats", e))?;
+            let mut table = txn
+                .open_table(TABLE_STATS)
+                .map_err(|e| err("open stats", e))?;
             table
                 .insert(STATS_KEY, bytes.as_slice())
                 .map_err(|e| err("write stats", e))?;
@@ -468,10 +505,10 @@ impl DiskBackedStore {
             for entry in iter {
                 let entry = entry.map_err(|e| err("scan claims", e))?;
                 let value = entry.1.value().to_vec();
-                let claim: Claim =
-                    bincode::deserialize(&value).map_err(|e| map_bincode_err("deserialize claim", e))?;
+                let claim: Claim = bincode::deserialize(&value)
+                    .map_err(|e| map_bincode_err("deserialize claim", e))?;
                 dest.apply_claim_for_load(claim)
-                    .map_err(|e| format!("apply_claim_for_load: {e:?}"))?;
+                    .map_err(|e| format!("apply_claim_for_load: {e}"))?;
                 claims_loaded += 1;
             }
         }
@@ -490,7 +527,7 @@ impl DiskBackedStore {
                     let evidence: Vec<Evidence> = bincode::deserialize(&value)
                         .map_err(|e| map_bincode_err("deserialize evidence", e))?;
                     dest.apply_evidence_blob_for_load(&key, &evidence)
-                        .map_err(|e| format!("apply_evidence_blob_for_load: {e:?}"))?;
+                        .map_err(|e| format!("apply_evidence_blob_for_load: {e}"))?;
                 }
             }
         }
@@ -507,7 +544,7 @@ impl DiskBackedStore {
                     let edges: Vec<ClaimEdge> = bincode::deserialize(&value)
                         .map_err(|e| map_bincode_err("deserialize edges", e))?;
                     dest.apply_edge_blob_for_load(&key, &edges)
-                        .map_err(|e| format!("apply_edge_blob_for_load: {e:?}"))?;
+                        .map_err(|e| format!("apply_edge_blob_for_load: {e}"))?;
                 }
             }
         }
@@ -526,7 +563,7 @@ impl DiskBackedStore {
                         let vector: Vec<f32> = bincode::deserialize(&value)
                             .map_err(|e| map_bincode_err("deserialize vector", e))?;
                         dest.apply_claim_vector_blob_for_load(&key, vector)
-                            .map_err(|e| format!("apply_claim_vector_blob_for_load: {e:?}"))?;
+                            .map_err(|e| format!("apply_claim_vector_blob_for_load: {e}"))?;
                     }
                 }
                 Err(TableError::TableDoesNotExist(_)) => {
@@ -547,7 +584,7 @@ impl DiskBackedStore {
                     let commit: BatchCommitMetadata = bincode::deserialize(&value)
                         .map_err(|e| map_bincode_err("deserialize batch_commit", e))?;
                     dest.apply_batch_commit_for_load(&commit)
-                        .map_err(|e| format!("apply_batch_commit_for_load: {e:?}"))?;
+                        .map_err(|e| format!("apply_batch_commit_for_load: {e}"))?;
                 }
             }
         }
@@ -597,8 +634,8 @@ impl DiskBackedStore {
                 .open_table(TABLE_CLAIMS)
                 .map_err(|e| err("open claims", e))?;
             for claim in store.claims_iter() {
-                let bytes = bincode::serialize(claim)
-                    .map_err(|e| map_bincode_err("serialize claim", e))?;
+                let bytes =
+                    bincode::serialize(claim).map_err(|e| map_bincode_err("serialize claim", e))?;
                 claims_table
                     .insert(claim.claim_id.as_str(), bytes.as_slice())
                     .map_err(|e| err("write claim", e))?;
@@ -615,10 +652,12 @@ impl DiskBackedStore {
                     .map_err(|e| err("write evidence", e))?;
             }
 
-            let mut edges_table = txn.open_table(TABLE_EDGES).map_err(|e| err("open edges", e))?;
+            let mut edges_table = txn
+                .open_table(TABLE_EDGES)
+                .map_err(|e| err("open edges", e))?;
             for (from, edges) in store.edges_iter() {
-                let bytes = bincode::serialize(edges)
-                    .map_err(|e| map_bincode_err("serialize edges", e))?;
+                let bytes =
+                    bincode::serialize(edges).map_err(|e| map_bincode_err("serialize edges", e))?;
                 edges_table
                     .insert(from, bytes.as_slice())
                     .map_err(|e| err("write edges", e))?;
@@ -628,7 +667,7 @@ impl DiskBackedStore {
                 .open_table(TABLE_CLAIM_VECTORS)
                 .map_err(|e| err("open claim_vectors", e))?;
             for (claim_id, vector) in store.claim_vectors_iter() {
-                let bytes = bincode::serialize(vector)
+                let bytes = bincode::serialize(vector.as_ref())
                     .map_err(|e| map_bincode_err("serialize vector", e))?;
                 vectors_table
                     .insert(claim_id, bytes.as_slice())
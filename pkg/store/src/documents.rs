@@ -0,0 +1,204 @@
+//! Source documents and their chunks.
+//!
+//! Evidence says where it came from with a `doc_id`, a `chunk_id`, and a
+//! byte span, but only the ids were stored, so a citation could not be
+//! shown with the text it quotes. Documents and chunks registered with
+//! [`InMemoryStore::put_document`] and [`InMemoryStore::put_chunk`] are
+//! kept per tenant, and [`InMemoryStore::cited_text`] resolves a citation
+//! to the passage it points at. They are independent of claims: deleting
+//! or merging claims leaves the documents they cite in place.
+//!
+//! The persistent variants write a record to the WAL, and checkpoints
+//! write one for every document and chunk.
+
+use std::collections::{BTreeMap, HashMap};
+
+use schema::{Chunk, Citation, Document, Evidence, TenantId, validate_chunk, validate_document};
+
+use crate::wal::PersistedRecord;
+use crate::{ChangeRecord, FileWal, InMemoryStore, StoreError};
+
+/// A registered document and its chunks by id.
+#[derive(Debug, Clone)]
+pub(crate) struct StoredDocument {
+    document: Document,
+    chunks: BTreeMap<String, Chunk>,
+}
+
+/// A tenant's documents by id.
+pub(crate) type TenantDocuments = HashMap<String, StoredDocument>;
+
+impl InMemoryStore {
+    /// Register `document` with its tenant, replacing, and returning, any
+    /// document registered under the same id. The chunks of a replaced
+    /// document are kept.
+    pub fn put_document(&mut self, document: Document) -> Result<Option<Document>, StoreError> {
+        validate_document(&document)?;
+        Ok(self.apply_document(document))
+    }
+
+    /// [`Self::put_document`], recording the document in `wal` first.
+    pub fn put_document_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        document: Document,
+    ) -> Result<Option<Document>, StoreError> {
+        validate_document(&document)?;
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_document(&document)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        Ok(self.apply_document(document))
+    }
+
+    /// Register `chunk` under its document, replacing, and returning, any
+    /// chunk registered under the same id there. Fails with
+    /// [`StoreError::MissingDocument`] unless the document is registered.
+    pub fn put_chunk(&mut self, chunk: Chunk) -> Result<Option<Chunk>, StoreError> {
+        validate_chunk(&chunk)?;
+        self.apply_chunk(chunk)
+    }
+
+    /// [`Self::put_chunk`], recording the chunk in `wal` first.
+    pub fn put_chunk_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        chunk: Chunk,
+    ) -> Result<Option<Chunk>, StoreError> {
+        validate_chunk(&chunk)?;
+        if self.document(&chunk.tenant_id, &chunk.doc_id).is_none() {
+            return Err(StoreError::MissingDocument(chunk.doc_id));
+        }
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_chunk(&chunk)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        self.apply_chunk(chunk)
+    }
+
+    pub fn document(&self, tenant_id: &TenantId, doc_id: &str) -> Option<&Document> {
+        self.stored_document(tenant_id, doc_id)
+            .map(|stored| &stored.document)
+    }
+
+    pub fn chunk(&self, tenant_id: &TenantId, doc_id: &str, chunk_id: &str) -> Option<&Chunk> {
+        self.stored_document(tenant_id, doc_id)?
+            .chunks
+            .get(chunk_id)
+    }
+
+    /// The chunks of a document, in chunk id order.
+    pub fn document_chunks(&self, tenant_id: &TenantId, doc_id: &str) -> Vec<&Chunk> {
+        self.stored_document(tenant_id, doc_id)
+            .map(|stored| stored.chunks.values().collect())
+            .unwrap_or_default()
+    }
+
+    /// The documents registered for `tenant_id`, sorted.
+    pub fn document_ids(&self, tenant_id: &TenantId) -> Vec<String> {
+        let mut doc_ids: Vec<String> = self
+            .documents
+            .get(tenant_id.as_str())
+            .map(|documents| documents.keys().cloned().collect())
+            .unwrap_or_default();
+        doc_ids.sort_unstable();
+        doc_ids
+    }
+
+    /// The text a citation of `tenant_id` points at: its chunk's text when
+    /// the chunk is registered, otherwise the bytes of its span, end
+    /// included, in the document's text, otherwise the whole document.
+    /// `None` when the document is not registered or the span does not
+    /// fall on character boundaries inside it.
+    pub fn cited_text(&self, tenant_id: &TenantId, citation: &Citation) -> Option<&str> {
+        self.resolve_cited_text(
+            tenant_id,
+            citation.doc_id.as_deref()?,
+            citation.chunk_id.as_deref(),
+            citation.span_start.zip(citation.span_end),
+        )
+    }
+
+    /// [`Self::cited_text`] for a piece of evidence.
+    pub fn evidence_text(&self, tenant_id: &TenantId, evidence: &Evidence) -> Option<&str> {
+        self.resolve_cited_text(
+            tenant_id,
+            evidence.doc_id.as_deref()?,
+            evidence.chunk_id.as_deref(),
+            evidence.span_start.zip(evidence.span_end),
+        )
+    }
+
+    pub(crate) fn apply_document(&mut self, document: Document) -> Option<Document> {
+        self.change_feed
+            .publish_with(|| ChangeRecord::Document(document.clone()));
+        let documents = self
+            .documents
+            .entry(document.tenant_id.to_string())
+            .or_default();
+        match documents.get_mut(&document.doc_id) {
+            Some(stored) => Some(std::mem::replace(&mut stored.document, document)),
+            None => {
+                documents.insert(
+                    document.doc_id.clone(),
+                    StoredDocument {
+                        document,
+                        chunks: BTreeMap::new(),
+                    },
+                );
+                None
+            }
+        }
+    }
+
+    pub(crate) fn apply_chunk(&mut self, chunk: Chunk) -> Result<Option<Chunk>, StoreError> {
+        let Some(stored) = self
+            .documents
+            .get_mut(chunk.tenant_id.as_str())
+            .and_then(|documents| documents.get_mut(&chunk.doc_id))
+        else {
+            return Err(StoreError::MissingDocument(chunk.doc_id));
+        };
+        self.change_feed
+            .publish_with(|| ChangeRecord::Chunk(chunk.clone()));
+        Ok(stored.chunks.insert(chunk.chunk_id.clone(), chunk))
+    }
+
+    /// Every document followed by its chunks, by tenant and then id.
+    pub(crate) fn push_document_records(&self, records: &mut Vec<PersistedRecord>) {
+        let mut tenants: Vec<&String> = self.documents.keys().collect();
+        tenants.sort_unstable();
+        for tenant_id in tenants {
+            let documents = &self.documents[tenant_id];
+            let mut doc_ids: Vec<&String> = documents.keys().collect();
+            doc_ids.sort_unstable();
+            for doc_id in doc_ids {
+                let stored = &documents[doc_id];
+                records.push(PersistedRecord::Document(stored.document.clone()));
+                records.extend(stored.chunks.values().cloned().map(PersistedRecord::Chunk));
+            }
+        }
+    }
+
+    fn stored_document(&self, tenant_id: &str, doc_id: &str) -> Option<&StoredDocument> {
+        self.documents.get(tenant_id)?.get(doc_id)
+    }
+
+    fn resolve_cited_text(
+        &self,
+        tenant_id: &str,
+        doc_id: &str,
+        chunk_id: Option<&str>,
+        span: Option<(u32, u32)>,
+    ) -> Option<&str> {
+        let stored = self.stored_document(tenant_id, doc_id)?;
+        if let Some(chunk) = chunk_id.and_then(|chunk_id| stored.chunks.get(chunk_id)) {
+            return Some(&chunk.text);
+        }
+        let text = stored.document.text.as_str();
+        match span {
+            Some((start, end)) => text.get(start as usize..=end as usize),
+            None => Some(text),
+        }
+    }
+}
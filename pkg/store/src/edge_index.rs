@@ -0,0 +1,63 @@
+//! Edges by the claim they point at.
+//!
+//! Edges are stored with the claim they leave, so what supports or
+//! contradicts a given claim used to take a pass over every edge in the
+//! store. The store also keeps, for each claim an edge points at, the
+//! claims with an edge to it; [`InMemoryStore::edges_into_claim`]
+//! resolves through that and only reads those claims' edges.
+
+use std::collections::HashSet;
+
+use schema::{ClaimEdge, ClaimId};
+
+use crate::InMemoryStore;
+
+impl InMemoryStore {
+    /// Every edge pointing at `claim_id`, ordered by source claim and
+    /// then as stored. The source claims may belong to other tenants if
+    /// edges were written across tenants.
+    pub fn edges_into_claim(&self, claim_id: &ClaimId) -> Vec<ClaimEdge> {
+        let mut from_claim_ids: Vec<&String> = self
+            .incoming_edges
+            .get(claim_id.as_str())
+            .into_iter()
+            .flatten()
+            .collect();
+        from_claim_ids.sort_unstable();
+        from_claim_ids
+            .into_iter()
+            .filter_map(|from_claim_id| self.edges_by_claim.get(from_claim_id))
+            .flatten()
+            .filter(|edge| edge.to_claim_id == *claim_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Claims with at least one edge pointing at `claim_id`.
+    pub(crate) fn claims_with_edges_into(&self, claim_id: &str) -> HashSet<String> {
+        self.incoming_edges
+            .get(claim_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn index_incoming_edge(&mut self, edge: &ClaimEdge) {
+        self.incoming_edges
+            .entry(edge.to_claim_id.to_string())
+            .or_default()
+            .insert(edge.from_claim_id.to_string());
+    }
+
+    /// Drop the index entries of `from_claim_id`'s stored edges, before
+    /// they are removed or replaced.
+    pub(crate) fn unindex_incoming_edges(&mut self, from_claim_id: &str) {
+        for edge in self.edges_by_claim.get(from_claim_id).into_iter().flatten() {
+            if let Some(from_claim_ids) = self.incoming_edges.get_mut(edge.to_claim_id.as_str()) {
+                from_claim_ids.remove(from_claim_id);
+                if from_claim_ids.is_empty() {
+                    self.incoming_edges.remove(edge.to_claim_id.as_str());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Renaming an entity across a tenant.
+//!
+//! Entity canonicalization fixes, such as folding "ACME Corp." into
+//! "Acme", touch every claim naming the old entity. [`InMemoryStore::rename_entity`]
+//! rewrites those claims in place, keeping their evidence, edges, and
+//! vectors, instead of re-ingesting them. The persistent variant writes
+//! the rewritten claims to the WAL in batches of
+//! [`ENTITY_RENAME_WAL_BATCH_CLAIMS`], syncing after each, so a large
+//! rename neither holds one huge pending buffer nor loses finished
+//! batches to a crash.
+
+use schema::{Claim, ClaimId, TenantId, ValidationError, validate_claim_with};
+
+use crate::wal::PersistedRecord;
+use crate::{FileWal, InMemoryStore, StoreError, normalize_index_key};
+
+/// Claims written to the WAL between syncs by
+/// [`InMemoryStore::rename_entity_persistent`].
+pub(crate) const ENTITY_RENAME_WAL_BATCH_CLAIMS: usize = 256;
+
+impl InMemoryStore {
+    /// Replace `old_entity` with `new_entity` on every claim of
+    /// `tenant_id`, matching names the way the entity index does (trimmed
+    /// and case-insensitive). Returns the number of claims changed.
+    pub fn rename_entity(
+        &mut self,
+        tenant_id: &TenantId,
+        old_entity: &str,
+        new_entity: &str,
+    ) -> Result<usize, StoreError> {
+        let renamed = self.renamed_entity_claims(tenant_id, old_entity, new_entity)?;
+        let count = renamed.len();
+        for claim in renamed {
+            let vector_records = self.claim_vector_records(&claim);
+            self.replace_claim(claim, vector_records)?;
+        }
+        Ok(count)
+    }
+
+    pub fn rename_entity_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        old_entity: &str,
+        new_entity: &str,
+    ) -> Result<usize, StoreError> {
+        let renamed = self.renamed_entity_claims(tenant_id, old_entity, new_entity)?;
+        let count = renamed.len();
+        for batch in renamed.chunks(ENTITY_RENAME_WAL_BATCH_CLAIMS) {
+            let batch: Vec<(Claim, Vec<PersistedRecord>)> = batch
+                .iter()
+                .map(|claim| (claim.clone(), self.claim_vector_records(claim)))
+                .collect();
+
+            let wal_bytes_before = wal.appended_bytes();
+            for (claim, vector_records) in &batch {
+                wal.append_claim(claim)?;
+                for record in vector_records {
+                    wal.append_record(record)?;
+                }
+            }
+            wal.flush_pending_sync()?;
+            self.metrics
+                .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+
+            for (claim, vector_records) in batch {
+                self.replace_claim(claim, vector_records)?;
+            }
+        }
+        Ok(count)
+    }
+
+    /// The tenant's claims naming `old_entity`, with it renamed, sorted by
+    /// claim id. A claim that already names `new_entity` keeps one copy.
+    /// Entities are matched on their index name; a renamed entity with a
+    /// canonical name gets the new canonical name and keeps its name.
+    fn renamed_entity_claims(
+        &self,
+        tenant_id: &TenantId,
+        old_entity: &str,
+        new_entity: &str,
+    ) -> Result<Vec<Claim>, StoreError> {
+        let old_key = normalize_index_key(old_entity);
+        let new_entity = new_entity.trim();
+        if old_key.is_empty() || new_entity.is_empty() {
+            return Err(StoreError::Validation(ValidationError::MissingField(
+                "entity",
+            )));
+        }
+        let new_key = normalize_index_key(new_entity);
+
+        let mut claim_ids: Vec<ClaimId> = self
+            .claim_ids_for_entity(tenant_id, &old_key)
+            .into_iter()
+            .collect();
+        claim_ids.sort_unstable();
+        let mut renamed = Vec::with_capacity(claim_ids.len());
+        for claim_id in claim_ids {
+            let Some(claim) = self.claims.get(claim_id.as_str()) else {
+                continue;
+            };
+            let mut entities = Vec::with_capacity(claim.entities.len());
+            let mut has_new = false;
+            for entity in &claim.entities {
+                let key = normalize_index_key(entity.index_name());
+                let mut entity = entity.clone();
+                if key == old_key {
+                    match &mut entity.canonical_name {
+                        Some(canonical_name) => *canonical_name = new_entity.to_string(),
+                        None => entity.name = new_entity.to_string(),
+                    }
+                }
+                if key == old_key || key == new_key {
+                    if has_new {
+                        continue;
+                    }
+                    has_new = true;
+                }
+                entities.push(entity);
+            }
+            if entities == claim.entities {
+                continue;
+            }
+            let mut patched = claim.clone();
+            patched.entities = entities;
+            validate_claim_with(&patched, &self.validation_config)?;
+            renamed.push(patched);
+        }
+        Ok(renamed)
+    }
+}
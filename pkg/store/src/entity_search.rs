@@ -0,0 +1,156 @@
+//! Prefix and fuzzy lookups over the entity index.
+//!
+//! [`InMemoryStore::claim_ids_for_entity`] needs the exact normalized
+//! name. Prefix search finds every entity starting with what was typed,
+//! so "Company" finds "Company X" and "Company Y". Fuzzy search scores
+//! entity names by the trigrams they share with the query, as
+//! PostgreSQL's `pg_trgm` does, so a misspelling like "compny x" still
+//! finds "Company X". Each tenant keeps an index from trigram to entity
+//! names, so a fuzzy lookup only scores names sharing a trigram with the
+//! query.
+
+use std::collections::{HashMap, HashSet};
+
+use schema::{ClaimId, TenantId};
+
+use crate::{InMemoryStore, normalize_index_key};
+
+/// An indexed entity name and how similar it is to a fuzzy query, from 0
+/// (no trigram in common) to 1 (the same trigrams).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityMatch {
+    pub entity: String,
+    pub similarity: f32,
+    pub claim_count: usize,
+}
+
+impl InMemoryStore {
+    /// Claims of `tenant_id` naming an entity that starts with `prefix`,
+    /// compared trimmed and case-insensitively.
+    pub fn claim_ids_for_entity_prefix(
+        &self,
+        tenant_id: &TenantId,
+        prefix: &str,
+    ) -> HashSet<ClaimId> {
+        let prefix = normalize_index_key(prefix);
+        if prefix.is_empty() {
+            return HashSet::new();
+        }
+        self.entity_index
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|(entity, _)| entity.starts_with(&prefix))
+            .flat_map(|(_, claim_ids)| claim_ids.iter().map(ClaimId::from))
+            .collect()
+    }
+
+    /// Entity names of `tenant_id` at least `min_similarity` similar to
+    /// `entity`, most similar first.
+    pub fn fuzzy_entity_matches(
+        &self,
+        tenant_id: &TenantId,
+        entity: &str,
+        min_similarity: f32,
+    ) -> Vec<EntityMatch> {
+        let query = entity_trigrams(&normalize_index_key(entity));
+        let (Some(trigram_index), Some(entity_index)) = (
+            self.entity_trigrams.get(tenant_id.as_str()),
+            self.entity_index.get(tenant_id.as_str()),
+        ) else {
+            return Vec::new();
+        };
+        let mut shared: HashMap<&str, usize> = HashMap::new();
+        for trigram in &query {
+            for candidate in trigram_index.get(trigram).into_iter().flatten() {
+                *shared.entry(candidate.as_str()).or_default() += 1;
+            }
+        }
+        let mut matches: Vec<EntityMatch> = shared
+            .into_iter()
+            .filter_map(|(candidate, shared)| {
+                let union = query.len() + entity_trigrams(candidate).len() - shared;
+                let similarity = shared as f32 / union as f32;
+                (similarity >= min_similarity).then(|| EntityMatch {
+                    entity: candidate.to_string(),
+                    similarity,
+                    claim_count: entity_index.get(candidate).map_or(0, HashSet::len),
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then_with(|| a.entity.cmp(&b.entity))
+        });
+        matches
+    }
+
+    /// Claims of `tenant_id` naming an entity at least `min_similarity`
+    /// similar to `entity`; see [`Self::fuzzy_entity_matches`].
+    pub fn claim_ids_for_entity_fuzzy(
+        &self,
+        tenant_id: &TenantId,
+        entity: &str,
+        min_similarity: f32,
+    ) -> HashSet<ClaimId> {
+        self.fuzzy_entity_matches(tenant_id, entity, min_similarity)
+            .iter()
+            .flat_map(|matched| self.claim_ids_for_entity(tenant_id, &matched.entity))
+            .collect()
+    }
+
+    pub(crate) fn index_entity_trigrams(&mut self, tenant_id: &str, entities: &[String]) {
+        if entities.is_empty() {
+            return;
+        }
+        let trigram_index = self
+            .entity_trigrams
+            .entry(tenant_id.to_string())
+            .or_default();
+        for entity in entities {
+            for trigram in entity_trigrams(entity) {
+                trigram_index
+                    .entry(trigram)
+                    .or_default()
+                    .insert(entity.clone());
+            }
+        }
+    }
+
+    pub(crate) fn unindex_entity_trigrams(&mut self, tenant_id: &str, entities: &[String]) {
+        let Some(trigram_index) = self.entity_trigrams.get_mut(tenant_id) else {
+            return;
+        };
+        for entity in entities {
+            for trigram in entity_trigrams(entity) {
+                if let Some(names) = trigram_index.get_mut(&trigram) {
+                    names.remove(entity);
+                    if names.is_empty() {
+                        trigram_index.remove(&trigram);
+                    }
+                }
+            }
+        }
+        if trigram_index.is_empty() {
+            self.entity_trigrams.remove(tenant_id);
+        }
+    }
+}
+
+/// Trigrams of each word of a normalized name, padded with two spaces in
+/// front and one behind so short words and word starts still count.
+fn entity_trigrams(name: &str) -> HashSet<String> {
+    let mut trigrams = HashSet::new();
+    for word in name.split_whitespace() {
+        let padded: Vec<char> = "  "
+            .chars()
+            .chain(word.chars())
+            .chain(std::iter::once(' '))
+            .collect();
+        for window in padded.windows(3) {
+            trigrams.insert(window.iter().collect());
+        }
+    }
+    trigrams
+}
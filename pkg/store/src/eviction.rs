@@ -0,0 +1,204 @@
+//! Suggesting claims to evict before a tenant hits its quota.
+//!
+//! A tenant at its claim budget starts failing ingests. Before that,
+//! [`InMemoryStore::eviction_report`] lists the claims it could best do
+//! without, for an operator or an agent to review and then remove with
+//! [`InMemoryStore::delete_claims`]. Nothing is deleted by the report.
+//!
+//! Claims whose validity window has ended come first, then the rest by
+//! lowest confidence, and among equally confident claims the least
+//! retrieved. Retrievals are counted per claim as results are hydrated;
+//! the counts are runtime state, shared by clones of the store, and not
+//! written to the WAL, so after a restart every claim starts at zero.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use schema::{ClaimId, TenantId};
+
+use crate::InMemoryStore;
+
+/// When a tenant counts as near its quota, and how far a report trims.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvictionPolicy {
+    pub max_claims: usize,
+    /// Fraction of `max_claims` from which a report suggests anything.
+    pub soft_limit_ratio: f32,
+    /// Fraction of `max_claims` the suggestions bring the tenant back to.
+    pub target_ratio: f32,
+}
+
+impl EvictionPolicy {
+    /// Report from 90% of `max_claims`, trimming back to 80%.
+    pub fn new(max_claims: usize) -> Self {
+        Self {
+            max_claims,
+            soft_limit_ratio: 0.9,
+            target_ratio: 0.8,
+        }
+    }
+
+    fn claims_at(&self, ratio: f32) -> usize {
+        (self.max_claims as f64 * f64::from(ratio.clamp(0.0, 1.0))).floor() as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// The claim's validity window ended before now.
+    ExpiredValidity,
+    /// Ranked by confidence and retrievals.
+    LowValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvictionCandidate {
+    pub claim_id: ClaimId,
+    pub reason: EvictionReason,
+    pub confidence: f32,
+    pub valid_to: Option<i64>,
+    /// Times the claim was returned by retrieval since the store started.
+    pub retrievals: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvictionReport {
+    pub tenant_id: TenantId,
+    pub claim_count: usize,
+    pub max_claims: usize,
+    /// Whether the tenant has reached the policy's soft limit.
+    pub near_quota: bool,
+    /// Claims to delete to get back to the target, best first; empty
+    /// below the soft limit.
+    pub candidates: Vec<EvictionCandidate>,
+}
+
+impl EvictionReport {
+    pub fn candidate_claim_ids(&self) -> Vec<ClaimId> {
+        self.candidates
+            .iter()
+            .map(|candidate| candidate.claim_id.clone())
+            .collect()
+    }
+}
+
+/// Per-claim retrieval counts.
+#[derive(Debug, Default)]
+pub(crate) struct ClaimAccessLog {
+    retrievals: Mutex<HashMap<String, u64>>,
+}
+
+impl ClaimAccessLog {
+    pub(crate) fn record<'a>(&self, claim_ids: impl IntoIterator<Item = &'a str>) {
+        let mut retrievals = self
+            .retrievals
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for claim_id in claim_ids {
+            match retrievals.get_mut(claim_id) {
+                Some(count) => *count += 1,
+                None => {
+                    retrievals.insert(claim_id.to_string(), 1);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn forget(&self, claim_id: &str) {
+        self.retrievals
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(claim_id);
+    }
+
+    fn count(&self, claim_id: &str) -> u64 {
+        self.retrievals
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(claim_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn snapshot(&self) -> HashMap<String, u64> {
+        self.retrievals
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+impl InMemoryStore {
+    /// Times `claim_id` was returned by retrieval since the store
+    /// started.
+    pub fn claim_retrieval_count(&self, claim_id: &ClaimId) -> u64 {
+        self.claim_access.count(claim_id)
+    }
+
+    /// Claims of `tenant_id` to evict under `policy`, as of now.
+    pub fn eviction_report(&self, tenant_id: &TenantId, policy: &EvictionPolicy) -> EvictionReport {
+        let now_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        self.eviction_report_at(tenant_id, policy, now_unix)
+    }
+
+    /// [`Self::eviction_report`] with validity judged at `now_unix`.
+    pub fn eviction_report_at(
+        &self,
+        tenant_id: &TenantId,
+        policy: &EvictionPolicy,
+        now_unix: i64,
+    ) -> EvictionReport {
+        let claim_count = self
+            .tenant_claim_ids
+            .get(tenant_id.as_str())
+            .map_or(0, |ids| ids.len());
+        let near_quota = claim_count >= policy.claims_at(policy.soft_limit_ratio);
+        let mut report = EvictionReport {
+            tenant_id: tenant_id.into(),
+            claim_count,
+            max_claims: policy.max_claims,
+            near_quota,
+            candidates: Vec::new(),
+        };
+        if !near_quota {
+            return report;
+        }
+        let excess = claim_count.saturating_sub(policy.claims_at(policy.target_ratio));
+        let retrievals = self.claim_access.snapshot();
+        let mut candidates: Vec<EvictionCandidate> = self
+            .tenant_claim_ids
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter_map(|claim_id| self.claims.get(claim_id.as_str()))
+            .map(|claim| EvictionCandidate {
+                claim_id: claim.claim_id.clone(),
+                reason: if claim.valid_to.is_some_and(|valid_to| valid_to < now_unix) {
+                    EvictionReason::ExpiredValidity
+                } else {
+                    EvictionReason::LowValue
+                },
+                confidence: claim.confidence,
+                valid_to: claim.valid_to,
+                retrievals: retrievals
+                    .get(claim.claim_id.as_str())
+                    .copied()
+                    .unwrap_or_default(),
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            (b.reason == EvictionReason::ExpiredValidity)
+                .cmp(&(a.reason == EvictionReason::ExpiredValidity))
+                .then_with(|| a.confidence.total_cmp(&b.confidence))
+                .then_with(|| a.retrievals.cmp(&b.retrievals))
+                .then_with(|| a.claim_id.cmp(&b.claim_id))
+        });
+        candidates.truncate(excess);
+        report.candidates = candidates;
+        report
+    }
+}
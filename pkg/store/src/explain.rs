@@ -0,0 +1,102 @@
+//! Why a claim ranked where it did.
+//!
+//! [`InMemoryStore::retrieve_explained`] runs an ordinary retrieval and
+//! pairs each result with the signals it was scored on: lexical overlap,
+//! BM25, dense and sparse similarity, the support and contradiction
+//! adjustments, source quality, and confidence. The similarities are the
+//! ones each hit was scored on, whether they came from a query vector,
+//! several vector spaces or a sparse query. The components describe the
+//! scoring stage; a reranker later in the tenant's pipeline can still
+//! reorder results, in which case the result's own score differs from
+//! [`ScoreComponents::score`].
+
+use std::time::Instant;
+
+use schema::{RetrievalRequest, RetrievalResult};
+
+use crate::{InMemoryStore, RetrievalOptions};
+
+/// The signals behind one result's score. The weighted fields are the
+/// terms actually added up, so for lexical retrieval
+///
+/// ```text
+/// score = (0.6 * lexical_overlap + support_adjustment + contradiction_adjustment
+///          + quality + confidence) * 0.72 + bm25 * 0.28
+/// ```
+///
+/// With a query vector the dense similarity leads and that lexical score
+/// only breaks ties.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScoreComponents {
+    /// Share of query terms found in the claim, in `[0, 1]`.
+    pub lexical_overlap: f32,
+    pub bm25: f32,
+    /// Normalized similarity to the query vector; `None` without one.
+    pub dense_similarity: Option<f32>,
+    /// Normalized sparse similarity; 0.0 without a sparse query.
+    pub sparse_similarity: f32,
+    pub supports: usize,
+    pub contradicts: usize,
+    /// Weighted boost from supporting evidence and edges.
+    pub support_adjustment: f32,
+    /// Weighted penalty from contradicting evidence and edges; never
+    /// positive.
+    pub contradiction_adjustment: f32,
+    /// Weighted average source quality of the claim's evidence.
+    pub quality: f32,
+    /// Weighted claim confidence.
+    pub confidence: f32,
+    /// The score these combine into.
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainedResult {
+    pub result: RetrievalResult,
+    pub components: ScoreComponents,
+}
+
+impl InMemoryStore {
+    /// [`Self::retrieve_with`], with the score components of every
+    /// result. A cold tier in `options` is not consulted: its claims are
+    /// not resident, so there is nothing to explain them from.
+    pub fn retrieve_explained(
+        &self,
+        req: &RetrievalRequest,
+        options: &RetrievalOptions<'_>,
+    ) -> Vec<ExplainedResult> {
+        let started = Instant::now();
+        let (hits, truncated) = self.retrieve_hits_with(req, options);
+        let bm25_context = self.bm25_context_for_tenant(&req.tenant_id, &req.query);
+        self.claim_access
+            .record(hits.iter().map(|hit| hit.claim_id.as_str()));
+        let explained = hits
+            .into_iter()
+            .map(|hit| {
+                let claim = self
+                    .claims
+                    .get(hit.claim_id.as_str())
+                    .expect("ranked hits are resident claims");
+                let components = self
+                    .score_claim_components(
+                        req,
+                        hit.dense_similarity.is_some(),
+                        &bm25_context,
+                        self.resident_candidate(
+                            claim,
+                            hit.dense_similarity.unwrap_or(0.0),
+                            hit.sparse_similarity,
+                        ),
+                    )
+                    .expect("a ranked hit passes the filters it was scored under");
+                let result = self.hydrate_hit_with_fields(hit, claim, options.fields);
+                ExplainedResult { result, components }
+            })
+            .collect();
+        self.metrics.record_retrieval(started.elapsed());
+        if truncated {
+            self.metrics.record_retrieval_truncated();
+        }
+        explained
+    }
+}
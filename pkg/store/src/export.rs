@@ -0,0 +1,267 @@
+//! Portable JSONL export/import of a single tenant.
+//!
+//! Used to move a tenant between deployments or to seed test
+//! environments from a fixture file. Unlike the WAL and snapshot formats
+//! this one is meant to be read and written by other tools, so every
+//! line is a self-describing JSON object tagged with `kind`:
+//!
+//! ```text
+//! {"kind":"header","format":"dash-tenant-export","version":1,"tenant_id":"tenant-a"}
+//! {"kind":"claim", <schema::Claim fields>}
+//! {"kind":"evidence", <schema::Evidence fields>}
+//! {"kind":"edge", <schema::ClaimEdge fields>}
+//! {"kind":"vector","claim_id":"c1","values":[0.1,0.2]}
+//! ```
+//!
+//! The header is always the first line. Claims are written in claim id
+//! order, followed by their evidence and edges, then every claim vector.
+//! Blank lines are ignored on import.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use schema::{Claim, ClaimEdge, ClaimId, Evidence, TenantId};
+use serde::{Deserialize, Serialize};
+
+use crate::{FileWal, InMemoryStore, StoreError};
+
+const EXPORT_FORMAT: &str = "dash-tenant-export";
+const EXPORT_VERSION: u32 = 1;
+
+/// Record counts written by an export or applied by an import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TenantExportStats {
+    pub claims: usize,
+    pub evidence: usize,
+    pub edges: usize,
+    pub vectors: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ExportLine {
+    Header {
+        format: String,
+        version: u32,
+        tenant_id: String,
+    },
+    Claim(Claim),
+    Evidence(Evidence),
+    Edge(ClaimEdge),
+    Vector {
+        claim_id: ClaimId,
+        values: Vec<f32>,
+    },
+}
+
+/// A parsed export, grouped per claim so each bundle can be ingested
+/// through the normal validation path.
+struct ParsedExport {
+    bundles: Vec<(Claim, Vec<Evidence>, Vec<ClaimEdge>)>,
+    vectors: Vec<(ClaimId, Vec<f32>)>,
+}
+
+impl InMemoryStore {
+    /// Write every claim, evidence row, edge, and claim vector owned by
+    /// `tenant_id` to `writer` in the JSONL format described in this
+    /// module.
+    pub fn export_tenant_jsonl<W: Write>(
+        &self,
+        tenant_id: &TenantId,
+        mut writer: W,
+    ) -> Result<TenantExportStats, StoreError> {
+        let mut stats = TenantExportStats::default();
+        write_line(
+            &mut writer,
+            &ExportLine::Header {
+                format: EXPORT_FORMAT.to_string(),
+                version: EXPORT_VERSION,
+                tenant_id: tenant_id.to_string(),
+            },
+        )?;
+
+        let mut claim_ids: Vec<ClaimId> =
+            self.claim_ids_for_tenant(tenant_id).into_iter().collect();
+        claim_ids.sort_unstable();
+        for claim_id in &claim_ids {
+            let Some(claim) = self.claims.get(claim_id.as_str()) else {
+                continue;
+            };
+            write_line(&mut writer, &ExportLine::Claim(claim.clone()))?;
+            stats.claims += 1;
+            for evidence in self
+                .evidence_by_claim
+                .get(claim_id.as_str())
+                .into_iter()
+                .flatten()
+            {
+                write_line(&mut writer, &ExportLine::Evidence(evidence.clone()))?;
+                stats.evidence += 1;
+            }
+            for edge in self
+                .edges_by_claim
+                .get(claim_id.as_str())
+                .into_iter()
+                .flatten()
+            {
+                write_line(&mut writer, &ExportLine::Edge(edge.clone()))?;
+                stats.edges += 1;
+            }
+        }
+        for claim_id in &claim_ids {
+            if let Some(values) = self.claim_vectors.get(claim_id) {
+                write_line(
+                    &mut writer,
+                    &ExportLine::Vector {
+                        claim_id: claim_id.clone(),
+                        values: values.into_owned(),
+                    },
+                )?;
+                stats.vectors += 1;
+            }
+        }
+        writer.flush()?;
+        Ok(stats)
+    }
+
+    /// Load a tenant export produced by [`InMemoryStore::export_tenant_jsonl`].
+    /// The whole input is parsed and checked before anything is applied,
+    /// so a malformed file leaves the store untouched. Records go through
+    /// the same validation as regular ingest.
+    pub fn import_tenant_jsonl<R: BufRead>(
+        &mut self,
+        reader: R,
+    ) -> Result<TenantExportStats, StoreError> {
+        let parsed = parse_export(reader)?;
+        let mut stats = TenantExportStats::default();
+        for (claim, evidence, edges) in parsed.bundles {
+            stats.claims += 1;
+            stats.evidence += evidence.len();
+            stats.edges += edges.len();
+            self.ingest_bundle(claim, evidence, edges)?;
+        }
+        for (claim_id, values) in parsed.vectors {
+            self.upsert_claim_vector(&claim_id, values)?;
+            stats.vectors += 1;
+        }
+        Ok(stats)
+    }
+
+    /// Like [`InMemoryStore::import_tenant_jsonl`], but every imported
+    /// record is appended to `wal` so the tenant survives a restart.
+    pub fn import_tenant_jsonl_persistent<R: BufRead>(
+        &mut self,
+        wal: &mut FileWal,
+        reader: R,
+    ) -> Result<TenantExportStats, StoreError> {
+        let parsed = parse_export(reader)?;
+        let mut stats = TenantExportStats::default();
+        for (claim, evidence, edges) in parsed.bundles {
+            stats.claims += 1;
+            stats.evidence += evidence.len();
+            stats.edges += edges.len();
+            self.ingest_bundle_persistent(wal, claim, evidence, edges)?;
+        }
+        for (claim_id, values) in parsed.vectors {
+            self.upsert_claim_vector_persistent(wal, &claim_id, values)?;
+            stats.vectors += 1;
+        }
+        Ok(stats)
+    }
+}
+
+fn write_line<W: Write>(writer: &mut W, line: &ExportLine) -> Result<(), StoreError> {
+    let encoded = serde_json::to_string(line)
+        .map_err(|err| StoreError::Parse(format!("tenant export encode failed: {err}")))?;
+    writeln!(writer, "{encoded}")?;
+    Ok(())
+}
+
+fn parse_export<R: BufRead>(reader: R) -> Result<ParsedExport, StoreError> {
+    let mut tenant_id: Option<String> = None;
+    let mut bundles: Vec<(Claim, Vec<Evidence>, Vec<ClaimEdge>)> = Vec::new();
+    let mut bundle_index: HashMap<String, usize> = HashMap::new();
+    let mut vectors = Vec::new();
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_no = idx + 1;
+        let record: ExportLine = serde_json::from_str(&line).map_err(|err| {
+            StoreError::Parse(format!("tenant export line {line_no} is invalid: {err}"))
+        })?;
+        let Some(expected_tenant) = tenant_id.as_deref() else {
+            match record {
+                ExportLine::Header {
+                    format,
+                    version,
+                    tenant_id: header_tenant,
+                } if format == EXPORT_FORMAT && version == EXPORT_VERSION => {
+                    tenant_id = Some(header_tenant);
+                    continue;
+                }
+                _ => {
+                    return Err(StoreError::Parse(format!(
+                        "tenant export must start with a {EXPORT_FORMAT} v{EXPORT_VERSION} header"
+                    )));
+                }
+            }
+        };
+
+        match record {
+            ExportLine::Header { .. } => {
+                return Err(StoreError::Parse(format!(
+                    "tenant export line {line_no} repeats the header"
+                )));
+            }
+            ExportLine::Claim(claim) => {
+                if claim.tenant_id != expected_tenant {
+                    return Err(StoreError::Conflict(format!(
+                        "tenant export claim '{}' belongs to tenant '{}', expected '{}'",
+                        claim.claim_id, claim.tenant_id, expected_tenant
+                    )));
+                }
+                if bundle_index.contains_key(claim.claim_id.as_str()) {
+                    return Err(StoreError::Conflict(format!(
+                        "tenant export line {line_no} repeats claim '{}'",
+                        claim.claim_id
+                    )));
+                }
+                bundle_index.insert(claim.claim_id.to_string(), bundles.len());
+                bundles.push((claim, Vec::new(), Vec::new()));
+            }
+            ExportLine::Evidence(evidence) => {
+                let idx = bundle_position(&bundle_index, &evidence.claim_id, line_no)?;
+                bundles[idx].1.push(evidence);
+            }
+            ExportLine::Edge(edge) => {
+                let idx = bundle_position(&bundle_index, &edge.from_claim_id, line_no)?;
+                bundles[idx].2.push(edge);
+            }
+            ExportLine::Vector { claim_id, values } => {
+                bundle_position(&bundle_index, &claim_id, line_no)?;
+                vectors.push((claim_id, values));
+            }
+        }
+    }
+
+    if tenant_id.is_none() {
+        return Err(StoreError::Parse("tenant export is empty".to_string()));
+    }
+    Ok(ParsedExport { bundles, vectors })
+}
+
+/// Evidence, edges, and vectors must follow the claim they attach to.
+fn bundle_position(
+    bundle_index: &HashMap<String, usize>,
+    claim_id: &str,
+    line_no: usize,
+) -> Result<usize, StoreError> {
+    bundle_index.get(claim_id).copied().ok_or_else(|| {
+        StoreError::Parse(format!(
+            "tenant export line {line_no} references claim '{claim_id}' before it is defined"
+        ))
+    })
+}
@@ -0,0 +1,63 @@
+//! Weighting evidence by age and stance strength.
+//!
+//! By default every supporting or contradicting piece of evidence counts
+//! once, however old. With a freshness half-life in the store's
+//! [`RankingConfig`], evidence counts for `0.5^(age / half_life)` of
+//! that, measured from its `ingested_at` to the time of the query, so a
+//! claim backed by last week's reporting outranks one backed only by
+//! years-old documents. Evidence without an ingest time counts fully,
+//! and edges always do. The support counts reported on results stay
+//! unweighted.
+//!
+//! The same weighting carries each piece of evidence's stance strength:
+//! evidence that only weakly supports a claim counts for its
+//! [`Evidence::stance_weight`], on top of any decay, and negated evidence
+//! counts toward its [`Evidence::effective_stance`].
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ranking::{RankingConfig, WeightedSignals};
+use schema::{Evidence, Stance};
+
+use crate::InMemoryStore;
+
+impl InMemoryStore {
+    pub fn ranking_config(&self) -> &RankingConfig {
+        &self.ranking_config
+    }
+
+    /// Replace the store-wide ranking config; later retrievals score
+    /// with it.
+    pub fn set_ranking_config(&mut self, config: RankingConfig) {
+        self.ranking_config = config;
+    }
+
+    /// Support and contradiction from `evidence`, weighted by freshness
+    /// and stance strength, plus the edge counts at full weight.
+    pub(crate) fn weighted_signals(
+        &self,
+        evidence: &[Evidence],
+        edge_supports: usize,
+        edge_contradicts: usize,
+    ) -> WeightedSignals {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as i64)
+            .unwrap_or_default();
+        let weigh = |stance: Stance| -> f32 {
+            evidence
+                .iter()
+                .filter(|evidence| evidence.effective_stance() == stance)
+                .map(|evidence| {
+                    self.ranking_config
+                        .evidence_weight(evidence.ingested_at, now_ms)
+                        * evidence.stance_weight()
+                })
+                .sum()
+        };
+        WeightedSignals {
+            supports: weigh(Stance::Supports) + edge_supports as f32,
+            contradicts: weigh(Stance::Contradicts) + edge_contradicts as f32,
+        }
+    }
+}
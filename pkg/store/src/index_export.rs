@@ -0,0 +1,286 @@
+//! Index snapshot export for offline analytics.
+//!
+//! [`InMemoryStore::index_stats`] says how big each index is, not what is
+//! in it. An [`IndexSnapshot`] copies out, for one tenant, the inverted
+//! index's term statistics, the entity index's claim counts, and the
+//! event-time distribution at the store's temporal granularity, so memory
+//! composition can be analyzed in a notebook instead of a debugger. Each
+//! table is written on its own, as CSV with a header row or, with the
+//! `arrow-export` feature, as an Arrow IPC file. Every row carries the
+//! tenant id, so the exports of several tenants can be concatenated.
+
+use std::io::Write;
+
+use schema::TenantId;
+
+use crate::{InMemoryStore, TemporalBucket, TemporalGranularity};
+
+/// One table of an [`IndexSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexSnapshotTable {
+    /// `tenant_id,term,document_frequency,collection_frequency`
+    Terms,
+    /// `tenant_id,entity,claim_count`
+    Entities,
+    /// `tenant_id,granularity,bucket_start_unix,claim_count`
+    Temporal,
+}
+
+impl IndexSnapshotTable {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Terms => "terms",
+            Self::Entities => "entities",
+            Self::Temporal => "temporal",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "terms" | "term" => Some(Self::Terms),
+            "entities" | "entity" => Some(Self::Entities),
+            "temporal" | "timeline" => Some(Self::Temporal),
+            _ => None,
+        }
+    }
+
+    fn columns(self) -> &'static [&'static str] {
+        match self {
+            Self::Terms => &[
+                "tenant_id",
+                "term",
+                "document_frequency",
+                "collection_frequency",
+            ],
+            Self::Entities => &["tenant_id", "entity", "claim_count"],
+            Self::Temporal => &[
+                "tenant_id",
+                "granularity",
+                "bucket_start_unix",
+                "claim_count",
+            ],
+        }
+    }
+}
+
+/// An analyzed term and how often the tenant's claims use it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermSnapshotRow {
+    pub term: String,
+    /// Claims containing the term.
+    pub document_frequency: u64,
+    /// Occurrences of the term across every claim.
+    pub collection_frequency: u64,
+}
+
+/// A normalized entity name and how many claims name it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntitySnapshotRow {
+    pub entity: String,
+    pub claim_count: u64,
+}
+
+/// A copy of one tenant's term, entity, and temporal indexes, each table
+/// sorted by its key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSnapshot {
+    pub tenant_id: TenantId,
+    pub terms: Vec<TermSnapshotRow>,
+    pub entities: Vec<EntitySnapshotRow>,
+    pub temporal_granularity: TemporalGranularity,
+    pub temporal: Vec<TemporalBucket>,
+}
+
+impl InMemoryStore {
+    pub fn index_snapshot(&self, tenant_id: &TenantId) -> IndexSnapshot {
+        let mut terms: Vec<TermSnapshotRow> = self
+            .inverted_index
+            .get(tenant_id.as_str())
+            .map(|index| {
+                index
+                    .doc_freqs()
+                    .map(|(term, document_frequency)| TermSnapshotRow {
+                        term: term.to_string(),
+                        document_frequency: document_frequency as u64,
+                        collection_frequency: index
+                            .postings(term)
+                            .map(|(_, term_frequency)| u64::from(term_frequency))
+                            .sum(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        terms.sort_by(|a, b| a.term.cmp(&b.term));
+        let mut entities: Vec<EntitySnapshotRow> = self
+            .entity_index
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .map(|(entity, claim_ids)| EntitySnapshotRow {
+                entity: entity.clone(),
+                claim_count: claim_ids.len() as u64,
+            })
+            .collect();
+        entities.sort_by(|a, b| a.entity.cmp(&b.entity));
+        IndexSnapshot {
+            tenant_id: tenant_id.into(),
+            terms,
+            entities,
+            temporal_granularity: self.temporal_granularity,
+            temporal: self.temporal_histogram(tenant_id, self.temporal_granularity, None, None),
+        }
+    }
+}
+
+impl IndexSnapshot {
+    /// Write `table` as CSV with a header row. Returns the number of data
+    /// rows written.
+    pub fn write_csv(
+        &self,
+        table: IndexSnapshotTable,
+        out: &mut impl Write,
+    ) -> std::io::Result<usize> {
+        writeln!(out, "{}", table.columns().join(","))?;
+        let tenant_id = csv_field(&self.tenant_id);
+        match table {
+            IndexSnapshotTable::Terms => {
+                for row in &self.terms {
+                    writeln!(
+                        out,
+                        "{tenant_id},{},{},{}",
+                        csv_field(&row.term),
+                        row.document_frequency,
+                        row.collection_frequency
+                    )?;
+                }
+            }
+            IndexSnapshotTable::Entities => {
+                for row in &self.entities {
+                    writeln!(
+                        out,
+                        "{tenant_id},{},{}",
+                        csv_field(&row.entity),
+                        row.claim_count
+                    )?;
+                }
+            }
+            IndexSnapshotTable::Temporal => {
+                let granularity = self.temporal_granularity.as_str();
+                for bucket in &self.temporal {
+                    writeln!(
+                        out,
+                        "{tenant_id},{granularity},{},{}",
+                        bucket.start_unix, bucket.claim_count
+                    )?;
+                }
+            }
+        }
+        Ok(self.row_count(table))
+    }
+
+    /// Write `table` as an Arrow IPC file, one record batch holding every
+    /// row. Returns the number of rows written.
+    #[cfg(feature = "arrow-export")]
+    pub fn write_arrow_ipc(
+        &self,
+        table: IndexSnapshotTable,
+        out: impl Write,
+    ) -> Result<usize, crate::StoreError> {
+        use std::sync::Arc;
+
+        use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, UInt64Array};
+        use arrow_schema::{DataType, Field, Schema};
+
+        let tenant_ids = |rows: usize| -> ArrayRef {
+            Arc::new(StringArray::from(vec![self.tenant_id.as_str(); rows]))
+        };
+        let (types, arrays): (Vec<DataType>, Vec<ArrayRef>) = match table {
+            IndexSnapshotTable::Terms => (
+                vec![
+                    DataType::Utf8,
+                    DataType::Utf8,
+                    DataType::UInt64,
+                    DataType::UInt64,
+                ],
+                vec![
+                    tenant_ids(self.terms.len()),
+                    Arc::new(StringArray::from_iter_values(
+                        self.terms.iter().map(|row| row.term.as_str()),
+                    )),
+                    Arc::new(UInt64Array::from_iter_values(
+                        self.terms.iter().map(|row| row.document_frequency),
+                    )),
+                    Arc::new(UInt64Array::from_iter_values(
+                        self.terms.iter().map(|row| row.collection_frequency),
+                    )),
+                ],
+            ),
+            IndexSnapshotTable::Entities => (
+                vec![DataType::Utf8, DataType::Utf8, DataType::UInt64],
+                vec![
+                    tenant_ids(self.entities.len()),
+                    Arc::new(StringArray::from_iter_values(
+                        self.entities.iter().map(|row| row.entity.as_str()),
+                    )),
+                    Arc::new(UInt64Array::from_iter_values(
+                        self.entities.iter().map(|row| row.claim_count),
+                    )),
+                ],
+            ),
+            IndexSnapshotTable::Temporal => (
+                vec![
+                    DataType::Utf8,
+                    DataType::Utf8,
+                    DataType::Int64,
+                    DataType::UInt64,
+                ],
+                vec![
+                    tenant_ids(self.temporal.len()),
+                    Arc::new(StringArray::from(vec![
+                        self.temporal_granularity.as_str();
+                        self.temporal.len()
+                    ])),
+                    Arc::new(Int64Array::from_iter_values(
+                        self.temporal.iter().map(|bucket| bucket.start_unix),
+                    )),
+                    Arc::new(UInt64Array::from_iter_values(
+                        self.temporal.iter().map(|bucket| bucket.claim_count as u64),
+                    )),
+                ],
+            ),
+        };
+        let schema = Arc::new(Schema::new(
+            table
+                .columns()
+                .iter()
+                .zip(types)
+                .map(|(name, data_type)| Field::new(*name, data_type, false))
+                .collect::<Vec<_>>(),
+        ));
+        let arrow_error =
+            |err: arrow_schema::ArrowError| crate::StoreError::Io(std::io::Error::other(err));
+        let batch = RecordBatch::try_new(schema.clone(), arrays).map_err(arrow_error)?;
+        let mut writer =
+            arrow_ipc::writer::FileWriter::try_new(out, &schema).map_err(arrow_error)?;
+        writer.write(&batch).map_err(arrow_error)?;
+        writer.finish().map_err(arrow_error)?;
+        Ok(batch.num_rows())
+    }
+
+    fn row_count(&self, table: IndexSnapshotTable) -> usize {
+        match table {
+            IndexSnapshotTable::Terms => self.terms.len(),
+            IndexSnapshotTable::Entities => self.entities.len(),
+            IndexSnapshotTable::Temporal => self.temporal.len(),
+        }
+    }
+}
+
+/// Quote a CSV field when it holds a comma, quote, or line break.
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
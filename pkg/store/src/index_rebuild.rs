@@ -0,0 +1,192 @@
+//! Rebuilding a tenant's ANN index from its stored vectors.
+//!
+//! Incremental linking keeps a graph usable, but after many deletes and
+//! upserts its neighbor lists drift from what a fresh build would pick
+//! and recall drops. [`InMemoryStore::rebuild_vector_index`] rebuilds in
+//! place. For large tenants the work can move off the writer:
+//! [`InMemoryStore::prepare_vector_index_rebuild`] copies the tenant's
+//! vectors into a [`VectorIndexRebuild`], [`VectorIndexRebuild::build`]
+//! runs on any thread while the store keeps serving, and
+//! [`InMemoryStore::install_rebuilt_vector_index`] swaps the result in
+//! and re-applies whatever vectors changed in the meantime.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use schema::TenantId;
+
+use crate::ann::TenantAnnGraph;
+use crate::vector_index::VectorIndex;
+use crate::{AnnTuningConfig, DistanceMetric, InMemoryStore, StoreError};
+
+/// A tenant's vectors captured for an off-thread rebuild.
+pub struct VectorIndexRebuild {
+    tenant_id: String,
+    vectors: HashMap<String, Vec<f32>>,
+    /// Holds only the tenant's claims and vectors; the index is built
+    /// with the same code the live store uses.
+    scratch: InMemoryStore,
+}
+
+/// A built index waiting for [`InMemoryStore::install_rebuilt_vector_index`].
+pub struct RebuiltVectorIndex {
+    tenant_id: String,
+    vectors: HashMap<String, Vec<f32>>,
+    tuning: AnnTuningConfig,
+    metric: DistanceMetric,
+    graph: Option<TenantAnnGraph>,
+    index: Option<Box<dyn VectorIndex>>,
+}
+
+impl VectorIndexRebuild {
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    /// Number of vectors the rebuild covers.
+    pub fn vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn build(mut self) -> RebuiltVectorIndex {
+        let tenant_id = self.tenant_id;
+        self.scratch.rebuild_tenant_vector_index(&tenant_id);
+        RebuiltVectorIndex {
+            tuning: self.scratch.tenant_ann_tuning(&tenant_id).clone(),
+            metric: self.scratch.tenant_distance_metric(&tenant_id),
+            graph: self.scratch.ann_vector_graphs.remove(&tenant_id),
+            index: self.scratch.vector_indexes.remove(&tenant_id),
+            vectors: self.vectors,
+            tenant_id,
+        }
+    }
+}
+
+impl RebuiltVectorIndex {
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+}
+
+impl InMemoryStore {
+    /// Rebuild `tenant_id`'s ANN index from its stored vectors with the
+    /// tenant's current tuning, replacing the incrementally maintained
+    /// one. Returns the number of vectors indexed; tenants below the
+    /// exact-search threshold are left without an index and return 0.
+    pub fn rebuild_vector_index(&mut self, tenant_id: &TenantId) -> usize {
+        self.rebuild_tenant_vector_index(tenant_id);
+        if self.ann_vector_graphs.contains_key(tenant_id.as_str())
+            || self.vector_indexes.contains_key(tenant_id.as_str())
+        {
+            self.tenant_vector_claim_ids(tenant_id).len()
+        } else {
+            0
+        }
+    }
+
+    /// Copy `tenant_id`'s vectors and tuning so the index can be rebuilt
+    /// without holding the store.
+    pub fn prepare_vector_index_rebuild(&self, tenant_id: &TenantId) -> VectorIndexRebuild {
+        let mut scratch = InMemoryStore {
+            ann_tuning: self.ann_tuning.clone(),
+            ..InMemoryStore::default()
+        };
+        if let Some(config) = self.tenant_vector_configs.get(tenant_id.as_str()) {
+            scratch
+                .tenant_vector_configs
+                .insert(tenant_id.to_string(), config.clone());
+        }
+        scratch
+            .claim_vectors
+            .set_quantization(self.claim_vectors.quantization().cloned());
+        scratch
+            .claim_vectors
+            .set_storage(self.claim_vectors.storage());
+
+        let mut vectors = HashMap::new();
+        for claim_id in self.tenant_vector_claim_ids(tenant_id) {
+            let (Some(claim), Some(values)) = (
+                self.claims.get(claim_id.as_str()),
+                self.claim_vectors.get(&claim_id).map(Cow::into_owned),
+            ) else {
+                continue;
+            };
+            // Without a mapped-file config the scratch store cannot fail
+            // to insert.
+            if scratch
+                .claim_vectors
+                .insert(tenant_id, claim_id.clone(), values.clone())
+                .is_err()
+            {
+                continue;
+            }
+            scratch.claims.insert(claim.claim_id.clone(), claim.clone());
+            scratch
+                .tenant_claim_ids
+                .entry(tenant_id.to_string())
+                .or_default()
+                .insert(claim_id.clone());
+            vectors.insert(claim_id, values);
+        }
+        VectorIndexRebuild {
+            tenant_id: tenant_id.to_string(),
+            vectors,
+            scratch,
+        }
+    }
+
+    /// Swap in an index built by [`VectorIndexRebuild::build`]. Vectors
+    /// added, changed, or removed since the rebuild was prepared are
+    /// re-applied to the new index; the count of those is returned. Fails
+    /// with [`StoreError::Conflict`] when the tenant's tuning or metric
+    /// changed in the meantime, since the index no longer matches them.
+    pub fn install_rebuilt_vector_index(
+        &mut self,
+        rebuilt: RebuiltVectorIndex,
+    ) -> Result<usize, StoreError> {
+        let tenant_id = rebuilt.tenant_id.as_str();
+        if *self.tenant_ann_tuning(tenant_id) != rebuilt.tuning
+            || self.tenant_distance_metric(tenant_id) != rebuilt.metric
+        {
+            return Err(StoreError::Conflict(format!(
+                "ANN tuning for tenant {tenant_id} changed during the index rebuild"
+            )));
+        }
+
+        self.ann_vector_graphs.remove(tenant_id);
+        self.vector_indexes.remove(tenant_id);
+        if let Some(graph) = rebuilt.graph {
+            self.ann_vector_graphs.insert(tenant_id.to_string(), graph);
+        }
+        if let Some(index) = rebuilt.index {
+            self.vector_indexes.insert(tenant_id.to_string(), index);
+        }
+
+        let current = self.tenant_vector_claim_ids(tenant_id);
+        let mut caught_up = 0;
+        for claim_id in rebuilt.vectors.keys() {
+            if !self.claim_vectors.contains_key(claim_id)
+                || self
+                    .claims
+                    .get(claim_id.as_str())
+                    .is_none_or(|claim| claim.tenant_id != tenant_id)
+            {
+                self.remove_vector_index_entry(tenant_id, claim_id);
+                caught_up += 1;
+            }
+        }
+        for claim_id in current {
+            let Some(values) = self.claim_vectors.get(&claim_id).map(Cow::into_owned) else {
+                continue;
+            };
+            match rebuilt.vectors.get(&claim_id) {
+                Some(previous) if *previous == values => continue,
+                Some(_) => self.remove_vector_index_entry(tenant_id, &claim_id),
+                None => {}
+            }
+            self.add_vector_index_entry(tenant_id, &claim_id, &values);
+            caught_up += 1;
+        }
+        Ok(caught_up)
+    }
+}
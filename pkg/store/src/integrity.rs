@@ -0,0 +1,90 @@
+//! Consistency checks between the claim table and the structures derived
+//! from it.
+//!
+//! Replay rebuilds every index from the claim records, so a store that
+//! loads cleanly is normally consistent. [`InMemoryStore::verify_integrity`]
+//! confirms it after the fact: every claim is reachable from its tenant's
+//! claim set and lexical index, and nothing attached to a claim id
+//! outlives the claim. Operators run it through `dash-cli verify`.
+
+use schema::ClaimId;
+
+use crate::InMemoryStore;
+
+/// Inconsistencies found by [`InMemoryStore::verify_integrity`]. Every
+/// list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub claims_checked: usize,
+    /// Claims missing from their tenant's claim set or lexical index.
+    pub unindexed_claims: Vec<ClaimId>,
+    /// `(tenant, claim)` entries in a tenant's claim set naming a claim
+    /// that does not exist or belongs to another tenant.
+    pub stale_index_entries: Vec<(String, String)>,
+    /// Claim ids holding evidence or edges but no claim.
+    pub orphaned_attachments: Vec<String>,
+    /// Claim ids holding a vector but no claim.
+    pub orphaned_vectors: Vec<String>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.unindexed_claims.is_empty()
+            && self.stale_index_entries.is_empty()
+            && self.orphaned_attachments.is_empty()
+            && self.orphaned_vectors.is_empty()
+    }
+}
+
+impl InMemoryStore {
+    /// Cross-check the claim table against the per-tenant indexes and the
+    /// evidence, edge, and vector maps.
+    pub fn verify_integrity(&self) -> IntegrityReport {
+        let mut report = IntegrityReport {
+            claims_checked: self.claims.len(),
+            ..IntegrityReport::default()
+        };
+        for (claim_id, claim) in &self.claims {
+            let in_claim_set = self
+                .tenant_claim_ids
+                .get(claim.tenant_id.as_str())
+                .is_some_and(|ids| ids.contains(claim_id.as_str()));
+            if !in_claim_set || !self.claim_tokens.contains_key(claim_id.as_str()) {
+                report.unindexed_claims.push(claim_id.clone());
+            }
+        }
+        for (tenant_id, claim_ids) in &self.tenant_claim_ids {
+            for claim_id in claim_ids {
+                let owned = self
+                    .claims
+                    .get(claim_id.as_str())
+                    .is_some_and(|claim| &claim.tenant_id == tenant_id);
+                if !owned {
+                    report
+                        .stale_index_entries
+                        .push((tenant_id.clone(), claim_id.clone()));
+                }
+            }
+        }
+        report.orphaned_attachments = self
+            .evidence_by_claim
+            .keys()
+            .chain(self.edges_by_claim.keys())
+            .filter(|claim_id| !self.claims.contains_key(claim_id.as_str()))
+            .cloned()
+            .collect();
+        report.orphaned_vectors = self
+            .claim_vectors
+            .keys()
+            .filter(|claim_id| !self.claims.contains_key(claim_id.as_str()))
+            .cloned()
+            .collect();
+
+        report.unindexed_claims.sort_unstable();
+        report.stale_index_entries.sort_unstable();
+        report.orphaned_attachments.sort_unstable();
+        report.orphaned_attachments.dedup();
+        report.orphaned_vectors.sort_unstable();
+        report
+    }
+}
@@ -0,0 +1,142 @@
+//! Inverted-file (IVF) index, the clustering alternative to the ANN graph
+//! selected with [`AnnIndexKind::Ivf`].
+//!
+//! The tenant's vectors are clustered with k-means into `lists` centroids
+//! and every vector is filed under its nearest one. A query ranks the
+//! centroids under the tenant's metric and returns every member of the
+//! best `probes` lists; the store then scores those candidates exactly.
+//! Training follows the PQ index: first once the tenant holds as many
+//! vectors as there are lists, then whenever it has doubled since the
+//! last training, with exact search until the first round.
+//!
+//! [`AnnIndexKind::Ivf`]: crate::AnnIndexKind::Ivf
+
+use std::collections::{HashMap, HashSet};
+
+use crate::DistanceMetric;
+use crate::vector_index::{TRAINING_SAMPLE_MAX, VectorIndex, nearest_centroid, train_kmeans};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TenantIvfIndex {
+    lists: usize,
+    probes: usize,
+    centroids: Vec<Vec<f32>>,
+    /// `[list]` -> member claim ids.
+    members: Vec<HashSet<String>>,
+    assignments: HashMap<String, usize>,
+    /// Vectors held by the tenant, assigned or not.
+    vector_count: usize,
+    trained_on: usize,
+}
+
+impl TenantIvfIndex {
+    pub(crate) fn new(lists: usize, probes: usize) -> Self {
+        Self {
+            lists: lists.max(1),
+            probes: probes.max(1),
+            centroids: Vec::new(),
+            members: Vec::new(),
+            assignments: HashMap::new(),
+            vector_count: 0,
+            trained_on: 0,
+        }
+    }
+
+    /// The `probes` lists whose centroids score best against `query`.
+    fn probed_lists(&self, metric: DistanceMetric, query: &[f32]) -> Vec<usize> {
+        let mut ranked: Vec<(usize, f32)> = self
+            .centroids
+            .iter()
+            .enumerate()
+            .map(|(list, centroid)| {
+                let score = metric
+                    .similarity(query, centroid)
+                    .unwrap_or(f32::NEG_INFINITY);
+                (list, score)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+            .into_iter()
+            .take(self.probes)
+            .map(|(list, _)| list)
+            .collect()
+    }
+}
+
+impl VectorIndex for TenantIvfIndex {
+    fn is_trained(&self) -> bool {
+        !self.centroids.is_empty()
+    }
+
+    fn needs_training(&self) -> bool {
+        if self.is_trained() {
+            self.vector_count >= self.trained_on.saturating_mul(2)
+        } else {
+            self.vector_count >= self.lists
+        }
+    }
+
+    fn note_added(&mut self) {
+        self.vector_count += 1;
+    }
+
+    fn train(&mut self, vectors: &[(String, Vec<f32>)]) {
+        if vectors.is_empty() {
+            return;
+        }
+        let stride = vectors.len().div_ceil(TRAINING_SAMPLE_MAX).max(1);
+        let sample: Vec<&[f32]> = vectors
+            .iter()
+            .step_by(stride)
+            .map(|(_, values)| values.as_slice())
+            .collect();
+        self.centroids = train_kmeans(&sample, self.lists);
+        self.members = vec![HashSet::new(); self.centroids.len()];
+        self.assignments.clear();
+        for (claim_id, values) in vectors {
+            self.insert(claim_id, values);
+        }
+        self.vector_count = vectors.len();
+        self.trained_on = vectors.len();
+    }
+
+    fn insert(&mut self, claim_id: &str, values: &[f32]) {
+        if !self.is_trained() {
+            return;
+        }
+        if let Some(previous) = self.assignments.remove(claim_id) {
+            self.members[previous].remove(claim_id);
+        }
+        let list = nearest_centroid(&self.centroids, values);
+        self.members[list].insert(claim_id.to_string());
+        self.assignments.insert(claim_id.to_string(), list);
+    }
+
+    fn remove(&mut self, claim_id: &str) {
+        self.vector_count = self.vector_count.saturating_sub(1);
+        if let Some(list) = self.assignments.remove(claim_id) {
+            self.members[list].remove(claim_id);
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.vector_count == 0
+    }
+
+    /// Every member of the probed lists; `limit` is not applied because
+    /// the lists are unordered and the store re-scores them anyway.
+    fn candidates(&self, metric: DistanceMetric, query: &[f32], _limit: usize) -> Vec<String> {
+        if !self.is_trained() || query.is_empty() {
+            return Vec::new();
+        }
+        self.probed_lists(metric, query)
+            .into_iter()
+            .flat_map(|list| self.members[list].iter().cloned())
+            .collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn VectorIndex> {
+        Box::new(self.clone())
+    }
+}
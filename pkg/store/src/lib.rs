@@ -1,70 +1,315 @@
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
+    time::Instant,
 };
 
 #[cfg(feature = "gpu-backend")]
 use std::sync::OnceLock;
 
 use graph::summarize_edges;
-use ranking::{RankSignals, bm25_score, score_claim_with_bm25};
+use rand::distributions::OpenClosed01;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ranking::{RankSignals, WeightedSignals, bm25_score_tokens, score_breakdown_weighted};
 use schema::{
-    Citation, Claim, ClaimEdge, Evidence, RetrievalRequest,
-    RetrievalResult, Stance, StanceMode, ValidationError, tokenize, validate_claim,
-    validate_edge, validate_evidence,
+    Citation, Claim, ClaimEdge, ClaimId, ClaimType, Evidence, RetrievalRequest, RetrievalResult,
+    Stance, StanceMode, TenantId, ValidationConfig, ValidationError, validate_claim,
+    validate_claim_with, validate_edge, validate_evidence,
 };
 
 mod disk;
 pub use disk::{DiskBackedStore, DiskStatus};
 
-mod wal;
+mod analyzer;
 mod ann;
-mod metrics;
+mod as_of;
+mod backup;
+mod batch;
+mod bloom;
+mod cdc;
+mod certainty;
+mod claim_admin;
+mod claim_archive;
+mod claim_delete;
+mod claim_iter;
+mod claim_merge;
+mod claim_type_filter;
+mod claim_watch;
+mod cold;
+mod collections;
+mod confidence_filter;
+mod cross_tenant;
+mod documents;
+mod edge_index;
+mod entity_rename;
+mod entity_search;
+mod eviction;
+mod explain;
+mod export;
+mod freshness;
 #[cfg(feature = "gpu-backend")]
 mod gpu;
-pub use ann::AnnTuningConfig;
-pub use metrics::{StoreIndexStats, StoreLoadStats, VectorBackendRuntime};
-pub(crate) use metrics::{VectorBackendPreference, VECTOR_BACKEND_ENV};
-pub(crate) use ann::{TenantAnnGraph, ScoredNode, ANN_GRAPH_LEVELS};
+mod index_export;
+mod index_rebuild;
+mod integrity;
+mod ivf;
+mod memory;
+mod metadata_filter;
+mod metrics;
+mod migrations;
+mod mmap_vectors;
+mod named_vectors;
+mod options;
+mod outbox;
+mod pagination;
+mod phrase;
+mod pipeline;
+mod postings;
+mod pq;
+mod projection;
+mod query_dsl;
+mod read_repair;
+mod result_fields;
+mod score_normalization;
+mod shard_merge;
+mod source_filter;
+mod sources;
+mod sparse;
+mod standing_query;
+mod storage_report;
+mod supersession;
+mod temporal;
+mod tenant_migration;
+mod tenant_stats;
+mod tenanted;
+mod term_stats;
+mod vector_config;
+mod vector_index;
+mod vector_scorer;
+mod vector_store;
+mod visibility;
+mod wal;
+mod wal_backend;
+mod wal_migration;
+mod wal_tail;
+mod write_stall;
+pub use analyzer::{TextAnalyzer, TokenizerKind};
+pub(crate) use ann::{ANN_GRAPH_LEVELS, ANN_GRAPH_SNAPSHOT_VERSION, ScoredNode, TenantAnnGraph};
+pub use ann::{AnnIndexKind, AnnSearchOverrides, AnnTuningConfig};
+use as_of::ValidityIndex;
+pub use backup::{BackupManifest, verify_backup};
+pub(crate) use cdc::ChangeFeed;
+pub use cdc::{ChangeEvent, ChangeRecord, ChangeSubscription, DEFAULT_CHANGE_FEED_CAPACITY};
+pub(crate) use certainty::certainty_band;
+pub use claim_admin::{ClaimInspection, ClaimPatch};
+pub use claim_iter::ClaimPage;
+pub use claim_merge::ClaimMergeStats;
+pub use claim_watch::{
+    ClaimWatchEvent, ClaimWatchFilter, ClaimWatchNotification, ClaimWatchSubscription,
+    StanceBalance,
+};
+pub use cold::{ColdClaim, ColdClaimSource, ColdTier, ColdTierCounts, TieredRetrieval};
+pub use confidence_filter::ConfidenceRange;
+pub use cross_tenant::TenantRetrievalResult;
+pub use entity_search::EntityMatch;
+pub use eviction::{EvictionCandidate, EvictionPolicy, EvictionReason, EvictionReport};
+pub use explain::{ExplainedResult, ScoreComponents};
+pub use export::TenantExportStats;
+pub use index_export::{EntitySnapshotRow, IndexSnapshot, IndexSnapshotTable, TermSnapshotRow};
+pub use index_rebuild::{RebuiltVectorIndex, VectorIndexRebuild};
+pub use integrity::IntegrityReport;
+pub use memory::{DashMemory, DashMemoryConfig, DashMemoryMaintenance};
+pub use metadata_filter::MetadataFilter;
+pub use metrics::{StoreIndexStats, StoreLoadStats, StoreMetricsSnapshot, VectorBackendRuntime};
+pub(crate) use metrics::{StoreMetrics, VECTOR_BACKEND_ENV, VectorBackendPreference};
+pub use mmap_vectors::MmapVectorConfig;
+pub use named_vectors::VectorSpaceQuery;
+pub use options::{RetrievalOptions, RetrievalOutcome};
+pub use outbox::{Outbox, OutboxEvent, OutboxOp};
+use pagination::RankPosition;
+pub use pagination::{RetrievalCursor, RetrievalPage};
+pub(crate) use phrase::PhraseQuery;
+use phrase::parse_analyzed_phrase_queries;
+pub use pipeline::{
+    PipelineConfig, PipelineStage, RerankCandidate, Reranker, StageBreakerConfig,
+    StageBreakerState, StageBreakerStatus,
+};
+use postings::TenantTermIndex;
+pub use projection::VectorProjection;
+pub use query_dsl::{ParsedQuery, parse_query};
+pub use read_repair::StaleIndexEntry;
+pub use result_fields::ResultFields;
+pub use score_normalization::{ScoreNormalization, ScoreScale, TenantScoreNormalization};
+pub use shard_merge::{ScoreCalibration, merge_shard_results, scatter_gather};
+use sparse::SPARSE_SCORE_WEIGHT;
+pub use sparse::SparseVector;
+pub use standing_query::{StandingQuery, StandingQueryMatch};
+pub use storage_report::{
+    StorageAlert, StorageAlertKind, StorageReport, StorageThresholds, StorageUsage, storage_report,
+};
+pub use temporal::{TemporalBucket, TemporalGranularity};
+pub use tenant_migration::TenantMigrationStats;
+pub use tenant_stats::{TenantIndexMemory, TenantStats, TenantWalUsage};
+pub use tenanted::{TenantedStore, TenantedStoreConfig};
+pub use term_stats::TermStatistics;
+pub use vector_config::{DistanceMetric, TenantVectorConfig};
+use vector_index::{VectorIndex, new_vector_index};
+use vector_scorer::score_candidates_cpu;
+pub use vector_scorer::{CpuVectorScorer, VectorScorer};
+use vector_store::ClaimVectorStore;
+pub use vector_store::{Int8QuantizationConfig, VectorPrecision, VectorStorageConfig};
 
 #[derive(Default)]
 pub(crate) struct Bm25Context {
     doc_freq: HashMap<String, usize>,
     total_docs: usize,
     avg_doc_len: f32,
+    /// The query's terms, analyzed for the tenant.
+    query_tokens: Vec<String>,
+    /// Quoted phrases every hit must contain.
+    phrases: Vec<PhraseQuery>,
 }
 
+/// Borrowed inputs for scoring one retrieval candidate.
+#[derive(Clone, Copy)]
+struct ClaimCandidate<'a> {
+    claim: &'a Claim,
+    evidence: &'a [Evidence],
+    edges: &'a [ClaimEdge],
+    tokens: Option<&'a [String]>,
+    dense_similarity: f32,
+    /// Normalized sparse score; 0.0 without a sparse query.
+    sparse_similarity: f32,
+}
 
-
-pub use wal::{
-    CheckpointPolicy, FileWal, WalCheckpointStats, WalEvent, WalReplayBoundary,
-    WalReplayStats, WalReplicationDelta, WalReplicationExport, WalRollbackPoint,
-    WalWritePolicy,
-};
+pub use ranking::RankingConfig;
 pub(crate) use wal::{
-    BatchCommitRecord, ClaimVectorRecord, PersistedRecord, line_to_record,
+    AnnGraphHeaderRecord, AnnGraphNodeRecord, BatchCommitRecord, ClaimArchiveRecord,
+    ClaimVectorRecord, PersistedRecord, TenantVectorConfigRecord, TextAnalyzerRecord,
+    VectorProjectionRecord, WalEvent, line_to_record,
 };
-
+pub use wal::{
+    CheckpointPolicy, FileWal, WalCheckpointStats, WalReplayBoundary, WalReplayStats,
+    WalReplicationDelta, WalReplicationExport, WalRollbackPoint, WalWritePolicy,
+};
+pub use wal_backend::{FileWalBackend, LineVisitor, MemoryWalBackend, WalBackend};
+pub use wal_migration::{DualWriteVerification, DualWriteWalBackend, WalMigration};
+pub use wal_tail::{SnapshotShipment, WalFollowOutcome, WalFollower};
+pub use write_stall::WriteStall;
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct BatchCommitMetadata {
     pub commit_id: String,
     pub batch_size: usize,
     pub ts_unix_ms: u64,
-    pub claim_ids: Vec<String>,
+    pub claim_ids: Vec<ClaimId>,
     pub payload_fingerprint: String,
 }
 
+/// Index-only retrieval result: the ranking outcome for one claim
+/// without its text or citations. Returned by
+/// [`InMemoryStore::retrieve_hits`] for callers that rank many claims
+/// and hydrate only the ones they keep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetrievalHit {
+    pub claim_id: ClaimId,
+    pub score: f32,
+    pub supports: usize,
+    pub contradicts: usize,
+    /// Normalized dense similarity the hit was scored on; `None` without
+    /// a query vector.
+    pub dense_similarity: Option<f32>,
+    /// Normalized sparse similarity the hit was scored on; 0.0 without a
+    /// sparse query.
+    pub sparse_similarity: f32,
+}
+
+/// A claim with everything attached to it, as returned by
+/// [`InMemoryStore::get_claims`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimWithEvidence {
+    pub claim: Claim,
+    pub evidence: Vec<Evidence>,
+    pub edges: Vec<ClaimEdge>,
+}
+
+/// Result of a batch claim lookup. `claims` follows the order of the
+/// requested ids; ids that are unknown or owned by another tenant are
+/// listed in `missing_claim_ids` instead.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClaimHydration {
+    pub claims: Vec<ClaimWithEvidence>,
+    pub missing_claim_ids: Vec<ClaimId>,
+}
+
+/// Evidence attributed to one source within a tenant, as returned by
+/// [`InMemoryStore::sources_for_tenant`].
 #[derive(Debug, Clone, PartialEq)]
+pub struct SourceSummary {
+    pub source_id: String,
+    pub evidence_count: usize,
+    /// Number of distinct claims the source has evidence for.
+    pub claim_count: usize,
+    pub avg_source_quality: f32,
+    pub supports: usize,
+    pub contradicts: usize,
+    pub neutral: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum StoreError {
-    Validation(ValidationError),
+    #[error("validation failed: {0}")]
+    Validation(#[from] ValidationError),
+    #[error("missing claim: {0}")]
     MissingClaim(String),
+    #[error("conflict: {0}")]
     Conflict(String),
+    #[error("invalid vector: {0}")]
     InvalidVector(String),
-    Io(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parse error: {0}")]
     Parse(String),
+    #[error("quota exceeded: {0}")]
+    QuotaExceeded(String),
+    #[error("unknown tenant: {0}")]
+    UnknownTenant(String),
+    /// The store does not allow the operation, as for cross-tenant
+    /// search while it is disabled.
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+    /// A chunk names a document that is not registered.
+    #[error("missing document: {0}")]
+    MissingDocument(String),
+    /// Writes are stopped until a checkpoint shrinks the WAL backlog.
+    #[error("write stalled: {0}")]
+    WriteStalled(String),
+    /// A retrieval cursor was issued before the tenant's index changed,
+    /// so the page sequence it belongs to is no longer valid; restart
+    /// paging without a cursor.
+    #[error("stale cursor: {0}")]
+    StaleCursor(String),
+    /// A tenant's store failed to load; `source` is the replay error,
+    /// shared with [`TenantedStore::failed_tenants`].
+    #[error("tenant '{tenant_id}' failed to load")]
+    TenantLoad {
+        tenant_id: String,
+        #[source]
+        source: std::sync::Arc<StoreError>,
+    },
 }
 
+/// Wrap an error from the disk layer, which reports errors as strings.
+fn disk_error(message: String) -> StoreError {
+    StoreError::Io(std::io::Error::other(message))
+}
+
+/// Candidates scored between checks of a retrieval deadline.
+const DEADLINE_CHECK_INTERVAL: usize = 64;
+
 const FNV1A_64_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
 const FNV1A_64_PRIME: u64 = 0x100000001b3;
 
@@ -75,7 +320,7 @@ fn fnv1a64_feed(state: &mut u64, bytes: &[u8]) {
     }
 }
 
-pub fn batch_commit_payload_fingerprint(batch_size: usize, claim_ids: &[String]) -> String {
+pub fn batch_commit_payload_fingerprint(batch_size: usize, claim_ids: &[ClaimId]) -> String {
     let mut state = FNV1A_64_OFFSET_BASIS;
     fnv1a64_feed(&mut state, &(batch_size as u64).to_le_bytes());
     fnv1a64_feed(&mut state, &(claim_ids.len() as u64).to_le_bytes());
@@ -86,19 +331,6 @@ pub fn batch_commit_payload_fingerprint(batch_size: usize, claim_ids: &[String])
     format!("{state:016x}")
 }
 
-impl From<ValidationError> for StoreError {
-    fn from(value: ValidationError) -> Self {
-        Self::Validation(value)
-    }
-}
-
-impl From<std::io::Error> for StoreError {
-    fn from(value: std::io::Error) -> Self {
-        Self::Io(value.to_string())
-    }
-}
-
-
 #[derive(Default, Clone)]
 /// `Clone` preserves the disk handle via `Arc` (refcount bump, not a
 /// deep redb copy). This is the redb PR 2 fix: cloning a store no
@@ -108,26 +340,93 @@ impl From<std::io::Error> for StoreError {
 /// on the clone, which caused disk writes to be silently lost on any
 /// code path that cloned the store. With `Arc<DiskBackedStore>`,
 /// the cloned store shares the same redb handle and writes to either
-/// are visible to both.
+/// are visible to both. The metrics registry is shared the same way,
+/// so counters observed through a clone include the original's work,
+/// and so are the pipeline's stage circuit breakers.
 pub struct InMemoryStore {
-    claims: HashMap<String, Claim>,
+    claims: HashMap<ClaimId, Claim>,
     evidence_by_claim: HashMap<String, Vec<Evidence>>,
     edges_by_claim: HashMap<String, Vec<ClaimEdge>>,
-    claim_vectors: HashMap<String, Vec<f32>>,
+    /// Claims with an edge to each claim, by the claim pointed at.
+    incoming_edges: HashMap<String, HashSet<String>>,
+    claim_vectors: ClaimVectorStore,
     ann_vector_graphs: HashMap<String, TenantAnnGraph>,
+    /// Trained indexes for tenants whose index kind is not the graph.
+    vector_indexes: HashMap<String, Box<dyn VectorIndex>>,
+    /// Tenants whose graph is being restored from snapshot records.
+    restoring_ann_graphs: HashSet<String>,
     tenant_vector_dims: HashMap<String, usize>,
+    tenant_vector_configs: HashMap<String, TenantVectorConfig>,
+    /// Dimensionality reduction applied to each tenant's vectors.
+    vector_projections: HashMap<String, VectorProjection>,
+    /// Each tenant's named vector spaces, one inner store per name.
+    named_vector_spaces: HashMap<String, BTreeMap<String, InMemoryStore>>,
     tenant_claim_ids: HashMap<String, HashSet<String>>,
-    inverted_index: HashMap<String, HashMap<String, HashSet<String>>>,
+    sparse_vectors: HashMap<String, SparseVector>,
+    /// Per tenant, the claims whose sparse vector has each term.
+    sparse_postings: HashMap<String, HashMap<String, HashSet<String>>>,
+    inverted_index: HashMap<String, TenantTermIndex>,
     entity_index: HashMap<String, HashMap<String, HashSet<String>>>,
+    /// Per tenant, the normalized entity names containing each trigram.
+    entity_trigrams: HashMap<String, HashMap<String, HashSet<String>>>,
     embedding_index: HashMap<String, HashMap<String, HashSet<String>>>,
+    /// Per tenant, metadata key to value to claims, both normalized.
+    metadata_index: HashMap<String, HashMap<String, HashMap<String, HashSet<String>>>>,
+    /// Per tenant, claims by the start of their event time's bucket.
     temporal_index: HashMap<String, BTreeMap<i64, HashSet<String>>>,
+    temporal_granularity: TemporalGranularity,
+    /// Per tenant, typed claims by type.
+    claim_type_index: HashMap<String, HashMap<ClaimType, HashSet<String>>>,
+    /// Per tenant, claims with a validity window by its start and end.
+    validity_index: HashMap<String, ValidityIndex>,
+    /// Per tenant, claims by the sortable key of their confidence.
+    confidence_index: HashMap<String, BTreeMap<u32, HashSet<String>>>,
+    /// Per tenant, claims by the sources their evidence cites.
+    source_index: HashMap<String, HashMap<String, HashSet<String>>>,
+    /// Per tenant, claims kept but left out of retrieval.
+    archived_claims: HashMap<String, HashSet<String>>,
+    /// Per tenant, bumped by every change that can reorder retrieval
+    /// results; see [`RetrievalCursor`].
+    index_epochs: HashMap<String, u64>,
     batch_commits: HashMap<String, BatchCommitMetadata>,
     claim_tokens: HashMap<String, Vec<String>>,
     ann_tuning: AnnTuningConfig,
+    ranking_config: RankingConfig,
     vector_backend_runtime: VectorBackendRuntime,
+    vector_scorer: Option<Arc<dyn VectorScorer>>,
+    /// Tenants whose retrievals run a pipeline other than the default.
+    tenant_pipelines: HashMap<String, PipelineConfig>,
+    /// Tenants whose incoming scores are normalized before validation.
+    score_normalizations: HashMap<String, TenantScoreNormalization>,
+    /// Tenants whose text is analyzed beyond plain tokenization.
+    text_analyzers: HashMap<String, TextAnalyzer>,
+    /// Corpus statistics a coordinator distributed for BM25, per tenant.
+    global_term_stats: HashMap<String, TermStatistics>,
+    reranker: Option<Arc<dyn Reranker>>,
+    stage_breakers: pipeline::StageBreakers,
+    read_repair: read_repair::ReadRepair,
     wal: Vec<WalEvent>,
     disk: Option<Arc<disk::DiskBackedStore>>,
     disk_status: disk::DiskStatus,
+    metrics: Arc<StoreMetrics>,
+    /// Retrievals per claim, for eviction suggestions.
+    claim_access: Arc<eviction::ClaimAccessLog>,
+    change_feed: ChangeFeed,
+    /// Per tenant, standing queries run against each new claim.
+    standing_queries: HashMap<String, standing_query::TenantStandingQueries>,
+    cross_tenant_search_enabled: bool,
+    /// When each tenant last ingested a bundle in this process.
+    tenant_last_ingest_unix_ms: HashMap<String, u64>,
+    /// Per tenant, registered source documents and their chunks.
+    documents: HashMap<String, documents::TenantDocuments>,
+    /// Per tenant, registered evidence sources and their trust.
+    sources: HashMap<String, sources::TenantSources>,
+    /// Per tenant, claims by the collection they belong to.
+    collection_index: HashMap<String, HashMap<String, HashSet<String>>>,
+    /// Size limits on ingested claims and bundles.
+    validation_config: ValidationConfig,
+    /// Per tenant, claims carrying visibility labels.
+    labelled_claim_ids: HashMap<String, HashSet<String>>,
 }
 
 impl InMemoryStore {
@@ -141,6 +440,7 @@ impl InMemoryStore {
         Self {
             ann_tuning,
             vector_backend_runtime,
+            temporal_granularity: TemporalGranularity::from_env(),
             ..Self::default()
         }
     }
@@ -149,8 +449,198 @@ impl InMemoryStore {
         &self.ann_tuning
     }
 
+    /// Replace the store-wide ANN tuning. Tenants without their own
+    /// tuning whose index kind changes are re-indexed immediately.
     pub fn set_ann_tuning(&mut self, ann_tuning: AnnTuningConfig) {
-        self.ann_tuning = ann_tuning;
+        let previous = std::mem::replace(&mut self.ann_tuning, ann_tuning);
+        let mut tenants: Vec<String> = self
+            .tenant_vector_dims
+            .keys()
+            .filter(|tenant_id| {
+                self.tenant_vector_configs
+                    .get(tenant_id.as_str())
+                    .is_none_or(|config| config.ann_tuning.is_none())
+            })
+            .cloned()
+            .collect();
+        tenants.sort_unstable();
+        for tenant_id in tenants {
+            self.refresh_tenant_vector_index(&tenant_id, &previous);
+        }
+    }
+
+    /// ANN tuning used for `tenant_id`: the override from its registered
+    /// [`TenantVectorConfig`] if any, otherwise the store-wide config.
+    pub fn ann_tuning_for_tenant(&self, tenant_id: &TenantId) -> &AnnTuningConfig {
+        self.tenant_ann_tuning(tenant_id)
+    }
+
+    /// Metric used for `tenant_id`'s vectors; cosine unless a registered
+    /// [`TenantVectorConfig`] says otherwise.
+    pub fn distance_metric_for_tenant(&self, tenant_id: &TenantId) -> DistanceMetric {
+        self.tenant_distance_metric(tenant_id)
+    }
+
+    pub(crate) fn tenant_ann_tuning(&self, tenant_id: &str) -> &AnnTuningConfig {
+        self.tenant_vector_configs
+            .get(tenant_id)
+            .and_then(|config| config.ann_tuning.as_ref())
+            .unwrap_or(&self.ann_tuning)
+    }
+
+    pub(crate) fn tenant_distance_metric(&self, tenant_id: &str) -> DistanceMetric {
+        self.tenant_vector_configs
+            .get(tenant_id)
+            .map(|config| config.metric)
+            .unwrap_or_default()
+    }
+
+    pub fn vector_quantization(&self) -> Option<&Int8QuantizationConfig> {
+        self.claim_vectors.quantization()
+    }
+
+    /// Turn int8 vector quantization on or off. Existing vectors are
+    /// converted in place; ANN graphs are kept as built.
+    pub fn set_vector_quantization(&mut self, quantization: Option<Int8QuantizationConfig>) {
+        self.claim_vectors.set_quantization(quantization);
+    }
+
+    pub fn vector_storage_config(&self) -> VectorStorageConfig {
+        self.claim_vectors.storage()
+    }
+
+    /// Set the in-memory precision of claim vectors. Existing vectors are
+    /// converted in place; int8 quantization, when on, still applies.
+    pub fn set_vector_storage_config(&mut self, config: VectorStorageConfig) {
+        self.claim_vectors.set_storage(config);
+    }
+
+    pub fn validation_config(&self) -> &ValidationConfig {
+        &self.validation_config
+    }
+
+    /// Replace the size limits on ingested claims and bundles. They apply
+    /// to later ingests and patches only; stored claims, and claims
+    /// replayed from the WAL, are not checked against them.
+    pub fn set_validation_config(&mut self, config: ValidationConfig) {
+        self.validation_config = config;
+    }
+
+    /// Move tenants whose vectors exceed `config.tenant_budget_bytes`
+    /// into memory-mapped files, now and as they grow.
+    pub fn enable_mmap_vectors(&mut self, config: MmapVectorConfig) -> Result<(), StoreError> {
+        let mut tenant_ids: Vec<String> = self.tenant_vector_dims.keys().cloned().collect();
+        tenant_ids.sort_unstable();
+        let mapped = self.claim_vectors.mapped_tenant_ids();
+        let mut tenant_memory_bytes = HashMap::new();
+        for tenant_id in &tenant_ids {
+            if mapped.contains(tenant_id) {
+                continue;
+            }
+            let vectors = self.tenant_vector_claim_ids(tenant_id).len();
+            let dimension = self.tenant_vector_dims[tenant_id];
+            tenant_memory_bytes.insert(
+                tenant_id.clone(),
+                vectors * dimension * std::mem::size_of::<f32>(),
+            );
+        }
+        self.claim_vectors
+            .set_mmap(Some(config), tenant_memory_bytes);
+        for tenant_id in tenant_ids {
+            if self.claim_vectors.tenant_over_budget(&tenant_id) {
+                let claim_ids = self.tenant_vector_claim_ids(&tenant_id);
+                self.claim_vectors.map_tenant(&tenant_id, &claim_ids)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read every mapped tenant back into memory and stop mapping.
+    pub fn disable_mmap_vectors(&mut self) {
+        self.claim_vectors.set_mmap(None, HashMap::new());
+    }
+
+    pub fn mmap_vector_config(&self) -> Option<&MmapVectorConfig> {
+        self.claim_vectors.mmap()
+    }
+
+    /// Tenants whose vectors live in memory-mapped files, sorted.
+    pub fn mapped_vector_tenants(&self) -> Vec<TenantId> {
+        self.claim_vectors
+            .mapped_tenant_ids()
+            .into_iter()
+            .map(TenantId::from)
+            .collect()
+    }
+
+    /// Bytes of the memory-mapped vector files.
+    pub fn mapped_vector_bytes(&self) -> usize {
+        self.claim_vectors.mapped_bytes()
+    }
+
+    /// Approximate heap bytes held by claim vector payloads.
+    pub fn vector_storage_bytes(&self) -> usize {
+        self.claim_vectors.storage_bytes()
+    }
+
+    pub fn tenant_vector_config(&self, tenant_id: &TenantId) -> Option<&TenantVectorConfig> {
+        self.tenant_vector_configs.get(tenant_id.as_str())
+    }
+
+    /// Declare the vector dimension, metric, and optional ANN tuning for
+    /// `tenant_id`. Later vector upserts for the tenant must match the
+    /// declared dimension. Fails if the tenant already stores vectors of
+    /// a different dimension.
+    pub fn register_tenant_vector_config(
+        &mut self,
+        tenant_id: &TenantId,
+        config: TenantVectorConfig,
+    ) -> Result<(), StoreError> {
+        self.validate_tenant_vector_config(tenant_id, &config)?;
+        self.install_tenant_vector_config(tenant_id, config);
+        Ok(())
+    }
+
+    pub fn register_tenant_vector_config_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        config: TenantVectorConfig,
+    ) -> Result<(), StoreError> {
+        self.validate_tenant_vector_config(tenant_id, &config)?;
+        wal.append_tenant_vector_config(tenant_id, &config)?;
+        self.install_tenant_vector_config(tenant_id, config);
+        Ok(())
+    }
+
+    fn install_tenant_vector_config(&mut self, tenant_id: &str, config: TenantVectorConfig) {
+        let previous = self.tenant_ann_tuning(tenant_id).clone();
+        self.tenant_vector_configs
+            .insert(tenant_id.to_string(), config);
+        if self.tenant_vector_dims.contains_key(tenant_id) {
+            self.refresh_tenant_vector_index(tenant_id, &previous);
+        }
+    }
+
+    /// Bring `tenant_id`'s index in line with its tuning after a change:
+    /// rebuilt for a new index kind, dropped or built when the tenant now
+    /// sits on the other side of the exact-search threshold.
+    fn refresh_tenant_vector_index(&mut self, tenant_id: &str, previous: &AnnTuningConfig) {
+        let tuning = self.tenant_ann_tuning(tenant_id);
+        if tuning.index_kind != previous.index_kind {
+            self.rebuild_tenant_vector_index(tenant_id);
+            return;
+        }
+        if tuning.exact_search_threshold == previous.exact_search_threshold {
+            return;
+        }
+        let threshold = tuning.exact_search_threshold;
+        let indexed = self.ann_vector_graphs.contains_key(tenant_id)
+            || self.vector_indexes.contains_key(tenant_id);
+        let below_threshold = self.tenant_vector_claim_ids(tenant_id).len() < threshold;
+        if indexed == below_threshold {
+            self.rebuild_tenant_vector_index(tenant_id);
+        }
     }
 
     pub fn vector_backend_runtime(&self) -> VectorBackendRuntime {
@@ -161,6 +651,85 @@ impl InMemoryStore {
         self.vector_backend_runtime.as_str()
     }
 
+    /// Route exact vector scoring through `scorer` (a GPU or remote
+    /// implementation, say); `None` restores the built-in path. Clones
+    /// of the store share the scorer.
+    pub fn set_vector_scorer(&mut self, scorer: Option<Arc<dyn VectorScorer>>) {
+        for space in self
+            .named_vector_spaces
+            .values_mut()
+            .flat_map(BTreeMap::values_mut)
+        {
+            space.vector_scorer = scorer.clone();
+        }
+        self.vector_scorer = scorer;
+    }
+
+    pub fn vector_scorer_name(&self) -> Option<&str> {
+        self.vector_scorer.as_deref().map(VectorScorer::name)
+    }
+
+    /// Copy of the store's ingest, retrieval, ANN, checkpoint, and WAL
+    /// counters. Each counter is read on its own, so a snapshot taken
+    /// while other threads write may mix counts from before and after
+    /// one operation. Replay during `load_from_*` is not counted as
+    /// ingest.
+    pub fn metrics_snapshot(&self) -> StoreMetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Zero every counter in the metrics registry. Intended for
+    /// benchmark harnesses that measure one phase at a time.
+    pub fn reset_metrics(&self) {
+        self.metrics.reset();
+    }
+
+    /// Subscribe to the change feed. The subscription receives the full
+    /// payload of every claim, evidence, edge, vector, and batch-commit
+    /// mutation applied after this call, including records applied via
+    /// `apply_persisted_record_line` on replicas. Bulk loads and WAL
+    /// replay at construction time are not published. The subscription
+    /// queues up to [`DEFAULT_CHANGE_FEED_CAPACITY`] events.
+    pub fn subscribe(&self) -> ChangeSubscription {
+        self.subscribe_with_capacity(DEFAULT_CHANGE_FEED_CAPACITY)
+    }
+
+    /// [`InMemoryStore::subscribe`] with a queue of `capacity` events.
+    /// Changes published while the queue is full are dropped for this
+    /// subscriber and counted in [`ChangeSubscription::dropped_events`].
+    pub fn subscribe_with_capacity(&self, capacity: usize) -> ChangeSubscription {
+        self.change_feed.subscribe(capacity)
+    }
+
+    /// A copy of this store to apply a write to before it is known to
+    /// commit, e.g. ahead of its WAL append. Changes and metrics the copy
+    /// records are held back until [`InMemoryStore::commit_staged`], so a
+    /// write that is abandoned by dropping the copy is never published
+    /// or counted.
+    pub fn staged(&self) -> Self {
+        let mut staged = self.clone();
+        staged.change_feed = self.change_feed.staged();
+        staged.metrics = Arc::default();
+        staged
+    }
+
+    /// Replace this store with `staged`, taken from
+    /// [`InMemoryStore::staged`], publishing the changes and counting the
+    /// metrics it held back.
+    pub fn commit_staged(&mut self, mut staged: Self) {
+        let change_feed = std::mem::take(&mut self.change_feed);
+        let metrics = std::mem::take(&mut self.metrics);
+        metrics.absorb(&staged.metrics);
+        change_feed.commit(&staged.change_feed);
+        staged.change_feed = change_feed;
+        staged.metrics = metrics;
+        *self = staged;
+    }
+
+    pub fn change_subscriber_count(&self) -> usize {
+        self.change_feed.subscriber_count()
+    }
+
     /// Attach a `redb`-backed disk store to this in-memory store. The
     /// disk store is opened at `path` (creating it on first use).
     /// Every subsequent `apply_*` call will mirror the in-memory
@@ -182,7 +751,9 @@ impl InMemoryStore {
             }),
             Err(reason) => Ok(Self {
                 disk: None,
-                disk_status: disk::DiskStatus::Unavailable { reason: reason.clone() },
+                disk_status: disk::DiskStatus::Unavailable {
+                    reason: reason.clone(),
+                },
                 ..self
             }),
         }
@@ -211,32 +782,9 @@ impl InMemoryStore {
             store: &mut InMemoryStore,
             wal: &mut FileWal,
         ) -> Result<StoreLoadStats, String> {
-            let (records, replay_stats) = wal
-                .replay_records_with_stats()
-                .map_err(|e| format!("wal replay: {e:?}"))?;
-            let mut claims_loaded = 0usize;
-            let mut evidence_loaded = 0usize;
-            let mut edges_loaded = 0usize;
-            let mut vectors_loaded = 0usize;
-            for record in records {
-                match &record {
-                    PersistedRecord::Claim(_) => claims_loaded += 1,
-                    PersistedRecord::Evidence(_) => evidence_loaded += 1,
-                    PersistedRecord::Edge(_) => edges_loaded += 1,
-                    PersistedRecord::ClaimVector(_) => vectors_loaded += 1,
-                    PersistedRecord::BatchCommit(_) => {}
-                }
-                store
-                    .apply_persisted_record(record)
-                    .map_err(|e| format!("apply_persisted_record: {e:?}"))?;
-            }
-            Ok(StoreLoadStats {
-                replay: replay_stats,
-                claims_loaded,
-                evidence_loaded,
-                edges_loaded,
-                vectors_loaded,
-            })
+            store
+                .replay_wal_into(wal)
+                .map_err(|e| format!("wal replay: {e}"))
         }
 
         // 1. Open the disk. If the open fails, fall back to the
@@ -266,12 +814,7 @@ impl InMemoryStore {
         // pattern of `take()` + restore, which was only needed when
         // the disk was an owned `DiskBackedStore` (not Clone-able
         // because it wraps a `redb::Database`).
-        let disk = Arc::clone(
-            store
-                .disk
-                .as_ref()
-                .expect("disk was just attached"),
-        );
+        let disk = Arc::clone(store.disk.as_ref().expect("disk was just attached"));
         let claims_loaded = disk
             .bulk_load_claims_into(&mut store)
             .map_err(|e| format!("disk bulk load: {e}"))?;
@@ -308,40 +851,57 @@ impl InMemoryStore {
         ann_tuning: AnnTuningConfig,
     ) -> Result<(Self, StoreLoadStats), StoreError> {
         let mut store = Self::new_with_ann_tuning(ann_tuning);
-        let (records, replay_stats) = wal.replay_records_with_stats()?;
+        let stats = store.replay_wal_into(wal)?;
+        Ok((store, stats))
+    }
+
+    /// Apply every record of `wal` as it is parsed, so replay never holds
+    /// more than one record at a time.
+    fn replay_wal_into(&mut self, wal: &FileWal) -> Result<StoreLoadStats, StoreError> {
         let mut claims_loaded = 0usize;
         let mut evidence_loaded = 0usize;
         let mut edges_loaded = 0usize;
         let mut vectors_loaded = 0usize;
-
-        for record in records {
+        let replay_stats = wal.replay_with_stats(|record| {
             match &record {
                 PersistedRecord::Claim(_) => claims_loaded += 1,
                 PersistedRecord::Evidence(_) => evidence_loaded += 1,
                 PersistedRecord::Edge(_) => edges_loaded += 1,
                 PersistedRecord::ClaimVector(_) => vectors_loaded += 1,
-                PersistedRecord::BatchCommit(_) => {}
-            }
-            store.apply_persisted_record(record)?;
-        }
-        Ok((
-            store,
-            StoreLoadStats {
-                replay: replay_stats,
-                claims_loaded,
-                evidence_loaded,
-                edges_loaded,
-                vectors_loaded,
-            },
-        ))
+                PersistedRecord::BatchCommit(_)
+                | PersistedRecord::TenantVectorConfig(_)
+                | PersistedRecord::AnnGraphHeader(_)
+                | PersistedRecord::AnnGraphNode(_)
+                | PersistedRecord::VectorProjection(_)
+                | PersistedRecord::SparseVector(_)
+                | PersistedRecord::TextAnalyzer(_)
+                | PersistedRecord::ClaimDelete(_)
+                | PersistedRecord::ClaimMerge(_)
+                | PersistedRecord::ClaimArchive(_)
+                | PersistedRecord::Document(_)
+                | PersistedRecord::Chunk(_)
+                | PersistedRecord::Source(_)
+                | PersistedRecord::Checkpoint(_) => {}
+            }
+            self.apply_persisted_record(record)
+        })?;
+        self.finish_ann_graph_restore();
+        Ok(StoreLoadStats {
+            replay: replay_stats,
+            claims_loaded,
+            evidence_loaded,
+            edges_loaded,
+            vectors_loaded,
+        })
     }
 
     pub fn ingest_bundle(
         &mut self,
-        claim: Claim,
-        evidence: Vec<Evidence>,
+        mut claim: Claim,
+        mut evidence: Vec<Evidence>,
         edges: Vec<ClaimEdge>,
     ) -> Result<(), StoreError> {
+        self.normalize_bundle_scores(&mut claim, &mut evidence);
         self.validate_bundle(&claim, &evidence, &edges)?;
         self.apply_bundle(claim, evidence, edges)
     }
@@ -349,12 +909,14 @@ impl InMemoryStore {
     pub fn ingest_bundle_persistent(
         &mut self,
         wal: &mut FileWal,
-        claim: Claim,
-        evidence: Vec<Evidence>,
+        mut claim: Claim,
+        mut evidence: Vec<Evidence>,
         edges: Vec<ClaimEdge>,
     ) -> Result<(), StoreError> {
+        self.normalize_bundle_scores(&mut claim, &mut evidence);
         self.validate_bundle(&claim, &evidence, &edges)?;
 
+        let wal_bytes_before = wal.appended_bytes();
         wal.append_claim(&claim)?;
         for evd in &evidence {
             wal.append_evidence(evd)?;
@@ -362,10 +924,16 @@ impl InMemoryStore {
         for edge in &edges {
             wal.append_edge(edge)?;
         }
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
 
         self.apply_bundle(claim, evidence, edges)
     }
 
+    /// Ingest under `policy`, checkpointing when it is due. A write
+    /// slowdown is slept through in place; callers sharing the store
+    /// behind a lock call [`Self::throttle_write`] and wait after
+    /// releasing it instead.
     pub fn ingest_bundle_persistent_with_policy(
         &mut self,
         wal: &mut FileWal,
@@ -374,6 +942,9 @@ impl InMemoryStore {
         evidence: Vec<Evidence>,
         edges: Vec<ClaimEdge>,
     ) -> Result<Option<WalCheckpointStats>, StoreError> {
+        if let Some(delay) = self.throttle_write(wal, policy)? {
+            std::thread::sleep(delay);
+        }
         self.ingest_bundle_persistent(wal, claim, evidence, edges)?;
         if self.should_checkpoint(wal, policy)? {
             let stats = self.checkpoint_and_compact(wal)?;
@@ -385,29 +956,39 @@ impl InMemoryStore {
 
     pub fn upsert_claim_vector(
         &mut self,
-        claim_id: &str,
+        claim_id: &ClaimId,
         vector: Vec<f32>,
     ) -> Result<(), StoreError> {
-        self.apply_claim_vector(claim_id, vector)
+        self.apply_claim_vector(claim_id, vector)?;
+        self.metrics.record_vector_upserted();
+        Ok(())
     }
 
     pub fn upsert_claim_vector_persistent(
         &mut self,
         wal: &mut FileWal,
-        claim_id: &str,
+        claim_id: &ClaimId,
         vector: Vec<f32>,
     ) -> Result<(), StoreError> {
         validate_vector(&vector)?;
+        let wal_bytes_before = wal.appended_bytes();
         wal.append_claim_vector(claim_id, &vector)?;
-        self.apply_claim_vector(claim_id, vector)
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        self.apply_claim_vector(claim_id, vector)?;
+        self.metrics.record_vector_upserted();
+        Ok(())
     }
 
     pub fn checkpoint_and_compact(
         &self,
         wal: &mut FileWal,
     ) -> Result<WalCheckpointStats, StoreError> {
+        let started = Instant::now();
         let records = self.snapshot_records();
-        wal.compact_with_snapshot(&records)
+        let stats = wal.compact_with_snapshot(&records)?;
+        self.metrics.record_checkpoint(started.elapsed());
+        Ok(stats)
     }
 
     pub fn observe_batch_commit(
@@ -415,7 +996,7 @@ impl InMemoryStore {
         commit_id: &str,
         batch_size: usize,
         ts_unix_ms: u64,
-        claim_ids: &[String],
+        claim_ids: &[ClaimId],
     ) -> Result<(), StoreError> {
         self.apply_batch_commit_record(BatchCommitRecord {
             commit_id: commit_id.to_string(),
@@ -433,8 +1014,11 @@ impl InMemoryStore {
         self.apply_persisted_record(line_to_record(line)?)
     }
 
+    /// Lexical retrieval. Like every retrieve path, results are ordered
+    /// by score descending, then claim confidence descending, then claim
+    /// id ascending, so equal scores always come back in the same order.
     pub fn retrieve(&self, req: &RetrievalRequest) -> Vec<RetrievalResult> {
-        self.retrieve_with_time_range_and_query_vector(req, None, None, None)
+        self.retrieve_with(req, &RetrievalOptions::new())
     }
 
     /// Semantic-first retrieval. Takes a pre-computed embedding of the
@@ -449,11 +1033,9 @@ impl InMemoryStore {
         req: &RetrievalRequest,
         query_vector: &[f32],
     ) -> Vec<RetrievalResult> {
-        self.retrieve_with_time_range_and_query_vector(
+        self.retrieve_with(
             req,
-            None,
-            None,
-            Some(query_vector),
+            &RetrievalOptions::new().with_query_vector(Some(query_vector)),
         )
     }
 
@@ -463,7 +1045,10 @@ impl InMemoryStore {
         from_unix: Option<i64>,
         to_unix: Option<i64>,
     ) -> Vec<RetrievalResult> {
-        self.retrieve_with_time_range_and_query_vector(req, from_unix, to_unix, None)
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new().with_time_range(from_unix, to_unix),
+        )
     }
 
     pub fn retrieve_with_time_range_and_query_vector(
@@ -473,12 +1058,11 @@ impl InMemoryStore {
         to_unix: Option<i64>,
         query_vector: Option<&[f32]>,
     ) -> Vec<RetrievalResult> {
-        self.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+        self.retrieve_with(
             req,
-            from_unix,
-            to_unix,
-            query_vector,
-            None,
+            &RetrievalOptions::new()
+                .with_time_range(from_unix, to_unix)
+                .with_query_vector(query_vector),
         )
     }
 
@@ -488,17 +1072,63 @@ impl InMemoryStore {
         from_unix: Option<i64>,
         to_unix: Option<i64>,
         query_vector: Option<&[f32]>,
-        allowed_claim_ids: Option<&HashSet<String>>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
     ) -> Vec<RetrievalResult> {
-        let candidates = self.candidate_claim_ids(
-            &req.tenant_id,
-            &req.query,
-            (from_unix, to_unix),
-            query_vector,
-            req.top_k,
-            allowed_claim_ids,
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(from_unix, to_unix)
+                .with_query_vector(query_vector)
+                .with_allowed_claim_ids(allowed_claim_ids),
+        )
+    }
+
+    /// [`InMemoryStore::retrieve_with_time_range_query_vector_and_allowed_claim_ids`]
+    /// with the tenant's ANN search parameters overridden for this query
+    /// only, e.g. a larger expansion budget for a recall-sensitive call.
+    /// Runs the tenant's [`PipelineConfig`].
+    #[deprecated(note = "use `retrieve_with` with `RetrievalOptions::with_ann_overrides`")]
+    pub fn retrieve_with_ann_overrides(
+        &self,
+        req: &RetrievalRequest,
+        time_range: (Option<i64>, Option<i64>),
+        query_vector: Option<&[f32]>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+        ann_overrides: AnnSearchOverrides,
+    ) -> Vec<RetrievalResult> {
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(time_range.0, time_range.1)
+                .with_query_vector(query_vector)
+                .with_allowed_claim_ids(allowed_claim_ids)
+                .with_ann_overrides(ann_overrides),
+        )
+    }
+
+    /// Index-only variant of
+    /// [`InMemoryStore::retrieve_with_time_range_and_query_vector`].
+    /// Candidates are ranked identically, but only claim ids, scores, and
+    /// support counts are returned; canonical text, evidence, and
+    /// citations are never cloned. Suited to large `top_k` and analytical
+    /// queries where the caller hydrates a subset afterwards. Runs the
+    /// tenant's pipeline like [`InMemoryStore::retrieve_with`].
+    pub fn retrieve_hits(
+        &self,
+        req: &RetrievalRequest,
+        from_unix: Option<i64>,
+        to_unix: Option<i64>,
+        query_vector: Option<&[f32]>,
+    ) -> Vec<RetrievalHit> {
+        let started = Instant::now();
+        let (hits, _) = self.retrieve_hits_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(from_unix, to_unix)
+                .with_query_vector(query_vector),
         );
-        self.score_and_rank_candidate_claim_ids(req, query_vector, candidates)
+        self.metrics.record_retrieval(started.elapsed());
+        hits
     }
 
     pub fn retrieve_with_time_range_query_vector_and_explicit_candidate_claim_ids(
@@ -507,13 +1137,32 @@ impl InMemoryStore {
         from_unix: Option<i64>,
         to_unix: Option<i64>,
         query_vector: Option<&[f32]>,
-        candidate_claim_ids: &HashSet<String>,
-        allowed_claim_ids: Option<&HashSet<String>>,
+        candidate_claim_ids: &HashSet<ClaimId>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
     ) -> Vec<RetrievalResult> {
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(from_unix, to_unix)
+                .with_query_vector(query_vector)
+                .with_candidate_claim_ids(candidate_claim_ids)
+                .with_allowed_claim_ids(allowed_claim_ids),
+        )
+    }
+
+    /// `candidate_claim_ids` of the request's tenant that fall in
+    /// `time_range` and in `allowed_claim_ids`, sorted.
+    fn explicit_candidate_claim_ids(
+        &self,
+        req: &RetrievalRequest,
+        (from_unix, to_unix): (Option<i64>, Option<i64>),
+        candidate_claim_ids: &HashSet<ClaimId>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+    ) -> Vec<String> {
         let mut candidates: Vec<String> = candidate_claim_ids
             .iter()
             .filter_map(|claim_id| {
-                let claim = self.claims.get(claim_id)?;
+                let claim = self.claims.get(claim_id.as_str())?;
                 if claim.tenant_id != req.tenant_id {
                     return None;
                 }
@@ -525,218 +1174,486 @@ impl InMemoryStore {
                 {
                     return None;
                 }
-                Some(claim_id.clone())
+                Some(claim_id.to_string())
             })
             .collect();
         candidates.sort_unstable();
-        self.score_and_rank_candidate_claim_ids(req, query_vector, candidates)
+        candidates
+    }
+
+    fn hydrate_hits(&self, hits: Vec<RetrievalHit>) -> Vec<RetrievalResult> {
+        self.hydrate_hits_with_fields(hits, ResultFields::Full)
     }
 
-    fn score_and_rank_candidate_claim_ids(
+    /// Rank candidates without cloning claim bodies or evidence.
+    /// With a `deadline`, also whether scoring stopped short of some
+    /// candidates.
+    fn rank_candidate_hits(
         &self,
         req: &RetrievalRequest,
         query_vector: Option<&[f32]>,
         candidates: Vec<String>,
-    ) -> Vec<RetrievalResult> {
-        let mut ranked: Vec<RetrievalResult> = Vec::new();
+        deadline: Option<Instant>,
+        after: Option<RankPosition<'_>>,
+    ) -> (Vec<RetrievalHit>, bool) {
         let bm25_context = self.bm25_context_for_tenant(&req.tenant_id, &req.query);
-        let dense_similarities = query_vector.map(|vector| {
-            let candidate_vectors: Vec<(String, &[f32])> = candidates
-                .iter()
-                .filter_map(|claim_id| {
-                    let claim = self.claims.get(claim_id)?;
-                    if claim.tenant_id != req.tenant_id {
-                        return None;
-                    }
-                    let claim_vector = self.claim_vectors.get(claim_id)?;
-                    Some((claim_id.clone(), claim_vector.as_slice()))
-                })
-                .collect();
-            self.score_query_candidate_vectors(vector, candidate_vectors)
-                .into_iter()
-                .collect::<HashMap<String, f32>>()
-        });
-
-        for claim_id in candidates {
-            let Some(claim) = self.claims.get(&claim_id) else {
-                continue;
-            };
+        let (mut hits, truncated) =
+            self.score_candidate_hits(req, &bm25_context, query_vector, None, candidates, deadline);
+        if let Some(after) = after {
+            hits.retain(|hit| self.hit_ranked_after(hit, after));
+        }
+        hits.truncate(req.top_k);
+        (hits, truncated)
+    }
 
-            let evidence = self
-                .evidence_by_claim
-                .get(&claim.claim_id)
-                .cloned()
-                .unwrap_or_default();
-            let edges = self
-                .edges_by_claim
-                .get(&claim.claim_id)
-                .cloned()
-                .unwrap_or_default();
-            let edge_summary = summarize_edges(&edges);
+    /// Score and sort every candidate, best first, without cutting the
+    /// list down to `top_k`. See [`Self::rank_scored_candidate_hits`] for
+    /// the deadline.
+    fn score_candidate_hits(
+        &self,
+        req: &RetrievalRequest,
+        bm25_context: &Bm25Context,
+        query_vector: Option<&[f32]>,
+        sparse_query: Option<&SparseVector>,
+        candidates: Vec<String>,
+        deadline: Option<Instant>,
+    ) -> (Vec<RetrievalHit>, bool) {
+        let dense_similarities =
+            query_vector.map(|vector| self.dense_similarities(req, vector, &candidates));
+        let sparse_similarities =
+            sparse_query.map(|query| self.sparse_similarities(query, &candidates));
+        self.rank_scored_candidate_hits(
+            req,
+            bm25_context,
+            dense_similarities,
+            sparse_similarities,
+            candidates,
+            deadline,
+        )
+    }
 
-            let supports = evidence
-                .iter()
-                .filter(|e| matches!(e.stance, Stance::Supports))
-                .count()
-                + edge_summary.supports;
-            let contradicts = evidence
-                .iter()
-                .filter(|e| matches!(e.stance, Stance::Contradicts))
-                .count()
-                + edge_summary.contradicts;
+    /// Normalized dense similarity of each of `candidates` owned by the
+    /// requesting tenant that has a vector.
+    fn dense_similarities(
+        &self,
+        req: &RetrievalRequest,
+        query_vector: &[f32],
+        candidates: &[String],
+    ) -> HashMap<String, f32> {
+        let metric = self.tenant_distance_metric(&req.tenant_id);
+        let vector = self.project_query_vector(&req.tenant_id, query_vector);
+        let vector_claim_ids: Vec<String> = candidates
+            .iter()
+            .filter(|claim_id| {
+                self.claims
+                    .get(claim_id.as_str())
+                    .is_some_and(|claim| claim.tenant_id == req.tenant_id)
+            })
+            .cloned()
+            .collect();
+        self.score_claim_vectors(metric, &vector, vector_claim_ids)
+            .into_iter()
+            .map(|(claim_id, score)| (claim_id, metric.normalize_similarity(score)))
+            .collect()
+    }
 
-            if matches!(req.stance_mode, StanceMode::SupportOnly) && contradicts > supports {
-                continue;
+    /// Score and sort candidates given their normalized dense and sparse
+    /// similarities, if the query had either signal at all. The list is
+    /// not cut down to `top_k`. Once `deadline` passes, scoring stops at
+    /// the next check and the hits so far are ranked; the flag returned
+    /// says whether candidates were left unscored.
+    fn rank_scored_candidate_hits(
+        &self,
+        req: &RetrievalRequest,
+        bm25_context: &Bm25Context,
+        dense_similarities: Option<HashMap<String, f32>>,
+        sparse_similarities: Option<HashMap<String, f32>>,
+        candidates: Vec<String>,
+        deadline: Option<Instant>,
+    ) -> (Vec<RetrievalHit>, bool) {
+        let mut ranked: Vec<RetrievalHit> = Vec::new();
+        let mut truncated = false;
+        for (scored_count, claim_id) in candidates.into_iter().enumerate() {
+            if scored_count > 0
+                && scored_count % DEADLINE_CHECK_INTERVAL == 0
+                && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                truncated = true;
+                break;
             }
-
-            let avg_quality = if evidence.is_empty() {
-                0.0
-            } else {
-                evidence.iter().map(|e| e.source_quality).sum::<f32>() / evidence.len() as f32
+            let Some(claim) = self.claims.get(claim_id.as_str()) else {
+                self.note_stale_index_entry(&req.tenant_id, &claim_id);
+                continue;
             };
-
-            let bm25 = self
-                .claim_tokens
-                .get(&claim.claim_id)
-                .map(|tokens| {
-                    bm25_score(
-                        &req.query,
-                        tokens,
-                        &bm25_context.doc_freq,
-                        bm25_context.total_docs,
-                        bm25_context.avg_doc_len,
-                    )
-                })
-                .unwrap_or(0.0);
-
             let dense_similarity = dense_similarities
                 .as_ref()
-                .and_then(|scores| scores.get(&claim.claim_id))
+                .and_then(|scores| scores.get(claim.claim_id.as_str()))
                 .copied()
                 .unwrap_or(0.0);
-
-            let lexical_score = score_claim_with_bm25(
-                &req.query,
-                claim,
-                avg_quality,
-                RankSignals {
-                    supports,
-                    contradicts,
-                },
-                bm25,
+            let sparse_similarity = sparse_similarities
+                .as_ref()
+                .and_then(|scores| scores.get(claim.claim_id.as_str()))
+                .copied()
+                .unwrap_or(0.0);
+            let scored = self.score_claim_hit(
+                req,
+                dense_similarities.is_some(),
+                bm25_context,
+                self.resident_candidate(claim, dense_similarity, sparse_similarity),
             );
+            if let Some(result) = scored {
+                ranked.push(result);
+            }
+        }
 
-            let score = if query_vector.is_some() {
-                // Semantic-first retrieval: dense similarity is the
-                // PRIMARY signal (cosine in [-1, 1] -> mapped to
-                // [0, 1] via the embedding backend). The lexical/BM25
-                // score is a small tie-breaker when dense similarities
-                // are tied. This replaces the historical 0.35 additive
-                // weight with semantic-primary scoring, which is the
-                // right default when the caller explicitly provides
-                // a query vector.
-                let dense_primary = (dense_similarity + 1.0) * 0.5;
-                dense_primary + (lexical_score * 0.1)
-            } else {
-                // Lexical-only retrieval: historical behavior
-                // (dense_similarity is 0.0 when no query_vector).
-                lexical_score + (dense_similarity * 0.35)
-            };
-
-            let citations = evidence
+        ranked.sort_by(|a, b| {
+            compare_ranked(
+                (a.score, self.claim_confidence(&a.claim_id), &a.claim_id),
+                (b.score, self.claim_confidence(&b.claim_id), &b.claim_id),
+            )
+        });
+        (ranked, truncated)
+    }
+
+    /// A resident claim with its evidence, edges, and indexed tokens.
+    fn resident_candidate<'a>(
+        &'a self,
+        claim: &'a Claim,
+        dense_similarity: f32,
+        sparse_similarity: f32,
+    ) -> ClaimCandidate<'a> {
+        ClaimCandidate {
+            claim,
+            evidence: self
+                .evidence_by_claim
+                .get(claim.claim_id.as_str())
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            edges: self
+                .edges_by_claim
+                .get(claim.claim_id.as_str())
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            tokens: self
+                .claim_tokens
+                .get(claim.claim_id.as_str())
+                .map(Vec::as_slice),
+            dense_similarity,
+            sparse_similarity,
+        }
+    }
+
+    /// Score one candidate into a full result with text and citations.
+    /// Returns `None` when the stance mode filters it out. Shared by the
+    /// resident path and the cold-tier merge so both produce comparable
+    /// scores.
+    fn score_claim_candidate(
+        &self,
+        req: &RetrievalRequest,
+        semantic: bool,
+        bm25_context: &Bm25Context,
+        candidate: ClaimCandidate<'_>,
+    ) -> Option<RetrievalResult> {
+        let hit = self.score_claim_hit(req, semantic, bm25_context, candidate)?;
+        Some(hydrate_hit(
+            hit,
+            candidate.claim,
+            candidate.evidence,
+            |e| self.evidence_quality(&candidate.claim.tenant_id, e),
+            ResultFields::Full,
+        ))
+    }
+
+    fn score_claim_hit(
+        &self,
+        req: &RetrievalRequest,
+        semantic: bool,
+        bm25_context: &Bm25Context,
+        candidate: ClaimCandidate<'_>,
+    ) -> Option<RetrievalHit> {
+        let claim_id = candidate.claim.claim_id.clone();
+        let components = self.score_claim_components(req, semantic, bm25_context, candidate)?;
+        Some(RetrievalHit {
+            claim_id,
+            score: components.score,
+            supports: components.supports,
+            contradicts: components.contradicts,
+            dense_similarity: components.dense_similarity,
+            sparse_similarity: components.sparse_similarity,
+        })
+    }
+
+    /// The signals a candidate is scored on and the score they combine
+    /// into, or `None` when a phrase or the stance mode filters it out.
+    fn score_claim_components(
+        &self,
+        req: &RetrievalRequest,
+        semantic: bool,
+        bm25_context: &Bm25Context,
+        candidate: ClaimCandidate<'_>,
+    ) -> Option<ScoreComponents> {
+        let ClaimCandidate {
+            claim,
+            evidence,
+            edges,
+            tokens,
+            dense_similarity,
+            sparse_similarity,
+        } = candidate;
+        if !bm25_context.phrases.is_empty() {
+            let owned_tokens;
+            let tokens = match tokens {
+                Some(tokens) => tokens,
+                None => {
+                    owned_tokens = self.analyze_claim_text(claim);
+                    &owned_tokens
+                }
+            };
+            if !bm25_context
+                .phrases
                 .iter()
-                .map(|e| Citation {
-                    evidence_id: e.evidence_id.clone(),
-                    source_id: e.source_id.clone(),
-                    stance: e.stance.clone(),
-                    source_quality: e.source_quality,
-                    chunk_id: e.chunk_id.clone(),
-                    span_start: e.span_start,
-                    span_end: e.span_end,
-                    doc_id: e.doc_id.clone(),
-                    extraction_model: e.extraction_model.clone(),
-                    ingested_at: e.ingested_at,
-                })
-                .collect();
-            ranked.push(RetrievalResult {
-                claim_id: claim.claim_id.clone(),
-                canonical_text: claim.canonical_text.clone(),
-                score,
-                supports,
-                contradicts,
-                citations,
-            });
+                .all(|phrase| phrase.matches_tokens(tokens))
+            {
+                return None;
+            }
+        }
+        let edge_summary = summarize_edges(edges);
+
+        let supports = evidence
+            .iter()
+            .filter(|e| e.effective_stance() == Stance::Supports)
+            .count()
+            + edge_summary.supports;
+        let contradicts = evidence
+            .iter()
+            .filter(|e| e.effective_stance() == Stance::Contradicts)
+            .count()
+            + edge_summary.contradicts;
+
+        if matches!(req.stance_mode, StanceMode::SupportOnly) && contradicts > supports {
+            return None;
         }
 
-        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
-        ranked.into_iter().take(req.top_k).collect()
+        let avg_quality = if evidence.is_empty() {
+            0.0
+        } else {
+            evidence
+                .iter()
+                .map(|e| self.evidence_quality(&claim.tenant_id, e))
+                .sum::<f32>()
+                / evidence.len() as f32
+        };
+
+        let bm25 = tokens
+            .map(|tokens| {
+                bm25_score_tokens(
+                    &bm25_context.query_tokens,
+                    tokens,
+                    &bm25_context.doc_freq,
+                    bm25_context.total_docs,
+                    bm25_context.avg_doc_len,
+                )
+            })
+            .unwrap_or(0.0);
+
+        let signals = if self.ranking_config.freshness_half_life.is_some()
+            || evidence.iter().any(|e| e.stance_strength.is_some())
+        {
+            self.weighted_signals(evidence, edge_summary.supports, edge_summary.contradicts)
+        } else {
+            WeightedSignals::from(RankSignals {
+                supports,
+                contradicts,
+            })
+        };
+        let breakdown = score_breakdown_weighted(&req.query, claim, avg_quality, signals);
+        let lexical_score = breakdown.total_with_bm25(bm25);
+
+        let score = if semantic {
+            // Semantic-first retrieval: dense similarity is the
+            // PRIMARY signal (cosine in [-1, 1] -> mapped to
+            // [0, 1] via the embedding backend). The lexical/BM25
+            // score is a small tie-breaker when dense similarities
+            // are tied. This replaces the historical 0.35 additive
+            // weight with semantic-primary scoring, which is the
+            // right default when the caller explicitly provides
+            // a query vector.
+            let dense_primary = (dense_similarity + 1.0) * 0.5;
+            dense_primary + (lexical_score * 0.1)
+        } else {
+            // Lexical-only retrieval: historical behavior
+            // (dense_similarity is 0.0 when no query_vector).
+            lexical_score + (dense_similarity * 0.35)
+        };
+        let score = score + sparse_similarity * SPARSE_SCORE_WEIGHT;
+
+        Some(ScoreComponents {
+            lexical_overlap: breakdown.lexical_overlap,
+            bm25,
+            dense_similarity: semantic.then_some(dense_similarity),
+            sparse_similarity,
+            supports,
+            contradicts,
+            support_adjustment: breakdown.support,
+            contradiction_adjustment: -breakdown.contradiction_penalty,
+            quality: breakdown.quality,
+            confidence: breakdown.confidence,
+            score,
+        })
     }
 
-    pub fn claims_for_tenant(&self, tenant_id: &str) -> Vec<Claim> {
+    fn claim_confidence(&self, claim_id: &str) -> f32 {
         self.claims
-            .values()
-            .filter(|claim| claim.tenant_id == tenant_id)
-            .cloned()
-            .collect()
+            .get(claim_id)
+            .map(|claim| claim.confidence)
+            .unwrap_or(0.0)
     }
 
-    pub fn tenant_ids(&self) -> Vec<String> {
-        let mut out: Vec<String> = self.tenant_claim_ids.keys().cloned().collect();
+    /// Copies of every claim of `tenant_id`; see [`Self::iter_claims`]
+    /// and [`Self::claims_page`] for large tenants.
+    pub fn claims_for_tenant(&self, tenant_id: &TenantId) -> Vec<Claim> {
+        self.iter_claims(tenant_id).cloned().collect()
+    }
+
+    pub fn tenant_ids(&self) -> Vec<TenantId> {
+        let mut out: Vec<TenantId> = self.tenant_claim_ids.keys().map(TenantId::from).collect();
         out.sort_unstable();
         out
     }
 
-    pub fn claim_ids_for_tenant(&self, tenant_id: &str) -> HashSet<String> {
+    pub fn claim_ids_for_tenant(&self, tenant_id: &TenantId) -> HashSet<ClaimId> {
         self.tenant_claim_ids
-            .get(tenant_id)
-            .cloned()
-            .unwrap_or_default()
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect()
     }
 
-    pub fn claim_by_id(&self, claim_id: &str) -> Option<&Claim> {
+    pub fn claim_by_id(&self, claim_id: &ClaimId) -> Option<&Claim> {
         self.claims.get(claim_id)
     }
 
-    pub fn claim_ids_for_entity(&self, tenant_id: &str, entity: &str) -> HashSet<String> {
+    /// Claims of `tenant_id` naming `entity`.
+    pub fn claim_ids_for_entity(&self, tenant_id: &TenantId, entity: &str) -> HashSet<ClaimId> {
         let key = normalize_index_key(entity);
         if key.is_empty() {
             return HashSet::new();
         }
         self.entity_index
-            .get(tenant_id)
+            .get(tenant_id.as_str())
             .and_then(|index| index.get(&key))
-            .cloned()
-            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect()
     }
 
     pub fn claim_ids_for_embedding_id(
         &self,
-        tenant_id: &str,
+        tenant_id: &TenantId,
         embedding_id: &str,
-    ) -> HashSet<String> {
+    ) -> HashSet<ClaimId> {
         let key = embedding_id.trim();
         if key.is_empty() {
             return HashSet::new();
         }
         self.embedding_index
-            .get(tenant_id)
+            .get(tenant_id.as_str())
             .and_then(|index| index.get(key))
-            .cloned()
-            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect()
     }
 
-    pub fn edges_for_claim(&self, claim_id: &str) -> Vec<ClaimEdge> {
+    pub fn edges_for_claim(&self, claim_id: &ClaimId) -> Vec<ClaimEdge> {
         self.edges_by_claim
-            .get(claim_id)
+            .get(claim_id.as_str())
             .cloned()
             .unwrap_or_default()
     }
 
-    pub fn claims_for_entity(&self, tenant_id: &str, entity: &str) -> Vec<Claim> {
+    /// Fetch full claims with their evidence and edges in one call, for
+    /// example to hydrate the hits of [`InMemoryStore::retrieve_hits`].
+    /// Repeated ids are returned once.
+    pub fn get_claims(&self, tenant_id: &TenantId, claim_ids: &[ClaimId]) -> ClaimHydration {
+        let mut out = ClaimHydration::default();
+        let mut seen: HashSet<&ClaimId> = HashSet::with_capacity(claim_ids.len());
+        for claim_id in claim_ids {
+            if !seen.insert(claim_id) {
+                continue;
+            }
+            match self.claims.get(claim_id) {
+                Some(claim) if claim.tenant_id == *tenant_id => {
+                    out.claims.push(ClaimWithEvidence {
+                        claim: claim.clone(),
+                        evidence: self
+                            .evidence_by_claim
+                            .get(claim_id.as_str())
+                            .cloned()
+                            .unwrap_or_default(),
+                        edges: self.edges_for_claim(claim_id),
+                    });
+                }
+                _ => out.missing_claim_ids.push(claim_id.clone()),
+            }
+        }
+        out
+    }
+
+    /// Every distinct `source_id` cited by `tenant_id`'s evidence, with
+    /// counts, average quality, and stance distribution, ordered by
+    /// source id.
+    pub fn sources_for_tenant(&self, tenant_id: &TenantId) -> Vec<SourceSummary> {
+        let mut by_source: BTreeMap<&str, (SourceSummary, f32, HashSet<&str>)> = BTreeMap::new();
+        let Some(claim_ids) = self.tenant_claim_ids.get(tenant_id.as_str()) else {
+            return Vec::new();
+        };
+        for claim_id in claim_ids {
+            for evidence in self.evidence_by_claim.get(claim_id).into_iter().flatten() {
+                let (summary, quality_sum, claims) = by_source
+                    .entry(evidence.source_id.as_str())
+                    .or_insert_with(|| {
+                        (
+                            SourceSummary {
+                                source_id: evidence.source_id.clone(),
+                                evidence_count: 0,
+                                claim_count: 0,
+                                avg_source_quality: 0.0,
+                                supports: 0,
+                                contradicts: 0,
+                                neutral: 0,
+                            },
+                            0.0,
+                            HashSet::new(),
+                        )
+                    });
+                summary.evidence_count += 1;
+                *quality_sum += self.evidence_quality(tenant_id, evidence);
+                claims.insert(claim_id.as_str());
+                match evidence.effective_stance() {
+                    Stance::Supports => summary.supports += 1,
+                    Stance::Contradicts => summary.contradicts += 1,
+                    Stance::Neutral => summary.neutral += 1,
+                }
+            }
+        }
+        by_source
+            .into_values()
+            .map(|(mut summary, quality_sum, claims)| {
+                summary.claim_count = claims.len();
+                summary.avg_source_quality = quality_sum / summary.evidence_count as f32;
+                summary
+            })
+            .collect()
+    }
+
+    pub fn claims_for_entity(&self, tenant_id: &TenantId, entity: &str) -> Vec<Claim> {
         let mut out: Vec<Claim> = self
             .claim_ids_for_entity(tenant_id, entity)
             .iter()
-            .filter_map(|id| self.claims.get(id).cloned())
+            .filter_map(|id| self.claims.get(id.as_str()).cloned())
             .collect();
         out.sort_by(|a, b| a.claim_id.cmp(&b.claim_id));
         out
@@ -746,7 +1663,7 @@ impl InMemoryStore {
         let inverted_terms = self
             .inverted_index
             .values()
-            .map(|tenant_index| tenant_index.len())
+            .map(TenantTermIndex::term_count)
             .sum();
         let entity_terms = self
             .entity_index
@@ -776,7 +1693,7 @@ impl InMemoryStore {
 
     pub fn candidate_count(
         &self,
-        tenant_id: &str,
+        tenant_id: &TenantId,
         query: &str,
         from_unix: Option<i64>,
         to_unix: Option<i64>,
@@ -809,14 +1726,32 @@ impl InMemoryStore {
         req: &RetrievalRequest,
         query_vector: Option<&[f32]>,
         time_range: (Option<i64>, Option<i64>),
-        allowed_claim_ids: Option<&HashSet<String>>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+    ) -> usize {
+        self.candidate_count_with_ann_overrides(
+            req,
+            query_vector,
+            time_range,
+            allowed_claim_ids,
+            AnnSearchOverrides::default(),
+        )
+    }
+
+    /// Candidate count of the same query under [`InMemoryStore::retrieve_with`]
+    /// with these ANN overrides.
+    pub fn candidate_count_with_ann_overrides(
+        &self,
+        req: &RetrievalRequest,
+        query_vector: Option<&[f32]>,
+        time_range: (Option<i64>, Option<i64>),
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+        ann_overrides: AnnSearchOverrides,
     ) -> usize {
-        let (from_unix, to_unix) = time_range;
         self.candidate_claim_ids(
             &req.tenant_id,
             &req.query,
-            (from_unix, to_unix),
-            query_vector,
+            time_range,
+            query_vector.map(|vector| (vector, ann_overrides)),
             req.top_k,
             allowed_claim_ids,
         )
@@ -825,57 +1760,73 @@ impl InMemoryStore {
 
     pub fn ann_candidate_count_for_query_vector(
         &self,
-        tenant_id: &str,
+        tenant_id: &TenantId,
         query_vector: &[f32],
         top_k: usize,
     ) -> usize {
         if query_vector.is_empty() {
             return 0;
         }
-        let vector_top_n = (top_k.saturating_mul(20)).clamp(100, 5000);
-        self.vector_candidates(tenant_id, query_vector, vector_top_n)
-            .len()
+        self.vector_candidates(
+            tenant_id,
+            query_vector,
+            vector_candidate_pool(top_k),
+            AnnSearchOverrides::default(),
+        )
+        .len()
     }
 
     pub fn ann_vector_top_candidates(
         &self,
-        tenant_id: &str,
+        tenant_id: &TenantId,
         query_vector: &[f32],
         top_n: usize,
-    ) -> Vec<String> {
+    ) -> Vec<ClaimId> {
         if query_vector.is_empty() || top_n == 0 {
             return Vec::new();
         }
-        self.vector_candidates(tenant_id, query_vector, top_n)
+        self.vector_candidates(
+            tenant_id,
+            query_vector,
+            top_n,
+            AnnSearchOverrides::default(),
+        )
+        .into_iter()
+        .map(ClaimId::from)
+        .collect()
     }
 
     pub fn exact_vector_top_candidates(
         &self,
-        tenant_id: &str,
+        tenant_id: &TenantId,
         query_vector: &[f32],
         top_n: usize,
-    ) -> Vec<String> {
+    ) -> Vec<ClaimId> {
         if query_vector.is_empty() || top_n == 0 {
             return Vec::new();
         }
+        let query_vector = &*self.project_query_vector(tenant_id, query_vector);
 
-        let candidate_vectors: Vec<(String, &[f32])> = self
+        let vector_claim_ids: Vec<String> = self
             .claim_vectors
-            .iter()
-            .filter_map(|(claim_id, vector)| {
-                let claim = self.claims.get(claim_id)?;
-                if claim.tenant_id != tenant_id {
-                    return None;
-                }
-                Some((claim_id.clone(), vector.as_slice()))
+            .keys()
+            .filter(|claim_id| {
+                self.claims
+                    .get(claim_id.as_str())
+                    .is_some_and(|claim| claim.tenant_id == *tenant_id)
             })
+            .cloned()
             .collect();
-        let mut scored = self.score_query_candidate_vectors(query_vector, candidate_vectors);
-        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut scored = self.score_claim_vectors(
+            self.tenant_distance_metric(tenant_id),
+            query_vector,
+            vector_claim_ids,
+        );
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         scored
             .into_iter()
             .take(top_n)
-            .map(|(claim_id, _)| claim_id)
+            .map(|(claim_id, _)| claim_id.into())
             .collect()
     }
 
@@ -898,21 +1849,18 @@ impl InMemoryStore {
     }
 
     pub(crate) fn evidence_iter(&self) -> impl Iterator<Item = (&str, &Vec<Evidence>)> {
-        self.evidence_by_claim
-            .iter()
-            .map(|(k, v)| (k.as_str(), v))
+        self.evidence_by_claim.iter().map(|(k, v)| (k.as_str(), v))
     }
 
     pub(crate) fn edges_iter(&self) -> impl Iterator<Item = (&str, &Vec<ClaimEdge>)> {
-        self.edges_by_claim
-            .iter()
-            .map(|(k, v)| (k.as_str(), v))
+        self.edges_by_claim.iter().map(|(k, v)| (k.as_str(), v))
     }
 
-    pub(crate) fn claim_vectors_iter(&self) -> impl Iterator<Item = (&str, &Vec<f32>)> {
-        self.claim_vectors
-            .iter()
-            .map(|(k, v)| (k.as_str(), v))
+    pub(crate) fn claim_vectors_iter(&self) -> impl Iterator<Item = (&str, Cow<'_, [f32]>)> {
+        self.claim_vectors.keys().filter_map(|k| {
+            let v = self.claim_vectors.get(k)?;
+            Some((k.as_str(), v))
+        })
     }
 
     pub(crate) fn batch_commits_iter(&self) -> impl Iterator<Item = &BatchCommitMetadata> {
@@ -920,17 +1868,15 @@ impl InMemoryStore {
     }
 
     pub(crate) fn tenant_dims_iter(&self) -> impl Iterator<Item = (&str, &usize)> {
-        self.tenant_vector_dims
-            .iter()
-            .map(|(k, v)| (k.as_str(), v))
+        self.tenant_vector_dims.iter().map(|(k, v)| (k.as_str(), v))
     }
 
     pub(crate) fn tenant_claim_set_iter(&self) -> impl Iterator<Item = (String, String)> {
-        self.tenant_claim_ids
-            .iter()
-            .flat_map(|(tenant, claims)| {
-                claims.iter().map(move |claim| (tenant.clone(), claim.clone()))
-            })
+        self.tenant_claim_ids.iter().flat_map(|(tenant, claims)| {
+            claims
+                .iter()
+                .map(move |claim| (tenant.clone(), claim.clone()))
+        })
     }
 
     fn should_checkpoint(
@@ -954,55 +1900,116 @@ impl InMemoryStore {
         tenant_id: &str,
         query: &str,
         time_range: (Option<i64>, Option<i64>),
-        query_vector: Option<&[f32]>,
+        query_vector: Option<(&[f32], AnnSearchOverrides)>,
         top_k: usize,
-        allowed_claim_ids: Option<&HashSet<String>>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
     ) -> Vec<String> {
-        let (from_unix, to_unix) = time_range;
+        let windowed = time_range.0.is_some() || time_range.1.is_some();
+        let bm25_context = self.bm25_context_for_tenant(tenant_id, query);
+        self.candidate_claim_ids_with_lexical(
+            tenant_id,
+            self.lexical_candidates(tenant_id, &bm25_context, windowed),
+            time_range,
+            query_vector,
+            top_k,
+            allowed_claim_ids,
+        )
+    }
+
+    /// Claims of `tenant_id` matching the query analyzed into
+    /// `bm25_context`. A query with no posting falls back to every claim
+    /// of the tenant, unless the term bloom proves none of its tokens
+    /// occur there and the retrieval is not `windowed` to a time range;
+    /// a windowed retrieval then browses the claims in the window.
+    fn lexical_candidates(
+        &self,
+        tenant_id: &str,
+        bm25_context: &Bm25Context,
+        windowed: bool,
+    ) -> HashSet<String> {
         let mut candidates: HashSet<String> = HashSet::new();
-        let query_tokens = tokenize(query);
+        let query_tokens = &bm25_context.query_tokens;
 
         if query_tokens.is_empty() {
             if let Some(ids) = self.tenant_claim_ids.get(tenant_id) {
                 candidates.extend(ids.iter().cloned());
             }
         } else if let Some(tenant_index) = self.inverted_index.get(tenant_id) {
-            for token in query_tokens {
-                if let Some(ids) = tenant_index.get(&token) {
-                    candidates.extend(ids.iter().cloned());
+            if let Some((first, rest)) = bm25_context.phrases.split_first() {
+                // Phrases are required, so only claims matching all of
+                // them are lexical candidates.
+                let mut matched = tenant_index.phrase_claim_ids(first);
+                for phrase in rest {
+                    let next = tenant_index.phrase_claim_ids(phrase);
+                    matched.retain(|claim_id| next.contains(claim_id));
                 }
-            }
-            if candidates.is_empty()
-                && let Some(ids) = self.tenant_claim_ids.get(tenant_id)
+                candidates.extend(matched.into_iter().map(str::to_string));
+            } else if query_tokens
+                .iter()
+                .any(|token| tenant_index.may_contain(token))
+                || windowed
             {
-                candidates.extend(ids.iter().cloned());
+                for token in query_tokens {
+                    candidates.extend(
+                        tenant_index
+                            .postings(token)
+                            .map(|(claim_id, _)| claim_id.to_string()),
+                    );
+                }
+                if candidates.is_empty()
+                    && let Some(ids) = self.tenant_claim_ids.get(tenant_id)
+                {
+                    candidates.extend(ids.iter().cloned());
+                }
+            } else {
+                // No query token occurs in the tenant, so neither the
+                // posting lists nor the tenant scan can yield a lexical
+                // match.
+                self.metrics.record_term_bloom_skip();
             }
         }
+        candidates
+    }
 
-        if let Some(vector) = query_vector {
-            let vector_top_n = (top_k.saturating_mul(20)).clamp(100, 5000);
-            for claim_id in self.vector_candidates(tenant_id, vector, vector_top_n) {
+    /// `lexical` candidates plus the vector ones, narrowed to
+    /// `time_range`, `allowed_claim_ids` and the claims `tenant_id` owns.
+    fn candidate_claim_ids_with_lexical(
+        &self,
+        tenant_id: &str,
+        mut candidates: HashSet<String>,
+        (from_unix, to_unix): (Option<i64>, Option<i64>),
+        query_vector: Option<(&[f32], AnnSearchOverrides)>,
+        top_k: usize,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+    ) -> Vec<String> {
+        if let Some((vector, ann_overrides)) = query_vector {
+            let vector_top_n = vector_candidate_pool(top_k);
+            for claim_id in self.vector_candidates(tenant_id, vector, vector_top_n, ann_overrides) {
                 candidates.insert(claim_id);
             }
         }
 
         if from_unix.is_some() || to_unix.is_some() {
-            candidates.retain(|claim_id| {
-                self.claims
-                    .get(claim_id)
-                    .is_some_and(|claim| claim_matches_time_range(claim, from_unix, to_unix))
+            candidates.retain(|claim_id| match self.claims.get(claim_id.as_str()) {
+                Some(claim) => claim_matches_time_range(claim, from_unix, to_unix),
+                None => {
+                    self.note_stale_index_entry(tenant_id, claim_id);
+                    false
+                }
             });
         }
         if let Some(allowed_ids) = allowed_claim_ids {
-            candidates = candidates.intersection(allowed_ids).cloned().collect();
+            candidates.retain(|claim_id| allowed_ids.contains(claim_id.as_str()));
         }
 
         let mut out: Vec<String> = candidates
             .into_iter()
-            .filter(|claim_id| {
-                self.claims
-                    .get(claim_id)
-                    .is_some_and(|claim| claim.tenant_id == tenant_id)
+            .filter(|claim_id| match self.claims.get(claim_id.as_str()) {
+                Some(claim) => claim.tenant_id == tenant_id,
+                None => {
+                    self.note_stale_index_entry(tenant_id, claim_id);
+                    false
+                }
             })
             .collect();
         out.sort_unstable();
@@ -1014,38 +2021,42 @@ impl InMemoryStore {
         tenant_id: &str,
         query_vector: &[f32],
         top_n: usize,
+        ann_overrides: AnnSearchOverrides,
     ) -> Vec<String> {
         if query_vector.is_empty() {
             return Vec::new();
         }
+        let query_vector = &*self.project_query_vector(tenant_id, query_vector);
 
-        let mut scoped_ids = self.approximate_vector_candidate_ids(tenant_id, query_vector, top_n);
+        let mut scoped_ids =
+            self.approximate_vector_candidate_ids(tenant_id, query_vector, top_n, ann_overrides);
         if scoped_ids.is_empty() {
             scoped_ids = self
                 .claim_vectors
                 .keys()
                 .filter(|claim_id| {
                     self.claims
-                        .get(*claim_id)
+                        .get(claim_id.as_str())
                         .is_some_and(|claim| claim.tenant_id == tenant_id)
                 })
                 .cloned()
                 .collect();
         }
 
-        let candidate_vectors: Vec<(String, &[f32])> = scoped_ids
+        let vector_claim_ids: Vec<String> = scoped_ids
             .into_iter()
-            .filter_map(|claim_id| {
-                let vector = self.claim_vectors.get(&claim_id)?;
-                let claim = self.claims.get(&claim_id)?;
-                if claim.tenant_id != tenant_id {
-                    return None;
-                }
-                Some((claim_id, vector.as_slice()))
+            .filter(|claim_id| {
+                self.claims
+                    .get(claim_id.as_str())
+                    .is_some_and(|claim| claim.tenant_id == tenant_id)
             })
             .collect();
-        let mut scored = self.score_query_candidate_vectors(query_vector, candidate_vectors);
-        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut scored = self.score_claim_vectors(
+            self.tenant_distance_metric(tenant_id),
+            query_vector,
+            vector_claim_ids,
+        );
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         scored
             .into_iter()
             .take(top_n)
@@ -1053,8 +2064,54 @@ impl InMemoryStore {
             .collect()
     }
 
+    /// Score stored vectors against `query_vector`. Quantized storage is
+    /// scored (and re-scored) in [`ClaimVectorStore::score_quantized`];
+    /// full-precision storage goes through the batch path that may
+    /// offload to a [`VectorScorer`] or the GPU.
+    fn score_claim_vectors(
+        &self,
+        metric: DistanceMetric,
+        query_vector: &[f32],
+        claim_ids: Vec<String>,
+    ) -> Vec<(String, f32)> {
+        if self.claim_vectors.quantization().is_some() {
+            return self
+                .claim_vectors
+                .score_quantized(metric, query_vector, claim_ids);
+        }
+        // Vectors held as `f32` in memory go through the batch path;
+        // half-precision and mapped ones are widened one at a time, or
+        // widened into the batch when a scorer will take it.
+        let offload = self.vector_scorer.is_some();
+        let mut scored = Vec::new();
+        let mut widened: Vec<(String, Vec<f32>)> = Vec::new();
+        let mut candidate_vectors: Vec<(String, &[f32])> = Vec::new();
+        for claim_id in claim_ids {
+            if let Some(vector) = self.claim_vectors.float(&claim_id) {
+                candidate_vectors.push((claim_id, vector));
+            } else if offload {
+                if let Some(vector) = self.claim_vectors.get(&claim_id) {
+                    widened.push((claim_id, vector.into_owned()));
+                }
+            } else if let Some(score) =
+                self.claim_vectors
+                    .similarity(metric, query_vector, &claim_id)
+            {
+                scored.push((claim_id, score));
+            }
+        }
+        candidate_vectors.extend(
+            widened
+                .iter()
+                .map(|(claim_id, vector)| (claim_id.clone(), vector.as_slice())),
+        );
+        scored.extend(self.score_query_candidate_vectors(metric, query_vector, candidate_vectors));
+        scored
+    }
+
     fn score_query_candidate_vectors(
         &self,
+        metric: DistanceMetric,
         query_vector: &[f32],
         candidate_vectors: Vec<(String, &[f32])>,
     ) -> Vec<(String, f32)> {
@@ -1062,7 +2119,15 @@ impl InMemoryStore {
             return Vec::new();
         }
 
-        if self.vector_backend_runtime.is_gpu() {
+        if let Some(scorer) = &self.vector_scorer
+            && candidate_vectors.len() >= scorer.min_batch_size()
+            && let Some(scored) = scorer.score(metric, query_vector, &candidate_vectors)
+        {
+            return scored;
+        }
+
+        // The GPU shader only implements cosine.
+        if metric == DistanceMetric::Cosine && self.vector_backend_runtime.is_gpu() {
             #[cfg(feature = "gpu-backend")]
             if let Some(scored) =
                 gpu_score_query_candidate_vectors(query_vector, &candidate_vectors)
@@ -1071,7 +2136,7 @@ impl InMemoryStore {
             }
         }
 
-        score_query_candidate_vectors_cpu(query_vector, &candidate_vectors)
+        score_candidates_cpu(metric, query_vector, &candidate_vectors)
     }
 
     fn approximate_vector_candidate_ids(
@@ -1079,18 +2144,29 @@ impl InMemoryStore {
         tenant_id: &str,
         query_vector: &[f32],
         top_n: usize,
+        ann_overrides: AnnSearchOverrides,
     ) -> HashSet<String> {
         let mut out = HashSet::new();
+        if self.tenant_ann_tuning(tenant_id).index_kind != AnnIndexKind::Graph {
+            let Some(index) = self.vector_indexes.get(tenant_id) else {
+                return out;
+            };
+            let budget = self.ann_expansion_budget(tenant_id, top_n, ann_overrides);
+            let metric = self.tenant_distance_metric(tenant_id);
+            out.extend(index.candidates(metric, query_vector, budget));
+            self.metrics.record_ann_search(out.len());
+            return out;
+        }
         let Some(graph) = self.ann_vector_graphs.get(tenant_id) else {
             return out;
         };
+        let metric = self.tenant_distance_metric(tenant_id);
         let Some(entry_point) = graph.entry_point.as_ref() else {
             return out;
         };
-        let Some(mut current_score) = self
-            .claim_vectors
-            .get(entry_point)
-            .and_then(|entry_vector| cosine_similarity(query_vector, entry_vector))
+        let Some(mut current_score) =
+            self.claim_vectors
+                .similarity(metric, query_vector, entry_point)
         else {
             return out;
         };
@@ -1106,10 +2182,7 @@ impl InMemoryStore {
                 for neighbor_id in neighbors {
                     let Some(score) =
                         self.claim_vectors
-                            .get(neighbor_id)
-                            .and_then(|neighbor_vector| {
-                                cosine_similarity(query_vector, neighbor_vector)
-                            })
+                            .similarity(metric, query_vector, neighbor_id)
                     else {
                         continue;
                     };
@@ -1136,8 +2209,7 @@ impl InMemoryStore {
         if visited.insert(entry_point.clone())
             && let Some(score) = self
                 .claim_vectors
-                .get(entry_point)
-                .and_then(|entry_vector| cosine_similarity(query_vector, entry_vector))
+                .similarity(metric, query_vector, entry_point)
         {
             frontier.push(ScoredNode {
                 claim_id: entry_point.clone(),
@@ -1145,14 +2217,7 @@ impl InMemoryStore {
             });
         }
 
-        let expansion_budget = top_n
-            .saturating_mul(self.ann_tuning.search_expansion_factor.max(1))
-            .clamp(
-                self.ann_tuning.search_expansion_min.max(1),
-                self.ann_tuning
-                    .search_expansion_max
-                    .max(self.ann_tuning.search_expansion_min.max(1)),
-            );
+        let expansion_budget = self.ann_expansion_budget(tenant_id, top_n, ann_overrides);
         let mut expanded = 0usize;
 
         while let Some(node) = frontier.pop() {
@@ -1169,10 +2234,10 @@ impl InMemoryStore {
                 if !visited.insert(neighbor_id.clone()) {
                     continue;
                 }
-                let Some(neighbor_vector) = self.claim_vectors.get(neighbor_id) else {
-                    continue;
-                };
-                let Some(score) = cosine_similarity(query_vector, neighbor_vector) else {
+                let Some(score) = self
+                    .claim_vectors
+                    .similarity(metric, query_vector, neighbor_id)
+                else {
                     continue;
                 };
                 frontier.push(ScoredNode {
@@ -1182,68 +2247,141 @@ impl InMemoryStore {
             }
         }
 
+        self.metrics.record_ann_search(expanded);
         out
     }
 
-    fn bm25_context_for_tenant(&self, tenant_id: &str, query: &str) -> Bm25Context {
-        let total_docs = self
-            .tenant_claim_ids
-            .get(tenant_id)
-            .map(|ids| ids.len())
-            .unwrap_or(0);
-        if total_docs == 0 {
-            return Bm25Context::default();
-        }
-
-        let mut total_len = 0usize;
-        for claim_id in self.tenant_claim_ids.get(tenant_id).into_iter().flatten() {
-            total_len += self
-                .claim_tokens
-                .get(claim_id)
-                .map(|tokens| tokens.len())
-                .unwrap_or(0);
+    fn ann_expansion_budget(
+        &self,
+        tenant_id: &str,
+        top_n: usize,
+        ann_overrides: AnnSearchOverrides,
+    ) -> usize {
+        if let Some(budget) = ann_overrides.expansion_budget {
+            return budget.max(1);
         }
-        let avg_doc_len = (total_len as f32 / total_docs as f32).max(1.0);
+        let tuning = self.tenant_ann_tuning(tenant_id);
+        top_n
+            .saturating_mul(tuning.search_expansion_factor.max(1))
+            .clamp(
+                tuning.search_expansion_min.max(1),
+                tuning
+                    .search_expansion_max
+                    .max(tuning.search_expansion_min.max(1)),
+            )
+    }
 
-        let mut doc_freq = HashMap::new();
-        if let Some(index) = self.inverted_index.get(tenant_id) {
-            for token in tokenize(query) {
-                doc_freq.insert(
-                    token.clone(),
-                    index.get(&token).map(|ids| ids.len()).unwrap_or(0),
-                );
-            }
+    fn bm25_context_for_tenant(&self, tenant_id: &str, query: &str) -> Bm25Context {
+        let analyzer = self.tenant_text_analyzer(tenant_id);
+        let query_tokens = analyzer.analyze(query);
+        let phrases = parse_analyzed_phrase_queries(query, &analyzer);
+        let Some(index) = self
+            .inverted_index
+            .get(tenant_id)
+            .filter(|index| !index.is_empty())
+        else {
+            return Bm25Context {
+                query_tokens,
+                phrases,
+                ..Bm25Context::default()
+            };
+        };
+        if let Some(global) = self.global_term_stats.get(tenant_id) {
+            let doc_freq = query_tokens
+                .iter()
+                .map(|token| {
+                    (
+                        token.clone(),
+                        global.doc_freq_or(token, index.doc_freq(token)),
+                    )
+                })
+                .collect();
+            return Bm25Context {
+                doc_freq,
+                total_docs: global.doc_count.max(index.doc_count() as u64) as usize,
+                avg_doc_len: global.avg_doc_len(),
+                query_tokens,
+                phrases,
+            };
         }
-
+        let doc_freq = query_tokens
+            .iter()
+            .map(|token| (token.clone(), index.doc_freq(token)))
+            .collect();
         Bm25Context {
             doc_freq,
-            total_docs,
-            avg_doc_len,
+            total_docs: index.doc_count(),
+            avg_doc_len: index.avg_doc_len(),
+            query_tokens,
+            phrases,
         }
     }
 
     fn snapshot_records(&self) -> Vec<PersistedRecord> {
-        let mut claim_ids: Vec<String> = self.claims.keys().cloned().collect();
+        let mut claim_ids: Vec<ClaimId> = self.claims.keys().cloned().collect();
         claim_ids.sort_unstable();
 
         let mut records = Vec::new();
+        let mut config_tenants: Vec<&String> = self.tenant_vector_configs.keys().collect();
+        config_tenants.sort_unstable();
+        for tenant_id in config_tenants {
+            records.push(PersistedRecord::TenantVectorConfig(
+                TenantVectorConfigRecord {
+                    tenant_id: tenant_id.clone(),
+                    config: self.tenant_vector_configs[tenant_id].clone(),
+                },
+            ));
+        }
+        let mut analyzed_tenants: Vec<&String> = self.text_analyzers.keys().collect();
+        analyzed_tenants.sort_unstable();
+        for tenant_id in analyzed_tenants {
+            records.push(PersistedRecord::TextAnalyzer(TextAnalyzerRecord {
+                tenant_id: tenant_id.clone(),
+                analyzer: self.text_analyzers[tenant_id],
+            }));
+        }
+        let mut projected_tenants: Vec<&String> = self.vector_projections.keys().collect();
+        projected_tenants.sort_unstable();
+        for tenant_id in projected_tenants {
+            records.push(PersistedRecord::VectorProjection(VectorProjectionRecord {
+                tenant_id: tenant_id.clone(),
+                projection: self.vector_projections[tenant_id].clone(),
+            }));
+        }
+        self.push_ann_graph_records(&mut records);
+        self.push_document_records(&mut records);
+        self.push_source_records(&mut records);
         for claim_id in &claim_ids {
-            if let Some(claim) = self.claims.get(claim_id) {
+            if let Some(claim) = self.claims.get(claim_id.as_str()) {
                 records.push(PersistedRecord::Claim(claim.clone()));
             }
         }
+        for claim_id in &claim_ids {
+            if let Some(claim) = self.claims.get(claim_id.as_str())
+                && self.is_claim_archived(&claim.tenant_id, claim_id)
+            {
+                records.push(PersistedRecord::ClaimArchive(ClaimArchiveRecord {
+                    tenant_id: claim.tenant_id.to_string(),
+                    claim_id: claim_id.to_string(),
+                    archived: true,
+                }));
+            }
+        }
 
         for claim_id in &claim_ids {
             if let Some(values) = self.claim_vectors.get(claim_id) {
                 records.push(PersistedRecord::ClaimVector(ClaimVectorRecord {
-                    claim_id: claim_id.clone(),
-                    values: values.clone(),
+                    claim_id: claim_id.to_string(),
+                    values: values.into_owned(),
+                    space: None,
                 }));
             }
         }
+        self.push_named_vector_records(&mut records);
+        self.push_sparse_vector_records(&claim_ids, &mut records);
 
         for claim_id in &claim_ids {
-            if let Some(evidence) = self.evidence_by_claim.get(claim_id) {
+            if let Some(evidence) = self.evidence_by_claim.get(claim_id.as_str()) {
                 let mut evidence = evidence.clone();
                 evidence.sort_by(|a, b| a.evidence_id.cmp(&b.evidence_id));
                 for evd in evidence {
@@ -1253,7 +2391,7 @@ impl InMemoryStore {
         }
 
         for claim_id in &claim_ids {
-            if let Some(edges) = self.edges_by_claim.get(claim_id) {
+            if let Some(edges) = self.edges_by_claim.get(claim_id.as_str()) {
                 let mut edges = edges.clone();
                 edges.sort_by(|a, b| a.edge_id.cmp(&b.edge_id));
                 for edge in edges {
@@ -1280,14 +2418,57 @@ impl InMemoryStore {
         records
     }
 
-    fn validate_bundle(
-        &self,
-        claim: &Claim,
-        evidence: &[Evidence],
-        edges: &[ClaimEdge],
-    ) -> Result<(), StoreError> {
-        validate_claim(claim)?;
-        if let Some(existing) = self.claims.get(&claim.claim_id)
+    /// Serialize every tenant graph ahead of the claim vectors so replay
+    /// can restore it instead of re-linking each vector.
+    fn push_ann_graph_records(&self, records: &mut Vec<PersistedRecord>) {
+        let mut tenants: Vec<&String> = self.ann_vector_graphs.keys().collect();
+        tenants.sort_unstable();
+        for tenant_id in tenants {
+            let graph = &self.ann_vector_graphs[tenant_id];
+            let tuning = self.tenant_ann_tuning(tenant_id);
+            if tuning.index_kind != AnnIndexKind::Graph || graph.node_levels.is_empty() {
+                continue;
+            }
+            records.push(PersistedRecord::AnnGraphHeader(AnnGraphHeaderRecord {
+                version: ANN_GRAPH_SNAPSHOT_VERSION,
+                tenant_id: tenant_id.clone(),
+                levels: ANN_GRAPH_LEVELS,
+                max_neighbors_base: tuning.max_neighbors_base,
+                max_neighbors_upper: tuning.max_neighbors_upper,
+                entry_point: graph.entry_point.clone(),
+                entry_level: graph.entry_level,
+            }));
+            let mut node_ids: Vec<&String> = graph.node_levels.keys().collect();
+            node_ids.sort_unstable();
+            for claim_id in node_ids {
+                let node_level = graph.node_levels[claim_id];
+                records.push(PersistedRecord::AnnGraphNode(AnnGraphNodeRecord {
+                    tenant_id: tenant_id.clone(),
+                    claim_id: claim_id.clone(),
+                    node_level,
+                    neighbors: (0..=node_level)
+                        .map(|level| {
+                            graph.levels[level]
+                                .get(claim_id)
+                                .cloned()
+                                .unwrap_or_default()
+                        })
+                        .collect(),
+                }));
+            }
+        }
+    }
+
+    fn validate_bundle(
+        &self,
+        claim: &Claim,
+        evidence: &[Evidence],
+        edges: &[ClaimEdge],
+    ) -> Result<(), StoreError> {
+        validate_claim_with(claim, &self.validation_config)?;
+        self.validation_config
+            .check_evidence_count(evidence.len())?;
+        if let Some(existing) = self.claims.get(claim.claim_id.as_str())
             && existing.tenant_id != claim.tenant_id
         {
             return Err(StoreError::Conflict(format!(
@@ -1298,13 +2479,13 @@ impl InMemoryStore {
         for evd in evidence {
             validate_evidence(evd)?;
             if evd.claim_id != claim.claim_id {
-                return Err(StoreError::MissingClaim(evd.claim_id.clone()));
+                return Err(StoreError::MissingClaim(evd.claim_id.to_string()));
             }
         }
         for edge in edges {
             validate_edge(edge)?;
             if edge.from_claim_id != claim.claim_id {
-                return Err(StoreError::MissingClaim(edge.from_claim_id.clone()));
+                return Err(StoreError::MissingClaim(edge.from_claim_id.to_string()));
             }
         }
         Ok(())
@@ -1316,12 +2497,18 @@ impl InMemoryStore {
         evidence: Vec<Evidence>,
         edges: Vec<ClaimEdge>,
     ) -> Result<(), StoreError> {
+        self.apply_read_repairs();
+        let tenant_id = claim.tenant_id.clone();
         self.apply_claim(claim)?;
+        self.note_tenant_ingest(&tenant_id);
+        self.metrics.record_claim_ingested();
         for evd in evidence {
             self.apply_evidence(evd)?;
+            self.metrics.record_evidence_ingested();
         }
         for edge in edges {
             self.apply_edge(edge)?;
+            self.metrics.record_edge_ingested();
         }
         Ok(())
     }
@@ -1331,20 +2518,177 @@ impl InMemoryStore {
             PersistedRecord::Claim(claim) => self.apply_claim(claim),
             PersistedRecord::Evidence(evidence) => self.apply_evidence(evidence),
             PersistedRecord::Edge(edge) => self.apply_edge(edge),
-            PersistedRecord::ClaimVector(record) => {
-                self.apply_claim_vector(&record.claim_id, record.values)
-            }
+            PersistedRecord::ClaimVector(record) => match record.space {
+                Some(space) => {
+                    self.apply_named_claim_vector(&record.claim_id, &space, record.values)
+                }
+                None => self.apply_claim_vector(&record.claim_id, record.values),
+            },
             PersistedRecord::BatchCommit(record) => self.apply_batch_commit_record(record),
+            PersistedRecord::TenantVectorConfig(record) => {
+                self.install_tenant_vector_config(&record.tenant_id, record.config);
+                Ok(())
+            }
+            PersistedRecord::AnnGraphHeader(record) => {
+                self.apply_ann_graph_header(record);
+                Ok(())
+            }
+            PersistedRecord::AnnGraphNode(record) => {
+                self.apply_ann_graph_node(record);
+                Ok(())
+            }
+            PersistedRecord::VectorProjection(record) => self
+                .install_vector_projection(&record.tenant_id, record.projection)
+                .map(|_| ()),
+            PersistedRecord::SparseVector(record) => {
+                self.apply_claim_sparse_vector(&record.claim_id, record.vector)
+            }
+            PersistedRecord::TextAnalyzer(record) => {
+                self.install_text_analyzer(&record.tenant_id, record.analyzer);
+                Ok(())
+            }
+            PersistedRecord::ClaimDelete(record) => self
+                .apply_claim_delete(&record.tenant_id, &record.claim_id)
+                .map(|_| ()),
+            PersistedRecord::ClaimMerge(record) => self
+                .apply_claim_merge(
+                    &record.tenant_id,
+                    &record.primary_claim_id,
+                    &record.duplicate_claim_ids,
+                )
+                .map(|_| ()),
+            PersistedRecord::ClaimArchive(record) => {
+                self.apply_claim_archive(&record.tenant_id, &record.claim_id, record.archived);
+                Ok(())
+            }
+            PersistedRecord::Document(document) => {
+                self.apply_document(document);
+                Ok(())
+            }
+            PersistedRecord::Chunk(chunk) => self.apply_chunk(chunk).map(|_| ()),
+            PersistedRecord::Source(source) => {
+                self.apply_source(source);
+                Ok(())
+            }
+            PersistedRecord::Checkpoint(_) => Ok(()),
+        }
+    }
+
+    /// Start restoring a tenant's graph from a snapshot. Headers that do
+    /// not match this store's graph geometry are ignored, which leaves
+    /// the graph to be rebuilt as the tenant's vectors are replayed.
+    fn apply_ann_graph_header(&mut self, record: AnnGraphHeaderRecord) {
+        let tuning = self.tenant_ann_tuning(&record.tenant_id);
+        let compatible = record.version == ANN_GRAPH_SNAPSHOT_VERSION
+            && record.levels == ANN_GRAPH_LEVELS
+            && record.entry_level < ANN_GRAPH_LEVELS
+            && record.max_neighbors_base == tuning.max_neighbors_base
+            && record.max_neighbors_upper == tuning.max_neighbors_upper
+            && tuning.index_kind == AnnIndexKind::Graph;
+        let already_indexed = self
+            .ann_vector_graphs
+            .get(&record.tenant_id)
+            .is_some_and(|graph| !graph.node_levels.is_empty());
+        if !compatible || already_indexed {
+            return;
+        }
+        self.restoring_ann_graphs.insert(record.tenant_id.clone());
+        self.ann_vector_graphs.insert(
+            record.tenant_id,
+            TenantAnnGraph {
+                entry_point: record.entry_point,
+                entry_level: record.entry_level,
+                ..TenantAnnGraph::default()
+            },
+        );
+    }
+
+    /// Restore one node into a graph opened by an accepted header. Its
+    /// vector, replayed later, is then stored without re-linking it.
+    fn apply_ann_graph_node(&mut self, record: AnnGraphNodeRecord) {
+        if record.node_level >= ANN_GRAPH_LEVELS
+            || !self.restoring_ann_graphs.contains(&record.tenant_id)
+            || self.claim_vectors.contains_key(&record.claim_id)
+        {
+            return;
+        }
+        let Some(graph) = self.ann_vector_graphs.get_mut(&record.tenant_id) else {
+            return;
+        };
+        graph
+            .node_levels
+            .insert(record.claim_id.clone(), record.node_level);
+        for (level, neighbors) in record.neighbors.into_iter().enumerate() {
+            graph.levels[level].insert(record.claim_id.clone(), neighbors);
+        }
+    }
+
+    /// Drop restored graph nodes whose vector never arrived (and links
+    /// to them), and graphs for tenants that no longer use one.
+    fn finish_ann_graph_restore(&mut self) {
+        self.restoring_ann_graphs.clear();
+        let mut tenants: Vec<String> = self.ann_vector_graphs.keys().cloned().collect();
+        tenants.sort_unstable();
+        for tenant_id in tenants {
+            if self.tenant_ann_tuning(&tenant_id).index_kind != AnnIndexKind::Graph {
+                self.ann_vector_graphs.remove(&tenant_id);
+                continue;
+            }
+            let mut orphaned: Vec<String> = self.ann_vector_graphs[&tenant_id]
+                .node_levels
+                .keys()
+                .filter(|claim_id| !self.claim_vectors.contains_key(claim_id.as_str()))
+                .cloned()
+                .collect();
+            orphaned.sort_unstable();
+            for claim_id in orphaned {
+                self.remove_vector_index_entry(&tenant_id, &claim_id);
+            }
+            if let Some(graph) = self.ann_vector_graphs.get_mut(&tenant_id) {
+                let node_levels = &graph.node_levels;
+                for (level, nodes) in graph.levels.iter_mut().enumerate() {
+                    nodes.retain(|claim_id, _| {
+                        node_levels.get(claim_id).is_some_and(|node| *node >= level)
+                    });
+                    for neighbors in nodes.values_mut() {
+                        neighbors.retain(|neighbor| {
+                            node_levels.get(neighbor).is_some_and(|node| *node >= level)
+                        });
+                    }
+                }
+                if graph
+                    .entry_point
+                    .as_ref()
+                    .is_none_or(|entry| !graph.node_levels.contains_key(entry))
+                {
+                    match graph
+                        .node_levels
+                        .iter()
+                        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                    {
+                        Some((entry, level)) => {
+                            graph.entry_point = Some(entry.clone());
+                            graph.entry_level = *level;
+                        }
+                        None => {
+                            graph.entry_point = None;
+                            graph.entry_level = 0;
+                        }
+                    }
+                }
+            }
         }
+        self.ann_vector_graphs
+            .retain(|_, graph| !graph.node_levels.is_empty());
     }
 
     fn apply_claim(&mut self, claim: Claim) -> Result<(), StoreError> {
         // Write to disk BEFORE mutating in-memory state. If the disk
         // write fails, the in-memory state is unchanged.
         if let Some(disk) = self.disk.as_ref() {
-            disk.put_claim(&claim).map_err(StoreError::Io)?;
+            disk.put_claim(&claim).map_err(disk_error)?;
             disk.add_claim_to_tenant(&claim.tenant_id, &claim.claim_id)
-                .map_err(StoreError::Io)?;
+                .map_err(disk_error)?;
         }
         self.apply_claim_inner(claim)
     }
@@ -1359,7 +2703,7 @@ impl InMemoryStore {
     fn apply_claim_inner(&mut self, claim: Claim) -> Result<(), StoreError> {
         validate_claim(&claim)?;
         let claim_id = claim.claim_id.clone();
-        if let Some(previous) = self.claims.get(&claim_id).cloned() {
+        if let Some(previous) = self.claims.get(claim_id.as_str()).cloned() {
             if previous.tenant_id != claim.tenant_id {
                 return Err(StoreError::Conflict(format!(
                     "claim_id '{}' already exists for tenant '{}'",
@@ -1369,8 +2713,12 @@ impl InMemoryStore {
             self.remove_claim_indexes(&previous);
         }
         self.add_claim_indexes(&claim);
+        self.change_feed
+            .publish_with(|| ChangeRecord::Claim(claim.clone()));
+        let tenant_id = claim.tenant_id.clone();
         self.claims.insert(claim_id.clone(), claim);
-        self.wal.push(WalEvent::ClaimUpsert(claim_id));
+        self.evaluate_standing_queries(&tenant_id, &claim_id);
+        self.wal.push(WalEvent::ClaimUpsert(claim_id.into()));
         Ok(())
     }
 
@@ -1383,11 +2731,11 @@ impl InMemoryStore {
             // append + replace keeps the on-disk state consistent.
             let mut current: Vec<Evidence> = disk
                 .get_evidence_blob(&evidence.claim_id)
-                .map_err(StoreError::Io)?
+                .map_err(disk_error)?
                 .unwrap_or_default();
             current.push(evidence.clone());
             disk.put_evidence_blob(&evidence.claim_id, &current)
-                .map_err(StoreError::Io)?;
+                .map_err(disk_error)?;
         }
         self.apply_evidence_inner(evidence)
     }
@@ -1399,8 +2747,12 @@ impl InMemoryStore {
         claim_id: &str,
         evidence: &[Evidence],
     ) -> Result<(), StoreError> {
-        if !self.claims.contains_key(claim_id) {
+        let Some(claim) = self.claims.get(claim_id) else {
             return Err(StoreError::MissingClaim(claim_id.to_string()));
+        };
+        let tenant_id = claim.tenant_id.clone();
+        for evd in evidence {
+            self.index_evidence_source(&tenant_id, evd);
         }
         let entry = self
             .evidence_by_claim
@@ -1414,15 +2766,20 @@ impl InMemoryStore {
 
     fn apply_evidence_inner(&mut self, evidence: Evidence) -> Result<(), StoreError> {
         validate_evidence(&evidence)?;
-        if !self.claims.contains_key(&evidence.claim_id) {
-            return Err(StoreError::MissingClaim(evidence.claim_id));
-        }
+        let Some(claim) = self.claims.get(evidence.claim_id.as_str()) else {
+            return Err(StoreError::MissingClaim(evidence.claim_id.into()));
+        };
+        let tenant_id = claim.tenant_id.clone();
+        self.bump_index_epoch(&tenant_id);
+        self.index_evidence_source(&tenant_id, &evidence);
         self.evidence_by_claim
-            .entry(evidence.claim_id.clone())
+            .entry(evidence.claim_id.to_string())
             .or_default()
             .push(evidence.clone());
+        self.change_feed
+            .publish_with(|| ChangeRecord::Evidence(evidence.clone()));
         self.wal
-            .push(WalEvent::EvidenceUpsert(evidence.evidence_id));
+            .push(WalEvent::EvidenceUpsert(evidence.evidence_id.into()));
         Ok(())
     }
 
@@ -1431,11 +2788,11 @@ impl InMemoryStore {
         if let Some(disk) = self.disk.as_ref() {
             let mut current: Vec<ClaimEdge> = disk
                 .get_edge_blob(&edge.from_claim_id)
-                .map_err(StoreError::Io)?
+                .map_err(disk_error)?
                 .unwrap_or_default();
             current.push(edge.clone());
             disk.put_edge_blob(&edge.from_claim_id, &current)
-                .map_err(StoreError::Io)?;
+                .map_err(disk_error)?;
         }
         self.apply_edge_inner(edge)
     }
@@ -1450,23 +2807,30 @@ impl InMemoryStore {
         if !self.claims.contains_key(from) {
             return Err(StoreError::MissingClaim(from.to_string()));
         }
-        let entry = self.edges_by_claim.entry(from.to_string()).or_default();
         for edge in edges {
-            entry.push(edge.clone());
+            self.index_incoming_edge(edge);
         }
+        self.edges_by_claim
+            .entry(from.to_string())
+            .or_default()
+            .extend_from_slice(edges);
         Ok(())
     }
 
     fn apply_edge_inner(&mut self, edge: ClaimEdge) -> Result<(), StoreError> {
         validate_edge(&edge)?;
-        if !self.claims.contains_key(&edge.from_claim_id) {
-            return Err(StoreError::MissingClaim(edge.from_claim_id));
-        }
+        let Some(claim) = self.claims.get(edge.from_claim_id.as_str()) else {
+            return Err(StoreError::MissingClaim(edge.from_claim_id.into()));
+        };
+        self.bump_index_epoch(&claim.tenant_id.clone());
+        self.index_incoming_edge(&edge);
         self.edges_by_claim
-            .entry(edge.from_claim_id.clone())
+            .entry(edge.from_claim_id.to_string())
             .or_default()
             .push(edge.clone());
-        self.wal.push(WalEvent::EdgeUpsert(edge.edge_id));
+        self.change_feed
+            .publish_with(|| ChangeRecord::Edge(edge.clone()));
+        self.wal.push(WalEvent::EdgeUpsert(edge.edge_id.into()));
         Ok(())
     }
 
@@ -1478,23 +2842,16 @@ impl InMemoryStore {
             .get(claim_id)
             .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string()))?;
         let tenant_id = claim.tenant_id.clone();
-        let new_dim_needed = match self.tenant_vector_dims.get(&tenant_id) {
-            Some(existing_dim) if *existing_dim != vector.len() => {
-                return Err(StoreError::InvalidVector(format!(
-                    "vector dimension mismatch for tenant '{}': expected {}, got {}",
-                    tenant_id, existing_dim, vector.len()
-                )));
-            }
-            None => Some(vector.len()),
-            _ => None,
-        };
+        let vector = self.project_incoming_vector(&tenant_id, vector);
+        self.check_tenant_vector_dimension(&tenant_id, vector.len())?;
+        let new_dim_needed =
+            (!self.tenant_vector_dims.contains_key(tenant_id.as_str())).then_some(vector.len());
 
         // Write to disk BEFORE mutating in-memory state.
         if let Some(disk) = self.disk.as_ref() {
-            disk.put_vector(claim_id, &vector).map_err(StoreError::Io)?;
+            disk.put_vector(claim_id, &vector).map_err(disk_error)?;
             if let Some(dim) = new_dim_needed {
-                disk.put_tenant_dim(&tenant_id, dim)
-                    .map_err(StoreError::Io)?;
+                disk.put_tenant_dim(&tenant_id, dim).map_err(disk_error)?;
             }
         }
         self.apply_claim_vector_inner(claim_id, vector)
@@ -1521,32 +2878,36 @@ impl InMemoryStore {
             .get(claim_id)
             .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string()))?;
         let tenant_id = claim.tenant_id.clone();
-        match self.tenant_vector_dims.get(&tenant_id) {
-            Some(existing_dim) if *existing_dim != vector.len() => {
-                return Err(StoreError::InvalidVector(format!(
-                    "vector dimension mismatch for tenant '{}': expected {}, got {}",
-                    tenant_id,
-                    existing_dim,
-                    vector.len()
-                )));
-            }
-            None => {
-                self.tenant_vector_dims
-                    .insert(tenant_id.clone(), vector.len());
-            }
-            _ => {}
-        }
+        self.check_tenant_vector_dimension(&tenant_id, vector.len())?;
+        self.tenant_vector_dims
+            .entry(tenant_id.to_string())
+            .or_insert(vector.len());
 
+        // A node restored from a snapshot graph is already linked; only
+        // its vector is missing.
+        let restored_node = !self.claim_vectors.contains_key(claim_id)
+            && self
+                .ann_vector_graphs
+                .get(tenant_id.as_str())
+                .is_some_and(|graph| graph.node_levels.contains_key(claim_id));
         if self.claim_vectors.contains_key(claim_id) {
             self.remove_vector_index_entry(&tenant_id, claim_id);
         }
 
-        self.claim_vectors.insert(claim_id.to_string(), vector);
-        let stored_vector =
-            self.claim_vectors.get(claim_id).cloned().ok_or_else(|| {
-                StoreError::InvalidVector("failed to store claim vector".to_string())
-            })?;
-        self.add_vector_index_entry(&tenant_id, claim_id, &stored_vector);
+        let stored_vector = vector.clone();
+        self.claim_vectors
+            .insert(&tenant_id, claim_id.to_string(), vector)?;
+        if self.claim_vectors.tenant_over_budget(&tenant_id) {
+            let claim_ids = self.tenant_vector_claim_ids(&tenant_id);
+            self.claim_vectors.map_tenant(&tenant_id, &claim_ids)?;
+        }
+        if !restored_node {
+            self.add_vector_index_entry(&tenant_id, claim_id, &stored_vector);
+        }
+        self.change_feed.publish_with(|| ChangeRecord::ClaimVector {
+            claim_id: claim_id.into(),
+            values: stored_vector,
+        });
         self.wal
             .push(WalEvent::ClaimVectorUpsert(claim_id.to_string()));
         Ok(())
@@ -1576,13 +2937,91 @@ impl InMemoryStore {
             payload_fingerprint: payload_fingerprint.clone(),
         };
         if let Some(disk) = self.disk.as_ref() {
-            disk.put_batch_commit(&metadata).map_err(StoreError::Io)?;
+            disk.put_batch_commit(&metadata).map_err(disk_error)?;
         }
-        self.batch_commits.insert(record.commit_id.clone(), metadata);
+        self.change_feed
+            .publish_with(|| ChangeRecord::BatchCommit(metadata.clone()));
+        self.batch_commits
+            .insert(record.commit_id.clone(), metadata);
         self.wal.push(WalEvent::BatchCommit(record.commit_id));
         Ok(())
     }
 
+    /// A registered config pins the dimension; otherwise the dimension of
+    /// the tenant's first vector does.
+    fn check_tenant_vector_dimension(
+        &self,
+        tenant_id: &str,
+        dimension: usize,
+    ) -> Result<(), StoreError> {
+        // Projected vectors are checked against the projection instead:
+        // the registered dimension is the one clients send.
+        if let Some(projection) = self.vector_projections.get(tenant_id) {
+            if dimension != projection.output_dimension() {
+                return Err(StoreError::InvalidVector(format!(
+                    "vector dimension mismatch for tenant '{}': projection expects {}, got {}",
+                    tenant_id,
+                    projection.input_dimension(),
+                    dimension
+                )));
+            }
+            return Ok(());
+        }
+        if let Some(config) = self.tenant_vector_configs.get(tenant_id)
+            && config.dimension != dimension
+        {
+            return Err(StoreError::InvalidVector(format!(
+                "vector dimension mismatch for tenant '{}': registered config declares {}, got {}",
+                tenant_id, config.dimension, dimension
+            )));
+        }
+        if let Some(existing_dim) = self.tenant_vector_dims.get(tenant_id)
+            && *existing_dim != dimension
+        {
+            return Err(StoreError::InvalidVector(format!(
+                "vector dimension mismatch for tenant '{}': expected {}, got {}",
+                tenant_id, existing_dim, dimension
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_tenant_vector_config(
+        &self,
+        tenant_id: &str,
+        config: &TenantVectorConfig,
+    ) -> Result<(), StoreError> {
+        if config.dimension == 0 {
+            return Err(StoreError::InvalidVector(format!(
+                "vector config for tenant '{}' must declare a non-zero dimension",
+                tenant_id
+            )));
+        }
+        let existing_dim = match self.vector_projections.get(tenant_id) {
+            Some(projection) => Some(projection.input_dimension()),
+            None => self.tenant_vector_dims.get(tenant_id).copied(),
+        };
+        if let Some(existing_dim) = existing_dim
+            && existing_dim != config.dimension
+        {
+            return Err(StoreError::Conflict(format!(
+                "tenant '{}' already stores {}-dimensional vectors; cannot register dimension {}",
+                tenant_id, existing_dim, config.dimension
+            )));
+        }
+        if self.tenant_vector_dims.contains_key(tenant_id)
+            && self.tenant_distance_metric(tenant_id) != config.metric
+        {
+            return Err(StoreError::Conflict(format!(
+                "tenant '{}' already has vectors indexed with the {} metric; cannot switch to {}",
+                tenant_id,
+                self.tenant_distance_metric(tenant_id).as_str(),
+                config.metric.as_str()
+            )));
+        }
+        Ok(())
+    }
+
     /// Apply a batch-commit metadata to the in-memory state. No
     /// disk mirror. Used by the bulk-load path.
     pub(crate) fn apply_batch_commit_for_load(
@@ -1619,7 +3058,29 @@ impl InMemoryStore {
     }
 
     fn add_vector_index_entry(&mut self, tenant_id: &str, claim_id: &str, vector: &[f32]) {
-        let node_level = self.assign_ann_level(claim_id);
+        self.bump_index_epoch(tenant_id);
+        // Below the exact-search threshold a tenant has no index; the
+        // vector that reaches it builds one over all of them.
+        let threshold = self.tenant_ann_tuning(tenant_id).exact_search_threshold;
+        if threshold > 0
+            && !self.ann_vector_graphs.contains_key(tenant_id)
+            && !self.vector_indexes.contains_key(tenant_id)
+        {
+            if self.tenant_vector_claim_ids(tenant_id).len() >= threshold {
+                self.rebuild_tenant_vector_index(tenant_id);
+            }
+            return;
+        }
+        self.index_vector_entry(tenant_id, claim_id, vector);
+    }
+
+    fn index_vector_entry(&mut self, tenant_id: &str, claim_id: &str, vector: &[f32]) {
+        let kind = self.tenant_ann_tuning(tenant_id).index_kind;
+        if kind != AnnIndexKind::Graph {
+            self.add_trained_index_entry(tenant_id, claim_id, vector, kind);
+            return;
+        }
+        let node_level = self.assign_ann_level(tenant_id, claim_id);
         {
             let graph = self
                 .ann_vector_graphs
@@ -1636,7 +3097,7 @@ impl InMemoryStore {
         }
 
         for level in (0..=node_level).rev() {
-            let max_neighbors = self.ann_level_max_neighbors(level);
+            let max_neighbors = self.ann_level_max_neighbors(tenant_id, level);
             let neighbor_ids =
                 self.select_ann_neighbors(tenant_id, claim_id, vector, level, max_neighbors);
             for neighbor_id in neighbor_ids {
@@ -1653,14 +3114,28 @@ impl InMemoryStore {
     }
 
     fn remove_vector_index_entry(&mut self, tenant_id: &str, claim_id: &str) {
+        if let Some(index) = self.vector_indexes.get_mut(tenant_id) {
+            index.remove(claim_id);
+            if index.is_empty() {
+                self.vector_indexes.remove(tenant_id);
+            }
+        }
         let mut remove_graph = false;
+        // `[level]` -> nodes that linked to or from the removed one.
+        let mut orphaned: Vec<Vec<String>> = Vec::new();
         if let Some(graph) = self.ann_vector_graphs.get_mut(tenant_id) {
             graph.node_levels.remove(claim_id);
             for level in &mut graph.levels {
-                level.remove(claim_id);
-                for neighbor_ids in level.values_mut() {
+                let mut level_orphans = level.remove(claim_id).unwrap_or_default();
+                for (node_id, neighbor_ids) in level.iter_mut() {
+                    let before = neighbor_ids.len();
                     neighbor_ids.retain(|id| id != claim_id);
+                    if neighbor_ids.len() < before && !level_orphans.contains(node_id) {
+                        level_orphans.push(node_id.clone());
+                    }
                 }
+                level_orphans.sort_unstable();
+                orphaned.push(level_orphans);
             }
             if graph.entry_point.as_deref() == Some(claim_id) {
                 if let Some((next_id, next_level)) = graph
@@ -1680,6 +3155,47 @@ impl InMemoryStore {
         }
         if remove_graph {
             self.ann_vector_graphs.remove(tenant_id);
+        } else {
+            self.relink_ann_orphans(tenant_id, orphaned);
+        }
+    }
+
+    /// Re-link across the hole a removed node leaves. Each node that lost
+    /// a link to it is offered the removed node's other neighbors at that
+    /// level, nearest first, until it is back at the level's degree;
+    /// `connect_ann_nodes` keeps the other side within its bound too.
+    fn relink_ann_orphans(&mut self, tenant_id: &str, orphaned: Vec<Vec<String>>) {
+        let metric = self.tenant_distance_metric(tenant_id);
+        for (level, orphans) in orphaned.into_iter().enumerate() {
+            let max_neighbors = self.ann_level_max_neighbors(tenant_id, level);
+            for orphan in &orphans {
+                let Some(linked) = self
+                    .ann_vector_graphs
+                    .get(tenant_id)
+                    .and_then(|graph| graph.levels[level].get(orphan))
+                    .cloned()
+                else {
+                    continue;
+                };
+                if linked.len() >= max_neighbors {
+                    continue;
+                }
+                let Some(vector) = self.claim_vectors.get(orphan).map(Cow::into_owned) else {
+                    continue;
+                };
+                let mut scored: Vec<(&String, f32)> = orphans
+                    .iter()
+                    .filter(|candidate| *candidate != orphan && !linked.contains(candidate))
+                    .filter_map(|candidate| {
+                        let score = self.claim_vectors.similarity(metric, &vector, candidate)?;
+                        Some((candidate, score))
+                    })
+                    .collect();
+                scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+                for (candidate, _) in scored.into_iter().take(max_neighbors - linked.len()) {
+                    self.connect_ann_nodes(tenant_id, level, orphan, candidate, max_neighbors);
+                }
+            }
         }
     }
 
@@ -1691,29 +3207,35 @@ impl InMemoryStore {
         level: usize,
         max_neighbors: usize,
     ) -> Vec<String> {
-        let mut scored: Vec<(String, f32)> = self
-            .claim_vectors
+        let metric = self.tenant_distance_metric(tenant_id);
+        let Some(graph) = self.ann_vector_graphs.get(tenant_id) else {
+            return Vec::new();
+        };
+        // Only the tenant's graph nodes reaching `level` are candidates.
+        let mut scored: Vec<(&str, f32)> = graph
+            .node_levels
             .iter()
-            .filter_map(|(other_claim_id, other_vector)| {
-                if other_claim_id == claim_id {
-                    return None;
-                }
-                let claim = self.claims.get(other_claim_id)?;
-                if claim.tenant_id != tenant_id {
-                    return None;
-                }
-                if !self.ann_node_is_visible_at_level(tenant_id, other_claim_id, level) {
+            .filter_map(|(other_claim_id, node_level)| {
+                if other_claim_id == claim_id || *node_level < level {
                     return None;
                 }
-                let sim = cosine_similarity(vector, other_vector)?;
-                Some((other_claim_id.clone(), sim))
+                let sim = self
+                    .claim_vectors
+                    .similarity(metric, vector, other_claim_id)?;
+                Some((other_claim_id.as_str(), sim))
             })
             .collect();
-        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let by_rank =
+            |a: &(&str, f32), b: &(&str, f32)| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0));
+        if scored.len() > max_neighbors && max_neighbors > 0 {
+            scored.select_nth_unstable_by(max_neighbors - 1, by_rank);
+            scored.truncate(max_neighbors);
+        }
+        scored.sort_unstable_by(by_rank);
         scored
             .into_iter()
             .take(max_neighbors)
-            .map(|(id, _)| id)
+            .map(|(id, _)| id.to_string())
             .collect()
     }
 
@@ -1769,7 +3291,7 @@ impl InMemoryStore {
         if level >= ANN_GRAPH_LEVELS {
             return;
         }
-        let Some(node_vector) = self.claim_vectors.get(claim_id).cloned() else {
+        let Some(node_vector) = self.claim_vectors.get(claim_id).map(Cow::into_owned) else {
             return;
         };
         let Some(candidate_neighbors) = self
@@ -1783,16 +3305,18 @@ impl InMemoryStore {
         if candidate_neighbors.len() <= max_neighbors {
             return;
         }
+        let metric = self.tenant_distance_metric(tenant_id);
 
         let mut scored: Vec<(String, f32)> = candidate_neighbors
             .into_iter()
             .filter_map(|neighbor_id| {
-                let neighbor_vector = self.claim_vectors.get(&neighbor_id)?;
-                let similarity = cosine_similarity(&node_vector, neighbor_vector)?;
+                let similarity =
+                    self.claim_vectors
+                        .similarity(metric, &node_vector, &neighbor_id)?;
                 Some((neighbor_id, similarity))
             })
             .collect();
-        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         let keep: Vec<String> = scored
             .into_iter()
             .take(max_neighbors)
@@ -1807,72 +3331,146 @@ impl InMemoryStore {
         }
     }
 
-    fn ann_node_is_visible_at_level(&self, tenant_id: &str, claim_id: &str, level: usize) -> bool {
-        self.ann_vector_graphs
+    fn add_trained_index_entry(
+        &mut self,
+        tenant_id: &str,
+        claim_id: &str,
+        vector: &[f32],
+        kind: AnnIndexKind,
+    ) {
+        if !self.vector_indexes.contains_key(tenant_id) {
+            let Some(index) = new_vector_index(kind) else {
+                return;
+            };
+            self.vector_indexes.insert(tenant_id.to_string(), index);
+        }
+        let Some(index) = self.vector_indexes.get_mut(tenant_id) else {
+            return;
+        };
+        index.note_added();
+        if !index.needs_training() {
+            index.insert(claim_id, vector);
+            return;
+        }
+        let mut claim_ids: Vec<&String> = self
+            .tenant_claim_ids
+            .get(tenant_id)
+            .map(|ids| {
+                ids.iter()
+                    .filter(|id| self.claim_vectors.contains_key(id.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        claim_ids.sort_unstable();
+        let vectors: Vec<(String, Vec<f32>)> = claim_ids
+            .into_iter()
+            .filter_map(|id| {
+                let values = self.claim_vectors.get(id)?.into_owned();
+                Some((id.clone(), values))
+            })
+            .collect();
+        if let Some(index) = self.vector_indexes.get_mut(tenant_id) {
+            index.train(&vectors);
+        }
+    }
+
+    /// Drop and rebuild `tenant_id`'s approximate index from its stored
+    /// vectors, using the tenant's current index kind. Tenants below the
+    /// exact-search threshold are left without one.
+    fn rebuild_tenant_vector_index(&mut self, tenant_id: &str) {
+        self.ann_vector_graphs.remove(tenant_id);
+        self.vector_indexes.remove(tenant_id);
+        let claim_ids = self.tenant_vector_claim_ids(tenant_id);
+        if claim_ids.len() < self.tenant_ann_tuning(tenant_id).exact_search_threshold {
+            return;
+        }
+        for claim_id in claim_ids {
+            if let Some(vector) = self.claim_vectors.get(&claim_id).map(Cow::into_owned) {
+                self.index_vector_entry(tenant_id, &claim_id, &vector);
+            }
+        }
+    }
+
+    /// Ids of `tenant_id`'s claims that have a vector, sorted.
+    fn tenant_vector_claim_ids(&self, tenant_id: &str) -> Vec<String> {
+        let mut claim_ids: Vec<String> = self
+            .tenant_claim_ids
             .get(tenant_id)
-            .and_then(|graph| graph.node_levels.get(claim_id))
-            .is_some_and(|node_level| *node_level >= level)
+            .map(|ids| {
+                ids.iter()
+                    .filter(|id| self.claim_vectors.contains_key(id.as_str()))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        claim_ids.sort_unstable();
+        claim_ids
     }
 
-    fn ann_level_max_neighbors(&self, level: usize) -> usize {
+    fn ann_level_max_neighbors(&self, tenant_id: &str, level: usize) -> usize {
+        let tuning = self.tenant_ann_tuning(tenant_id);
         if level == 0 {
-            self.ann_tuning.max_neighbors_base.max(1)
+            tuning.max_neighbors_base.max(1)
         } else {
-            self.ann_tuning.max_neighbors_upper.max(1)
+            tuning.max_neighbors_upper.max(1)
         }
     }
 
-    fn assign_ann_level(&self, claim_id: &str) -> usize {
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        claim_id.hash(&mut hasher);
-        let hash = hasher.finish();
-        let level = (hash.trailing_zeros() as usize) / 4;
+    /// Draw the level of `claim_id`'s graph node:
+    /// `floor(-ln(u) * level_multiplier)` for `u` uniform in (0, 1],
+    /// capped at the top level. `u` comes from a generator seeded with the
+    /// tenant's level seed, the tenant id and the claim id, so a node gets
+    /// the same level however its inserts are ordered or replayed, and no
+    /// generator state outlives the call.
+    fn assign_ann_level(&self, tenant_id: &str, claim_id: &str) -> usize {
+        let tuning = self.tenant_ann_tuning(tenant_id);
+        let (multiplier, seed) = (tuning.level_multiplier, tuning.level_seed);
+        if multiplier.is_nan() || multiplier <= 0.0 {
+            return 0;
+        }
+        let mut state = FNV1A_64_OFFSET_BASIS ^ seed;
+        fnv1a64_feed(&mut state, tenant_id.as_bytes());
+        fnv1a64_feed(&mut state, &[0]);
+        fnv1a64_feed(&mut state, claim_id.as_bytes());
+        let unit: f64 = StdRng::seed_from_u64(state).sample(OpenClosed01);
+        let level = (-unit.ln() * multiplier).floor() as usize;
         level.min(ANN_GRAPH_LEVELS.saturating_sub(1))
     }
 
     fn add_claim_indexes(&mut self, claim: &Claim) {
         self.tenant_claim_ids
-            .entry(claim.tenant_id.clone())
+            .entry(claim.tenant_id.to_string())
             .or_default()
-            .insert(claim.claim_id.clone());
+            .insert(claim.claim_id.to_string());
 
-        let tokens = tokenize(&claim.canonical_text);
-        self.claim_tokens
-            .insert(claim.claim_id.clone(), tokens.clone());
-        let token_index = self
-            .inverted_index
-            .entry(claim.tenant_id.clone())
-            .or_default();
-        let mut seen = HashSet::new();
-        for token in tokens {
-            if seen.insert(token.clone()) {
-                token_index
-                    .entry(token)
-                    .or_default()
-                    .insert(claim.claim_id.clone());
-            }
-        }
+        let tokens = self.analyze_claim_text(claim);
+        self.inverted_index
+            .entry(claim.tenant_id.to_string())
+            .or_default()
+            .insert(&claim.claim_id, &tokens);
+        self.claim_tokens.insert(claim.claim_id.to_string(), tokens);
 
         let entity_index = self
             .entity_index
-            .entry(claim.tenant_id.clone())
+            .entry(claim.tenant_id.to_string())
             .or_default();
+        let mut new_entity_keys = Vec::new();
         for entity in &claim.entities {
-            let key = normalize_index_key(entity);
+            let key = normalize_index_key(entity.index_name());
             if key.is_empty() {
                 continue;
             }
-            entity_index
-                .entry(key)
-                .or_default()
-                .insert(claim.claim_id.clone());
+            let claim_ids = entity_index.entry(key.clone()).or_default();
+            if claim_ids.is_empty() {
+                new_entity_keys.push(key);
+            }
+            claim_ids.insert(claim.claim_id.to_string());
         }
+        self.index_entity_trigrams(&claim.tenant_id, &new_entity_keys);
 
         let embedding_index = self
             .embedding_index
-            .entry(claim.tenant_id.clone())
+            .entry(claim.tenant_id.to_string())
             .or_default();
         let mut seen_embedding = HashSet::new();
         for embedding_id in &claim.embedding_ids {
@@ -1883,85 +3481,79 @@ impl InMemoryStore {
             embedding_index
                 .entry(key.to_string())
                 .or_default()
-                .insert(claim.claim_id.clone());
+                .insert(claim.claim_id.to_string());
         }
 
-        if let Some(ts) = claim.event_time_unix {
-            self.temporal_index
-                .entry(claim.tenant_id.clone())
-                .or_default()
-                .entry(ts)
-                .or_default()
-                .insert(claim.claim_id.clone());
-        }
+        self.index_claim_event_time(claim);
+        self.bump_index_epoch(&claim.tenant_id);
+        self.index_claim_metadata(claim);
+        self.index_claim_confidence(claim);
+        self.index_claim_type(claim);
+        self.index_claim_collection(claim);
+        self.index_claim_validity(claim);
+        self.index_claim_visibility(claim);
     }
 
     fn remove_claim_indexes(&mut self, claim: &Claim) {
-        if let Some(previous) = self.claim_vectors.remove(&claim.claim_id) {
-            let _ = previous;
+        if self.claim_vectors.remove(&claim.tenant_id, &claim.claim_id) {
             self.remove_vector_index_entry(&claim.tenant_id, &claim.claim_id);
         }
+        self.remove_named_claim_vectors(claim);
+        self.remove_claim_sparse_vector(claim);
+        self.unindex_claim_metadata(claim);
+        self.unindex_claim_confidence(claim);
+        self.unindex_claim_type(claim);
+        self.unindex_claim_collection(claim);
+        self.unindex_claim_validity(claim);
+        self.unindex_claim_visibility(claim);
+        self.bump_index_epoch(&claim.tenant_id);
 
         let mut drop_tenant_claim_ids = false;
-        if let Some(ids) = self.tenant_claim_ids.get_mut(&claim.tenant_id) {
-            ids.remove(&claim.claim_id);
+        if let Some(ids) = self.tenant_claim_ids.get_mut(claim.tenant_id.as_str()) {
+            ids.remove(claim.claim_id.as_str());
             drop_tenant_claim_ids = ids.is_empty();
         }
         if drop_tenant_claim_ids {
-            self.tenant_claim_ids.remove(&claim.tenant_id);
+            self.tenant_claim_ids.remove(claim.tenant_id.as_str());
         }
 
-        if let Some(tokens) = self.claim_tokens.remove(&claim.claim_id)
-            && let Some(token_index) = self.inverted_index.get_mut(&claim.tenant_id)
+        if let Some(tokens) = self.claim_tokens.remove(claim.claim_id.as_str())
+            && let Some(token_index) = self.inverted_index.get_mut(claim.tenant_id.as_str())
         {
-            let mut seen = HashSet::new();
-            let mut remove_tokens = Vec::new();
-            for token in tokens {
-                if !seen.insert(token.clone()) {
-                    continue;
-                }
-                if let Some(ids) = token_index.get_mut(&token) {
-                    ids.remove(&claim.claim_id);
-                    if ids.is_empty() {
-                        remove_tokens.push(token);
-                    }
-                }
-            }
-            for token in remove_tokens {
-                token_index.remove(&token);
-            }
+            token_index.remove(&claim.claim_id, &tokens);
         }
         if self
             .inverted_index
-            .get(&claim.tenant_id)
+            .get(claim.tenant_id.as_str())
             .is_some_and(|index| index.is_empty())
         {
-            self.inverted_index.remove(&claim.tenant_id);
+            self.inverted_index.remove(claim.tenant_id.as_str());
         }
 
         let mut remove_entity_index = false;
-        if let Some(entity_index) = self.entity_index.get_mut(&claim.tenant_id) {
-            let mut remove_keys = Vec::new();
+        let mut removed_entity_keys = Vec::new();
+        if let Some(entity_index) = self.entity_index.get_mut(claim.tenant_id.as_str()) {
             for entity in &claim.entities {
-                let key = normalize_index_key(entity);
+                let key = normalize_index_key(entity.index_name());
                 if let Some(ids) = entity_index.get_mut(&key) {
-                    ids.remove(&claim.claim_id);
+                    ids.remove(claim.claim_id.as_str());
                     if ids.is_empty() {
-                        remove_keys.push(key);
+                        removed_entity_keys.push(key);
                     }
                 }
             }
-            for key in remove_keys {
-                entity_index.remove(&key);
+            for key in &removed_entity_keys {
+                entity_index.remove(key);
             }
             remove_entity_index = entity_index.is_empty();
         }
         if remove_entity_index {
-            self.entity_index.remove(&claim.tenant_id);
+            self.entity_index.remove(claim.tenant_id.as_str());
         }
+        self.unindex_entity_trigrams(&claim.tenant_id, &removed_entity_keys);
 
         let mut remove_embedding_index = false;
-        if let Some(embedding_index) = self.embedding_index.get_mut(&claim.tenant_id) {
+        if let Some(embedding_index) = self.embedding_index.get_mut(claim.tenant_id.as_str()) {
             let mut remove_keys = Vec::new();
             let mut seen_embedding = HashSet::new();
             for embedding_id in &claim.embedding_ids {
@@ -1970,7 +3562,7 @@ impl InMemoryStore {
                     continue;
                 }
                 if let Some(ids) = embedding_index.get_mut(key) {
-                    ids.remove(&claim.claim_id);
+                    ids.remove(claim.claim_id.as_str());
                     if ids.is_empty() {
                         remove_keys.push(key.to_string());
                     }
@@ -1982,34 +3574,18 @@ impl InMemoryStore {
             remove_embedding_index = embedding_index.is_empty();
         }
         if remove_embedding_index {
-            self.embedding_index.remove(&claim.tenant_id);
+            self.embedding_index.remove(claim.tenant_id.as_str());
         }
 
-        let mut remove_temporal_index = false;
-        if let Some(ts) = claim.event_time_unix
-            && let Some(timeline) = self.temporal_index.get_mut(&claim.tenant_id)
-        {
-            let mut drop_ts = false;
-            if let Some(ids) = timeline.get_mut(&ts) {
-                ids.remove(&claim.claim_id);
-                drop_ts = ids.is_empty();
-            }
-            if drop_ts {
-                timeline.remove(&ts);
-            }
-            remove_temporal_index = timeline.is_empty();
-        }
-        if remove_temporal_index {
-            self.temporal_index.remove(&claim.tenant_id);
-        }
+        self.unindex_claim_event_time(claim);
 
         let has_remaining_vectors_for_tenant = self.claim_vectors.keys().any(|claim_id| {
             self.claims
-                .get(claim_id)
+                .get(claim_id.as_str())
                 .is_some_and(|stored_claim| stored_claim.tenant_id == claim.tenant_id)
         });
         if !has_remaining_vectors_for_tenant {
-            self.tenant_vector_dims.remove(&claim.tenant_id);
+            self.tenant_vector_dims.remove(claim.tenant_id.as_str());
         }
     }
 }
@@ -2054,17 +3630,64 @@ fn resolve_gpu_backend_runtime(explicit_gpu_required: bool) -> VectorBackendRunt
     }
 }
 
-fn score_query_candidate_vectors_cpu(
-    query_vector: &[f32],
-    candidate_vectors: &[(String, &[f32])],
-) -> Vec<(String, f32)> {
-    candidate_vectors
+/// Canonical order of retrieval results, given as `(score, claim
+/// confidence, claim_id)`: score descending, then confidence descending,
+/// then claim id ascending. Every retrieve path sorts with this so that
+/// equal scores never fall back to hash-map iteration order, which keeps
+/// paginated and cached results reproducible.
+pub(crate) fn compare_ranked(a: (f32, f32, &str), b: (f32, f32, &str)) -> std::cmp::Ordering {
+    b.0.total_cmp(&a.0)
+        .then_with(|| b.1.total_cmp(&a.1))
+        .then_with(|| a.2.cmp(b.2))
+}
+
+/// Attach text, citations and a certainty band to an index-only hit.
+/// `evidence` is all of the claim's evidence, rated by `quality`;
+/// `fields` decides whether it is cited.
+fn hydrate_hit(
+    hit: RetrievalHit,
+    claim: &Claim,
+    evidence: &[Evidence],
+    quality: impl Fn(&Evidence) -> f32,
+    fields: ResultFields,
+) -> RetrievalResult {
+    let certainty = certainty_band(claim, evidence, &quality, hit.supports, hit.contradicts);
+    let cited = if fields.includes_citations() {
+        evidence
+    } else {
+        &[]
+    };
+    let citations = cited
         .iter()
-        .filter_map(|(claim_id, candidate_vector)| {
-            let score = cosine_similarity(query_vector, candidate_vector)?;
-            Some((claim_id.clone(), score))
+        .map(|e| {
+            let mut citation = Citation::new(
+                e.evidence_id.to_string(),
+                e.source_id.clone(),
+                e.effective_stance(),
+                quality(e),
+            );
+            citation.negated = e.negated;
+            citation.stance_strength = e.stance_strength;
+            citation.chunk_id = e.chunk_id.clone();
+            citation.span_start = e.span_start;
+            citation.span_end = e.span_end;
+            citation.doc_id = e.doc_id.clone();
+            citation.extraction_model = e.extraction_model.clone();
+            citation.ingested_at = e.ingested_at;
+            citation
         })
-        .collect()
+        .collect();
+    let canonical_text = if fields.includes_text() {
+        claim.canonical_text.clone()
+    } else {
+        String::new()
+    };
+    let mut result = RetrievalResult::new(hit.claim_id, canonical_text, hit.score);
+    result.supports = hit.supports;
+    result.contradicts = hit.contradicts;
+    result.citations = citations;
+    result.certainty = certainty;
+    result
 }
 
 fn value_in_time_range(value: i64, from_unix: Option<i64>, to_unix: Option<i64>) -> bool {
@@ -2114,6 +3737,11 @@ fn claim_matches_time_range(claim: &Claim, from_unix: Option<i64>, to_unix: Opti
     }
 }
 
+/// How many vector candidates a query with `top_k` pulls from an index.
+fn vector_candidate_pool(top_k: usize) -> usize {
+    (top_k.saturating_mul(20)).clamp(100, 5000)
+}
+
 fn normalize_index_key(value: &str) -> String {
     value.trim().to_ascii_lowercase()
 }
@@ -2197,43 +3825,25 @@ struct GpuCosineParams {
     pad1: u32,
 }
 
-fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
-    if a.len() != b.len() || a.is_empty() {
-        return None;
-    }
-    let mut dot = 0.0f32;
-    let mut norm_a = 0.0f32;
-    let mut norm_b = 0.0f32;
-    for i in 0..a.len() {
-        dot += a[i] * b[i];
-        norm_a += a[i] * a[i];
-        norm_b += b[i] * b[i];
-    }
-    let denom = norm_a.sqrt() * norm_b.sqrt();
-    if denom <= f32::EPSILON {
-        None
-    } else {
-        Some(dot / denom)
-    }
-}
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use schema::{Claim, ClaimEdge, ClaimType, Relation, RetrievalRequest, Stance, StanceMode};
-    use std::path::PathBuf;
+    use schema::{
+        Chunk, Claim, ClaimEdge, ClaimId, ClaimType, Document, EdgeId, Entity, EvidenceBuilder,
+        Relation, RetrievalRequest, Source, Stance, StanceMode, TenantId, TrustTier,
+    };
+    use std::path::{Path, PathBuf};
     use std::time::Duration;
     use std::{
         fs::{read_to_string, remove_file},
-        sync::atomic::{AtomicU64, Ordering},
+        sync::atomic::{AtomicU64, AtomicUsize, Ordering},
         time::{SystemTime, UNIX_EPOCH},
     };
 
     fn claim_for_tenant(id: &str, text: &str, tenant_id: &str) -> Claim {
         Claim {
-            claim_id: id.to_string(),
-            tenant_id: tenant_id.to_string(),
+            claim_id: id.into(),
+            tenant_id: tenant_id.into(),
             canonical_text: text.to_string(),
             confidence: 0.9,
             event_time_unix: None,
@@ -2244,6 +3854,10 @@ mod tests {
             valid_to: None,
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         }
     }
 
@@ -2319,6 +3933,9 @@ mod tests {
             doc_id: None,
             extraction_model: None,
             ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
         }];
         let edges = vec![ClaimEdge {
             edge_id: "edge1".into(),
@@ -2354,6 +3971,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2374,17 +3994,19 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
             .unwrap();
 
-        let results = store.retrieve(&RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "Did Company X acquire Company Y?".into(),
-            top_k: 2,
-            stance_mode: StanceMode::Balanced,
-        });
+        let results = store.retrieve(&RetrievalRequest::new(
+            "tenant-a",
+            "Did Company X acquire Company Y?",
+            2,
+        ));
 
         assert_eq!(results.len(), 2);
         assert_eq!(results[0].claim_id, "c1");
@@ -2409,6 +4031,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-old".into(),
@@ -2422,6 +4048,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2441,6 +4070,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-new".into(),
@@ -2454,6 +4087,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2473,6 +4109,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-no-time".into(),
@@ -2486,17 +4126,15 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
             .unwrap();
 
-        let req = RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "project orion launch milestone".into(),
-            top_k: 5,
-            stance_mode: StanceMode::Balanced,
-        };
+        let req = RetrievalRequest::new("tenant-a", "project orion launch milestone", 5);
         let results = store.retrieve_with_time_range(&req, Some(150), Some(250));
 
         assert_eq!(results.len(), 1);
@@ -2521,6 +4159,10 @@ mod tests {
                     valid_to: Some(260),
                     created_at: Some(10),
                     updated_at: Some(20),
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-window-hit".into(),
@@ -2534,6 +4176,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2553,6 +4198,10 @@ mod tests {
                     valid_to: Some(500),
                     created_at: Some(11),
                     updated_at: Some(21),
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-window-miss".into(),
@@ -2566,17 +4215,15 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
             .unwrap();
 
-        let req = RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "claim validity window".into(),
-            top_k: 5,
-            stance_mode: StanceMode::Balanced,
-        };
+        let req = RetrievalRequest::new("tenant-a", "claim validity window", 5);
         let results = store.retrieve_with_time_range(&req, Some(150), Some(240));
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].claim_id, "c-window-hit");
@@ -2600,6 +4247,10 @@ mod tests {
                     valid_to: Some(260),
                     created_at: Some(12),
                     updated_at: Some(22),
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-both-miss".into(),
@@ -2613,6 +4264,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2632,6 +4286,10 @@ mod tests {
                     valid_to: Some(260),
                     created_at: Some(13),
                     updated_at: Some(23),
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-both-hit".into(),
@@ -2645,17 +4303,15 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
             .unwrap();
 
-        let req = RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "claim event validity".into(),
-            top_k: 5,
-            stance_mode: StanceMode::Balanced,
-        };
+        let req = RetrievalRequest::new("tenant-a", "claim event validity", 5);
         let results = store.retrieve_with_time_range(&req, Some(150), Some(240));
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].claim_id, "c-both-hit");
@@ -2680,6 +4336,9 @@ mod tests {
                         doc_id: None,
                         extraction_model: None,
                         ingested_at: None,
+                        language: None,
+                        stance_strength: None,
+                        negated: false,
                     },
                     Evidence {
                         evidence_id: "e2".into(),
@@ -2693,6 +4352,9 @@ mod tests {
                         doc_id: None,
                         extraction_model: None,
                         ingested_at: None,
+                        language: None,
+                        stance_strength: None,
+                        negated: false,
                     },
                     Evidence {
                         evidence_id: "e3".into(),
@@ -2706,18 +4368,19 @@ mod tests {
                         doc_id: None,
                         extraction_model: None,
                         ingested_at: None,
+                        language: None,
+                        stance_strength: None,
+                        negated: false,
                     },
                 ],
                 vec![],
             )
             .unwrap();
 
-        let support_only_results = store.retrieve(&RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "Company X acquired Company Y".into(),
-            top_k: 10,
-            stance_mode: StanceMode::SupportOnly,
-        });
+        let support_only_results = store.retrieve(
+            &RetrievalRequest::new("tenant-a", "Company X acquired Company Y", 10)
+                .with_stance_mode(StanceMode::SupportOnly),
+        );
         assert!(support_only_results.is_empty());
     }
 
@@ -2743,6 +4406,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2751,12 +4417,11 @@ mod tests {
 
         let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
         assert_eq!(replayed.claims_len(), 1);
-        let results = replayed.retrieve(&RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "company x acquired company y".into(),
-            top_k: 1,
-            stance_mode: StanceMode::Balanced,
-        });
+        let results = replayed.retrieve(&RetrievalRequest::new(
+            "tenant-a",
+            "company x acquired company y",
+            1,
+        ));
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].claim_id, "c1");
 
@@ -2785,6 +4450,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2828,18 +4496,20 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
             .unwrap();
 
         let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
-        let results = replayed.retrieve(&RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "company x acquired company y".into(),
-            top_k: 1,
-            stance_mode: StanceMode::Balanced,
-        });
+        let results = replayed.retrieve(&RetrievalRequest::new(
+            "tenant-a",
+            "company x acquired company y",
+            1,
+        ));
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].claim_id, "c-tab");
 
@@ -3049,6 +4719,10 @@ mod tests {
                     valid_to: Some(260),
                     created_at: Some(1_771_620_000_000),
                     updated_at: Some(1_771_620_100_000),
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-meta".into(),
@@ -3062,6 +4736,9 @@ mod tests {
                     doc_id: Some("doc://meta".into()),
                     extraction_model: Some("extractor-v5".into()),
                     ingested_at: Some(1_771_620_200_000),
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3072,10 +4749,7 @@ mod tests {
             .claims
             .get("c-meta")
             .expect("claim metadata should be replayed");
-        assert_eq!(
-            claim.entities,
-            vec!["Company X".to_string(), "Company Y".to_string()]
-        );
+        assert_eq!(claim.entities, vec!["Company X".into(), "Company Y".into()]);
         assert_eq!(claim.embedding_ids, vec!["emb://v1/42".to_string()]);
         assert_eq!(claim.claim_type, Some(ClaimType::Temporal));
         assert_eq!(claim.valid_from, Some(180));
@@ -3147,6 +4821,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3167,6 +4844,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3197,6 +4877,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3204,12 +4887,11 @@ mod tests {
 
         let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
         assert_eq!(replayed.claims_len(), 3);
-        let results = replayed.retrieve(&RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "post-compaction claim".into(),
-            top_k: 3,
-            stance_mode: StanceMode::Balanced,
-        });
+        let results = replayed.retrieve(&RetrievalRequest::new(
+            "tenant-a",
+            "post-compaction claim",
+            3,
+        ));
         assert_eq!(results[0].claim_id, "c3");
 
         cleanup_persistence_files(&wal);
@@ -3285,7 +4967,7 @@ mod tests {
         let mut store = InMemoryStore::new();
         let policy = CheckpointPolicy {
             max_wal_records: Some(4),
-            max_wal_bytes: None,
+            ..CheckpointPolicy::default()
         };
 
         let first = store
@@ -3305,6 +4987,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3328,6 +5013,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3346,8 +5034,8 @@ mod tests {
         let mut wal = FileWal::open(&wal_path).unwrap();
         let mut store = InMemoryStore::new();
         let policy = CheckpointPolicy {
-            max_wal_records: None,
             max_wal_bytes: Some(1),
+            ..CheckpointPolicy::default()
         };
 
         let stats = store
@@ -3367,6 +5055,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3379,8 +5070,64 @@ mod tests {
     }
 
     #[test]
-    fn entity_lookup_uses_entity_index() {
-        let mut store = InMemoryStore::new();
+    fn checkpoint_backlog_slows_then_stops_writes_until_checkpoint_succeeds() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let policy = CheckpointPolicy {
+            max_wal_records: Some(2),
+            write_slowdown_multiple: Some(2),
+            write_stop_multiple: Some(3),
+            ..CheckpointPolicy::default()
+        };
+        // A directory where the snapshot is staged makes every checkpoint fail.
+        let blocker = PathBuf::from(format!("{}.tmp", wal.snapshot_path().display()));
+        std::fs::create_dir(&blocker).unwrap();
+
+        for i in 0..5 {
+            let id = format!("c{i}");
+            store
+                .ingest_bundle_persistent(&mut wal, claim(&id, "Stalled write"), vec![], vec![])
+                .unwrap();
+        }
+        assert_eq!(
+            policy.write_stall(&wal).unwrap(),
+            WriteStall::Slowdown(Duration::from_millis(50))
+        );
+        assert_eq!(
+            store.throttle_write(&mut wal, &policy).unwrap(),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(store.metrics_snapshot().write_slowdowns, 1);
+
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c5", "Stalled write"), vec![], vec![])
+            .unwrap();
+        assert_eq!(policy.write_stall(&wal).unwrap(), WriteStall::Stop);
+        let err = store
+            .ingest_bundle_persistent_with_policy(
+                &mut wal,
+                &policy,
+                claim("c6", "Rejected write"),
+                vec![],
+                vec![],
+            )
+            .unwrap_err();
+        assert!(matches!(err, StoreError::WriteStalled(_)));
+        assert!(!store.claims.contains_key("c6"));
+        assert_eq!(store.metrics_snapshot().write_stops, 1);
+
+        std::fs::remove_dir(&blocker).unwrap();
+        assert_eq!(store.throttle_write(&mut wal, &policy).unwrap(), None);
+        assert_eq!(wal.wal_record_count().unwrap(), 0);
+        assert_eq!(policy.write_stall(&wal).unwrap(), WriteStall::Clear);
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn entity_lookup_uses_entity_index() {
+        let mut store = InMemoryStore::new();
         store
             .ingest_bundle(
                 Claim {
@@ -3396,13 +5143,17 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
             )
             .unwrap();
 
-        let claims = store.claims_for_entity("tenant-a", "company x");
+        let claims = store.claims_for_entity(&"tenant-a".into(), "company x");
         assert_eq!(claims.len(), 1);
         assert_eq!(claims[0].claim_id, "c-entity");
 
@@ -3412,6 +5163,37 @@ mod tests {
         assert!(stats.temporal_buckets >= 1);
     }
 
+    #[test]
+    fn structured_entities_replay_from_wal_and_index_canonical_names() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let mut claim = claim("c-typed", "Acme Corp., Inc. hired a new CFO");
+        claim.entities = vec![
+            Entity::new("Acme Corp., Inc.")
+                .with_type("organization")
+                .with_canonical_name("Acme"),
+            Entity::new("CFO").with_type("role"),
+            Entity::new("Q3"),
+        ];
+        let entities = claim.entities.clone();
+        store
+            .ingest_bundle_persistent(&mut wal, claim, vec![], vec![])
+            .unwrap();
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(replayed.claims.get("c-typed").unwrap().entities, entities);
+        for store in [&store, &replayed] {
+            let claim_ids = |entity: &str| store.claim_ids_for_entity(&"tenant-a".into(), entity);
+            assert!(claim_ids("acme").contains("c-typed"));
+            assert!(claim_ids("acme corp., inc.").is_empty());
+            assert!(claim_ids("cfo").contains("c-typed"));
+            assert!(claim_ids("q3").contains("c-typed"));
+        }
+
+        cleanup_persistence_files(&wal);
+    }
+
     #[test]
     fn embedding_lookup_uses_embedding_index() {
         let mut store = InMemoryStore::new();
@@ -3430,13 +5212,17 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
             )
             .unwrap();
 
-        let ids = store.claim_ids_for_embedding_id("tenant-a", "emb://claim-a");
+        let ids = store.claim_ids_for_embedding_id(&"tenant-a".into(), "emb://claim-a");
         assert_eq!(ids.len(), 1);
         assert!(ids.contains("c-embedding"));
     }
@@ -3459,22 +5245,17 @@ mod tests {
             )
             .unwrap();
         store
-            .upsert_claim_vector("c-allow", vec![1.0, 0.0, 0.0, 0.0])
+            .upsert_claim_vector(&"c-allow".into(), vec![1.0, 0.0, 0.0, 0.0])
             .unwrap();
         store
-            .upsert_claim_vector("c-deny", vec![0.9, 0.1, 0.0, 0.0])
+            .upsert_claim_vector(&"c-deny".into(), vec![0.9, 0.1, 0.0, 0.0])
             .unwrap();
 
         let mut allowed = HashSet::new();
-        allowed.insert("c-allow".to_string());
+        allowed.insert(ClaimId::from("c-allow"));
 
         let results = store.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
-            &RetrievalRequest {
-                tenant_id: "tenant-a".into(),
-                query: "project helios startup nova".into(),
-                top_k: 5,
-                stance_mode: StanceMode::Balanced,
-            },
+            &RetrievalRequest::new("tenant-a", "project helios startup nova", 5),
             None,
             None,
             Some(&[1.0, 0.0, 0.0, 0.0]),
@@ -3506,25 +5287,19 @@ mod tests {
             .ingest_bundle(claim("c-other", "Unrelated weather update"), vec![], vec![])
             .unwrap();
         store
-            .upsert_claim_vector("c-segment", vec![1.0, 0.0, 0.0, 0.0])
+            .upsert_claim_vector(&"c-segment".into(), vec![1.0, 0.0, 0.0, 0.0])
             .unwrap();
         store
-            .upsert_claim_vector("c-delta", vec![0.8, 0.2, 0.0, 0.0])
+            .upsert_claim_vector(&"c-delta".into(), vec![0.8, 0.2, 0.0, 0.0])
             .unwrap();
         store
-            .upsert_claim_vector("c-other", vec![0.1, 0.9, 0.0, 0.0])
+            .upsert_claim_vector(&"c-other".into(), vec![0.1, 0.9, 0.0, 0.0])
             .unwrap();
 
-        let explicit: HashSet<String> = ["c-segment".to_string(), "c-delta".to_string()]
-            .into_iter()
-            .collect();
+        let explicit: HashSet<ClaimId> =
+            ["c-segment".into(), "c-delta".into()].into_iter().collect();
         let results = store.retrieve_with_time_range_query_vector_and_explicit_candidate_claim_ids(
-            &RetrievalRequest {
-                tenant_id: "tenant-a".into(),
-                query: "project helios acquisition".into(),
-                top_k: 10,
-                stance_mode: StanceMode::Balanced,
-            },
+            &RetrievalRequest::new("tenant-a", "project helios acquisition", 10),
             None,
             None,
             Some(&[1.0, 0.0, 0.0, 0.0]),
@@ -3532,7 +5307,10 @@ mod tests {
             None,
         );
 
-        let result_ids: HashSet<String> = results.into_iter().map(|row| row.claim_id).collect();
+        let result_ids: HashSet<String> = results
+            .into_iter()
+            .map(|row| row.claim_id.into_string())
+            .collect();
         assert_eq!(result_ids.len(), 2);
         assert!(result_ids.contains("c-segment"));
         assert!(result_ids.contains("c-delta"));
@@ -3557,6 +5335,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3576,21 +5357,23 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
             .unwrap();
 
         let candidate_count =
-            store.candidate_count("tenant-a", "did company x acquire y", None, None);
+            store.candidate_count(&"tenant-a".into(), "did company x acquire y", None, None);
         assert_eq!(candidate_count, 1);
 
-        let results = store.retrieve(&RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "did company x acquire y".into(),
-            top_k: 2,
-            stance_mode: StanceMode::Balanced,
-        });
+        let results = store.retrieve(&RetrievalRequest::new(
+            "tenant-a",
+            "did company x acquire y",
+            2,
+        ));
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].claim_id, "c-good");
     }
@@ -3610,19 +5393,14 @@ mod tests {
             )
             .unwrap();
         store
-            .upsert_claim_vector_persistent(&mut wal, "c-vec", vec![0.1, 0.3, 0.5, 0.7])
+            .upsert_claim_vector_persistent(&mut wal, &"c-vec".into(), vec![0.1, 0.3, 0.5, 0.7])
             .unwrap();
 
         let (replayed, stats) = InMemoryStore::load_from_wal_with_stats(&wal).unwrap();
         assert!(stats.vectors_loaded >= 1);
 
         let results = replayed.retrieve_with_time_range_and_query_vector(
-            &RetrievalRequest {
-                tenant_id: "tenant-a".into(),
-                query: "vector indexed claim".into(),
-                top_k: 1,
-                stance_mode: StanceMode::Balanced,
-            },
+            &RetrievalRequest::new("tenant-a", "vector indexed claim", 1),
             None,
             None,
             Some(&[0.1, 0.3, 0.5, 0.7]),
@@ -3646,13 +5424,8 @@ mod tests {
                 vec![],
             )
             .unwrap();
-        wal.append_batch_commit(
-            "commit-test-123",
-            1,
-            1_700_000_000_000,
-            &["c-batch".to_string()],
-        )
-        .expect("batch commit append should succeed");
+        wal.append_batch_commit("commit-test-123", 1, 1_700_000_000_000, &["c-batch".into()])
+            .expect("batch commit append should succeed");
 
         let (replayed, stats) = InMemoryStore::load_from_wal_with_stats(&wal).unwrap();
         assert_eq!(replayed.claims_len(), 1);
@@ -3740,7 +5513,7 @@ mod tests {
     #[test]
     fn observe_batch_commit_is_idempotent_for_same_payload() {
         let mut store = InMemoryStore::new();
-        let claim_ids = vec!["c-idem-1".to_string(), "c-idem-2".to_string()];
+        let claim_ids = vec![ClaimId::from("c-idem-1"), ClaimId::from("c-idem-2")];
         store
             .observe_batch_commit(
                 "commit-idem-1",
@@ -3776,7 +5549,7 @@ mod tests {
                 "commit-conflict-1",
                 1,
                 1_700_000_000_000,
-                &["c-conflict-1".to_string()],
+                &["c-conflict-1".into()],
             )
             .expect("first commit metadata insert should succeed");
         let err = store
@@ -3784,7 +5557,7 @@ mod tests {
                 "commit-conflict-1",
                 1,
                 1_700_000_000_111,
-                &["c-conflict-2".to_string()],
+                &["c-conflict-2".into()],
             )
             .expect_err("conflicting payload should fail");
         assert!(matches!(err, StoreError::Conflict(_)));
@@ -3793,10 +5566,183 @@ mod tests {
         assert!(message.contains("incoming_fingerprint="));
     }
 
+    #[test]
+    fn store_errors_display_and_chain_validation_sources() {
+        let err = InMemoryStore::new()
+            .ingest_bundle(claim("c1", " "), vec![], vec![])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "validation failed: missing field: canonical_text"
+        );
+        let source = std::error::Error::source(&err).expect("validation source");
+        assert_eq!(source.to_string(), "missing field: canonical_text");
+        assert_eq!(
+            StoreError::MissingClaim("c9".into()).to_string(),
+            "missing claim: c9"
+        );
+        assert!(std::error::Error::source(&StoreError::MissingClaim("c9".into())).is_none());
+
+        let missing = temp_wal_path().join("missing");
+        let err = StoreError::from(std::fs::File::open(&missing).unwrap_err());
+        assert!(err.to_string().starts_with("io error: "));
+        let source = std::error::Error::source(&err)
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .expect("io source");
+        assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn stance_strength_and_negation_shape_support_and_ranking() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let supporting = |id: &str, claim_id: &str| {
+            EvidenceBuilder::new(id, claim_id, "source://wire").with_stance(Stance::Supports)
+        };
+        for (claim_id, evidence) in [
+            ("c-strong", supporting("e1", "c-strong")),
+            (
+                "c-weak",
+                supporting("e2", "c-weak").with_stance_strength(0.2),
+            ),
+            ("c-denied", supporting("e3", "c-denied").negated()),
+        ] {
+            store
+                .ingest_bundle_persistent(
+                    &mut wal,
+                    claim(claim_id, "company x acquired company y"),
+                    vec![evidence.build().unwrap()],
+                    vec![],
+                )
+                .unwrap();
+        }
+
+        let req = RetrievalRequest::new("tenant-a", "company x acquired", 5);
+        let check = |store: &InMemoryStore| {
+            let results = store.retrieve(&req);
+            let ids: Vec<&str> = results.iter().map(|r| r.claim_id.as_str()).collect();
+            assert_eq!(ids, vec!["c-strong", "c-weak", "c-denied"]);
+            assert_eq!((results[1].supports, results[1].contradicts), (1, 0));
+            assert_eq!((results[2].supports, results[2].contradicts), (0, 1));
+            let denied = &results[2].citations[0];
+            assert_eq!(
+                (&denied.stance, denied.negated),
+                (&Stance::Contradicts, true)
+            );
+            assert_eq!(results[1].citations[0].stance_strength, Some(0.2));
+            let weak = &store.evidence_by_claim["c-weak"][0];
+            assert_eq!(weak.stance_strength, Some(0.2));
+            assert!(store.evidence_by_claim["c-denied"][0].negated);
+        };
+        check(&store);
+        check(&InMemoryStore::load_from_wal(&wal).unwrap());
+
+        let support_only = req.clone().with_stance_mode(StanceMode::SupportOnly);
+        assert_eq!(store.retrieve(&support_only).len(), 2);
+        assert!(matches!(
+            supporting("e4", "c-strong")
+                .with_stance_strength(1.5)
+                .build(),
+            Err(ValidationError::InvalidRange("stance_strength"))
+        ));
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn validation_limits_reject_oversized_ingests_and_patches() {
+        let mut store = InMemoryStore::new();
+        let mut limits = ValidationConfig::default();
+        limits.max_text_len = 12;
+        limits.max_entities = 1;
+        limits.max_embedding_ids = 1;
+        limits.max_evidence_per_bundle = 1;
+        store.set_validation_config(limits);
+
+        let err = store
+            .ingest_bundle(claim("k1", "thirteen char"), vec![], vec![])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StoreError::Validation(ValidationError::TooLong {
+                field: "canonical_text",
+                len: 13,
+                max: 12,
+            })
+        ));
+        assert_eq!(
+            err.to_string(),
+            "validation failed: canonical_text is too long: 13 characters, limit 12"
+        );
+        let crowded = Claim {
+            entities: vec![Entity::new("Acme"), Entity::new("Globex")],
+            ..claim("k1", "short text")
+        };
+        assert!(matches!(
+            store.ingest_bundle(crowded, vec![], vec![]),
+            Err(StoreError::Validation(ValidationError::TooMany {
+                field: "entities",
+                ..
+            }))
+        ));
+        let evidence: Vec<Evidence> = ["e1", "e2"]
+            .iter()
+            .map(|id| {
+                EvidenceBuilder::new(id, "k1", "source://a")
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        assert!(matches!(
+            store.ingest_bundle(claim("k1", "short text"), evidence.clone(), vec![]),
+            Err(StoreError::Validation(ValidationError::TooMany {
+                field: "evidence",
+                count: 2,
+                max: 1,
+            }))
+        ));
+        assert!(store.claims.is_empty());
+
+        store
+            .ingest_bundle(claim("k1", "short text"), evidence[..1].to_vec(), vec![])
+            .unwrap();
+        let patch = ClaimPatch {
+            canonical_text: Some("a much longer text".to_string()),
+            ..ClaimPatch::default()
+        };
+        assert!(matches!(
+            store.patch_claim(&"tenant-a".into(), &"k1".into(), &patch),
+            Err(StoreError::Validation(ValidationError::TooLong { .. }))
+        ));
+        assert_eq!(store.claims["k1"].canonical_text, "short text");
+
+        store.set_validation_config(ValidationConfig::unlimited());
+        store
+            .patch_claim(&"tenant-a".into(), &"k1".into(), &patch)
+            .unwrap();
+        store
+            .ingest_bundle(claim("k2", "another long claim text"), vec![], vec![])
+            .unwrap();
+
+        // Renames are held to the configured limits as well.
+        let crowded = claim("k3", "short text")
+            .with_entities(vec![Entity::new("Acme"), Entity::new("Globex")]);
+        store.ingest_bundle(crowded, vec![], vec![]).unwrap();
+        store.set_validation_config(limits);
+        assert!(matches!(
+            store.rename_entity(&"tenant-a".into(), "Acme", "Acme Corp"),
+            Err(StoreError::Validation(ValidationError::TooMany {
+                field: "entities",
+                ..
+            }))
+        ));
+        assert_eq!(store.claims["k3"].entities[0].name, "Acme");
+    }
+
     #[test]
     fn batch_commit_payload_fingerprint_is_deterministic_and_order_sensitive() {
-        let ordered = vec!["c1".to_string(), "c2".to_string()];
-        let swapped = vec!["c2".to_string(), "c1".to_string()];
+        let ordered = vec![ClaimId::from("c1"), ClaimId::from("c2")];
+        let swapped = vec![ClaimId::from("c2"), ClaimId::from("c1")];
         let first = batch_commit_payload_fingerprint(2, &ordered);
         let second = batch_commit_payload_fingerprint(2, &ordered);
         let third = batch_commit_payload_fingerprint(2, &swapped);
@@ -3829,19 +5775,14 @@ mod tests {
             .ingest_bundle(claim("c-far", "Semantic distant claim"), vec![], vec![])
             .unwrap();
         store
-            .upsert_claim_vector("c-near", vec![1.0, 0.0, 0.0, 0.0])
+            .upsert_claim_vector(&"c-near".into(), vec![1.0, 0.0, 0.0, 0.0])
             .unwrap();
         store
-            .upsert_claim_vector("c-far", vec![0.0, 1.0, 0.0, 0.0])
+            .upsert_claim_vector(&"c-far".into(), vec![0.0, 1.0, 0.0, 0.0])
             .unwrap();
 
         let results = store.retrieve_with_time_range_and_query_vector(
-            &RetrievalRequest {
-                tenant_id: "tenant-a".into(),
-                query: "semantic claim".into(),
-                top_k: 2,
-                stance_mode: StanceMode::Balanced,
-            },
+            &RetrievalRequest::new("tenant-a", "semantic claim", 2),
             None,
             None,
             Some(&[0.99, 0.01, 0.0, 0.0]),
@@ -3863,32 +5804,26 @@ mod tests {
             .ingest_bundle(claim("c-far", "Semantic distant claim"), vec![], vec![])
             .unwrap();
         store
-            .upsert_claim_vector("c-near", vec![1.0, 0.0, 0.0, 0.0])
+            .upsert_claim_vector(&"c-near".into(), vec![1.0, 0.0, 0.0, 0.0])
             .unwrap();
         store
-            .upsert_claim_vector("c-far", vec![0.0, 1.0, 0.0, 0.0])
+            .upsert_claim_vector(&"c-far".into(), vec![0.0, 1.0, 0.0, 0.0])
             .unwrap();
 
         let query = [0.99, 0.01, 0.0, 0.0];
-        let ann = store.ann_vector_top_candidates("tenant-a", &query, 1);
-        let exact = store.exact_vector_top_candidates("tenant-a", &query, 1);
+        let ann = store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1);
+        let exact = store.exact_vector_top_candidates(&"tenant-a".into(), &query, 1);
 
-        assert_eq!(ann.first().map(String::as_str), Some("c-near"));
-        assert_eq!(exact.first().map(String::as_str), Some("c-near"));
+        assert_eq!(ann.first().map(ClaimId::as_str), Some("c-near"));
+        assert_eq!(exact.first().map(ClaimId::as_str), Some("c-near"));
     }
 
     #[test]
     fn ann_graph_populates_multiple_levels_for_tenant() {
         let mut store = InMemoryStore::new();
-        let mut high_level_claim_id = None;
 
         for i in 0..256 {
             let claim_id = format!("c-level-{i}");
-            let level = store.assign_ann_level(&claim_id);
-            if level > 0 {
-                high_level_claim_id = Some(claim_id.clone());
-            }
-
             store
                 .ingest_bundle(
                     claim(&claim_id, "ANN graph level population"),
@@ -3896,21 +5831,22 @@ mod tests {
                     vec![],
                 )
                 .unwrap();
-            let vector = vec![
-                0.1 + (i as f32 * 0.001),
-                0.2,
-                0.3,
-                if level > 0 { 0.99 } else { 0.4 },
-            ];
-            store.upsert_claim_vector(&claim_id, vector).unwrap();
+            let vector = vec![0.1 + (i as f32 * 0.001), 0.2, 0.3, 0.4];
+            store
+                .upsert_claim_vector(&ClaimId::from(&claim_id), vector)
+                .unwrap();
         }
 
-        let high_level_claim_id =
-            high_level_claim_id.expect("expected at least one claim to land in an upper ANN level");
         let graph = store
             .ann_vector_graphs
             .get("tenant-a")
             .expect("tenant ANN graph should exist");
+        let high_level_claim_id = graph
+            .node_levels
+            .iter()
+            .find(|(_, level)| **level == graph.entry_level)
+            .map(|(claim_id, _)| claim_id.clone())
+            .expect("entry level should hold a node");
 
         assert_eq!(graph.levels[0].len(), 256);
         assert!(!graph.levels[1].is_empty());
@@ -3918,6 +5854,51 @@ mod tests {
         assert!(graph.levels[graph.entry_level].contains_key(&high_level_claim_id));
     }
 
+    #[test]
+    fn ann_levels_follow_seed_and_multiplier() {
+        let levels_in_order = |level_multiplier: f64, level_seed: u64, reversed: bool| {
+            let mut store = InMemoryStore::new_with_ann_tuning(AnnTuningConfig {
+                level_multiplier,
+                level_seed,
+                ..AnnTuningConfig::default()
+            });
+            let mut order: Vec<usize> = (0..512).collect();
+            if reversed {
+                order.reverse();
+            }
+            for i in order {
+                let claim_id = format!("c-seeded-{i}");
+                store
+                    .ingest_bundle(claim(&claim_id, "seeded ANN levels"), vec![], vec![])
+                    .unwrap();
+                let vector = vec![(i % 7) as f32, (i % 11) as f32, 1.0, i as f32 * 0.01];
+                store
+                    .upsert_claim_vector(&ClaimId::from(&claim_id), vector)
+                    .unwrap();
+            }
+            let mut levels: Vec<(String, usize)> = store.ann_vector_graphs["tenant-a"]
+                .node_levels
+                .clone()
+                .into_iter()
+                .collect();
+            levels.sort();
+            levels
+        };
+        let levels = |level_multiplier: f64, level_seed: u64| {
+            levels_in_order(level_multiplier, level_seed, false)
+        };
+        let upper = |levels: &[(String, usize)]| levels.iter().filter(|(_, l)| *l > 0).count();
+
+        let seeded = levels(0.4, 11);
+        assert_eq!(seeded, levels(0.4, 11));
+        assert_eq!(seeded, levels_in_order(0.4, 11, true));
+        assert_ne!(seeded, levels(0.4, 12));
+        // exp(-1 / 0.4) of 512 nodes is about 42 above the base level.
+        assert!((20..=70).contains(&upper(&seeded)), "{}", upper(&seeded));
+        assert!(upper(&levels(1.0, 11)) > upper(&seeded));
+        assert_eq!(upper(&levels(0.0, 11)), 0);
+    }
+
     #[test]
     fn store_ann_tuning_can_be_overridden() {
         let tuning = AnnTuningConfig {
@@ -3926,6 +5907,10 @@ mod tests {
             search_expansion_factor: 9,
             search_expansion_min: 32,
             search_expansion_max: 2048,
+            index_kind: AnnIndexKind::Graph,
+            exact_search_threshold: 0,
+            level_multiplier: 0.5,
+            level_seed: 7,
         };
         let store = InMemoryStore::new_with_ann_tuning(tuning.clone());
         assert_eq!(store.ann_tuning(), &tuning);
@@ -3973,9 +5958,11 @@ mod tests {
             .unwrap();
 
         store
-            .upsert_claim_vector("c1", vec![0.1, 0.2, 0.3])
+            .upsert_claim_vector(&"c1".into(), vec![0.1, 0.2, 0.3])
             .unwrap();
-        let err = store.upsert_claim_vector("c2", vec![0.1, 0.2]).unwrap_err();
+        let err = store
+            .upsert_claim_vector(&"c2".into(), vec![0.1, 0.2])
+            .unwrap_err();
         match err {
             StoreError::InvalidVector(message) => {
                 assert!(message.contains("dimension mismatch"));
@@ -3984,6 +5971,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn registered_tenant_vector_config_pins_dimension_and_tuning() {
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(claim("c1", "First vector claim"), vec![], vec![])
+            .unwrap();
+        let tuning = AnnTuningConfig {
+            max_neighbors_base: 4,
+            max_neighbors_upper: 2,
+            search_expansion_factor: 4,
+            search_expansion_min: 8,
+            search_expansion_max: 32,
+            index_kind: AnnIndexKind::Graph,
+            exact_search_threshold: 0,
+            level_multiplier: 0.25,
+            level_seed: 42,
+        };
+        let config = TenantVectorConfig {
+            ann_tuning: Some(tuning.clone()),
+            ..TenantVectorConfig::new(3)
+        };
+        store
+            .register_tenant_vector_config(&"tenant-a".into(), config.clone())
+            .unwrap();
+        assert_eq!(store.ann_tuning_for_tenant(&"tenant-a".into()), &tuning);
+        assert_eq!(
+            store.ann_tuning_for_tenant(&"tenant-b".into()),
+            store.ann_tuning()
+        );
+
+        // The first vector no longer decides the dimension.
+        let err = store
+            .upsert_claim_vector(&"c1".into(), vec![0.1, 0.2])
+            .unwrap_err();
+        assert!(
+            matches!(err, StoreError::InvalidVector(ref message) if message.contains("registered config declares 3"))
+        );
+        store
+            .upsert_claim_vector(&"c1".into(), vec![0.1, 0.2, 0.3])
+            .unwrap();
+
+        let err = store
+            .register_tenant_vector_config(&"tenant-a".into(), TenantVectorConfig::new(4))
+            .unwrap_err();
+        assert!(matches!(err, StoreError::Conflict(_)));
+        assert!(matches!(
+            store.register_tenant_vector_config(&"tenant-b".into(), TenantVectorConfig::new(0)),
+            Err(StoreError::InvalidVector(_))
+        ));
+    }
+
+    #[test]
+    fn tenant_vector_config_survives_wal_replay_and_checkpoint() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let config = TenantVectorConfig {
+            ann_tuning: Some(AnnTuningConfig {
+                max_neighbors_base: 5,
+                ..AnnTuningConfig::default()
+            }),
+            ..TenantVectorConfig::new(2)
+        };
+        store
+            .register_tenant_vector_config_persistent(&mut wal, &"tenant-a".into(), config.clone())
+            .unwrap();
+        store
+            .register_tenant_vector_config_persistent(
+                &mut wal,
+                &"tenant-b".into(),
+                TenantVectorConfig::new(8),
+            )
+            .unwrap();
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.tenant_vector_config(&"tenant-a".into()),
+            Some(&config)
+        );
+        assert_eq!(
+            replayed.tenant_vector_config(&"tenant-b".into()),
+            Some(&TenantVectorConfig::new(8))
+        );
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        let reloaded = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            reloaded.tenant_vector_config(&"tenant-a".into()),
+            Some(&config)
+        );
+        cleanup_persistence_files(&wal);
+    }
+
     #[test]
     fn claim_id_reuse_across_tenants_is_rejected() {
         let mut store = InMemoryStore::new();
@@ -4010,8 +6090,8 @@ mod tests {
             other => panic!("expected Conflict, got {other:?}"),
         }
 
-        assert_eq!(store.claims_for_tenant("tenant-a").len(), 1);
-        assert_eq!(store.claims_for_tenant("tenant-b").len(), 0);
+        assert_eq!(store.claims_for_tenant(&"tenant-a".into()).len(), 1);
+        assert_eq!(store.claims_for_tenant(&"tenant-b".into()).len(), 0);
     }
 
     #[test]
@@ -4074,4 +6154,5699 @@ mod tests {
 
         cleanup_persistence_files(&wal);
     }
+
+    #[test]
+    fn metrics_snapshot_tracks_ingest_retrieval_and_checkpoint_counters() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c1", "Company X acquired Company Y"),
+                vec![Evidence {
+                    evidence_id: "e1".into(),
+                    claim_id: "c1".into(),
+                    source_id: "doc-1".into(),
+                    stance: Stance::Supports,
+                    source_quality: 0.9,
+                    chunk_id: None,
+                    span_start: None,
+                    span_end: None,
+                    doc_id: None,
+                    extraction_model: None,
+                    ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
+                }],
+                vec![],
+            )
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"c1".into(), vec![1.0, 0.0])
+            .unwrap();
+        let _ = store.retrieve_semantic(
+            &RetrievalRequest::new("tenant-a", "company x", 1),
+            &[1.0, 0.0],
+        );
+        store.checkpoint_and_compact(&mut wal).unwrap();
+
+        let metrics = store.metrics_snapshot();
+        assert_eq!(metrics.claims_ingested, 1);
+        assert_eq!(metrics.evidence_ingested, 1);
+        assert_eq!(metrics.edges_ingested, 0);
+        assert_eq!(metrics.vectors_upserted, 1);
+        assert_eq!(metrics.retrievals, 1);
+        assert_eq!(metrics.ann_searches, 1);
+        assert!(metrics.ann_expansions >= 1);
+        assert_eq!(metrics.checkpoints, 1);
+        assert_eq!(metrics.wal_bytes_written, wal.appended_bytes());
+        assert!(metrics.wal_bytes_written > 0);
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(replayed.metrics_snapshot().claims_ingested, 0);
+
+        store.reset_metrics();
+        assert_eq!(store.metrics_snapshot(), StoreMetricsSnapshot::default());
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn claim_watch_notifies_on_balance_changes_and_threshold_crossings() {
+        let mut store = InMemoryStore::new();
+        let evidence = |id: &str, claim_id: &str, stance: Stance| Evidence {
+            evidence_id: id.into(),
+            claim_id: claim_id.into(),
+            source_id: "doc-1".into(),
+            stance,
+            source_quality: 0.9,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        let supporting = vec![evidence("e1", "c1", Stance::Supports)];
+        store
+            .ingest_bundle(claim("c1", "the merger closed"), supporting, vec![])
+            .unwrap();
+        store
+            .ingest_bundle(claim("other", "unrelated"), vec![], vec![])
+            .unwrap();
+        let filter = ClaimWatchFilter::new(["c1", "later"]).with_confidence_threshold(0.5);
+        let mut watch = store.watch_claims(filter);
+
+        let c1 = claim("c1", "the merger closed");
+        let contradicting = vec![evidence("e2", "c1", Stance::Contradicts)];
+        store
+            .ingest_bundle(c1.clone(), contradicting, vec![])
+            .unwrap();
+        let edges = vec![ClaimEdge {
+            edge_id: "edge1".into(),
+            from_claim_id: "c1".into(),
+            to_claim_id: "other".into(),
+            relation: Relation::Contradicts,
+            strength: 0.7,
+            reason_codes: vec![],
+            created_at: None,
+        }];
+        store.ingest_bundle(c1.clone(), vec![], edges).unwrap();
+        let unwatched = vec![evidence("e3", "other", Stance::Contradicts)];
+        store
+            .ingest_bundle(claim("other", "unrelated"), unwatched, vec![])
+            .unwrap();
+        let mut lowered = c1;
+        lowered.confidence = 0.4;
+        store.ingest_bundle(lowered, vec![], vec![]).unwrap();
+        let mut later = claim("later", "the merger was blocked");
+        later.confidence = 0.2;
+        store.ingest_bundle(later, vec![], vec![]).unwrap();
+
+        let notifications: Vec<ClaimWatchNotification> = watch
+            .drain()
+            .into_iter()
+            .map(|event| event.notification)
+            .collect();
+        assert_eq!(
+            notifications,
+            vec![
+                ClaimWatchNotification::BalanceChanged {
+                    claim_id: "c1".into(),
+                    from: StanceBalance::Supported,
+                    to: StanceBalance::Even,
+                    supports: 1,
+                    contradicts: 1,
+                },
+                ClaimWatchNotification::BalanceChanged {
+                    claim_id: "c1".into(),
+                    from: StanceBalance::Even,
+                    to: StanceBalance::Contradicted,
+                    supports: 1,
+                    contradicts: 2,
+                },
+                ClaimWatchNotification::ConfidenceCrossed {
+                    claim_id: "c1".into(),
+                    threshold: 0.5,
+                    previous: 0.9,
+                    current: 0.4,
+                },
+            ]
+        );
+
+        let mut raised = claim("later", "the merger was blocked");
+        raised.confidence = 0.6;
+        store.ingest_bundle(raised, vec![], vec![]).unwrap();
+        let event = watch
+            .recv_timeout(Duration::from_millis(50))
+            .expect("a watched claim crossed its threshold");
+        assert_eq!(event.notification.claim_id(), "later");
+        assert!(watch.try_recv().is_none());
+    }
+
+    #[test]
+    fn subscribe_yields_full_payloads_for_committed_changes() {
+        let mut store = InMemoryStore::new();
+        let subscription = store.subscribe();
+        assert_eq!(store.change_subscriber_count(), 1);
+
+        let claim = claim("c1", "Company X acquired Company Y");
+        let edge = ClaimEdge {
+            edge_id: "edge1".into(),
+            from_claim_id: "c1".into(),
+            to_claim_id: "c2".into(),
+            relation: Relation::Supports,
+            strength: 0.6,
+            reason_codes: vec![],
+            created_at: None,
+        };
+        store
+            .ingest_bundle(claim.clone(), vec![], vec![edge.clone()])
+            .unwrap();
+        store
+            .upsert_claim_vector(&"c1".into(), vec![0.5, 0.5])
+            .unwrap();
+        store
+            .observe_batch_commit("commit-1", 1, 42, &["c1".into()])
+            .unwrap();
+        // Rejected mutations never reach subscribers.
+        assert!(
+            store
+                .upsert_claim_vector(&"missing".into(), vec![1.0])
+                .is_err()
+        );
+
+        let events = subscription.drain();
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(events[0].record, ChangeRecord::Claim(claim));
+        assert_eq!(events[1].record, ChangeRecord::Edge(edge));
+        assert_eq!(
+            events[2].record,
+            ChangeRecord::ClaimVector {
+                claim_id: "c1".into(),
+                values: vec![0.5, 0.5],
+            }
+        );
+        assert!(matches!(
+            &events[3].record,
+            ChangeRecord::BatchCommit(metadata) if metadata.commit_id == "commit-1"
+        ));
+
+        drop(subscription);
+        store
+            .ingest_bundle(claim_for_tenant("c3", "text", "tenant-a"), vec![], vec![])
+            .unwrap();
+        assert_eq!(store.change_subscriber_count(), 0);
+    }
+
+    #[test]
+    fn subscribe_yields_sparse_named_vector_document_and_chunk_changes() {
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(claim("c1", "Company X acquired Company Y"), vec![], vec![])
+            .unwrap();
+        let subscription = store.subscribe();
+
+        let sparse = SparseVector::new([("acquired".to_string(), 0.7)]).unwrap();
+        store
+            .upsert_claim_sparse_vector(&"c1".into(), sparse.clone())
+            .unwrap();
+        store
+            .upsert_named_claim_vector(&"c1".into(), "title", vec![1.0, 0.0])
+            .unwrap();
+        let document = Document::new("doc://deal", "tenant-a", "Company X acquired Company Y.");
+        store.put_document(document.clone()).unwrap();
+        let chunk = Chunk::new("chunk-1", "doc://deal", "tenant-a", "Company X acquired");
+        store.put_chunk(chunk.clone()).unwrap();
+        // Rejected mutations never reach subscribers.
+        assert!(
+            store
+                .upsert_named_claim_vector(&"missing".into(), "title", vec![1.0, 0.0])
+                .is_err()
+        );
+        let mut orphan = chunk.clone();
+        orphan.doc_id = "doc://missing".into();
+        assert!(store.put_chunk(orphan).is_err());
+
+        let records = subscription
+            .drain()
+            .into_iter()
+            .map(|event| event.record)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            vec![
+                ChangeRecord::SparseVector {
+                    claim_id: "c1".into(),
+                    vector: sparse,
+                },
+                ChangeRecord::NamedClaimVector {
+                    claim_id: "c1".into(),
+                    space: "title".into(),
+                    values: vec![1.0, 0.0],
+                },
+                ChangeRecord::Document(document),
+                ChangeRecord::Chunk(chunk),
+            ]
+        );
+    }
+
+    #[test]
+    fn change_feed_holds_staged_changes_until_commit_and_bounds_each_queue() {
+        let mut store = InMemoryStore::new();
+        let subscription = store.subscribe();
+        let lagging = store.subscribe_with_capacity(1);
+
+        let mut abandoned = store.staged();
+        abandoned
+            .ingest_bundle(claim("c-abandoned", "never committed"), vec![], vec![])
+            .unwrap();
+        drop(abandoned);
+        assert!(subscription.drain().is_empty());
+        assert_eq!(store.metrics_snapshot().claims_ingested, 0);
+
+        let mut staged = store.staged();
+        staged
+            .ingest_bundle(claim("c1", "first committed"), vec![], vec![])
+            .unwrap();
+        staged
+            .ingest_bundle(claim("c2", "second committed"), vec![], vec![])
+            .unwrap();
+        assert!(subscription.drain().is_empty());
+        store.commit_staged(staged);
+        assert_eq!(store.claims_len(), 2);
+        assert_eq!(store.metrics_snapshot().claims_ingested, 2);
+        assert_eq!(
+            subscription
+                .drain()
+                .iter()
+                .map(|event| event.sequence)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        assert_eq!(lagging.drain().len(), 1);
+        assert_eq!(lagging.dropped_events(), 1);
+        store
+            .ingest_bundle(claim("c3", "after catching up"), vec![], vec![])
+            .unwrap();
+        assert_eq!(lagging.drain()[0].sequence, 2);
+    }
+
+    #[test]
+    fn backup_archive_round_trips_snapshot_and_wal_delta() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c1", "snapshot claim"), vec![], vec![])
+            .unwrap();
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c2", "delta claim"), vec![], vec![])
+            .unwrap();
+
+        let mut archive_path = temp_wal_path();
+        archive_path.set_extension("bak");
+        let manifest = wal.backup_to(&archive_path).unwrap();
+        assert_eq!(manifest.snapshot_records, 1);
+        assert_eq!(manifest.wal_records, 1);
+        assert_eq!(verify_backup(&archive_path).unwrap(), manifest);
+
+        let restore_path = temp_wal_path();
+        let mut restored_wal = FileWal::open(&restore_path).unwrap();
+        restored_wal
+            .append_claim(&claim("stale", "overwritten by restore"))
+            .unwrap();
+        assert_eq!(restored_wal.restore_from(&archive_path).unwrap(), manifest);
+        let restored = InMemoryStore::load_from_wal(&restored_wal).unwrap();
+        assert_eq!(restored.claims_len(), 2);
+        assert!(restored.claim_by_id(&"stale".into()).is_none());
+        assert_eq!(restored_wal.wal_record_count().unwrap(), 1);
+
+        // Tampering with a record breaks the checksum and leaves the
+        // target untouched.
+        let tampered = read_to_string(&archive_path)
+            .unwrap()
+            .replace("delta claim", "forged claim");
+        std::fs::write(&archive_path, tampered).unwrap();
+        assert!(matches!(
+            restored_wal.restore_from(&archive_path),
+            Err(StoreError::Parse(_))
+        ));
+        assert_eq!(
+            InMemoryStore::load_from_wal(&restored_wal)
+                .unwrap()
+                .claims_len(),
+            2
+        );
+
+        let _ = remove_file(&archive_path);
+        cleanup_persistence_files(&wal);
+        cleanup_persistence_files(&restored_wal);
+    }
+
+    #[test]
+    fn tenant_jsonl_export_round_trips_into_a_fresh_store() {
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(
+                claim("c1", "Company X acquired Company Y"),
+                vec![Evidence {
+                    evidence_id: "e1".into(),
+                    claim_id: "c1".into(),
+                    source_id: "doc-1".into(),
+                    stance: Stance::Supports,
+                    source_quality: 0.9,
+                    chunk_id: None,
+                    span_start: None,
+                    span_end: None,
+                    doc_id: None,
+                    extraction_model: None,
+                    ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
+                }],
+                vec![ClaimEdge {
+                    edge_id: "edge1".into(),
+                    from_claim_id: "c1".into(),
+                    to_claim_id: "c2".into(),
+                    relation: Relation::Supports,
+                    strength: 0.6,
+                    reason_codes: vec![],
+                    created_at: None,
+                }],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(claim("c2", "Company Y was acquired"), vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector(&"c1".into(), vec![0.1, 0.2, 0.3])
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim_for_tenant("other", "not exported", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+
+        let mut exported = Vec::new();
+        let stats = store
+            .export_tenant_jsonl(&"tenant-a".into(), &mut exported)
+            .unwrap();
+        assert_eq!(
+            stats,
+            TenantExportStats {
+                claims: 2,
+                evidence: 1,
+                edges: 1,
+                vectors: 1,
+            }
+        );
+        let text = String::from_utf8(exported.clone()).unwrap();
+        assert!(text.starts_with("{\"kind\":\"header\""));
+        assert!(!text.contains("not exported"));
+
+        let mut imported = InMemoryStore::new();
+        assert_eq!(
+            imported.import_tenant_jsonl(exported.as_slice()).unwrap(),
+            stats
+        );
+        assert_eq!(imported.claims_len(), 2);
+        assert_eq!(imported.edges_for_claim(&"c1".into()).len(), 1);
+        let mut round_trip = Vec::new();
+        imported
+            .export_tenant_jsonl(&"tenant-a".into(), &mut round_trip)
+            .unwrap();
+        assert_eq!(round_trip, exported);
+
+        // A claim for a different tenant than the header is rejected
+        // before anything is applied.
+        let forged = text.replacen(
+            "\"tenant_id\":\"tenant-a\",\"canonical_text\"",
+            "\"tenant_id\":\"tenant-b\",\"canonical_text\"",
+            1,
+        );
+        let mut rejected = InMemoryStore::new();
+        assert!(matches!(
+            rejected.import_tenant_jsonl(forged.as_bytes()),
+            Err(StoreError::Conflict(_))
+        ));
+        assert_eq!(rejected.claims_len(), 0);
+    }
+
+    #[test]
+    fn cold_tier_candidates_merge_with_resident_results_under_budget() {
+        let mut hot = InMemoryStore::new();
+        hot.ingest_bundle(
+            claim("hot-1", "Company X acquired Company Y"),
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let mut archive = InMemoryStore::new();
+        for (id, text) in [
+            ("cold-1", "Company X acquired Company Y in 2019"),
+            ("cold-2", "Company X acquired Company Z"),
+            ("cold-3", "Company X acquired Company W"),
+        ] {
+            archive
+                .ingest_bundle(claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "Company X acquired Company Y", 5);
+        let cold_ids: HashSet<ClaimId> = ["hot-1", "cold-1", "cold-2", "cold-3", "cold-missing"]
+            .into_iter()
+            .map(ClaimId::from)
+            .collect();
+
+        let with_cold_tier = |max_loads| {
+            hot.retrieve_with_outcome(
+                &req,
+                &RetrievalOptions::new().with_cold_tier(&cold_ids, &archive, max_loads),
+            )
+        };
+        let outcome = with_cold_tier(3);
+        // `hot-1` is resident, so only four ids compete for three loads.
+        assert_eq!(outcome.cold_tier.loaded, 3);
+        assert_eq!(outcome.cold_tier.skipped_budget, 1);
+        assert_eq!(outcome.cold_tier.missing, 0);
+        let ids: Vec<&str> = outcome
+            .results
+            .iter()
+            .map(|r| r.claim_id.as_str())
+            .collect();
+        assert_eq!(ids.len(), 4);
+        assert!(ids.contains(&"hot-1"));
+        assert!(ids.contains(&"cold-1"));
+        assert!(!ids.contains(&"cold-missing"));
+        assert!(
+            outcome
+                .results
+                .windows(2)
+                .all(|pair| pair[0].score >= pair[1].score)
+        );
+
+        let outcome = with_cold_tier(10);
+        assert_eq!(outcome.cold_tier.loaded, 3);
+        assert_eq!(outcome.cold_tier.missing, 1);
+    }
+
+    #[test]
+    fn tenant_distance_metric_drives_ann_exact_and_scored_paths() {
+        let mut store = InMemoryStore::new();
+        store
+            .register_tenant_vector_config(
+                &"tenant-a".into(),
+                TenantVectorConfig {
+                    metric: DistanceMetric::Euclidean,
+                    ..TenantVectorConfig::new(2)
+                },
+            )
+            .unwrap();
+        store
+            .register_tenant_vector_config(
+                &"tenant-b".into(),
+                TenantVectorConfig {
+                    metric: DistanceMetric::Dot,
+                    ..TenantVectorConfig::new(2)
+                },
+            )
+            .unwrap();
+        // Same direction, different norms: cosine ties them, euclidean
+        // prefers the near one, dot prefers the long one.
+        for (tenant, suffix) in [("tenant-a", "a"), ("tenant-b", "b")] {
+            store
+                .ingest_bundle(
+                    claim_for_tenant(&format!("near-{suffix}"), "near vector", tenant),
+                    vec![],
+                    vec![],
+                )
+                .unwrap();
+            store
+                .ingest_bundle(
+                    claim_for_tenant(&format!("far-{suffix}"), "far vector", tenant),
+                    vec![],
+                    vec![],
+                )
+                .unwrap();
+            store
+                .upsert_claim_vector(&format!("near-{suffix}").into(), vec![1.0, 1.0])
+                .unwrap();
+            store
+                .upsert_claim_vector(&format!("far-{suffix}").into(), vec![10.0, 10.0])
+                .unwrap();
+        }
+        let query = [1.0, 1.0];
+
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["near-a".to_string()]
+        );
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["near-a".to_string()]
+        );
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-b".into(), &query, 1),
+            vec!["far-b".to_string()]
+        );
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-b".into(), &query, 1),
+            vec!["far-b".to_string()]
+        );
+
+        let req = RetrievalRequest::new("tenant-a", "vector", 2);
+        let results = store.retrieve_semantic(&req, &query);
+        assert_eq!(results[0].claim_id, "near-a");
+        assert!(results.iter().all(|r| (0.0..=1.2).contains(&r.score)));
+
+        assert!(matches!(
+            store.register_tenant_vector_config(&"tenant-a".into(), TenantVectorConfig::new(2)),
+            Err(StoreError::Conflict(_))
+        ));
+        assert_eq!(DistanceMetric::parse("L2"), Some(DistanceMetric::Euclidean));
+    }
+
+    #[test]
+    fn retrieve_hits_matches_full_retrieval_ranking() {
+        let mut store = InMemoryStore::new();
+        for (id, text, stance) in [
+            ("c1", "Company X acquired Company Y", Stance::Supports),
+            ("c2", "Company X opened an office", Stance::Contradicts),
+            ("c3", "Company Z hired staff", Stance::Supports),
+        ] {
+            store
+                .ingest_bundle(
+                    claim(id, text),
+                    vec![Evidence {
+                        evidence_id: format!("e-{id}").into(),
+                        claim_id: id.into(),
+                        source_id: "doc-1".into(),
+                        stance,
+                        source_quality: 0.8,
+                        chunk_id: None,
+                        span_start: None,
+                        span_end: None,
+                        doc_id: None,
+                        extraction_model: None,
+                        ingested_at: None,
+                        language: None,
+                        stance_strength: None,
+                        negated: false,
+                    }],
+                    vec![],
+                )
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "Company X acquired", 3);
+
+        let full = store.retrieve(&req);
+        let hits = store.retrieve_hits(&req, None, None, None);
+        assert_eq!(hits.len(), full.len());
+        for (hit, result) in hits.iter().zip(&full) {
+            assert_eq!(hit.claim_id, result.claim_id);
+            assert_eq!(hit.score, result.score);
+            assert_eq!(hit.supports, result.supports);
+            assert_eq!(hit.contradicts, result.contradicts);
+        }
+        assert_eq!(hits[0].claim_id, "c1");
+    }
+
+    #[test]
+    fn get_claims_hydrates_in_request_order_and_reports_missing_ids() {
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(
+                claim("c1", "Company X acquired Company Y"),
+                vec![Evidence {
+                    evidence_id: "e1".into(),
+                    claim_id: "c1".into(),
+                    source_id: "doc-1".into(),
+                    stance: Stance::Supports,
+                    source_quality: 0.9,
+                    chunk_id: None,
+                    span_start: None,
+                    span_end: None,
+                    doc_id: None,
+                    extraction_model: None,
+                    ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
+                }],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("c2", "Company Y was acquired"),
+                vec![],
+                vec![ClaimEdge {
+                    edge_id: "edge-1".into(),
+                    from_claim_id: "c2".into(),
+                    to_claim_id: "c1".into(),
+                    relation: Relation::Supports,
+                    strength: 0.7,
+                    reason_codes: vec![],
+                    created_at: None,
+                }],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim_for_tenant("other", "Other tenant claim", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+
+        let ids: Vec<ClaimId> = ["c2", "missing", "c1", "other", "c2"]
+            .into_iter()
+            .map(ClaimId::from)
+            .collect();
+        let hydrated = store.get_claims(&"tenant-a".into(), &ids);
+
+        let found: Vec<&str> = hydrated
+            .claims
+            .iter()
+            .map(|entry| entry.claim.claim_id.as_str())
+            .collect();
+        assert_eq!(found, vec!["c2", "c1"]);
+        assert_eq!(hydrated.claims[0].edges.len(), 1);
+        assert_eq!(hydrated.claims[1].evidence.len(), 1);
+        assert_eq!(
+            hydrated.missing_claim_ids,
+            vec!["missing".to_string(), "other".to_string()]
+        );
+    }
+
+    #[test]
+    fn int8_quantization_keeps_ranking_and_shrinks_vector_storage() {
+        let mut store = InMemoryStore::new();
+        let dim = 64;
+        for idx in 0..32 {
+            let id = format!("q{idx}");
+            store
+                .ingest_bundle(claim(&id, "quantized vector claim"), vec![], vec![])
+                .unwrap();
+            let vector: Vec<f32> = (0..dim)
+                .map(|d| ((idx * 31 + d * 17) as f32).sin())
+                .collect();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector)
+                .unwrap();
+        }
+        let query: Vec<f32> = (0..dim).map(|d| ((5 * 31 + d * 17) as f32).sin()).collect();
+        let float_top = store.exact_vector_top_candidates(&"tenant-a".into(), &query, 3);
+        let float_bytes = store.vector_storage_bytes();
+
+        store.set_vector_quantization(Some(Int8QuantizationConfig {
+            rescore_candidates: 0,
+            float_cache_capacity: 0,
+        }));
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            float_top[..1].to_vec()
+        );
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            float_top[..1].to_vec()
+        );
+        assert!(store.vector_storage_bytes() * 3 < float_bytes);
+        assert_eq!(store.index_stats().vector_count, 32);
+
+        // Vectors written while quantized land in the float cache and
+        // are re-scored exactly.
+        store.set_vector_quantization(Some(Int8QuantizationConfig {
+            rescore_candidates: 4,
+            float_cache_capacity: 2,
+        }));
+        store
+            .ingest_bundle(claim("exact", "quantized vector claim"), vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector(&"exact".into(), query.clone())
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "quantized", 1);
+        let results = store.retrieve_semantic(&req, &query);
+        assert_eq!(results[0].claim_id, "exact");
+        assert!(results[0].score >= 1.0);
+
+        store.set_vector_quantization(None);
+        assert!(store.vector_quantization().is_none());
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["exact".to_string()]
+        );
+    }
+
+    #[test]
+    fn sources_for_tenant_aggregates_evidence_per_source() {
+        let mut store = InMemoryStore::new();
+        let evidence = |id: &str, claim_id: &str, source_id: &str, stance, quality| Evidence {
+            evidence_id: id.into(),
+            claim_id: claim_id.into(),
+            source_id: source_id.into(),
+            stance,
+            source_quality: quality,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        store
+            .ingest_bundle(
+                claim("c1", "Company X acquired Company Y"),
+                vec![
+                    evidence("e1", "c1", "doc-a", Stance::Supports, 0.9),
+                    evidence("e2", "c1", "doc-b", Stance::Contradicts, 0.4),
+                ],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("c2", "Company Y was acquired"),
+                vec![evidence("e3", "c2", "doc-a", Stance::Neutral, 0.5)],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim_for_tenant("c3", "Other tenant claim", "tenant-b"),
+                vec![evidence("e4", "c3", "doc-c", Stance::Supports, 0.7)],
+                vec![],
+            )
+            .unwrap();
+
+        let sources = store.sources_for_tenant(&"tenant-a".into());
+        assert_eq!(sources.len(), 2);
+        let doc_a = &sources[0];
+        assert_eq!(doc_a.source_id, "doc-a");
+        assert_eq!(doc_a.evidence_count, 2);
+        assert_eq!(doc_a.claim_count, 2);
+        assert!((doc_a.avg_source_quality - 0.7).abs() < 1e-6);
+        assert_eq!(
+            (doc_a.supports, doc_a.contradicts, doc_a.neutral),
+            (1, 0, 1)
+        );
+        assert_eq!(sources[1].source_id, "doc-b");
+        assert_eq!(sources[1].contradicts, 1);
+        assert!(store.sources_for_tenant(&"missing".into()).is_empty());
+    }
+
+    #[test]
+    fn product_quantization_index_serves_ann_search_and_survives_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let dim = 16;
+        let vector_for = |idx: usize| -> Vec<f32> {
+            (0..dim)
+                .map(|d| ((idx * 37 + d * 11) as f32).sin())
+                .collect()
+        };
+        for idx in 0..96 {
+            let id = format!("pq-{idx:03}");
+            store
+                .ingest_bundle_persistent(&mut wal, claim(&id, "pq claim"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector_persistent(&mut wal, &ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        // Switching an existing tenant to PQ rebuilds its index.
+        let config = TenantVectorConfig {
+            ann_tuning: Some(AnnTuningConfig {
+                search_expansion_min: 8,
+                index_kind: AnnIndexKind::ProductQuantization {
+                    subspaces: 4,
+                    centroids: 16,
+                },
+                ..AnnTuningConfig::default()
+            }),
+            ..TenantVectorConfig::new(dim)
+        };
+        store
+            .register_tenant_vector_config_persistent(&mut wal, &"tenant-a".into(), config.clone())
+            .unwrap();
+        assert!(!store.ann_vector_graphs.contains_key("tenant-a"));
+        assert!(store.vector_indexes["tenant-a"].is_trained());
+
+        let query = vector_for(42);
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["pq-042".to_string()]
+        );
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.tenant_vector_config(&"tenant-a".into()),
+            Some(&config)
+        );
+        assert_eq!(
+            replayed.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["pq-042".to_string()]
+        );
+        assert_eq!(
+            AnnIndexKind::parse(&config.ann_tuning.unwrap().index_kind.encode()),
+            Some(AnnIndexKind::ProductQuantization {
+                subspaces: 4,
+                centroids: 16
+            })
+        );
+        assert_eq!(AnnIndexKind::parse("pq:4:512"), None);
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn ann_graph_is_restored_from_snapshot_instead_of_rebuilt() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        // Insert in reverse id order; a rebuild links in id order and
+        // would not reproduce the same neighbor lists.
+        for idx in (0..48).rev() {
+            let id = format!("g-{idx:02}");
+            store
+                .ingest_bundle_persistent(&mut wal, claim(&id, "graph claim"), vec![], vec![])
+                .unwrap();
+            let vector: Vec<f32> = (0..8).map(|d| ((idx * 13 + d * 7) as f32).cos()).collect();
+            store
+                .upsert_claim_vector_persistent(&mut wal, &ClaimId::from(&id), vector)
+                .unwrap();
+        }
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        let snapshot = std::fs::read_to_string(wal.snapshot_path()).unwrap();
+        let header = format!("A\t{ANN_GRAPH_SNAPSHOT_VERSION}\ttenant-a\t");
+        assert!(snapshot.lines().any(|line| line.starts_with(&header)));
+
+        let restored = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            restored.ann_vector_graphs.get("tenant-a"),
+            store.ann_vector_graphs.get("tenant-a")
+        );
+        let query: Vec<f32> = (0..8).map(|d| ((5 * 13 + d * 7) as f32).cos()).collect();
+        assert_eq!(
+            restored.ann_vector_top_candidates(&"tenant-a".into(), &query, 3),
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 3)
+        );
+
+        // Different graph geometry: the snapshot graph is ignored and
+        // rebuilt from the vectors.
+        let rebuilt = InMemoryStore::load_from_wal_with_ann_tuning(
+            &wal,
+            AnnTuningConfig {
+                max_neighbors_base: 4,
+                ..AnnTuningConfig::default()
+            },
+        )
+        .unwrap();
+        let graph = &rebuilt.ann_vector_graphs["tenant-a"];
+        assert_eq!(graph.node_levels.len(), 48);
+        assert!(
+            graph.levels[0]
+                .values()
+                .all(|neighbors| neighbors.len() <= 4)
+        );
+        assert_eq!(
+            rebuilt.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["g-05".to_string()]
+        );
+
+        // A graph written by an older version is rebuilt as well.
+        let stale = format!("A\t{}\ttenant-a\t", ANN_GRAPH_SNAPSHOT_VERSION - 1);
+        std::fs::write(wal.snapshot_path(), snapshot.replace(&header, &stale)).unwrap();
+        let rebuilt = InMemoryStore::load_from_wal(&wal).unwrap();
+        let graph = &rebuilt.ann_vector_graphs["tenant-a"];
+        assert_eq!(graph.node_levels.len(), 48);
+        assert_ne!(Some(graph), store.ann_vector_graphs.get("tenant-a"));
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn ivf_index_probes_nearest_lists_and_survives_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let dim = 8;
+        let config = TenantVectorConfig {
+            ann_tuning: Some(AnnTuningConfig {
+                index_kind: AnnIndexKind::Ivf {
+                    lists: 8,
+                    probes: 2,
+                },
+                ..AnnTuningConfig::default()
+            }),
+            ..TenantVectorConfig::new(dim)
+        };
+        store
+            .register_tenant_vector_config_persistent(&mut wal, &"tenant-a".into(), config.clone())
+            .unwrap();
+        let vector_for = |idx: usize| -> Vec<f32> {
+            (0..dim)
+                .map(|d| ((idx * 29 + d * 5) as f32).sin())
+                .collect()
+        };
+        for idx in 0..64 {
+            let id = format!("ivf-{idx:02}");
+            store
+                .ingest_bundle_persistent(&mut wal, claim(&id, "ivf claim"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector_persistent(&mut wal, &ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        assert!(!store.ann_vector_graphs.contains_key("tenant-a"));
+        assert!(store.vector_indexes["tenant-a"].is_trained());
+
+        // Two of eight lists are probed, so only part of the tenant is
+        // a candidate, and the query's own vector is always among them.
+        let query = vector_for(17);
+        let metric = store.distance_metric_for_tenant(&"tenant-a".into());
+        let candidates = store.vector_indexes["tenant-a"].candidates(metric, &query, 1);
+        assert!(candidates.contains(&"ivf-17".to_string()));
+        assert!(candidates.len() < 64);
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["ivf-17".to_string()]
+        );
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.tenant_vector_config(&"tenant-a".into()),
+            Some(&config)
+        );
+        assert_eq!(
+            replayed.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["ivf-17".to_string()]
+        );
+        assert_eq!(
+            AnnIndexKind::parse("ivf:8:2"),
+            Some(AnnIndexKind::Ivf {
+                lists: 8,
+                probes: 2
+            })
+        );
+        assert_eq!(AnnIndexKind::parse("ivf:8:0"), None);
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn half_precision_storage_halves_vector_memory_and_keeps_ranking() {
+        let mut store = InMemoryStore::new();
+        let dim = 64;
+        for idx in 0..32 {
+            let id = format!("h{idx}");
+            store
+                .ingest_bundle(claim(&id, "half precision claim"), vec![], vec![])
+                .unwrap();
+            let vector: Vec<f32> = (0..dim)
+                .map(|d| ((idx * 31 + d * 17) as f32).sin())
+                .collect();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector)
+                .unwrap();
+        }
+        let query: Vec<f32> = (0..dim).map(|d| ((9 * 31 + d * 17) as f32).sin()).collect();
+        let float_top = store.exact_vector_top_candidates(&"tenant-a".into(), &query, 5);
+        let float_bytes = store.vector_storage_bytes();
+
+        for precision in [VectorPrecision::F16, VectorPrecision::Bf16] {
+            store.set_vector_storage_config(VectorStorageConfig { precision });
+            assert_eq!(store.vector_storage_bytes() * 2, float_bytes);
+            assert_eq!(
+                store.exact_vector_top_candidates(&"tenant-a".into(), &query, 1),
+                float_top[..1].to_vec()
+            );
+            assert_eq!(
+                store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+                float_top[..1].to_vec()
+            );
+            // Upserts are narrowed on write and widened on read.
+            let written: Vec<f32> = query.iter().map(|value| -value).collect();
+            store
+                .upsert_claim_vector(&"h0".into(), written.clone())
+                .unwrap();
+            let stored = store.claim_vectors.get("h0").unwrap();
+            for (stored, original) in stored.iter().zip(&written) {
+                assert!((stored - original).abs() <= original.abs() / 128.0 + 1e-3);
+            }
+            store.set_vector_storage_config(VectorStorageConfig::default());
+        }
+        assert_eq!(store.vector_storage_bytes(), float_bytes);
+        assert_eq!(VectorPrecision::parse("BF16"), Some(VectorPrecision::Bf16));
+        assert_eq!(VectorPrecision::parse("f8"), None);
+    }
+
+    #[test]
+    fn small_tenants_use_exact_search_until_threshold_then_build_index() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new_with_ann_tuning(AnnTuningConfig {
+            exact_search_threshold: 10,
+            ..AnnTuningConfig::default()
+        });
+        let vector_for =
+            |idx: usize| -> Vec<f32> { (0..6).map(|d| ((idx * 7 + d * 3) as f32).cos()).collect() };
+        for idx in 0..9 {
+            let id = format!("s{idx}");
+            store
+                .ingest_bundle_persistent(&mut wal, claim(&id, "small tenant"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector_persistent(&mut wal, &ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        assert!(!store.ann_vector_graphs.contains_key("tenant-a"));
+        let query = vector_for(4);
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 3),
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 3)
+        );
+
+        store
+            .ingest_bundle_persistent(&mut wal, claim("s9", "small tenant"), vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"s9".into(), vector_for(9))
+            .unwrap();
+        assert_eq!(store.ann_vector_graphs["tenant-a"].node_levels.len(), 10);
+
+        // A per-tenant threshold above the tenant's size drops the index.
+        let config = TenantVectorConfig {
+            ann_tuning: Some(AnnTuningConfig {
+                exact_search_threshold: 64,
+                ..AnnTuningConfig::default()
+            }),
+            ..TenantVectorConfig::new(6)
+        };
+        store
+            .register_tenant_vector_config_persistent(&mut wal, &"tenant-a".into(), config.clone())
+            .unwrap();
+        store.set_ann_tuning(AnnTuningConfig::default());
+        assert!(!store.ann_vector_graphs.contains_key("tenant-a"));
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.tenant_vector_config(&"tenant-a".into()),
+            Some(&config)
+        );
+        assert!(!replayed.ann_vector_graphs.contains_key("tenant-a"));
+        assert_eq!(
+            replayed.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["s4".to_string()]
+        );
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn tenants_over_budget_move_to_mapped_vector_files() {
+        let directory = temp_wal_path().with_extension("vectors");
+        let mut store = InMemoryStore::new();
+        let dim = 8;
+        let vector_for = |idx: usize| -> Vec<f32> {
+            (0..dim)
+                .map(|d| ((idx * 11 + d * 7) as f32).sin())
+                .collect()
+        };
+        for idx in 0..40 {
+            let id = format!("m{idx:03}");
+            store
+                .ingest_bundle(claim(&id, "mapped vector"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        store
+            .ingest_bundle(
+                claim_for_tenant("small", "kept in memory", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .upsert_claim_vector(&"small".into(), vector_for(0))
+            .unwrap();
+        let query = vector_for(21);
+        let before = store.exact_vector_top_candidates(&"tenant-a".into(), &query, 5);
+
+        // 40 vectors of 8 f32s are 1280 bytes; tenant-b's 32 fit.
+        store
+            .enable_mmap_vectors(MmapVectorConfig {
+                directory: directory.clone(),
+                tenant_budget_bytes: 1024,
+            })
+            .unwrap();
+        assert_eq!(store.mapped_vector_tenants(), vec!["tenant-a".to_string()]);
+        assert_eq!(store.vector_storage_bytes(), dim * 4);
+        assert!(store.mapped_vector_bytes() >= 40 * dim * 4);
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 5),
+            before
+        );
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            before[..1]
+        );
+
+        // Writes after mapping go to the file, which grows past its
+        // initial slots.
+        for idx in 40..100 {
+            let id = format!("m{idx:03}");
+            store
+                .ingest_bundle(claim(&id, "mapped vector"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        store
+            .upsert_claim_vector(&"m021".into(), vector_for(77))
+            .unwrap();
+        assert_eq!(
+            store.claim_vectors.get("m021").unwrap().as_ref(),
+            vector_for(77).as_slice()
+        );
+        assert_eq!(store.index_stats().vector_count, 101);
+        let query = vector_for(64);
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["m064".to_string()]
+        );
+
+        let cloned = store.clone();
+        assert_eq!(
+            cloned.exact_vector_top_candidates(&"tenant-a".into(), &query, 3),
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 3)
+        );
+
+        store.disable_mmap_vectors();
+        assert!(store.mapped_vector_tenants().is_empty());
+        assert_eq!(store.vector_storage_bytes(), 101 * dim * 4);
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 0);
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 3),
+            cloned.exact_vector_top_candidates(&"tenant-a".into(), &query, 3)
+        );
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn ann_expansion_budget_can_be_overridden_per_query() {
+        let mut store = InMemoryStore::new();
+        let vector_for = |idx: usize| -> Vec<f32> {
+            (0..8).map(|d| ((idx * 13 + d * 5) as f32).sin()).collect()
+        };
+        for idx in 0..300 {
+            let id = format!("o{idx:03}");
+            store
+                .ingest_bundle(claim(&id, &format!("marker{idx}")), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "marker7", 5);
+        let query = vector_for(150);
+        let narrow = AnnSearchOverrides {
+            expansion_budget: Some(1),
+        };
+        let wide = AnnSearchOverrides {
+            expansion_budget: Some(10_000),
+        };
+
+        let default_count = store.candidate_count_with_query_vector(&req, Some(&query), None, None);
+        let narrow_count = store.candidate_count_with_ann_overrides(
+            &req,
+            Some(&query),
+            (None, None),
+            None,
+            narrow,
+        );
+        let wide_count =
+            store.candidate_count_with_ann_overrides(&req, Some(&query), (None, None), None, wide);
+        assert!(narrow_count <= 2, "narrow budget yielded {narrow_count}");
+        assert!(default_count > narrow_count);
+        // A budget covering the tenant makes the vector leg exact.
+        let mut exact: HashSet<ClaimId> = store
+            .exact_vector_top_candidates(&"tenant-a".into(), &query, 100)
+            .into_iter()
+            .collect();
+        exact.insert("o007".into());
+        assert_eq!(wide_count, exact.len());
+
+        let results = store.retrieve_with(
+            &req,
+            &RetrievalOptions::new()
+                .with_query_vector(Some(&query))
+                .with_ann_overrides(wide),
+        );
+        assert!(results.iter().any(|result| result.claim_id == "o150"));
+        // The override is per query; the tenant's tuning is untouched.
+        assert_eq!(
+            store
+                .retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+                    &req,
+                    None,
+                    None,
+                    Some(&query),
+                    None,
+                )
+                .len(),
+            results.len()
+        );
+    }
+
+    #[test]
+    fn vector_index_rebuild_matches_fresh_build_and_catches_up_off_thread() {
+        let vector_for = |seed: usize| -> Vec<f32> {
+            (0..6).map(|d| ((seed * 17 + d * 5) as f32).sin()).collect()
+        };
+        let mut store = InMemoryStore::new();
+        let mut fresh = InMemoryStore::new();
+        for idx in 0..40 {
+            let id = format!("r{idx:02}");
+            store
+                .ingest_bundle(claim(&id, "rebuild"), vec![], vec![])
+                .unwrap();
+            fresh
+                .ingest_bundle(claim(&id, "rebuild"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        // Churn the graph with upserts, then load the final vectors into
+        // a fresh store in the order a rebuild uses.
+        for idx in 0..40 {
+            let id = format!("r{idx:02}");
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector_for(idx + 100))
+                .unwrap();
+            fresh
+                .upsert_claim_vector(&ClaimId::from(&id), vector_for(idx + 100))
+                .unwrap();
+        }
+        assert_eq!(store.rebuild_vector_index(&"tenant-a".into()), 40);
+        assert_eq!(
+            store.ann_vector_graphs["tenant-a"],
+            fresh.ann_vector_graphs["tenant-a"]
+        );
+        assert_eq!(store.rebuild_vector_index(&"tenant-missing".into()), 0);
+
+        let rebuild = store.prepare_vector_index_rebuild(&"tenant-a".into());
+        assert_eq!(rebuild.vector_count(), 40);
+        let handle = std::thread::spawn(move || rebuild.build());
+        store
+            .ingest_bundle(claim("r40", "rebuild"), vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector(&"r40".into(), vector_for(7))
+            .unwrap();
+        store
+            .upsert_claim_vector(&"r00".into(), vector_for(9))
+            .unwrap();
+        let rebuilt = handle.join().unwrap();
+        assert_eq!(rebuilt.tenant_id(), "tenant-a");
+        assert_eq!(store.install_rebuilt_vector_index(rebuilt).unwrap(), 2);
+        let graph = &store.ann_vector_graphs["tenant-a"];
+        assert_eq!(graph.node_levels.len(), 41);
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &vector_for(9), 1),
+            vec!["r00".to_string()]
+        );
+
+        let rebuilt = store
+            .prepare_vector_index_rebuild(&"tenant-a".into())
+            .build();
+        store.set_ann_tuning(AnnTuningConfig {
+            max_neighbors_base: 4,
+            ..AnnTuningConfig::default()
+        });
+        assert!(matches!(
+            store.install_rebuilt_vector_index(rebuilt),
+            Err(StoreError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn vector_scorer_takes_over_exact_scoring_batches() {
+        struct CountingScorer {
+            batches: AtomicUsize,
+        }
+
+        impl VectorScorer for CountingScorer {
+            fn name(&self) -> &str {
+                "counting"
+            }
+
+            fn min_batch_size(&self) -> usize {
+                4
+            }
+
+            fn score(
+                &self,
+                metric: DistanceMetric,
+                query: &[f32],
+                candidates: &[(String, &[f32])],
+            ) -> Option<Vec<(String, f32)>> {
+                self.batches.fetch_add(1, Ordering::SeqCst);
+                CpuVectorScorer.score(metric, query, candidates)
+            }
+        }
+
+        let mut store = InMemoryStore::new();
+        for (idx, vector) in [[1.0, 0.0], [0.8, 0.2], [0.0, 1.0], [-1.0, 0.0]]
+            .into_iter()
+            .enumerate()
+        {
+            let id = format!("v{idx}");
+            store
+                .ingest_bundle(claim(&id, "scored"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), vector.to_vec())
+                .unwrap();
+        }
+        store
+            .ingest_bundle(
+                claim_for_tenant("small", "scored", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .upsert_claim_vector(&"small".into(), vec![1.0, 0.0])
+            .unwrap();
+        let expected = store.exact_vector_top_candidates(&"tenant-a".into(), &[1.0, 0.1], 4);
+
+        let scorer = Arc::new(CountingScorer {
+            batches: AtomicUsize::new(0),
+        });
+        store.set_vector_scorer(Some(scorer.clone()));
+        assert_eq!(store.vector_scorer_name(), Some("counting"));
+        // Half-precision vectors are widened into the batch.
+        store.set_vector_storage_config(VectorStorageConfig {
+            precision: VectorPrecision::F16,
+        });
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-a".into(), &[1.0, 0.1], 4),
+            expected
+        );
+        assert_eq!(scorer.batches.load(Ordering::SeqCst), 1);
+
+        // Batches under the scorer's minimum stay on the CPU.
+        store.exact_vector_top_candidates(&"tenant-b".into(), &[1.0, 0.0], 1);
+        assert_eq!(scorer.batches.load(Ordering::SeqCst), 1);
+
+        store.set_vector_scorer(None);
+        assert_eq!(store.vector_scorer_name(), None);
+    }
+
+    #[test]
+    fn removing_a_graph_node_relinks_its_neighbors() {
+        let mut store = InMemoryStore::new_with_ann_tuning(AnnTuningConfig {
+            max_neighbors_base: 2,
+            max_neighbors_upper: 2,
+            ..AnnTuningConfig::default()
+        });
+        let at_degrees = |degrees: f32| -> Vec<f32> {
+            let radians = degrees.to_radians();
+            vec![radians.cos(), radians.sin()]
+        };
+        for idx in 0..6 {
+            let id = format!("c{idx}");
+            store
+                .ingest_bundle(claim(&id, "arc"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector(&ClaimId::from(&id), at_degrees(idx as f32 * 10.0))
+                .unwrap();
+        }
+        let linked = |store: &InMemoryStore, a: &str, b: &str| {
+            store.ann_vector_graphs["tenant-a"].levels[0]
+                .get(a)
+                .is_some_and(|neighbors| neighbors.iter().any(|id| id == b))
+        };
+        assert!(!linked(&store, "c1", "c3"));
+
+        // Moving c2 to the far side of the circle unlinks it from c1 and
+        // c3; the repair bridges the gap it leaves.
+        store
+            .upsert_claim_vector(&"c2".into(), at_degrees(180.0))
+            .unwrap();
+        assert!(linked(&store, "c1", "c3"));
+        assert!(linked(&store, "c3", "c1"));
+        let query = at_degrees(12.0);
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 3),
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 3)
+        );
+    }
+
+    #[test]
+    fn vector_projection_reduces_stored_vectors_and_survives_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        // 16-dimensional vectors that only vary in 4 directions.
+        let vector_for = |idx: usize| -> Vec<f32> {
+            let latent: Vec<f32> = (0..4).map(|d| ((idx * 7 + d * 3) as f32).sin()).collect();
+            (0..16)
+                .map(|d| latent[d % 4] * (1.0 + d as f32 / 8.0))
+                .collect()
+        };
+        for idx in 0..40 {
+            let id = format!("p{idx:02}");
+            store
+                .ingest_bundle_persistent(&mut wal, claim(&id, "projected"), vec![], vec![])
+                .unwrap();
+            store
+                .upsert_claim_vector_persistent(&mut wal, &ClaimId::from(&id), vector_for(idx))
+                .unwrap();
+        }
+        let query = vector_for(13);
+        let before = store.exact_vector_top_candidates(&"tenant-a".into(), &query, 5);
+        let bytes_before = store.vector_storage_bytes();
+
+        let projection = store
+            .train_vector_projection(&"tenant-a".into(), 4)
+            .unwrap();
+        assert_eq!(projection.input_dimension(), 16);
+        assert_eq!(
+            store
+                .set_vector_projection_persistent(&mut wal, &"tenant-a".into(), projection.clone())
+                .unwrap(),
+            40
+        );
+        assert_eq!(store.vector_storage_bytes() * 4, bytes_before);
+        assert_eq!(store.claim_vectors.get("p00").unwrap().len(), 4);
+        assert_eq!(
+            store.exact_vector_top_candidates(&"tenant-a".into(), &query, 5),
+            before
+        );
+        assert_eq!(
+            store.ann_vector_top_candidates(&"tenant-a".into(), &query, 1),
+            vec!["p13"]
+        );
+
+        // Later upserts arrive full-size and are projected on the way in.
+        store
+            .ingest_bundle_persistent(&mut wal, claim("p40", "projected"), vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"p40".into(), vector_for(40))
+            .unwrap();
+        assert_eq!(store.claim_vectors.get("p40").unwrap().len(), 4);
+        assert!(matches!(
+            store.upsert_claim_vector(&"p40".into(), vec![1.0; 8]),
+            Err(StoreError::InvalidVector(_))
+        ));
+        assert!(matches!(
+            store.set_vector_projection(&"tenant-a".into(), projection.clone()),
+            Err(StoreError::Conflict(_))
+        ));
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.vector_projection(&"tenant-a".into()),
+            Some(&projection)
+        );
+        assert_eq!(
+            replayed.exact_vector_top_candidates(&"tenant-a".into(), &query, 5),
+            before
+        );
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        let compacted = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            compacted.vector_projection(&"tenant-a".into()),
+            Some(&projection)
+        );
+        assert_eq!(compacted.claim_vectors.get("p40").unwrap().len(), 4);
+        assert_eq!(
+            compacted.exact_vector_top_candidates(&"tenant-a".into(), &query, 5),
+            before
+        );
+
+        assert!(VectorProjection::random(8, 8, 1).is_err());
+        assert_eq!(
+            VectorProjection::random(8, 3, 1)
+                .unwrap()
+                .project(&[1.0; 8])
+                .len(),
+            3
+        );
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn named_vector_spaces_are_searched_and_fused_per_query() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        for id in ["title-match", "body-match"] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim(id, "named spaces"), vec![], vec![])
+                .unwrap();
+        }
+        // Body vectors in the default space, title vectors in a space of
+        // their own with a different dimension.
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"title-match".into(), vec![0.0, 1.0, 0.0])
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"body-match".into(), vec![1.0, 0.0, 0.0])
+            .unwrap();
+        store
+            .upsert_named_claim_vector_persistent(
+                &mut wal,
+                &"title-match".into(),
+                "title",
+                vec![1.0, 0.0],
+            )
+            .unwrap();
+        store
+            .upsert_named_claim_vector_persistent(
+                &mut wal,
+                &"body-match".into(),
+                "title",
+                vec![0.0, 1.0],
+            )
+            .unwrap();
+        assert!(matches!(
+            store.upsert_named_claim_vector(&"title-match".into(), "title", vec![1.0, 0.0, 0.0]),
+            Err(StoreError::InvalidVector(_))
+        ));
+        assert!(matches!(
+            store.upsert_named_claim_vector(&"missing".into(), "title", vec![1.0, 0.0]),
+            Err(StoreError::MissingClaim(_))
+        ));
+        assert_eq!(store.vector_space_names(&"tenant-a".into()), vec!["title"]);
+
+        let req = RetrievalRequest::new("tenant-a", "", 2);
+        let ranked = |store: &InMemoryStore, queries: &[VectorSpaceQuery]| -> Vec<String> {
+            store
+                .retrieve_with(&req, &RetrievalOptions::new().with_vector_spaces(queries))
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect()
+        };
+        let title = VectorSpaceQuery::named("title", vec![1.0, 0.0]);
+        let body = VectorSpaceQuery::default_space(vec![1.0, 0.0, 0.0]);
+        assert_eq!(
+            ranked(&store, std::slice::from_ref(&title)),
+            vec!["title-match", "body-match"]
+        );
+        assert_eq!(
+            ranked(&store, std::slice::from_ref(&body)),
+            vec!["body-match", "title-match"]
+        );
+        let fused = [title.clone().with_weight(3.0), body.clone()];
+        assert_eq!(ranked(&store, &fused), vec!["title-match", "body-match"]);
+        let fused = [title.clone(), body.clone().with_weight(3.0)];
+        assert_eq!(ranked(&store, &fused), vec!["body-match", "title-match"]);
+
+        for replayed in [InMemoryStore::load_from_wal(&wal).unwrap(), {
+            store.checkpoint_and_compact(&mut wal).unwrap();
+            InMemoryStore::load_from_wal(&wal).unwrap()
+        }] {
+            assert_eq!(
+                replayed.named_claim_vector(&"title-match".into(), "title"),
+                Some(vec![1.0, 0.0])
+            );
+            assert_eq!(
+                ranked(&replayed, std::slice::from_ref(&title)),
+                vec!["title-match", "body-match"]
+            );
+        }
+
+        // Re-ingesting a claim drops its vectors, named ones included.
+        store
+            .ingest_bundle(claim("title-match", "named spaces"), vec![], vec![])
+            .unwrap();
+        assert_eq!(
+            store.named_claim_vector(&"title-match".into(), "title"),
+            None
+        );
+        assert_eq!(
+            store.named_vector_top_candidates(&"tenant-a".into(), "title", &[1.0, 0.0], 5),
+            vec!["body-match"]
+        );
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn tenant_pipeline_controls_stage_order_and_extra_stages() {
+        struct ShortestFirst;
+        impl Reranker for ShortestFirst {
+            fn name(&self) -> &str {
+                "shortest-first"
+            }
+            fn rerank(&self, _query: &str, candidates: &[RerankCandidate<'_>]) -> Option<Vec<f32>> {
+                Some(candidates.iter().map(|c| -(c.text.len() as f32)).collect())
+            }
+        }
+
+        let mut store = InMemoryStore::new();
+        for (id, text) in [
+            ("c1", "rust borrow checker rules for memory safety"),
+            (
+                "c2",
+                "rust borrow checker rules for memory safety explained",
+            ),
+            ("c3", "rust memory"),
+            ("c4", "python garbage collection"),
+        ] {
+            store
+                .ingest_bundle(claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "rust borrow checker memory safety", 3);
+        let ids = |results: Vec<RetrievalResult>| -> Vec<String> {
+            results
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect()
+        };
+        let allowed: HashSet<ClaimId> = ["c2".into(), "c3".into()].into();
+        let baseline = ids(
+            store.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+                &req, None, None, None, None,
+            ),
+        );
+        assert_eq!(baseline, vec!["c1", "c2", "c3"]);
+
+        for invalid in [
+            vec![],
+            vec![PipelineStage::Scoring],
+            vec![PipelineStage::CandidateGeneration, PipelineStage::Filters],
+            vec![
+                PipelineStage::CandidateGeneration,
+                PipelineStage::Rerank { window: 5 },
+                PipelineStage::Scoring,
+            ],
+            vec![
+                PipelineStage::CandidateGeneration,
+                PipelineStage::Scoring,
+                PipelineStage::Scoring,
+            ],
+        ] {
+            assert!(matches!(
+                PipelineConfig::new(invalid),
+                Err(StoreError::Validation(_))
+            ));
+        }
+
+        // Without a filter stage the allowed ids still apply.
+        let unfiltered = PipelineConfig::new(vec![
+            PipelineStage::CandidateGeneration,
+            PipelineStage::Scoring,
+        ])
+        .unwrap();
+        store.set_tenant_pipeline(&"tenant-a".into(), Some(unfiltered));
+        let results = store.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+            &req,
+            None,
+            None,
+            None,
+            Some(&allowed),
+        );
+        assert_eq!(ids(results), vec!["c2", "c3"]);
+
+        let diverse = PipelineConfig::new(vec![
+            PipelineStage::CandidateGeneration,
+            PipelineStage::Scoring,
+            PipelineStage::Diversity { max_overlap: 0.7 },
+            PipelineStage::Filters,
+        ])
+        .unwrap();
+        store.set_tenant_pipeline(&"tenant-a".into(), Some(diverse));
+        let results = store.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+            &req, None, None, None, None,
+        );
+        assert_eq!(ids(results), vec!["c1", "c3"]);
+        // Index-only hits run the tenant pipeline too.
+        let hits = store.retrieve_hits(&req, None, None, None);
+        let hit_ids: Vec<&str> = hits.iter().map(|hit| hit.claim_id.as_str()).collect();
+        assert_eq!(hit_ids, vec!["c1", "c3"]);
+        // The allowed ids narrow the candidates before diversity runs, so
+        // c2 is no longer shadowed by c1.
+        let results = store.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+            &req,
+            None,
+            None,
+            None,
+            Some(&allowed),
+        );
+        assert_eq!(ids(results), vec!["c2", "c3"]);
+
+        let packed = PipelineConfig::new(vec![
+            PipelineStage::CandidateGeneration,
+            PipelineStage::Scoring,
+            PipelineStage::Packing { max_chars: 60 },
+        ])
+        .unwrap();
+        let results = store.retrieve_with(&req, &RetrievalOptions::new().with_pipeline(&packed));
+        assert_eq!(ids(results), vec!["c1", "c3"]);
+
+        let reranked = PipelineConfig::new(vec![
+            PipelineStage::CandidateGeneration,
+            PipelineStage::Filters,
+            PipelineStage::Scoring,
+            PipelineStage::Rerank { window: 2 },
+        ])
+        .unwrap();
+        let results = store.retrieve_with(&req, &RetrievalOptions::new().with_pipeline(&reranked));
+        assert_eq!(ids(results), baseline);
+        store.set_reranker(Some(Arc::new(ShortestFirst)));
+        assert_eq!(store.reranker_name(), Some("shortest-first"));
+        let results = store.retrieve_with(&req, &RetrievalOptions::new().with_pipeline(&reranked));
+        assert_eq!(ids(results), vec!["c1", "c2", "c3"]);
+        let reranked = PipelineConfig::new(vec![
+            PipelineStage::CandidateGeneration,
+            PipelineStage::Scoring,
+            PipelineStage::Rerank { window: 3 },
+        ])
+        .unwrap();
+        let results = store.retrieve_with(&req, &RetrievalOptions::new().with_pipeline(&reranked));
+        assert_eq!(ids(results), vec!["c3", "c1", "c2"]);
+
+        store.set_tenant_pipeline(&"tenant-a".into(), None);
+        assert_eq!(
+            store.tenant_pipeline(&"tenant-a".into()),
+            PipelineConfig::default()
+        );
+    }
+
+    #[test]
+    fn pipeline_without_filters_keeps_hidden_and_archived_claims_out() {
+        let mut store = InMemoryStore::new();
+        let mut restricted = claim("c2", "rust borrow checker for finance");
+        restricted.visibility_labels = vec!["team:finance".to_string()];
+        for claim in [
+            claim("c1", "rust borrow checker basics"),
+            restricted,
+            claim("c3", "rust borrow checker archived notes"),
+        ] {
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        store
+            .archive_claim(&"tenant-a".into(), &"c3".into())
+            .unwrap();
+        store.set_tenant_pipeline(
+            &"tenant-a".into(),
+            Some(
+                PipelineConfig::new(vec![
+                    PipelineStage::CandidateGeneration,
+                    PipelineStage::Scoring,
+                ])
+                .unwrap(),
+            ),
+        );
+        let req = RetrievalRequest::new("tenant-a", "rust borrow checker", 5);
+        let ids = |options: &RetrievalOptions<'_>| -> Vec<String> {
+            let mut ids: Vec<String> = store
+                .retrieve_with(&req, options)
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        let support = RetrievalOptions::new().with_visibility_labels(["team:support"]);
+        assert_eq!(ids(&support), vec!["c1"]);
+        let finance = RetrievalOptions::new().with_visibility_labels(["team:finance"]);
+        assert_eq!(ids(&finance), vec!["c1", "c2"]);
+        assert_eq!(
+            ids(&finance.with_include_archived(true)),
+            vec!["c1", "c2", "c3"]
+        );
+    }
+
+    #[test]
+    fn sparse_vectors_fuse_into_hybrid_rank_and_survive_replay() {
+        let sparse = |entries: &[(&str, f32)]| {
+            SparseVector::new(
+                entries
+                    .iter()
+                    .map(|(term, weight)| (term.to_string(), *weight)),
+            )
+            .unwrap()
+        };
+        assert!(matches!(
+            SparseVector::new([("a".to_string(), 1.0), ("a".to_string(), 2.0)]),
+            Err(StoreError::InvalidVector(_))
+        ));
+        assert_eq!(sparse(&[("b", 2.0), ("a", 1.0), ("c", 0.0)]).len(), 2);
+        assert_eq!(
+            sparse(&[("a", 1.0), ("b", 2.0)]).dot(&sparse(&[("b", 3.0), ("z", 1.0)])),
+            6.0
+        );
+
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        for (id, text) in [
+            ("s1", "alpha report"),
+            ("s2", "alpha report"),
+            ("s3", "gamma"),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        store
+            .upsert_claim_sparse_vector_persistent(
+                &mut wal,
+                &"s1".into(),
+                sparse(&[("alpha", 1.0)]),
+            )
+            .unwrap();
+        store
+            .upsert_claim_sparse_vector_persistent(
+                &mut wal,
+                &"s2".into(),
+                sparse(&[("alpha", 1.0), ("beta", 2.0)]),
+            )
+            .unwrap();
+        store
+            .upsert_claim_sparse_vector_persistent(&mut wal, &"s3".into(), sparse(&[("beta", 1.0)]))
+            .unwrap();
+        assert!(matches!(
+            store.upsert_claim_sparse_vector(&"s1".into(), SparseVector::default()),
+            Err(StoreError::InvalidVector(_))
+        ));
+
+        let req = RetrievalRequest::new("tenant-a", "alpha report", 3);
+        let query = sparse(&[("beta", 1.0)]);
+        let ranked = |store: &InMemoryStore, sparse_query: Option<&SparseVector>| -> Vec<String> {
+            store
+                .retrieve_with(
+                    &req,
+                    &RetrievalOptions::new().with_sparse_query(sparse_query),
+                )
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect()
+        };
+        assert_eq!(ranked(&store, None), vec!["s1", "s2"]);
+        // The sparse match lifts s2 over its lexical twin and pulls in s3,
+        // which shares no query term.
+        assert_eq!(ranked(&store, Some(&query)), vec!["s2", "s1", "s3"]);
+        assert_eq!(
+            store.sparse_vector_top_candidates(&"tenant-a".into(), &query, 5),
+            vec!["s2", "s3"]
+        );
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.claim_sparse_vector(&"s2".into()),
+            store.claim_sparse_vector(&"s2".into())
+        );
+        assert_eq!(ranked(&replayed, Some(&query)), vec!["s2", "s1", "s3"]);
+
+        store
+            .ingest_bundle(claim("s2", "alpha report"), vec![], vec![])
+            .unwrap();
+        assert_eq!(store.claim_sparse_vector(&"s2".into()), None);
+        assert_eq!(
+            store.sparse_vector_top_candidates(&"tenant-a".into(), &query, 5),
+            vec!["s3"]
+        );
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn metadata_filters_match_indexed_labels_and_survive_replay() {
+        let labelled = |id: &str, text: &str, metadata: &[(&str, &str)]| Claim {
+            metadata: metadata
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            ..claim(id, text)
+        };
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        for claim in [
+            labelled(
+                "m1",
+                "apollo launch slipped",
+                &[("project", "Apollo"), ("label", "urgent")],
+            ),
+            labelled(
+                "m2",
+                "apollo budget approved",
+                &[("project", "apollo"), ("label", "done")],
+            ),
+            labelled(
+                "m3",
+                "gemini launch slipped",
+                &[("project", "gemini"), ("label", "urgent")],
+            ),
+            labelled(
+                "m4",
+                "apollo tab\tin\nvalue",
+                &[("note", "tab\tand\nnewline")],
+            ),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim, vec![], vec![])
+                .unwrap();
+        }
+
+        let sorted = |ids: HashSet<ClaimId>| {
+            let mut ids: Vec<ClaimId> = ids.into_iter().collect();
+            ids.sort();
+            ids
+        };
+        let apollo = MetadataFilter::equals("Project", " APOLLO ");
+        let open = MetadataFilter::any_of("label", vec!["urgent".into(), "blocked".into()]);
+        assert_eq!(
+            sorted(
+                store
+                    .claim_ids_matching_metadata(&"tenant-a".into(), std::slice::from_ref(&apollo))
+            ),
+            vec!["m1", "m2"]
+        );
+        assert_eq!(
+            sorted(
+                store.claim_ids_matching_metadata(
+                    &"tenant-a".into(),
+                    &[apollo.clone(), open.clone()]
+                )
+            ),
+            vec!["m1"]
+        );
+        assert_eq!(
+            store
+                .claim_ids_matching_metadata(&"tenant-a".into(), &[])
+                .len(),
+            4
+        );
+        assert!(
+            store
+                .claim_ids_matching_metadata(&"tenant-b".into(), std::slice::from_ref(&apollo))
+                .is_empty()
+        );
+
+        let req = RetrievalRequest::new("tenant-a", "launch slipped", 5);
+        let results = store.retrieve_with(&req, &RetrievalOptions::new().with_metadata(&[open]));
+        let ids: Vec<&str> = results.iter().map(|r| r.claim_id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"m1") && ids.contains(&"m3"));
+
+        // Re-ingesting with new labels drops the old ones from the index.
+        let relabelled = labelled("m2", "apollo budget approved", &[("label", "urgent")]);
+        store.ingest_bundle(relabelled, vec![], vec![]).unwrap();
+        assert_eq!(
+            sorted(
+                store
+                    .claim_ids_matching_metadata(&"tenant-a".into(), std::slice::from_ref(&apollo))
+            ),
+            vec!["m1"]
+        );
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.claims.get("m4").unwrap().metadata,
+            vec![("note".to_string(), "tab\tand\nnewline".to_string())]
+        );
+        assert_eq!(
+            sorted(
+                replayed
+                    .claim_ids_matching_metadata(&"tenant-a".into(), std::slice::from_ref(&apollo))
+            ),
+            vec!["m1", "m2"]
+        );
+        replayed.checkpoint_and_compact(&mut wal).unwrap();
+        let compacted = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            compacted.claims.get("m1").unwrap().metadata,
+            store.claims["m1"].metadata
+        );
+        assert_eq!(compacted.claims.get("m3").unwrap().metadata.len(), 2);
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn visibility_labels_restrict_retrieval_and_survive_replay() {
+        let labelled = |id: &str, labels: &[&str]| Claim {
+            visibility_labels: labels.iter().map(|label| label.to_string()).collect(),
+            ..claim(id, "quarterly revenue grew")
+        };
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        for claim in [
+            labelled("v-open", &[]),
+            labelled("v-finance", &["team:finance"]),
+            labelled("v-board", &["team:finance", "board"]),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim, vec![], vec![])
+                .unwrap();
+        }
+        assert!(matches!(
+            store.ingest_bundle(labelled("v-bad", &["team finance"]), vec![], vec![]),
+            Err(StoreError::Validation(_))
+        ));
+
+        let req = RetrievalRequest::new("tenant-a", "quarterly revenue", 5);
+        let visible_ids = |store: &InMemoryStore, labels: &[&str]| {
+            let options = RetrievalOptions::new().with_visibility_labels(labels.iter().copied());
+            let mut ids: Vec<String> = store
+                .retrieve_with(&req, &options)
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(visible_ids(&store, &[]), vec!["v-open"]);
+        assert_eq!(visible_ids(&store, &["board"]), vec!["v-board", "v-open"]);
+        assert_eq!(
+            visible_ids(&store, &["team:finance"]),
+            vec!["v-board", "v-finance", "v-open"]
+        );
+        assert_eq!(visible_ids(&store, &["*"]), vec!["v-open"]);
+        assert_eq!(store.retrieve_with(&req, &RetrievalOptions::new()).len(), 3);
+        assert_eq!(
+            store.claim_ids_hidden_from(&"tenant-a".into(), &["board".to_string()]),
+            HashSet::from(["v-finance".into()])
+        );
+        assert!(!store.has_labelled_claims(&"tenant-b".into()));
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.claims["v-board"].visibility_labels,
+            vec!["team:finance", "board"]
+        );
+        assert_eq!(
+            visible_ids(&replayed, &["board"]),
+            vec!["v-board", "v-open"]
+        );
+
+        let mut relabelled = replayed;
+        for claim in [labelled("v-finance", &[]), labelled("v-board", &[])] {
+            relabelled.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        assert!(!relabelled.has_labelled_claims(&"tenant-a".into()));
+        assert_eq!(visible_ids(&relabelled, &[]).len(), 3);
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn slow_rerank_stage_is_cut_at_its_budget_and_tripped_by_the_breaker() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        struct ShortestFirst {
+            slow: AtomicBool,
+            calls: AtomicUsize,
+        }
+        impl Reranker for ShortestFirst {
+            fn name(&self) -> &str {
+                "shortest-first"
+            }
+            fn rerank(&self, _query: &str, candidates: &[RerankCandidate<'_>]) -> Option<Vec<f32>> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                if self.slow.load(Ordering::SeqCst) {
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+                Some(candidates.iter().map(|c| -(c.text.len() as f32)).collect())
+            }
+        }
+
+        let mut store = InMemoryStore::new();
+        for (id, text) in [
+            ("c1", "rust borrow checker rules for memory safety"),
+            (
+                "c2",
+                "rust borrow checker rules for memory safety explained",
+            ),
+            ("c3", "rust memory"),
+        ] {
+            store
+                .ingest_bundle(claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let reranker = Arc::new(ShortestFirst {
+            slow: AtomicBool::new(true),
+            calls: AtomicUsize::new(0),
+        });
+        store.set_reranker(Some(reranker.clone()));
+
+        let stages = vec![
+            PipelineStage::CandidateGeneration,
+            PipelineStage::Filters,
+            PipelineStage::Scoring,
+            PipelineStage::Rerank { window: 3 },
+        ];
+        assert!(matches!(
+            PipelineConfig::new(stages.clone())
+                .unwrap()
+                .with_stage_budget("scoring", Duration::from_millis(1)),
+            Err(StoreError::Validation(_))
+        ));
+        assert!(
+            PipelineConfig::default()
+                .with_stage_budget("rerank", Duration::from_millis(1))
+                .is_err()
+        );
+        let pipeline = PipelineConfig::new(stages)
+            .unwrap()
+            .with_stage_budget("rerank", Duration::from_millis(5))
+            .unwrap()
+            .with_circuit_breaker(Some(StageBreakerConfig {
+                failure_threshold: 2,
+                cooldown: Duration::from_millis(100),
+            }));
+        store.set_tenant_pipeline(&"tenant-a".into(), Some(pipeline));
+
+        let req = RetrievalRequest::new("tenant-a", "rust borrow checker memory safety", 3);
+        let retrieve = |store: &InMemoryStore| -> Vec<String> {
+            store
+                .retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+                    &req, None, None, None, None,
+                )
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect()
+        };
+        let breaker =
+            |store: &InMemoryStore| store.pipeline_stage_breakers(&"tenant-a".into())[0].clone();
+
+        // A late reranker is ignored, and two late runs in a row open the breaker.
+        assert_eq!(retrieve(&store), vec!["c1", "c2", "c3"]);
+        assert_eq!(breaker(&store).state, StageBreakerState::Closed);
+        assert_eq!(breaker(&store).consecutive_over_budget, 1);
+        assert_eq!(retrieve(&store), vec!["c1", "c2", "c3"]);
+        assert_eq!(breaker(&store).state, StageBreakerState::Open);
+        assert_eq!(breaker(&store).trips, 1);
+
+        // While open the stage is skipped without calling the reranker.
+        assert_eq!(retrieve(&store), vec!["c1", "c2", "c3"]);
+        assert_eq!(reranker.calls.load(Ordering::SeqCst), 2);
+        let metrics = store.metrics_snapshot();
+        assert_eq!(metrics.pipeline_stages_over_budget, 2);
+        assert_eq!(metrics.pipeline_stages_skipped, 1);
+        assert!(store.pipeline_stage_breakers(&"tenant-b".into()).is_empty());
+
+        // After the cooldown a probe that fits the budget closes it again.
+        std::thread::sleep(Duration::from_millis(120));
+        assert_eq!(breaker(&store).state, StageBreakerState::HalfOpen);
+        reranker.slow.store(false, Ordering::SeqCst);
+        assert_eq!(retrieve(&store), vec!["c3", "c1", "c2"]);
+        assert_eq!(breaker(&store).state, StageBreakerState::Closed);
+        assert_eq!(breaker(&store).consecutive_over_budget, 0);
+    }
+
+    #[test]
+    fn patched_and_reindexed_claims_keep_their_vectors_and_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let entity_claim = Claim {
+            entities: vec!["Company X".into()],
+            ..claim("a1", "company x acquired company y")
+        };
+        store
+            .ingest_bundle_persistent(&mut wal, entity_claim, vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"a1".into(), vec![0.1, 0.2, 0.3])
+            .unwrap();
+        store
+            .upsert_named_claim_vector_persistent(&mut wal, &"a1".into(), "title", vec![1.0, 0.0])
+            .unwrap();
+        let sparse = SparseVector::new([("acquired".to_string(), 0.7)]).unwrap();
+        store
+            .upsert_claim_sparse_vector_persistent(&mut wal, &"a1".into(), sparse)
+            .unwrap();
+
+        let (tenant_a, a1) = (TenantId::from("tenant-a"), ClaimId::from("a1"));
+        let inspection = store.inspect_claim(&tenant_a, &a1).unwrap();
+        assert_eq!(inspection.vector_dimension, Some(3));
+        assert_eq!(inspection.vector_spaces, vec!["title".to_string()]);
+        assert_eq!(inspection.sparse_term_count, Some(1));
+        assert!(store.inspect_claim(&"tenant-b".into(), &a1).is_none());
+
+        let patch = ClaimPatch {
+            canonical_text: Some("company x bought company y".into()),
+            confidence: Some(0.4),
+            ..ClaimPatch::default()
+        };
+        let patched = store
+            .patch_claim_persistent(&mut wal, &tenant_a, &a1, &patch)
+            .unwrap();
+        assert_eq!(patched.confidence, 0.4);
+        assert_eq!(
+            store
+                .inspect_claim(&tenant_a, &a1)
+                .unwrap()
+                .vector_dimension,
+            Some(3)
+        );
+        let req = RetrievalRequest::new("tenant-a", "bought", 5);
+        assert_eq!(store.retrieve(&req).len(), 1);
+
+        let entities = vec![Entity::new("Company Z")];
+        store
+            .reassign_claim_entities_persistent(&mut wal, &tenant_a, &a1, entities)
+            .unwrap();
+        assert!(
+            store
+                .claim_ids_for_entity(&"tenant-a".into(), "company x")
+                .is_empty()
+        );
+        assert!(
+            store
+                .claim_ids_for_entity(&"tenant-a".into(), "company z")
+                .contains("a1")
+        );
+
+        let before_reindex = store.inspect_claim(&tenant_a, &a1).unwrap();
+        assert_eq!(before_reindex.vector_spaces, vec!["title".to_string()]);
+        assert_eq!(before_reindex.sparse_term_count, Some(1));
+        store.reindex_claim(&tenant_a, &a1).unwrap();
+        assert_eq!(store.inspect_claim(&tenant_a, &a1), Some(before_reindex));
+        assert!(matches!(
+            store.patch_claim(&tenant_a, &"missing".into(), &patch),
+            Err(StoreError::MissingClaim(_))
+        ));
+        let invalid = ClaimPatch {
+            confidence: Some(1.5),
+            ..ClaimPatch::default()
+        };
+        assert!(store.patch_claim(&tenant_a, &a1, &invalid).is_err());
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.inspect_claim(&tenant_a, &a1),
+            store.inspect_claim(&tenant_a, &a1)
+        );
+        assert!(
+            replayed
+                .claim_ids_for_entity(&"tenant-a".into(), "company z")
+                .contains("a1")
+        );
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn confidence_range_is_resolved_from_the_sorted_index() {
+        let mut store = InMemoryStore::new();
+        for (id, confidence) in [("k0", 0.0), ("k1", 0.3), ("k2", 0.6), ("k3", 1.0)] {
+            let mut scored = claim(id, "quarterly revenue grew");
+            scored.confidence = confidence;
+            store.ingest_bundle(scored, vec![], vec![]).unwrap();
+        }
+        let sorted = |ids: HashSet<ClaimId>| {
+            let mut ids: Vec<ClaimId> = ids.into_iter().collect();
+            ids.sort();
+            ids
+        };
+        let in_range =
+            |store: &InMemoryStore, min, max| {
+                sorted(store.claim_ids_in_confidence_range(
+                    &"tenant-a".into(),
+                    ConfidenceRange::new(min, max),
+                ))
+            };
+
+        assert_eq!(in_range(&store, Some(0.3), Some(0.6)), vec!["k1", "k2"]);
+        assert_eq!(in_range(&store, None, Some(0.0)), vec!["k0"]);
+        assert_eq!(in_range(&store, Some(0.6), None), vec!["k2", "k3"]);
+        assert_eq!(in_range(&store, None, None).len(), 4);
+        assert!(in_range(&store, Some(0.7), Some(0.4)).is_empty());
+        assert!(in_range(&store, Some(1.5), None).is_empty());
+        assert!(
+            store
+                .claim_ids_in_confidence_range(&"tenant-b".into(), ConfidenceRange::at_least(0.0))
+                .is_empty()
+        );
+
+        // Re-ingesting a claim moves it to its new confidence.
+        let mut demoted = claim("k3", "quarterly revenue grew");
+        demoted.confidence = 0.1;
+        store.ingest_bundle(demoted, vec![], vec![]).unwrap();
+        assert_eq!(in_range(&store, Some(0.6), None), vec!["k2"]);
+
+        let req = RetrievalRequest::new("tenant-a", "revenue", 10);
+        let results = store.retrieve_with(
+            &req,
+            &RetrievalOptions::new().with_confidence(ConfidenceRange::at_least(0.25)),
+        );
+        let mut ids: Vec<&str> = results.iter().map(|r| r.claim_id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec!["k1", "k2"]);
+    }
+
+    #[test]
+    fn rename_entity_rewrites_claims_in_wal_batches_and_replays() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let naming = |id: &str, entities: &[&str]| Claim {
+            entities: entities.iter().map(|entity| Entity::new(*entity)).collect(),
+            ..claim(id, "acme shipped the release")
+        };
+        let claim_count = entity_rename::ENTITY_RENAME_WAL_BATCH_CLAIMS + 2;
+        for idx in 0..claim_count {
+            let renamed = naming(&format!("r{idx:04}"), &["ACME Corp.", "Widget"]);
+            store
+                .ingest_bundle_persistent(&mut wal, renamed, vec![], vec![])
+                .unwrap();
+        }
+        for extra in [
+            naming("both", &["Acme", "acme corp."]),
+            naming("other", &["Widget"]),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, extra, vec![], vec![])
+                .unwrap();
+        }
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"r0000".into(), vec![0.5, 0.5])
+            .unwrap();
+
+        let renamed = store
+            .rename_entity_persistent(&mut wal, &"tenant-a".into(), " acme corp. ", "Acme")
+            .unwrap();
+        assert_eq!(renamed, claim_count + 1);
+        assert!(
+            store
+                .claim_ids_for_entity(&"tenant-a".into(), "acme corp.")
+                .is_empty()
+        );
+        assert_eq!(
+            store.claim_ids_for_entity(&"tenant-a".into(), "acme").len(),
+            claim_count + 1
+        );
+        assert_eq!(
+            store.claims["r0000"].entities,
+            vec![Entity::new("Acme"), Entity::new("Widget")]
+        );
+        assert_eq!(store.claims["both"].entities, vec![Entity::new("Acme")]);
+        assert_eq!(store.claims["other"].entities, vec![Entity::new("Widget")]);
+        assert!(store.claim_vectors.get("r0000").is_some());
+
+        // Nothing left to rename.
+        assert_eq!(
+            store
+                .rename_entity(&"tenant-a".into(), "ACME Corp.", "Acme")
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            store
+                .rename_entity(&"tenant-b".into(), "Widget", "Gadget")
+                .unwrap(),
+            0
+        );
+        assert!(matches!(
+            store.rename_entity(&"tenant-a".into(), "Widget", "  "),
+            Err(StoreError::Validation(_))
+        ));
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert!(
+            replayed
+                .claim_ids_for_entity(&"tenant-a".into(), "acme corp.")
+                .is_empty()
+        );
+        assert_eq!(replayed.claims["both"].entities, vec![Entity::new("Acme")]);
+        assert!(replayed.claim_vectors.get("r0000").is_some());
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn claim_type_index_follows_updates_and_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let typed = |id: &str, claim_type| {
+            let mut typed = claim(id, "the launch moved to march");
+            typed.claim_type = claim_type;
+            typed
+        };
+        for claim in [
+            typed("t1", Some(ClaimType::Factual)),
+            typed("t2", Some(ClaimType::Prediction)),
+            typed("t3", None),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim, vec![], vec![])
+                .unwrap();
+        }
+
+        let factual = store.claim_ids_for_claim_type(&"tenant-a".into(), &ClaimType::Factual);
+        assert_eq!(factual, HashSet::from(["t1".into()]));
+        let either = [ClaimType::Factual, ClaimType::Prediction];
+        assert_eq!(
+            store
+                .claim_ids_for_claim_types(&"tenant-a".into(), &either)
+                .len(),
+            2
+        );
+        assert!(
+            store
+                .claim_ids_for_claim_type(&"tenant-b".into(), &ClaimType::Factual)
+                .is_empty()
+        );
+
+        let req = RetrievalRequest::new("tenant-a", "launch", 5);
+        let results = store.retrieve_with(
+            &req,
+            &RetrievalOptions::new().with_claim_types(&[ClaimType::Prediction]),
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].claim_id, "t2");
+        assert_eq!(
+            store
+                .retrieve_with(&req, &RetrievalOptions::new().with_claim_types(&[]))
+                .len(),
+            3
+        );
+
+        // Retyping a claim moves it between indexes.
+        let retyped = typed("t2", Some(ClaimType::Factual));
+        store
+            .ingest_bundle_persistent(&mut wal, retyped, vec![], vec![])
+            .unwrap();
+        assert!(
+            store
+                .claim_ids_for_claim_type(&"tenant-a".into(), &ClaimType::Prediction)
+                .is_empty()
+        );
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.claim_ids_for_claim_type(&"tenant-a".into(), &ClaimType::Factual),
+            HashSet::from(["t1".into(), "t2".into()])
+        );
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn collections_partition_a_tenant_for_retrieval_and_survive_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let collected = |id: &str, collection: Option<&str>| {
+            let mut collected = claim(id, "the renewal terms changed in march");
+            collected.collection = collection.map(str::to_string);
+            collected
+        };
+        for claim in [
+            collected("k1", Some("support-tickets")),
+            collected("k2", Some("contracts")),
+            collected("k3", None),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim, vec![], vec![])
+                .unwrap();
+        }
+        assert_eq!(
+            store.collections(&"tenant-a".into()),
+            vec![
+                ("contracts".to_string(), 1),
+                ("support-tickets".to_string(), 1)
+            ]
+        );
+        assert!(store.collections(&"tenant-b".into()).is_empty());
+
+        let req = RetrievalRequest::new("tenant-a", "renewal terms", 5);
+        let ids = |results: Vec<RetrievalResult>| {
+            let mut ids: Vec<String> = results
+                .into_iter()
+                .map(|r| r.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+        let contracts = RetrievalOptions::new().with_collections(["contracts"]);
+        assert_eq!(ids(store.retrieve_with(&req, &contracts)), vec!["k2"]);
+        let both = contracts.clone().with_collections(["support-tickets"]);
+        assert_eq!(ids(store.retrieve_with(&req, &both)), vec!["k1", "k2"]);
+        assert_eq!(store.retrieve_with(&req, &RetrievalOptions::new()).len(), 3);
+        let parsed = parse_query("collection:support-tickets renewal terms").unwrap();
+        assert_eq!(ids(store.retrieve_with(&req, &parsed.options)), vec!["k1"]);
+
+        // Moving a claim to another collection moves it between indexes.
+        store
+            .ingest_bundle_persistent(&mut wal, collected("k1", Some("contracts")), vec![], vec![])
+            .unwrap();
+        assert!(
+            store
+                .claim_ids_in_collection(&"tenant-a".into(), "support-tickets")
+                .is_empty()
+        );
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.claim_ids_in_collection(&"tenant-a".into(), "contracts"),
+            HashSet::from(["k1".into(), "k2".into()])
+        );
+        assert_eq!(replayed.claims["k3"].collection, None);
+
+        let mut blank = collected("k4", Some(" "));
+        assert!(matches!(
+            store.ingest_bundle(blank.clone(), vec![], vec![]),
+            Err(StoreError::Validation(ValidationError::MissingField(
+                "collection"
+            )))
+        ));
+        blank.collection = Some("support tickets".to_string());
+        assert!(matches!(
+            store.ingest_bundle(blank, vec![], vec![]),
+            Err(StoreError::Validation(ValidationError::InvalidRange(
+                "collection"
+            )))
+        ));
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn as_of_lookup_uses_validity_windows_with_open_bounds() {
+        let mut store = InMemoryStore::new();
+        let windowed = |id: &str, valid_from, valid_to| {
+            let mut windowed = claim(id, "the office is in berlin");
+            windowed.valid_from = valid_from;
+            windowed.valid_to = valid_to;
+            windowed
+        };
+        for claim in [
+            windowed("w1", Some(100), Some(200)),
+            windowed("w2", Some(150), None),
+            windowed("w3", None, Some(120)),
+            windowed("w4", None, None),
+            windowed("w5", Some(300), Some(300)),
+        ] {
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        let valid_at = |store: &InMemoryStore, as_of| {
+            let mut ids: Vec<ClaimId> = store
+                .claim_ids_valid_at(&"tenant-a".into(), as_of)
+                .into_iter()
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(valid_at(&store, 50), vec!["w3"]);
+        assert_eq!(valid_at(&store, 100), vec!["w1", "w3"]);
+        assert_eq!(valid_at(&store, 160), vec!["w1", "w2"]);
+        assert_eq!(valid_at(&store, 300), vec!["w2", "w5"]);
+        assert_eq!(valid_at(&store, 301), vec!["w2"]);
+        assert!(store.claim_ids_valid_at(&"tenant-b".into(), 160).is_empty());
+
+        // Closing w2's window takes it out of later lookups.
+        store
+            .ingest_bundle(windowed("w2", Some(150), Some(250)), vec![], vec![])
+            .unwrap();
+        assert_eq!(valid_at(&store, 301), Vec::<String>::new());
+        assert!(as_of::claim_valid_at(&store.claims["w2"], 250));
+
+        let req = RetrievalRequest::new("tenant-a", "office berlin", 10);
+        let results = store.retrieve_with(&req, &RetrievalOptions::new().with_valid_at(110));
+        let mut ids: Vec<&str> = results.iter().map(|r| r.claim_id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec!["w1", "w3"]);
+    }
+
+    #[test]
+    fn tenant_migration_moves_claims_and_settings_to_the_new_ids() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let owned_by =
+            |id: &str, tenant_id: &str| claim_for_tenant(id, "acme shipped the release", tenant_id);
+        for (id, tenant_id) in [("c1", "acme-old"), ("c2", "acme"), ("c3", "globex")] {
+            store
+                .ingest_bundle_persistent(&mut wal, owned_by(id, tenant_id), vec![], vec![])
+                .unwrap();
+        }
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"c1".into(), vec![0.5, 0.5])
+            .unwrap();
+        store
+            .register_tenant_vector_config_persistent(
+                &mut wal,
+                &"acme-old".into(),
+                TenantVectorConfig::new(2),
+            )
+            .unwrap();
+        store
+            .register_tenant_vector_config_persistent(
+                &mut wal,
+                &"acme".into(),
+                TenantVectorConfig::new(3),
+            )
+            .unwrap();
+
+        let renames = BTreeMap::from([("acme-old".to_string(), "acme".to_string())]);
+        assert!(matches!(
+            InMemoryStore::migrate_tenant_ids(&mut wal, &renames),
+            Err(StoreError::Conflict(_))
+        ));
+        let unchanged = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(unchanged.claims["c1"].tenant_id, "acme-old");
+
+        store
+            .register_tenant_vector_config_persistent(
+                &mut wal,
+                &"acme".into(),
+                TenantVectorConfig::new(2),
+            )
+            .unwrap();
+        let (migrated, stats) = InMemoryStore::migrate_tenant_ids(&mut wal, &renames).unwrap();
+        assert_eq!(stats.claims_rewritten, 1);
+        assert_eq!(stats.tenant_settings_rewritten, 1);
+        assert_eq!(migrated.claims["c1"].tenant_id, "acme");
+        assert_eq!(migrated.claims["c3"].tenant_id, "globex");
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(replayed.claims["c1"].tenant_id, "acme");
+        assert_eq!(replayed.tenant_claim_ids["acme"].len(), 2);
+        assert!(!replayed.tenant_claim_ids.contains_key("acme-old"));
+        assert!(!replayed.tenant_vector_configs.contains_key("acme-old"));
+        assert!(replayed.claim_vectors.get("c1").is_some());
+
+        let chained = BTreeMap::from([
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "c".to_string()),
+        ]);
+        assert!(matches!(
+            InMemoryStore::migrate_tenant_ids(&mut wal, &chained),
+            Err(StoreError::Conflict(_))
+        ));
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn retrieve_page_walks_the_ranking_and_rejects_stale_cursors() {
+        let mut store = InMemoryStore::new();
+        for (id, text) in [
+            ("c1", "acme shipped a release"),
+            ("c2", "acme acme shipped a release"),
+            ("c3", "acme hired staff"),
+            ("c4", "acme shipped"),
+            ("c5", "acme opened an office"),
+            ("c6", "globex shipped a release"),
+        ] {
+            store
+                .ingest_bundle(claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let req = |top_k| RetrievalRequest::new("tenant-a", "acme shipped", top_k);
+        let expected: Vec<String> = store
+            .retrieve(&req(100))
+            .into_iter()
+            .map(|result| result.claim_id.into_string())
+            .collect();
+        assert!(expected.len() > 4);
+
+        let mut paged = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = store
+                .retrieve_page(&req(2), &RetrievalOptions::new(), cursor.as_ref())
+                .unwrap();
+            assert!(page.results.len() <= 2);
+            paged.extend(page.results.into_iter().map(|result| result.claim_id));
+            let Some(next) = page.next_cursor else {
+                break;
+            };
+            let token = next.encode();
+            cursor = Some(RetrievalCursor::decode(&token).unwrap());
+            assert_eq!(cursor.as_ref(), Some(&next));
+        }
+        assert_eq!(paged, expected);
+
+        // Later pages keep the first page's `valid_at` and the filters.
+        for (id, valid_from, valid_to) in [("v0", 0, 100), ("v1", 0, 200), ("v2", 150, 300)] {
+            let mut windowed = claim(id, "acme shipped");
+            windowed.valid_from = Some(valid_from);
+            windowed.valid_to = Some(valid_to);
+            store.ingest_bundle(windowed, vec![], vec![]).unwrap();
+        }
+        let options = RetrievalOptions::new().with_valid_at(50);
+        let first = store.retrieve_page(&req(1), &options, None).unwrap();
+        let cursor = first.next_cursor.expect("two claims valid at 50");
+        assert_eq!(cursor.valid_at, Some(50));
+        let later = RetrievalOptions::new().with_valid_at(175);
+        let second = store.retrieve_page(&req(1), &later, Some(&cursor)).unwrap();
+        let mut valid: Vec<String> = first
+            .results
+            .into_iter()
+            .chain(second.results)
+            .map(|result| result.claim_id.into_string())
+            .collect();
+        valid.sort();
+        assert_eq!(valid, vec!["v0", "v1"]);
+        assert!(second.next_cursor.is_none());
+
+        let first = store
+            .retrieve_page(&req(2), &RetrievalOptions::new(), None)
+            .unwrap();
+        let stale = first.next_cursor.expect("more than one page");
+        store
+            .ingest_bundle(claim("c7", "acme shipped again"), vec![], vec![])
+            .unwrap();
+        assert!(matches!(
+            store.retrieve_page(&req(2), &RetrievalOptions::new(), Some(&stale)),
+            Err(StoreError::StaleCursor(_))
+        ));
+        assert!(matches!(
+            RetrievalCursor::decode("c2.zz.-.0.0.00"),
+            Err(StoreError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn outbox_tracks_consumer_offsets_across_reopen_and_pruning() {
+        let wal = temp_wal_path();
+        let path = wal.with_extension("outbox");
+        let mut outbox = Outbox::open(&path).unwrap();
+        let last = outbox
+            .append([
+                ("tenant-a", "c1", OutboxOp::Ingest),
+                ("tenant-a", "c2", OutboxOp::Ingest),
+                ("tenant-b", "c\t3", OutboxOp::Delete),
+            ])
+            .unwrap();
+        assert_eq!(last, 3);
+
+        let polled = outbox.poll("kafka", 2).unwrap();
+        let lsns: Vec<u64> = polled.iter().map(|event| event.lsn).collect();
+        assert_eq!(lsns, vec![1, 2]);
+        assert_eq!(outbox.ack("kafka", 2).unwrap(), 2);
+        assert_eq!(outbox.ack("kafka", 1).unwrap(), 2);
+        assert!(matches!(
+            outbox.ack("kafka", 4),
+            Err(StoreError::Conflict(_))
+        ));
+        assert_eq!(outbox.ack("audit", 3).unwrap(), 3);
+        assert_eq!(outbox.prune_acknowledged().unwrap(), 2);
+
+        let mut reopened = Outbox::open(&path).unwrap();
+        assert_eq!(reopened.last_lsn(), 3);
+        assert_eq!(reopened.acked_lsn("kafka"), 2);
+        let remaining = reopened.poll("kafka", 10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].claim_id, "c\t3");
+        assert_eq!(remaining[0].op, OutboxOp::Delete);
+        assert_eq!(reopened.poll("new-consumer", 10).unwrap().len(), 1);
+
+        reopened.ack("kafka", 3).unwrap();
+        assert_eq!(reopened.prune_acknowledged().unwrap(), 1);
+        let reopened = Outbox::open(&path).unwrap();
+        assert_eq!(reopened.last_lsn(), 3);
+        let _ = std::fs::remove_file(reopened.offsets_path());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn retrieve_with_result_fields_leaves_out_unselected_fields() {
+        let mut store = InMemoryStore::new();
+        let evidence = Evidence {
+            evidence_id: "e1".into(),
+            claim_id: "c1".into(),
+            source_id: "doc-1".into(),
+            stance: Stance::Supports,
+            source_quality: 0.9,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        store
+            .ingest_bundle(
+                claim("c1", "Company X acquired Company Y"),
+                vec![evidence],
+                vec![],
+            )
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "company x acquired", 5);
+        let retrieve =
+            |fields| store.retrieve_with(&req, &RetrievalOptions::new().with_fields(fields));
+
+        let full = retrieve(ResultFields::Full);
+        assert_eq!(full, store.retrieve(&req));
+        assert_eq!(full[0].citations.len(), 1);
+
+        let no_citations = retrieve(ResultFields::NoCitations);
+        assert_eq!(
+            no_citations[0].canonical_text,
+            "Company X acquired Company Y"
+        );
+        assert!(no_citations[0].citations.is_empty());
+        assert_eq!(no_citations[0].supports, 1);
+
+        let ids_only = retrieve(ResultFields::IdsOnly);
+        assert_eq!(ids_only[0].claim_id, "c1");
+        assert_eq!(ids_only[0].score, full[0].score);
+        assert!(ids_only[0].canonical_text.is_empty());
+        assert!(ids_only[0].citations.is_empty());
+
+        let candidates = HashSet::from([ClaimId::from("c1")]);
+        let explicit = store.retrieve_candidates_with_result_fields(
+            &req,
+            (None, None),
+            None,
+            &candidates,
+            None,
+            ResultFields::IdsOnly,
+        );
+        assert_eq!(explicit, ids_only);
+        assert_eq!(ResultFields::parse("ids"), Some(ResultFields::IdsOnly));
+        assert_eq!(ResultFields::parse("summary"), None);
+    }
+
+    #[test]
+    fn term_index_keeps_postings_and_bm25_stats_in_step_with_claims() {
+        let mut store = InMemoryStore::new();
+        for idx in 0..200 {
+            let text = if idx % 2 == 0 {
+                "alpha beta beta"
+            } else {
+                "gamma"
+            };
+            store
+                .ingest_bundle(claim(&format!("c{idx}"), text), vec![], vec![])
+                .unwrap();
+        }
+        // Re-ingesting a claim with new text replaces its postings.
+        store
+            .ingest_bundle(claim("c0", "gamma gamma delta"), vec![], vec![])
+            .unwrap();
+
+        let index = &store.inverted_index["tenant-a"];
+        assert_eq!(index.doc_count(), 200);
+        assert_eq!(index.doc_freq("beta"), 99);
+        assert_eq!(index.doc_freq("gamma"), 101);
+        assert_eq!(index.doc_freq("delta"), 1);
+        let total_len: usize = store.claim_tokens.values().map(Vec::len).sum();
+        assert_eq!(index.avg_doc_len(), total_len as f32 / 200.0);
+
+        let gamma: Vec<(&str, u32)> = index.postings("gamma").collect();
+        assert_eq!(gamma.len(), 101);
+        assert!(gamma.contains(&("c199", 1)));
+        assert!(gamma.contains(&("c0", 2)));
+        assert!(
+            index
+                .postings("beta")
+                .all(|(claim_id, freq)| claim_id != "c0" && freq == 2)
+        );
+
+        let req = RetrievalRequest::new("tenant-a", "delta", 1);
+        assert_eq!(store.retrieve(&req)[0].claim_id, "c0");
+
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(claim("c1", "alpha"), vec![], vec![])
+            .unwrap();
+        store
+            .ingest_bundle(claim("c1", "beta"), vec![], vec![])
+            .unwrap();
+        let index = &store.inverted_index["tenant-a"];
+        assert_eq!((index.doc_count(), index.term_count()), (1, 1));
+        assert_eq!(index.doc_freq("alpha"), 0);
+    }
+
+    #[test]
+    fn file_wal_runs_over_a_memory_backend() {
+        let backend = MemoryWalBackend::new();
+        let mut wal = FileWal::with_backend(backend.clone(), WalWritePolicy::default()).unwrap();
+        assert_eq!(wal.path(), Path::new(""));
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c1", "Company X acquired Y"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(backend.record_count().unwrap(), 1);
+        assert_eq!(backend.sync_count(), 1);
+
+        let point = wal.begin_rollback_point().unwrap();
+        wal.append_claim(&claim("c2", "Company Z")).unwrap();
+        wal.rollback_to(point).unwrap();
+        assert_eq!(backend.record_count().unwrap(), 1);
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        assert_eq!(backend.record_count().unwrap(), 0);
+        // The checkpoint record, then c1.
+        assert_eq!(backend.read_snapshot().unwrap().len(), 2);
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c3", "Company Q"), vec![], vec![])
+            .unwrap();
+        drop(wal);
+
+        let reopened = FileWal::with_backend(backend.clone(), WalWritePolicy::default()).unwrap();
+        assert_eq!(reopened.wal_record_count().unwrap(), 1);
+        let loaded = InMemoryStore::load_from_wal(&reopened).unwrap();
+        assert_eq!(loaded.claims_len(), 2);
+        assert!(
+            loaded
+                .claims_for_tenant(&"tenant-a".into())
+                .iter()
+                .all(|c| c.claim_id != "c2")
+        );
+    }
+
+    #[test]
+    fn file_wal_install_interrupted_between_renames_is_finished_on_open() {
+        let checkpointed = MemoryWalBackend::new();
+        let mut wal =
+            FileWal::with_backend(checkpointed.clone(), WalWritePolicy::default()).unwrap();
+        let mut store = InMemoryStore::new();
+        for (claim_id, text) in [("c1", "Company X"), ("c2", "Company Y")] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim(claim_id, text), vec![], vec![])
+                .unwrap();
+        }
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c3", "Company Z"), vec![], vec![])
+            .unwrap();
+        drop(wal);
+
+        let path = temp_wal_path();
+        let mut wal = FileWal::open(&path).unwrap();
+        InMemoryStore::new()
+            .ingest_bundle_persistent(&mut wal, claim("c1", "Company X"), vec![], vec![])
+            .unwrap();
+        drop(wal);
+
+        // Stage the checkpoint, rename the snapshot, then stop as if the
+        // process died before the log was renamed.
+        let backend = FileWalBackend::open(&path).unwrap();
+        backend
+            .stage_install(
+                &checkpointed.read_snapshot().unwrap(),
+                &checkpointed.read_from(0).unwrap(),
+            )
+            .unwrap();
+        let snapshot_path = backend.snapshot_path();
+        std::fs::rename(wal::sibling_tmp_path(&snapshot_path), &snapshot_path).unwrap();
+        assert!(read_to_string(&path).unwrap().contains("c1"));
+
+        let wal = FileWal::open(&path).unwrap();
+        assert_eq!(wal.wal_record_count().unwrap(), 1);
+        let loaded = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(loaded.claims_len(), 3);
+        assert!(!wal::sibling_tmp_path(&path).exists());
+    }
+
+    #[test]
+    fn wal_migration_dual_writes_verifies_and_cuts_over() {
+        let path = temp_wal_path();
+        let text_log = FileWalBackend::open(&path).unwrap();
+        let mut wal = FileWal::with_backend(text_log.clone(), WalWritePolicy::default()).unwrap();
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c1", "Company X acquired Y"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        drop(wal);
+
+        let new_log = MemoryWalBackend::new();
+        let migration =
+            WalMigration::start(text_log.clone(), new_log.clone(), Duration::ZERO).unwrap();
+        assert_eq!(new_log.record_count().unwrap(), 1);
+        let mut wal =
+            FileWal::with_backend(migration.dual_write_backend(), WalWritePolicy::default())
+                .unwrap();
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c2", "Company Z"), vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"c2".into(), vec![0.1, 0.2])
+            .unwrap();
+        drop(wal);
+        // The checkpoint record, then c1.
+        assert_eq!(new_log.read_snapshot().unwrap().len(), 2);
+
+        let verification = migration.verify().unwrap();
+        assert!(verification.is_consistent());
+        assert_eq!(verification.primary_records, 3);
+        assert_eq!(verification.secondary_records, 3);
+        let waiting = migration.clone().with_started_unix_ms(u64::MAX);
+        assert!(!waiting.dual_write_period_elapsed());
+        assert!(matches!(waiting.cutover(), Err(StoreError::Conflict(_))));
+
+        let mut stray = FileWal::with_backend(new_log.clone(), WalWritePolicy::default()).unwrap();
+        stray
+            .append_claim(&claim("c3", "Only in the new log"))
+            .unwrap();
+        drop(stray);
+        assert_eq!(migration.verify().unwrap().mismatched_claim_ids, vec!["c3"]);
+        assert!(matches!(
+            migration.clone().cutover(),
+            Err(StoreError::Conflict(_))
+        ));
+        new_log.clone().truncate_after(2).unwrap();
+
+        let cut_over = migration.cutover().unwrap();
+        assert!(!path.exists());
+        let wal = FileWal::with_backend(cut_over, WalWritePolicy::default()).unwrap();
+        let loaded = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(loaded.claims_len(), 2);
+        assert!(loaded.claim_vectors.contains_key("c2"));
+    }
+
+    #[test]
+    fn wal_migration_records_a_failed_secondary_write_instead_of_failing() {
+        let old_log = MemoryWalBackend::new();
+        let path = temp_wal_path();
+        let new_log = FileWalBackend::open(&path).unwrap();
+        let mut migration =
+            WalMigration::start(old_log.clone(), new_log.clone(), Duration::ZERO).unwrap();
+        let backend = migration.dual_write_backend();
+        let mut wal = FileWal::with_backend(backend.clone(), WalWritePolicy::default()).unwrap();
+        let mut store = InMemoryStore::new();
+
+        // The new log's file turns into a directory, so appends to it fail.
+        remove_file(&path).unwrap();
+        std::fs::create_dir(&path).unwrap();
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c1", "Company X"), vec![], vec![])
+            .unwrap();
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c2", "Company Y"), vec![], vec![])
+            .unwrap();
+        drop(wal);
+        assert_eq!(old_log.record_count().unwrap(), 2);
+        assert!(backend.divergence().is_some());
+        assert!(matches!(
+            migration.clone().cutover(),
+            Err(StoreError::Conflict(reason)) if reason.contains("diverged")
+        ));
+
+        std::fs::remove_dir(&path).unwrap();
+        migration.resync().unwrap();
+        assert!(backend.divergence().is_none());
+        let verification = migration.verify().unwrap();
+        assert!(verification.is_consistent());
+        assert_eq!(verification.secondary_records, 2);
+        migration.cutover().unwrap();
+        let _ = remove_file(&path);
+        let _ = remove_file(new_log.snapshot_path());
+    }
+
+    #[test]
+    fn phrase_queries_require_tokens_in_order_within_slop() {
+        let mut store = InMemoryStore::new();
+        for (id, text) in [
+            ("c1", "Company X acquired Company Y in 2024"),
+            ("c2", "Company Y said X acquired nothing"),
+            ("c3", "Company X quietly acquired a startup"),
+            ("c4", "acquired company x shares"),
+        ] {
+            store
+                .ingest_bundle(claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let ids = |store: &InMemoryStore, query: &str| {
+            let req = RetrievalRequest::new("tenant-a", query, 10);
+            let mut ids: Vec<String> = store
+                .retrieve(&req)
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(ids(&store, "company x acquired").len(), 4);
+        assert_eq!(ids(&store, "\"company x acquired\""), vec!["c1"]);
+        assert_eq!(ids(&store, "\"company x acquired\"~1"), vec!["c1", "c3"]);
+        assert_eq!(ids(&store, "\"company x\" \"acquired a\""), vec!["c3"]);
+        assert!(ids(&store, "\"x company\"").is_empty());
+
+        assert_eq!(
+            phrase::parse_analyzed_phrase_queries(
+                "\"Company  X\"~3 acquired \"\" \"open",
+                &TextAnalyzer::default()
+            ),
+            vec![PhraseQuery {
+                tokens: vec!["company".into(), "x".into()],
+                slop: 3,
+            }]
+        );
+
+        // Removing a claim drops its positions with it.
+        store
+            .ingest_bundle(claim("c1", "Company Y acquired X"), vec![], vec![])
+            .unwrap();
+        assert!(ids(&store, "\"company x acquired\"").is_empty());
+        assert_eq!(ids(&store, "\"y acquired x\""), vec!["c1"]);
+    }
+
+    #[test]
+    fn read_repair_removes_index_entries_for_missing_claims() {
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(claim("c1", "alpha beta"), vec![], vec![])
+            .unwrap();
+        store
+            .ingest_bundle(claim("c2", "alpha gamma"), vec![], vec![])
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "alpha", 10);
+
+        // Simulate a partial delete: the claim is gone but its index
+        // entries are not.
+        store.claims.remove("c1");
+        assert_eq!(store.retrieve(&req).len(), 1);
+        assert_eq!(store.pending_read_repairs(), 0);
+        assert_eq!(store.metrics_snapshot().stale_index_entries, 0);
+
+        store.set_read_repair(true);
+        assert_eq!(store.retrieve(&req).len(), 1);
+        store.retrieve(&req);
+        assert_eq!(store.pending_read_repairs(), 1);
+        assert_eq!(
+            store.pending_read_repair_entries(),
+            vec![StaleIndexEntry {
+                tenant_id: "tenant-a".into(),
+                claim_id: "c1".into(),
+            }]
+        );
+        assert_eq!(store.metrics_snapshot().stale_index_entries, 1);
+        assert!(store.tenant_claim_ids["tenant-a"].contains("c1"));
+
+        // The next write applies the queued repair.
+        store
+            .ingest_bundle(claim("c3", "delta"), vec![], vec![])
+            .unwrap();
+        assert_eq!(store.pending_read_repairs(), 0);
+        assert_eq!(store.metrics_snapshot().read_repairs, 1);
+        assert!(!store.tenant_claim_ids["tenant-a"].contains("c1"));
+        assert!(!store.claim_tokens.contains_key("c1"));
+        let index = &store.inverted_index["tenant-a"];
+        assert_eq!(
+            (
+                index.doc_count(),
+                index.doc_freq("alpha"),
+                index.doc_freq("beta")
+            ),
+            (2, 1, 0)
+        );
+
+        // A claim ingested again before the repair runs keeps its entries.
+        store.claims.remove("c2");
+        store.retrieve(&req);
+        store.claims.insert("c2".into(), claim("c2", "alpha gamma"));
+        assert_eq!(store.apply_read_repairs(), 0);
+        assert_eq!(store.retrieve(&req)[0].claim_id, "c2");
+    }
+
+    #[test]
+    fn tenant_score_normalization_brings_scores_into_range_at_ingest() {
+        let evidence = |quality: f32| Evidence {
+            evidence_id: "e1".into(),
+            claim_id: "c1".into(),
+            source_id: "doc-1".into(),
+            stance: Stance::Supports,
+            source_quality: quality,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        let scored = |confidence: f32| {
+            let mut scored = claim("c1", "Company X acquired Company Y");
+            scored.confidence = confidence;
+            scored
+        };
+
+        let mut store = InMemoryStore::new();
+        assert!(matches!(
+            store.ingest_bundle(scored(87.0), vec![], vec![]),
+            Err(StoreError::Validation(_))
+        ));
+
+        store.set_tenant_score_normalization(
+            &"tenant-a".into(),
+            Some(TenantScoreNormalization {
+                confidence: ScoreNormalization::Rescale(ScoreScale::Percent),
+                source_quality: ScoreNormalization::Clamp,
+            }),
+        );
+        store
+            .ingest_bundle(scored(87.0), vec![evidence(1.4)], vec![])
+            .unwrap();
+        assert!((store.claims["c1"].confidence - 0.87).abs() < 1e-6);
+        assert_eq!(store.evidence_by_claim["c1"][0].source_quality, 1.0);
+
+        store.set_tenant_score_normalization(
+            &"tenant-a".into(),
+            Some(TenantScoreNormalization {
+                confidence: ScoreNormalization::Rescale(ScoreScale::Logit),
+                source_quality: ScoreNormalization::Strict,
+            }),
+        );
+        store.ingest_bundle(scored(0.0), vec![], vec![]).unwrap();
+        assert_eq!(store.claims["c1"].confidence, 0.5);
+        assert!(matches!(
+            store.ingest_bundle(scored(0.0), vec![evidence(-0.2)], vec![]),
+            Err(StoreError::Validation(_))
+        ));
+        assert!(matches!(
+            store.ingest_bundle(scored(f32::NAN), vec![], vec![]),
+            Err(StoreError::Validation(_))
+        ));
+
+        // Other tenants keep strict validation, and `None` restores it.
+        assert_eq!(
+            store.tenant_score_normalization(&"tenant-b".into()),
+            TenantScoreNormalization::default()
+        );
+        store.set_tenant_score_normalization(&"tenant-a".into(), None);
+        assert!(matches!(
+            store.ingest_bundle(scored(87.0), vec![], vec![]),
+            Err(StoreError::Validation(_))
+        ));
+        assert_eq!(
+            ScoreNormalization::parse("Rescale_Percent"),
+            Some(ScoreNormalization::Rescale(ScoreScale::Percent))
+        );
+    }
+
+    #[test]
+    fn tenant_text_analyzer_applies_at_index_and_query_time_and_survives_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        for (id, text) in [
+            ("c1", "Company X acquired the startups"),
+            ("c2", "Company Y is acquiring a startup"),
+            ("c3", "Weather of the week"),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim_for_tenant("b1", "Company Z acquires startups", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let ids = |store: &InMemoryStore, tenant: &str, query: &str| {
+            let req = RetrievalRequest::new(tenant, query, 10);
+            let mut ids: Vec<String> = store
+                .retrieve(&req)
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            ids(&store, "tenant-a", "\"acquires startup\""),
+            Vec::<String>::new()
+        );
+
+        assert_eq!(
+            store
+                .set_text_analyzer_persistent(&mut wal, &"tenant-a".into(), TextAnalyzer::english())
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            store.claim_tokens["c1"],
+            vec!["company", "x", "acquir", "startup"]
+        );
+        assert_eq!(
+            store.claim_tokens["b1"],
+            vec!["company", "z", "acquires", "startups"]
+        );
+        assert_eq!(
+            ids(&store, "tenant-a", "\"acquires startup\""),
+            vec!["c1", "c2"]
+        );
+        assert_eq!(
+            ids(&store, "tenant-a", "\"company y acquire\"~1"),
+            vec!["c2"]
+        );
+        // A stopword-only query has no terms left, like an empty one.
+        assert_eq!(ids(&store, "tenant-a", "the of").len(), 3);
+        assert_eq!(store.inverted_index["tenant-a"].doc_freq("the"), 0);
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.text_analyzer(&"tenant-a".into()),
+            TextAnalyzer::english()
+        );
+        assert_eq!(
+            replayed.text_analyzer(&"tenant-b".into()),
+            TextAnalyzer::default()
+        );
+        assert_eq!(replayed.claim_tokens, store.claim_tokens);
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        let compacted = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(compacted.claim_tokens, store.claim_tokens);
+        assert_eq!(
+            ids(&compacted, "tenant-a", "\"acquires startup\""),
+            vec!["c1", "c2"]
+        );
+
+        // Going back to plain tokenization re-indexes the tenant again.
+        store.set_text_analyzer(&"tenant-a".into(), TextAnalyzer::default());
+        assert_eq!(
+            store.claim_tokens["c3"],
+            vec!["weather", "of", "the", "week"]
+        );
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn claim_language_limits_english_analysis_and_survives_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        store
+            .set_text_analyzer_persistent(&mut wal, &"tenant-a".into(), TextAnalyzer::english())
+            .unwrap();
+        let tagged = |id: &str, text: &str, language: &str| Claim {
+            language: Some(language.into()),
+            ..claim(id, text)
+        };
+        let evidence = Evidence {
+            evidence_id: "e1".into(),
+            claim_id: "c2".into(),
+            source_id: "source://le-monde".into(),
+            stance: Stance::Supports,
+            source_quality: 0.9,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: Some("fr-FR".into()),
+            stance_strength: None,
+            negated: false,
+        };
+        assert!(matches!(
+            store.ingest_bundle(tagged("c0", "Company X", "en_GB"), vec![], vec![]),
+            Err(StoreError::Validation(ValidationError::InvalidRange(
+                "language"
+            )))
+        ));
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                tagged("c1", "The company acquired startups", "en-GB"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                tagged("c2", "La société a acquis des startups", "fr"),
+                vec![evidence.clone()],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(
+            store.claim_tokens["c1"],
+            vec!["company", "acquir", "startup"]
+        );
+        assert_eq!(
+            store.claim_tokens["c2"],
+            vec!["la", "socit", "a", "acquis", "des", "startups"]
+        );
+
+        wal.flush_pending_sync().unwrap();
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(replayed.claims["c2"].language.as_deref(), Some("fr"));
+        assert_eq!(replayed.evidence_by_claim["c2"], vec![evidence]);
+
+        // Re-indexing under another analyzer keeps honoring the tags.
+        store.set_text_analyzer(&"tenant-a".into(), TextAnalyzer::default());
+        store.set_text_analyzer(&"tenant-a".into(), TextAnalyzer::english());
+        assert_eq!(store.claim_tokens, replayed.claim_tokens);
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn unicode_tokenizer_makes_cjk_claims_retrievable() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        store
+            .set_text_analyzer_persistent(
+                &mut wal,
+                &"tenant-a".into(),
+                TextAnalyzer::multilingual(),
+            )
+            .unwrap();
+        for (id, text) in [
+            ("c1", "甲公司收购了乙公司"),
+            ("c2", "乙公司发布了新产品"),
+            ("c3", "Société Générale acquired 株式会社"),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let ids = |store: &InMemoryStore, query: &str| {
+            let req = RetrievalRequest::new("tenant-a", query, 10);
+            let mut ids: Vec<String> = store
+                .retrieve(&req)
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(ids(&store, "\"公司收购\""), vec!["c1"]);
+        assert_eq!(ids(&store, "\"乙公司\""), vec!["c1", "c2"]);
+        assert_eq!(ids(&store, "\"société générale\""), vec!["c3"]);
+        assert_eq!(ids(&store, "\"会社\""), vec!["c3"]);
+        let req = RetrievalRequest::new("tenant-a", "新产品", 1);
+        assert_eq!(store.retrieve(&req)[0].claim_id, "c2");
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.text_analyzer(&"tenant-a".into()).tokenizer,
+            TokenizerKind::Unicode { ngram: 2 }
+        );
+        assert_eq!(ids(&replayed, "\"公司收购\""), vec!["c1"]);
+        assert_eq!(
+            TokenizerKind::parse("unicode:3"),
+            Some(TokenizerKind::Unicode { ngram: 3 })
+        );
+        assert_eq!(TokenizerKind::parse("unicode:0"), None);
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn query_language_compiles_filters_into_retrieval_options() {
+        let parsed = parse_query(
+            "entity:\"Company X\" type:factual after:2025-01-01 acquisition \"rival bid\"~2 \
+             meta:region=EU before:1767225600 min_confidence:0.5 http://example.com",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.text,
+            "acquisition \"rival bid\"~2 http://example.com"
+        );
+        assert_eq!(
+            parsed.options,
+            RetrievalOptions {
+                time_range: (Some(1_735_689_600), Some(1_767_225_599)),
+                entities: vec!["Company X".into()],
+                claim_types: vec![ClaimType::Factual],
+                metadata: vec![MetadataFilter::equals("region", "EU")],
+                confidence: ConfidenceRange::at_least(0.5),
+                ..RetrievalOptions::default()
+            }
+        );
+        for invalid in [
+            "type:rumor x",
+            "entity:\"open x",
+            "meta:region x",
+            "after:yesterday x",
+            "after:2025-02-01 before:2025-01-01 x",
+            "min_confidence:1.5 x",
+            "entity: x",
+        ] {
+            assert!(
+                matches!(parse_query(invalid), Err(StoreError::Parse(_))),
+                "{invalid}"
+            );
+        }
+
+        let mut store = InMemoryStore::new();
+        let tagged = |id: &str, entity: &str, claim_type: ClaimType, ts: i64| Claim {
+            entities: vec![entity.into()],
+            claim_type: Some(claim_type),
+            event_time_unix: Some(ts),
+            ..claim(id, "Company X acquisition announced")
+        };
+        for claim in [
+            tagged("c1", "Company X", ClaimType::Factual, 1_740_000_000),
+            tagged("c2", "Company X", ClaimType::Opinion, 1_740_000_000),
+            tagged("c3", "Company X", ClaimType::Factual, 1_700_000_000),
+            tagged("c4", "Company Y", ClaimType::Factual, 1_740_000_000),
+        ] {
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        let parsed =
+            parse_query("entity:\"company x\" type:factual after:2025-01-01 acquisition").unwrap();
+        let results = store.retrieve_with(&parsed.request(&"tenant-a".into(), 10), &parsed.options);
+        let ids: Vec<&str> = results
+            .iter()
+            .map(|result| result.claim_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c1"]);
+        assert!(RetrievalOptions::default().is_unfiltered());
+        let unfiltered = RetrievalOptions::default();
+        assert_eq!(
+            store.allowed_claim_ids_for_options(&"tenant-a".into(), &unfiltered),
+            None
+        );
+    }
+
+    #[test]
+    fn verify_integrity_reports_index_entries_without_claims() {
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(claim("c1", "Company X acquired Company Y"), vec![], vec![])
+            .unwrap();
+        let report = store.verify_integrity();
+        assert!(report.is_clean());
+        assert_eq!(report.claims_checked, 1);
+
+        store
+            .tenant_claim_ids
+            .get_mut("tenant-a")
+            .unwrap()
+            .insert("ghost".to_string());
+        store.claims.remove("c1");
+        let report = store.verify_integrity();
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.stale_index_entries,
+            vec![
+                ("tenant-a".to_string(), "c1".to_string()),
+                ("tenant-a".to_string(), "ghost".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn term_bloom_skips_queries_whose_tokens_never_occur_in_the_tenant() {
+        let mut store = InMemoryStore::new();
+        for i in 0..200 {
+            let text = format!("Company X filing term{i}");
+            store
+                .ingest_bundle(claim(&format!("c{i}"), &text), vec![], vec![])
+                .unwrap();
+        }
+        let index = &store.inverted_index["tenant-a"];
+        assert!((0..200).all(|i| index.may_contain(&format!("term{i}"))));
+
+        let req = |query: &str| RetrievalRequest::new("tenant-a", query, 5);
+        assert!(store.retrieve(&req("unrelated zebra")).is_empty());
+        assert_eq!(store.metrics_snapshot().term_bloom_skips, 1);
+
+        let results = store.retrieve(&req("term7 zebra"));
+        assert_eq!(results[0].claim_id, "c7");
+        assert_eq!(store.metrics_snapshot().term_bloom_skips, 1);
+
+        // A deleted claim's terms stay in the filter; a query that passes
+        // it but finds no postings still falls back to the tenant scan.
+        store
+            .delete_claim(&"tenant-a".into(), &"c199".into())
+            .unwrap();
+        let index = &store.inverted_index["tenant-a"];
+        assert!(index.may_contain("term199"));
+        assert_eq!(index.postings("term199").count(), 0);
+        assert_eq!(store.retrieve(&req("term199")).len(), 5);
+        assert_eq!(store.metrics_snapshot().term_bloom_skips, 1);
+    }
+
+    #[test]
+    fn retrieve_batch_matches_individual_retrievals_in_request_order() {
+        let mut store = InMemoryStore::new();
+        let bundles = [
+            ("c1", "Company X acquired Company Y", "tenant-a"),
+            ("c2", "Company X opened an office in Berlin", "tenant-a"),
+            ("c3", "Company Z acquired Company W", "tenant-b"),
+        ];
+        for (id, text, tenant) in bundles {
+            store
+                .ingest_bundle(claim_for_tenant(id, text, tenant), vec![], vec![])
+                .unwrap();
+        }
+        let req =
+            |tenant: &str, query: &str, top_k: usize| RetrievalRequest::new(tenant, query, top_k);
+        let requests = vec![
+            req("tenant-a", "company x", 5),
+            req("tenant-b", "acquired", 5),
+            req("tenant-a", "company x", 1),
+            req("tenant-a", "\"office in berlin\"", 5),
+        ];
+
+        let batch = store.retrieve_batch(&requests);
+        let individual: Vec<Vec<RetrievalResult>> =
+            requests.iter().map(|req| store.retrieve(req)).collect();
+        assert_eq!(batch, individual);
+        assert_eq!(batch[0].len(), 2);
+        assert_eq!(batch[1][0].claim_id, "c3");
+        assert_eq!(batch[2].len(), 1);
+        assert_eq!(batch[3][0].claim_id, "c2");
+        assert!(store.retrieve_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn deleted_claims_stay_deleted_after_wal_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c1", "Company X acquired Y"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle_persistent(&mut wal, claim("c2", "Company X hired Z"), vec![], vec![])
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"c1".into(), vec![0.1, 0.2])
+            .unwrap();
+
+        let c1 = ClaimId::from("c1");
+        assert!(
+            !store
+                .delete_claim_persistent(&mut wal, &"tenant-b".into(), &c1)
+                .unwrap()
+        );
+        assert!(
+            store
+                .delete_claim_persistent(&mut wal, &"tenant-a".into(), &c1)
+                .unwrap()
+        );
+        assert!(
+            !store
+                .delete_claim_persistent(&mut wal, &"tenant-a".into(), &c1)
+                .unwrap()
+        );
+        assert!(store.verify_integrity().is_clean());
+
+        let req = RetrievalRequest::new("tenant-a", "company x", 5);
+        let ids = |store: &InMemoryStore| {
+            store
+                .retrieve(&req)
+                .into_iter()
+                .map(|result| result.claim_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&store), vec!["c2"]);
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(ids(&replayed), vec!["c2"]);
+        assert!(replayed.verify_integrity().is_clean());
+
+        replayed.checkpoint_and_compact(&mut wal).unwrap();
+        let compacted = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(ids(&compacted), vec!["c2"]);
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn dash_memory_remembers_recalls_and_forgets_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = Arc::new(embeddings::HashEmbeddingProvider::new(16));
+        let mut memory = DashMemory::open_dir(dir.path())
+            .unwrap()
+            .with_embedder(embedder.clone());
+        let kept = memory
+            .remember(&"tenant-a".into(), "the user prefers dark mode")
+            .unwrap();
+        let forgotten = memory
+            .remember(&"tenant-a".into(), "the user lives in berlin")
+            .unwrap();
+        memory
+            .remember(&"tenant-b".into(), "dark mode is unsupported")
+            .unwrap();
+
+        let recalled = memory.recall(&"tenant-a".into(), "dark mode");
+        assert_eq!(recalled[0].claim_id, kept);
+        assert!(
+            recalled
+                .iter()
+                .all(|result| result.claim_id == kept || result.claim_id == forgotten)
+        );
+        assert!(
+            memory
+                .forget(&"tenant-a".into(), &ClaimId::from(&forgotten))
+                .unwrap()
+        );
+        assert!(
+            !memory
+                .forget(&"tenant-a".into(), &ClaimId::from(&forgotten))
+                .unwrap()
+        );
+        assert!(memory.maintain().unwrap().checkpoint.is_none());
+        drop(memory);
+
+        let memory = DashMemory::open_dir(dir.path())
+            .unwrap()
+            .with_embedder(embedder);
+        let ids: Vec<String> = memory
+            .recall_top_k(&"tenant-a".into(), "user", 10)
+            .into_iter()
+            .map(|result| result.claim_id.into_string())
+            .collect();
+        assert_eq!(ids, vec![kept.clone()]);
+        assert_eq!(
+            memory.store().claims_for_tenant(&"tenant-b".into()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn retrieve_with_matches_the_positional_retrieve_methods() {
+        let mut store = InMemoryStore::new();
+        for (id, text, ts) in [
+            ("c1", "Company X acquired Company Y", 100),
+            ("c2", "Company X acquisition closed", 200),
+            ("c3", "Company X acquisition rumored", 300),
+        ] {
+            let claim = Claim {
+                event_time_unix: Some(ts),
+                valid_from: Some(ts),
+                ..claim(id, text)
+            };
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "company x acquisition", 10);
+        let ids = |results: Vec<RetrievalResult>| -> Vec<String> {
+            results
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect()
+        };
+        let allowed: HashSet<ClaimId> = ["c1", "c2"].into_iter().map(ClaimId::from).collect();
+        let candidates: HashSet<ClaimId> = ["c2", "c3"].into_iter().map(ClaimId::from).collect();
+
+        assert_eq!(
+            ids(store.retrieve_with(&req, &RetrievalOptions::new())),
+            ids(store.retrieve(&req))
+        );
+        assert_eq!(
+            ids(store.retrieve_with(
+                &req,
+                &RetrievalOptions::new()
+                    .with_time_range(Some(150), None)
+                    .with_allowed_claim_ids(Some(&allowed)),
+            )),
+            ids(
+                store.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
+                    &req,
+                    Some(150),
+                    None,
+                    None,
+                    Some(&allowed),
+                )
+            )
+        );
+        let narrowed = store.retrieve_with(
+            &req,
+            &RetrievalOptions::new()
+                .with_candidate_claim_ids(&candidates)
+                .with_allowed_claim_ids(Some(&allowed))
+                .with_valid_at(250)
+                .with_fields(ResultFields::IdsOnly),
+        );
+        assert_eq!(ids(narrowed.clone()), vec!["c2".to_string()]);
+        assert!(narrowed[0].canonical_text.is_empty());
+        assert!(!RetrievalOptions::new().with_valid_at(250).is_unfiltered());
+    }
+
+    #[test]
+    fn retrieve_explained_breaks_scores_into_their_components() {
+        let mut store = InMemoryStore::new();
+        let contradicting = Evidence {
+            evidence_id: "e1".into(),
+            claim_id: "c2".into(),
+            source_id: "doc-1".into(),
+            stance: Stance::Contradicts,
+            source_quality: 0.8,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        store
+            .ingest_bundle(claim("c1", "Company X acquired Company Y"), vec![], vec![])
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("c2", "Company X acquisition was denied"),
+                vec![contradicting],
+                vec![],
+            )
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "company x acquired", 5);
+
+        let explained = store.retrieve_explained(&req, &RetrievalOptions::new());
+        assert_eq!(
+            explained
+                .iter()
+                .map(|explained| explained.result.clone())
+                .collect::<Vec<_>>(),
+            store.retrieve(&req)
+        );
+        let top = &explained[0];
+        assert_eq!(top.result.claim_id, "c1");
+        assert_eq!(top.components.score, top.result.score);
+        assert_eq!(top.components.lexical_overlap, 1.0);
+        assert!(top.components.bm25 > 0.0);
+        assert_eq!(top.components.dense_similarity, None);
+        let denied = &explained[1].components;
+        assert_eq!(denied.contradicts, 1);
+        assert!(denied.contradiction_adjustment < 0.0);
+        assert!(denied.quality > 0.0);
+        assert!(denied.lexical_overlap < 1.0);
+
+        store
+            .upsert_claim_vector(&"c1".into(), vec![1.0, 0.0])
+            .unwrap();
+        store
+            .upsert_claim_vector(&"c2".into(), vec![0.0, 1.0])
+            .unwrap();
+        let query_vector = [0.0, 1.0];
+        let explained = store.retrieve_explained(
+            &req,
+            &RetrievalOptions::new().with_query_vector(Some(&query_vector)),
+        );
+        assert_eq!(explained[0].result.claim_id, "c2");
+        assert_eq!(explained[0].components.dense_similarity, Some(1.0));
+        assert_eq!(explained[0].components.score, explained[0].result.score);
+
+        // Sparse and vector-space similarities are the ones scored on.
+        let sparse = |weight: f32| SparseVector::new([("acquired".to_string(), weight)]).unwrap();
+        store
+            .upsert_claim_sparse_vector(&"c1".into(), sparse(0.5))
+            .unwrap();
+        store
+            .upsert_claim_sparse_vector(&"c2".into(), sparse(1.0))
+            .unwrap();
+        store
+            .upsert_named_claim_vector(&"c1".into(), "title", vec![0.0, 1.0])
+            .unwrap();
+        let sparse_query = sparse(1.0);
+        let spaces = [
+            VectorSpaceQuery::default_space(vec![1.0, 0.0]),
+            VectorSpaceQuery::named("title", vec![0.0, 1.0]).with_weight(0.5),
+        ];
+        for options in [
+            RetrievalOptions::new().with_sparse_query(Some(&sparse_query)),
+            RetrievalOptions::new().with_vector_spaces(&spaces),
+        ] {
+            let explained = store.retrieve_explained(&req, &options);
+            assert_eq!(explained.len(), 2);
+            for explained in &explained {
+                assert_eq!(explained.components.score, explained.result.score);
+            }
+        }
+        let options = RetrievalOptions::new().with_sparse_query(Some(&sparse_query));
+        let explained = store.retrieve_explained(&req, &options);
+        let c1 = explained
+            .iter()
+            .find(|explained| explained.result.claim_id == "c1")
+            .unwrap();
+        assert_eq!(c1.components.sparse_similarity, 0.5);
+    }
+
+    #[test]
+    fn shard_merge_calibrates_scores_before_ranking() {
+        let scored = |claim_id: &str, score: f32| RetrievalResult::new(claim_id, "", score);
+        let shards = || {
+            vec![
+                vec![scored("a", 10.0), scored("b", 9.0)],
+                vec![scored("c", 0.9), scored("d", 0.1)],
+            ]
+        };
+        let ids = |results: Vec<RetrievalResult>| -> Vec<String> {
+            results
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect()
+        };
+        assert_eq!(
+            ids(merge_shard_results(shards(), 2, ScoreCalibration::Raw)),
+            vec!["a", "b"]
+        );
+        let min_max = merge_shard_results(shards(), 4, ScoreCalibration::MinMax);
+        assert_eq!(ids(min_max.clone()), vec!["a", "c", "b", "d"]);
+        assert_eq!(min_max[1].score, 1.0);
+        let z_score = merge_shard_results(shards(), 2, ScoreCalibration::ZScore);
+        assert_eq!(ids(z_score.clone()), vec!["a", "c"]);
+        assert!((z_score[0].score - 1.0).abs() < 1e-6);
+        assert_eq!(
+            ScoreCalibration::parse("zscore"),
+            Some(ScoreCalibration::ZScore)
+        );
+
+        let mut east = InMemoryStore::new();
+        let mut west = InMemoryStore::new();
+        east.ingest_bundle(claim("c1", "Company X acquired Company Y"), vec![], vec![])
+            .unwrap();
+        west.ingest_bundle(claim("c2", "Company X acquisition closed"), vec![], vec![])
+            .unwrap();
+        west.ingest_bundle(claim("c3", "Company Z opened an office"), vec![], vec![])
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "company x acquired", 5);
+        let merged = scatter_gather(
+            &[&east, &west, &east],
+            &req,
+            &RetrievalOptions::new(),
+            ScoreCalibration::MinMax,
+        );
+        assert_eq!(ids(merged), vec!["c1", "c2", "c3"]);
+    }
+
+    #[test]
+    fn global_term_statistics_make_shard_scores_match_a_single_corpus() {
+        let claims = [
+            ("c1", "Company X acquired Company Y"),
+            ("c2", "Company X acquired a rival"),
+            ("c3", "Company Z opened an office"),
+            ("c4", "Company W hired staff in Berlin"),
+        ];
+        let mut combined = InMemoryStore::new();
+        let mut east = InMemoryStore::new();
+        let mut west = InMemoryStore::new();
+        for (idx, (id, text)) in claims.into_iter().enumerate() {
+            combined
+                .ingest_bundle(claim(id, text), vec![], vec![])
+                .unwrap();
+            let shard = if idx < 2 { &mut east } else { &mut west };
+            shard
+                .ingest_bundle(claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "company acquired", 5);
+        let score_of = |store: &InMemoryStore, claim_id: &str| {
+            store
+                .retrieve(&req)
+                .into_iter()
+                .find(|result| result.claim_id == claim_id)
+                .map(|result| result.score)
+                .unwrap()
+        };
+        assert_ne!(score_of(&east, "c1"), score_of(&combined, "c1"));
+
+        let east_stats = east.export_query_term_statistics(&"tenant-a".into(), &req.query);
+        assert_eq!(east_stats.doc_count, 2);
+        assert_eq!(east_stats.doc_freq["acquired"], 2);
+        let west_stats = west.export_term_statistics(&"tenant-a".into());
+        assert_eq!(west_stats.doc_freq.get("acquired"), None);
+        let global = TermStatistics::merged([&east_stats, &west_stats]);
+        assert_eq!(global.doc_count, 4);
+        assert_eq!(global.doc_freq["company"], 4);
+        assert_eq!(global, {
+            let mut full = combined.export_term_statistics(&"tenant-a".into());
+            full.doc_freq
+                .retain(|term, _| global.doc_freq.contains_key(term));
+            full
+        });
+
+        east.set_global_term_statistics(&"tenant-a".into(), Some(global.clone()));
+        west.set_global_term_statistics(&"tenant-a".into(), Some(global));
+        assert_eq!(score_of(&east, "c1"), score_of(&combined, "c1"));
+        assert_eq!(score_of(&west, "c3"), score_of(&combined, "c3"));
+        east.set_global_term_statistics(&"tenant-a".into(), None);
+        assert!(east.global_term_statistics(&"tenant-a".into()).is_none());
+    }
+
+    #[test]
+    fn retrieval_deadline_returns_partial_results_flagged_truncated() {
+        let mut store = InMemoryStore::new();
+        for i in 0..300 {
+            let text = format!("Company X filing number {i}");
+            store
+                .ingest_bundle(claim(&format!("c{i:03}"), &text), vec![], vec![])
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "company x filing", 10);
+
+        let complete = store.retrieve_with_outcome(&req, &RetrievalOptions::new());
+        assert!(!complete.truncated);
+        assert_eq!(complete.results, store.retrieve(&req));
+
+        let expired = RetrievalOptions::new().with_deadline(Instant::now());
+        let partial = store.retrieve_with_outcome(&req, &expired);
+        assert!(partial.truncated);
+        assert_eq!(partial.results.len(), 10);
+        assert_eq!(store.metrics_snapshot().truncated_retrievals, 1);
+
+        let candidates: HashSet<ClaimId> = (0..300).map(|i| format!("c{i:03}").into()).collect();
+        let partial = store.retrieve_with_outcome(
+            &req,
+            &RetrievalOptions::new()
+                .with_candidate_claim_ids(&candidates)
+                .with_deadline(Instant::now()),
+        );
+        assert!(partial.truncated);
+        assert!(!partial.results.is_empty());
+
+        let page = store.retrieve_page(&req, &expired, None).unwrap();
+        assert!(page.truncated);
+        let page = store
+            .retrieve_page(&req, &RetrievalOptions::new(), None)
+            .unwrap();
+        assert!(!page.truncated);
+
+        let generous =
+            RetrievalOptions::new().with_deadline(Instant::now() + Duration::from_secs(60));
+        let outcome = store.retrieve_with_outcome(&req, &generous);
+        assert!(!outcome.truncated);
+        assert_eq!(outcome.results, complete.results);
+    }
+
+    #[test]
+    fn merge_claims_moves_evidence_and_edges_onto_the_primary_and_replays() {
+        let evidence = |evidence_id: &str, claim_id: &str| Evidence {
+            evidence_id: evidence_id.into(),
+            claim_id: claim_id.into(),
+            source_id: format!("doc-{evidence_id}"),
+            stance: Stance::Supports,
+            source_quality: 0.9,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        let edge = |edge_id: &str, from: &str, to: &str| ClaimEdge {
+            edge_id: edge_id.into(),
+            from_claim_id: from.into(),
+            to_claim_id: to.into(),
+            relation: Relation::Supports,
+            strength: 0.8,
+            reason_codes: vec![],
+            created_at: None,
+        };
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        for (claim, evidence, edges) in [
+            (
+                claim("c1", "Company X acquired Company Y"),
+                vec![evidence("e1", "c1")],
+                vec![],
+            ),
+            (
+                claim("c2", "Company X has acquired Company Y"),
+                vec![evidence("e2", "c2")],
+                vec![edge("g1", "c2", "c3"), edge("g2", "c2", "c1")],
+            ),
+            (
+                claim("c3", "Company Y shareholders approved"),
+                vec![],
+                vec![],
+            ),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim, evidence, edges)
+                .unwrap();
+        }
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c4", "Regulators cleared the deal"),
+                vec![],
+                vec![edge("g3", "c4", "c2")],
+            )
+            .unwrap();
+
+        assert!(matches!(
+            store.merge_claims(&"c1".into(), &["c1".into()]),
+            Err(StoreError::Validation(_))
+        ));
+        assert!(matches!(
+            store.merge_claims(&"c1".into(), &["missing".into()]),
+            Err(StoreError::MissingClaim(_))
+        ));
+        let stats = store
+            .merge_claims_persistent(&mut wal, &"c1".into(), &["c2".into()])
+            .unwrap();
+        assert_eq!(
+            stats,
+            ClaimMergeStats {
+                merged_claim_ids: vec!["c2".into()],
+                evidence_moved: 1,
+                edges_moved: 1,
+                incoming_edges_repointed: 1,
+            }
+        );
+
+        let check = |store: &InMemoryStore| {
+            assert!(store.claim_by_id(&"c2".into()).is_none());
+            let merged = &store.get_claims(&"tenant-a".into(), &["c1".into()]).claims[0];
+            let evidence_ids: Vec<(&str, &str)> = merged
+                .evidence
+                .iter()
+                .map(|e| (e.evidence_id.as_str(), e.claim_id.as_str()))
+                .collect();
+            assert_eq!(evidence_ids, vec![("e1", "c1"), ("e2", "c1")]);
+            let edges: Vec<(&str, &str, Relation)> = merged
+                .edges
+                .iter()
+                .map(|e| {
+                    (
+                        e.edge_id.as_str(),
+                        e.to_claim_id.as_str(),
+                        e.relation.clone(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                edges,
+                vec![
+                    ("g1", "c3", Relation::Supports),
+                    ("merge:c1:c2", "c2", Relation::Duplicates),
+                ]
+            );
+            assert_eq!(store.edges_for_claim(&"c4".into())[0].to_claim_id, "c1");
+            let req = RetrievalRequest::new("tenant-a", "company x acquired company y", 5);
+            let top = &store.retrieve(&req)[0];
+            assert_eq!((top.claim_id.as_str(), top.supports), ("c1", 3));
+        };
+        check(&store);
+        check(&InMemoryStore::load_from_wal(&wal).unwrap());
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        check(&InMemoryStore::load_from_wal(&wal).unwrap());
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn wal_replay_streams_escaped_and_long_records() {
+        let neighbors: Vec<Vec<String>> = (0..20)
+            .map(|level| vec![format!("n{level}"), format!("m{level}")])
+            .collect();
+        let node_line = wal::record_to_line(&PersistedRecord::AnnGraphNode(AnnGraphNodeRecord {
+            tenant_id: "tenant-a".to_string(),
+            claim_id: "c1".to_string(),
+            node_level: 19,
+            neighbors,
+        }));
+        assert!(node_line.split('\t').count() > 16);
+        let reparsed = line_to_record(&node_line).unwrap();
+        assert_eq!(wal::record_to_line(&reparsed), node_line);
+
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let mut escaped = claim("c1", "tab\there, newline\nthere, and a \\ backslash");
+        escaped.metadata = vec![("source\tkind".to_string(), "memo\\2".to_string())];
+        store
+            .ingest_bundle_persistent(&mut wal, escaped.clone(), vec![], vec![])
+            .unwrap();
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c2", "after the checkpoint"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        wal.flush_pending_sync().unwrap();
+
+        let (replayed, stats) = InMemoryStore::load_from_wal_with_stats(&wal).unwrap();
+        assert_eq!(stats.replay.snapshot_records, 1);
+        assert_eq!(stats.replay.wal_records, 1);
+        assert_eq!(stats.claims_loaded, 2);
+        let restored = replayed.claims.get("c1").unwrap();
+        assert_eq!(restored.canonical_text, escaped.canonical_text);
+        assert_eq!(restored.metadata, escaped.metadata);
+        assert!(replayed.claims.contains_key("c2"));
+
+        assert!(matches!(
+            line_to_record("D\ttenant-a\tbad\\qescape"),
+            Err(StoreError::Parse(_))
+        ));
+        assert!(matches!(
+            line_to_record("D\ttenant-a\tdangling\\"),
+            Err(StoreError::Parse(_))
+        ));
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn legacy_record_lines_upgrade_to_the_current_versioned_layout() {
+        for layout in migrations::LAYOUTS {
+            let (kind, version) = migrations::parse_header(layout.header).unwrap();
+            assert_eq!(kind, layout.kind);
+            assert!(
+                layout
+                    .upgrade(version, layout.field_count())
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+
+        let legacy = line_to_record("C\tc1\ttenant-a\tLegacy claim\t0.9\tnull").unwrap();
+        let PersistedRecord::Claim(legacy_claim) = &legacy else {
+            panic!("expected a claim record");
+        };
+        assert_eq!(legacy_claim.canonical_text, "Legacy claim");
+        assert!(legacy_claim.claim_type.is_none() && legacy_claim.language.is_none());
+        let line = wal::record_to_line(&legacy);
+        assert!(line.starts_with("C@8\tc1\t"));
+        assert_eq!(line.split('\t').count(), migrations::CLAIM.field_count());
+        assert_eq!(wal::record_to_line(&line_to_record(&line).unwrap()), line);
+
+        let PersistedRecord::ClaimVector(vector) = line_to_record("V\tc1\t0.5,0.25").unwrap()
+        else {
+            panic!("expected a vector record");
+        };
+        assert_eq!(vector.space, None);
+        let PersistedRecord::TenantVectorConfig(config) =
+            line_to_record("T\ttenant-a\t2\tcosine\t8\t16\t2\t10\t100").unwrap()
+        else {
+            panic!("expected a tenant vector config record");
+        };
+        let tuning = config.config.ann_tuning.unwrap();
+        assert_eq!(tuning.index_kind, AnnIndexKind::Graph);
+        assert_eq!(tuning.exact_search_threshold, 0);
+        assert_eq!(tuning.level_multiplier, ann::ANN_LEVEL_MULTIPLIER_DEFAULT);
+        assert_eq!(tuning.level_seed, ann::ANN_LEVEL_SEED_DEFAULT);
+
+        for line in [
+            "C@9\tc1\ttenant-a\ttext\t0.9\tnull",
+            "C@2\tc1\ttenant-a\ttext\t0.9\tnull",
+            "C\tc1\ttenant-a\ttext\t0.9\tnull\t",
+            "G@1\te1\tc1\tc2\tsupports\t0.5",
+        ] {
+            assert!(
+                matches!(line_to_record(line), Err(StoreError::Parse(_))),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn exclude_sources_drops_claims_supported_only_by_those_sources() {
+        let mut store = InMemoryStore::new();
+        let evidence = |id: &str, claim_id: &str, source_id: &str, stance: Stance| Evidence {
+            evidence_id: id.into(),
+            claim_id: claim_id.into(),
+            source_id: source_id.into(),
+            stance,
+            source_quality: 0.8,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        store
+            .ingest_bundle(
+                claim("c1", "Company X acquired Company Y"),
+                vec![evidence("e1", "c1", "tabloid", Stance::Supports)],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("c2", "Company X acquisition closed"),
+                vec![
+                    evidence("e2", "c2", "tabloid", Stance::Supports),
+                    evidence("e3", "c2", "filing", Stance::Supports),
+                ],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("c3", "Company X acquisition denied"),
+                vec![evidence("e4", "c3", "tabloid", Stance::Contradicts)],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(claim("c4", "Company X acquisition rumored"), vec![], vec![])
+            .unwrap();
+
+        assert_eq!(
+            store.claim_ids_for_source(&"tenant-a".into(), "tabloid"),
+            ["c1", "c2", "c3"].into_iter().map(ClaimId::from).collect()
+        );
+        let req = RetrievalRequest::new("tenant-a", "company x acquisition", 10);
+        let mut ids: Vec<String> = store
+            .retrieve_with(
+                &req,
+                &RetrievalOptions::new().with_exclude_sources(["tabloid"]),
+            )
+            .into_iter()
+            .map(|result| result.claim_id.into_string())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["c2", "c3", "c4"]);
+
+        store
+            .delete_claim(&"tenant-a".into(), &"c1".into())
+            .unwrap();
+        assert_eq!(
+            store.claim_ids_for_source(&"tenant-a".into(), "tabloid"),
+            ["c2", "c3"].into_iter().map(ClaimId::from).collect()
+        );
+    }
+
+    #[test]
+    fn archived_claims_leave_retrieval_but_keep_their_history_across_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        for (id, text) in [
+            ("c1", "Company X acquired Company Y"),
+            ("c2", "Company X acquisition closed"),
+            ("c3", "Company X acquisition rumored"),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "company x acquisition", 10);
+        let ids = |results: Vec<RetrievalResult>| -> Vec<String> {
+            let mut ids: Vec<String> = results
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        let tenant_a = TenantId::from("tenant-a");
+        let (c1, c2) = (ClaimId::from("c1"), ClaimId::from("c2"));
+        assert!(
+            store
+                .archive_claim_persistent(&mut wal, &tenant_a, &c1)
+                .unwrap()
+        );
+        assert!(
+            !store
+                .archive_claim_persistent(&mut wal, &tenant_a, &c1)
+                .unwrap()
+        );
+        assert!(matches!(
+            store.archive_claim(&"tenant-b".into(), &c2),
+            Err(StoreError::MissingClaim(_))
+        ));
+        assert_eq!(ids(store.retrieve(&req)), vec!["c2", "c3"]);
+        assert_eq!(
+            ids(store.retrieve_batch(std::slice::from_ref(&req)).remove(0)),
+            vec!["c2", "c3"]
+        );
+        assert_eq!(
+            ids(store.retrieve_with(&req, &RetrievalOptions::new().with_include_archived(true))),
+            vec!["c1", "c2", "c3"]
+        );
+        assert!(store.claims.contains_key("c1"));
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        store
+            .archive_claim_persistent(&mut wal, &tenant_a, &c2)
+            .unwrap();
+        store
+            .unarchive_claim_persistent(&mut wal, &tenant_a, &c1)
+            .unwrap();
+        wal.flush_pending_sync().unwrap();
+
+        let (replayed, _) = InMemoryStore::load_from_wal_with_stats(&wal).unwrap();
+        assert_eq!(replayed.archived_claim_ids(&"tenant-a".into()), vec!["c2"]);
+        assert_eq!(ids(replayed.retrieve(&req)), vec!["c1", "c3"]);
+
+        store.delete_claim(&tenant_a, &c2).unwrap();
+        assert!(store.archived_claim_ids(&"tenant-a".into()).is_empty());
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn superseded_claims_leave_retrieval_until_their_successor_is_deleted() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let edge = |id: &str, from: &str, to: &str, relation: Relation| ClaimEdge {
+            edge_id: id.into(),
+            from_claim_id: from.into(),
+            to_claim_id: to.into(),
+            relation,
+            strength: 1.0,
+            reason_codes: vec![],
+            created_at: None,
+        };
+        for (claim, edges) in [
+            (claim("c1", "Company X revenue was 10M"), vec![]),
+            (
+                claim("c2", "Company X revenue was 12M after restatement"),
+                vec![edge("g1", "c2", "c1", Relation::Supersedes)],
+            ),
+            (
+                claim("c3", "Company X revenue grew in Europe"),
+                vec![edge("g2", "c3", "c2", Relation::Elaborates)],
+            ),
+        ] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim, vec![], edges)
+                .unwrap();
+        }
+        store
+            .ingest_bundle(
+                claim_for_tenant("b1", "Company X revenue", "tenant-b"),
+                vec![],
+                vec![edge("g3", "b1", "c3", Relation::Supersedes)],
+            )
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "company x revenue", 10);
+        let ids = |results: Vec<RetrievalResult>| -> Vec<String> {
+            let mut ids: Vec<String> = results
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(store.superseding_claim_ids(&"c1".into()), vec!["c2"]);
+        assert!(!store.is_claim_superseded(&"c3".into()));
+        assert_eq!(ids(store.retrieve(&req)), vec!["c2", "c3"]);
+        assert_eq!(
+            ids(store.retrieve_with(&req, &RetrievalOptions::new().with_include_superseded(true))),
+            vec!["c1", "c2", "c3"]
+        );
+        let summary = summarize_edges(&store.edges_by_claim["c3"]);
+        assert_eq!((summary.elaborates, summary.supersedes), (1, 0));
+
+        wal.flush_pending_sync().unwrap();
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.edges_by_claim["c2"][0].relation,
+            Relation::Supersedes
+        );
+        assert_eq!(ids(replayed.retrieve(&req)), vec!["c2", "c3"]);
+
+        store
+            .delete_claim(&"tenant-a".into(), &"c2".into())
+            .unwrap();
+        assert!(!store.is_claim_superseded(&"c1".into()));
+        assert_eq!(ids(store.retrieve(&req)), vec!["c1", "c3"]);
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn documents_and_chunks_resolve_citations_and_survive_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let mut document = Document::new(
+            "doc://deal",
+            "tenant-a",
+            "Company X acquired Company Y.\tTerms were not disclosed.",
+        );
+        document.uri = Some("https://example.com/deal".into());
+        document.hash = Some("9f2c".into());
+        document.ingested_at = Some(1_771_620_000_000);
+        let chunk = Chunk::new(
+            "chunk-2",
+            "doc://deal",
+            "tenant-a",
+            "Terms were not disclosed.",
+        );
+        let mut orphan = chunk.clone();
+        orphan.doc_id = "doc://missing".into();
+        assert!(matches!(
+            store.put_chunk_persistent(&mut wal, orphan),
+            Err(StoreError::MissingDocument(_))
+        ));
+        let mut blank = document.clone();
+        blank.doc_id = " ".into();
+        assert!(matches!(
+            store.put_document(blank),
+            Err(StoreError::Validation(ValidationError::MissingField(
+                "doc_id"
+            )))
+        ));
+        assert_eq!(
+            store
+                .put_document_persistent(&mut wal, document.clone())
+                .unwrap(),
+            None
+        );
+        store.put_chunk_persistent(&mut wal, chunk.clone()).unwrap();
+
+        let mut evidence = Evidence {
+            evidence_id: "e1".into(),
+            claim_id: "c1".into(),
+            source_id: "source://deal".into(),
+            stance: Stance::Supports,
+            source_quality: 0.9,
+            chunk_id: None,
+            span_start: Some(0),
+            span_end: Some(27),
+            doc_id: Some("doc://deal".into()),
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        assert_eq!(
+            store.evidence_text(&"tenant-a".into(), &evidence),
+            Some("Company X acquired Company Y")
+        );
+        assert_eq!(store.evidence_text(&"tenant-b".into(), &evidence), None);
+        evidence.chunk_id = Some("chunk-2".into());
+        assert_eq!(
+            store.evidence_text(&"tenant-a".into(), &evidence),
+            Some("Terms were not disclosed.")
+        );
+        evidence.chunk_id = None;
+        evidence.span_end = Some(500);
+        assert_eq!(store.evidence_text(&"tenant-a".into(), &evidence), None);
+
+        wal.flush_pending_sync().unwrap();
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed.document(&"tenant-a".into(), "doc://deal"),
+            Some(&document)
+        );
+        assert_eq!(
+            replayed.document_chunks(&"tenant-a".into(), "doc://deal"),
+            vec![&chunk]
+        );
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        let compacted = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            compacted.document_ids(&"tenant-a".into()),
+            vec!["doc://deal"]
+        );
+        assert_eq!(
+            compacted.chunk(&"tenant-a".into(), "doc://deal", "chunk-2"),
+            Some(&chunk)
+        );
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn registered_sources_set_evidence_quality_and_survive_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let evidence = |id: &str, claim_id: &str, source_id: &str, quality: f32| Evidence {
+            evidence_id: id.into(),
+            claim_id: claim_id.into(),
+            source_id: source_id.into(),
+            stance: Stance::Supports,
+            source_quality: quality,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("wire", "Company X acquired Company Y"),
+                vec![evidence("e1", "wire", "src://wire", 0.5)],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("blog", "Company X acquired Company Y"),
+                vec![evidence("e2", "blog", "src://blog", 0.95)],
+                vec![],
+            )
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "company x acquired company y", 2);
+        assert_eq!(store.retrieve(&req)[0].claim_id, "blog");
+
+        let mut wire = Source::new("src://wire", "tenant-a", "Newswire", 1.0);
+        wire.category = Some("news".into());
+        wire.trust_tier = TrustTier::Authoritative;
+        let mut blog = Source::new("src://blog", "tenant-a", "Some\tblog", 0.9);
+        blog.trust_tier = TrustTier::Untrusted;
+        let mut negative = wire.clone();
+        negative.default_quality = -0.1;
+        assert!(matches!(
+            store.put_source(negative),
+            Err(StoreError::Validation(ValidationError::InvalidRange(
+                "default_quality"
+            )))
+        ));
+        assert_eq!(
+            store.put_source_persistent(&mut wal, wire.clone()).unwrap(),
+            None
+        );
+        store.put_source_persistent(&mut wal, blog.clone()).unwrap();
+        assert_eq!(
+            store.evidence_quality(
+                &"tenant-a".into(),
+                &evidence("e9", "blog", "src://blog", 0.95)
+            ),
+            0.2
+        );
+        assert_eq!(
+            store.evidence_quality(
+                &"tenant-b".into(),
+                &evidence("e9", "blog", "src://blog", 0.95)
+            ),
+            0.95
+        );
+        assert_eq!(store.retrieve(&req)[0].claim_id, "wire");
+
+        wal.flush_pending_sync().unwrap();
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(replayed.sources(&"tenant-a".into()), vec![&blog, &wire]);
+        assert_eq!(replayed.retrieve(&req)[0].claim_id, "wire");
+
+        store.checkpoint_and_compact(&mut wal).unwrap();
+        let compacted = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            compacted.source(&"tenant-a".into(), "src://wire"),
+            Some(&wire)
+        );
+        assert_eq!(compacted.source(&"tenant-b".into(), "src://wire"), None);
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn registered_sources_decide_certainty_citations_and_summaries() {
+        use schema::CertaintyBand;
+
+        let mut store = InMemoryStore::new();
+        let evidence = |id: &str, source_id: &str| {
+            EvidenceBuilder::new(id, "c1", source_id)
+                .with_stance(Stance::Supports)
+                .with_source_quality(0.9)
+                .build()
+                .unwrap()
+        };
+        store
+            .ingest_bundle(
+                claim("c1", "Company X acquired Company Y"),
+                vec![evidence("e1", "src://wire"), evidence("e2", "src://blog")],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(
+            store.claim_certainty_band(&"c1".into()),
+            Some(CertaintyBand::Corroborated)
+        );
+        let changes = store.subscribe();
+        let epoch = store.index_epoch(&"tenant-a".into());
+
+        let mut blog = Source::new("src://blog", "tenant-a", "Blog", 0.9);
+        blog.trust_tier = TrustTier::Untrusted;
+        store.put_source(blog.clone()).unwrap();
+        assert_eq!(store.index_epoch(&"tenant-a".into()), epoch + 1);
+        assert_eq!(
+            changes.try_recv().map(|change| change.record),
+            Some(ChangeRecord::Source(blog))
+        );
+
+        assert_eq!(
+            store.claim_certainty_band(&"c1".into()),
+            Some(CertaintyBand::Unverified)
+        );
+        let req = RetrievalRequest::new("tenant-a", "company x acquired", 1);
+        let result = &store.retrieve(&req)[0];
+        assert_eq!(result.certainty, CertaintyBand::Unverified);
+        let blog_citation = result
+            .citations
+            .iter()
+            .find(|citation| citation.source_id == "src://blog")
+            .unwrap();
+        assert_eq!(blog_citation.source_quality, 0.2);
+        let summaries = store.sources_for_tenant(&"tenant-a".into());
+        assert_eq!(summaries[0].source_id, "src://blog");
+        assert_eq!(summaries[0].avg_source_quality, 0.2);
+        assert_eq!(summaries[1].avg_source_quality, 0.9);
+    }
+
+    #[test]
+    fn freshness_half_life_lets_recent_evidence_outweigh_old_evidence() {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let five_years_ms = 5 * 365 * 86_400_000;
+        let evidence = |id: &str, claim_id: &str, ingested_at: i64| Evidence {
+            evidence_id: id.into(),
+            claim_id: claim_id.into(),
+            source_id: format!("doc-{id}"),
+            stance: Stance::Supports,
+            source_quality: 0.8,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: Some(ingested_at),
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(
+                claim("old", "Company X acquired Company Y"),
+                vec![
+                    evidence("e1", "old", now_ms - five_years_ms),
+                    evidence("e2", "old", now_ms - five_years_ms),
+                ],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("new", "Company X acquired Company Y"),
+                vec![evidence("e3", "new", now_ms)],
+                vec![],
+            )
+            .unwrap();
+        let req = RetrievalRequest::new("tenant-a", "company x acquired company y", 2);
+
+        assert_eq!(store.retrieve(&req)[0].claim_id, "old");
+
+        store.set_ranking_config(
+            RankingConfig::default().with_freshness_half_life(Duration::from_secs(30 * 86_400)),
+        );
+        let results = store.retrieve(&req);
+        assert_eq!(results[0].claim_id, "new");
+        assert_eq!(results[1].supports, 2);
+    }
+
+    #[test]
+    fn wal_follower_applies_leader_records_and_resyncs_after_checkpoint() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut leader = InMemoryStore::new();
+        leader
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c-f1", "Follower claim one"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+
+        let mut follower = WalFollower::open(&wal_path).unwrap();
+        assert!(follower.store().claims.contains_key("c-f1"));
+        assert_eq!(follower.next_offset(), 1);
+
+        let idle = follower.poll(Duration::from_millis(30)).unwrap();
+        assert_eq!(idle, WalFollowOutcome::default());
+
+        leader
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c-f2", "Follower claim two"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let caught_up = follower.poll(Duration::from_secs(1)).unwrap();
+        assert_eq!(caught_up.applied, 1);
+        assert!(!caught_up.resynced);
+        assert!(follower.store().claims.contains_key("c-f2"));
+
+        leader.checkpoint_and_compact(&mut wal).unwrap();
+        leader
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c-f3", "Follower claim three"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let resynced = follower.poll(Duration::from_secs(1)).unwrap();
+        assert!(resynced.resynced);
+        let mut claim_ids: Vec<_> = follower.store().claims.keys().cloned().collect();
+        claim_ids.sort();
+        assert_eq!(claim_ids, vec!["c-f1", "c-f2", "c-f3"]);
+
+        // A second checkpoint leaving as many snapshot records and an empty
+        // log still reads as a new snapshot.
+        leader.checkpoint_and_compact(&mut wal).unwrap();
+        follower.poll(Duration::from_millis(30)).unwrap();
+        leader
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c-f1", "Follower claim revised"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        leader.checkpoint_and_compact(&mut wal).unwrap();
+        let resynced = follower.poll(Duration::from_secs(1)).unwrap();
+        assert!(resynced.resynced);
+        assert_eq!(
+            follower
+                .store()
+                .claims
+                .get("c-f1")
+                .map(|claim| claim.canonical_text.as_str()),
+            Some("Follower claim revised")
+        );
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn certainty_bands_label_results_and_filter_retrieval() {
+        use schema::CertaintyBand;
+
+        let mut store = InMemoryStore::new();
+        let evidence =
+            |id: &str, claim_id: &str, source: &str, stance: Stance, quality: f32| Evidence {
+                evidence_id: id.into(),
+                claim_id: claim_id.into(),
+                source_id: source.into(),
+                stance,
+                source_quality: quality,
+                chunk_id: None,
+                span_start: None,
+                span_end: None,
+                doc_id: None,
+                extraction_model: None,
+                ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
+            };
+        store
+            .ingest_bundle(
+                claim("c1", "Reactor output rose in March"),
+                vec![
+                    evidence("e1", "c1", "registry", Stance::Supports, 0.9),
+                    evidence("e2", "c1", "wire", Stance::Supports, 0.7),
+                ],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("c2", "Reactor output fell in March"),
+                vec![
+                    evidence("e3", "c2", "registry", Stance::Supports, 0.9),
+                    evidence("e4", "c2", "wire", Stance::Supports, 0.7),
+                    evidence("e5", "c2", "audit", Stance::Contradicts, 0.9),
+                ],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim("c3", "Reactor output doubled in March"),
+                vec![
+                    evidence("e6", "c3", "forum", Stance::Supports, 0.2),
+                    evidence("e7", "c3", "blog", Stance::Supports, 0.3),
+                ],
+                vec![],
+            )
+            .unwrap();
+
+        let req = RetrievalRequest::new("tenant-a", "reactor output march", 10);
+        let bands: HashMap<String, CertaintyBand> = store
+            .retrieve_with(
+                &req,
+                &RetrievalOptions::new().with_fields(ResultFields::IdsOnly),
+            )
+            .into_iter()
+            .map(|result| (result.claim_id.into_string(), result.certainty))
+            .collect();
+        assert_eq!(bands["c1"], CertaintyBand::Corroborated);
+        assert_eq!(bands["c2"], CertaintyBand::Contested);
+        assert_eq!(bands["c3"], CertaintyBand::Unverified);
+
+        let trusted = RetrievalOptions::new().with_certainty_bands(&[CertaintyBand::Corroborated]);
+        let ids: Vec<String> = store
+            .retrieve_with(&req, &trusted)
+            .into_iter()
+            .map(|result| result.claim_id.into_string())
+            .collect();
+        assert_eq!(ids, vec!["c1"]);
+    }
+
+    #[test]
+    fn shipped_snapshot_bootstraps_a_follower_that_tails_on() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut leader = InMemoryStore::new();
+        for (id, text) in [("c-s1", "Shipped claim one"), ("c-s2", "Shipped claim two")] {
+            leader
+                .ingest_bundle_persistent(&mut wal, claim(id, text), vec![], vec![])
+                .unwrap();
+        }
+        leader.checkpoint_and_compact(&mut wal).unwrap();
+        leader
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c-s3", "Shipped claim three"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+
+        let mut shipment = Vec::new();
+        let shipped = leader.ship_snapshot(&mut wal, &mut shipment).unwrap();
+        assert_eq!(shipped.start_offset, wal.wal_record_count().unwrap());
+
+        let mut follower = WalFollower::bootstrap(&wal_path, shipment.as_slice()).unwrap();
+        assert_eq!(follower.next_offset(), shipped.start_offset);
+        assert_eq!(follower.store().claims.len(), 3);
+
+        leader
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c-s4", "Shipped claim four"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let outcome = follower.poll(Duration::from_secs(1)).unwrap();
+        assert!(!outcome.resynced);
+        assert_eq!(outcome.applied, 1);
+        assert!(follower.store().claims.contains_key("c-s4"));
+
+        assert!(matches!(
+            WalFollower::bootstrap(&wal_path, &b"SHIP\t1\t0\t0\n"[..]),
+            Err(StoreError::Parse(_))
+        ));
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn bootstrapped_follower_resyncs_after_a_checkpoint_taken_before_bootstrap() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut leader = InMemoryStore::new();
+        leader
+            .ingest_bundle_persistent(&mut wal, claim("c-b1", "Shipped claim"), vec![], vec![])
+            .unwrap();
+        leader.checkpoint_and_compact(&mut wal).unwrap();
+
+        let mut shipment = Vec::new();
+        let shipped = leader.ship_snapshot(&mut wal, &mut shipment).unwrap();
+        assert_eq!(shipped.start_offset, 0);
+        assert_eq!(shipped.checkpoint_generation, 1);
+
+        // The log is empty again when the follower starts, so only the
+        // shipped generation shows the state it holds is stale.
+        leader
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c-b2", "Checkpointed claim"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        leader.checkpoint_and_compact(&mut wal).unwrap();
+        assert_eq!(wal.wal_record_count().unwrap(), 0);
+
+        let mut follower = WalFollower::bootstrap(&wal_path, shipment.as_slice()).unwrap();
+        assert!(!follower.store().claims.contains_key("c-b2"));
+        let outcome = follower.poll(Duration::from_millis(30)).unwrap();
+        assert!(outcome.resynced);
+        assert!(follower.store().claims.contains_key("c-b2"));
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn storage_report_sorts_usage_by_kind_and_tenant_and_raises_alerts() {
+        let dir = temp_wal_path().with_extension("storage");
+        let write = |relative: &str, bytes: usize| {
+            let path = dir.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![b'x'; bytes]).unwrap();
+        };
+        write("wal.log", 100);
+        write("wal.log.snapshot", 40);
+        write("tenants/acme%2Feu/wal.log", 30);
+        write("tenants/acme%2Feu/wal.log.snapshot", 5);
+        write("segments/tenant-b/hot-0.seg", 20);
+        write("segments/tenant-b/segments.manifest", 2);
+        write("vectors/tenant-7431-0123456789abcdef.vectors", 64);
+        write("wal.log.snapshot.tmp", 1);
+
+        let report = storage_report(&dir).unwrap();
+        assert_eq!(
+            report.totals,
+            StorageUsage {
+                wal_bytes: 130,
+                snapshot_bytes: 45,
+                segment_bytes: 22,
+                vector_bytes: 64,
+                other_bytes: 1,
+            }
+        );
+        assert_eq!(report.totals.total_bytes(), 262);
+        assert_eq!(report.tenants["acme/eu"].total_bytes(), 35);
+        assert_eq!(report.tenants["tenant-b"].segment_bytes, 22);
+        assert_eq!(report.tenants["t1"].vector_bytes, 64);
+        assert_eq!(report.tenants.len(), 3);
+
+        let alerts = report.alerts(&StorageThresholds {
+            max_total_bytes: Some(1_000),
+            max_wal_bytes: Some(120),
+            max_tenant_bytes: Some(50),
+        });
+        let summary: Vec<(StorageAlertKind, Option<&str>, u64)> = alerts
+            .iter()
+            .map(|alert| (alert.kind, alert.tenant_id.as_deref(), alert.used_bytes))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (StorageAlertKind::Wal, None, 130),
+                (StorageAlertKind::Tenant, Some("t1"), 64),
+            ]
+        );
+        assert!(
+            storage_report(dir.join("missing"))
+                .unwrap()
+                .tenants
+                .is_empty()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn edges_into_claim_follows_ingest_delete_merge_and_replay() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let edge = |id: &str, from: &str, to: &str, relation: Relation| ClaimEdge {
+            edge_id: id.into(),
+            from_claim_id: from.into(),
+            to_claim_id: to.into(),
+            relation,
+            strength: 0.7,
+            reason_codes: vec![],
+            created_at: None,
+        };
+        for id in ["c1", "c2", "c3", "c4"] {
+            store
+                .ingest_bundle_persistent(&mut wal, claim(id, "Edge index claim"), vec![], vec![])
+                .unwrap();
+        }
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c2", "Edge index claim"),
+                vec![],
+                vec![edge("e2", "c2", "c3", Relation::Contradicts)],
+            )
+            .unwrap();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim("c1", "Edge index claim"),
+                vec![],
+                vec![
+                    edge("e1", "c1", "c3", Relation::Supports),
+                    edge("e3", "c1", "c4", Relation::Supports),
+                ],
+            )
+            .unwrap();
+        let edge_ids = |store: &InMemoryStore, claim_id: &str| -> Vec<EdgeId> {
+            store
+                .edges_into_claim(&claim_id.into())
+                .into_iter()
+                .map(|edge| edge.edge_id)
+                .collect()
+        };
+        assert_eq!(edge_ids(&store, "c3"), vec!["e1", "e2"]);
+        assert_eq!(edge_ids(&store, "c4"), vec!["e3"]);
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(edge_ids(&replayed, "c3"), vec!["e1", "e2"]);
+
+        store
+            .delete_claim(&"tenant-a".into(), &"c2".into())
+            .unwrap();
+        assert_eq!(edge_ids(&store, "c3"), vec!["e1"]);
+
+        store.merge_claims(&"c3".into(), &["c4".into()]).unwrap();
+        assert_eq!(edge_ids(&store, "c3"), vec!["e1", "e3"]);
+        assert_eq!(edge_ids(&store, "c4"), vec!["merge:c3:c4"]);
+
+        cleanup_persistence_files(&wal);
+    }
+
+    #[test]
+    fn eviction_report_ranks_expired_then_low_confidence_then_unretrieved_claims() {
+        let mut store = InMemoryStore::new();
+        for (id, confidence, valid_to) in [
+            ("c1", 0.9, None),
+            ("c2", 0.3, None),
+            ("c3", 0.3, None),
+            ("c4", 0.95, Some(1_000)),
+            ("c5", 0.8, None),
+        ] {
+            let mut claim = claim(id, &format!("Quota claim {id}"));
+            claim.confidence = confidence;
+            claim.valid_to = valid_to;
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        let req = RetrievalRequest::new("tenant-a", "quota claim c3", 1);
+        assert_eq!(store.retrieve(&req)[0].claim_id, "c3");
+        assert_eq!(store.claim_retrieval_count(&"c3".into()), 1);
+
+        let roomy = store.eviction_report_at(&"tenant-a".into(), &EvictionPolicy::new(10), 2_000);
+        assert!(!roomy.near_quota);
+        assert!(roomy.candidates.is_empty());
+
+        let policy = EvictionPolicy {
+            max_claims: 5,
+            soft_limit_ratio: 0.9,
+            target_ratio: 0.4,
+        };
+        let report = store.eviction_report_at(&"tenant-a".into(), &policy, 2_000);
+        assert!(report.near_quota);
+        assert_eq!(report.claim_count, 5);
+        assert_eq!(report.candidate_claim_ids(), vec!["c4", "c2", "c3"]);
+        assert_eq!(report.candidates[0].reason, EvictionReason::ExpiredValidity);
+        assert_eq!(report.candidates[2].retrievals, 1);
+
+        let deleted = store
+            .delete_claims(&"tenant-a".into(), &report.candidate_claim_ids())
+            .unwrap();
+        assert_eq!(deleted, 3);
+        assert_eq!(store.claim_retrieval_count(&"c3".into()), 0);
+        assert!(
+            !store
+                .eviction_report_at(&"tenant-a".into(), &policy, 2_000)
+                .near_quota
+        );
+    }
+
+    #[test]
+    fn claims_page_walks_a_tenant_in_id_order_across_changes() {
+        let mut store = InMemoryStore::new();
+        for id in ["c5", "c1", "c4", "c2", "c3"] {
+            store
+                .ingest_bundle(claim(id, "Paged claim"), vec![], vec![])
+                .unwrap();
+        }
+        store
+            .ingest_bundle(
+                claim_for_tenant("c0", "Other tenant", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(store.iter_claims(&"tenant-a".into()).count(), 5);
+
+        let ids = |page: &ClaimPage| -> Vec<ClaimId> {
+            page.claims
+                .iter()
+                .map(|claim| claim.claim_id.clone())
+                .collect()
+        };
+        let first = store.claims_page(&"tenant-a".into(), None, 2);
+        assert_eq!(ids(&first), vec!["c1", "c2"]);
+        assert_eq!(first.next_cursor.as_deref(), Some("c2"));
+
+        store
+            .delete_claim(&"tenant-a".into(), &"c3".into())
+            .unwrap();
+        let second = store.claims_page(&"tenant-a".into(), first.next_cursor.as_ref(), 2);
+        assert_eq!(ids(&second), vec!["c4", "c5"]);
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[test]
+    fn temporal_granularity_buckets_the_index_and_answers_ranges_and_histograms() {
+        const DAY: i64 = 86_400;
+        let mut store = InMemoryStore::new();
+        store.set_temporal_granularity(TemporalGranularity::Second);
+        let times = [
+            ("t1", DAY + 10),
+            ("t2", DAY + 3_600 + 5),
+            ("t3", DAY + 3_600 + 50),
+            ("t4", 2 * DAY + 7),
+        ];
+        for (claim_id, ts) in times {
+            let mut timed = claim(claim_id, "timeline event");
+            timed.event_time_unix = Some(ts);
+            store
+                .ingest_bundle(timed, vec![], vec![])
+                .expect("ingest should succeed");
+        }
+        store
+            .ingest_bundle(claim("untimed", "no event time"), vec![], vec![])
+            .expect("ingest should succeed");
+        assert_eq!(store.index_stats().temporal_buckets, 4);
+
+        assert_eq!(store.set_temporal_granularity(TemporalGranularity::Day), 4);
+        assert_eq!(store.temporal_granularity(), TemporalGranularity::Day);
+        assert_eq!(store.index_stats().temporal_buckets, 2);
+        assert_eq!(
+            store.claim_ids_in_event_time_range(
+                &"tenant-a".into(),
+                Some(DAY + 3_600),
+                Some(2 * DAY)
+            ),
+            vec!["t2", "t3"]
+        );
+        assert_eq!(
+            store.claim_ids_in_event_time_range(&"tenant-a".into(), None, None),
+            vec!["t1", "t2", "t3", "t4"]
+        );
+        assert!(
+            store
+                .claim_ids_in_event_time_range(&"tenant-a".into(), Some(2 * DAY), Some(DAY))
+                .is_empty()
+        );
+
+        let bucket = |start_unix, claim_count| TemporalBucket {
+            start_unix,
+            claim_count,
+        };
+        assert_eq!(
+            store.temporal_histogram(&"tenant-a".into(), TemporalGranularity::Day, None, None),
+            vec![bucket(DAY, 3), bucket(2 * DAY, 1)]
+        );
+        assert_eq!(
+            store.temporal_histogram(
+                &"tenant-a".into(),
+                TemporalGranularity::Hour,
+                None,
+                Some(2 * DAY)
+            ),
+            vec![bucket(DAY, 1), bucket(DAY + 3_600, 2)]
+        );
+        assert_eq!(
+            store.temporal_histogram(
+                &"tenant-a".into(),
+                TemporalGranularity::Minute,
+                Some(DAY + 3_600 + 30),
+                None
+            ),
+            vec![bucket(DAY + 3_600, 1), bucket(2 * DAY, 1)]
+        );
+
+        store
+            .delete_claim(&"tenant-a".into(), &"t4".into())
+            .expect("delete should succeed");
+        assert_eq!(store.index_stats().temporal_buckets, 1);
+        assert_eq!(
+            store.temporal_histogram(&"tenant-a".into(), TemporalGranularity::Day, None, None),
+            vec![bucket(DAY, 3)]
+        );
+        assert_eq!(TemporalGranularity::Hour.bucket_start(-1), -3_600);
+        assert_eq!(
+            TemporalGranularity::parse(" Hour "),
+            Some(TemporalGranularity::Hour)
+        );
+    }
+
+    #[test]
+    fn entity_prefix_and_fuzzy_lookups_tolerate_partial_and_misspelled_names() {
+        let mut store = InMemoryStore::new();
+        for (id, entity) in [("c1", "Company X"), ("c2", "Company Y"), ("c3", "Acme")] {
+            let mut claim = claim(id, "filler text");
+            claim.entities = vec![entity.into()];
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+
+        let mut prefixed: Vec<ClaimId> = store
+            .claim_ids_for_entity_prefix(&"tenant-a".into(), " company")
+            .into_iter()
+            .collect();
+        prefixed.sort();
+        assert_eq!(prefixed, vec!["c1", "c2"]);
+        assert!(
+            store
+                .claim_ids_for_entity_prefix(&"tenant-b".into(), "company")
+                .is_empty()
+        );
+        assert!(
+            store
+                .claim_ids_for_entity_prefix(&"tenant-a".into(), "  ")
+                .is_empty()
+        );
+
+        let matches = store.fuzzy_entity_matches(&"tenant-a".into(), "compny x", 0.3);
+        assert_eq!(matches[0].entity, "company x");
+        assert_eq!(matches[0].claim_count, 1);
+        assert!(matches.iter().all(|matched| matched.entity != "acme"));
+        assert_eq!(
+            store.claim_ids_for_entity_fuzzy(&"tenant-a".into(), "compny x", 0.5),
+            HashSet::from(["c1".into()])
+        );
+
+        store
+            .delete_claim(&"tenant-a".into(), &"c1".into())
+            .unwrap();
+        assert!(
+            store
+                .fuzzy_entity_matches(&"tenant-a".into(), "compny x", 0.5)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn standing_queries_record_new_matching_claims_in_their_feed() {
+        let mut store = InMemoryStore::new();
+        store
+            .ingest_bundle(
+                claim("c-old", "Company X acquired Company Y"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        assert!(
+            store
+                .register_standing_query(
+                    &"tenant-a".into(),
+                    "acquisitions",
+                    StandingQuery::new("acquired")
+                )
+                .is_none()
+        );
+        store.register_standing_query(
+            &"tenant-a".into(),
+            "acme-acquisitions",
+            StandingQuery::new("acquired")
+                .with_options(RetrievalOptions::new().with_entities(["Acme"])),
+        );
+
+        let mut acme = claim("c-acme", "Acme acquired a rival");
+        acme.entities = vec!["Acme".into()];
+        store.ingest_bundle(acme.clone(), vec![], vec![]).unwrap();
+        store
+            .ingest_bundle(claim("c-other", "Revenue grew in March"), vec![], vec![])
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim_for_tenant("c-foreign", "Beta acquired Gamma", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(claim("c-later", "Delta acquired Epsilon"), vec![], vec![])
+            .unwrap();
+        store.ingest_bundle(acme, vec![], vec![]).unwrap();
+
+        let matches = store
+            .standing_query_matches(&"tenant-a".into(), "acquisitions", 0, 10)
+            .unwrap();
+        let ids: Vec<(u64, &str)> = matches
+            .iter()
+            .map(|matched| (matched.sequence, matched.claim_id.as_str()))
+            .collect();
+        assert_eq!(ids, vec![(0, "c-acme"), (1, "c-later")]);
+        assert!(matches.iter().all(|matched| matched.score > 0.0));
+        let page = store
+            .standing_query_matches(&"tenant-a".into(), "acquisitions", 1, 10)
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].claim_id, "c-later");
+
+        let acme_only = store
+            .standing_query_matches(&"tenant-a".into(), "acme-acquisitions", 0, 10)
+            .unwrap();
+        assert_eq!(acme_only.len(), 1);
+        assert_eq!(acme_only[0].claim_id, "c-acme");
+        assert_eq!(
+            store.standing_query_ids(&"tenant-a".into()),
+            vec!["acme-acquisitions", "acquisitions"]
+        );
+
+        assert!(store.remove_standing_query(&"tenant-a".into(), "acquisitions"));
+        assert!(
+            store
+                .standing_query_matches(&"tenant-a".into(), "acquisitions", 0, 10)
+                .is_none()
+        );
+        assert_eq!(store.metrics_snapshot().retrievals, 0);
+    }
+
+    #[test]
+    fn cross_tenant_search_requires_the_capability_and_tags_each_tenant() {
+        let mut store = InMemoryStore::new();
+        for (id, text, tenant_id) in [
+            ("a-1", "phishing kit sold on forum", "tenant-a"),
+            ("b-1", "phishing campaign reported", "tenant-b"),
+            ("b-2", "quarterly revenue grew", "tenant-b"),
+        ] {
+            store
+                .ingest_bundle(claim_for_tenant(id, text, tenant_id), vec![], vec![])
+                .unwrap();
+        }
+        let req = RetrievalRequest::new("ignored", "phishing", 10);
+        assert!(matches!(
+            store.retrieve_all_tenants(&req),
+            Err(StoreError::Forbidden(_))
+        ));
+
+        store.set_cross_tenant_search_enabled(true);
+        let results = store.retrieve_all_tenants(&req).unwrap();
+        let mut tagged: Vec<(&str, &str)> = results
+            .iter()
+            .map(|hit| (hit.tenant_id.as_str(), hit.result.claim_id.as_str()))
+            .collect();
+        tagged.sort();
+        assert_eq!(tagged, vec![("tenant-a", "a-1"), ("tenant-b", "b-1")]);
+        assert!(
+            results
+                .windows(2)
+                .all(|pair| pair[0].result.score >= pair[1].result.score)
+        );
+
+        let mut req = req;
+        req.top_k = 1;
+        let top = store.retrieve_all_tenants(&req).unwrap();
+        assert_eq!(top.len(), 1);
+    }
+
+    fn index_snapshot_fixture() -> InMemoryStore {
+        let mut store = InMemoryStore::new();
+        let mut first = claim("c1", "acme acquired acme labs");
+        first.entities = vec!["Acme".into(), "Acme, Labs".into()];
+        first.event_time_unix = Some(90);
+        let mut second = claim("c2", "acme grew");
+        second.entities = vec!["Acme".into()];
+        second.event_time_unix = Some(30);
+        store.ingest_bundle(first, vec![], vec![]).unwrap();
+        store.ingest_bundle(second, vec![], vec![]).unwrap();
+        store.set_temporal_granularity(TemporalGranularity::Minute);
+        store
+    }
+
+    #[test]
+    fn index_snapshot_exports_terms_entities_and_timeline_as_csv() {
+        let store = index_snapshot_fixture();
+        let snapshot = store.index_snapshot(&"tenant-a".into());
+        assert_eq!(
+            snapshot.terms.iter().find(|row| row.term == "acme"),
+            Some(&TermSnapshotRow {
+                term: "acme".to_string(),
+                document_frequency: 2,
+                collection_frequency: 3,
+            })
+        );
+
+        let mut entities = Vec::new();
+        assert_eq!(
+            snapshot
+                .write_csv(IndexSnapshotTable::Entities, &mut entities)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            String::from_utf8(entities).unwrap(),
+            "tenant_id,entity,claim_count\ntenant-a,acme,2\ntenant-a,\"acme, labs\",1\n"
+        );
+
+        let mut temporal = Vec::new();
+        snapshot
+            .write_csv(IndexSnapshotTable::Temporal, &mut temporal)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(temporal).unwrap(),
+            "tenant_id,granularity,bucket_start_unix,claim_count\n\
+             tenant-a,minute,0,1\ntenant-a,minute,60,1\n"
+        );
+        assert!(store.index_snapshot(&"tenant-b".into()).terms.is_empty());
+        assert_eq!(
+            IndexSnapshotTable::parse("Terms"),
+            Some(IndexSnapshotTable::Terms)
+        );
+    }
+
+    #[cfg(feature = "arrow-export")]
+    #[test]
+    fn index_snapshot_exports_arrow_ipc_files() {
+        let snapshot = index_snapshot_fixture().index_snapshot("tenant-a");
+        let mut bytes = Vec::new();
+        let rows = snapshot
+            .write_arrow_ipc(IndexSnapshotTable::Terms, &mut bytes)
+            .unwrap();
+        assert_eq!(rows, snapshot.terms.len());
+
+        let reader =
+            arrow_ipc::reader::FileReader::try_new(std::io::Cursor::new(bytes), None).unwrap();
+        let batches: Vec<_> = reader.map(|batch| batch.unwrap()).collect();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), rows);
+        assert_eq!(batches[0].schema().field(2).name(), "document_frequency");
+    }
+
+    #[test]
+    fn tenant_stats_break_down_records_memory_and_wal_usage() {
+        let wal_path = temp_wal_path();
+        let mut wal = FileWal::open(&wal_path).unwrap();
+        let mut store = InMemoryStore::new();
+        let evidence = Evidence {
+            evidence_id: "e1".into(),
+            claim_id: "a-1".into(),
+            source_id: "doc-1".into(),
+            stance: Stance::Supports,
+            source_quality: 0.9,
+            chunk_id: None,
+            span_start: None,
+            span_end: None,
+            doc_id: None,
+            extraction_model: None,
+            ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
+        };
+        let mut with_entity = claim("a-1", "Company X acquired Company Y");
+        with_entity.entities = vec!["Company X".into()];
+        with_entity.event_time_unix = Some(100);
+        store
+            .ingest_bundle_persistent(&mut wal, with_entity, vec![evidence], vec![])
+            .unwrap();
+        store
+            .ingest_bundle_persistent(
+                &mut wal,
+                claim_for_tenant("b-1", "Revenue grew", "tenant-b"),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .upsert_claim_vector_persistent(&mut wal, &"a-1".into(), vec![0.1, 0.2, 0.3])
+            .unwrap();
+
+        let stats = store.tenant_stats(&"tenant-a".into());
+        assert_eq!(
+            (stats.claims, stats.evidence, stats.edges, stats.vectors),
+            (1, 1, 0, 1)
+        );
+        assert!(stats.last_ingest_unix_ms.is_some());
+        assert_eq!(stats.memory.vector_bytes, 3 * std::mem::size_of::<f32>());
+        assert!(stats.memory.inverted_index_bytes > 0);
+        assert!(stats.memory.entity_index_bytes > 0);
+        assert!(stats.memory.temporal_index_bytes > 0);
+        assert_eq!(
+            store
+                .tenant_stats(&"tenant-b".into())
+                .memory
+                .entity_index_bytes,
+            0
+        );
+        assert_eq!(
+            store.tenant_stats(&"tenant-c".into()),
+            TenantStats {
+                tenant_id: "tenant-c".into(),
+                ..TenantStats::default()
+            }
+        );
+
+        let usage = store.tenant_wal_usage(&wal).unwrap();
+        assert_eq!(usage["tenant-a"].wal_records, 3);
+        assert_eq!(usage["tenant-b"].wal_records, 1);
+        assert_eq!(
+            usage.values().map(|tenant| tenant.wal_bytes).sum::<u64>(),
+            wal.wal_size_bytes().unwrap()
+        );
+
+        let replayed = InMemoryStore::load_from_wal(&wal).unwrap();
+        assert_eq!(
+            replayed
+                .tenant_stats(&"tenant-a".into())
+                .last_ingest_unix_ms,
+            None
+        );
+        cleanup_persistence_files(&wal);
+    }
 }
@@ -0,0 +1,236 @@
+//! An embeddable, single-process memory.
+//!
+//! [`DashMemory`] owns a store and its WAL in one data directory and
+//! hides the plumbing the services do by hand: replay on open, WAL
+//! appends, checkpointing under a [`CheckpointPolicy`], applying queued
+//! read repairs, and, when an embedder is attached, embedding claims on
+//! write and queries on read. Applications that only need
+//! `remember`/`recall`/`forget` can start from
+//! [`DashMemoryConfig::new`] and change nothing else.
+
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use embeddings::EmbeddingProvider;
+use schema::{Claim, ClaimEdge, ClaimId, Evidence, RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::{
+    AnnTuningConfig, CheckpointPolicy, FileWal, InMemoryStore, StoreError, WalCheckpointStats,
+    WalWritePolicy,
+};
+
+/// Name of the WAL file inside [`DashMemoryConfig::data_dir`]; its
+/// snapshot sits next to it.
+pub(crate) const DASH_MEMORY_WAL_FILE: &str = "wal.log";
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct DashMemoryConfig {
+    pub data_dir: PathBuf,
+    pub wal_policy: WalWritePolicy,
+    pub checkpoint_policy: CheckpointPolicy,
+    pub ann_tuning: AnnTuningConfig,
+    /// Results returned by [`DashMemory::recall`].
+    pub default_top_k: usize,
+}
+
+impl DashMemoryConfig {
+    /// Defaults for `data_dir`: every append synced, a checkpoint every
+    /// 10,000 WAL records or 64 MiB, writes slowed once checkpoints fall
+    /// twice that far behind and stopped at four times, and five results
+    /// per recall.
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            wal_policy: WalWritePolicy::default(),
+            checkpoint_policy: CheckpointPolicy {
+                max_wal_records: Some(10_000),
+                max_wal_bytes: Some(64 * 1024 * 1024),
+                write_slowdown_multiple: Some(2),
+                write_stop_multiple: Some(4),
+            },
+            ann_tuning: AnnTuningConfig::default(),
+            default_top_k: 5,
+        }
+    }
+
+    pub fn wal_path(&self) -> PathBuf {
+        self.data_dir.join(DASH_MEMORY_WAL_FILE)
+    }
+}
+
+/// What one [`DashMemory::maintain`] pass did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DashMemoryMaintenance {
+    pub read_repairs_applied: usize,
+    pub wal_synced: bool,
+    pub checkpoint: Option<WalCheckpointStats>,
+}
+
+pub struct DashMemory {
+    store: InMemoryStore,
+    wal: FileWal,
+    config: DashMemoryConfig,
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+impl DashMemory {
+    /// Open the memory in `config.data_dir`, creating the directory on
+    /// first use and replaying whatever it already holds.
+    pub fn open(config: DashMemoryConfig) -> Result<Self, StoreError> {
+        create_dir_all(&config.data_dir)?;
+        let wal = FileWal::open_with_policy(config.wal_path(), config.wal_policy.clone())?;
+        let store = InMemoryStore::load_from_wal_with_ann_tuning(&wal, config.ann_tuning.clone())?;
+        Ok(Self {
+            store,
+            wal,
+            config,
+            embedder: None,
+        })
+    }
+
+    /// [`Self::open`] with [`DashMemoryConfig::new`] defaults.
+    pub fn open_dir(data_dir: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::open(DashMemoryConfig::new(data_dir.as_ref()))
+    }
+
+    /// Embed claims as they are remembered and queries as they are
+    /// recalled. Claims remembered before an embedder was attached stay
+    /// lexical-only.
+    pub fn with_embedder(mut self, embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    pub fn config(&self) -> &DashMemoryConfig {
+        &self.config
+    }
+
+    /// The underlying store, for anything the façade does not cover.
+    pub fn store(&self) -> &InMemoryStore {
+        &self.store
+    }
+
+    /// Store `text` as a new claim for `tenant_id` and return its id.
+    /// With an embedder attached the text is embedded first, and an
+    /// embedding failure leaves nothing written.
+    pub fn remember(&mut self, tenant_id: &TenantId, text: &str) -> Result<ClaimId, StoreError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        let claim =
+            Claim::new(uuid::Uuid::new_v4().to_string(), tenant_id, text, 1.0).with_created_at(now);
+        let claim_id = claim.claim_id.clone();
+        self.remember_claim(claim, Vec::new(), Vec::new())?;
+        Ok(claim_id)
+    }
+
+    /// Store a fully specified claim bundle, embedding its canonical text
+    /// when an embedder is attached.
+    pub fn remember_claim(
+        &mut self,
+        claim: Claim,
+        evidence: Vec<Evidence>,
+        edges: Vec<ClaimEdge>,
+    ) -> Result<(), StoreError> {
+        let vector = match &self.embedder {
+            Some(embedder) => Some(embed_one(embedder.as_ref(), &claim.canonical_text)?),
+            None => None,
+        };
+        let claim_id = claim.claim_id.clone();
+        if let Some(delay) = self
+            .store
+            .throttle_write(&mut self.wal, &self.config.checkpoint_policy)?
+        {
+            std::thread::sleep(delay);
+        }
+        self.store
+            .ingest_bundle_persistent(&mut self.wal, claim, evidence, edges)?;
+        if let Some(vector) = vector {
+            self.store
+                .upsert_claim_vector_persistent(&mut self.wal, &claim_id, vector)?;
+        }
+        self.checkpoint_if_due()?;
+        Ok(())
+    }
+
+    /// The `default_top_k` claims best matching `query`. With an embedder
+    /// attached the query is embedded for semantic ranking; if that fails
+    /// the recall falls back to lexical ranking rather than erroring.
+    pub fn recall(&self, tenant_id: &TenantId, query: &str) -> Vec<RetrievalResult> {
+        self.recall_top_k(tenant_id, query, self.config.default_top_k)
+    }
+
+    pub fn recall_top_k(
+        &self,
+        tenant_id: &TenantId,
+        query: &str,
+        top_k: usize,
+    ) -> Vec<RetrievalResult> {
+        let req = RetrievalRequest::new(tenant_id, query, top_k);
+        let query_vector = self
+            .embedder
+            .as_ref()
+            .and_then(|embedder| embed_one(embedder.as_ref(), query).ok());
+        match query_vector {
+            Some(query_vector) => self.store.retrieve_semantic(&req, &query_vector),
+            None => self.store.retrieve(&req),
+        }
+    }
+
+    /// Delete a remembered claim. Returns `false` when the tenant holds
+    /// no claim with that id.
+    pub fn forget(&mut self, tenant_id: &TenantId, claim_id: &ClaimId) -> Result<bool, StoreError> {
+        let deleted = self
+            .store
+            .delete_claim_persistent(&mut self.wal, tenant_id, claim_id)?;
+        if deleted {
+            self.checkpoint_if_due()?;
+        }
+        Ok(deleted)
+    }
+
+    /// Housekeeping for callers without a background thread: apply queued
+    /// read repairs, sync buffered WAL appends whose interval elapsed, and
+    /// checkpoint if the policy asks for it.
+    pub fn maintain(&mut self) -> Result<DashMemoryMaintenance, StoreError> {
+        Ok(DashMemoryMaintenance {
+            read_repairs_applied: self.store.apply_read_repairs(),
+            wal_synced: self.wal.flush_pending_sync_if_interval_elapsed()?,
+            checkpoint: self.checkpoint_if_due()?,
+        })
+    }
+
+    /// Sync every buffered WAL append to disk.
+    pub fn flush(&mut self) -> Result<(), StoreError> {
+        self.wal.flush_pending_sync()
+    }
+
+    /// Snapshot the store and truncate the WAL now, whatever the policy.
+    pub fn checkpoint(&mut self) -> Result<WalCheckpointStats, StoreError> {
+        self.store.checkpoint_and_compact(&mut self.wal)
+    }
+
+    fn checkpoint_if_due(&mut self) -> Result<Option<WalCheckpointStats>, StoreError> {
+        if self
+            .store
+            .should_checkpoint(&self.wal, &self.config.checkpoint_policy)?
+        {
+            self.checkpoint().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn embed_one(embedder: &dyn EmbeddingProvider, text: &str) -> Result<Vec<f32>, StoreError> {
+    embedder
+        .embed(&[text.to_string()])
+        .map_err(|err| StoreError::InvalidVector(format!("embedding failed: {err}")))?
+        .pop()
+        .ok_or_else(|| StoreError::InvalidVector("embedder returned no vector".to_string()))
+}
@@ -0,0 +1,140 @@
+//! Filtering claims on their `metadata` labels.
+//!
+//! Every claim's `(key, value)` labels are indexed per tenant, trimmed and
+//! lowercased like entity names. A [`MetadataFilter`] matches claims with
+//! one of its values under its key, and a list of filters matches claims
+//! that satisfy all of them. [`InMemoryStore::claim_ids_matching_metadata`]
+//! resolves filters to claim ids, which retrieval takes as its allowed
+//! set when [`RetrievalOptions::metadata`](crate::RetrievalOptions::metadata)
+//! is given.
+
+use std::collections::HashSet;
+
+use schema::{Claim, ClaimId, TenantId};
+
+use crate::{InMemoryStore, normalize_index_key};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MetadataFilter {
+    Equals { key: String, value: String },
+    In { key: String, values: Vec<String> },
+}
+
+impl MetadataFilter {
+    pub fn equals(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::Equals {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn any_of(key: impl Into<String>, values: Vec<String>) -> Self {
+        Self::In {
+            key: key.into(),
+            values,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Equals { key, .. } | Self::In { key, .. } => key,
+        }
+    }
+
+    pub fn values(&self) -> &[String] {
+        match self {
+            Self::Equals { value, .. } => std::slice::from_ref(value),
+            Self::In { values, .. } => values,
+        }
+    }
+}
+
+impl InMemoryStore {
+    pub fn claim_ids_for_metadata(
+        &self,
+        tenant_id: &TenantId,
+        key: &str,
+        value: &str,
+    ) -> HashSet<ClaimId> {
+        self.metadata_index
+            .get(tenant_id.as_str())
+            .and_then(|index| index.get(&normalize_index_key(key)))
+            .and_then(|values| values.get(&normalize_index_key(value)))
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    /// Claims of `tenant_id` that match every filter. With no filters
+    /// every claim of the tenant matches.
+    pub fn claim_ids_matching_metadata(
+        &self,
+        tenant_id: &TenantId,
+        filters: &[MetadataFilter],
+    ) -> HashSet<ClaimId> {
+        let Some((first, rest)) = filters.split_first() else {
+            return self.claim_ids_for_tenant(tenant_id);
+        };
+        let matching = |filter: &MetadataFilter| -> HashSet<ClaimId> {
+            filter
+                .values()
+                .iter()
+                .flat_map(|value| self.claim_ids_for_metadata(tenant_id, filter.key(), value))
+                .collect()
+        };
+        let mut claim_ids = matching(first);
+        for filter in rest {
+            if claim_ids.is_empty() {
+                break;
+            }
+            let other = matching(filter);
+            claim_ids.retain(|claim_id| other.contains(claim_id));
+        }
+        claim_ids
+    }
+
+    pub(crate) fn index_claim_metadata(&mut self, claim: &Claim) {
+        if claim.metadata.is_empty() {
+            return;
+        }
+        let index = self
+            .metadata_index
+            .entry(claim.tenant_id.to_string())
+            .or_default();
+        for (key, value) in &claim.metadata {
+            index
+                .entry(normalize_index_key(key))
+                .or_default()
+                .entry(normalize_index_key(value))
+                .or_default()
+                .insert(claim.claim_id.to_string());
+        }
+    }
+
+    pub(crate) fn unindex_claim_metadata(&mut self, claim: &Claim) {
+        let Some(index) = self.metadata_index.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        for (key, value) in &claim.metadata {
+            let key = normalize_index_key(key);
+            let Some(values) = index.get_mut(&key) else {
+                continue;
+            };
+            let value = normalize_index_key(value);
+            if let Some(claim_ids) = values.get_mut(&value) {
+                claim_ids.remove(claim.claim_id.as_str());
+                if claim_ids.is_empty() {
+                    values.remove(&value);
+                }
+            }
+            if values.is_empty() {
+                index.remove(&key);
+            }
+        }
+        if index.is_empty() {
+            self.metadata_index.remove(claim.tenant_id.as_str());
+        }
+    }
+}
@@ -5,6 +5,9 @@
 //! store. They live in their own module so the metrics surface
 //! can evolve independently of the InMemoryStore implementation.
 
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 use crate::WalReplayStats;
@@ -73,3 +76,289 @@ pub struct StoreIndexStats {
     pub temporal_buckets: usize,
     pub ann_vector_buckets: usize,
 }
+
+/// Internal counters updated by the store on its hot paths. Every
+/// field is an atomic so read-only paths (`retrieve`, ANN search)
+/// can record without `&mut self`. Callers never see this type;
+/// they read a [`StoreMetricsSnapshot`] via
+/// `InMemoryStore::metrics_snapshot`.
+#[derive(Debug, Default)]
+pub(crate) struct StoreMetrics {
+    claims_ingested: AtomicU64,
+    evidence_ingested: AtomicU64,
+    edges_ingested: AtomicU64,
+    vectors_upserted: AtomicU64,
+    retrievals: AtomicU64,
+    retrieval_latency_micros_total: AtomicU64,
+    retrieval_latency_micros_max: AtomicU64,
+    ann_searches: AtomicU64,
+    ann_expansions: AtomicU64,
+    checkpoints: AtomicU64,
+    checkpoint_micros_total: AtomicU64,
+    checkpoint_micros_max: AtomicU64,
+    wal_bytes_written: AtomicU64,
+    pipeline_stages_over_budget: AtomicU64,
+    pipeline_stages_skipped: AtomicU64,
+    stale_index_entries: AtomicU64,
+    read_repairs: AtomicU64,
+    term_bloom_skips: AtomicU64,
+    truncated_retrievals: AtomicU64,
+    write_slowdowns: AtomicU64,
+    write_slowdown_micros_total: AtomicU64,
+    write_stops: AtomicU64,
+}
+
+impl StoreMetrics {
+    pub(crate) fn record_claim_ingested(&self) {
+        self.claims_ingested.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_evidence_ingested(&self) {
+        self.evidence_ingested.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_edge_ingested(&self) {
+        self.edges_ingested.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_vector_upserted(&self) {
+        self.vectors_upserted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_retrieval(&self, elapsed: Duration) {
+        let micros = duration_micros(elapsed);
+        self.retrievals.fetch_add(1, Ordering::Relaxed);
+        self.retrieval_latency_micros_total
+            .fetch_add(micros, Ordering::Relaxed);
+        self.retrieval_latency_micros_max
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_ann_search(&self, expansions: usize) {
+        self.ann_searches.fetch_add(1, Ordering::Relaxed);
+        self.ann_expansions
+            .fetch_add(expansions as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_checkpoint(&self, elapsed: Duration) {
+        let micros = duration_micros(elapsed);
+        self.checkpoints.fetch_add(1, Ordering::Relaxed);
+        self.checkpoint_micros_total
+            .fetch_add(micros, Ordering::Relaxed);
+        self.checkpoint_micros_max
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_wal_bytes(&self, bytes: u64) {
+        self.wal_bytes_written.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_pipeline_stage_over_budget(&self) {
+        self.pipeline_stages_over_budget
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_pipeline_stage_skipped(&self) {
+        self.pipeline_stages_skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_stale_index_entry(&self) {
+        self.stale_index_entries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_read_repair(&self) {
+        self.read_repairs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_term_bloom_skip(&self) {
+        self.term_bloom_skips.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_retrieval_truncated(&self) {
+        self.truncated_retrievals.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_write_slowdown(&self, delay: Duration) {
+        self.write_slowdowns.fetch_add(1, Ordering::Relaxed);
+        self.write_slowdown_micros_total
+            .fetch_add(duration_micros(delay), Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_write_stop(&self) {
+        self.write_stops.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> StoreMetricsSnapshot {
+        StoreMetricsSnapshot {
+            claims_ingested: self.claims_ingested.load(Ordering::Relaxed),
+            evidence_ingested: self.evidence_ingested.load(Ordering::Relaxed),
+            edges_ingested: self.edges_ingested.load(Ordering::Relaxed),
+            vectors_upserted: self.vectors_upserted.load(Ordering::Relaxed),
+            retrievals: self.retrievals.load(Ordering::Relaxed),
+            retrieval_latency_micros_total: self
+                .retrieval_latency_micros_total
+                .load(Ordering::Relaxed),
+            retrieval_latency_micros_max: self.retrieval_latency_micros_max.load(Ordering::Relaxed),
+            ann_searches: self.ann_searches.load(Ordering::Relaxed),
+            ann_expansions: self.ann_expansions.load(Ordering::Relaxed),
+            checkpoints: self.checkpoints.load(Ordering::Relaxed),
+            checkpoint_micros_total: self.checkpoint_micros_total.load(Ordering::Relaxed),
+            checkpoint_micros_max: self.checkpoint_micros_max.load(Ordering::Relaxed),
+            wal_bytes_written: self.wal_bytes_written.load(Ordering::Relaxed),
+            pipeline_stages_over_budget: self.pipeline_stages_over_budget.load(Ordering::Relaxed),
+            pipeline_stages_skipped: self.pipeline_stages_skipped.load(Ordering::Relaxed),
+            stale_index_entries: self.stale_index_entries.load(Ordering::Relaxed),
+            read_repairs: self.read_repairs.load(Ordering::Relaxed),
+            term_bloom_skips: self.term_bloom_skips.load(Ordering::Relaxed),
+            truncated_retrievals: self.truncated_retrievals.load(Ordering::Relaxed),
+            write_slowdowns: self.write_slowdowns.load(Ordering::Relaxed),
+            write_slowdown_micros_total: self.write_slowdown_micros_total.load(Ordering::Relaxed),
+            write_stops: self.write_stops.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Add the counts `staged` recorded for a write that has since
+    /// committed.
+    pub(crate) fn absorb(&self, staged: &StoreMetrics) {
+        for (counter, recorded) in [
+            (&self.claims_ingested, &staged.claims_ingested),
+            (&self.evidence_ingested, &staged.evidence_ingested),
+            (&self.edges_ingested, &staged.edges_ingested),
+            (&self.vectors_upserted, &staged.vectors_upserted),
+            (&self.retrievals, &staged.retrievals),
+            (
+                &self.retrieval_latency_micros_total,
+                &staged.retrieval_latency_micros_total,
+            ),
+            (&self.ann_searches, &staged.ann_searches),
+            (&self.ann_expansions, &staged.ann_expansions),
+            (&self.checkpoints, &staged.checkpoints),
+            (
+                &self.checkpoint_micros_total,
+                &staged.checkpoint_micros_total,
+            ),
+            (&self.wal_bytes_written, &staged.wal_bytes_written),
+            (
+                &self.pipeline_stages_over_budget,
+                &staged.pipeline_stages_over_budget,
+            ),
+            (
+                &self.pipeline_stages_skipped,
+                &staged.pipeline_stages_skipped,
+            ),
+            (&self.stale_index_entries, &staged.stale_index_entries),
+            (&self.read_repairs, &staged.read_repairs),
+            (&self.term_bloom_skips, &staged.term_bloom_skips),
+            (&self.truncated_retrievals, &staged.truncated_retrievals),
+            (&self.write_slowdowns, &staged.write_slowdowns),
+            (
+                &self.write_slowdown_micros_total,
+                &staged.write_slowdown_micros_total,
+            ),
+            (&self.write_stops, &staged.write_stops),
+        ] {
+            counter.fetch_add(recorded.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        for (counter, recorded) in [
+            (
+                &self.retrieval_latency_micros_max,
+                &staged.retrieval_latency_micros_max,
+            ),
+            (&self.checkpoint_micros_max, &staged.checkpoint_micros_max),
+        ] {
+            counter.fetch_max(recorded.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+    }
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.claims_ingested,
+            &self.evidence_ingested,
+            &self.edges_ingested,
+            &self.vectors_upserted,
+            &self.retrievals,
+            &self.retrieval_latency_micros_total,
+            &self.retrieval_latency_micros_max,
+            &self.ann_searches,
+            &self.ann_expansions,
+            &self.checkpoints,
+            &self.checkpoint_micros_total,
+            &self.checkpoint_micros_max,
+            &self.wal_bytes_written,
+            &self.pipeline_stages_over_budget,
+            &self.pipeline_stages_skipped,
+            &self.stale_index_entries,
+            &self.read_repairs,
+            &self.term_bloom_skips,
+            &self.truncated_retrievals,
+            &self.write_slowdowns,
+            &self.write_slowdown_micros_total,
+            &self.write_stops,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+fn duration_micros(elapsed: Duration) -> u64 {
+    u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// Point-in-time copy of the store's internal counters. Counters
+/// are monotonic since store construction (or the last
+/// `reset_metrics`); consumers compute rates by diffing two
+/// snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct StoreMetricsSnapshot {
+    pub claims_ingested: u64,
+    pub evidence_ingested: u64,
+    pub edges_ingested: u64,
+    pub vectors_upserted: u64,
+    pub retrievals: u64,
+    pub retrieval_latency_micros_total: u64,
+    pub retrieval_latency_micros_max: u64,
+    pub ann_searches: u64,
+    pub ann_expansions: u64,
+    pub checkpoints: u64,
+    pub checkpoint_micros_total: u64,
+    pub checkpoint_micros_max: u64,
+    pub wal_bytes_written: u64,
+    /// Budgeted pipeline stages that ran past their budget.
+    pub pipeline_stages_over_budget: u64,
+    /// Optional pipeline stages skipped because their breaker was open.
+    pub pipeline_stages_skipped: u64,
+    /// Index entries naming a missing claim, found by retrieval with read
+    /// repair enabled.
+    pub stale_index_entries: u64,
+    /// Claims whose stale index entries read repair removed.
+    pub read_repairs: u64,
+    /// Lexical queries whose tokens the tenant's term bloom filter ruled
+    /// out before probing the inverted index.
+    pub term_bloom_skips: u64,
+    /// Retrievals whose deadline passed before every candidate was
+    /// considered.
+    pub truncated_retrievals: u64,
+    /// Writes delayed because checkpoints fell behind, and the total
+    /// delay.
+    pub write_slowdowns: u64,
+    pub write_slowdown_micros_total: u64,
+    /// Writes rejected because checkpoints fell too far behind.
+    pub write_stops: u64,
+}
+
+impl StoreMetricsSnapshot {
+    /// Mean retrieval latency in microseconds, or `0` before the
+    /// first retrieval.
+    pub fn retrieval_latency_micros_avg(&self) -> u64 {
+        self.retrieval_latency_micros_total
+            .checked_div(self.retrievals)
+            .unwrap_or(0)
+    }
+
+    /// Mean checkpoint duration in microseconds, or `0` before the
+    /// first checkpoint.
+    pub fn checkpoint_micros_avg(&self) -> u64 {
+        self.checkpoint_micros_total
+            .checked_div(self.checkpoints)
+            .unwrap_or(0)
+    }
+}
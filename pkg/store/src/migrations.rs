@@ -0,0 +1,173 @@
+//! Record layout versions and the upgrades that read older lines.
+//!
+//! A WAL or snapshot record is a line of tab-separated fields behind a
+//! kind letter. Kinds whose layout has grown carry the layout version
+//! in that header, written `C@7`, followed by every field of that
+//! layout. Lines written before versioning have a bare kind; their
+//! version is inferred from the field count, and this module is the
+//! only place that still looks at it.
+//!
+//! [`RecordLayout::upgrade`] hands back the encoded defaults of the
+//! fields an older version predates, so the parser in `wal` always
+//! reads the current layout at fixed positions. Adding a field to a
+//! versioned kind means appending one field count and one default to
+//! its layout below. Kinds without a layout have only ever had one
+//! and are written bare.
+
+use crate::StoreError;
+
+/// The versions of one record kind's layout.
+pub(crate) struct RecordLayout {
+    /// The kind letter that starts the line.
+    pub(crate) kind: &'static str,
+    /// The header written for the current version.
+    pub(crate) header: &'static str,
+    /// Used in parse errors, as in "claim record".
+    name: &'static str,
+    /// Field count of each version, oldest first, counting the header.
+    field_counts: &'static [usize],
+    /// Encoded values of the fields added after the first version, in
+    /// field order.
+    defaults: &'static [&'static str],
+}
+
+pub(crate) const CLAIM: RecordLayout = RecordLayout {
+    kind: "C",
+    header: "C@8",
+    name: "claim",
+    field_counts: &[6, 8, 13, 14, 15, 16, 17, 18],
+    defaults: &[
+        "",     // entities
+        "",     // embedding ids
+        "null", // claim type
+        "null", // valid from
+        "null", // valid to
+        "null", // created at
+        "null", // updated at
+        "",     // metadata
+        "",     // entity details
+        "",     // visibility labels
+        "",     // language
+        "",     // collection
+    ],
+};
+
+pub(crate) const EVIDENCE: RecordLayout = RecordLayout {
+    kind: "E",
+    header: "E@5",
+    name: "evidence",
+    field_counts: &[6, 9, 12, 13, 15],
+    defaults: &[
+        "null", // chunk id
+        "null", // span start
+        "null", // span end
+        "null", // doc id
+        "null", // extraction model
+        "null", // ingested at
+        "",     // language
+        "null", // stance strength
+        "0",    // negated
+    ],
+};
+
+pub(crate) const CLAIM_VECTOR: RecordLayout = RecordLayout {
+    kind: "V",
+    header: "V@2",
+    name: "vector",
+    field_counts: &[3, 4],
+    defaults: &[
+        "", // vector space, empty for the default one
+    ],
+};
+
+pub(crate) const TENANT_VECTOR_CONFIG: RecordLayout = RecordLayout {
+    kind: "T",
+    header: "T@6",
+    name: "tenant vector config",
+    // The first version only ever held a `null` ANN tuning; the tuning
+    // fields after it are ignored while that is so.
+    field_counts: &[5, 9, 10, 11, 12, 13],
+    defaults: &[
+        "",      // max neighbors upper
+        "",      // search expansion factor
+        "",      // search expansion min
+        "",      // search expansion max
+        "graph", // index kind
+        "0",     // exact search threshold
+        "0.4",   // level multiplier
+        "0",     // level seed
+    ],
+};
+
+pub(crate) const TEXT_ANALYZER: RecordLayout = RecordLayout {
+    kind: "L",
+    header: "L@2",
+    name: "text analyzer",
+    field_counts: &[4, 5],
+    defaults: &[
+        "ascii", // tokenizer
+    ],
+};
+
+pub(crate) const LAYOUTS: [&RecordLayout; 5] = [
+    &CLAIM,
+    &EVIDENCE,
+    &CLAIM_VECTOR,
+    &TENANT_VECTOR_CONFIG,
+    &TEXT_ANALYZER,
+];
+
+impl RecordLayout {
+    /// Field count of the current version, counting the header.
+    pub(crate) const fn field_count(&self) -> usize {
+        self.field_counts[self.field_counts.len() - 1]
+    }
+
+    /// Checks a line of `field_count` fields against `version`, or
+    /// infers the version of a bare line, and returns the defaults to
+    /// append to bring it to the current version.
+    pub(crate) fn upgrade(
+        &self,
+        version: Option<u32>,
+        field_count: usize,
+    ) -> Result<&'static [&'static str], StoreError> {
+        let index = match version {
+            Some(version) => {
+                let index = (version as usize)
+                    .checked_sub(1)
+                    .filter(|index| *index < self.field_counts.len())
+                    .ok_or_else(|| {
+                        StoreError::Parse(format!(
+                            "{} record has unknown layout version {version}",
+                            self.name
+                        ))
+                    })?;
+                (self.field_counts[index] == field_count).then_some(index)
+            }
+            None => self
+                .field_counts
+                .iter()
+                .position(|count| *count == field_count),
+        };
+        let index = index.ok_or_else(|| {
+            StoreError::Parse(format!("{} record has invalid field count", self.name))
+        })?;
+        Ok(&self.defaults[self.field_counts[index] - self.field_counts[0]..])
+    }
+}
+
+/// The layout of a versioned record kind.
+pub(crate) fn layout(kind: &str) -> Option<&'static RecordLayout> {
+    LAYOUTS.into_iter().find(|layout| layout.kind == kind)
+}
+
+/// Splits a record header into its kind and layout version, if any.
+pub(crate) fn parse_header(header: &str) -> Result<(&str, Option<u32>), StoreError> {
+    match header.split_once('@') {
+        None => Ok((header, None)),
+        Some((kind, version)) => version
+            .parse::<u32>()
+            .map(|version| (kind, Some(version)))
+            .map_err(|_| StoreError::Parse("wal record has invalid layout version".to_string())),
+    }
+}
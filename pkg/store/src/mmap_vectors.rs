@@ -0,0 +1,195 @@
+//! Memory-mapped claim vectors for tenants that outgrow memory.
+//!
+//! With [`MmapVectorConfig`] set, a tenant whose vectors exceed
+//! `tenant_budget_bytes` is moved into its own file under `directory`.
+//! The file is an array of fixed-size slots, one `f32` vector of the
+//! tenant's dimension each; an in-memory id table maps claim ids to
+//! slots and freed slots are reused. Reads go through the map, so the
+//! page cache rather than the heap holds the tenant's vectors and ANN
+//! search touches only the pages it needs.
+//!
+//! Mapped files are scratch space: the WAL and snapshots remain the
+//! source of truth, every file is created under a fresh name when its
+//! tenant is mapped, and it is deleted when dropped. A cloned store copies mapped
+//! vectors into anonymous maps instead of sharing the file.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+
+use memmap2::MmapMut;
+
+const F32_BYTES: usize = std::mem::size_of::<f32>();
+
+/// Smallest slot capacity of a new tenant file.
+const INITIAL_SLOTS: usize = 64;
+
+/// Where and when tenants' vectors move to memory-mapped files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmapVectorConfig {
+    /// Directory holding one file per mapped tenant.
+    pub directory: PathBuf,
+    /// Vector bytes (counted at `f32`) above which a tenant is mapped.
+    pub tenant_budget_bytes: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct TenantVectorFile {
+    /// `None` for anonymous copies made by `clone`.
+    backing: Option<(PathBuf, File)>,
+    map: MmapMut,
+    dimension: usize,
+    capacity: usize,
+    slots: HashMap<String, usize>,
+    free_slots: Vec<usize>,
+    next_slot: usize,
+}
+
+impl TenantVectorFile {
+    pub(crate) fn create(path: PathBuf, dimension: usize) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        let capacity = INITIAL_SLOTS;
+        file.set_len((capacity * dimension.max(1) * F32_BYTES) as u64)?;
+        // SAFETY: the file was just created and truncated by this
+        // process, and nothing else maps or resizes it while we hold it.
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        Ok(Self {
+            backing: Some((path, file)),
+            map,
+            dimension: dimension.max(1),
+            capacity,
+            slots: HashMap::new(),
+            free_slots: Vec::new(),
+            next_slot: 0,
+        })
+    }
+
+    pub(crate) fn insert(&mut self, claim_id: &str, values: &[f32]) -> io::Result<()> {
+        if values.len() != self.dimension {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "vector has {} dimensions, mapped file holds {}",
+                    values.len(),
+                    self.dimension
+                ),
+            ));
+        }
+        let slot = match self.slots.get(claim_id) {
+            Some(slot) => *slot,
+            None => {
+                let slot = match self.free_slots.pop() {
+                    Some(slot) => slot,
+                    None => {
+                        if self.next_slot == self.capacity {
+                            self.grow()?;
+                        }
+                        self.next_slot += 1;
+                        self.next_slot - 1
+                    }
+                };
+                self.slots.insert(claim_id.to_string(), slot);
+                slot
+            }
+        };
+        let start = slot * self.dimension * F32_BYTES;
+        for (chunk, value) in self.map[start..start + self.dimension * F32_BYTES]
+            .chunks_exact_mut(F32_BYTES)
+            .zip(values)
+        {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove(&mut self, claim_id: &str) -> bool {
+        let Some(slot) = self.slots.remove(claim_id) else {
+            return false;
+        };
+        self.free_slots.push(slot);
+        true
+    }
+
+    pub(crate) fn get(&self, claim_id: &str) -> Option<Vec<f32>> {
+        let slot = *self.slots.get(claim_id)?;
+        let start = slot * self.dimension * F32_BYTES;
+        Some(
+            self.map[start..start + self.dimension * F32_BYTES]
+                .chunks_exact(F32_BYTES)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Bytes of the mapping, including unused slots.
+    pub(crate) fn mapped_bytes(&self) -> usize {
+        self.map.len()
+    }
+
+    fn grow(&mut self) -> io::Result<()> {
+        let capacity = self.capacity.saturating_mul(2).max(INITIAL_SLOTS);
+        let bytes = capacity * self.dimension * F32_BYTES;
+        match &self.backing {
+            Some((_, file)) => {
+                self.map.flush_async()?;
+                file.set_len(bytes as u64)?;
+                // SAFETY: as in `create`; the old map is dropped on
+                // assignment and no references into it outlive this call.
+                self.map = unsafe { MmapMut::map_mut(file)? };
+            }
+            None => {
+                let mut map = MmapMut::map_anon(bytes)?;
+                map[..self.map.len()].copy_from_slice(&self.map);
+                self.map = map;
+            }
+        }
+        self.capacity = capacity;
+        Ok(())
+    }
+}
+
+impl Clone for TenantVectorFile {
+    fn clone(&self) -> Self {
+        let mut map =
+            MmapMut::map_anon(self.map.len()).expect("anonymous map for a cloned vector file");
+        map.copy_from_slice(&self.map);
+        Self {
+            backing: None,
+            map,
+            dimension: self.dimension,
+            capacity: self.capacity,
+            slots: self.slots.clone(),
+            free_slots: self.free_slots.clone(),
+            next_slot: self.next_slot,
+        }
+    }
+}
+
+impl Drop for TenantVectorFile {
+    fn drop(&mut self) {
+        if let Some((path, _)) = &self.backing {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// A fresh file name for `tenant_id`'s vectors. Hex keeps arbitrary
+/// tenant ids path-safe; the random suffix keeps stores that share a
+/// directory (a store and its replayed copy, say) off each other's files.
+pub(crate) fn tenant_vector_file_name(tenant_id: &str) -> String {
+    let hex: String = tenant_id
+        .bytes()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("tenant-{hex}-{}.vectors", uuid::Uuid::new_v4().simple())
+}
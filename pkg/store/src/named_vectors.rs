@@ -0,0 +1,422 @@
+//! Named vector spaces: more than one vector per claim.
+//!
+//! A claim's default vector ([`InMemoryStore::upsert_claim_vector`]) lives
+//! in the store's own index. Further vectors, such as a `title` or
+//! `entity` embedding next to the body one, are upserted under a space
+//! name with [`InMemoryStore::upsert_named_claim_vector`]. Each of a
+//! tenant's spaces is an inner store holding placeholder claims and that
+//! space's vectors only, so it has its own dimension (pinned by its first
+//! vector) and its own ANN index, built by the same code as the default
+//! space with the metric and tuning the tenant had when the space was
+//! created.
+//!
+//! [`RetrievalOptions::with_vector_spaces`] searches any mix of
+//! spaces: each contributes its nearest claims as candidates, and their
+//! normalized similarities are fused by weight into the dense signal.
+//! Named vectors are persisted through the WAL and snapshots; the redb
+//! mirror holds default vectors only.
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use schema::{Claim, ClaimId, RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::pipeline::PipelineScope;
+use crate::wal::{ClaimVectorRecord, PersistedRecord};
+use crate::{
+    AnnSearchOverrides, ChangeRecord, FileWal, InMemoryStore, RetrievalHit, RetrievalOptions,
+    StoreError, TenantVectorConfig, claim_matches_time_range, validate_vector,
+    vector_candidate_pool,
+};
+
+/// One vector space to search and its share of the fused similarity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorSpaceQuery {
+    /// `None` searches the claims' default vectors.
+    pub space: Option<String>,
+    pub vector: Vec<f32>,
+    pub weight: f32,
+}
+
+impl VectorSpaceQuery {
+    pub fn default_space(vector: Vec<f32>) -> Self {
+        Self {
+            space: None,
+            vector,
+            weight: 1.0,
+        }
+    }
+
+    pub fn named(space: impl Into<String>, vector: Vec<f32>) -> Self {
+        Self {
+            space: Some(space.into()),
+            vector,
+            weight: 1.0,
+        }
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+impl InMemoryStore {
+    /// Store `vector` as `claim_id`'s vector in the named `space`,
+    /// replacing any previous one there.
+    pub fn upsert_named_claim_vector(
+        &mut self,
+        claim_id: &ClaimId,
+        space: &str,
+        vector: Vec<f32>,
+    ) -> Result<(), StoreError> {
+        self.apply_named_claim_vector(claim_id, space, vector)?;
+        self.metrics.record_vector_upserted();
+        Ok(())
+    }
+
+    pub fn upsert_named_claim_vector_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        claim_id: &ClaimId,
+        space: &str,
+        vector: Vec<f32>,
+    ) -> Result<(), StoreError> {
+        validate_space_name(space)?;
+        validate_vector(&vector)?;
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_named_claim_vector(claim_id, space, &vector)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        self.apply_named_claim_vector(claim_id, space, vector)?;
+        self.metrics.record_vector_upserted();
+        Ok(())
+    }
+
+    pub fn named_claim_vector(&self, claim_id: &ClaimId, space: &str) -> Option<Vec<f32>> {
+        let claim = self.claims.get(claim_id)?;
+        self.named_vector_space(&claim.tenant_id, space)?
+            .claim_vectors
+            .get(claim_id)
+            .map(Cow::into_owned)
+    }
+
+    /// Names of `tenant_id`'s vector spaces besides the default one,
+    /// sorted.
+    pub fn vector_space_names(&self, tenant_id: &TenantId) -> Vec<String> {
+        self.named_vector_spaces
+            .get(tenant_id.as_str())
+            .map(|spaces| spaces.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Nearest `top_n` claims to `query_vector` in the named `space`.
+    pub fn named_vector_top_candidates(
+        &self,
+        tenant_id: &TenantId,
+        space: &str,
+        query_vector: &[f32],
+        top_n: usize,
+    ) -> Vec<ClaimId> {
+        self.named_space_candidates(tenant_id, space, query_vector, top_n)
+            .into_iter()
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    fn named_space_candidates(
+        &self,
+        tenant_id: &str,
+        space: &str,
+        query_vector: &[f32],
+        top_n: usize,
+    ) -> Vec<String> {
+        if query_vector.is_empty() || top_n == 0 {
+            return Vec::new();
+        }
+        self.named_vector_space(tenant_id, space)
+            .map(|store| {
+                store.vector_candidates(
+                    tenant_id,
+                    query_vector,
+                    top_n,
+                    AnnSearchOverrides::default(),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// Retrieve with dense similarity taken from several vector spaces.
+    #[deprecated(note = "use `retrieve_with` with `RetrievalOptions::with_vector_spaces`")]
+    pub fn retrieve_with_vector_spaces(
+        &self,
+        req: &RetrievalRequest,
+        time_range: (Option<i64>, Option<i64>),
+        space_queries: &[VectorSpaceQuery],
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+    ) -> Vec<RetrievalResult> {
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(time_range.0, time_range.1)
+                .with_vector_spaces(space_queries)
+                .with_allowed_claim_ids(allowed_claim_ids),
+        )
+    }
+
+    /// Ranked hits with dense similarity taken from several vector
+    /// spaces. Lexical candidates are joined by each space's nearest
+    /// claims, and a candidate's dense similarity is the weighted mean of
+    /// its normalized similarities, counting zero in spaces where it has
+    /// no vector. Queries with an empty vector or a non-positive weight
+    /// are ignored; with none left this ranks like a lexical-only
+    /// retrieval.
+    pub(crate) fn vector_space_hits(
+        &self,
+        req: &RetrievalRequest,
+        space_queries: &[VectorSpaceQuery],
+        scope: PipelineScope<'_>,
+    ) -> (Vec<RetrievalHit>, bool) {
+        let (from_unix, to_unix) = scope.time_range;
+        let allowed_claim_ids = scope.allowed_claim_ids;
+        let space_queries: Vec<&VectorSpaceQuery> = space_queries
+            .iter()
+            .filter(|query| !query.vector.is_empty() && query.weight > 0.0)
+            .collect();
+
+        let mut candidates: BTreeSet<String> = self
+            .candidate_claim_ids(
+                &req.tenant_id,
+                &req.query,
+                scope.time_range,
+                None,
+                req.top_k,
+                allowed_claim_ids,
+            )
+            .into_iter()
+            .collect();
+        let vector_top_n = vector_candidate_pool(req.top_k);
+        for query in &space_queries {
+            for claim_id in self.space_vector_candidates(&req.tenant_id, query, vector_top_n) {
+                let in_scope = self.claims.get(claim_id.as_str()).is_some_and(|claim| {
+                    claim.tenant_id == req.tenant_id
+                        && claim_matches_time_range(claim, from_unix, to_unix)
+                }) && allowed_claim_ids
+                    .is_none_or(|ids| ids.contains(claim_id.as_str()));
+                if in_scope {
+                    candidates.insert(claim_id);
+                }
+            }
+        }
+        let mut candidates: Vec<String> = candidates.into_iter().collect();
+        if let Some(hidden) = scope.hidden_claim_ids {
+            candidates.retain(|claim_id| !hidden.contains(claim_id.as_str()));
+        }
+        if !scope.include_archived {
+            self.retain_unarchived(&req.tenant_id, &mut candidates);
+        }
+        if !scope.include_superseded {
+            self.retain_unsuperseded(&mut candidates);
+        }
+
+        let dense_similarities = (!space_queries.is_empty())
+            .then(|| self.fused_space_similarities(&req.tenant_id, &space_queries, &candidates));
+        let bm25_context = self.bm25_context_for_tenant(&req.tenant_id, &req.query);
+        let (mut hits, truncated) = self.rank_scored_candidate_hits(
+            req,
+            &bm25_context,
+            dense_similarities,
+            None,
+            candidates,
+            scope.deadline,
+        );
+        if let Some(after) = scope.after {
+            hits.retain(|hit| self.hit_ranked_after(hit, after));
+        }
+        hits.truncate(req.top_k);
+        (hits, truncated)
+    }
+
+    fn space_vector_candidates(
+        &self,
+        tenant_id: &str,
+        query: &VectorSpaceQuery,
+        top_n: usize,
+    ) -> Vec<String> {
+        match &query.space {
+            None => self.vector_candidates(
+                tenant_id,
+                &query.vector,
+                top_n,
+                AnnSearchOverrides::default(),
+            ),
+            Some(space) => self.named_space_candidates(tenant_id, space, &query.vector, top_n),
+        }
+    }
+
+    fn fused_space_similarities(
+        &self,
+        tenant_id: &str,
+        space_queries: &[&VectorSpaceQuery],
+        candidates: &[String],
+    ) -> HashMap<String, f32> {
+        let metric = self.tenant_distance_metric(tenant_id);
+        let total_weight: f32 = space_queries.iter().map(|query| query.weight).sum();
+        let mut fused: HashMap<String, f32> = HashMap::new();
+        for query in space_queries {
+            let scored = match &query.space {
+                None => {
+                    let vector = self.project_query_vector(tenant_id, &query.vector);
+                    self.score_claim_vectors(metric, &vector, candidates.to_vec())
+                }
+                Some(space) => match self.named_vector_space(tenant_id, space) {
+                    Some(store) => {
+                        store.score_claim_vectors(metric, &query.vector, candidates.to_vec())
+                    }
+                    None => continue,
+                },
+            };
+            for (claim_id, score) in scored {
+                *fused.entry(claim_id).or_default() +=
+                    query.weight * metric.normalize_similarity(score);
+            }
+        }
+        for score in fused.values_mut() {
+            *score /= total_weight;
+        }
+        fused
+    }
+
+    fn named_vector_space(&self, tenant_id: &str, space: &str) -> Option<&InMemoryStore> {
+        self.named_vector_spaces.get(tenant_id)?.get(space)
+    }
+
+    pub(crate) fn apply_named_claim_vector(
+        &mut self,
+        claim_id: &str,
+        space: &str,
+        vector: Vec<f32>,
+    ) -> Result<(), StoreError> {
+        validate_space_name(space)?;
+        validate_vector(&vector)?;
+        let claim = self
+            .claims
+            .get(claim_id)
+            .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string()))?;
+        let tenant_id = claim.tenant_id.clone();
+        // The inner store only needs the claim to resolve its tenant.
+        let placeholder = Claim::new(
+            claim.claim_id.clone(),
+            tenant_id.clone(),
+            String::new(),
+            claim.confidence,
+        );
+
+        match self.named_vector_space(&tenant_id, space) {
+            Some(store) => store.check_tenant_vector_dimension(&tenant_id, vector.len())?,
+            None => {
+                let store = self.new_vector_space(&tenant_id, vector.len());
+                self.named_vector_spaces
+                    .entry(tenant_id.to_string())
+                    .or_default()
+                    .insert(space.to_string(), store);
+            }
+        }
+        let store = self
+            .named_vector_spaces
+            .get_mut(tenant_id.as_str())
+            .and_then(|spaces| spaces.get_mut(space))
+            .expect("vector space was just created");
+        store
+            .claims
+            .insert(placeholder.claim_id.clone(), placeholder);
+        store
+            .tenant_claim_ids
+            .entry(tenant_id.into())
+            .or_default()
+            .insert(claim_id.to_string());
+        store.apply_claim_vector_inner(claim_id, vector.clone())?;
+        self.change_feed
+            .publish_with(|| ChangeRecord::NamedClaimVector {
+                claim_id: claim_id.into(),
+                space: space.to_string(),
+                values: vector,
+            });
+        Ok(())
+    }
+
+    fn new_vector_space(&self, tenant_id: &str, dimension: usize) -> InMemoryStore {
+        let mut store = InMemoryStore {
+            ann_tuning: self.ann_tuning.clone(),
+            vector_backend_runtime: self.vector_backend_runtime,
+            vector_scorer: self.vector_scorer.clone(),
+            metrics: Arc::clone(&self.metrics),
+            ..InMemoryStore::default()
+        };
+        store.tenant_vector_configs.insert(
+            tenant_id.to_string(),
+            TenantVectorConfig {
+                dimension,
+                metric: self.tenant_distance_metric(tenant_id),
+                ann_tuning: self
+                    .tenant_vector_configs
+                    .get(tenant_id)
+                    .and_then(|config| config.ann_tuning.clone()),
+            },
+        );
+        store
+            .claim_vectors
+            .set_quantization(self.claim_vectors.quantization().cloned());
+        store
+            .claim_vectors
+            .set_storage(self.claim_vectors.storage());
+        store
+    }
+
+    /// Drop `claim`'s vectors from every named space, and spaces left
+    /// empty with them.
+    pub(crate) fn remove_named_claim_vectors(&mut self, claim: &Claim) {
+        let Some(spaces) = self.named_vector_spaces.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        spaces.retain(|_, store| {
+            if let Some(placeholder) = store.claims.remove(claim.claim_id.as_str()) {
+                store.remove_claim_indexes(&placeholder);
+            }
+            !store.claims.is_empty()
+        });
+        if spaces.is_empty() {
+            self.named_vector_spaces.remove(claim.tenant_id.as_str());
+        }
+    }
+
+    /// Snapshot records for every named vector; they follow the claim
+    /// records they refer to.
+    pub(crate) fn push_named_vector_records(&self, records: &mut Vec<PersistedRecord>) {
+        let mut tenant_ids: Vec<&String> = self.named_vector_spaces.keys().collect();
+        tenant_ids.sort_unstable();
+        for tenant_id in tenant_ids {
+            for (space, store) in &self.named_vector_spaces[tenant_id] {
+                for claim_id in store.tenant_vector_claim_ids(tenant_id) {
+                    if let Some(values) = store.claim_vectors.get(&claim_id) {
+                        records.push(PersistedRecord::ClaimVector(ClaimVectorRecord {
+                            values: values.into_owned(),
+                            claim_id,
+                            space: Some(space.clone()),
+                        }));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn validate_space_name(space: &str) -> Result<(), StoreError> {
+    if space.trim().is_empty() {
+        return Err(StoreError::InvalidVector(
+            "vector space name cannot be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
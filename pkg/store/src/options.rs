@@ -0,0 +1,449 @@
+//! Everything a retrieval can be asked to do, gathered in one value.
+//!
+//! The retrieve entry points grew one positional argument at a time —
+//! time range, query vector, allowed claim ids, ANN overrides, result
+//! fields, a pipeline, a sparse query, vector spaces, a cold tier.
+//! [`RetrievalOptions`] carries any combination of them and
+//! [`InMemoryStore::retrieve_with`] is the one entry point that honors
+//! them all; the older methods are thin wrappers over it, the ones that
+//! took a single extra argument are deprecated, and filters added since
+//! are options fields only. Callers that assemble a query
+//! from user input, such as the query language in
+//! [`parse_query`](crate::parse_query), fill in the same value.
+//!
+//! Filters resolve to allowed claim sets that are intersected, so a claim
+//! must pass every filter given. Query vectors and claim id sets are
+//! borrowed, so building options never copies them.
+//!
+//! A deadline trades completeness for latency: once it passes, candidate
+//! generation and the optional pipeline stages cut their work short and
+//! scoring stops after the candidates scored so far, which are ranked
+//! and returned. [`InMemoryStore::retrieve_with_outcome`] reports whether
+//! that happened.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::time::Instant;
+
+use schema::{CertaintyBand, ClaimId, ClaimType, RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::cold::{ColdTier, ColdTierCounts};
+use crate::pagination::RankPosition;
+use crate::pipeline::PipelineScope;
+use crate::{
+    AnnSearchOverrides, ColdClaimSource, ConfidenceRange, InMemoryStore, MetadataFilter,
+    PipelineConfig, ResultFields, RetrievalHit, SparseVector, VectorSpaceQuery,
+};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct RetrievalOptions<'a> {
+    /// Inclusive event-time or validity bounds, as for
+    /// [`InMemoryStore::retrieve_with_time_range`].
+    pub time_range: (Option<i64>, Option<i64>),
+    /// Claims mentioning any of these entities.
+    pub entities: Vec<String>,
+    /// Claims having any of these types.
+    pub claim_types: Vec<ClaimType>,
+    /// Claims in any of these collections.
+    pub collections: Vec<String>,
+    /// Claims matching every one of these labels.
+    pub metadata: Vec<MetadataFilter>,
+    pub confidence: ConfidenceRange,
+    /// Claims whose validity window covers this timestamp.
+    pub valid_at: Option<i64>,
+    /// Leave out claims supported only by these sources.
+    pub exclude_sources: Vec<String>,
+    /// Claims in any of these certainty bands.
+    pub certainty_bands: Vec<CertaintyBand>,
+    /// The caller's visibility labels; claims carrying labels none of
+    /// which is here are left out. `None` does not filter.
+    pub visibility_labels: Option<Vec<String>>,
+    /// Embedding of the query for dense scoring and ANN candidates.
+    pub query_vector: Option<&'a [f32]>,
+    /// Claims the caller has already resolved as visible, intersected
+    /// with the filters above.
+    pub allowed_claim_ids: Option<&'a HashSet<ClaimId>>,
+    /// Score exactly these claims instead of generating candidates from
+    /// the indexes. ANN overrides, the pipeline, the sparse query and
+    /// vector spaces do not apply.
+    pub candidate_claim_ids: Option<&'a HashSet<ClaimId>>,
+    pub ann_overrides: AnnSearchOverrides,
+    /// Run this pipeline instead of the tenant's own.
+    pub pipeline: Option<&'a PipelineConfig>,
+    /// Sparse query vector fused into the rank alongside BM25 and the
+    /// dense query vector; candidate generation adds its best matches.
+    pub sparse_query: Option<&'a SparseVector>,
+    /// Take dense similarity from these vector spaces instead of the
+    /// query vector. The pipeline and sparse query do not apply.
+    pub vector_spaces: &'a [VectorSpaceQuery],
+    /// Cold-tier claims to load and merge with the resident results.
+    /// Their results always carry every field.
+    pub cold_tier: Option<ColdTier<'a>>,
+    /// Which fields of each result to hydrate.
+    pub fields: ResultFields,
+    /// When to stop looking and return the best results so far.
+    pub deadline: Option<Instant>,
+    /// Return archived claims too; see
+    /// [`InMemoryStore::archive_claim`].
+    pub include_archived: bool,
+    /// Return claims another claim supersedes too; see
+    /// [`InMemoryStore::is_claim_superseded`].
+    pub include_superseded: bool,
+    /// Only hits ranked after this position; set by
+    /// [`InMemoryStore::retrieve_page`].
+    pub(crate) page_after: Option<RankPosition<'a>>,
+}
+
+/// Results of [`InMemoryStore::retrieve_with_outcome`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct RetrievalOutcome {
+    pub results: Vec<RetrievalResult>,
+    /// The deadline passed before every candidate was considered, so a
+    /// better match may have been missed.
+    pub truncated: bool,
+    /// What the cold tier in the options contributed.
+    pub cold_tier: ColdTierCounts,
+}
+
+impl<'a> RetrievalOptions<'a> {
+    /// No filters, no query vector, full results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_time_range(mut self, from_unix: Option<i64>, to_unix: Option<i64>) -> Self {
+        self.time_range = (from_unix, to_unix);
+        self
+    }
+
+    pub fn with_entities(mut self, entities: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.entities.extend(entities.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_claim_types(mut self, claim_types: &[ClaimType]) -> Self {
+        self.claim_types.extend_from_slice(claim_types);
+        self
+    }
+
+    pub fn with_collections(
+        mut self,
+        collections: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.collections
+            .extend(collections.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_metadata(mut self, filters: &[MetadataFilter]) -> Self {
+        self.metadata.extend_from_slice(filters);
+        self
+    }
+
+    pub fn with_confidence(mut self, range: ConfidenceRange) -> Self {
+        self.confidence = range;
+        self
+    }
+
+    pub fn with_valid_at(mut self, as_of_unix: i64) -> Self {
+        self.valid_at = Some(as_of_unix);
+        self
+    }
+
+    pub fn with_exclude_sources(
+        mut self,
+        source_ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.exclude_sources
+            .extend(source_ids.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_certainty_bands(mut self, bands: &[CertaintyBand]) -> Self {
+        self.certainty_bands.extend_from_slice(bands);
+        self
+    }
+
+    /// Restricts results to claims visible to a caller holding `labels`;
+    /// see [`Claim::is_visible_to`](schema::Claim::is_visible_to).
+    pub fn with_visibility_labels(
+        mut self,
+        labels: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.visibility_labels = Some(labels.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the query vector; `None` leaves retrieval lexical.
+    pub fn with_query_vector(mut self, query_vector: Option<&'a [f32]>) -> Self {
+        self.query_vector = query_vector;
+        self
+    }
+
+    /// Sets the allowed claim ids; `None` allows every claim.
+    pub fn with_allowed_claim_ids(mut self, allowed: Option<&'a HashSet<ClaimId>>) -> Self {
+        self.allowed_claim_ids = allowed;
+        self
+    }
+
+    pub fn with_candidate_claim_ids(mut self, candidates: &'a HashSet<ClaimId>) -> Self {
+        self.candidate_claim_ids = Some(candidates);
+        self
+    }
+
+    pub fn with_ann_overrides(mut self, overrides: AnnSearchOverrides) -> Self {
+        self.ann_overrides = overrides;
+        self
+    }
+
+    pub fn with_fields(mut self, fields: ResultFields) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub fn with_pipeline(mut self, pipeline: &'a PipelineConfig) -> Self {
+        self.pipeline = Some(pipeline);
+        self
+    }
+
+    /// Sets the sparse query; `None` leaves sparse scoring off.
+    pub fn with_sparse_query(mut self, sparse_query: Option<&'a SparseVector>) -> Self {
+        self.sparse_query = sparse_query;
+        self
+    }
+
+    pub fn with_vector_spaces(mut self, space_queries: &'a [VectorSpaceQuery]) -> Self {
+        self.vector_spaces = space_queries;
+        self
+    }
+
+    /// Also score `candidate_ids` that are not resident, loading at most
+    /// `max_loads` of them from `source` in claim id order.
+    pub fn with_cold_tier(
+        mut self,
+        candidate_ids: &'a HashSet<ClaimId>,
+        source: &'a (dyn ColdClaimSource + Sync),
+        max_loads: usize,
+    ) -> Self {
+        self.cold_tier = Some(ColdTier {
+            candidate_ids,
+            source,
+            max_loads,
+        });
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_include_archived(mut self, include_archived: bool) -> Self {
+        self.include_archived = include_archived;
+        self
+    }
+
+    pub fn with_include_superseded(mut self, include_superseded: bool) -> Self {
+        self.include_superseded = include_superseded;
+        self
+    }
+
+    /// Whether no filter restricts the tenant's claims.
+    pub fn is_unfiltered(&self) -> bool {
+        self.time_range == (None, None)
+            && self.entities.is_empty()
+            && self.claim_types.is_empty()
+            && self.collections.is_empty()
+            && self.metadata.is_empty()
+            && self.confidence.is_unbounded()
+            && self.valid_at.is_none()
+            && self.exclude_sources.is_empty()
+            && self.certainty_bands.is_empty()
+            && self.visibility_labels.is_none()
+            && self.allowed_claim_ids.is_none()
+            && self.candidate_claim_ids.is_none()
+    }
+}
+
+impl InMemoryStore {
+    /// Retrieve for `req` as `options` describe. Runs the tenant's
+    /// [`PipelineConfig`](crate::PipelineConfig) unless explicit
+    /// candidates are given, which are ranked directly.
+    pub fn retrieve_with(
+        &self,
+        req: &RetrievalRequest,
+        options: &RetrievalOptions<'_>,
+    ) -> Vec<RetrievalResult> {
+        self.retrieve_with_outcome(req, options).results
+    }
+
+    /// [`Self::retrieve_with`], also saying whether the deadline in
+    /// `options` cut the retrieval short.
+    pub fn retrieve_with_outcome(
+        &self,
+        req: &RetrievalRequest,
+        options: &RetrievalOptions<'_>,
+    ) -> RetrievalOutcome {
+        let started = Instant::now();
+        let (hits, truncated) = self.retrieve_hits_with(req, options);
+        let mut results = self.hydrate_hits_with_fields(hits, options.fields);
+        let cold_tier = options
+            .cold_tier
+            .map(|cold_tier| {
+                self.merge_cold_tier(
+                    req,
+                    options.query_vector,
+                    (
+                        cold_tier.candidate_ids,
+                        cold_tier.source,
+                        cold_tier.max_loads,
+                    ),
+                    &mut results,
+                )
+            })
+            .unwrap_or_default();
+        self.metrics.record_retrieval(started.elapsed());
+        if truncated {
+            self.metrics.record_retrieval_truncated();
+        }
+        RetrievalOutcome {
+            results,
+            truncated,
+            cold_tier,
+        }
+    }
+
+    /// Ranked hits for `req` under `options`, unhydrated and left out of
+    /// the retrieval metrics, and whether the deadline cut them short.
+    pub(crate) fn retrieve_hits_with(
+        &self,
+        req: &RetrievalRequest,
+        options: &RetrievalOptions<'_>,
+    ) -> (Vec<RetrievalHit>, bool) {
+        let filtered = self.allowed_claim_ids_for_options(&req.tenant_id, options);
+        let allowed = match (filtered, options.allowed_claim_ids) {
+            (Some(mut filtered), Some(allowed)) => {
+                filtered.retain(|claim_id| allowed.contains(claim_id.as_str()));
+                Some(Cow::Owned(filtered))
+            }
+            (Some(filtered), None) => Some(Cow::Owned(filtered)),
+            (None, allowed) => allowed.map(Cow::Borrowed),
+        };
+        let hidden = options
+            .visibility_labels
+            .as_ref()
+            .map(|labels| self.claim_ids_hidden_from(&req.tenant_id, labels))
+            .filter(|hidden| !hidden.is_empty());
+        match options.candidate_claim_ids {
+            Some(candidates) => {
+                let mut candidates = self.explicit_candidate_claim_ids(
+                    req,
+                    options.time_range,
+                    candidates,
+                    allowed.as_deref(),
+                );
+                if let Some(hidden) = &hidden {
+                    candidates.retain(|claim_id| !hidden.contains(claim_id.as_str()));
+                }
+                if !options.include_archived {
+                    self.retain_unarchived(&req.tenant_id, &mut candidates);
+                }
+                if !options.include_superseded {
+                    self.retain_unsuperseded(&mut candidates);
+                }
+                self.rank_candidate_hits(
+                    req,
+                    options.query_vector,
+                    candidates,
+                    options.deadline,
+                    options.page_after,
+                )
+            }
+            None => {
+                let scope = PipelineScope {
+                    time_range: options.time_range,
+                    allowed_claim_ids: allowed.as_deref(),
+                    hidden_claim_ids: hidden.as_ref(),
+                    deadline: options.deadline,
+                    include_archived: options.include_archived,
+                    include_superseded: options.include_superseded,
+                    after: options.page_after,
+                };
+                if !options.vector_spaces.is_empty() {
+                    return self.vector_space_hits(req, options.vector_spaces, scope);
+                }
+                let default_pipeline = PipelineConfig::default();
+                let pipeline = options.pipeline.unwrap_or_else(|| {
+                    self.tenant_pipelines
+                        .get(req.tenant_id.as_str())
+                        .unwrap_or(&default_pipeline)
+                });
+                let bm25_context = self.bm25_context_for_tenant(&req.tenant_id, &req.query);
+                self.run_pipeline_with_context(
+                    pipeline,
+                    req,
+                    &bm25_context,
+                    scope,
+                    (
+                        options
+                            .query_vector
+                            .map(|vector| (vector, options.ann_overrides)),
+                        options.sparse_query,
+                    ),
+                )
+            }
+        }
+    }
+
+    /// The claims of `tenant_id` passing the index-backed filters of
+    /// `options`, or `None` when none is set. The time range is applied
+    /// during candidate generation, and the caller's allowed set and
+    /// visibility labels by [`InMemoryStore::retrieve_with`].
+    pub fn allowed_claim_ids_for_options(
+        &self,
+        tenant_id: &TenantId,
+        options: &RetrievalOptions<'_>,
+    ) -> Option<HashSet<ClaimId>> {
+        let entity_ids = (!options.entities.is_empty()).then(|| {
+            options
+                .entities
+                .iter()
+                .flat_map(|entity| self.claim_ids_for_entity(tenant_id, entity))
+                .collect::<HashSet<ClaimId>>()
+        });
+        let type_ids = (!options.claim_types.is_empty())
+            .then(|| self.claim_ids_for_claim_types(tenant_id, &options.claim_types));
+        let collection_ids = (!options.collections.is_empty())
+            .then(|| self.claim_ids_in_collections(tenant_id, &options.collections));
+        let metadata_ids = (!options.metadata.is_empty())
+            .then(|| self.claim_ids_matching_metadata(tenant_id, &options.metadata));
+        let confidence_ids = (!options.confidence.is_unbounded())
+            .then(|| self.claim_ids_in_confidence_range(tenant_id, options.confidence));
+        let validity_ids = options
+            .valid_at
+            .map(|as_of_unix| self.claim_ids_valid_at(tenant_id, as_of_unix));
+        let source_ids = (!options.exclude_sources.is_empty())
+            .then(|| self.claim_ids_outside_sources(tenant_id, options));
+        let certainty_ids = (!options.certainty_bands.is_empty())
+            .then(|| self.claim_ids_in_certainty_bands(tenant_id, &options.certainty_bands));
+
+        [
+            entity_ids,
+            type_ids,
+            collection_ids,
+            metadata_ids,
+            confidence_ids,
+            validity_ids,
+            source_ids,
+            certainty_ids,
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(|mut allowed, other| {
+            allowed.retain(|claim_id| other.contains(claim_id));
+            allowed
+        })
+    }
+}
@@ -0,0 +1,282 @@
+//! Durable outbox of claim ingest and delete events.
+//!
+//! The WAL is an internal format that changes with the store; the outbox
+//! is the stable feed for integration consumers such as a Kafka
+//! connector. Each [`OutboxEvent`] names the tenant, the claim, and the
+//! operation, and carries a log sequence number (LSN) that increases by
+//! one per event. Events are appended one per line:
+//!
+//! ```text
+//! <lsn>\t<op>\t<tenant_id>\t<claim_id>
+//! ```
+//!
+//! Consumers [`Outbox::poll`] from their last acknowledged LSN and
+//! [`Outbox::ack`] what they have processed; acknowledged offsets are
+//! kept in a sidecar `<path>.offsets` file, so a connector resumes where
+//! it left off after either side restarts. Delivery is at least once: an
+//! event is appended after its write commits, and a write that is
+//! retried is recorded again. [`Outbox::prune_acknowledged`] drops the
+//! events every consumer has acknowledged.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions, create_dir_all, rename};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use schema::{ClaimId, TenantId};
+
+use crate::StoreError;
+use crate::wal::{escape_field, sibling_tmp_path, unescape_field};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutboxOp {
+    Ingest,
+    Delete,
+}
+
+impl OutboxOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ingest => "ingest",
+            Self::Delete => "delete",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "ingest" => Some(Self::Ingest),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxEvent {
+    pub lsn: u64,
+    pub tenant_id: TenantId,
+    pub claim_id: ClaimId,
+    pub op: OutboxOp,
+}
+
+#[derive(Debug)]
+pub struct Outbox {
+    path: PathBuf,
+    last_lsn: u64,
+    acked: BTreeMap<String, u64>,
+}
+
+impl Outbox {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            create_dir_all(parent)?;
+        }
+        OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut outbox = Self {
+            path,
+            last_lsn: 0,
+            acked: BTreeMap::new(),
+        };
+        outbox.acked = outbox.read_offsets()?;
+        // Pruning can empty the log, but never past an acknowledged LSN.
+        let logged = outbox
+            .read_events(0, usize::MAX)?
+            .last()
+            .map(|event| event.lsn);
+        let acked = outbox.acked.values().copied().max();
+        outbox.last_lsn = logged.max(acked).unwrap_or(0);
+        Ok(outbox)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn offsets_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".offsets");
+        PathBuf::from(path)
+    }
+
+    /// LSN of the newest event; 0 before the first one.
+    pub fn last_lsn(&self) -> u64 {
+        self.last_lsn
+    }
+
+    /// Append one event per `(tenant_id, claim_id, op)` and sync them as
+    /// one write. Returns the LSN of the last event appended.
+    pub fn append<'a, I>(&mut self, events: I) -> Result<u64, StoreError>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str, OutboxOp)>,
+    {
+        let mut lines = String::new();
+        let mut lsn = self.last_lsn;
+        for (tenant_id, claim_id, op) in events {
+            lsn += 1;
+            lines.push_str(&event_line(lsn, op, tenant_id, claim_id));
+            lines.push('\n');
+        }
+        if lines.is_empty() {
+            return Ok(self.last_lsn);
+        }
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+        file.sync_data()?;
+        self.last_lsn = lsn;
+        Ok(lsn)
+    }
+
+    /// Up to `limit` events with an LSN above `after_lsn`, in LSN order.
+    pub fn read_after(&self, after_lsn: u64, limit: usize) -> Result<Vec<OutboxEvent>, StoreError> {
+        self.read_events(after_lsn, limit)
+    }
+
+    /// The next events `consumer` has not acknowledged.
+    pub fn poll(&self, consumer: &str, limit: usize) -> Result<Vec<OutboxEvent>, StoreError> {
+        self.read_events(self.acked_lsn(consumer), limit)
+    }
+
+    /// Highest LSN `consumer` has acknowledged; 0 for a new consumer.
+    pub fn acked_lsn(&self, consumer: &str) -> u64 {
+        self.acked.get(consumer).copied().unwrap_or(0)
+    }
+
+    /// Record that `consumer` has processed every event up to and
+    /// including `lsn`. Acknowledging an LSN below the current offset is
+    /// a no-op, so a connector replaying its last batch stays idempotent.
+    pub fn ack(&mut self, consumer: &str, lsn: u64) -> Result<u64, StoreError> {
+        if consumer.trim().is_empty() {
+            return Err(StoreError::Conflict(
+                "outbox consumer name must not be empty".to_string(),
+            ));
+        }
+        if lsn > self.last_lsn {
+            return Err(StoreError::Conflict(format!(
+                "cannot acknowledge outbox lsn {lsn} past the last lsn {}",
+                self.last_lsn
+            )));
+        }
+        let current = self.acked_lsn(consumer);
+        if lsn <= current {
+            return Ok(current);
+        }
+        let mut acked = self.acked.clone();
+        acked.insert(consumer.to_string(), lsn);
+        self.write_offsets(&acked)?;
+        self.acked = acked;
+        Ok(lsn)
+    }
+
+    /// Consumers and the LSN each has acknowledged, by name.
+    pub fn consumers(&self) -> &BTreeMap<String, u64> {
+        &self.acked
+    }
+
+    /// Drop the events every known consumer has acknowledged and return
+    /// how many were dropped. Without consumers nothing is dropped.
+    pub fn prune_acknowledged(&mut self) -> Result<usize, StoreError> {
+        let Some(floor) = self.acked.values().copied().min() else {
+            return Ok(0);
+        };
+        let events = self.read_events(0, usize::MAX)?;
+        let kept: Vec<&OutboxEvent> = events.iter().filter(|event| event.lsn > floor).collect();
+        let dropped = events.len() - kept.len();
+        if dropped == 0 {
+            return Ok(0);
+        }
+        let tmp_path = sibling_tmp_path(&self.path);
+        let mut file = File::create(&tmp_path)?;
+        for event in kept {
+            let line = event_line(event.lsn, event.op, &event.tenant_id, &event.claim_id);
+            writeln!(file, "{line}")?;
+        }
+        file.sync_all()?;
+        rename(tmp_path, &self.path)?;
+        Ok(dropped)
+    }
+
+    fn read_events(&self, after_lsn: u64, limit: usize) -> Result<Vec<OutboxEvent>, StoreError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut events = Vec::new();
+        for line in reader.lines() {
+            if events.len() >= limit {
+                break;
+            }
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = parse_event_line(&line)?;
+            if event.lsn > after_lsn {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    fn read_offsets(&self) -> Result<BTreeMap<String, u64>, StoreError> {
+        let path = self.offsets_path();
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let mut acked = BTreeMap::new();
+        for line in BufReader::new(File::open(&path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (consumer, lsn) = line
+                .split_once('\t')
+                .ok_or_else(|| StoreError::Parse(format!("invalid outbox offset '{line}'")))?;
+            let lsn = lsn
+                .parse()
+                .map_err(|_| StoreError::Parse(format!("invalid outbox offset '{line}'")))?;
+            acked.insert(unescape_field(consumer)?, lsn);
+        }
+        Ok(acked)
+    }
+
+    fn write_offsets(&self, acked: &BTreeMap<String, u64>) -> Result<(), StoreError> {
+        let path = self.offsets_path();
+        let tmp_path = sibling_tmp_path(&path);
+        let mut file = File::create(&tmp_path)?;
+        for (consumer, lsn) in acked {
+            writeln!(file, "{}\t{lsn}", escape_field(consumer))?;
+        }
+        file.sync_all()?;
+        rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+fn event_line(lsn: u64, op: OutboxOp, tenant_id: &str, claim_id: &str) -> String {
+    format!(
+        "{lsn}\t{}\t{}\t{}",
+        op.as_str(),
+        escape_field(tenant_id),
+        escape_field(claim_id)
+    )
+}
+
+fn parse_event_line(line: &str) -> Result<OutboxEvent, StoreError> {
+    let invalid = || StoreError::Parse(format!("invalid outbox event '{line}'"));
+    let mut fields = line.split('\t');
+    let (Some(lsn), Some(op), Some(tenant_id), Some(claim_id), None) = (
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+    ) else {
+        return Err(invalid());
+    };
+    Ok(OutboxEvent {
+        lsn: lsn.parse().map_err(|_| invalid())?,
+        op: OutboxOp::parse(op).ok_or_else(invalid)?,
+        tenant_id: unescape_field(tenant_id)?.into(),
+        claim_id: unescape_field(claim_id)?.into(),
+    })
+}
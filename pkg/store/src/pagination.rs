@@ -0,0 +1,208 @@
+//! Cursor-based paging over retrieval results.
+//!
+//! `retrieve` only returns the first `top_k` results;
+//! [`InMemoryStore::retrieve_page`] returns the results ranked after a
+//! [`RetrievalCursor`] instead, along with a cursor for the next page. A
+//! cursor records the last result's score and claim id, which locate it
+//! in the canonical order (see `compare_ranked`), and the tenant's index
+//! epoch. The epoch is bumped by every claim, evidence, edge, and vector
+//! change to the tenant, so a cursor taken before a change is rejected
+//! with [`StoreError::StaleCursor`] rather than silently skipping or
+//! repeating results; clients restart from the first page. The cursor also
+//! pins the first page's `valid_at`, so every page judges validity at
+//! the same instant.
+//!
+//! Each page is one retrieval: candidates ranked at or before the cursor
+//! are dropped right after scoring, so a page costs the same however deep
+//! it is. The stages after scoring act on what follows the cursor, and a
+//! tenant pipeline's rerank stage is skipped while paging, since reranked
+//! scores would not compare with the cursor's.
+
+use std::time::Instant;
+
+use schema::{ClaimId, RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::{InMemoryStore, RetrievalHit, RetrievalOptions, StoreError, compare_ranked};
+
+const CURSOR_VERSION: &str = "c2";
+
+/// A place in the canonical order: score, confidence, claim id.
+pub(crate) type RankPosition<'a> = (f32, f32, &'a str);
+
+/// Position after the last result of a page. [`RetrievalCursor::encode`]
+/// gives the opaque token handed to clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetrievalCursor {
+    pub epoch: u64,
+    /// The `valid_at` of the first page, applied to every later one.
+    pub valid_at: Option<i64>,
+    /// Results returned before this cursor.
+    pub depth: usize,
+    pub score: f32,
+    pub claim_id: ClaimId,
+}
+
+impl RetrievalCursor {
+    pub fn encode(&self) -> String {
+        let claim_id: String = self
+            .claim_id
+            .bytes()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let valid_at = self
+            .valid_at
+            .map_or_else(|| "-".to_string(), |valid_at| valid_at.to_string());
+        format!(
+            "{CURSOR_VERSION}.{:x}.{valid_at}.{:x}.{:08x}.{claim_id}",
+            self.epoch,
+            self.depth,
+            self.score.to_bits()
+        )
+    }
+
+    pub fn decode(token: &str) -> Result<Self, StoreError> {
+        let invalid = || StoreError::Parse(format!("invalid retrieval cursor '{token}'"));
+        let mut parts = token.trim().split('.');
+        if parts.next() != Some(CURSOR_VERSION) {
+            return Err(invalid());
+        }
+        let (Some(epoch), Some(valid_at), Some(depth), Some(score), Some(claim_id), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(invalid());
+        };
+        let epoch = u64::from_str_radix(epoch, 16).map_err(|_| invalid())?;
+        let valid_at = match valid_at {
+            "-" => None,
+            valid_at => Some(valid_at.parse::<i64>().map_err(|_| invalid())?),
+        };
+        let depth = usize::from_str_radix(depth, 16).map_err(|_| invalid())?;
+        let score = u32::from_str_radix(score, 16)
+            .map(f32::from_bits)
+            .map_err(|_| invalid())?;
+        if claim_id.is_empty() || claim_id.len() % 2 != 0 {
+            return Err(invalid());
+        }
+        let bytes = (0..claim_id.len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(&claim_id[idx..idx + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+        let claim_id = String::from_utf8(bytes).map_err(|_| invalid())?.into();
+        Ok(Self {
+            epoch,
+            valid_at,
+            depth,
+            score,
+            claim_id,
+        })
+    }
+}
+
+/// One page of results. `next_cursor` is `None` on the last page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetrievalPage {
+    pub results: Vec<RetrievalResult>,
+    pub next_cursor: Option<RetrievalCursor>,
+    /// The retrieval deadline cut scoring short, so the page may miss
+    /// results and `next_cursor` may end the sequence early.
+    pub truncated: bool,
+}
+
+impl InMemoryStore {
+    /// Change counter for `tenant_id`'s indexed claims.
+    pub fn index_epoch(&self, tenant_id: &TenantId) -> u64 {
+        self.index_epochs
+            .get(tenant_id.as_str())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The `req.top_k` results under `options` ranked after `cursor`, or
+    /// the first `req.top_k` without one. With a cursor, its `valid_at`
+    /// replaces the one in `options`. Fails with [`StoreError::StaleCursor`]
+    /// when the tenant changed since the cursor was issued, and with
+    /// [`StoreError::Conflict`] when the cursor's claim is not the tenant's.
+    pub fn retrieve_page(
+        &self,
+        req: &RetrievalRequest,
+        options: &RetrievalOptions<'_>,
+        cursor: Option<&RetrievalCursor>,
+    ) -> Result<RetrievalPage, StoreError> {
+        let started = Instant::now();
+        let epoch = self.index_epoch(&req.tenant_id);
+        let mut page_options = options.clone();
+        let mut depth = 0;
+        if let Some(cursor) = cursor {
+            if cursor.epoch != epoch {
+                return Err(StoreError::StaleCursor(format!(
+                    "retrieval cursor is from index epoch {} but tenant '{}' is at {epoch}",
+                    cursor.epoch, req.tenant_id
+                )));
+            }
+            let confidence = self
+                .claims
+                .get(cursor.claim_id.as_str())
+                .filter(|claim| claim.tenant_id == req.tenant_id)
+                .map(|claim| claim.confidence)
+                .ok_or_else(|| {
+                    StoreError::Conflict(format!(
+                        "retrieval cursor claim '{}' is not in tenant '{}'",
+                        cursor.claim_id, req.tenant_id
+                    ))
+                })?;
+            page_options.valid_at = cursor.valid_at;
+            page_options.page_after = Some((cursor.score, confidence, cursor.claim_id.as_str()));
+            depth = cursor.depth;
+        }
+        let page_size = req.top_k;
+        if page_size == 0 {
+            return Ok(RetrievalPage {
+                results: Vec::new(),
+                next_cursor: None,
+                truncated: false,
+            });
+        }
+
+        // One result past the page says whether another follows. The
+        // request keeps the full depth so vector candidate pools reach
+        // as far down the ranking as the page does.
+        let mut page_req = req.clone();
+        page_req.top_k = depth.saturating_add(page_size).saturating_add(1);
+        let (mut hits, truncated) = self.retrieve_hits_with(&page_req, &page_options);
+        let has_more = hits.len() > page_size;
+        hits.truncate(page_size);
+        let next_cursor = hits.last().filter(|_| has_more).map(|hit| RetrievalCursor {
+            epoch,
+            valid_at: page_options.valid_at,
+            depth: depth + page_size,
+            score: hit.score,
+            claim_id: hit.claim_id.clone(),
+        });
+        let results = self.hydrate_hits_with_fields(hits, page_options.fields);
+        self.metrics.record_retrieval(started.elapsed());
+        if truncated {
+            self.metrics.record_retrieval_truncated();
+        }
+        Ok(RetrievalPage {
+            results,
+            next_cursor,
+            truncated,
+        })
+    }
+
+    /// Whether `hit` ranks after `position`.
+    pub(crate) fn hit_ranked_after(&self, hit: &RetrievalHit, position: RankPosition<'_>) -> bool {
+        let confidence = self.claim_confidence(&hit.claim_id);
+        compare_ranked((hit.score, confidence, &hit.claim_id), position).is_gt()
+    }
+
+    pub(crate) fn bump_index_epoch(&mut self, tenant_id: &str) {
+        *self.index_epochs.entry(tenant_id.to_string()).or_default() += 1;
+    }
+}
@@ -0,0 +1,99 @@
+//! Quoted phrase queries.
+//!
+//! A query can require words in order by quoting them: `"company x
+//! acquired"` only matches claims where those tokens appear next to each
+//! other, not claims that merely contain all three. A slop suffix,
+//! `"company acquired"~2`, allows up to that many other tokens in total
+//! between the phrase's words, still in order. Candidate generation reads
+//! phrase matches from the positional postings, and scoring drops any
+//! candidate, such as one found by vector search, whose tokens miss a
+//! phrase. The quoted words still count as ordinary query terms for BM25.
+
+use crate::TextAnalyzer;
+use crate::postings::term_positions;
+
+/// A quoted phrase from a query, analyzed the way claims are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PhraseQuery {
+    pub tokens: Vec<String>,
+    /// Most tokens allowed between the phrase's words, summed over the
+    /// phrase. `0` requires them to be adjacent.
+    pub slop: u32,
+}
+
+impl PhraseQuery {
+    /// Whether `tokens`, a claim's token stream, contains the phrase.
+    pub fn matches_tokens(&self, tokens: &[String]) -> bool {
+        let positions = term_positions(tokens);
+        let mut per_token = Vec::with_capacity(self.tokens.len());
+        for token in &self.tokens {
+            match positions.get(token.as_str()) {
+                Some(found) => per_token.push(found.as_slice()),
+                None => return false,
+            }
+        }
+        positions_match_phrase(&per_token, self.slop)
+    }
+}
+
+/// The quoted phrases in `query`, in order, with the phrase text run
+/// through `analyzer`. An unclosed quote and quotes with no tokens inside
+/// are ignored.
+pub(crate) fn parse_analyzed_phrase_queries(
+    query: &str,
+    analyzer: &TextAnalyzer,
+) -> Vec<PhraseQuery> {
+    let mut phrases = Vec::new();
+    let mut rest = query;
+    while let Some(open) = rest.find('"') {
+        let after_open = &rest[open + 1..];
+        let Some(close) = after_open.find('"') else {
+            break;
+        };
+        let tokens = analyzer.analyze(&after_open[..close]);
+        rest = &after_open[close + 1..];
+
+        let mut slop = 0;
+        if let Some(after_tilde) = rest.strip_prefix('~') {
+            let digits = after_tilde
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after_tilde.len());
+            if let Ok(parsed) = after_tilde[..digits].parse::<u32>() {
+                slop = parsed;
+                rest = &after_tilde[digits..];
+            }
+        }
+        if !tokens.is_empty() {
+            phrases.push(PhraseQuery { tokens, slop });
+        }
+    }
+    phrases
+}
+
+/// Whether one position can be picked for each phrase word, in phrase
+/// order and strictly increasing, with at most `slop` other positions
+/// between the first and the last. `positions[i]` lists the positions of
+/// the phrase's `i`th word, ascending.
+pub(crate) fn positions_match_phrase(positions: &[&[u32]], slop: u32) -> bool {
+    let Some((first, rest)) = positions.split_first() else {
+        return false;
+    };
+    let extra_allowed = u64::from(slop);
+    'starts: for &start in *first {
+        // Taking the earliest position after the previous word gives the
+        // shortest span from this start.
+        let mut previous = start;
+        for word_positions in rest {
+            let next = word_positions.partition_point(|&position| position <= previous);
+            match word_positions.get(next) {
+                Some(&position) => previous = position,
+                None => break 'starts,
+            }
+        }
+        let span = u64::from(previous - start);
+        if span.saturating_sub(rest.len() as u64) <= extra_allowed {
+            return true;
+        }
+    }
+    false
+}
@@ -0,0 +1,703 @@
+//! Retrieval as a configurable sequence of stages.
+//!
+//! A [`PipelineConfig`] lists the stages a retrieval runs, in order:
+//! candidate generation, filters, scoring, and optionally reranking,
+//! diversity, and packing. The default pipeline (generate, filter, score)
+//! is what [`InMemoryStore::retrieve_with`] and the retrieve variants
+//! built on it have always done. A tenant's own pipeline, set with
+//! [`InMemoryStore::set_tenant_pipeline`], can leave out the filters,
+//! move them after scoring, or add the later stages in any order, and
+//! [`RetrievalOptions::with_pipeline`] runs another for one query.
+//! Pipelines are runtime configuration and are not written to the WAL.
+//!
+//! Whatever the stages, candidates are always narrowed to the claims the
+//! caller may retrieve: the allowed claim ids the retrieval options fold
+//! into (visibility labels, collections, and the other option filters),
+//! and no archived or superseded claims unless asked for. The filters
+//! stage only decides where the request's time range applies.
+//!
+//! Candidate generation and the stages after scoring can be given a
+//! latency budget. A stage that runs out of budget returns what it has so
+//! far: generation skips its remaining candidate sources (dense, then
+//! sparse), diversity passes the rest of the hits through unchecked,
+//! packing stops, and a reranker that answers late is ignored. When one
+//! of the optional stages overruns its budget on several retrievals in a
+//! row, its circuit breaker opens and the stage is skipped for that
+//! tenant until a cooldown passes; the next run is then a probe that
+//! closes the breaker again if it fits the budget. Breaker state is
+//! reported by [`InMemoryStore::pipeline_stage_breakers`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use schema::{ClaimId, RetrievalRequest, RetrievalResult, TenantId, ValidationError};
+
+use crate::pagination::RankPosition;
+use crate::{
+    AnnSearchOverrides, Bm25Context, InMemoryStore, RetrievalHit, RetrievalOptions, SparseVector,
+    StoreError, claim_matches_time_range, compare_ranked, vector_candidate_pool,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineStage {
+    /// Lexical and vector candidates for the query. Always first.
+    CandidateGeneration,
+    /// The request's time range. Leaving the stage out ignores it; the
+    /// allowed claim ids apply either way.
+    Filters,
+    /// Rank candidates on the lexical, dense, and evidence signals.
+    Scoring,
+    /// Rescore the best `window` hits with the store's [`Reranker`]; a
+    /// no-op when none is set.
+    Rerank { window: usize },
+    /// Drop a hit whose token overlap (Jaccard) with a better hit already
+    /// kept exceeds `max_overlap`.
+    Diversity { max_overlap: f32 },
+    /// Keep hits, best first, while their canonical text fits in
+    /// `max_chars` in total; a hit that does not fit is skipped.
+    Packing { max_chars: usize },
+}
+
+impl PipelineStage {
+    /// Stable label used for budgets and diagnostics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelineStage::CandidateGeneration => "candidate_generation",
+            PipelineStage::Filters => "filters",
+            PipelineStage::Scoring => "scoring",
+            PipelineStage::Rerank { .. } => "rerank",
+            PipelineStage::Diversity { .. } => "diversity",
+            PipelineStage::Packing { .. } => "packing",
+        }
+    }
+
+    /// Rerank, diversity, and packing: the stages a retrieval can do
+    /// without, and the ones a circuit breaker may switch off.
+    fn needs_hits(&self) -> bool {
+        matches!(
+            self,
+            PipelineStage::Rerank { .. }
+                | PipelineStage::Diversity { .. }
+                | PipelineStage::Packing { .. }
+        )
+    }
+
+    fn same_kind(&self, other: &PipelineStage) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// The stages of a retrieval, in the order they run. A pipeline starts
+/// with [`PipelineStage::CandidateGeneration`], scores before reranking,
+/// diversity, or packing, and runs each stage at most once. It returns
+/// at most `top_k` hits; diversity and packing stop once they have kept
+/// that many.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineConfig {
+    stages: Vec<PipelineStage>,
+    /// Latency budget per stage name.
+    budgets: BTreeMap<&'static str, Duration>,
+    circuit_breaker: Option<StageBreakerConfig>,
+}
+
+impl PipelineConfig {
+    pub fn new(stages: Vec<PipelineStage>) -> Result<Self, StoreError> {
+        let invalid = || StoreError::Validation(ValidationError::InvalidRange("pipeline stages"));
+        if stages.first() != Some(&PipelineStage::CandidateGeneration) {
+            return Err(invalid());
+        }
+        let mut scored = false;
+        for (idx, stage) in stages.iter().enumerate() {
+            if stages[..idx].iter().any(|earlier| earlier.same_kind(stage)) {
+                return Err(invalid());
+            }
+            if stage.needs_hits() && !scored {
+                return Err(invalid());
+            }
+            scored |= *stage == PipelineStage::Scoring;
+        }
+        if !scored {
+            return Err(invalid());
+        }
+        Ok(Self {
+            stages,
+            ..Self::default()
+        })
+    }
+
+    pub fn stages(&self) -> &[PipelineStage] {
+        &self.stages
+    }
+
+    /// Give the stage named `stage` (see [`PipelineStage::name`]) a
+    /// latency budget. Only candidate generation and the stages after
+    /// scoring take one; filters and scoring always run to completion.
+    pub fn with_stage_budget(mut self, stage: &str, budget: Duration) -> Result<Self, StoreError> {
+        let stage = self
+            .stages
+            .iter()
+            .find(|candidate| candidate.name() == stage)
+            .filter(|candidate| {
+                **candidate == PipelineStage::CandidateGeneration || candidate.needs_hits()
+            })
+            .ok_or(StoreError::Validation(ValidationError::InvalidRange(
+                "pipeline stage budget",
+            )))?;
+        self.budgets.insert(stage.name(), budget);
+        Ok(self)
+    }
+
+    pub fn stage_budget(&self, stage: &PipelineStage) -> Option<Duration> {
+        self.budgets.get(stage.name()).copied()
+    }
+
+    /// Breaker settings for the optional stages that have a budget, or
+    /// `None` to always run them.
+    pub fn with_circuit_breaker(mut self, config: Option<StageBreakerConfig>) -> Self {
+        self.circuit_breaker = config;
+        self
+    }
+
+    pub fn circuit_breaker(&self) -> Option<StageBreakerConfig> {
+        self.circuit_breaker
+    }
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            stages: vec![
+                PipelineStage::CandidateGeneration,
+                PipelineStage::Filters,
+                PipelineStage::Scoring,
+            ],
+            budgets: BTreeMap::new(),
+            circuit_breaker: Some(StageBreakerConfig::default()),
+        }
+    }
+}
+
+/// When a stage's circuit breaker opens and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageBreakerConfig {
+    /// Consecutive over-budget runs that open the breaker.
+    pub failure_threshold: u32,
+    /// How long an open breaker skips the stage before probing it again.
+    pub cooldown: Duration,
+}
+
+impl Default for StageBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageBreakerState {
+    Closed,
+    /// The stage is being skipped.
+    Open,
+    /// The cooldown has passed; the next run decides.
+    HalfOpen,
+}
+
+impl StageBreakerState {
+    pub fn as_label(self) -> &'static str {
+        match self {
+            StageBreakerState::Closed => "closed",
+            StageBreakerState::Open => "open",
+            StageBreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageBreakerStatus {
+    pub stage: &'static str,
+    pub state: StageBreakerState,
+    pub consecutive_over_budget: u32,
+    /// Times the breaker has opened.
+    pub trips: u64,
+}
+
+#[derive(Debug, Default)]
+struct StageBreaker {
+    consecutive_over_budget: u32,
+    opened_at: Option<Instant>,
+    cooldown: Duration,
+    trips: u64,
+}
+
+impl StageBreaker {
+    fn state(&self) -> StageBreakerState {
+        match self.opened_at {
+            None => StageBreakerState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => StageBreakerState::Open,
+            Some(_) => StageBreakerState::HalfOpen,
+        }
+    }
+}
+
+/// Breakers per tenant and stage. Shared by clones of a store, like its
+/// metrics.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StageBreakers {
+    inner: Arc<Mutex<HashMap<(String, &'static str), StageBreaker>>>,
+}
+
+impl StageBreakers {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(String, &'static str), StageBreaker>> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn allows(&self, tenant_id: &str, stage: &'static str) -> bool {
+        self.lock()
+            .get(&(tenant_id.to_string(), stage))
+            .is_none_or(|breaker| breaker.state() != StageBreakerState::Open)
+    }
+
+    fn record(
+        &self,
+        tenant_id: &str,
+        stage: &'static str,
+        config: StageBreakerConfig,
+        over_budget: bool,
+    ) {
+        let mut breakers = self.lock();
+        let breaker = breakers.entry((tenant_id.to_string(), stage)).or_default();
+        if !over_budget {
+            breaker.consecutive_over_budget = 0;
+            breaker.opened_at = None;
+            return;
+        }
+        breaker.consecutive_over_budget = breaker.consecutive_over_budget.saturating_add(1);
+        let probe_failed = breaker.state() == StageBreakerState::HalfOpen;
+        if probe_failed || breaker.consecutive_over_budget >= config.failure_threshold.max(1) {
+            breaker.opened_at = Some(Instant::now());
+            breaker.cooldown = config.cooldown;
+            breaker.trips += 1;
+        }
+    }
+
+    fn statuses(&self, tenant_id: &str) -> Vec<StageBreakerStatus> {
+        let mut statuses: Vec<StageBreakerStatus> = self
+            .lock()
+            .iter()
+            .filter(|((tenant, _), _)| tenant == tenant_id)
+            .map(|((_, stage), breaker)| StageBreakerStatus {
+                stage,
+                state: breaker.state(),
+                consecutive_over_budget: breaker.consecutive_over_budget,
+                trips: breaker.trips,
+            })
+            .collect();
+        statuses.sort_by_key(|status| status.stage);
+        statuses
+    }
+}
+
+/// One hit offered to a [`Reranker`].
+#[derive(Debug, Clone, Copy)]
+pub struct RerankCandidate<'a> {
+    pub claim_id: &'a ClaimId,
+    pub text: &'a str,
+    pub score: f32,
+}
+
+pub trait Reranker: Send + Sync {
+    /// Label for logs and metrics.
+    fn name(&self) -> &str;
+
+    /// New scores for `candidates`, one per candidate in the same order,
+    /// higher is better. Returning `None` (or the wrong number of scores)
+    /// leaves the hits as they were.
+    fn rerank(&self, query: &str, candidates: &[RerankCandidate<'_>]) -> Option<Vec<f32>>;
+}
+
+/// The claims a pipeline run may return, and when it must finish.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct PipelineScope<'a> {
+    pub(crate) time_range: (Option<i64>, Option<i64>),
+    pub(crate) allowed_claim_ids: Option<&'a HashSet<ClaimId>>,
+    /// Claims the caller's visibility labels don't reach.
+    pub(crate) hidden_claim_ids: Option<&'a HashSet<ClaimId>>,
+    pub(crate) deadline: Option<Instant>,
+    /// Let archived claims through candidate generation.
+    pub(crate) include_archived: bool,
+    /// Let superseded claims through candidate generation.
+    pub(crate) include_superseded: bool,
+    /// Keep only the hits scored after this position, for paging. The
+    /// rerank stage is skipped, as reranked scores would not compare
+    /// with the position.
+    pub(crate) after: Option<RankPosition<'a>>,
+}
+
+enum PipelineState {
+    Candidates(Vec<String>),
+    Hits(Vec<RetrievalHit>),
+}
+
+impl InMemoryStore {
+    /// Run `tenant_id`'s retrievals through `pipeline`, or back through
+    /// the default one with `None`.
+    pub fn set_tenant_pipeline(&mut self, tenant_id: &TenantId, pipeline: Option<PipelineConfig>) {
+        match pipeline {
+            Some(pipeline) => {
+                self.tenant_pipelines
+                    .insert(tenant_id.to_string(), pipeline);
+            }
+            None => {
+                self.tenant_pipelines.remove(tenant_id.as_str());
+            }
+        }
+    }
+
+    pub fn tenant_pipeline(&self, tenant_id: &TenantId) -> PipelineConfig {
+        self.tenant_pipelines
+            .get(tenant_id.as_str())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_reranker(&mut self, reranker: Option<Arc<dyn Reranker>>) {
+        self.reranker = reranker;
+    }
+
+    pub fn reranker_name(&self) -> Option<&str> {
+        self.reranker.as_deref().map(Reranker::name)
+    }
+
+    /// Circuit breakers of `tenant_id`'s budgeted optional stages that
+    /// have run at least once, by stage name.
+    pub fn pipeline_stage_breakers(&self, tenant_id: &TenantId) -> Vec<StageBreakerStatus> {
+        self.stage_breakers.statuses(tenant_id)
+    }
+
+    /// Retrieve through `pipeline` instead of the tenant's own.
+    #[deprecated(note = "use `retrieve_with` with `RetrievalOptions::with_pipeline`")]
+    pub fn retrieve_with_pipeline(
+        &self,
+        req: &RetrievalRequest,
+        time_range: (Option<i64>, Option<i64>),
+        query_vector: Option<&[f32]>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+        pipeline: &PipelineConfig,
+    ) -> Vec<RetrievalResult> {
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(time_range.0, time_range.1)
+                .with_query_vector(query_vector)
+                .with_allowed_claim_ids(allowed_claim_ids)
+                .with_pipeline(pipeline),
+        )
+    }
+
+    /// Run `pipeline` for a query already analyzed into `bm25_context`,
+    /// which candidate generation and scoring share.
+    ///
+    /// A `deadline` bounds the whole run: every stage that takes a budget
+    /// stops at whichever of the two comes first, and scoring stops early
+    /// too. The flag returned says whether the deadline cut any stage
+    /// short, in which case the hits are the best of what was scored.
+    pub(crate) fn run_pipeline_with_context(
+        &self,
+        pipeline: &PipelineConfig,
+        req: &RetrievalRequest,
+        bm25_context: &Bm25Context,
+        scope: PipelineScope<'_>,
+        (query_vector, sparse_query): (Option<(&[f32], AnnSearchOverrides)>, Option<&SparseVector>),
+    ) -> (Vec<RetrievalHit>, bool) {
+        let PipelineScope {
+            time_range,
+            allowed_claim_ids,
+            hidden_claim_ids,
+            deadline: query_deadline,
+            include_archived,
+            include_superseded,
+            after,
+        } = scope;
+        let archived = self
+            .archived_claim_set(&req.tenant_id)
+            .filter(|_| !include_archived);
+        let in_scope = |claim_id: &str| {
+            self.claims.contains_key(claim_id)
+                && allowed_claim_ids.is_none_or(|ids| ids.contains(claim_id))
+                && hidden_claim_ids.is_none_or(|ids| !ids.contains(claim_id))
+                && archived.is_none_or(|ids| !ids.contains(claim_id))
+                && (include_superseded || !self.superseded(claim_id))
+        };
+        let in_time_range = |claim_id: &str| {
+            self.claims
+                .get(claim_id)
+                .is_some_and(|claim| claim_matches_time_range(claim, time_range.0, time_range.1))
+        };
+
+        // The time range only applies through the filters stage.
+        let windowed = (time_range.0.is_some() || time_range.1.is_some())
+            && pipeline.stages().contains(&PipelineStage::Filters);
+
+        let mut state = PipelineState::Candidates(Vec::new());
+        let mut truncated = false;
+        for stage in pipeline.stages() {
+            let budget = pipeline.stage_budget(stage);
+            let breaker = pipeline
+                .circuit_breaker()
+                .filter(|_| budget.is_some() && stage.needs_hits());
+            if breaker.is_some() && !self.stage_breakers.allows(&req.tenant_id, stage.name()) {
+                self.metrics.record_pipeline_stage_skipped();
+                continue;
+            }
+            let stage_started = Instant::now();
+            let deadline = match (budget.map(|budget| stage_started + budget), query_deadline) {
+                (Some(stage_deadline), Some(query_deadline)) => {
+                    Some(stage_deadline.min(query_deadline))
+                }
+                (stage_deadline, query_deadline) => stage_deadline.or(query_deadline),
+            };
+            if *stage != PipelineStage::Filters
+                && query_deadline.is_some_and(|query_deadline| stage_started >= query_deadline)
+            {
+                truncated = true;
+            }
+            state = match (stage, state) {
+                (PipelineStage::CandidateGeneration, _) => {
+                    let mut claim_ids = self.generate_candidates(
+                        req,
+                        bm25_context,
+                        windowed,
+                        query_vector,
+                        sparse_query,
+                        deadline,
+                    );
+                    claim_ids.retain(|claim_id| in_scope(claim_id));
+                    PipelineState::Candidates(claim_ids)
+                }
+                (PipelineStage::Filters, PipelineState::Candidates(mut claim_ids)) => {
+                    claim_ids.retain(|claim_id| in_time_range(claim_id));
+                    PipelineState::Candidates(claim_ids)
+                }
+                (PipelineStage::Filters, PipelineState::Hits(mut hits)) => {
+                    hits.retain(|hit| in_time_range(&hit.claim_id));
+                    PipelineState::Hits(hits)
+                }
+                (PipelineStage::Scoring, PipelineState::Candidates(claim_ids)) => {
+                    let (mut hits, scoring_truncated) = self.score_candidate_hits(
+                        req,
+                        bm25_context,
+                        query_vector.map(|(vector, _)| vector),
+                        sparse_query,
+                        claim_ids,
+                        query_deadline,
+                    );
+                    truncated |= scoring_truncated;
+                    if let Some(after) = after {
+                        hits.retain(|hit| self.hit_ranked_after(hit, after));
+                    }
+                    PipelineState::Hits(hits)
+                }
+                (PipelineStage::Rerank { window }, PipelineState::Hits(hits))
+                    if after.is_none() =>
+                {
+                    PipelineState::Hits(self.rerank_hits(&req.query, hits, *window, deadline))
+                }
+                (PipelineStage::Diversity { max_overlap }, PipelineState::Hits(hits)) => {
+                    PipelineState::Hits(self.diversify_hits(
+                        hits,
+                        *max_overlap,
+                        req.top_k,
+                        deadline,
+                    ))
+                }
+                (PipelineStage::Packing { max_chars }, PipelineState::Hits(hits)) => {
+                    PipelineState::Hits(self.pack_hits(hits, *max_chars, req.top_k, deadline))
+                }
+                // `PipelineConfig::new` rules out every other pairing.
+                (_, state) => state,
+            };
+            if let Some(budget) = budget {
+                let over_budget = stage_started.elapsed() > budget;
+                if over_budget {
+                    self.metrics.record_pipeline_stage_over_budget();
+                }
+                if let Some(config) = breaker {
+                    self.stage_breakers
+                        .record(&req.tenant_id, stage.name(), config, over_budget);
+                }
+            }
+        }
+        let hits = match state {
+            PipelineState::Hits(mut hits) => {
+                hits.truncate(req.top_k);
+                hits
+            }
+            PipelineState::Candidates(_) => Vec::new(),
+        };
+        (hits, truncated)
+    }
+
+    /// Lexical candidates, then the dense and sparse ones while the
+    /// stage is within its budget. `windowed` says the filters stage
+    /// restricts the retrieval to a time range.
+    fn generate_candidates(
+        &self,
+        req: &RetrievalRequest,
+        bm25_context: &Bm25Context,
+        windowed: bool,
+        query_vector: Option<(&[f32], AnnSearchOverrides)>,
+        sparse_query: Option<&SparseVector>,
+        deadline: Option<Instant>,
+    ) -> Vec<String> {
+        let mut claim_ids = self.candidate_claim_ids_with_lexical(
+            &req.tenant_id,
+            self.lexical_candidates(&req.tenant_id, bm25_context, windowed),
+            (None, None),
+            None,
+            req.top_k,
+            None,
+        );
+        let in_budget = || deadline.is_none_or(|deadline| Instant::now() < deadline);
+        if let Some((vector, ann_overrides)) = query_vector
+            && in_budget()
+        {
+            claim_ids.extend(
+                self.vector_candidates(
+                    &req.tenant_id,
+                    vector,
+                    vector_candidate_pool(req.top_k),
+                    ann_overrides,
+                )
+                .into_iter()
+                .filter(|claim_id| {
+                    self.claims
+                        .get(claim_id.as_str())
+                        .is_some_and(|claim| claim.tenant_id == req.tenant_id)
+                }),
+            );
+        }
+        if let Some(sparse_query) = sparse_query
+            && in_budget()
+        {
+            claim_ids.extend(
+                self.sparse_vector_top_candidates(
+                    &req.tenant_id,
+                    sparse_query,
+                    vector_candidate_pool(req.top_k),
+                )
+                .into_iter()
+                .map(ClaimId::into_string),
+            );
+        }
+        claim_ids.sort_unstable();
+        claim_ids.dedup();
+        claim_ids
+    }
+
+    fn rerank_hits(
+        &self,
+        query: &str,
+        mut hits: Vec<RetrievalHit>,
+        window: usize,
+        deadline: Option<Instant>,
+    ) -> Vec<RetrievalHit> {
+        let Some(reranker) = &self.reranker else {
+            return hits;
+        };
+        let window = window.min(hits.len());
+        let candidates: Vec<RerankCandidate<'_>> = hits[..window]
+            .iter()
+            .map(|hit| RerankCandidate {
+                claim_id: &hit.claim_id,
+                text: self
+                    .claims
+                    .get(hit.claim_id.as_str())
+                    .map(|claim| claim.canonical_text.as_str())
+                    .unwrap_or_default(),
+                score: hit.score,
+            })
+            .collect();
+        let Some(scores) = reranker.rerank(query, &candidates) else {
+            return hits;
+        };
+        if scores.len() != window || deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            return hits;
+        }
+        for (hit, score) in hits.iter_mut().zip(scores) {
+            hit.score = score;
+        }
+        hits[..window].sort_by(|a, b| {
+            compare_ranked(
+                (a.score, self.claim_confidence(&a.claim_id), &a.claim_id),
+                (b.score, self.claim_confidence(&b.claim_id), &b.claim_id),
+            )
+        });
+        hits
+    }
+
+    fn diversify_hits(
+        &self,
+        hits: Vec<RetrievalHit>,
+        max_overlap: f32,
+        limit: usize,
+        deadline: Option<Instant>,
+    ) -> Vec<RetrievalHit> {
+        let mut kept: Vec<RetrievalHit> = Vec::new();
+        let mut kept_tokens: Vec<HashSet<&str>> = Vec::new();
+        let mut hits = hits.into_iter();
+        while let Some(hit) = hits.next() {
+            if kept.len() == limit {
+                break;
+            }
+            if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+                kept.push(hit);
+                kept.extend(hits.by_ref().take(limit - kept.len()));
+                break;
+            }
+            let tokens: HashSet<&str> = self
+                .claim_tokens
+                .get(hit.claim_id.as_str())
+                .map(|tokens| tokens.iter().map(String::as_str).collect())
+                .unwrap_or_default();
+            let redundant = kept_tokens.iter().any(|other| {
+                let union = tokens.union(other).count();
+                union > 0 && tokens.intersection(other).count() as f32 / union as f32 > max_overlap
+            });
+            if !redundant {
+                kept.push(hit);
+                kept_tokens.push(tokens);
+            }
+        }
+        kept
+    }
+
+    fn pack_hits(
+        &self,
+        hits: Vec<RetrievalHit>,
+        max_chars: usize,
+        limit: usize,
+        deadline: Option<Instant>,
+    ) -> Vec<RetrievalHit> {
+        let mut used = 0usize;
+        let mut kept = Vec::new();
+        for hit in hits {
+            if kept.len() == limit || deadline.is_some_and(|deadline| Instant::now() > deadline) {
+                break;
+            }
+            let chars = self
+                .claims
+                .get(hit.claim_id.as_str())
+                .map_or(0, |claim| claim.canonical_text.chars().count());
+            if used + chars <= max_chars {
+                used += chars;
+                kept.push(hit);
+            }
+        }
+        kept
+    }
+}
@@ -0,0 +1,349 @@
+//! Per-tenant inverted index with compact posting lists.
+//!
+//! Each claim indexed for a tenant gets a numeric document id, assigned in
+//! increasing order and never reused. A term's posting list holds the
+//! documents containing it, sorted by document id, as LEB128 varints: the
+//! gap from the previous document id, the term's frequency in the
+//! document, and then that many token positions, each as the gap from the
+//! previous one. Positions let phrase queries check word order. Since new
+//! documents always take the highest id, indexing a claim only appends to
+//! its terms' lists; removing one rewrites the lists of its own terms.
+//!
+//! The BM25 statistics (document count, document frequency per term, and
+//! total document length) are kept up to date as claims come and go, so a
+//! query reads them instead of walking the tenant's claims. A bloom filter
+//! over the tenant's terms lets a query skip the index when none of its
+//! tokens can occur.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::bloom::TermBloom;
+use crate::phrase::{PhraseQuery, positions_match_phrase};
+
+/// A term's postings: `doc id gap, term frequency, position gaps...`
+/// varint runs.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PostingList {
+    bytes: Vec<u8>,
+    len: usize,
+    last_doc_id: u64,
+}
+
+impl PostingList {
+    /// Number of documents containing the term.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub(crate) fn iter(&self) -> PostingIter<'_> {
+        PostingIter {
+            bytes: &self.bytes,
+            pos: 0,
+            doc_id: 0,
+        }
+    }
+
+    /// Postings with the term's positions in each document, ascending.
+    pub(crate) fn iter_positions(&self) -> impl Iterator<Item = (u64, Vec<u32>)> + '_ {
+        let mut iter = self.iter();
+        std::iter::from_fn(move || iter.next_with_positions())
+    }
+
+    /// Append a posting; `doc_id` must be above every id in the list and
+    /// `positions` ascending and non-empty.
+    fn push(&mut self, doc_id: u64, positions: &[u32]) {
+        debug_assert!(self.len == 0 || doc_id > self.last_doc_id);
+        debug_assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        write_varint(&mut self.bytes, doc_id - self.last_doc_id);
+        write_varint(&mut self.bytes, positions.len() as u64);
+        let mut previous = 0u32;
+        for &position in positions {
+            write_varint(&mut self.bytes, u64::from(position - previous));
+            previous = position;
+        }
+        self.last_doc_id = doc_id;
+        self.len += 1;
+    }
+
+    /// Drop the posting for `doc_id`, if present.
+    fn remove(&mut self, doc_id: u64) -> bool {
+        if !self.iter().any(|(id, _)| id == doc_id) {
+            return false;
+        }
+        let mut rebuilt = Self::default();
+        for (id, positions) in self.iter_positions().filter(|(id, _)| *id != doc_id) {
+            rebuilt.push(id, &positions);
+        }
+        *self = rebuilt;
+        true
+    }
+}
+
+/// Decodes a [`PostingList`] into `(doc id, term frequency)` pairs,
+/// skipping positions.
+pub(crate) struct PostingIter<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    doc_id: u64,
+}
+
+impl PostingIter<'_> {
+    fn next_header(&mut self) -> Option<(u64, u32)> {
+        let gap = read_varint(self.bytes, &mut self.pos)?;
+        let term_freq = read_varint(self.bytes, &mut self.pos)?;
+        self.doc_id += gap;
+        Some((self.doc_id, term_freq as u32))
+    }
+
+    fn next_with_positions(&mut self) -> Option<(u64, Vec<u32>)> {
+        let (doc_id, term_freq) = self.next_header()?;
+        let mut positions = Vec::with_capacity(term_freq as usize);
+        let mut position = 0u32;
+        for _ in 0..term_freq {
+            position += read_varint(self.bytes, &mut self.pos)? as u32;
+            positions.push(position);
+        }
+        Some((doc_id, positions))
+    }
+}
+
+impl Iterator for PostingIter<'_> {
+    type Item = (u64, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (doc_id, term_freq) = self.next_header()?;
+        for _ in 0..term_freq {
+            read_varint(self.bytes, &mut self.pos)?;
+        }
+        Some((doc_id, term_freq))
+    }
+}
+
+/// One tenant's term index and BM25 statistics.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TenantTermIndex {
+    doc_ids: HashMap<String, u64>,
+    claim_ids: HashMap<u64, String>,
+    next_doc_id: u64,
+    postings: HashMap<String, PostingList>,
+    total_doc_len: u64,
+    terms: TermBloom,
+}
+
+impl TenantTermIndex {
+    /// Index `claim_id` with its token stream. A claim being re-indexed
+    /// must be removed first.
+    pub(crate) fn insert(&mut self, claim_id: &str, tokens: &[String]) {
+        debug_assert!(!self.doc_ids.contains_key(claim_id));
+        let doc_id = self.next_doc_id;
+        self.next_doc_id += 1;
+        self.doc_ids.insert(claim_id.to_string(), doc_id);
+        self.claim_ids.insert(doc_id, claim_id.to_string());
+        self.total_doc_len += tokens.len() as u64;
+
+        for (term, positions) in term_positions(tokens) {
+            // Most terms already have a list; only a new one needs its own key.
+            if let Some(postings) = self.postings.get_mut(term) {
+                postings.push(doc_id, &positions);
+                continue;
+            }
+            let mut postings = PostingList::default();
+            postings.push(doc_id, &positions);
+            self.postings.insert(term.to_string(), postings);
+            if self.terms.is_full() {
+                self.terms = TermBloom::from_terms(self.postings.keys());
+            } else {
+                self.terms.insert(term);
+            }
+        }
+    }
+
+    /// Remove `claim_id`, indexed with `tokens`.
+    pub(crate) fn remove(&mut self, claim_id: &str, tokens: &[String]) {
+        let Some(doc_id) = self.doc_ids.remove(claim_id) else {
+            return;
+        };
+        self.claim_ids.remove(&doc_id);
+        self.total_doc_len = self.total_doc_len.saturating_sub(tokens.len() as u64);
+        for token in tokens {
+            if let Some(postings) = self.postings.get_mut(token)
+                && postings.remove(doc_id)
+                && postings.is_empty()
+            {
+                self.postings.remove(token);
+            }
+        }
+    }
+
+    /// Remove `claim_id` without its token stream, checking every term.
+    pub(crate) fn purge(&mut self, claim_id: &str) {
+        let Some(doc_id) = self.doc_ids.remove(claim_id) else {
+            return;
+        };
+        self.claim_ids.remove(&doc_id);
+        let mut doc_len = 0u64;
+        self.postings.retain(|_, postings| {
+            if let Some((_, term_freq)) = postings.iter().find(|&(id, _)| id == doc_id) {
+                doc_len += u64::from(term_freq);
+                postings.remove(doc_id);
+            }
+            !postings.is_empty()
+        });
+        self.total_doc_len = self.total_doc_len.saturating_sub(doc_len);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.doc_ids.is_empty()
+    }
+
+    pub(crate) fn doc_count(&self) -> usize {
+        self.doc_ids.len()
+    }
+
+    pub(crate) fn term_count(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Approximate heap bytes of the terms, postings, and claim id maps.
+    pub(crate) fn estimated_bytes(&self) -> usize {
+        let postings: usize = self
+            .postings
+            .iter()
+            .map(|(term, postings)| term.len() + postings.bytes.len())
+            .sum();
+        let claim_ids: usize = self
+            .doc_ids
+            .keys()
+            .map(|claim_id| 2 * (claim_id.len() + std::mem::size_of::<u64>()))
+            .sum();
+        postings + claim_ids
+    }
+
+    /// Tokens across every indexed claim.
+    pub(crate) fn total_doc_len(&self) -> u64 {
+        self.total_doc_len
+    }
+
+    /// Every indexed term with the number of claims containing it.
+    pub(crate) fn doc_freqs(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.postings
+            .iter()
+            .map(|(term, postings)| (term.as_str(), postings.len()))
+    }
+
+    /// `false` only when no indexed claim has `term`; see [`TermBloom`].
+    pub(crate) fn may_contain(&self, term: &str) -> bool {
+        self.terms.may_contain(term)
+    }
+
+    pub(crate) fn doc_freq(&self, term: &str) -> usize {
+        self.postings.get(term).map_or(0, PostingList::len)
+    }
+
+    /// Mean document length in tokens, at least 1.
+    pub(crate) fn avg_doc_len(&self) -> f32 {
+        if self.doc_ids.is_empty() {
+            return 1.0;
+        }
+        (self.total_doc_len as f32 / self.doc_ids.len() as f32).max(1.0)
+    }
+
+    /// Claims containing `term`, with the term's frequency in each.
+    pub(crate) fn postings(&self, term: &str) -> impl Iterator<Item = (&str, u32)> + '_ {
+        self.postings
+            .get(term)
+            .into_iter()
+            .flat_map(PostingList::iter)
+            .filter_map(|(doc_id, term_freq)| {
+                let claim_id = self.claim_ids.get(&doc_id)?;
+                Some((claim_id.as_str(), term_freq))
+            })
+    }
+
+    /// Claims containing `phrase`, checked against term positions.
+    pub(crate) fn phrase_claim_ids(&self, phrase: &PhraseQuery) -> HashSet<&str> {
+        let mut terms: Vec<&str> = phrase.tokens.iter().map(String::as_str).collect();
+        terms.sort_unstable();
+        terms.dedup();
+        if terms.is_empty() {
+            return HashSet::new();
+        }
+        let mut lists = Vec::with_capacity(terms.len());
+        for term in &terms {
+            match self.postings.get(*term) {
+                Some(list) => lists.push((*term, list)),
+                None => return HashSet::new(),
+            }
+        }
+        // Walk the rarest term's documents and decode the others only for
+        // those.
+        lists.sort_by_key(|(_, list)| list.len());
+        let mut docs: HashMap<u64, HashMap<&str, Vec<u32>>> = HashMap::new();
+        let (rarest_term, rarest) = lists[0];
+        for (doc_id, positions) in rarest.iter_positions() {
+            docs.entry(doc_id)
+                .or_default()
+                .insert(rarest_term, positions);
+        }
+        for &(term, list) in &lists[1..] {
+            let mut seen: HashMap<u64, HashMap<&str, Vec<u32>>> = HashMap::new();
+            for (doc_id, positions) in list.iter_positions() {
+                if let Some(mut by_term) = docs.remove(&doc_id) {
+                    by_term.insert(term, positions);
+                    seen.insert(doc_id, by_term);
+                }
+            }
+            docs = seen;
+        }
+
+        docs.into_iter()
+            .filter(|(_, by_term)| {
+                let positions: Vec<&[u32]> = phrase
+                    .tokens
+                    .iter()
+                    .map(|token| by_term[token.as_str()].as_slice())
+                    .collect();
+                positions_match_phrase(&positions, phrase.slop)
+            })
+            .filter_map(|(doc_id, _)| self.claim_ids.get(&doc_id).map(String::as_str))
+            .collect()
+    }
+}
+
+/// Each term's token positions, ascending.
+pub(crate) fn term_positions(tokens: &[String]) -> HashMap<&str, Vec<u32>> {
+    let mut positions: HashMap<&str, Vec<u32>> = HashMap::new();
+    for (position, token) in tokens.iter().enumerate() {
+        positions
+            .entry(token.as_str())
+            .or_default()
+            .push(position as u32);
+    }
+    positions
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
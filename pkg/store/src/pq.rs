@@ -0,0 +1,218 @@
+//! Product-quantization index, the compact alternative to the ANN graph
+//! selected with [`AnnIndexKind::ProductQuantization`].
+//!
+//! Each vector is split into contiguous subspaces and every chunk is
+//! replaced by the id of its nearest codebook centroid, so a vector
+//! costs one byte per subspace plus its norm. Codebooks are trained with
+//! k-means on the tenant's own vectors once it holds at least as many
+//! vectors as there are centroids, and retrained whenever the tenant
+//! has doubled in size since the last training. Until the first
+//! training the store falls back to exact search.
+//!
+//! [`AnnIndexKind::ProductQuantization`]: crate::AnnIndexKind::ProductQuantization
+
+use std::collections::HashMap;
+
+use crate::DistanceMetric;
+use crate::vector_index::{
+    TRAINING_SAMPLE_MAX, VectorIndex, dot, nearest_centroid, squared_l2, train_kmeans,
+};
+
+#[derive(Debug, Clone)]
+struct PqCode {
+    centroids: Vec<u8>,
+    norm: f32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TenantPqIndex {
+    subspaces: usize,
+    centroids: usize,
+    /// `[subspace][centroid]` -> sub-vector.
+    codebooks: Vec<Vec<Vec<f32>>>,
+    codes: HashMap<String, PqCode>,
+    /// Vectors held by the tenant, encoded or not.
+    vector_count: usize,
+    trained_on: usize,
+}
+
+impl TenantPqIndex {
+    pub(crate) fn new(subspaces: usize, centroids: usize) -> Self {
+        Self {
+            subspaces: subspaces.max(1),
+            centroids: centroids.clamp(1, 256),
+            codebooks: Vec::new(),
+            codes: HashMap::new(),
+            vector_count: 0,
+            trained_on: 0,
+        }
+    }
+
+    /// Asymmetric-distance scores of every encoded vector against
+    /// `query`, best first, truncated to `limit`.
+    pub(crate) fn search(
+        &self,
+        metric: DistanceMetric,
+        query: &[f32],
+        limit: usize,
+    ) -> Vec<(String, f32)> {
+        if !self.is_trained() || query.is_empty() {
+            return Vec::new();
+        }
+        let dimension = query.len();
+        let mut dot_table = Vec::with_capacity(self.subspaces);
+        let mut l2_table = Vec::with_capacity(self.subspaces);
+        for (subspace, codebook) in self.codebooks.iter().enumerate() {
+            let chunk = &query[subspace_range(dimension, self.subspaces, subspace)];
+            if codebook
+                .first()
+                .is_some_and(|centroid| centroid.len() != chunk.len())
+            {
+                return Vec::new();
+            }
+            dot_table.push(
+                codebook
+                    .iter()
+                    .map(|centroid| dot(chunk, centroid))
+                    .collect::<Vec<f32>>(),
+            );
+            l2_table.push(
+                codebook
+                    .iter()
+                    .map(|centroid| squared_l2(chunk, centroid))
+                    .collect::<Vec<f32>>(),
+            );
+        }
+        let query_norm = query.iter().map(|value| value * value).sum::<f32>().sqrt();
+
+        let mut scored: Vec<(String, f32)> = self
+            .codes
+            .iter()
+            .filter_map(|(claim_id, code)| {
+                let score = match metric {
+                    DistanceMetric::Dot | DistanceMetric::Cosine => {
+                        let dot: f32 = code
+                            .centroids
+                            .iter()
+                            .enumerate()
+                            .map(|(subspace, centroid)| dot_table[subspace][*centroid as usize])
+                            .sum();
+                        if metric == DistanceMetric::Dot {
+                            dot
+                        } else {
+                            let denom = query_norm * code.norm;
+                            if denom <= f32::EPSILON {
+                                return None;
+                            }
+                            dot / denom
+                        }
+                    }
+                    DistanceMetric::Euclidean => {
+                        let squared: f32 = code
+                            .centroids
+                            .iter()
+                            .enumerate()
+                            .map(|(subspace, centroid)| l2_table[subspace][*centroid as usize])
+                            .sum();
+                        -squared.sqrt()
+                    }
+                };
+                Some((claim_id.clone(), score))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scored.truncate(limit);
+        scored
+    }
+}
+
+impl VectorIndex for TenantPqIndex {
+    fn is_trained(&self) -> bool {
+        !self.codebooks.is_empty()
+    }
+
+    fn needs_training(&self) -> bool {
+        if self.is_trained() {
+            self.vector_count >= self.trained_on.saturating_mul(2)
+        } else {
+            self.vector_count >= self.centroids
+        }
+    }
+
+    fn note_added(&mut self) {
+        self.vector_count += 1;
+    }
+
+    fn remove(&mut self, claim_id: &str) {
+        self.vector_count = self.vector_count.saturating_sub(1);
+        self.codes.remove(claim_id);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.vector_count == 0
+    }
+
+    fn train(&mut self, vectors: &[(String, Vec<f32>)]) {
+        let Some(dimension) = vectors.first().map(|(_, values)| values.len()) else {
+            return;
+        };
+        let subspaces = self.subspaces.min(dimension);
+        let stride = vectors.len().div_ceil(TRAINING_SAMPLE_MAX).max(1);
+        let sample: Vec<&[f32]> = vectors
+            .iter()
+            .step_by(stride)
+            .map(|(_, values)| values.as_slice())
+            .collect();
+
+        self.subspaces = subspaces;
+        self.codebooks = (0..subspaces)
+            .map(|subspace| {
+                let range = subspace_range(dimension, subspaces, subspace);
+                let chunks: Vec<&[f32]> =
+                    sample.iter().map(|values| &values[range.clone()]).collect();
+                train_kmeans(&chunks, self.centroids)
+            })
+            .collect();
+        self.codes.clear();
+        for (claim_id, values) in vectors {
+            self.insert(claim_id, values);
+        }
+        self.vector_count = vectors.len();
+        self.trained_on = vectors.len();
+    }
+
+    fn insert(&mut self, claim_id: &str, values: &[f32]) {
+        if !self.is_trained() {
+            return;
+        }
+        let dimension = values.len();
+        let centroids = (0..self.subspaces)
+            .map(|subspace| {
+                let chunk = &values[subspace_range(dimension, self.subspaces, subspace)];
+                nearest_centroid(&self.codebooks[subspace], chunk) as u8
+            })
+            .collect();
+        self.codes.insert(
+            claim_id.to_string(),
+            PqCode {
+                centroids,
+                norm: values.iter().map(|value| value * value).sum::<f32>().sqrt(),
+            },
+        );
+    }
+
+    fn candidates(&self, metric: DistanceMetric, query: &[f32], limit: usize) -> Vec<String> {
+        self.search(metric, query, limit)
+            .into_iter()
+            .map(|(claim_id, _)| claim_id)
+            .collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn VectorIndex> {
+        Box::new(self.clone())
+    }
+}
+
+fn subspace_range(dimension: usize, subspaces: usize, subspace: usize) -> std::ops::Range<usize> {
+    (subspace * dimension / subspaces)..((subspace + 1) * dimension / subspaces)
+}
@@ -0,0 +1,336 @@
+//! Per-tenant dimensionality reduction of claim vectors.
+//!
+//! A [`VectorProjection`] is a `output_dimension x input_dimension`
+//! matrix with orthonormal (PCA) or random (Johnson-Lindenstrauss) rows.
+//! Once set for a tenant, every incoming vector of the input dimension is
+//! multiplied by it before it is stored or indexed, and so is every query
+//! vector, so clients keep sending full-size embeddings while the store
+//! holds and searches the reduced ones. Vectors that already have the
+//! output dimension are taken as projected, which is how snapshots and
+//! the disk mirror replay them.
+//!
+//! [`InMemoryStore::train_vector_projection`] fits a PCA projection to
+//! the tenant's current vectors; setting it re-projects them in place
+//! and rebuilds the tenant's index. The projection is written to the WAL
+//! and to snapshots ahead of the vectors, so replay re-creates the same
+//! reduced vectors. It is not reversible: the dropped dimensions are gone.
+
+use std::borrow::Cow;
+
+use schema::TenantId;
+
+use crate::vector_index::{TRAINING_SAMPLE_MAX, dot};
+use crate::{FileWal, InMemoryStore, StoreError};
+
+/// Subspace-iteration rounds of PCA training.
+const PCA_ITERATIONS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorProjection {
+    input_dimension: usize,
+    output_dimension: usize,
+    /// Row-major, one row of `input_dimension` weights per output value.
+    matrix: Vec<f32>,
+}
+
+impl VectorProjection {
+    /// A projection from an explicit row-major matrix, e.g. one trained
+    /// outside the store.
+    pub fn from_matrix(
+        input_dimension: usize,
+        output_dimension: usize,
+        matrix: Vec<f32>,
+    ) -> Result<Self, StoreError> {
+        check_reduction(input_dimension, output_dimension)?;
+        if matrix.len() != input_dimension * output_dimension {
+            return Err(StoreError::InvalidVector(format!(
+                "projection matrix has {} weights, expected {}",
+                matrix.len(),
+                input_dimension * output_dimension
+            )));
+        }
+        if matrix.iter().any(|weight| !weight.is_finite()) {
+            return Err(StoreError::InvalidVector(
+                "projection matrix contains non-finite weights".to_string(),
+            ));
+        }
+        Ok(Self {
+            input_dimension,
+            output_dimension,
+            matrix,
+        })
+    }
+
+    /// A random sign projection scaled by `1/sqrt(output)`, which
+    /// roughly preserves distances and needs no training data.
+    pub fn random(
+        input_dimension: usize,
+        output_dimension: usize,
+        seed: u64,
+    ) -> Result<Self, StoreError> {
+        let scale = 1.0 / (output_dimension.max(1) as f32).sqrt();
+        let mut rng = SplitMix64(seed);
+        let matrix = (0..input_dimension * output_dimension)
+            .map(|_| if rng.next() & 1 == 0 { scale } else { -scale })
+            .collect();
+        Self::from_matrix(input_dimension, output_dimension, matrix)
+    }
+
+    /// The `output_dimension` principal directions of `vectors`, found by
+    /// subspace iteration on an (uncentered) sample of at most
+    /// `TRAINING_SAMPLE_MAX` vectors. Uncentered, the projection keeps the
+    /// largest share of inner products, which suits every metric.
+    pub fn train_pca(vectors: &[&[f32]], output_dimension: usize) -> Result<Self, StoreError> {
+        let Some(input_dimension) = vectors.first().map(|values| values.len()) else {
+            return Err(StoreError::InvalidVector(
+                "projection training needs vectors".to_string(),
+            ));
+        };
+        if vectors.iter().any(|values| values.len() != input_dimension) {
+            return Err(StoreError::InvalidVector(
+                "projection training vectors differ in dimension".to_string(),
+            ));
+        }
+        if vectors.len() < output_dimension {
+            return Err(StoreError::InvalidVector(format!(
+                "projection to {output_dimension} dimensions needs at least as many vectors, got {}",
+                vectors.len()
+            )));
+        }
+        check_reduction(input_dimension, output_dimension)?;
+
+        let stride = vectors.len().div_ceil(TRAINING_SAMPLE_MAX).max(1);
+        let sample: Vec<&[f32]> = vectors.iter().step_by(stride).copied().collect();
+        let mut rng = SplitMix64(0x5eed);
+        let mut basis: Vec<Vec<f32>> = (0..output_dimension)
+            .map(|_| random_unit(&mut rng, input_dimension))
+            .collect();
+        orthonormalize(&mut basis, &mut rng);
+        for _ in 0..PCA_ITERATIONS {
+            // basis <- orth(X^T X basis), one sample pass per round.
+            let mut next = vec![vec![0.0f32; input_dimension]; output_dimension];
+            for values in &sample {
+                for (row, component) in next.iter_mut().zip(&basis) {
+                    let weight = dot(values, component);
+                    for (sum, value) in row.iter_mut().zip(values.iter()) {
+                        *sum += weight * value;
+                    }
+                }
+            }
+            basis = next;
+            orthonormalize(&mut basis, &mut rng);
+        }
+        Self::from_matrix(input_dimension, output_dimension, basis.concat())
+    }
+
+    pub fn input_dimension(&self) -> usize {
+        self.input_dimension
+    }
+
+    pub fn output_dimension(&self) -> usize {
+        self.output_dimension
+    }
+
+    pub fn matrix(&self) -> &[f32] {
+        &self.matrix
+    }
+
+    /// `values` (of the input dimension) in the reduced space.
+    pub fn project(&self, values: &[f32]) -> Vec<f32> {
+        self.matrix
+            .chunks_exact(self.input_dimension)
+            .map(|row| dot(row, values))
+            .collect()
+    }
+}
+
+impl InMemoryStore {
+    pub fn vector_projection(&self, tenant_id: &TenantId) -> Option<&VectorProjection> {
+        self.vector_projections.get(tenant_id.as_str())
+    }
+
+    /// Fit a PCA projection to `output_dimension` on `tenant_id`'s stored
+    /// vectors. The store is not changed; pass the result to
+    /// [`InMemoryStore::set_vector_projection`] to apply it.
+    pub fn train_vector_projection(
+        &self,
+        tenant_id: &TenantId,
+        output_dimension: usize,
+    ) -> Result<VectorProjection, StoreError> {
+        if self.vector_projections.contains_key(tenant_id.as_str()) {
+            return Err(StoreError::Conflict(format!(
+                "tenant '{tenant_id}' vectors are already projected"
+            )));
+        }
+        let vectors: Vec<Vec<f32>> = self
+            .tenant_vector_claim_ids(tenant_id)
+            .iter()
+            .filter_map(|claim_id| self.claim_vectors.get(claim_id).map(Cow::into_owned))
+            .collect();
+        let views: Vec<&[f32]> = vectors.iter().map(Vec::as_slice).collect();
+        VectorProjection::train_pca(&views, output_dimension)
+    }
+
+    /// Reduce `tenant_id`'s vectors with `projection` from now on:
+    /// re-project the stored ones, rebuild the tenant's index, and
+    /// project every later upsert and query. Returns the number of
+    /// vectors re-projected.
+    pub fn set_vector_projection(
+        &mut self,
+        tenant_id: &TenantId,
+        projection: VectorProjection,
+    ) -> Result<usize, StoreError> {
+        self.validate_vector_projection(tenant_id, &projection)?;
+        self.install_vector_projection(tenant_id, projection)
+    }
+
+    pub fn set_vector_projection_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        tenant_id: &TenantId,
+        projection: VectorProjection,
+    ) -> Result<usize, StoreError> {
+        self.validate_vector_projection(tenant_id, &projection)?;
+        wal.append_vector_projection(tenant_id, &projection)?;
+        self.install_vector_projection(tenant_id, projection)
+    }
+
+    fn validate_vector_projection(
+        &self,
+        tenant_id: &str,
+        projection: &VectorProjection,
+    ) -> Result<(), StoreError> {
+        if self.vector_projections.contains_key(tenant_id) {
+            return Err(StoreError::Conflict(format!(
+                "tenant '{tenant_id}' vectors are already projected"
+            )));
+        }
+        let declared = self
+            .tenant_vector_configs
+            .get(tenant_id)
+            .map(|config| config.dimension);
+        let stored = self.tenant_vector_dims.get(tenant_id).copied();
+        for dimension in [declared, stored].into_iter().flatten() {
+            if dimension != projection.input_dimension {
+                return Err(StoreError::InvalidVector(format!(
+                    "projection for tenant '{tenant_id}' expects {}-dimensional vectors, tenant uses {dimension}",
+                    projection.input_dimension
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn install_vector_projection(
+        &mut self,
+        tenant_id: &str,
+        projection: VectorProjection,
+    ) -> Result<usize, StoreError> {
+        let claim_ids = self.tenant_vector_claim_ids(tenant_id);
+        let mut reprojected = Vec::new();
+        for claim_id in &claim_ids {
+            if let Some(values) = self.claim_vectors.get(claim_id)
+                && values.len() == projection.input_dimension
+            {
+                reprojected.push((claim_id.clone(), projection.project(&values)));
+            }
+        }
+        // Remove before re-inserting so a mapped tenant's file, sized for
+        // the old dimension, is dropped rather than written to.
+        for (claim_id, _) in &reprojected {
+            self.claim_vectors.remove(tenant_id, claim_id);
+        }
+        let count = reprojected.len();
+        for (claim_id, values) in reprojected {
+            self.claim_vectors.insert(tenant_id, claim_id, values)?;
+        }
+        if self.tenant_vector_dims.contains_key(tenant_id) {
+            self.tenant_vector_dims
+                .insert(tenant_id.to_string(), projection.output_dimension);
+        }
+        self.vector_projections
+            .insert(tenant_id.to_string(), projection);
+        if self.claim_vectors.tenant_over_budget(tenant_id) {
+            self.claim_vectors.map_tenant(tenant_id, &claim_ids)?;
+        }
+        self.rebuild_tenant_vector_index(tenant_id);
+        Ok(count)
+    }
+
+    /// An incoming vector in the space `tenant_id` stores vectors in.
+    pub(crate) fn project_incoming_vector(&self, tenant_id: &str, vector: Vec<f32>) -> Vec<f32> {
+        match self.vector_projections.get(tenant_id) {
+            Some(projection) if vector.len() == projection.input_dimension => {
+                projection.project(&vector)
+            }
+            _ => vector,
+        }
+    }
+
+    /// A query vector in the space `tenant_id` stores vectors in.
+    pub(crate) fn project_query_vector<'a>(
+        &self,
+        tenant_id: &str,
+        query_vector: &'a [f32],
+    ) -> Cow<'a, [f32]> {
+        match self.vector_projections.get(tenant_id) {
+            Some(projection) if query_vector.len() == projection.input_dimension => {
+                Cow::Owned(projection.project(query_vector))
+            }
+            _ => Cow::Borrowed(query_vector),
+        }
+    }
+}
+
+fn check_reduction(input_dimension: usize, output_dimension: usize) -> Result<(), StoreError> {
+    if output_dimension == 0 || output_dimension >= input_dimension {
+        return Err(StoreError::InvalidVector(format!(
+            "projection must reduce dimensions: {input_dimension} -> {output_dimension}"
+        )));
+    }
+    Ok(())
+}
+
+/// Gram-Schmidt in place; rows that collapse (a rank-deficient sample)
+/// are replaced with fresh random directions.
+fn orthonormalize(rows: &mut [Vec<f32>], rng: &mut SplitMix64) {
+    let dimension = rows.first().map_or(0, Vec::len);
+    for idx in 0..rows.len() {
+        for _ in 0..4 {
+            let (done, rest) = rows.split_at_mut(idx);
+            let row = &mut rest[0];
+            for previous in done.iter() {
+                let overlap = dot(row, previous);
+                for (value, basis) in row.iter_mut().zip(previous) {
+                    *value -= overlap * basis;
+                }
+            }
+            let norm = dot(row, row).sqrt();
+            if norm > 1e-6 {
+                row.iter_mut().for_each(|value| *value /= norm);
+                break;
+            }
+            *row = random_unit(rng, dimension);
+        }
+    }
+}
+
+fn random_unit(rng: &mut SplitMix64, dimension: usize) -> Vec<f32> {
+    let values: Vec<f32> = (0..dimension)
+        .map(|_| (rng.next() >> 40) as f32 / (1u64 << 24) as f32 - 0.5)
+        .collect();
+    let norm = dot(&values, &values).sqrt().max(f32::EPSILON);
+    values.into_iter().map(|value| value / norm).collect()
+}
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,198 @@
+//! A small query language for retrieval.
+//!
+//! A query string mixes free text with `field:value` filters, so CLI tools
+//! and HTTP clients can express filters without building nested JSON:
+//!
+//! ```text
+//! entity:"Company X" type:factual after:2025-01-01 acquisition
+//! ```
+//!
+//! | Filter | Meaning |
+//! | --- | --- |
+//! | `entity:NAME` | claims mentioning `NAME`; repeated filters match any |
+//! | `type:TYPE` | claims of a [`ClaimType`]; repeated filters match any |
+//! | `collection:NAME` | claims in collection `NAME`; repeated filters match any |
+//! | `meta:KEY=VALUE` | claims labelled `KEY=VALUE`; repeated filters must all match |
+//! | `after:TIME` | event or validity time at or after `TIME` |
+//! | `before:TIME` | event or validity time strictly before `TIME` |
+//! | `min_confidence:X`, `max_confidence:X` | inclusive confidence bounds |
+//!
+//! Values containing spaces are double-quoted. `TIME` is a `YYYY-MM-DD`
+//! date (midnight UTC), an RFC 3339 timestamp, or unix seconds. Words that
+//! are not a known filter, including quoted phrases, stay in the query
+//! text unchanged, so `"company x"~2` is still a phrase query.
+
+use chrono::{DateTime, NaiveDate};
+use schema::{ClaimType, RetrievalRequest, TenantId};
+
+use crate::{ConfidenceRange, MetadataFilter, RetrievalOptions, StoreError};
+
+const FIELDS: &[&str] = &[
+    "entity",
+    "type",
+    "collection",
+    "meta",
+    "after",
+    "before",
+    "min_confidence",
+    "max_confidence",
+];
+
+/// A query string split into its free text and its filters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedQuery {
+    pub text: String,
+    pub options: RetrievalOptions<'static>,
+}
+
+impl ParsedQuery {
+    /// A request for the query's text.
+    pub fn request(&self, tenant_id: &TenantId, top_k: usize) -> RetrievalRequest {
+        RetrievalRequest::new(tenant_id, self.text.clone(), top_k)
+    }
+}
+
+/// Parse a query string; see the module docs for the syntax.
+pub fn parse_query(input: &str) -> Result<ParsedQuery, StoreError> {
+    let mut parsed = ParsedQuery::default();
+    let mut text: Vec<&str> = Vec::new();
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        if let Some((field, after_colon)) = split_field(rest) {
+            let (value, remaining) = take_value(field, after_colon)?;
+            apply_filter(&mut parsed.options, field, value)?;
+            rest = remaining;
+        } else {
+            let (term, remaining) = take_term(rest);
+            text.push(term);
+            rest = remaining;
+        }
+        rest = rest.trim_start();
+    }
+
+    if let (Some(from), Some(to)) = parsed.options.time_range
+        && from > to
+    {
+        return Err(query_error("after must be earlier than before"));
+    }
+    let ConfidenceRange { min, max } = parsed.options.confidence;
+    if let (Some(min), Some(max)) = (min, max)
+        && min > max
+    {
+        return Err(query_error(
+            "min_confidence must not be above max_confidence",
+        ));
+    }
+    parsed.text = text.join(" ");
+    Ok(parsed)
+}
+
+/// The known filter field `rest` starts with, and what follows its colon.
+fn split_field(rest: &str) -> Option<(&'static str, &str)> {
+    let (name, value) = rest.split_once(':')?;
+    let name = name.to_ascii_lowercase();
+    FIELDS
+        .iter()
+        .find(|field| **field == name)
+        .map(|field| (*field, value))
+}
+
+fn take_value<'a>(field: &str, rest: &'a str) -> Result<(&'a str, &'a str), StoreError> {
+    let (value, remaining) = if let Some(quoted) = rest.strip_prefix('"') {
+        let close = quoted
+            .find('"')
+            .ok_or_else(|| query_error(&format!("{field} value has an unclosed quote")))?;
+        (&quoted[..close], &quoted[close + 1..])
+    } else {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        (&rest[..end], &rest[end..])
+    };
+    if value.trim().is_empty() {
+        return Err(query_error(&format!("{field} needs a value")));
+    }
+    Ok((value.trim(), remaining))
+}
+
+/// One word of free text; a quoted phrase and its slop suffix stay whole.
+fn take_term(rest: &str) -> (&str, &str) {
+    let end = match rest.strip_prefix('"').and_then(|quoted| quoted.find('"')) {
+        Some(close) => {
+            let after = close + 2;
+            after
+                + rest[after..]
+                    .find(char::is_whitespace)
+                    .unwrap_or(rest.len() - after)
+        }
+        None => rest.find(char::is_whitespace).unwrap_or(rest.len()),
+    };
+    (&rest[..end], &rest[end..])
+}
+
+fn apply_filter(
+    options: &mut RetrievalOptions<'_>,
+    field: &str,
+    value: &str,
+) -> Result<(), StoreError> {
+    match field {
+        "entity" => options.entities.push(value.to_string()),
+        "type" => options.claim_types.push(parse_claim_type(value)?),
+        "collection" => options.collections.push(value.to_string()),
+        "meta" => {
+            let (key, label) = value
+                .split_once('=')
+                .filter(|(key, label)| !key.trim().is_empty() && !label.trim().is_empty())
+                .ok_or_else(|| query_error("meta filters must look like meta:key=value"))?;
+            options
+                .metadata
+                .push(MetadataFilter::equals(key.trim(), label.trim()));
+        }
+        "after" => options.time_range.0 = Some(parse_time(field, value)?),
+        "before" => options.time_range.1 = Some(parse_time(field, value)? - 1),
+        "min_confidence" => options.confidence.min = Some(parse_confidence(field, value)?),
+        "max_confidence" => options.confidence.max = Some(parse_confidence(field, value)?),
+        _ => unreachable!("split_field only returns known fields"),
+    }
+    Ok(())
+}
+
+fn parse_claim_type(raw: &str) -> Result<ClaimType, StoreError> {
+    match raw.to_ascii_lowercase().as_str() {
+        "factual" => Ok(ClaimType::Factual),
+        "opinion" => Ok(ClaimType::Opinion),
+        "prediction" => Ok(ClaimType::Prediction),
+        "temporal" => Ok(ClaimType::Temporal),
+        "causal" => Ok(ClaimType::Causal),
+        _ => Err(query_error(
+            "type must be factual, opinion, prediction, temporal, or causal",
+        )),
+    }
+}
+
+fn parse_time(field: &str, raw: &str) -> Result<i64, StoreError> {
+    if let Ok(unix) = raw.parse::<i64>() {
+        return Ok(unix);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        && let Some(midnight) = date.and_hms_opt(0, 0, 0)
+    {
+        return Ok(midnight.and_utc().timestamp());
+    }
+    DateTime::parse_from_rfc3339(raw)
+        .map(|time| time.timestamp())
+        .map_err(|_| {
+            query_error(&format!(
+                "{field} must be a YYYY-MM-DD date, an RFC 3339 timestamp, or unix seconds"
+            ))
+        })
+}
+
+fn parse_confidence(field: &str, raw: &str) -> Result<f32, StoreError> {
+    raw.parse::<f32>()
+        .ok()
+        .filter(|value| (0.0..=1.0).contains(value))
+        .ok_or_else(|| query_error(&format!("{field} must be a number between 0 and 1")))
+}
+
+fn query_error(message: &str) -> StoreError {
+    StoreError::Parse(format!("invalid query: {message}"))
+}
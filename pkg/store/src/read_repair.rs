@@ -0,0 +1,133 @@
+//! Read repair of index entries that point at missing claims.
+//!
+//! The candidate indexes (a tenant's claim set, its term postings, and its
+//! vectors) should only name claims the store holds. An entry naming a
+//! missing claim means a bug or a partially applied delete; retrieval has
+//! always skipped such entries silently. With read repair enabled,
+//! retrieval also records each one it meets and counts it in
+//! [`StoreMetricsSnapshot::stale_index_entries`](crate::StoreMetricsSnapshot);
+//! [`InMemoryStore::pending_read_repair_entries`] lists the ones still
+//! queued, for a caller that wants to log them. Retrieval only has shared
+//! access to the store, so the entries are queued and removed at the next
+//! write, or on demand through [`InMemoryStore::apply_read_repairs`].
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use schema::{ClaimId, TenantId};
+
+use crate::InMemoryStore;
+
+/// An index entry of `tenant_id` naming `claim_id`, which the store does
+/// not hold.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StaleIndexEntry {
+    pub tenant_id: TenantId,
+    pub claim_id: ClaimId,
+}
+
+/// Stale entries found by retrieval, waiting to be removed. Shared by
+/// clones of a store, like its metrics.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReadRepair {
+    enabled: bool,
+    pending: Arc<Mutex<HashSet<(String, String)>>>,
+}
+
+impl ReadRepair {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<(String, String)>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn take_pending(&self) -> Vec<(String, String)> {
+        let mut pending: Vec<(String, String)> = self.lock().drain().collect();
+        pending.sort_unstable();
+        pending
+    }
+}
+
+impl InMemoryStore {
+    /// Turn read repair on or off. Off by default.
+    pub fn set_read_repair(&mut self, enabled: bool) {
+        self.read_repair.enabled = enabled;
+    }
+
+    pub fn read_repair_enabled(&self) -> bool {
+        self.read_repair.enabled
+    }
+
+    /// Number of stale entries found and not yet removed.
+    pub fn pending_read_repairs(&self) -> usize {
+        self.read_repair.lock().len()
+    }
+
+    /// The stale entries found and not yet removed, by tenant then claim.
+    pub fn pending_read_repair_entries(&self) -> Vec<StaleIndexEntry> {
+        let mut entries: Vec<StaleIndexEntry> = self
+            .read_repair
+            .lock()
+            .iter()
+            .map(|(tenant_id, claim_id)| StaleIndexEntry {
+                tenant_id: tenant_id.into(),
+                claim_id: claim_id.into(),
+            })
+            .collect();
+        entries.sort_unstable();
+        entries
+    }
+
+    /// Remove the queued stale entries from the candidate indexes and
+    /// return how many claims were repaired. An entry whose claim has
+    /// been ingested since it was found is left alone.
+    pub fn apply_read_repairs(&mut self) -> usize {
+        let mut repaired = 0;
+        for (tenant_id, claim_id) in self.read_repair.take_pending() {
+            if self.claims.contains_key(claim_id.as_str()) {
+                continue;
+            }
+            self.remove_stale_index_entries(&tenant_id, &claim_id);
+            self.metrics.record_read_repair();
+            repaired += 1;
+        }
+        repaired
+    }
+
+    /// Note that an index of `tenant_id` named `claim_id`, which the store
+    /// does not hold. A no-op unless read repair is enabled.
+    pub(crate) fn note_stale_index_entry(&self, tenant_id: &str, claim_id: &str) {
+        if !self.read_repair.enabled {
+            return;
+        }
+        let newly_found = self
+            .read_repair
+            .lock()
+            .insert((tenant_id.to_string(), claim_id.to_string()));
+        if newly_found {
+            self.metrics.record_stale_index_entry();
+        }
+    }
+
+    fn remove_stale_index_entries(&mut self, tenant_id: &str, claim_id: &str) {
+        if let Some(ids) = self.tenant_claim_ids.get_mut(tenant_id) {
+            ids.remove(claim_id);
+            if ids.is_empty() {
+                self.tenant_claim_ids.remove(tenant_id);
+            }
+        }
+        let tokens = self.claim_tokens.remove(claim_id);
+        if let Some(index) = self.inverted_index.get_mut(tenant_id) {
+            match tokens {
+                Some(tokens) => index.remove(claim_id, &tokens),
+                None => index.purge(claim_id),
+            }
+            if index.is_empty() {
+                self.inverted_index.remove(tenant_id);
+            }
+        }
+        self.claim_vectors.remove(tenant_id, claim_id);
+        self.remove_vector_index_entry(tenant_id, claim_id);
+        self.bump_index_epoch(tenant_id);
+    }
+}
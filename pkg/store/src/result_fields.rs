@@ -0,0 +1,135 @@
+//! Choosing which fields retrieval results carry.
+//!
+//! Hydrating a result clones the claim's canonical text and every piece
+//! of its evidence into citations, which is most of the cost of a large
+//! `top_k` when the caller only needs claim ids and scores. A
+//! [`ResultFields`] selection is applied while results are hydrated, so
+//! the fields left out are never cloned. Ranking, scores, support counts
+//! and certainty bands are the same for every selection.
+
+use std::collections::HashSet;
+
+use schema::{Claim, ClaimId, Evidence, RetrievalRequest, RetrievalResult};
+
+use crate::{AnnSearchOverrides, InMemoryStore, RetrievalHit, RetrievalOptions, hydrate_hit};
+
+/// The fields of a [`RetrievalResult`] to fill in. Left-out fields are
+/// empty: no citations, and an empty `canonical_text` for
+/// [`ResultFields::IdsOnly`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResultFields {
+    #[default]
+    Full,
+    NoCitations,
+    /// Claim id, score, and support counts only.
+    IdsOnly,
+}
+
+impl ResultFields {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::NoCitations => "no_citations",
+            Self::IdsOnly => "ids_only",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "full" => Some(Self::Full),
+            "no_citations" => Some(Self::NoCitations),
+            "ids_only" | "ids" => Some(Self::IdsOnly),
+            _ => None,
+        }
+    }
+
+    pub fn includes_text(self) -> bool {
+        self != Self::IdsOnly
+    }
+
+    pub fn includes_citations(self) -> bool {
+        self == Self::Full
+    }
+}
+
+impl InMemoryStore {
+    /// Retrieve with per-query ANN overrides, returning only the
+    /// selected `fields` of each result.
+    #[deprecated(note = "use `retrieve_with` with `RetrievalOptions::with_fields`")]
+    pub fn retrieve_with_result_fields(
+        &self,
+        req: &RetrievalRequest,
+        time_range: (Option<i64>, Option<i64>),
+        query_vector: Option<&[f32]>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+        ann_overrides: AnnSearchOverrides,
+        fields: ResultFields,
+    ) -> Vec<RetrievalResult> {
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(time_range.0, time_range.1)
+                .with_query_vector(query_vector)
+                .with_allowed_claim_ids(allowed_claim_ids)
+                .with_ann_overrides(ann_overrides)
+                .with_fields(fields),
+        )
+    }
+
+    /// [`InMemoryStore::retrieve_with_time_range_query_vector_and_explicit_candidate_claim_ids`]
+    /// returning only the selected `fields` of each result.
+    pub fn retrieve_candidates_with_result_fields(
+        &self,
+        req: &RetrievalRequest,
+        time_range: (Option<i64>, Option<i64>),
+        query_vector: Option<&[f32]>,
+        candidate_claim_ids: &HashSet<ClaimId>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+        fields: ResultFields,
+    ) -> Vec<RetrievalResult> {
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(time_range.0, time_range.1)
+                .with_query_vector(query_vector)
+                .with_candidate_claim_ids(candidate_claim_ids)
+                .with_allowed_claim_ids(allowed_claim_ids)
+                .with_fields(fields),
+        )
+    }
+
+    pub(crate) fn hydrate_hits_with_fields(
+        &self,
+        hits: Vec<RetrievalHit>,
+        fields: ResultFields,
+    ) -> Vec<RetrievalResult> {
+        self.claim_access
+            .record(hits.iter().map(|hit| hit.claim_id.as_str()));
+        hits.into_iter()
+            .filter_map(|hit| {
+                let claim = self.claims.get(hit.claim_id.as_str())?;
+                Some(self.hydrate_hit_with_fields(hit, claim, fields))
+            })
+            .collect()
+    }
+
+    pub(crate) fn hydrate_hit_with_fields(
+        &self,
+        hit: RetrievalHit,
+        claim: &Claim,
+        fields: ResultFields,
+    ) -> RetrievalResult {
+        let evidence: &[Evidence] = self
+            .evidence_by_claim
+            .get(hit.claim_id.as_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        hydrate_hit(
+            hit,
+            claim,
+            evidence,
+            |e| self.evidence_quality(&claim.tenant_id, e),
+            fields,
+        )
+    }
+}
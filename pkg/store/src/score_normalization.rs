@@ -0,0 +1,113 @@
+//! Per-tenant normalization of claim confidence and evidence quality.
+//!
+//! Ranking assumes `confidence` and `source_quality` are probabilities in
+//! `[0, 1]`, but some sources produce percentages or logits. A tenant's
+//! [`TenantScoreNormalization`] maps each field into range before the
+//! bundle is validated, so the WAL and the indexes only ever hold
+//! normalized values. The default, [`ScoreNormalization::Strict`], leaves
+//! values alone and lets validation reject anything out of range.
+
+use schema::{Claim, Evidence, TenantId};
+
+use crate::InMemoryStore;
+
+/// The scale a source reports a score on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScoreScale {
+    /// `0` to `100`.
+    Percent,
+    /// Log-odds, mapped through the logistic function.
+    Logit,
+}
+
+/// How an incoming score is brought into `[0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScoreNormalization {
+    /// Reject scores outside `[0, 1]`.
+    #[default]
+    Strict,
+    /// Clamp scores into `[0, 1]`.
+    Clamp,
+    /// Convert from `scale`, then clamp.
+    Rescale(ScoreScale),
+}
+
+impl ScoreNormalization {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Clamp => "clamp",
+            Self::Rescale(ScoreScale::Percent) => "rescale_percent",
+            Self::Rescale(ScoreScale::Logit) => "rescale_logit",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "clamp" => Some(Self::Clamp),
+            "rescale_percent" | "percent" => Some(Self::Rescale(ScoreScale::Percent)),
+            "rescale_logit" | "logit" => Some(Self::Rescale(ScoreScale::Logit)),
+            _ => None,
+        }
+    }
+
+    /// The normalized score. Non-finite scores pass through unchanged so
+    /// validation rejects them in every mode.
+    pub fn apply(self, value: f32) -> f32 {
+        if !value.is_finite() {
+            return value;
+        }
+        match self {
+            Self::Strict => value,
+            Self::Clamp => value.clamp(0.0, 1.0),
+            Self::Rescale(ScoreScale::Percent) => (value / 100.0).clamp(0.0, 1.0),
+            Self::Rescale(ScoreScale::Logit) => 1.0 / (1.0 + (-value).exp()),
+        }
+    }
+}
+
+/// A tenant's normalization of each ranking input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TenantScoreNormalization {
+    pub confidence: ScoreNormalization,
+    pub source_quality: ScoreNormalization,
+}
+
+impl InMemoryStore {
+    /// Normalize `tenant_id`'s incoming scores with `normalization`, or
+    /// go back to strict validation with `None`. Claims already stored
+    /// are not touched.
+    pub fn set_tenant_score_normalization(
+        &mut self,
+        tenant_id: &TenantId,
+        normalization: Option<TenantScoreNormalization>,
+    ) {
+        match normalization {
+            Some(normalization) if normalization != TenantScoreNormalization::default() => {
+                self.score_normalizations
+                    .insert(tenant_id.to_string(), normalization);
+            }
+            _ => {
+                self.score_normalizations.remove(tenant_id.as_str());
+            }
+        }
+    }
+
+    pub fn tenant_score_normalization(&self, tenant_id: &TenantId) -> TenantScoreNormalization {
+        self.score_normalizations
+            .get(tenant_id.as_str())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn normalize_bundle_scores(&self, claim: &mut Claim, evidence: &mut [Evidence]) {
+        let Some(normalization) = self.score_normalizations.get(claim.tenant_id.as_str()) else {
+            return;
+        };
+        claim.confidence = normalization.confidence.apply(claim.confidence);
+        for evd in evidence {
+            evd.source_quality = normalization.source_quality.apply(evd.source_quality);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Scatter-gather retrieval over several shards.
+//!
+//! Each shard ranks against its own corpus, so raw scores from two shards
+//! are not on the same scale: BM25 weights terms by the shard's own
+//! document frequencies and a small shard inflates them. Merging by raw
+//! score then favors whichever shard happens to score high. A
+//! [`ScoreCalibration`] rescales every shard's scores onto a common scale
+//! before the merge: min-max maps each shard's list onto `[0, 1]`, and
+//! z-score expresses each score in standard deviations from its shard's
+//! mean. Merged results carry the calibrated score.
+
+use std::collections::HashMap;
+
+use schema::{ClaimId, RetrievalRequest, RetrievalResult};
+
+use crate::{InMemoryStore, RetrievalOptions};
+
+/// How per-shard scores are made comparable before merging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreCalibration {
+    /// Merge raw scores; right when every shard sees the same corpus
+    /// statistics.
+    #[default]
+    Raw,
+    /// `(score - min) / (max - min)` per shard; a shard whose scores are
+    /// all equal maps them to 1.0.
+    MinMax,
+    /// `(score - mean) / stddev` per shard; a shard whose scores are all
+    /// equal maps them to 0.0.
+    ZScore,
+}
+
+impl ScoreCalibration {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScoreCalibration::Raw => "raw",
+            ScoreCalibration::MinMax => "min_max",
+            ScoreCalibration::ZScore => "z_score",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "raw" | "none" => Some(ScoreCalibration::Raw),
+            "min_max" | "minmax" => Some(ScoreCalibration::MinMax),
+            "z_score" | "zscore" => Some(ScoreCalibration::ZScore),
+            _ => None,
+        }
+    }
+
+    /// Rescale one shard's scores in place.
+    pub fn calibrate(self, results: &mut [RetrievalResult]) {
+        if results.is_empty() {
+            return;
+        }
+        match self {
+            ScoreCalibration::Raw => {}
+            ScoreCalibration::MinMax => {
+                let (min, max) = results.iter().fold((f32::MAX, f32::MIN), |(min, max), r| {
+                    (min.min(r.score), max.max(r.score))
+                });
+                let span = max - min;
+                for result in results {
+                    result.score = if span > f32::EPSILON {
+                        (result.score - min) / span
+                    } else {
+                        1.0
+                    };
+                }
+            }
+            ScoreCalibration::ZScore => {
+                let count = results.len() as f32;
+                let mean = results.iter().map(|r| r.score).sum::<f32>() / count;
+                let variance = results
+                    .iter()
+                    .map(|r| (r.score - mean).powi(2))
+                    .sum::<f32>()
+                    / count;
+                let stddev = variance.sqrt();
+                for result in results {
+                    result.score = if stddev > f32::EPSILON {
+                        (result.score - mean) / stddev
+                    } else {
+                        0.0
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Merge the ranked results of several shards into the best `top_k`,
+/// calibrating each shard's scores first. A claim returned by more than
+/// one shard keeps its best calibrated score. Ties go to the lower claim
+/// id, so the merge is independent of shard order.
+pub fn merge_shard_results(
+    shards: Vec<Vec<RetrievalResult>>,
+    top_k: usize,
+    calibration: ScoreCalibration,
+) -> Vec<RetrievalResult> {
+    let mut best: HashMap<ClaimId, RetrievalResult> = HashMap::new();
+    for mut results in shards {
+        calibration.calibrate(&mut results);
+        for result in results {
+            match best.get(&result.claim_id) {
+                Some(kept) if kept.score >= result.score => {}
+                _ => {
+                    best.insert(result.claim_id.clone(), result);
+                }
+            }
+        }
+    }
+    let mut merged: Vec<RetrievalResult> = best.into_values().collect();
+    merged.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.claim_id.cmp(&b.claim_id))
+    });
+    merged.truncate(top_k);
+    merged
+}
+
+/// Retrieve `req` from every shard with the same options and merge the
+/// results with `calibration`.
+pub fn scatter_gather(
+    shards: &[&InMemoryStore],
+    req: &RetrievalRequest,
+    options: &RetrievalOptions<'_>,
+    calibration: ScoreCalibration,
+) -> Vec<RetrievalResult> {
+    let per_shard = shards
+        .iter()
+        .map(|shard| shard.retrieve_with(req, options))
+        .collect();
+    merge_shard_results(per_shard, req.top_k, calibration)
+}
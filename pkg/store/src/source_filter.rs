@@ -0,0 +1,104 @@
+//! Excluding claims that rest on distrusted sources.
+//!
+//! Evidence is indexed per tenant by `source_id`, so the claims citing a
+//! source resolve without a pass over the tenant. A retrieval that lists
+//! sources in [`RetrievalOptions::exclude_sources`] drops every claim
+//! whose supporting evidence all comes from those sources. A claim that
+//! also has support from a source not listed is kept, as is a claim with
+//! no supporting evidence at all: only the listed sources' word is
+//! discarded, not claims they merely mention.
+
+use std::collections::HashSet;
+
+use schema::{ClaimId, Evidence, Stance, TenantId};
+
+use crate::{InMemoryStore, RetrievalOptions};
+
+impl InMemoryStore {
+    /// Claims of `tenant_id` with evidence citing `source_id`, in any
+    /// stance.
+    pub fn claim_ids_for_source(&self, tenant_id: &TenantId, source_id: &str) -> HashSet<ClaimId> {
+        self.source_index
+            .get(tenant_id.as_str())
+            .and_then(|index| index.get(source_id))
+            .into_iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    /// Claims of `tenant_id` whose supporting evidence all cites one of
+    /// `source_ids`.
+    pub fn claim_ids_supported_only_by(
+        &self,
+        tenant_id: &TenantId,
+        source_ids: &[String],
+    ) -> HashSet<ClaimId> {
+        let Some(index) = self.source_index.get(tenant_id.as_str()) else {
+            return HashSet::new();
+        };
+        let excluded: HashSet<&str> = source_ids.iter().map(String::as_str).collect();
+        excluded
+            .iter()
+            .filter_map(|source_id| index.get(*source_id))
+            .flatten()
+            .filter(|claim_id| {
+                let mut supports = self
+                    .evidence_by_claim
+                    .get(claim_id.as_str())
+                    .into_iter()
+                    .flatten()
+                    .filter(|evidence| evidence.effective_stance() == Stance::Supports)
+                    .peekable();
+                supports.peek().is_some()
+                    && supports.all(|evidence| excluded.contains(evidence.source_id.as_str()))
+            })
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    /// The allowed set for [`RetrievalOptions::exclude_sources`]: every
+    /// claim of `tenant_id` not supported only by the excluded sources.
+    pub(crate) fn claim_ids_outside_sources(
+        &self,
+        tenant_id: &TenantId,
+        options: &RetrievalOptions<'_>,
+    ) -> HashSet<ClaimId> {
+        let excluded = self.claim_ids_supported_only_by(tenant_id, &options.exclude_sources);
+        self.tenant_claim_ids
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|claim_id| !excluded.contains(claim_id.as_str()))
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    pub(crate) fn index_evidence_source(&mut self, tenant_id: &str, evidence: &Evidence) {
+        self.source_index
+            .entry(tenant_id.to_string())
+            .or_default()
+            .entry(evidence.source_id.clone())
+            .or_default()
+            .insert(evidence.claim_id.to_string());
+    }
+
+    /// Drop `claim_id` from the index entries of every source its
+    /// evidence cites.
+    pub(crate) fn unindex_evidence_sources(&mut self, tenant_id: &str, claim_id: &str) {
+        let Some(index) = self.source_index.get_mut(tenant_id) else {
+            return;
+        };
+        for evidence in self.evidence_by_claim.get(claim_id).into_iter().flatten() {
+            if let Some(claim_ids) = index.get_mut(&evidence.source_id) {
+                claim_ids.remove(claim_id);
+                if claim_ids.is_empty() {
+                    index.remove(&evidence.source_id);
+                }
+            }
+        }
+        if index.is_empty() {
+            self.source_index.remove(tenant_id);
+        }
+    }
+}
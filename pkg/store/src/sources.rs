@@ -0,0 +1,97 @@
+//! A per-tenant registry of the sources evidence cites.
+//!
+//! Evidence carries its own `source_quality`, set by whoever ingested it,
+//! so one source can be scored differently on every piece of evidence.
+//! Sources registered with [`InMemoryStore::put_source`] carry a default
+//! quality and a [`TrustTier`](schema::TrustTier) instead, and ranking
+//! scores evidence citing a registered source at the source's
+//! [`effective_quality`](schema::Source::effective_quality), whatever the
+//! evidence says. Evidence from unregistered sources keeps its own
+//! quality. Re-registering a source re-scores all of its evidence at
+//! once, with no evidence rewritten. The same quality decides certainty
+//! bands, citations and [`InMemoryStore::sources_for_tenant`].
+//!
+//! The persistent variant writes a record to the WAL, and checkpoints
+//! write one for every source.
+
+use std::collections::HashMap;
+
+use schema::{Evidence, Source, TenantId, validate_source};
+
+use crate::wal::PersistedRecord;
+use crate::{ChangeRecord, FileWal, InMemoryStore, StoreError};
+
+/// A tenant's registered sources by id.
+pub(crate) type TenantSources = HashMap<String, Source>;
+
+impl InMemoryStore {
+    /// Register `source` with its tenant, replacing, and returning, any
+    /// source registered under the same id.
+    pub fn put_source(&mut self, source: Source) -> Result<Option<Source>, StoreError> {
+        validate_source(&source)?;
+        Ok(self.apply_source(source))
+    }
+
+    /// [`Self::put_source`], recording the source in `wal` first.
+    pub fn put_source_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        source: Source,
+    ) -> Result<Option<Source>, StoreError> {
+        validate_source(&source)?;
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_source(&source)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        Ok(self.apply_source(source))
+    }
+
+    pub fn source(&self, tenant_id: &TenantId, source_id: &str) -> Option<&Source> {
+        self.sources.get(tenant_id.as_str())?.get(source_id)
+    }
+
+    /// The sources registered for `tenant_id`, by id.
+    pub fn sources(&self, tenant_id: &TenantId) -> Vec<&Source> {
+        let mut sources: Vec<&Source> = self
+            .sources
+            .get(tenant_id.as_str())
+            .map(|sources| sources.values().collect())
+            .unwrap_or_default();
+        sources.sort_unstable_by(|a, b| a.source_id.cmp(&b.source_id));
+        sources
+    }
+
+    /// The quality ranking gives `evidence` of `tenant_id`: its source's
+    /// effective quality when the source is registered, otherwise the
+    /// evidence's own.
+    pub fn evidence_quality(&self, tenant_id: &TenantId, evidence: &Evidence) -> f32 {
+        self.source(tenant_id, &evidence.source_id)
+            .map_or(evidence.source_quality, Source::effective_quality)
+    }
+
+    /// Register `source`. Its evidence is re-scored, so the tenant's
+    /// index epoch moves on and retrieval cursors restart.
+    pub(crate) fn apply_source(&mut self, source: Source) -> Option<Source> {
+        self.bump_index_epoch(&source.tenant_id);
+        self.change_feed
+            .publish_with(|| ChangeRecord::Source(source.clone()));
+        self.sources
+            .entry(source.tenant_id.to_string())
+            .or_default()
+            .insert(source.source_id.clone(), source)
+    }
+
+    /// Every registered source, by tenant and then id.
+    pub(crate) fn push_source_records(&self, records: &mut Vec<PersistedRecord>) {
+        let mut tenants: Vec<TenantId> = self.sources.keys().map(TenantId::from).collect();
+        tenants.sort_unstable();
+        for tenant_id in &tenants {
+            records.extend(
+                self.sources(tenant_id)
+                    .into_iter()
+                    .cloned()
+                    .map(PersistedRecord::Source),
+            );
+        }
+    }
+}
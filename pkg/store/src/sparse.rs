@@ -0,0 +1,290 @@
+//! Sparse term-weight vectors and hybrid scoring.
+//!
+//! A [`SparseVector`] maps terms to weights, the shape learned sparse
+//! encoders such as SPLADE produce. Each claim may carry one next to its
+//! dense vector; a per-tenant posting list from term to claims lets a
+//! sparse query pull candidates without scanning. A claim's sparse score
+//! is the dot product with the query, divided by the best score among the
+//! query's candidates so it lands in `[0, 1]`, and is added to the
+//! BM25-and-dense score in the final rank. Sparse vectors are persisted
+//! through the WAL and snapshots; the redb mirror does not hold them.
+
+use std::collections::{HashMap, HashSet};
+
+use schema::{Claim, ClaimId, RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::wal::{PersistedRecord, SparseVectorRecord};
+use crate::{ChangeRecord, FileWal, InMemoryStore, RetrievalOptions, StoreError};
+
+/// Weight of the normalized sparse score in a hit's final score.
+pub(crate) const SPARSE_SCORE_WEIGHT: f32 = 0.3;
+
+/// Terms and their weights, kept sorted by term. Zero weights are
+/// dropped on construction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SparseVector {
+    terms: Vec<String>,
+    weights: Vec<f32>,
+}
+
+impl SparseVector {
+    /// Fails with [`StoreError::InvalidVector`] on an empty term, a
+    /// non-finite weight, or a term given twice.
+    pub fn new(entries: impl IntoIterator<Item = (String, f32)>) -> Result<Self, StoreError> {
+        let mut entries: Vec<(String, f32)> = entries
+            .into_iter()
+            .filter(|(_, weight)| *weight != 0.0)
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        for (idx, (term, weight)) in entries.iter().enumerate() {
+            if term.is_empty() {
+                return Err(StoreError::InvalidVector(
+                    "sparse vector terms cannot be empty".to_string(),
+                ));
+            }
+            if !weight.is_finite() {
+                return Err(StoreError::InvalidVector(
+                    "sparse vector weights must be finite".to_string(),
+                ));
+            }
+            if idx > 0 && entries[idx - 1].0 == *term {
+                return Err(StoreError::InvalidVector(format!(
+                    "sparse vector term '{term}' appears more than once"
+                )));
+            }
+        }
+        let (terms, weights) = entries.into_iter().unzip();
+        Ok(Self { terms, weights })
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.terms
+            .iter()
+            .map(String::as_str)
+            .zip(self.weights.iter().copied())
+    }
+
+    pub fn weight(&self, term: &str) -> Option<f32> {
+        let idx = self
+            .terms
+            .binary_search_by(|candidate| candidate.as_str().cmp(term))
+            .ok()?;
+        Some(self.weights[idx])
+    }
+
+    pub fn dot(&self, other: &SparseVector) -> f32 {
+        let (mut left, mut right) = (0, 0);
+        let mut sum = 0.0;
+        while left < self.terms.len() && right < other.terms.len() {
+            match self.terms[left].cmp(&other.terms[right]) {
+                std::cmp::Ordering::Less => left += 1,
+                std::cmp::Ordering::Greater => right += 1,
+                std::cmp::Ordering::Equal => {
+                    sum += self.weights[left] * other.weights[right];
+                    left += 1;
+                    right += 1;
+                }
+            }
+        }
+        sum
+    }
+}
+
+impl InMemoryStore {
+    pub fn upsert_claim_sparse_vector(
+        &mut self,
+        claim_id: &ClaimId,
+        vector: SparseVector,
+    ) -> Result<(), StoreError> {
+        self.apply_claim_sparse_vector(claim_id, vector)?;
+        self.metrics.record_vector_upserted();
+        Ok(())
+    }
+
+    pub fn upsert_claim_sparse_vector_persistent(
+        &mut self,
+        wal: &mut FileWal,
+        claim_id: &ClaimId,
+        vector: SparseVector,
+    ) -> Result<(), StoreError> {
+        validate_sparse_vector(&vector)?;
+        let wal_bytes_before = wal.appended_bytes();
+        wal.append_claim_sparse_vector(claim_id, &vector)?;
+        self.metrics
+            .record_wal_bytes(wal.appended_bytes() - wal_bytes_before);
+        self.apply_claim_sparse_vector(claim_id, vector)?;
+        self.metrics.record_vector_upserted();
+        Ok(())
+    }
+
+    pub fn claim_sparse_vector(&self, claim_id: &ClaimId) -> Option<&SparseVector> {
+        self.sparse_vectors.get(claim_id.as_str())
+    }
+
+    /// The `top_n` claims of `tenant_id` with the highest positive dot
+    /// product against `query`.
+    pub fn sparse_vector_top_candidates(
+        &self,
+        tenant_id: &TenantId,
+        query: &SparseVector,
+        top_n: usize,
+    ) -> Vec<ClaimId> {
+        let mut scored: Vec<(String, f32)> = self
+            .sparse_dot_products(tenant_id, query)
+            .into_iter()
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scored
+            .into_iter()
+            .take(top_n)
+            .map(|(claim_id, _)| claim_id.into())
+            .collect()
+    }
+
+    /// Retrieve with a sparse query vector fused into the rank alongside
+    /// BM25 and, when given, the dense query vector.
+    #[deprecated(note = "use `retrieve_with` with `RetrievalOptions::with_sparse_query`")]
+    pub fn retrieve_hybrid(
+        &self,
+        req: &RetrievalRequest,
+        time_range: (Option<i64>, Option<i64>),
+        query_vector: Option<&[f32]>,
+        sparse_query: Option<&SparseVector>,
+        allowed_claim_ids: Option<&HashSet<ClaimId>>,
+    ) -> Vec<RetrievalResult> {
+        self.retrieve_with(
+            req,
+            &RetrievalOptions::new()
+                .with_time_range(time_range.0, time_range.1)
+                .with_query_vector(query_vector)
+                .with_sparse_query(sparse_query)
+                .with_allowed_claim_ids(allowed_claim_ids),
+        )
+    }
+
+    /// Normalized sparse scores of `candidates`, clamped at zero.
+    pub(crate) fn sparse_similarities(
+        &self,
+        query: &SparseVector,
+        candidates: &[String],
+    ) -> HashMap<String, f32> {
+        let scores: Vec<(&String, f32)> = candidates
+            .iter()
+            .filter_map(|claim_id| {
+                let score = self.sparse_vectors.get(claim_id)?.dot(query);
+                (score > 0.0).then_some((claim_id, score))
+            })
+            .collect();
+        let best = scores.iter().map(|(_, score)| *score).fold(0.0, f32::max);
+        scores
+            .into_iter()
+            .map(|(claim_id, score)| (claim_id.clone(), score / best))
+            .collect()
+    }
+
+    fn sparse_dot_products(&self, tenant_id: &str, query: &SparseVector) -> HashMap<String, f32> {
+        let mut scores: HashMap<String, f32> = HashMap::new();
+        let Some(postings) = self.sparse_postings.get(tenant_id) else {
+            return scores;
+        };
+        for (term, query_weight) in query.iter() {
+            let Some(claim_ids) = postings.get(term) else {
+                continue;
+            };
+            for claim_id in claim_ids {
+                if let Some(weight) = self
+                    .sparse_vectors
+                    .get(claim_id)
+                    .and_then(|vector| vector.weight(term))
+                {
+                    *scores.entry(claim_id.clone()).or_default() += query_weight * weight;
+                }
+            }
+        }
+        scores
+    }
+
+    pub(crate) fn apply_claim_sparse_vector(
+        &mut self,
+        claim_id: &str,
+        vector: SparseVector,
+    ) -> Result<(), StoreError> {
+        validate_sparse_vector(&vector)?;
+        let claim = self
+            .claims
+            .get(claim_id)
+            .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string()))?
+            .clone();
+        self.remove_claim_sparse_vector(&claim);
+        let postings = self
+            .sparse_postings
+            .entry(claim.tenant_id.into())
+            .or_default();
+        for (term, _) in vector.iter() {
+            postings
+                .entry(term.to_string())
+                .or_default()
+                .insert(claim_id.to_string());
+        }
+        self.change_feed
+            .publish_with(|| ChangeRecord::SparseVector {
+                claim_id: claim_id.into(),
+                vector: vector.clone(),
+            });
+        self.sparse_vectors.insert(claim_id.to_string(), vector);
+        Ok(())
+    }
+
+    pub(crate) fn remove_claim_sparse_vector(&mut self, claim: &Claim) {
+        let Some(vector) = self.sparse_vectors.remove(claim.claim_id.as_str()) else {
+            return;
+        };
+        let Some(postings) = self.sparse_postings.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        for (term, _) in vector.iter() {
+            if let Some(claim_ids) = postings.get_mut(term) {
+                claim_ids.remove(claim.claim_id.as_str());
+                if claim_ids.is_empty() {
+                    postings.remove(term);
+                }
+            }
+        }
+        if postings.is_empty() {
+            self.sparse_postings.remove(claim.tenant_id.as_str());
+        }
+    }
+
+    pub(crate) fn push_sparse_vector_records(
+        &self,
+        claim_ids: &[ClaimId],
+        records: &mut Vec<PersistedRecord>,
+    ) {
+        for claim_id in claim_ids {
+            if let Some(vector) = self.sparse_vectors.get(claim_id.as_str()) {
+                records.push(PersistedRecord::SparseVector(SparseVectorRecord {
+                    claim_id: claim_id.to_string(),
+                    vector: vector.clone(),
+                }));
+            }
+        }
+    }
+}
+
+fn validate_sparse_vector(vector: &SparseVector) -> Result<(), StoreError> {
+    if vector.is_empty() {
+        return Err(StoreError::InvalidVector(
+            "sparse vector cannot be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+//! Standing queries evaluated as claims arrive.
+//!
+//! Alerting on new claims used to mean re-running the same retrieval on
+//! a timer and diffing the results. A [`StandingQuery`] registered for a
+//! tenant is instead evaluated once per claim applied to that tenant,
+//! with the new claim as the only allowed candidate, so each evaluation
+//! costs one candidate no matter how large the tenant is. A claim that
+//! the query would retrieve at or above the query's minimum score is
+//! appended to the watch's feed, which callers page through by sequence
+//! number with [`InMemoryStore::standing_query_matches`].
+//!
+//! A claim is evaluated when it is applied, before the evidence that
+//! arrives with it in the same bundle, and matches a watch at most once
+//! even if it is upserted again. Feeds keep the latest
+//! [`STANDING_QUERY_FEED_CAPACITY`] matches; a gap in sequence numbers
+//! means older matches were dropped before they were read.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use schema::{ClaimId, RetrievalRequest, StanceMode, TenantId};
+
+use crate::{InMemoryStore, RetrievalOptions};
+
+/// Matches kept per watch before the oldest are dropped.
+pub(crate) const STANDING_QUERY_FEED_CAPACITY: usize = 1024;
+
+/// A retrieval to run against every new claim of a tenant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandingQuery {
+    pub query: String,
+    pub stance_mode: StanceMode,
+    /// Filters a new claim must pass. The deadline is ignored.
+    pub options: RetrievalOptions<'static>,
+    /// Matches scoring below this are not recorded.
+    pub min_score: f32,
+}
+
+impl StandingQuery {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            stance_mode: StanceMode::Balanced,
+            options: RetrievalOptions::new(),
+            min_score: 0.0,
+        }
+    }
+
+    pub fn with_stance_mode(mut self, stance_mode: StanceMode) -> Self {
+        self.stance_mode = stance_mode;
+        self
+    }
+
+    pub fn with_options(mut self, options: RetrievalOptions<'static>) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.min_score = min_score;
+        self
+    }
+}
+
+/// A claim that matched a standing query. `sequence` counts the watch's
+/// matches from zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandingQueryMatch {
+    pub sequence: u64,
+    pub claim_id: ClaimId,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StandingQueryWatch {
+    query: StandingQuery,
+    matched_claim_ids: HashSet<String>,
+    feed: VecDeque<StandingQueryMatch>,
+    next_sequence: u64,
+}
+
+/// A tenant's watches by id.
+pub(crate) type TenantStandingQueries = BTreeMap<String, StandingQueryWatch>;
+
+impl InMemoryStore {
+    /// Evaluate `query` against every claim applied to `tenant_id` from
+    /// now on. Replaces, and returns, any query already registered as
+    /// `watch_id`; its feed starts over.
+    pub fn register_standing_query(
+        &mut self,
+        tenant_id: &TenantId,
+        watch_id: &str,
+        query: StandingQuery,
+    ) -> Option<StandingQuery> {
+        let watch = StandingQueryWatch {
+            query,
+            matched_claim_ids: HashSet::new(),
+            feed: VecDeque::new(),
+            next_sequence: 0,
+        };
+        self.standing_queries
+            .entry(tenant_id.to_string())
+            .or_default()
+            .insert(watch_id.to_string(), watch)
+            .map(|previous| previous.query)
+    }
+
+    pub fn remove_standing_query(&mut self, tenant_id: &TenantId, watch_id: &str) -> bool {
+        let Some(watches) = self.standing_queries.get_mut(tenant_id.as_str()) else {
+            return false;
+        };
+        let removed = watches.remove(watch_id).is_some();
+        if watches.is_empty() {
+            self.standing_queries.remove(tenant_id.as_str());
+        }
+        removed
+    }
+
+    /// Watch ids registered for `tenant_id`, sorted.
+    pub fn standing_query_ids(&self, tenant_id: &TenantId) -> Vec<String> {
+        self.standing_queries
+            .get(tenant_id.as_str())
+            .map(|watches| watches.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn standing_query(&self, tenant_id: &TenantId, watch_id: &str) -> Option<&StandingQuery> {
+        self.standing_query_watch(tenant_id, watch_id)
+            .map(|watch| &watch.query)
+    }
+
+    /// Up to `limit` matches of the watch with a sequence number of at
+    /// least `from_sequence`, oldest first. Reading does not consume
+    /// them; pass the last sequence seen plus one to read on. `None` when
+    /// no such watch is registered.
+    pub fn standing_query_matches(
+        &self,
+        tenant_id: &TenantId,
+        watch_id: &str,
+        from_sequence: u64,
+        limit: usize,
+    ) -> Option<Vec<StandingQueryMatch>> {
+        let watch = self.standing_query_watch(tenant_id, watch_id)?;
+        Some(
+            watch
+                .feed
+                .iter()
+                .filter(|matched| matched.sequence >= from_sequence)
+                .take(limit)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Run the tenant's standing queries against one newly applied claim.
+    pub(crate) fn evaluate_standing_queries(&mut self, tenant_id: &str, claim_id: &str) {
+        let Some(watches) = self.standing_queries.get(tenant_id) else {
+            return;
+        };
+        let candidate = HashSet::from([ClaimId::from(claim_id)]);
+        let matches: Vec<(String, f32)> = watches
+            .iter()
+            .filter(|(_, watch)| !watch.matched_claim_ids.contains(claim_id))
+            .filter_map(|(watch_id, watch)| {
+                let score = self.standing_query_score(tenant_id, &watch.query, &candidate)?;
+                Some((watch_id.clone(), score))
+            })
+            .collect();
+        let Some(watches) = self.standing_queries.get_mut(tenant_id) else {
+            return;
+        };
+        for (watch_id, score) in matches {
+            let Some(watch) = watches.get_mut(&watch_id) else {
+                continue;
+            };
+            watch.matched_claim_ids.insert(claim_id.to_string());
+            watch.feed.push_back(StandingQueryMatch {
+                sequence: watch.next_sequence,
+                claim_id: claim_id.into(),
+                score,
+            });
+            watch.next_sequence += 1;
+            if watch.feed.len() > STANDING_QUERY_FEED_CAPACITY {
+                watch.feed.pop_front();
+            }
+        }
+    }
+
+    /// The score `query` gives the one claim in `candidate`, if it would
+    /// retrieve it at or above the minimum score.
+    fn standing_query_score(
+        &self,
+        tenant_id: &str,
+        query: &StandingQuery,
+        candidate: &HashSet<ClaimId>,
+    ) -> Option<f32> {
+        let claim_id = candidate.iter().next()?;
+        let options = &query.options;
+        if [options.allowed_claim_ids, options.candidate_claim_ids]
+            .into_iter()
+            .flatten()
+            .any(|ids| !ids.contains(claim_id.as_str()))
+        {
+            return None;
+        }
+        let options = RetrievalOptions {
+            allowed_claim_ids: Some(candidate),
+            candidate_claim_ids: None,
+            deadline: None,
+            ..options.clone()
+        };
+        let req = RetrievalRequest::new(tenant_id, query.query.clone(), 1)
+            .with_stance_mode(query.stance_mode.clone());
+        let (hits, _) = self.retrieve_hits_with(&req, &options);
+        hits.into_iter()
+            .find(|hit| &hit.claim_id == claim_id)
+            .map(|hit| hit.score)
+            .filter(|score| *score >= query.min_score)
+    }
+
+    fn standing_query_watch(&self, tenant_id: &str, watch_id: &str) -> Option<&StandingQueryWatch> {
+        self.standing_queries.get(tenant_id)?.get(watch_id)
+    }
+}
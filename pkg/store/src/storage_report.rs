@@ -0,0 +1,241 @@
+//! Disk usage of a data directory, and warnings before it fills.
+//!
+//! [`storage_report`] walks a data directory and sorts every file by what
+//! it holds, from its name: WAL logs (`.log`, `.wal`, `.jsonl`),
+//! snapshots (`.snapshot`), index segments (`.seg` and segment
+//! manifests) and mapped vector files (`.vectors`). Bytes are attributed
+//! to a tenant where the layout says whose they are: files under
+//! `tenants/<tenant>/` as written by [`TenantedStore`](crate::TenantedStore),
+//! segments by the tenant directory holding them, and vector files by
+//! the tenant id encoded in their name. Everything else counts towards
+//! the totals only.
+//!
+//! [`StorageReport::alerts`] compares a report against
+//! [`StorageThresholds`], so a health check can warn while there is
+//! still room to checkpoint, compact or move tenants.
+
+use std::collections::BTreeMap;
+use std::fs::read_dir;
+use std::path::Path;
+
+use schema::TenantId;
+
+use crate::StoreError;
+use crate::tenanted::{TENANTS_DIR, decode_tenant_dir_name};
+
+const SEGMENT_MANIFEST_FILE: &str = "segments.manifest";
+
+/// Bytes on disk by kind of file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageUsage {
+    pub wal_bytes: u64,
+    pub snapshot_bytes: u64,
+    pub segment_bytes: u64,
+    pub vector_bytes: u64,
+    /// Files of no kind above: temporaries, manifests of other tools.
+    pub other_bytes: u64,
+}
+
+impl StorageUsage {
+    pub fn total_bytes(&self) -> u64 {
+        self.wal_bytes
+            + self.snapshot_bytes
+            + self.segment_bytes
+            + self.vector_bytes
+            + self.other_bytes
+    }
+
+    fn add(&mut self, kind: FileKind, bytes: u64) {
+        let slot = match kind {
+            FileKind::Wal => &mut self.wal_bytes,
+            FileKind::Snapshot => &mut self.snapshot_bytes,
+            FileKind::Segment => &mut self.segment_bytes,
+            FileKind::Vectors => &mut self.vector_bytes,
+            FileKind::Other => &mut self.other_bytes,
+        };
+        *slot += bytes;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageReport {
+    pub totals: StorageUsage,
+    /// Usage attributable to each tenant, by tenant id as found on disk.
+    pub tenants: BTreeMap<String, StorageUsage>,
+}
+
+/// Limits past which [`StorageReport::alerts`] warns; `None` never
+/// warns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageThresholds {
+    pub max_total_bytes: Option<u64>,
+    pub max_wal_bytes: Option<u64>,
+    pub max_tenant_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageAlertKind {
+    Total,
+    Wal,
+    Tenant,
+}
+
+impl StorageAlertKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StorageAlertKind::Total => "total",
+            StorageAlertKind::Wal => "wal",
+            StorageAlertKind::Tenant => "tenant",
+        }
+    }
+}
+
+/// A threshold the report exceeds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageAlert {
+    pub kind: StorageAlertKind,
+    /// The tenant, for [`StorageAlertKind::Tenant`].
+    pub tenant_id: Option<TenantId>,
+    pub used_bytes: u64,
+    pub threshold_bytes: u64,
+}
+
+impl StorageReport {
+    /// Every threshold exceeded, totals first, then tenants by id.
+    pub fn alerts(&self, thresholds: &StorageThresholds) -> Vec<StorageAlert> {
+        let exceeded = |kind, tenant_id: Option<&String>, used_bytes, threshold: Option<u64>| {
+            threshold
+                .filter(|threshold_bytes| used_bytes > *threshold_bytes)
+                .map(|threshold_bytes| StorageAlert {
+                    kind,
+                    tenant_id: tenant_id.map(TenantId::from),
+                    used_bytes,
+                    threshold_bytes,
+                })
+        };
+        let mut alerts: Vec<StorageAlert> = [
+            exceeded(
+                StorageAlertKind::Total,
+                None,
+                self.totals.total_bytes(),
+                thresholds.max_total_bytes,
+            ),
+            exceeded(
+                StorageAlertKind::Wal,
+                None,
+                self.totals.wal_bytes,
+                thresholds.max_wal_bytes,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        alerts.extend(self.tenants.iter().filter_map(|(tenant_id, usage)| {
+            exceeded(
+                StorageAlertKind::Tenant,
+                Some(tenant_id),
+                usage.total_bytes(),
+                thresholds.max_tenant_bytes,
+            )
+        }));
+        alerts
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Wal,
+    Snapshot,
+    Segment,
+    Vectors,
+    Other,
+}
+
+/// Summarize the disk usage under `data_dir`. A missing directory is
+/// empty.
+pub fn storage_report(data_dir: impl AsRef<Path>) -> Result<StorageReport, StoreError> {
+    let mut report = StorageReport::default();
+    let data_dir = data_dir.as_ref();
+    if data_dir.is_dir() {
+        walk(data_dir, data_dir, &mut report)?;
+    }
+    Ok(report)
+}
+
+fn walk(data_dir: &Path, dir: &Path, report: &mut StorageReport) -> Result<(), StoreError> {
+    for entry in read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            walk(data_dir, &path, report)?;
+            continue;
+        }
+        if !file_type.is_file() {
+            continue;
+        }
+        let bytes = entry.metadata()?.len();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let kind = file_kind(&name);
+        report.totals.add(kind, bytes);
+        let relative = path.strip_prefix(data_dir).unwrap_or(&path);
+        if let Some(tenant_id) = file_tenant(relative, &name, kind) {
+            report
+                .tenants
+                .entry(tenant_id)
+                .or_default()
+                .add(kind, bytes);
+        }
+    }
+    Ok(())
+}
+
+fn file_kind(name: &str) -> FileKind {
+    let extension = name.rsplit_once('.').map(|(_, extension)| extension);
+    match extension {
+        _ if name == SEGMENT_MANIFEST_FILE => FileKind::Segment,
+        Some("log" | "wal" | "jsonl") => FileKind::Wal,
+        Some("snapshot") => FileKind::Snapshot,
+        Some("seg") => FileKind::Segment,
+        Some("vectors") => FileKind::Vectors,
+        _ => FileKind::Other,
+    }
+}
+
+fn file_tenant(relative: &Path, name: &str, kind: FileKind) -> Option<String> {
+    let mut components = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy());
+    let first = components.next()?;
+    if first == TENANTS_DIR
+        && let Some(dir) = components.next()
+        && components.next().is_some()
+    {
+        return decode_tenant_dir_name(&dir);
+    }
+    match kind {
+        FileKind::Segment => relative
+            .parent()
+            .and_then(Path::file_name)
+            .map(|dir| dir.to_string_lossy().into_owned()),
+        FileKind::Vectors => vector_file_tenant(name),
+        _ => None,
+    }
+}
+
+/// The tenant of a file named as by `tenant_vector_file_name`:
+/// `tenant-<hex id>-<uuid>.vectors`.
+fn vector_file_tenant(name: &str) -> Option<String> {
+    let (hex, _uuid) = name
+        .strip_prefix("tenant-")?
+        .strip_suffix(".vectors")?
+        .rsplit_once('-')?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
@@ -0,0 +1,64 @@
+//! Claims replaced by newer claims.
+//!
+//! A [`Relation::Supersedes`] edge from one claim to another records that
+//! the first replaces the second, as when a corrected figure or a later
+//! status is ingested. The superseded claim stays stored with its
+//! evidence and edges, but every retrieve path skips it unless
+//! [`RetrievalOptions::include_superseded`](crate::RetrievalOptions::include_superseded)
+//! is set, the same way archived claims are skipped. Only edges from a
+//! claim of the same tenant count, and deleting the superseding claim
+//! brings the older one back.
+
+use schema::{ClaimId, Relation};
+
+use crate::InMemoryStore;
+
+impl InMemoryStore {
+    /// The claims of the same tenant with a supersedes edge to
+    /// `claim_id`, by id.
+    pub fn superseding_claim_ids(&self, claim_id: &ClaimId) -> Vec<ClaimId> {
+        let mut superseding: Vec<ClaimId> = self
+            .superseding_claims(claim_id)
+            .map(ClaimId::from)
+            .collect();
+        superseding.sort_unstable();
+        superseding
+    }
+
+    /// Whether a claim of the same tenant supersedes `claim_id`.
+    pub fn is_claim_superseded(&self, claim_id: &ClaimId) -> bool {
+        self.superseded(claim_id)
+    }
+
+    pub(crate) fn superseded(&self, claim_id: &str) -> bool {
+        self.incoming_edges.contains_key(claim_id)
+            && self.superseding_claims(claim_id).next().is_some()
+    }
+
+    fn superseding_claims<'a>(&'a self, claim_id: &'a str) -> impl Iterator<Item = &'a String> {
+        let tenant_id = self.claims.get(claim_id).map(|claim| &claim.tenant_id);
+        self.incoming_edges
+            .get(claim_id)
+            .into_iter()
+            .flatten()
+            .filter(move |from_claim_id| {
+                tenant_id.is_some_and(|tenant_id| {
+                    self.claims
+                        .get(from_claim_id.as_str())
+                        .is_some_and(|claim| &claim.tenant_id == tenant_id)
+                }) && self.has_supersedes_edge(from_claim_id, claim_id)
+            })
+    }
+
+    fn has_supersedes_edge(&self, from_claim_id: &str, to_claim_id: &str) -> bool {
+        self.edges_by_claim
+            .get(from_claim_id)
+            .into_iter()
+            .flatten()
+            .any(|edge| edge.relation == Relation::Supersedes && edge.to_claim_id == to_claim_id)
+    }
+
+    pub(crate) fn retain_unsuperseded(&self, candidates: &mut Vec<String>) {
+        candidates.retain(|claim_id| !self.superseded(claim_id));
+    }
+}
@@ -0,0 +1,240 @@
+//! Event-time index granularity and timeline histograms.
+//!
+//! The temporal index keeps, per tenant, the claims whose
+//! `event_time_unix` falls in each bucket. With one bucket per distinct
+//! second, high-resolution timestamps give nearly one map entry per
+//! claim; coarser buckets trade that memory for a per-claim check on the
+//! buckets a range only partly covers. The granularity is store-wide,
+//! read from `DASH_TEMPORAL_GRANULARITY` at construction and changeable
+//! with [`InMemoryStore::set_temporal_granularity`], which re-buckets the
+//! index.
+
+use std::{collections::BTreeMap, ops::Bound};
+
+use schema::{Claim, ClaimId, TenantId};
+
+use crate::{InMemoryStore, value_in_time_range};
+
+/// Environment variable selecting the temporal index granularity. Values:
+/// `second`, `minute`, `hour`, `day`, or unset (`second`).
+pub(crate) const TEMPORAL_GRANULARITY_ENV: &str = "DASH_TEMPORAL_GRANULARITY";
+
+/// Width of a temporal index or histogram bucket. Buckets start at
+/// multiples of their width since the unix epoch, so days are UTC days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum TemporalGranularity {
+    #[default]
+    Second,
+    Minute,
+    Hour,
+    Day,
+}
+
+impl TemporalGranularity {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "second" | "seconds" | "s" => Some(Self::Second),
+            "minute" | "minutes" | "m" => Some(Self::Minute),
+            "hour" | "hours" | "h" => Some(Self::Hour),
+            "day" | "days" | "d" => Some(Self::Day),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Second => "second",
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            Self::Second => 1,
+            Self::Minute => 60,
+            Self::Hour => 3_600,
+            Self::Day => 86_400,
+        }
+    }
+
+    /// Start of the bucket holding `unix`.
+    pub fn bucket_start(self, unix: i64) -> i64 {
+        unix - unix.rem_euclid(self.seconds())
+    }
+
+    pub(crate) fn from_env() -> Self {
+        std::env::var(TEMPORAL_GRANULARITY_ENV)
+            .ok()
+            .and_then(|raw| Self::parse(&raw))
+            .unwrap_or_default()
+    }
+}
+
+/// Claims whose event time falls in one bucket of
+/// [`InMemoryStore::temporal_histogram`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemporalBucket {
+    pub start_unix: i64,
+    pub claim_count: usize,
+}
+
+impl InMemoryStore {
+    pub fn temporal_granularity(&self) -> TemporalGranularity {
+        self.temporal_granularity
+    }
+
+    /// Re-bucket the temporal index at `granularity`. Returns how many
+    /// claims were re-indexed, or 0 when the granularity is unchanged.
+    pub fn set_temporal_granularity(&mut self, granularity: TemporalGranularity) -> usize {
+        if self.temporal_granularity == granularity {
+            return 0;
+        }
+        self.temporal_granularity = granularity;
+        self.temporal_index.clear();
+        let timed: Vec<(String, String, i64)> = self
+            .claims
+            .values()
+            .filter_map(|claim| {
+                claim
+                    .event_time_unix
+                    .map(|ts| (claim.tenant_id.to_string(), claim.claim_id.to_string(), ts))
+            })
+            .collect();
+        for (tenant_id, claim_id, ts) in &timed {
+            self.temporal_index
+                .entry(tenant_id.clone())
+                .or_default()
+                .entry(granularity.bucket_start(*ts))
+                .or_default()
+                .insert(claim_id.clone());
+        }
+        timed.len()
+    }
+
+    /// Claims of `tenant_id` whose event time is within the inclusive
+    /// range, sorted. Only `event_time_unix` counts here, unlike the
+    /// retrieval time filter, which also matches validity windows.
+    pub fn claim_ids_in_event_time_range(
+        &self,
+        tenant_id: &TenantId,
+        from_unix: Option<i64>,
+        to_unix: Option<i64>,
+    ) -> Vec<ClaimId> {
+        let mut out: Vec<ClaimId> = self
+            .event_time_buckets(tenant_id, from_unix, to_unix)
+            .flat_map(|(&bucket, claim_ids)| {
+                let whole = self.bucket_within(bucket, from_unix, to_unix);
+                claim_ids.iter().filter(move |claim_id| {
+                    whole
+                        || self
+                            .claims
+                            .get(claim_id.as_str())
+                            .and_then(|claim| claim.event_time_unix)
+                            .is_some_and(|ts| value_in_time_range(ts, from_unix, to_unix))
+                })
+            })
+            .map(ClaimId::from)
+            .collect();
+        out.sort_unstable();
+        out
+    }
+
+    /// Claims of `tenant_id` per `granularity` bucket of event time within
+    /// the inclusive range, in time order, leaving out empty buckets.
+    /// Index buckets wholly inside the range and nested in a histogram
+    /// bucket are counted without looking at their claims.
+    pub fn temporal_histogram(
+        &self,
+        tenant_id: &TenantId,
+        granularity: TemporalGranularity,
+        from_unix: Option<i64>,
+        to_unix: Option<i64>,
+    ) -> Vec<TemporalBucket> {
+        let nests = granularity.seconds() % self.temporal_granularity.seconds() == 0;
+        let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+        for (&bucket, claim_ids) in self.event_time_buckets(tenant_id, from_unix, to_unix) {
+            if nests && self.bucket_within(bucket, from_unix, to_unix) {
+                *counts.entry(granularity.bucket_start(bucket)).or_default() += claim_ids.len();
+                continue;
+            }
+            for ts in claim_ids
+                .iter()
+                .filter_map(|claim_id| self.claims.get(claim_id.as_str()))
+                .filter_map(|claim| claim.event_time_unix)
+                .filter(|ts| value_in_time_range(*ts, from_unix, to_unix))
+            {
+                *counts.entry(granularity.bucket_start(ts)).or_default() += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(start_unix, claim_count)| TemporalBucket {
+                start_unix,
+                claim_count,
+            })
+            .collect()
+    }
+
+    pub(crate) fn index_claim_event_time(&mut self, claim: &Claim) {
+        let Some(ts) = claim.event_time_unix else {
+            return;
+        };
+        self.temporal_index
+            .entry(claim.tenant_id.to_string())
+            .or_default()
+            .entry(self.temporal_granularity.bucket_start(ts))
+            .or_default()
+            .insert(claim.claim_id.to_string());
+    }
+
+    pub(crate) fn unindex_claim_event_time(&mut self, claim: &Claim) {
+        let Some(ts) = claim.event_time_unix else {
+            return;
+        };
+        let bucket = self.temporal_granularity.bucket_start(ts);
+        let Some(timeline) = self.temporal_index.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        if let Some(claim_ids) = timeline.get_mut(&bucket) {
+            claim_ids.remove(claim.claim_id.as_str());
+            if claim_ids.is_empty() {
+                timeline.remove(&bucket);
+            }
+        }
+        if timeline.is_empty() {
+            self.temporal_index.remove(claim.tenant_id.as_str());
+        }
+    }
+
+    fn event_time_buckets(
+        &self,
+        tenant_id: &str,
+        from_unix: Option<i64>,
+        to_unix: Option<i64>,
+    ) -> impl Iterator<Item = (&i64, &std::collections::HashSet<String>)> {
+        let granularity = self.temporal_granularity;
+        let lower = from_unix.map_or(Bound::Unbounded, |from| {
+            Bound::Included(granularity.bucket_start(from))
+        });
+        let upper = to_unix.map_or(Bound::Unbounded, |to| {
+            Bound::Included(granularity.bucket_start(to))
+        });
+        let empty_range = matches!(
+            (from_unix, to_unix),
+            (Some(from), Some(to)) if from > to
+        );
+        self.temporal_index
+            .get(tenant_id)
+            .filter(|_| !empty_range)
+            .into_iter()
+            .flat_map(move |timeline| timeline.range((lower, upper)))
+    }
+
+    /// Whether the whole index bucket starting at `bucket` is in range.
+    fn bucket_within(&self, bucket: i64, from_unix: Option<i64>, to_unix: Option<i64>) -> bool {
+        let last = bucket.saturating_add(self.temporal_granularity.seconds() - 1);
+        from_unix.is_none_or(|from| bucket >= from) && to_unix.is_none_or(|to| last <= to)
+    }
+}
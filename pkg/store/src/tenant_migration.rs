@@ -0,0 +1,213 @@
+//! Rewriting tenant ids in a WAL directory.
+//!
+//! Used after an org rename or a consolidation, once the services have
+//! been resolving the old ids through tenant aliases for a while: the
+//! migration moves the stored data under the new ids so the aliases can
+//! eventually be dropped. The WAL is replayed, every claim and per-tenant
+//! vector setting owned by a renamed tenant is moved to its new id, and
+//! the result is written back as a fresh snapshot with an empty WAL.
+//!
+//! Claim, evidence, and edge ids are global, so merging one tenant into
+//! another cannot collide. Document and source ids are per tenant: a
+//! merge fails if two of the merged tenants hold different documents,
+//! chunks, or sources under one id. Per-tenant ANN graphs are not carried over for
+//! the tenants involved; they are rebuilt from the claim vectors when the
+//! snapshot is loaded. Segment directories are not touched here: the
+//! ingestion startup reconcile in repair mode rebuilds them from the
+//! migrated store.
+
+use std::collections::{BTreeMap, HashMap};
+
+use schema::TenantId;
+
+use crate::wal::PersistedRecord;
+use crate::{FileWal, InMemoryStore, StoreError};
+
+/// What a tenant id migration rewrote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TenantMigrationStats {
+    pub claims_rewritten: usize,
+    pub tenant_settings_rewritten: usize,
+    /// Documents, chunks, and sources moved to a new tenant id.
+    pub documents_rewritten: usize,
+    pub ann_graphs_dropped: usize,
+    pub snapshot_records: usize,
+}
+
+impl InMemoryStore {
+    /// Move every claim of each `old` tenant in `renames` to its `new`
+    /// tenant and compact `wal` to the result. `renames` must already be
+    /// resolved: a `new` id is never itself renamed. Nothing is written
+    /// when the migrated records fail to load, or when a merged tenant
+    /// would end up with two different vector configs, projections, text
+    /// analyzers, documents, or sources under one id.
+    pub fn migrate_tenant_ids(
+        wal: &mut FileWal,
+        renames: &BTreeMap<String, String>,
+    ) -> Result<(Self, TenantMigrationStats), StoreError> {
+        if let Some((old, new)) = renames.iter().find(|(_, new)| renames.contains_key(*new)) {
+            return Err(StoreError::Conflict(format!(
+                "tenant rename '{old}' -> '{new}' targets a tenant that is itself renamed"
+            )));
+        }
+        let source = Self::load_from_wal(wal)?;
+        let renamed = |tenant_id: &mut String| match renames.get(tenant_id.as_str()) {
+            Some(new) => {
+                *tenant_id = new.clone();
+                true
+            }
+            None => false,
+        };
+        let renamed_id = |tenant_id: &mut TenantId| match renames.get(tenant_id.as_str()) {
+            Some(new) => {
+                *tenant_id = new.into();
+                true
+            }
+            None => false,
+        };
+
+        let mut stats = TenantMigrationStats::default();
+        let mut records = Vec::new();
+        let mut configs = HashMap::new();
+        let mut projections = HashMap::new();
+        let mut analyzers = HashMap::new();
+        let mut documents = HashMap::new();
+        let mut chunks = HashMap::new();
+        let mut sources = HashMap::new();
+        for mut record in source.snapshot_records() {
+            match &mut record {
+                PersistedRecord::Claim(claim) => {
+                    if let Some(new) = renames.get(claim.tenant_id.as_str()) {
+                        claim.tenant_id = new.into();
+                        stats.claims_rewritten += 1;
+                    }
+                }
+                PersistedRecord::TenantVectorConfig(record) => {
+                    if renamed(&mut record.tenant_id) {
+                        stats.tenant_settings_rewritten += 1;
+                    }
+                    match configs.get(&record.tenant_id) {
+                        Some(existing) if *existing == record.config => continue,
+                        Some(_) => {
+                            return Err(StoreError::Conflict(format!(
+                                "merged tenant '{}' has conflicting vector configs",
+                                record.tenant_id
+                            )));
+                        }
+                        None => {
+                            configs.insert(record.tenant_id.clone(), record.config.clone());
+                        }
+                    }
+                }
+                PersistedRecord::VectorProjection(record) => {
+                    if renamed(&mut record.tenant_id) {
+                        stats.tenant_settings_rewritten += 1;
+                    }
+                    match projections.get(&record.tenant_id) {
+                        Some(existing) if *existing == record.projection => continue,
+                        Some(_) => {
+                            return Err(StoreError::Conflict(format!(
+                                "merged tenant '{}' has conflicting vector projections",
+                                record.tenant_id
+                            )));
+                        }
+                        None => {
+                            projections.insert(record.tenant_id.clone(), record.projection.clone());
+                        }
+                    }
+                }
+                PersistedRecord::TextAnalyzer(record) => {
+                    if renamed(&mut record.tenant_id) {
+                        stats.tenant_settings_rewritten += 1;
+                    }
+                    match analyzers.get(&record.tenant_id) {
+                        Some(existing) if *existing == record.analyzer => continue,
+                        Some(_) => {
+                            return Err(StoreError::Conflict(format!(
+                                "merged tenant '{}' has conflicting text analyzers",
+                                record.tenant_id
+                            )));
+                        }
+                        None => {
+                            analyzers.insert(record.tenant_id.clone(), record.analyzer);
+                        }
+                    }
+                }
+                PersistedRecord::Document(document) => {
+                    stats.documents_rewritten += usize::from(renamed_id(&mut document.tenant_id));
+                    let key = (document.tenant_id.clone(), document.doc_id.clone());
+                    match documents.get(&key) {
+                        Some(existing) if existing == document => continue,
+                        Some(_) => {
+                            return Err(StoreError::Conflict(format!(
+                                "merged tenant '{}' has conflicting documents '{}'",
+                                key.0, key.1
+                            )));
+                        }
+                        None => {
+                            documents.insert(key, document.clone());
+                        }
+                    }
+                }
+                PersistedRecord::Chunk(chunk) => {
+                    stats.documents_rewritten += usize::from(renamed_id(&mut chunk.tenant_id));
+                    let key = (
+                        chunk.tenant_id.clone(),
+                        chunk.doc_id.clone(),
+                        chunk.chunk_id.clone(),
+                    );
+                    match chunks.get(&key) {
+                        Some(existing) if existing == chunk => continue,
+                        Some(_) => {
+                            return Err(StoreError::Conflict(format!(
+                                "merged tenant '{}' has conflicting chunks '{}' of document '{}'",
+                                key.0, key.2, key.1
+                            )));
+                        }
+                        None => {
+                            chunks.insert(key, chunk.clone());
+                        }
+                    }
+                }
+                PersistedRecord::Source(source) => {
+                    stats.documents_rewritten += usize::from(renamed_id(&mut source.tenant_id));
+                    let key = (source.tenant_id.clone(), source.source_id.clone());
+                    match sources.get(&key) {
+                        Some(existing) if existing == source => continue,
+                        Some(_) => {
+                            return Err(StoreError::Conflict(format!(
+                                "merged tenant '{}' has conflicting sources '{}'",
+                                key.0, key.1
+                            )));
+                        }
+                        None => {
+                            sources.insert(key, source.clone());
+                        }
+                    }
+                }
+                PersistedRecord::AnnGraphHeader(header)
+                    if involved_in(renames, &header.tenant_id) =>
+                {
+                    stats.ann_graphs_dropped += 1;
+                    continue;
+                }
+                PersistedRecord::AnnGraphNode(node) if involved_in(renames, &node.tenant_id) => {
+                    continue;
+                }
+                _ => {}
+            }
+            records.push(record);
+        }
+
+        let mut migrated = Self::new();
+        for record in &records {
+            migrated.apply_persisted_record(record.clone())?;
+        }
+        stats.snapshot_records = wal.compact_with_snapshot(&records)?.snapshot_records;
+        Ok((migrated, stats))
+    }
+}
+
+fn involved_in(renames: &BTreeMap<String, String>, tenant_id: &str) -> bool {
+    renames.contains_key(tenant_id) || renames.values().any(|new| new == tenant_id)
+}
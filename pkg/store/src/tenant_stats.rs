@@ -0,0 +1,180 @@
+//! Per-tenant usage for monitoring and billing.
+//!
+//! [`InMemoryStore::index_stats`] and the metrics registry describe the
+//! store as a whole. [`InMemoryStore::tenant_stats`] breaks usage down for
+//! one tenant: record counts, when it last ingested, and an estimate of
+//! the heap its index entries and vectors take. Estimates count payload
+//! bytes, the keys and claim ids held in each index, not allocator or
+//! hash table overhead, so they are for comparing tenants rather than
+//! sizing hosts.
+//!
+//! A tenant's share of the WAL and snapshot is read from the files, so it
+//! is a separate call, [`InMemoryStore::tenant_wal_usage`], which scans
+//! them once for every tenant. Records are attributed to the tenant
+//! owning their claim; evidence and vectors of a claim that is neither in
+//! the store nor earlier in the log are left out.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use schema::TenantId;
+
+use crate::wal::{PersistedRecord, line_to_record};
+use crate::{FileWal, InMemoryStore, StoreError};
+
+/// Approximate heap bytes of one tenant's index entries and vectors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantIndexMemory {
+    pub inverted_index_bytes: usize,
+    pub entity_index_bytes: usize,
+    pub temporal_index_bytes: usize,
+    /// Default claim vectors held in memory; mapped files are not
+    /// counted.
+    pub vector_bytes: usize,
+}
+
+impl TenantIndexMemory {
+    pub fn total_bytes(&self) -> usize {
+        self.inverted_index_bytes
+            + self.entity_index_bytes
+            + self.temporal_index_bytes
+            + self.vector_bytes
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantStats {
+    pub tenant_id: TenantId,
+    pub claims: usize,
+    pub evidence: usize,
+    pub edges: usize,
+    /// Claims with a default vector.
+    pub vectors: usize,
+    /// Wall-clock time of the tenant's last ingested bundle in this
+    /// process; `None` when it has only been loaded or replayed.
+    pub last_ingest_unix_ms: Option<u64>,
+    pub memory: TenantIndexMemory,
+}
+
+/// Records and bytes a tenant has in the snapshot and the WAL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantWalUsage {
+    pub snapshot_records: usize,
+    pub snapshot_bytes: u64,
+    pub wal_records: usize,
+    pub wal_bytes: u64,
+}
+
+impl InMemoryStore {
+    pub fn tenant_stats(&self, tenant_id: &TenantId) -> TenantStats {
+        let claim_ids = self.tenant_claim_ids.get(tenant_id.as_str());
+        let claim_ids = claim_ids.into_iter().flatten();
+        let mut stats = TenantStats {
+            tenant_id: tenant_id.into(),
+            last_ingest_unix_ms: self
+                .tenant_last_ingest_unix_ms
+                .get(tenant_id.as_str())
+                .copied(),
+            ..TenantStats::default()
+        };
+        for claim_id in claim_ids {
+            stats.claims += 1;
+            stats.evidence += self.evidence_by_claim.get(claim_id).map_or(0, Vec::len);
+            stats.edges += self.edges_by_claim.get(claim_id).map_or(0, Vec::len);
+            if self.claim_vectors.contains_key(claim_id) {
+                stats.vectors += 1;
+                stats.memory.vector_bytes += self.claim_vectors.claim_storage_bytes(claim_id);
+            }
+        }
+        stats.memory.inverted_index_bytes = self
+            .inverted_index
+            .get(tenant_id.as_str())
+            .map_or(0, |index| index.estimated_bytes());
+        stats.memory.entity_index_bytes = self
+            .entity_index
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .map(|(entity, claim_ids)| {
+                entity.len() + claim_ids.iter().map(String::len).sum::<usize>()
+            })
+            .sum();
+        stats.memory.temporal_index_bytes = self
+            .temporal_index
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .map(|(_, claim_ids)| {
+                std::mem::size_of::<i64>() + claim_ids.iter().map(String::len).sum::<usize>()
+            })
+            .sum();
+        stats
+    }
+
+    /// Every tenant's share of `wal`'s snapshot and log, by tenant id.
+    /// Bytes count each record's line and its newline.
+    pub fn tenant_wal_usage(
+        &self,
+        wal: &FileWal,
+    ) -> Result<BTreeMap<TenantId, TenantWalUsage>, StoreError> {
+        let mut usage: BTreeMap<TenantId, TenantWalUsage> = BTreeMap::new();
+        let mut logged_claims: HashMap<String, String> = HashMap::new();
+        wal.visit_lines(|line, in_snapshot| {
+            let record = line_to_record(line)?;
+            let claim_tenant = |claim_id: &str| {
+                logged_claims.get(claim_id).cloned().or_else(|| {
+                    self.claims
+                        .get(claim_id)
+                        .map(|claim| claim.tenant_id.to_string())
+                })
+            };
+            let tenant_id = match &record {
+                PersistedRecord::Claim(claim) => {
+                    logged_claims.insert(claim.claim_id.to_string(), claim.tenant_id.to_string());
+                    Some(claim.tenant_id.to_string())
+                }
+                PersistedRecord::Evidence(evidence) => claim_tenant(&evidence.claim_id),
+                PersistedRecord::Edge(edge) => claim_tenant(&edge.from_claim_id),
+                PersistedRecord::ClaimVector(vector) => claim_tenant(&vector.claim_id),
+                PersistedRecord::SparseVector(vector) => claim_tenant(&vector.claim_id),
+                PersistedRecord::BatchCommit(commit) => commit
+                    .claim_ids
+                    .first()
+                    .and_then(|claim_id| claim_tenant(claim_id)),
+                PersistedRecord::TenantVectorConfig(config) => Some(config.tenant_id.clone()),
+                PersistedRecord::AnnGraphHeader(header) => Some(header.tenant_id.clone()),
+                PersistedRecord::AnnGraphNode(node) => Some(node.tenant_id.clone()),
+                PersistedRecord::VectorProjection(projection) => Some(projection.tenant_id.clone()),
+                PersistedRecord::TextAnalyzer(analyzer) => Some(analyzer.tenant_id.clone()),
+                PersistedRecord::ClaimDelete(delete) => Some(delete.tenant_id.clone()),
+                PersistedRecord::ClaimMerge(merge) => Some(merge.tenant_id.clone()),
+                PersistedRecord::ClaimArchive(archive) => Some(archive.tenant_id.clone()),
+                PersistedRecord::Document(document) => Some(document.tenant_id.to_string()),
+                PersistedRecord::Chunk(chunk) => Some(chunk.tenant_id.to_string()),
+                PersistedRecord::Source(source) => Some(source.tenant_id.to_string()),
+                PersistedRecord::Checkpoint(_) => None,
+            };
+            if let Some(tenant_id) = tenant_id {
+                let entry = usage.entry(TenantId::from(tenant_id)).or_default();
+                let bytes = line.len() as u64 + 1;
+                if in_snapshot {
+                    entry.snapshot_records += 1;
+                    entry.snapshot_bytes += bytes;
+                } else {
+                    entry.wal_records += 1;
+                    entry.wal_bytes += bytes;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(usage)
+    }
+
+    pub(crate) fn note_tenant_ingest(&mut self, tenant_id: &str) {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        self.tenant_last_ingest_unix_ms
+            .insert(tenant_id.to_string(), now_ms);
+    }
+}
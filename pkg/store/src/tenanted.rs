@@ -0,0 +1,509 @@
+//! Hard tenant isolation: one [`InMemoryStore`] + [`FileWal`] per tenant.
+//!
+//! The default deployment keeps every tenant in a single store and a
+//! single WAL. That is cheap, but it couples tenants together: one
+//! corrupt record blocks replay for everyone, a checkpoint rewrites the
+//! whole snapshot, and there is no natural place to enforce a per-tenant
+//! size budget. [`TenantedStore`] is a façade that owns one store/WAL
+//! pair per tenant under a shared data directory and routes the familiar
+//! ingest and retrieval calls by `tenant_id`.
+//!
+//! On-disk layout:
+//!
+//! ```text
+//! <data_dir>/tenants/<encoded-tenant-id>/wal.log
+//! <data_dir>/tenants/<encoded-tenant-id>/wal.log.snapshot
+//! ```
+//!
+//! Tenant ids are percent-encoded into directory names so arbitrary ids
+//! cannot escape the data directory. A tenant whose WAL fails to replay
+//! is recorded in [`TenantedStore::failed_tenants`] and rejected on
+//! access; every other tenant keeps serving.
+//!
+//! Tenants are loaded lazily: `open` only discovers tenant directories,
+//! and a tenant's snapshot + WAL are replayed on first access. Resident
+//! tenants can be unloaded explicitly, after an idle period, or
+//! automatically when the residency budget in [`TenantedStoreConfig`]
+//! is exceeded. Unloading always flushes and checkpoints first, so the
+//! next load replays a compact snapshot.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::{create_dir_all, read_dir};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use schema::{Claim, ClaimEdge, ClaimId, Evidence, RetrievalRequest, RetrievalResult, TenantId};
+
+use crate::{
+    AnnTuningConfig, CheckpointPolicy, ClaimHydration, EvictionPolicy, EvictionReport, FileWal,
+    InMemoryStore, RetrievalHit, SourceSummary, StoreError, TenantVectorConfig, WalCheckpointStats,
+    WalWritePolicy,
+};
+
+pub(crate) const TENANTS_DIR: &str = "tenants";
+const TENANT_WAL_FILE: &str = "wal.log";
+
+/// Per-tenant knobs applied uniformly to every tenant in the façade.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenantedStoreConfig {
+    pub data_dir: PathBuf,
+    pub wal_policy: WalWritePolicy,
+    pub checkpoint_policy: CheckpointPolicy,
+    pub ann_tuning: AnnTuningConfig,
+    /// Upper bound on claims per tenant. New claim ids beyond the
+    /// budget are rejected with `StoreError::QuotaExceeded`; updates to
+    /// existing claims are always accepted.
+    pub max_claims_per_tenant: Option<usize>,
+    /// Maximum number of tenants kept in memory at once. The least
+    /// recently used tenants are unloaded when a load exceeds it.
+    pub max_resident_tenants: Option<usize>,
+    /// Maximum number of claims kept in memory across all resident
+    /// tenants — a cheap proxy for the façade's memory footprint.
+    pub max_resident_claims: Option<usize>,
+}
+
+impl TenantedStoreConfig {
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            wal_policy: WalWritePolicy::default(),
+            checkpoint_policy: CheckpointPolicy::default(),
+            ann_tuning: AnnTuningConfig::default(),
+            max_claims_per_tenant: None,
+            max_resident_tenants: None,
+            max_resident_claims: None,
+        }
+    }
+}
+
+pub(crate) struct TenantSlot {
+    pub(crate) store: InMemoryStore,
+    pub(crate) wal: FileWal,
+    last_access: Instant,
+}
+
+/// Façade over one isolated store + WAL per tenant.
+pub struct TenantedStore {
+    config: TenantedStoreConfig,
+    known_tenants: BTreeSet<TenantId>,
+    tenants: BTreeMap<TenantId, TenantSlot>,
+    failed_tenants: BTreeMap<TenantId, Arc<StoreError>>,
+}
+
+impl TenantedStore {
+    /// Discover every tenant under `config.data_dir`. No tenant is
+    /// replayed until it is first accessed.
+    pub fn open(config: TenantedStoreConfig) -> Result<Self, StoreError> {
+        let root = config.data_dir.join(TENANTS_DIR);
+        create_dir_all(&root)?;
+        let mut known_tenants = BTreeSet::new();
+        for entry in read_dir(&root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            if let Some(tenant_id) = entry.file_name().to_str().and_then(decode_tenant_dir_name) {
+                known_tenants.insert(TenantId::from(tenant_id));
+            }
+        }
+        Ok(Self {
+            config,
+            known_tenants,
+            tenants: BTreeMap::new(),
+            failed_tenants: BTreeMap::new(),
+        })
+    }
+
+    pub fn config(&self) -> &TenantedStoreConfig {
+        &self.config
+    }
+
+    /// Directory holding the WAL and snapshot for `tenant_id`.
+    pub fn tenant_dir(&self, tenant_id: &TenantId) -> PathBuf {
+        tenant_dir(&self.config.data_dir, tenant_id)
+    }
+
+    /// Every tenant known to the façade, resident or not, sorted.
+    pub fn tenant_ids(&self) -> Vec<TenantId> {
+        self.known_tenants.iter().cloned().collect()
+    }
+
+    /// Tenants currently loaded in memory, sorted.
+    pub fn resident_tenant_ids(&self) -> Vec<TenantId> {
+        self.tenants.keys().cloned().collect()
+    }
+
+    /// Claims held in memory across all resident tenants.
+    pub fn resident_claim_count(&self) -> usize {
+        self.tenants
+            .values()
+            .map(|slot| slot.store.claims_len())
+            .sum()
+    }
+
+    /// Tenants whose replay failed, with the error. A failed tenant
+    /// stays failed until [`Self::clear_failed_tenant`] is called.
+    pub fn failed_tenants(&self) -> &BTreeMap<TenantId, Arc<StoreError>> {
+        &self.failed_tenants
+    }
+
+    /// Forget a recorded replay failure so the next access retries.
+    pub fn clear_failed_tenant(&mut self, tenant_id: &TenantId) -> bool {
+        self.failed_tenants.remove(tenant_id.as_str()).is_some()
+    }
+
+    /// Read-only access to a resident tenant's store, for callers that
+    /// need the full single-store API. Use [`Self::load_tenant`] to make
+    /// a tenant resident first.
+    pub fn tenant_store(&self, tenant_id: &TenantId) -> Option<&InMemoryStore> {
+        self.tenants.get(tenant_id.as_str()).map(|slot| &slot.store)
+    }
+
+    /// Make a known tenant resident and return its store. Unknown
+    /// tenants are not created.
+    pub fn load_tenant(&mut self, tenant_id: &TenantId) -> Result<&InMemoryStore, StoreError> {
+        if !self.known_tenants.contains(tenant_id.as_str()) {
+            return Err(StoreError::UnknownTenant(tenant_id.to_string()));
+        }
+        Ok(&self.tenant_slot_mut(tenant_id)?.store)
+    }
+
+    pub fn ingest_bundle(
+        &mut self,
+        claim: Claim,
+        evidence: Vec<Evidence>,
+        edges: Vec<ClaimEdge>,
+    ) -> Result<Option<WalCheckpointStats>, StoreError> {
+        let max_claims = self.config.max_claims_per_tenant;
+        let policy = self.config.checkpoint_policy.clone();
+        let slot = self.tenant_slot_mut(&claim.tenant_id)?;
+        if let Some(max_claims) = max_claims
+            && slot.store.claim_by_id(&claim.claim_id).is_none()
+            && slot.store.claims_len() >= max_claims
+        {
+            return Err(StoreError::QuotaExceeded(format!(
+                "tenant '{}' is at its claim budget ({max_claims})",
+                claim.tenant_id
+            )));
+        }
+        slot.store.ingest_bundle_persistent_with_policy(
+            &mut slot.wal,
+            &policy,
+            claim,
+            evidence,
+            edges,
+        )
+    }
+
+    /// Claims of `tenant_id` to evict before it reaches
+    /// `max_claims_per_tenant`, under the default [`EvictionPolicy`];
+    /// `None` without a budget.
+    pub fn eviction_report(
+        &mut self,
+        tenant_id: &TenantId,
+    ) -> Result<Option<EvictionReport>, StoreError> {
+        let Some(max_claims) = self.config.max_claims_per_tenant else {
+            return Ok(None);
+        };
+        let store = self.load_tenant(tenant_id)?;
+        Ok(Some(store.eviction_report(
+            tenant_id,
+            &EvictionPolicy::new(max_claims),
+        )))
+    }
+
+    /// Delete `claim_ids` from `tenant_id`, recording tombstones in its
+    /// WAL. Returns how many were deleted.
+    pub fn delete_claims(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_ids: &[ClaimId],
+    ) -> Result<usize, StoreError> {
+        if !self.known_tenants.contains(tenant_id.as_str()) {
+            return Err(StoreError::UnknownTenant(tenant_id.to_string()));
+        }
+        let slot = self.tenant_slot_mut(tenant_id)?;
+        slot.store
+            .delete_claims_persistent(&mut slot.wal, tenant_id, claim_ids)
+    }
+
+    /// Register a tenant's vector config in its own WAL, creating the
+    /// tenant if it does not exist yet.
+    pub fn register_vector_config(
+        &mut self,
+        tenant_id: &TenantId,
+        config: TenantVectorConfig,
+    ) -> Result<(), StoreError> {
+        let slot = self.tenant_slot_mut(tenant_id)?;
+        slot.store
+            .register_tenant_vector_config_persistent(&mut slot.wal, tenant_id, config)
+    }
+
+    pub fn upsert_claim_vector(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+        vector: Vec<f32>,
+    ) -> Result<(), StoreError> {
+        let slot = self.tenant_slot_mut(tenant_id)?;
+        slot.store
+            .upsert_claim_vector_persistent(&mut slot.wal, claim_id, vector)
+    }
+
+    pub fn retrieve(&mut self, req: &RetrievalRequest) -> Vec<RetrievalResult> {
+        self.load_tenant(&req.tenant_id)
+            .map(|store| store.retrieve(req))
+            .unwrap_or_default()
+    }
+
+    pub fn retrieve_semantic(
+        &mut self,
+        req: &RetrievalRequest,
+        query_vector: &[f32],
+    ) -> Vec<RetrievalResult> {
+        self.load_tenant(&req.tenant_id)
+            .map(|store| store.retrieve_semantic(req, query_vector))
+            .unwrap_or_default()
+    }
+
+    pub fn retrieve_with_time_range_and_query_vector(
+        &mut self,
+        req: &RetrievalRequest,
+        from_unix: Option<i64>,
+        to_unix: Option<i64>,
+        query_vector: Option<&[f32]>,
+    ) -> Vec<RetrievalResult> {
+        self.load_tenant(&req.tenant_id)
+            .map(|store| {
+                store.retrieve_with_time_range_and_query_vector(
+                    req,
+                    from_unix,
+                    to_unix,
+                    query_vector,
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn retrieve_hits(
+        &mut self,
+        req: &RetrievalRequest,
+        from_unix: Option<i64>,
+        to_unix: Option<i64>,
+        query_vector: Option<&[f32]>,
+    ) -> Vec<RetrievalHit> {
+        self.load_tenant(&req.tenant_id)
+            .map(|store| store.retrieve_hits(req, from_unix, to_unix, query_vector))
+            .unwrap_or_default()
+    }
+
+    pub fn get_claims(&mut self, tenant_id: &TenantId, claim_ids: &[ClaimId]) -> ClaimHydration {
+        match self.load_tenant(tenant_id) {
+            Ok(store) => store.get_claims(tenant_id, claim_ids),
+            Err(_) => {
+                let mut missing_claim_ids = claim_ids.to_vec();
+                let mut seen = HashSet::new();
+                missing_claim_ids.retain(|claim_id| seen.insert(claim_id.clone()));
+                ClaimHydration {
+                    claims: Vec::new(),
+                    missing_claim_ids,
+                }
+            }
+        }
+    }
+
+    pub fn sources_for_tenant(&mut self, tenant_id: &TenantId) -> Vec<SourceSummary> {
+        self.load_tenant(tenant_id)
+            .map(|store| store.sources_for_tenant(tenant_id))
+            .unwrap_or_default()
+    }
+
+    pub fn claim_by_id(&mut self, tenant_id: &TenantId, claim_id: &ClaimId) -> Option<Claim> {
+        self.load_tenant(tenant_id)
+            .ok()?
+            .claim_by_id(claim_id)
+            .cloned()
+    }
+
+    /// Checkpoint a single tenant's WAL into its snapshot, loading the
+    /// tenant if needed.
+    pub fn checkpoint_tenant(
+        &mut self,
+        tenant_id: &TenantId,
+    ) -> Result<WalCheckpointStats, StoreError> {
+        if !self.known_tenants.contains(tenant_id.as_str()) {
+            return Err(StoreError::UnknownTenant(tenant_id.to_string()));
+        }
+        let slot = self.tenant_slot_mut(tenant_id)?;
+        slot.store.checkpoint_and_compact(&mut slot.wal)
+    }
+
+    /// Checkpoint every resident tenant. A failure for one tenant does
+    /// not stop the others; per-tenant results are returned.
+    pub fn checkpoint_all(&mut self) -> BTreeMap<TenantId, Result<WalCheckpointStats, StoreError>> {
+        self.tenants
+            .iter_mut()
+            .map(|(tenant_id, slot)| {
+                (
+                    tenant_id.clone(),
+                    slot.store.checkpoint_and_compact(&mut slot.wal),
+                )
+            })
+            .collect()
+    }
+
+    /// Flush buffered WAL records for every resident tenant.
+    pub fn flush_all(&mut self) -> Result<(), StoreError> {
+        for slot in self.tenants.values_mut() {
+            slot.wal.flush_pending_sync()?;
+        }
+        Ok(())
+    }
+
+    /// Flush, checkpoint, and drop a resident tenant from memory.
+    /// Returns `false` if the tenant was not resident. On checkpoint
+    /// failure the tenant stays resident.
+    pub fn unload_tenant(&mut self, tenant_id: &TenantId) -> Result<bool, StoreError> {
+        let Some(slot) = self.tenants.get_mut(tenant_id.as_str()) else {
+            return Ok(false);
+        };
+        slot.wal.flush_pending_sync()?;
+        slot.store.checkpoint_and_compact(&mut slot.wal)?;
+        self.tenants.remove(tenant_id.as_str());
+        Ok(true)
+    }
+
+    /// Unload every tenant that has not been accessed for `idle_for`.
+    /// Returns the unloaded tenant ids.
+    pub fn unload_idle(&mut self, idle_for: Duration) -> Result<Vec<TenantId>, StoreError> {
+        let idle: Vec<TenantId> = self
+            .tenants
+            .iter()
+            .filter(|(_, slot)| slot.last_access.elapsed() >= idle_for)
+            .map(|(tenant_id, _)| tenant_id.clone())
+            .collect();
+        for tenant_id in &idle {
+            self.unload_tenant(tenant_id)?;
+        }
+        Ok(idle)
+    }
+
+    fn tenant_slot_mut(&mut self, tenant_id: &TenantId) -> Result<&mut TenantSlot, StoreError> {
+        if tenant_id.trim().is_empty() {
+            return Err(StoreError::Validation(
+                schema::ValidationError::MissingField("tenant_id"),
+            ));
+        }
+        if let Some(err) = self.failed_tenants.get(tenant_id) {
+            return Err(tenant_load_failure(tenant_id, err));
+        }
+        if !self.tenants.contains_key(tenant_id) {
+            let slot = match self.open_tenant_slot(tenant_id) {
+                Ok(slot) => slot,
+                Err(err) => {
+                    let err = Arc::new(err);
+                    self.failed_tenants
+                        .insert(tenant_id.clone(), Arc::clone(&err));
+                    return Err(tenant_load_failure(tenant_id, &err));
+                }
+            };
+            self.known_tenants.insert(tenant_id.clone());
+            self.tenants.insert(tenant_id.clone(), slot);
+            self.enforce_residency_budget(tenant_id)?;
+        }
+        let slot = self
+            .tenants
+            .get_mut(tenant_id)
+            .expect("tenant slot was just inserted");
+        slot.last_access = Instant::now();
+        Ok(slot)
+    }
+
+    /// Unload least-recently-used tenants (never `keep`) until the
+    /// residency budget is met.
+    fn enforce_residency_budget(&mut self, keep: &TenantId) -> Result<(), StoreError> {
+        loop {
+            let over_tenants = self
+                .config
+                .max_resident_tenants
+                .is_some_and(|max| self.tenants.len() > max.max(1));
+            let over_claims = self
+                .config
+                .max_resident_claims
+                .is_some_and(|max| self.resident_claim_count() > max);
+            if !over_tenants && !over_claims {
+                return Ok(());
+            }
+            let Some(victim) = self
+                .tenants
+                .iter()
+                .filter(|(tenant_id, _)| *tenant_id != keep)
+                .min_by_key(|(_, slot)| slot.last_access)
+                .map(|(tenant_id, _)| tenant_id.clone())
+            else {
+                return Ok(());
+            };
+            self.unload_tenant(&victim)?;
+        }
+    }
+
+    pub(crate) fn open_tenant_slot(&self, tenant_id: &str) -> Result<TenantSlot, StoreError> {
+        let wal_path = tenant_dir(&self.config.data_dir, tenant_id).join(TENANT_WAL_FILE);
+        let wal = FileWal::open_with_policy(wal_path, self.config.wal_policy.clone())?;
+        let store =
+            InMemoryStore::load_from_wal_with_ann_tuning(&wal, self.config.ann_tuning.clone())?;
+        Ok(TenantSlot {
+            store,
+            wal,
+            last_access: Instant::now(),
+        })
+    }
+}
+
+/// The error returned for a tenant whose slot failed to load, with the
+/// original error, also kept in [`TenantedStore::failed_tenants`], as
+/// its source.
+fn tenant_load_failure(tenant_id: &str, err: &Arc<StoreError>) -> StoreError {
+    StoreError::TenantLoad {
+        tenant_id: tenant_id.to_string(),
+        source: Arc::clone(err),
+    }
+}
+
+pub(crate) fn tenant_dir(data_dir: &Path, tenant_id: &str) -> PathBuf {
+    data_dir
+        .join(TENANTS_DIR)
+        .join(encode_tenant_dir_name(tenant_id))
+}
+
+/// Percent-encode every byte outside `[A-Za-z0-9_-]` so a tenant id
+/// maps to exactly one safe directory name.
+fn encode_tenant_dir_name(tenant_id: &str) -> String {
+    let mut out = String::with_capacity(tenant_id.len());
+    for byte in tenant_id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+pub(crate) fn decode_tenant_dir_name(name: &str) -> Option<String> {
+    let bytes = name.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = name.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
@@ -0,0 +1,129 @@
+//! Corpus statistics shared between shards.
+//!
+//! BM25 weighs a term by how rare it is in the corpus, and each shard
+//! only sees its own slice of it: a term that is rare on one shard and
+//! common everywhere else gets an inflated weight there, and the shards'
+//! scores drift apart. The exchange runs in two steps. Each shard exports
+//! its [`TermStatistics`] for a tenant, either for the terms of one query
+//! or for its whole vocabulary; the coordinator merges them and
+//! distributes the result back with
+//! [`InMemoryStore::set_global_term_statistics`]. From then on the shard
+//! computes IDF and length normalization over the logical corpus. Global
+//! statistics are runtime state and are not written to the WAL.
+
+use std::collections::HashMap;
+
+use schema::TenantId;
+
+use crate::InMemoryStore;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TermStatistics {
+    /// Indexed claims.
+    pub doc_count: u64,
+    /// Tokens across every indexed claim.
+    pub total_doc_len: u64,
+    /// Claims containing each term, as analyzed for the tenant.
+    pub doc_freq: HashMap<String, u64>,
+}
+
+impl TermStatistics {
+    /// Add another shard's statistics to these. Shards are assumed to
+    /// hold disjoint claims.
+    pub fn merge(&mut self, other: &TermStatistics) {
+        self.doc_count += other.doc_count;
+        self.total_doc_len += other.total_doc_len;
+        for (term, freq) in &other.doc_freq {
+            *self.doc_freq.entry(term.clone()).or_insert(0) += freq;
+        }
+    }
+
+    /// The statistics of every shard combined.
+    pub fn merged<'a>(shards: impl IntoIterator<Item = &'a TermStatistics>) -> Self {
+        let mut merged = Self::default();
+        for shard in shards {
+            merged.merge(shard);
+        }
+        merged
+    }
+
+    /// Mean claim length in tokens, at least 1.
+    pub fn avg_doc_len(&self) -> f32 {
+        if self.doc_count == 0 {
+            return 1.0;
+        }
+        (self.total_doc_len as f32 / self.doc_count as f32).max(1.0)
+    }
+
+    /// The document frequency of `term`, or `fallback` when these
+    /// statistics do not cover it.
+    pub(crate) fn doc_freq_or(&self, term: &str, fallback: usize) -> usize {
+        self.doc_freq
+            .get(term)
+            .map_or(fallback, |freq| *freq as usize)
+    }
+}
+
+impl InMemoryStore {
+    /// `tenant_id`'s local statistics for every indexed term.
+    pub fn export_term_statistics(&self, tenant_id: &TenantId) -> TermStatistics {
+        let Some(index) = self.inverted_index.get(tenant_id.as_str()) else {
+            return TermStatistics::default();
+        };
+        TermStatistics {
+            doc_count: index.doc_count() as u64,
+            total_doc_len: index.total_doc_len(),
+            doc_freq: index
+                .doc_freqs()
+                .map(|(term, freq)| (term.to_string(), freq as u64))
+                .collect(),
+        }
+    }
+
+    /// `tenant_id`'s local statistics for the terms of `query` only, as
+    /// the tenant's analyzer splits it; enough for one query's exchange.
+    pub fn export_query_term_statistics(
+        &self,
+        tenant_id: &TenantId,
+        query: &str,
+    ) -> TermStatistics {
+        let Some(index) = self.inverted_index.get(tenant_id.as_str()) else {
+            return TermStatistics::default();
+        };
+        TermStatistics {
+            doc_count: index.doc_count() as u64,
+            total_doc_len: index.total_doc_len(),
+            doc_freq: self
+                .text_analyzer(tenant_id)
+                .analyze(query)
+                .into_iter()
+                .map(|term| {
+                    let freq = index.doc_freq(&term) as u64;
+                    (term, freq)
+                })
+                .collect(),
+        }
+    }
+
+    /// Score `tenant_id`'s BM25 with `stats` instead of the local index's
+    /// own, or go back to local statistics with `None`. Terms `stats`
+    /// does not cover keep their local document frequency.
+    pub fn set_global_term_statistics(
+        &mut self,
+        tenant_id: &TenantId,
+        stats: Option<TermStatistics>,
+    ) {
+        match stats {
+            Some(stats) => {
+                self.global_term_stats.insert(tenant_id.to_string(), stats);
+            }
+            None => {
+                self.global_term_stats.remove(tenant_id.as_str());
+            }
+        }
+    }
+
+    pub fn global_term_statistics(&self, tenant_id: &TenantId) -> Option<&TermStatistics> {
+        self.global_term_stats.get(tenant_id.as_str())
+    }
+}
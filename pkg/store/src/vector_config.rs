@@ -0,0 +1,108 @@
+//! Per-tenant vector configuration.
+//!
+//! Without a registered config a tenant's vector dimension is inferred
+//! from the first vector it receives and every tenant shares the store's
+//! global [`AnnTuningConfig`]. Registering a [`TenantVectorConfig`] up
+//! front pins the dimension and metric before any vector arrives, so a
+//! mis-sized embedding from a misconfigured client is rejected instead
+//! of silently becoming the tenant's dimension, and lets large tenants
+//! use different graph parameters from small ones.
+
+use crate::AnnTuningConfig;
+
+/// Similarity function used to compare claim vectors. The same metric
+/// is used to build a tenant's ANN graph, to walk it at query time, and
+/// on the exact brute-force path, so all three agree on what "nearest"
+/// means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceMetric {
+    #[default]
+    Cosine,
+    /// Raw inner product. Suits embedding models trained for maximum
+    /// inner product search, where vector norm carries signal.
+    Dot,
+    /// L2 distance, reported as a negated distance so that larger is
+    /// still closer.
+    Euclidean,
+}
+
+impl DistanceMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::Dot => "dot",
+            Self::Euclidean => "euclidean",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "cosine" => Some(Self::Cosine),
+            "dot" | "inner_product" => Some(Self::Dot),
+            "euclidean" | "l2" => Some(Self::Euclidean),
+            _ => None,
+        }
+    }
+
+    /// Similarity of `a` and `b`; larger means closer. `None` when the
+    /// lengths differ, the vectors are empty, or (for cosine) either
+    /// vector has zero norm.
+    pub fn similarity(&self, a: &[f32], b: &[f32]) -> Option<f32> {
+        if a.len() != b.len() || a.is_empty() {
+            return None;
+        }
+        match self {
+            Self::Cosine => {
+                let mut dot = 0.0f32;
+                let mut norm_a = 0.0f32;
+                let mut norm_b = 0.0f32;
+                for i in 0..a.len() {
+                    dot += a[i] * b[i];
+                    norm_a += a[i] * a[i];
+                    norm_b += b[i] * b[i];
+                }
+                let denom = norm_a.sqrt() * norm_b.sqrt();
+                if denom <= f32::EPSILON {
+                    None
+                } else {
+                    Some(dot / denom)
+                }
+            }
+            Self::Dot => Some(a.iter().zip(b).map(|(x, y)| x * y).sum()),
+            Self::Euclidean => {
+                let squared: f32 = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+                Some(-squared.sqrt())
+            }
+        }
+    }
+
+    /// Map a [`Self::similarity`] value into `[-1, 1]` so it can be
+    /// blended with lexical signals the same way cosine always has been.
+    /// The mapping is monotonic, so it never changes vector ranking.
+    pub fn normalize_similarity(&self, similarity: f32) -> f32 {
+        match self {
+            Self::Cosine => similarity,
+            Self::Dot => similarity.tanh(),
+            Self::Euclidean => 2.0 / (1.0 - similarity) - 1.0,
+        }
+    }
+}
+
+/// Declared vector shape for one tenant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenantVectorConfig {
+    pub dimension: usize,
+    pub metric: DistanceMetric,
+    /// Overrides the store-wide ANN tuning for this tenant when set.
+    pub ann_tuning: Option<AnnTuningConfig>,
+}
+
+impl TenantVectorConfig {
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            metric: DistanceMetric::default(),
+            ann_tuning: None,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Trained per-tenant vector indexes.
+//!
+//! The neighbor graph in [`crate::ann`] is linked incrementally against
+//! the stored vectors. The alternatives selected through
+//! [`AnnIndexKind`] are instead trained on a tenant's vectors: they hold
+//! a codebook or a set of centroids, are retrained as the tenant grows,
+//! and only generate candidates that the store then re-scores exactly.
+//! They share the [`VectorIndex`] trait so the store keeps one map of
+//! them and one insert/remove/search path.
+
+use crate::ivf::TenantIvfIndex;
+use crate::pq::TenantPqIndex;
+use crate::{AnnIndexKind, DistanceMetric};
+
+/// k-means iterations per training round.
+const KMEANS_ITERATIONS: usize = 8;
+
+/// Upper bound on the vectors sampled for one training round.
+pub(crate) const TRAINING_SAMPLE_MAX: usize = 4096;
+
+pub(crate) trait VectorIndex: Send + Sync {
+    fn is_trained(&self) -> bool;
+
+    /// Whether the vector just counted by [`Self::note_added`] should
+    /// trigger a (re)training round over all of the tenant's vectors.
+    fn needs_training(&self) -> bool;
+
+    fn note_added(&mut self);
+
+    /// Train on `vectors` (sorted by claim id for reproducibility) and
+    /// re-index all of them.
+    fn train(&mut self, vectors: &[(String, Vec<f32>)]);
+
+    /// Index one vector with the current training. No-op before the
+    /// first training.
+    fn insert(&mut self, claim_id: &str, values: &[f32]);
+
+    fn remove(&mut self, claim_id: &str);
+
+    fn is_empty(&self) -> bool;
+
+    /// Candidate claim ids for `query`, best first where the index can
+    /// tell. `limit` bounds indexes that score candidates themselves.
+    fn candidates(&self, metric: DistanceMetric, query: &[f32], limit: usize) -> Vec<String>;
+
+    fn clone_box(&self) -> Box<dyn VectorIndex>;
+}
+
+impl Clone for Box<dyn VectorIndex> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// A fresh index for `kind`, or `None` for the graph, which the store
+/// maintains itself.
+pub(crate) fn new_vector_index(kind: AnnIndexKind) -> Option<Box<dyn VectorIndex>> {
+    match kind {
+        AnnIndexKind::Graph => None,
+        AnnIndexKind::ProductQuantization {
+            subspaces,
+            centroids,
+        } => Some(Box::new(TenantPqIndex::new(subspaces, centroids))),
+        AnnIndexKind::Ivf { lists, probes } => Some(Box::new(TenantIvfIndex::new(lists, probes))),
+    }
+}
+
+/// Lloyd's k-means seeded with evenly spaced samples, so training is
+/// deterministic for a given input order.
+pub(crate) fn train_kmeans(points: &[&[f32]], centroids: usize) -> Vec<Vec<f32>> {
+    let k = centroids.min(points.len()).max(1);
+    let mut codebook: Vec<Vec<f32>> = (0..k)
+        .map(|idx| points[idx * points.len() / k].to_vec())
+        .collect();
+    let width = codebook[0].len();
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut sums = vec![vec![0.0f32; width]; k];
+        let mut counts = vec![0usize; k];
+        for point in points {
+            let nearest = nearest_centroid(&codebook, point);
+            counts[nearest] += 1;
+            for (sum, value) in sums[nearest].iter_mut().zip(point.iter()) {
+                *sum += value;
+            }
+        }
+        for ((centroid, sum), count) in codebook.iter_mut().zip(sums).zip(counts) {
+            // Empty clusters keep their previous centroid.
+            if count > 0 {
+                for (value, total) in centroid.iter_mut().zip(sum) {
+                    *value = total / count as f32;
+                }
+            }
+        }
+    }
+    codebook
+}
+
+pub(crate) fn nearest_centroid(codebook: &[Vec<f32>], point: &[f32]) -> usize {
+    codebook
+        .iter()
+        .enumerate()
+        .map(|(idx, centroid)| (idx, squared_l2(point, centroid)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(idx, _)| idx)
+        .unwrap_or(0)
+}
+
+pub(crate) fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+pub(crate) fn squared_l2(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
@@ -0,0 +1,71 @@
+//! Pluggable exact vector scoring.
+//!
+//! Exact search ([`InMemoryStore::exact_vector_top_candidates`], the
+//! fallback for tenants without an index, and the re-scoring of ANN
+//! candidates) hands batches of full-precision vectors to one scoring
+//! call. A [`VectorScorer`] installed with
+//! [`InMemoryStore::set_vector_scorer`] takes over that call, so large
+//! tenants can be scored on a GPU or by an external service. Without one,
+//! or when the scorer declines a batch, the store scores on the CPU (or
+//! through the built-in `gpu-backend` shader where enabled).
+//!
+//! [`InMemoryStore::exact_vector_top_candidates`]: crate::InMemoryStore::exact_vector_top_candidates
+//! [`InMemoryStore::set_vector_scorer`]: crate::InMemoryStore::set_vector_scorer
+
+use crate::DistanceMetric;
+
+pub trait VectorScorer: Send + Sync {
+    /// Label for logs and metrics.
+    fn name(&self) -> &str;
+
+    /// Batches with fewer candidates stay on the CPU path, where the
+    /// offload round trip would cost more than it saves.
+    fn min_batch_size(&self) -> usize {
+        0
+    }
+
+    /// Score each `(claim_id, vector)` candidate against `query` under
+    /// `metric`, higher is better, in any order. Candidates the metric
+    /// cannot score (a zero vector under cosine, say) are left out.
+    /// Returning `None` declines the batch and the store falls back to
+    /// its own scoring.
+    fn score(
+        &self,
+        metric: DistanceMetric,
+        query: &[f32],
+        candidates: &[(String, &[f32])],
+    ) -> Option<Vec<(String, f32)>>;
+}
+
+/// The in-crate CPU scorer, equivalent to running without one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuVectorScorer;
+
+impl VectorScorer for CpuVectorScorer {
+    fn name(&self) -> &str {
+        "cpu"
+    }
+
+    fn score(
+        &self,
+        metric: DistanceMetric,
+        query: &[f32],
+        candidates: &[(String, &[f32])],
+    ) -> Option<Vec<(String, f32)>> {
+        Some(score_candidates_cpu(metric, query, candidates))
+    }
+}
+
+pub(crate) fn score_candidates_cpu(
+    metric: DistanceMetric,
+    query: &[f32],
+    candidates: &[(String, &[f32])],
+) -> Vec<(String, f32)> {
+    candidates
+        .iter()
+        .filter_map(|(claim_id, candidate)| {
+            let score = metric.similarity(query, candidate)?;
+            Some((claim_id.clone(), score))
+        })
+        .collect()
+}
@@ -0,0 +1,624 @@
+//! Claim vector storage.
+//!
+//! By default every claim vector is held as `f32`. [`VectorStorageConfig`]
+//! can switch that to half precision (`f16` or `bf16`): values are
+//! narrowed on upsert and widened again for scoring, halving vector
+//! memory at a small cost in precision. With [`Int8QuantizationConfig`]
+//! enabled, vectors are stored as int8 codes with one scale factor per
+//! vector (about a quarter of the memory) and similarity is computed
+//! directly against the quantized form. A small FIFO cache keeps the
+//! full-precision copies of recently written vectors so the top
+//! candidates of a query can be re-scored exactly. Quantization takes
+//! precedence over the configured precision.
+//!
+//! Tenants moved to memory-mapped files (see [`crate::MmapVectorConfig`])
+//! keep full `f32` vectors in the file whatever the precision or
+//! quantization setting, which applies only to in-memory tenants.
+//!
+//! The WAL always records vectors as written. A snapshot or disk
+//! checkpoint stores the widened values of half-precision vectors and
+//! the dequantized values of quantized vectors that have left the float
+//! cache.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+
+use half::{bf16, f16};
+
+use crate::DistanceMetric;
+use crate::mmap_vectors::{MmapVectorConfig, TenantVectorFile, tenant_vector_file_name};
+
+const INT8_MAX: f32 = i8::MAX as f32;
+
+/// In-memory element type for claim vectors that are not quantized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VectorPrecision {
+    #[default]
+    F32,
+    /// IEEE half precision: 10-bit mantissa, range up to 65504.
+    F16,
+    /// bfloat16: the `f32` exponent range with a 7-bit mantissa.
+    Bf16,
+}
+
+impl VectorPrecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::F16 => "f16",
+            Self::Bf16 => "bf16",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "f32" | "float32" => Some(Self::F32),
+            "f16" | "float16" | "half" => Some(Self::F16),
+            "bf16" | "bfloat16" => Some(Self::Bf16),
+            _ => None,
+        }
+    }
+
+    // `F32` vectors are never narrowed; it shares the `bf16` arms only so
+    // the matches stay exhaustive.
+    fn narrow(self, values: &[f32]) -> Vec<u16> {
+        match self {
+            Self::F16 => values
+                .iter()
+                .map(|value| f16::from_f32(*value).to_bits())
+                .collect(),
+            Self::F32 | Self::Bf16 => values
+                .iter()
+                .map(|value| bf16::from_f32(*value).to_bits())
+                .collect(),
+        }
+    }
+
+    fn widen(self, bits: &[u16]) -> Vec<f32> {
+        match self {
+            Self::F16 => bits
+                .iter()
+                .map(|bits| f16::from_bits(*bits).to_f32())
+                .collect(),
+            Self::F32 | Self::Bf16 => bits
+                .iter()
+                .map(|bits| bf16::from_bits(*bits).to_f32())
+                .collect(),
+        }
+    }
+}
+
+/// How claim vectors are held in memory when int8 quantization is off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VectorStorageConfig {
+    pub precision: VectorPrecision,
+}
+
+/// Opt-in int8 scalar quantization for claim vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Int8QuantizationConfig {
+    /// How many of the best approximate candidates are re-scored with
+    /// full-precision vectors when those are still cached. 0 disables
+    /// re-scoring.
+    pub rescore_candidates: usize,
+    /// Maximum number of full-precision vectors kept alongside the
+    /// quantized ones. Oldest writes are evicted first.
+    pub float_cache_capacity: usize,
+}
+
+impl Default for Int8QuantizationConfig {
+    fn default() -> Self {
+        Self {
+            rescore_candidates: 64,
+            float_cache_capacity: 4096,
+        }
+    }
+}
+
+/// A vector stored as `scale * codes[i]`, with the norm of the original
+/// vector kept for cosine.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct QuantizedVector {
+    scale: f32,
+    norm: f32,
+    codes: Vec<i8>,
+}
+
+impl QuantizedVector {
+    pub(crate) fn quantize(values: &[f32]) -> Self {
+        let max_abs = values
+            .iter()
+            .fold(0.0f32, |acc, value| acc.max(value.abs()));
+        let scale = if max_abs > 0.0 {
+            max_abs / INT8_MAX
+        } else {
+            0.0
+        };
+        let codes = values
+            .iter()
+            .map(|value| {
+                if scale == 0.0 {
+                    0
+                } else {
+                    (value / scale).round().clamp(-INT8_MAX, INT8_MAX) as i8
+                }
+            })
+            .collect();
+        Self {
+            scale,
+            norm: values.iter().map(|value| value * value).sum::<f32>().sqrt(),
+            codes,
+        }
+    }
+
+    pub(crate) fn dequantize(&self) -> Vec<f32> {
+        self.codes
+            .iter()
+            .map(|code| *code as f32 * self.scale)
+            .collect()
+    }
+
+    /// Approximate [`DistanceMetric::similarity`] between a full-precision
+    /// query and this vector, without dequantizing it.
+    pub(crate) fn similarity(&self, metric: DistanceMetric, query: &[f32]) -> Option<f32> {
+        if query.len() != self.codes.len() || query.is_empty() {
+            return None;
+        }
+        match metric {
+            DistanceMetric::Cosine => {
+                let query_norm = query.iter().map(|value| value * value).sum::<f32>().sqrt();
+                let denom = query_norm * self.norm;
+                if denom <= f32::EPSILON {
+                    None
+                } else {
+                    Some(self.dot(query) / denom)
+                }
+            }
+            DistanceMetric::Dot => Some(self.dot(query)),
+            DistanceMetric::Euclidean => {
+                let squared: f32 = query
+                    .iter()
+                    .zip(&self.codes)
+                    .map(|(q, code)| {
+                        let diff = q - *code as f32 * self.scale;
+                        diff * diff
+                    })
+                    .sum();
+                Some(-squared.sqrt())
+            }
+        }
+    }
+
+    fn dot(&self, query: &[f32]) -> f32 {
+        let raw: f32 = query
+            .iter()
+            .zip(&self.codes)
+            .map(|(q, code)| q * *code as f32)
+            .sum();
+        raw * self.scale
+    }
+
+    fn storage_bytes(&self) -> usize {
+        self.codes.len() + 2 * std::mem::size_of::<f32>()
+    }
+
+    fn dimension(&self) -> usize {
+        self.codes.len()
+    }
+}
+
+/// All claim vectors of a store, in whichever representation the
+/// configured quantization calls for.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClaimVectorStore {
+    /// Every vector when stored as `f32`; the float cache when quantized.
+    floats: HashMap<String, Vec<f32>>,
+    /// Raw `f16`/`bf16` bits when stored at half precision.
+    halves: HashMap<String, Vec<u16>>,
+    quantized: HashMap<String, QuantizedVector>,
+    quantization: Option<Int8QuantizationConfig>,
+    storage: VectorStorageConfig,
+    float_cache_order: VecDeque<String>,
+    mmap: Option<MmapVectorConfig>,
+    mapped_tenants: HashMap<String, TenantVectorFile>,
+    /// Tenant of every vector held in a mapped file.
+    mapped_claims: HashMap<String, String>,
+    /// In-memory vector bytes per tenant, counted at `f32`; tracked only
+    /// while `mmap` is set.
+    tenant_memory_bytes: HashMap<String, usize>,
+}
+
+impl ClaimVectorStore {
+    pub(crate) fn quantization(&self) -> Option<&Int8QuantizationConfig> {
+        self.quantization.as_ref()
+    }
+
+    /// Switch representation, converting every stored vector. Turning
+    /// quantization off restores cached floats and dequantizes the rest.
+    pub(crate) fn set_quantization(&mut self, quantization: Option<Int8QuantizationConfig>) {
+        self.convert(|vectors| vectors.quantization = quantization);
+    }
+
+    pub(crate) fn storage(&self) -> VectorStorageConfig {
+        self.storage
+    }
+
+    /// Switch the precision of non-quantized vectors, converting every
+    /// stored vector. Narrowing is lossy; widening back does not restore
+    /// the dropped bits.
+    pub(crate) fn set_storage(&mut self, storage: VectorStorageConfig) {
+        self.convert(|vectors| vectors.storage = storage);
+    }
+
+    /// Whether non-quantized vectors are held at half precision.
+    pub(crate) fn is_half_precision(&self) -> bool {
+        self.quantization.is_none() && self.storage.precision != VectorPrecision::F32
+    }
+
+    fn convert(&mut self, reconfigure: impl FnOnce(&mut Self)) {
+        let mut vectors: Vec<(String, Vec<f32>)> = self
+            .memory_keys()
+            .map(|claim_id| {
+                let values = self.get(claim_id).map(Cow::into_owned).unwrap_or_default();
+                (claim_id.clone(), values)
+            })
+            .collect();
+        vectors.sort_by(|a, b| a.0.cmp(&b.0));
+        self.floats.clear();
+        self.halves.clear();
+        self.quantized.clear();
+        self.float_cache_order.clear();
+        reconfigure(self);
+        for (claim_id, values) in vectors {
+            self.insert_in_memory(claim_id, values);
+        }
+    }
+
+    pub(crate) fn mmap(&self) -> Option<&MmapVectorConfig> {
+        self.mmap.as_ref()
+    }
+
+    /// Set or clear the mmap config. `tenant_memory_bytes` seeds the
+    /// per-tenant accounting for vectors already in memory. Clearing it
+    /// reads every mapped tenant back into memory.
+    pub(crate) fn set_mmap(
+        &mut self,
+        mmap: Option<MmapVectorConfig>,
+        tenant_memory_bytes: HashMap<String, usize>,
+    ) {
+        if mmap.is_none() {
+            let mut mapped: Vec<(String, Vec<f32>)> = self
+                .mapped_claims
+                .keys()
+                .filter_map(|claim_id| {
+                    let values = self.get(claim_id)?.into_owned();
+                    Some((claim_id.clone(), values))
+                })
+                .collect();
+            mapped.sort_by(|a, b| a.0.cmp(&b.0));
+            self.mapped_claims.clear();
+            self.mapped_tenants.clear();
+            for (claim_id, values) in mapped {
+                self.insert_in_memory(claim_id, values);
+            }
+            self.tenant_memory_bytes.clear();
+        } else {
+            self.tenant_memory_bytes = tenant_memory_bytes;
+        }
+        self.mmap = mmap;
+    }
+
+    /// Whether `tenant_id` is held in memory and over the mmap budget.
+    pub(crate) fn tenant_over_budget(&self, tenant_id: &str) -> bool {
+        let Some(mmap) = &self.mmap else {
+            return false;
+        };
+        !self.mapped_tenants.contains_key(tenant_id)
+            && self
+                .tenant_memory_bytes
+                .get(tenant_id)
+                .is_some_and(|bytes| *bytes > mmap.tenant_budget_bytes)
+    }
+
+    /// Move `claim_ids`, all of `tenant_id`'s in-memory vectors, into a
+    /// new mapped file. On error nothing is moved.
+    pub(crate) fn map_tenant(&mut self, tenant_id: &str, claim_ids: &[String]) -> io::Result<()> {
+        let Some(mmap) = &self.mmap else {
+            return Ok(());
+        };
+        let Some(dimension) = claim_ids
+            .iter()
+            .find_map(|claim_id| self.memory_dimension(claim_id))
+        else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(&mmap.directory)?;
+        let path = mmap.directory.join(tenant_vector_file_name(tenant_id));
+        let mut file = TenantVectorFile::create(path, dimension)?;
+        for claim_id in claim_ids {
+            if let Some(values) = self.get(claim_id) {
+                file.insert(claim_id, &values)?;
+            }
+        }
+        for claim_id in claim_ids {
+            if self.remove_in_memory(claim_id) {
+                self.mapped_claims
+                    .insert(claim_id.clone(), tenant_id.to_string());
+            }
+        }
+        self.tenant_memory_bytes.remove(tenant_id);
+        self.mapped_tenants.insert(tenant_id.to_string(), file);
+        Ok(())
+    }
+
+    pub(crate) fn mapped_tenant_ids(&self) -> Vec<String> {
+        let mut tenant_ids: Vec<String> = self.mapped_tenants.keys().cloned().collect();
+        tenant_ids.sort_unstable();
+        tenant_ids
+    }
+
+    /// Bytes of all mapped files, including unused slots.
+    pub(crate) fn mapped_bytes(&self) -> usize {
+        self.mapped_tenants
+            .values()
+            .map(TenantVectorFile::mapped_bytes)
+            .sum()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.memory_len() + self.mapped_claims.len()
+    }
+
+    fn memory_len(&self) -> usize {
+        if self.quantization.is_some() {
+            self.quantized.len()
+        } else if self.is_half_precision() {
+            self.halves.len()
+        } else {
+            self.floats.len()
+        }
+    }
+
+    pub(crate) fn contains_key(&self, claim_id: &str) -> bool {
+        self.memory_contains_key(claim_id) || self.mapped_claims.contains_key(claim_id)
+    }
+
+    fn memory_contains_key(&self, claim_id: &str) -> bool {
+        if self.quantization.is_some() {
+            self.quantized.contains_key(claim_id)
+        } else if self.is_half_precision() {
+            self.halves.contains_key(claim_id)
+        } else {
+            self.floats.contains_key(claim_id)
+        }
+    }
+
+    pub(crate) fn keys(&self) -> Box<dyn Iterator<Item = &String> + '_> {
+        Box::new(self.memory_keys().chain(self.mapped_claims.keys()))
+    }
+
+    fn memory_keys(&self) -> Box<dyn Iterator<Item = &String> + '_> {
+        if self.quantization.is_some() {
+            Box::new(self.quantized.keys())
+        } else if self.is_half_precision() {
+            Box::new(self.halves.keys())
+        } else {
+            Box::new(self.floats.keys())
+        }
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        tenant_id: &str,
+        claim_id: String,
+        values: Vec<f32>,
+    ) -> io::Result<()> {
+        if let Some(file) = self.mapped_tenants.get_mut(tenant_id) {
+            file.insert(&claim_id, &values)?;
+            self.mapped_claims.insert(claim_id, tenant_id.to_string());
+            return Ok(());
+        }
+        if self.mmap.is_some() {
+            let previous = self.memory_dimension(&claim_id).unwrap_or(0);
+            let bytes = self
+                .tenant_memory_bytes
+                .entry(tenant_id.to_string())
+                .or_default();
+            *bytes = bytes.saturating_sub(previous * std::mem::size_of::<f32>())
+                + values.len() * std::mem::size_of::<f32>();
+        }
+        self.insert_in_memory(claim_id, values);
+        Ok(())
+    }
+
+    fn insert_in_memory(&mut self, claim_id: String, values: Vec<f32>) {
+        let Some(config) = self.quantization else {
+            if self.is_half_precision() {
+                let bits = self.storage.precision.narrow(&values);
+                self.halves.insert(claim_id, bits);
+            } else {
+                self.floats.insert(claim_id, values);
+            }
+            return;
+        };
+        self.quantized
+            .insert(claim_id.clone(), QuantizedVector::quantize(&values));
+        if config.float_cache_capacity == 0 {
+            self.floats.remove(&claim_id);
+            return;
+        }
+        if self.floats.insert(claim_id.clone(), values).is_none() {
+            self.float_cache_order.push_back(claim_id);
+        }
+        while self.floats.len() > config.float_cache_capacity {
+            let Some(evicted) = self.float_cache_order.pop_front() else {
+                break;
+            };
+            self.floats.remove(&evicted);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, tenant_id: &str, claim_id: &str) -> bool {
+        if let Some(tenant_id) = self.mapped_claims.remove(claim_id) {
+            let Some(file) = self.mapped_tenants.get_mut(&tenant_id) else {
+                return false;
+            };
+            let removed = file.remove(claim_id);
+            // An emptied tenant goes back to memory, which also lets its
+            // vectors change dimension (see `install_vector_projection`).
+            if file.is_empty() {
+                self.mapped_tenants.remove(&tenant_id);
+            }
+            return removed;
+        }
+        if self.mmap.is_some()
+            && let Some(dimension) = self.memory_dimension(claim_id)
+            && let Some(bytes) = self.tenant_memory_bytes.get_mut(tenant_id)
+        {
+            *bytes = bytes.saturating_sub(dimension * std::mem::size_of::<f32>());
+        }
+        self.remove_in_memory(claim_id)
+    }
+
+    fn memory_dimension(&self, claim_id: &str) -> Option<usize> {
+        self.floats
+            .get(claim_id)
+            .map(Vec::len)
+            .or_else(|| self.halves.get(claim_id).map(Vec::len))
+            .or_else(|| self.quantized.get(claim_id).map(QuantizedVector::dimension))
+    }
+
+    fn remove_in_memory(&mut self, claim_id: &str) -> bool {
+        let had_float = self.floats.remove(claim_id).is_some();
+        if self.quantization.is_none() {
+            return had_float || self.halves.remove(claim_id).is_some();
+        }
+        if had_float {
+            self.float_cache_order.retain(|cached| cached != claim_id);
+        }
+        self.quantized.remove(claim_id).is_some()
+    }
+
+    /// Values of `claim_id`; widened from half precision, dequantized
+    /// when the full-precision copy is no longer cached, or read from the
+    /// tenant's mapped file.
+    pub(crate) fn get(&self, claim_id: &str) -> Option<Cow<'_, [f32]>> {
+        if let Some(values) = self.floats.get(claim_id) {
+            return Some(Cow::Borrowed(values.as_slice()));
+        }
+        if let Some(bits) = self.halves.get(claim_id) {
+            return Some(Cow::Owned(self.storage.precision.widen(bits)));
+        }
+        if let Some(vector) = self.quantized.get(claim_id) {
+            return Some(Cow::Owned(vector.dequantize()));
+        }
+        let tenant_id = self.mapped_claims.get(claim_id)?;
+        self.mapped_tenants
+            .get(tenant_id)?
+            .get(claim_id)
+            .map(Cow::Owned)
+    }
+
+    /// Full-precision values, only when vectors are stored as `f32`.
+    /// Used by the batch scoring path that can offload to the GPU.
+    pub(crate) fn float(&self, claim_id: &str) -> Option<&[f32]> {
+        if self.quantization.is_some() || self.is_half_precision() {
+            return None;
+        }
+        self.floats.get(claim_id).map(Vec::as_slice)
+    }
+
+    /// Similarity between `query` and the stored vector, computed on the
+    /// quantized form when quantization is on and on the widened values
+    /// at half precision.
+    pub(crate) fn similarity(
+        &self,
+        metric: DistanceMetric,
+        query: &[f32],
+        claim_id: &str,
+    ) -> Option<f32> {
+        if self.mapped_claims.contains_key(claim_id) {
+            metric.similarity(query, &self.get(claim_id)?)
+        } else if self.quantization.is_some() {
+            self.quantized.get(claim_id)?.similarity(metric, query)
+        } else if self.is_half_precision() {
+            let values = self.storage.precision.widen(self.halves.get(claim_id)?);
+            metric.similarity(query, &values)
+        } else {
+            metric.similarity(query, self.floats.get(claim_id)?)
+        }
+    }
+
+    /// Score `claim_ids` on the quantized vectors, then re-score the best
+    /// `rescore_candidates` of them exactly where the float cache allows.
+    pub(crate) fn score_quantized(
+        &self,
+        metric: DistanceMetric,
+        query: &[f32],
+        claim_ids: Vec<String>,
+    ) -> Vec<(String, f32)> {
+        let mut scored: Vec<(String, f32)> = claim_ids
+            .into_iter()
+            .filter_map(|claim_id| {
+                let score = self.similarity(metric, query, &claim_id)?;
+                Some((claim_id, score))
+            })
+            .collect();
+        let rescore = self
+            .quantization
+            .map(|config| config.rescore_candidates)
+            .unwrap_or(0);
+        if rescore == 0 {
+            return scored;
+        }
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        for (claim_id, score) in scored.iter_mut().take(rescore) {
+            if let Some(exact) = self
+                .floats
+                .get(claim_id.as_str())
+                .and_then(|values| metric.similarity(query, values))
+            {
+                *score = exact;
+            }
+        }
+        scored
+    }
+
+    /// Approximate heap bytes of `claim_id`'s vector, as for
+    /// [`Self::storage_bytes`].
+    pub(crate) fn claim_storage_bytes(&self, claim_id: &str) -> usize {
+        self.floats
+            .get(claim_id)
+            .map_or(0, |values| values.len() * std::mem::size_of::<f32>())
+            + self
+                .halves
+                .get(claim_id)
+                .map_or(0, |bits| bits.len() * std::mem::size_of::<u16>())
+            + self
+                .quantized
+                .get(claim_id)
+                .map_or(0, QuantizedVector::storage_bytes)
+    }
+
+    /// Approximate heap bytes held by vector payloads; mapped files are
+    /// not counted.
+    pub(crate) fn storage_bytes(&self) -> usize {
+        let floats: usize = self
+            .floats
+            .values()
+            .map(|values| values.len() * std::mem::size_of::<f32>())
+            .sum();
+        let halves: usize = self
+            .halves
+            .values()
+            .map(|bits| bits.len() * std::mem::size_of::<u16>())
+            .sum();
+        let quantized: usize = self
+            .quantized
+            .values()
+            .map(QuantizedVector::storage_bytes)
+            .sum();
+        floats + halves + quantized
+    }
+}
@@ -0,0 +1,68 @@
+//! Row-level security on claims.
+//!
+//! A claim may carry visibility labels such as `team:finance`. A
+//! retrieval given the caller's labels in
+//! [`RetrievalOptions::visibility_labels`](crate::RetrievalOptions::visibility_labels)
+//! returns only unlabeled claims and claims sharing a label with the
+//! caller, as [`Claim::is_visible_to`](schema::Claim::is_visible_to)
+//! decides. Services fill the labels in from the caller's verified token,
+//! so the store enforces them once instead of every client filtering its
+//! own results. A retrieval without labels is not restricted.
+//!
+//! Most claims carry no labels, so the store indexes only the labelled
+//! ones per tenant and excludes the few the caller can't see rather than
+//! enumerating everything it can. A tenant without labelled claims costs
+//! nothing to filter.
+
+use std::collections::HashSet;
+
+use schema::{Claim, ClaimId, TenantId};
+
+use crate::InMemoryStore;
+
+impl InMemoryStore {
+    /// Claims of `tenant_id` a caller holding `labels` may not see; empty
+    /// when the tenant has no labelled claims.
+    pub fn claim_ids_hidden_from(
+        &self,
+        tenant_id: &TenantId,
+        labels: &[String],
+    ) -> HashSet<ClaimId> {
+        self.labelled_claim_ids
+            .get(tenant_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|claim_id| {
+                self.claims
+                    .get(claim_id.as_str())
+                    .is_some_and(|claim| !claim.is_visible_to(labels))
+            })
+            .map(ClaimId::from)
+            .collect()
+    }
+
+    /// Whether any claim of `tenant_id` carries visibility labels.
+    pub fn has_labelled_claims(&self, tenant_id: &TenantId) -> bool {
+        self.labelled_claim_ids.contains_key(tenant_id.as_str())
+    }
+
+    pub(crate) fn index_claim_visibility(&mut self, claim: &Claim) {
+        if claim.visibility_labels.is_empty() {
+            return;
+        }
+        self.labelled_claim_ids
+            .entry(claim.tenant_id.to_string())
+            .or_default()
+            .insert(claim.claim_id.to_string());
+    }
+
+    pub(crate) fn unindex_claim_visibility(&mut self, claim: &Claim) {
+        let Some(claim_ids) = self.labelled_claim_ids.get_mut(claim.tenant_id.as_str()) else {
+            return;
+        };
+        claim_ids.remove(claim.claim_id.as_str());
+        if claim_ids.is_empty() {
+            self.labelled_claim_ids.remove(claim.tenant_id.as_str());
+        }
+    }
+}
@@ -8,7 +8,8 @@
 //! Two top-level types are exported:
 //! - [`WalEvent`] — an in-memory record of one mutation, used by
 //!   `InMemoryStore.wal` for the segment-cache replay path.
-//! - [`FileWal`] — the on-disk append-only log.
+//! - [`FileWal`] — the append-only log, stored through a
+//!   [`WalBackend`](crate::WalBackend); a log file by default.
 //!
 //! All other types in this module are either private helpers
 //! ([`PersistedRecord`], [`ClaimVectorRecord`], [`BatchCommitRecord`])
@@ -16,24 +17,31 @@
 //! etc.) that the rest of the crate consumes via re-exports from
 //! `lib.rs`.
 
-use std::fs::{create_dir_all, rename, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
-
-const SNAPSHOT_HEADER: &str = "SNAP\t1";
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
-use schema::{Claim, ClaimEdge, ClaimType, Evidence, Relation, Stance};
+use schema::{
+    Chunk, Claim, ClaimEdge, ClaimId, ClaimType, Document, Entity, Evidence, Relation, Source,
+    Stance, TenantId, TrustTier,
+};
+
+use crate::migrations;
+use crate::{
+    AnnIndexKind, AnnTuningConfig, DistanceMetric, FileWalBackend, SparseVector, StoreError,
+    TenantVectorConfig, TextAnalyzer, TokenizerKind, VectorProjection, WalBackend,
+};
 
-use crate::StoreError;
+/// How often [`FileWal::tail_from`] looks for new records.
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(20);
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum WalEvent {
+pub(crate) enum WalEvent {
     ClaimUpsert(String),
     EvidenceUpsert(String),
     EdgeUpsert(String),
     ClaimVectorUpsert(String),
     BatchCommit(String),
+    ClaimDelete(String),
 }
 
 #[derive(Debug, Clone)]
@@ -43,12 +51,116 @@ pub(crate) enum PersistedRecord {
     Edge(ClaimEdge),
     ClaimVector(ClaimVectorRecord),
     BatchCommit(BatchCommitRecord),
+    TenantVectorConfig(TenantVectorConfigRecord),
+    AnnGraphHeader(AnnGraphHeaderRecord),
+    AnnGraphNode(AnnGraphNodeRecord),
+    VectorProjection(VectorProjectionRecord),
+    SparseVector(SparseVectorRecord),
+    TextAnalyzer(TextAnalyzerRecord),
+    ClaimDelete(ClaimDeleteRecord),
+    ClaimMerge(ClaimMergeRecord),
+    ClaimArchive(ClaimArchiveRecord),
+    Document(Document),
+    Chunk(Chunk),
+    Source(Source),
+    Checkpoint(CheckpointRecord),
+}
+
+/// Snapshot-only first record of a snapshot written by a checkpoint.
+/// `generation` counts the checkpoints of the log, so it names the
+/// snapshot even when a later one holds the same number of records.
+/// Snapshots written before it was recorded are generation 0.
+#[derive(Debug, Clone)]
+pub(crate) struct CheckpointRecord {
+    pub(crate) generation: u64,
+}
+
+/// Snapshot-only header for one tenant's serialized ANN graph. The
+/// graph is only restored when `version`, `levels`, and the neighbor
+/// limits match the loading store; otherwise it is rebuilt from the
+/// claim vectors.
+#[derive(Debug, Clone)]
+pub(crate) struct AnnGraphHeaderRecord {
+    pub(crate) version: u32,
+    pub(crate) tenant_id: String,
+    pub(crate) levels: usize,
+    pub(crate) max_neighbors_base: usize,
+    pub(crate) max_neighbors_upper: usize,
+    pub(crate) entry_point: Option<String>,
+    pub(crate) entry_level: usize,
+}
+
+/// One graph node: its level and its neighbor list on each level from
+/// 0 up to and including `node_level`.
+#[derive(Debug, Clone)]
+pub(crate) struct AnnGraphNodeRecord {
+    pub(crate) tenant_id: String,
+    pub(crate) claim_id: String,
+    pub(crate) node_level: usize,
+    pub(crate) neighbors: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct VectorProjectionRecord {
+    pub(crate) tenant_id: String,
+    pub(crate) projection: VectorProjection,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TextAnalyzerRecord {
+    pub(crate) tenant_id: String,
+    pub(crate) analyzer: TextAnalyzer,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TenantVectorConfigRecord {
+    pub(crate) tenant_id: String,
+    pub(crate) config: TenantVectorConfig,
+}
+
+/// Tombstone for a claim removed by
+/// [`InMemoryStore::delete_claim_persistent`](crate::InMemoryStore::delete_claim_persistent).
+/// Never written to snapshots: a deleted claim is simply absent there.
+#[derive(Debug, Clone)]
+pub(crate) struct ClaimDeleteRecord {
+    pub(crate) tenant_id: String,
+    pub(crate) claim_id: String,
+}
+
+/// A merge done by
+/// [`InMemoryStore::merge_claims_persistent`](crate::InMemoryStore::merge_claims_persistent).
+/// Replay repeats the merge, which only depends on the state before it.
+/// Like tombstones, never written to snapshots.
+#[derive(Debug, Clone)]
+pub(crate) struct ClaimMergeRecord {
+    pub(crate) tenant_id: String,
+    pub(crate) primary_claim_id: ClaimId,
+    pub(crate) duplicate_claim_ids: Vec<ClaimId>,
+}
+
+/// A claim archived or unarchived by
+/// [`InMemoryStore::archive_claim_persistent`](crate::InMemoryStore::archive_claim_persistent)
+/// and its counterpart. Snapshots carry one, archived, per archived
+/// claim.
+#[derive(Debug, Clone)]
+pub(crate) struct ClaimArchiveRecord {
+    pub(crate) tenant_id: String,
+    pub(crate) claim_id: String,
+    pub(crate) archived: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SparseVectorRecord {
+    pub(crate) claim_id: String,
+    pub(crate) vector: SparseVector,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct ClaimVectorRecord {
     pub(crate) claim_id: String,
     pub(crate) values: Vec<f32>,
+    /// Named vector space; `None` is the claim's default vector.
+    pub(crate) space: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -56,19 +168,26 @@ pub(crate) struct BatchCommitRecord {
     pub(crate) commit_id: String,
     pub(crate) batch_size: usize,
     pub(crate) ts_unix_ms: u64,
-    pub(crate) claim_ids: Vec<String>,
+    pub(crate) claim_ids: Vec<ClaimId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct WalCheckpointStats {
     pub snapshot_records: usize,
     pub truncated_wal_records: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
 pub struct CheckpointPolicy {
     pub max_wal_records: Option<usize>,
     pub max_wal_bytes: Option<u64>,
+    /// Slow writes once the WAL holds this many times a threshold; see
+    /// [`InMemoryStore::throttle_write`](crate::InMemoryStore::throttle_write).
+    pub write_slowdown_multiple: Option<u32>,
+    /// Reject writes once the WAL holds this many times a threshold.
+    pub write_stop_multiple: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -80,6 +199,10 @@ pub struct WalReplayStats {
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct WalReplayBoundary {
     pub snapshot_active: bool,
+    /// Checkpoints taken on this log, 0 before the first. Each one
+    /// replaces the snapshot, so this identifies the snapshot the WAL
+    /// delta follows.
+    pub checkpoint_generation: u64,
     pub snapshot_record_count: usize,
     pub wal_delta_record_count: usize,
     pub total_replay_record_count: usize,
@@ -102,6 +225,7 @@ pub struct WalReplicationExport {
 
 pub struct FileWal {
     path: PathBuf,
+    backend: Box<dyn WalBackend>,
     wal_records: usize,
     sync_every_records: usize,
     append_buffer_max_records: usize,
@@ -110,9 +234,11 @@ pub struct FileWal {
     append_buffer: Vec<String>,
     pub(crate) unsynced_records: usize,
     last_sync_at: Instant,
+    appended_bytes: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct WalWritePolicy {
     pub sync_every_records: usize,
     pub append_buffer_max_records: usize,
@@ -122,7 +248,6 @@ pub struct WalWritePolicy {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct WalRollbackPoint {
-    file_len_bytes: u64,
     wal_records: usize,
 }
 
@@ -137,6 +262,28 @@ impl Default for WalWritePolicy {
     }
 }
 
+impl WalWritePolicy {
+    /// Sync every `sync_every_records` appends, buffering at most
+    /// `append_buffer_max_records` of them in between.
+    pub fn new(sync_every_records: usize, append_buffer_max_records: usize) -> Self {
+        Self {
+            sync_every_records,
+            append_buffer_max_records,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_sync_interval(mut self, sync_interval: Option<Duration>) -> Self {
+        self.sync_interval = sync_interval;
+        self
+    }
+
+    pub fn with_background_flush_only(mut self, background_flush_only: bool) -> Self {
+        self.background_flush_only = background_flush_only;
+        self
+    }
+}
+
 impl FileWal {
     pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
         Self::open_with_sync_every_records(path, 1)
@@ -159,16 +306,20 @@ impl FileWal {
         path: impl AsRef<Path>,
         policy: WalWritePolicy,
     ) -> Result<Self, StoreError> {
-        let path = path.as_ref().to_path_buf();
-        if let Some(parent) = path.parent()
-            && !parent.as_os_str().is_empty()
-        {
-            create_dir_all(parent)?;
-        }
-        OpenOptions::new().create(true).append(true).open(&path)?;
-        let wal_records = count_non_empty_lines(&path)?;
+        Self::with_backend(FileWalBackend::open(path)?, policy)
+    }
+
+    /// A WAL over `backend` instead of a log file. `path` is empty unless
+    /// the backend has a file.
+    pub fn with_backend(
+        backend: impl WalBackend + 'static,
+        policy: WalWritePolicy,
+    ) -> Result<Self, StoreError> {
+        let path = backend.path().map(Path::to_path_buf).unwrap_or_default();
+        let wal_records = backend.record_count()?;
         Ok(Self {
             path,
+            backend: Box::new(backend),
             wal_records,
             sync_every_records: policy.sync_every_records.max(1),
             append_buffer_max_records: policy.append_buffer_max_records.max(1),
@@ -177,6 +328,7 @@ impl FileWal {
             append_buffer: Vec::new(),
             unsynced_records: 0,
             last_sync_at: Instant::now(),
+            appended_bytes: 0,
         })
     }
 
@@ -208,6 +360,13 @@ impl FileWal {
         self.append_buffer.len()
     }
 
+    /// Total bytes (including line terminators) appended through this
+    /// handle since it was opened. Buffered records count as soon as
+    /// they are accepted, before they reach the file.
+    pub fn appended_bytes(&self) -> u64 {
+        self.appended_bytes
+    }
+
     pub fn snapshot_path(&self) -> PathBuf {
         let mut path = self.path.clone().into_os_string();
         path.push(".snapshot");
@@ -228,21 +387,130 @@ impl FileWal {
 
     pub fn append_claim_vector(
         &mut self,
-        claim_id: &str,
+        claim_id: &ClaimId,
         values: &[f32],
     ) -> Result<(), StoreError> {
         self.append_record(&PersistedRecord::ClaimVector(ClaimVectorRecord {
             claim_id: claim_id.to_string(),
             values: values.to_vec(),
+            space: None,
+        }))
+    }
+
+    pub fn append_named_claim_vector(
+        &mut self,
+        claim_id: &ClaimId,
+        space: &str,
+        values: &[f32],
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::ClaimVector(ClaimVectorRecord {
+            claim_id: claim_id.to_string(),
+            values: values.to_vec(),
+            space: Some(space.to_string()),
+        }))
+    }
+
+    pub fn append_tenant_vector_config(
+        &mut self,
+        tenant_id: &TenantId,
+        config: &TenantVectorConfig,
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::TenantVectorConfig(
+            TenantVectorConfigRecord {
+                tenant_id: tenant_id.to_string(),
+                config: config.clone(),
+            },
+        ))
+    }
+
+    pub fn append_text_analyzer(
+        &mut self,
+        tenant_id: &TenantId,
+        analyzer: TextAnalyzer,
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::TextAnalyzer(TextAnalyzerRecord {
+            tenant_id: tenant_id.to_string(),
+            analyzer,
         }))
     }
 
+    pub fn append_vector_projection(
+        &mut self,
+        tenant_id: &TenantId,
+        projection: &VectorProjection,
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::VectorProjection(VectorProjectionRecord {
+            tenant_id: tenant_id.to_string(),
+            projection: projection.clone(),
+        }))
+    }
+
+    pub fn append_claim_sparse_vector(
+        &mut self,
+        claim_id: &ClaimId,
+        vector: &SparseVector,
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::SparseVector(SparseVectorRecord {
+            claim_id: claim_id.to_string(),
+            vector: vector.clone(),
+        }))
+    }
+
+    pub fn append_claim_delete(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::ClaimDelete(ClaimDeleteRecord {
+            tenant_id: tenant_id.to_string(),
+            claim_id: claim_id.to_string(),
+        }))
+    }
+
+    pub fn append_claim_merge(
+        &mut self,
+        tenant_id: &TenantId,
+        primary_claim_id: &ClaimId,
+        duplicate_claim_ids: &[ClaimId],
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::ClaimMerge(ClaimMergeRecord {
+            tenant_id: tenant_id.to_string(),
+            primary_claim_id: primary_claim_id.clone(),
+            duplicate_claim_ids: duplicate_claim_ids.to_vec(),
+        }))
+    }
+
+    pub fn append_claim_archive(
+        &mut self,
+        tenant_id: &TenantId,
+        claim_id: &ClaimId,
+        archived: bool,
+    ) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::ClaimArchive(ClaimArchiveRecord {
+            tenant_id: tenant_id.to_string(),
+            claim_id: claim_id.to_string(),
+            archived,
+        }))
+    }
+
+    pub fn append_document(&mut self, document: &Document) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::Document(document.clone()))
+    }
+
+    pub fn append_chunk(&mut self, chunk: &Chunk) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::Chunk(chunk.clone()))
+    }
+
+    pub fn append_source(&mut self, source: &Source) -> Result<(), StoreError> {
+        self.append_record(&PersistedRecord::Source(source.clone()))
+    }
+
     pub fn append_batch_commit(
         &mut self,
         commit_id: &str,
         batch_size: usize,
         ts_unix_ms: u64,
-        claim_ids: &[String],
+        claim_ids: &[ClaimId],
     ) -> Result<(), StoreError> {
         self.append_record(&PersistedRecord::BatchCommit(BatchCommitRecord {
             commit_id: commit_id.to_string(),
@@ -257,15 +525,27 @@ impl FileWal {
     }
 
     pub fn wal_size_bytes(&self) -> Result<u64, StoreError> {
-        Ok(std::fs::metadata(&self.path)?.len())
+        self.backend.size_bytes()
+    }
+
+    /// Checkpoints taken on this log, 0 before the first; see
+    /// [`WalReplayBoundary::checkpoint_generation`].
+    pub fn checkpoint_generation(&self) -> Result<u64, StoreError> {
+        match self.backend.read_snapshot_head()? {
+            Some(line) => snapshot_checkpoint_generation(&[line]),
+            None => Ok(0),
+        }
     }
 
     pub fn replay_boundary(&self) -> Result<WalReplayBoundary, StoreError> {
-        let snapshot_record_count = self.replay_snapshot_lines_raw()?.len();
+        let snapshot_lines = self.replay_snapshot_lines_raw()?;
+        let checkpoint_generation = snapshot_checkpoint_generation(&snapshot_lines)?;
+        let snapshot_record_count = snapshot_record_count(&snapshot_lines);
         let mut wal_delta_record_count = self.replay_wal_lines_raw()?.len();
         wal_delta_record_count = wal_delta_record_count.saturating_add(self.append_buffer.len());
         Ok(WalReplayBoundary {
             snapshot_active: snapshot_record_count > 0,
+            checkpoint_generation,
             snapshot_record_count,
             wal_delta_record_count,
             total_replay_record_count: snapshot_record_count.saturating_add(wal_delta_record_count),
@@ -275,20 +555,14 @@ impl FileWal {
     pub fn begin_rollback_point(&mut self) -> Result<WalRollbackPoint, StoreError> {
         self.flush_pending_sync()?;
         Ok(WalRollbackPoint {
-            file_len_bytes: self.wal_size_bytes()?,
             wal_records: self.wal_records,
         })
     }
 
     pub fn rollback_to(&mut self, point: WalRollbackPoint) -> Result<(), StoreError> {
         self.append_buffer.clear();
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(false)
-            .open(&self.path)?;
-        file.set_len(point.file_len_bytes)?;
-        file.sync_data()?;
+        self.backend.truncate_after(point.wal_records)?;
+        self.backend.sync()?;
         self.wal_records = point.wal_records;
         self.unsynced_records = 0;
         self.last_sync_at = Instant::now();
@@ -334,6 +608,51 @@ impl FileWal {
         })
     }
 
+    /// The records from `from_offset` on, waiting up to `timeout` for
+    /// the first of them to be written. Polls the backend, so another
+    /// process can tail a log this process does not write. A final
+    /// record that does not parse is taken to be mid-write and left for
+    /// the next call. With nothing new by the deadline the delta is
+    /// empty; as for [`Self::replication_delta_from`], an offset past
+    /// the end means the log was compacted and the caller must resync.
+    pub fn tail_from(
+        &self,
+        from_offset: usize,
+        timeout: Duration,
+    ) -> Result<WalReplicationDelta, StoreError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let total_records = self.backend.record_count()?;
+            if from_offset > total_records {
+                return Ok(WalReplicationDelta {
+                    from_offset,
+                    next_offset: total_records,
+                    total_records,
+                    needs_resync: true,
+                    wal_lines: Vec::new(),
+                });
+            }
+            let mut wal_lines = self.backend.read_from(from_offset)?;
+            if wal_lines
+                .last()
+                .is_some_and(|line| line_to_record(line).is_err())
+            {
+                wal_lines.pop();
+            }
+            let now = Instant::now();
+            if !wal_lines.is_empty() || now >= deadline {
+                return Ok(WalReplicationDelta {
+                    from_offset,
+                    next_offset: from_offset + wal_lines.len(),
+                    total_records,
+                    needs_resync: false,
+                    wal_lines,
+                });
+            }
+            std::thread::sleep(TAIL_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
     pub fn replication_export(&mut self) -> Result<WalReplicationExport, StoreError> {
         self.flush_pending_sync()?;
         Ok(WalReplicationExport {
@@ -363,11 +682,29 @@ impl FileWal {
         Ok(())
     }
 
-    fn append_record(&mut self, record: &PersistedRecord) -> Result<(), StoreError> {
+    /// Replace the snapshot and WAL with `snapshot_lines` and
+    /// `wal_lines` through [`WalBackend::install`]. Lines are assumed to
+    /// have been validated by the caller.
+    pub(crate) fn install_lines_atomic(
+        &mut self,
+        snapshot_lines: &[String],
+        wal_lines: &[String],
+    ) -> Result<(), StoreError> {
+        self.flush_pending_sync()?;
+        self.backend.install(snapshot_lines, wal_lines)?;
+        self.wal_records = wal_lines.len();
+        self.unsynced_records = 0;
+        self.last_sync_at = Instant::now();
+        self.append_buffer.clear();
+        Ok(())
+    }
+
+    pub(crate) fn append_record(&mut self, record: &PersistedRecord) -> Result<(), StoreError> {
         self.append_raw_record_line_unchecked(record_to_line(record))
     }
 
     fn append_raw_record_line_unchecked(&mut self, line: String) -> Result<(), StoreError> {
+        self.appended_bytes += line.len() as u64 + 1;
         self.append_buffer.push(line);
         self.wal_records += 1;
         self.unsynced_records += 1;
@@ -410,13 +747,8 @@ impl FileWal {
         if self.append_buffer.is_empty() {
             return Ok(());
         }
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)?;
-        for line in self.append_buffer.drain(..) {
-            writeln!(file, "{line}")?;
-        }
+        self.backend.append(&self.append_buffer)?;
+        self.append_buffer.clear();
         Ok(())
     }
 
@@ -424,158 +756,88 @@ impl FileWal {
         if self.unsynced_records == 0 && self.append_buffer.is_empty() {
             return Ok(());
         }
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)?;
-        for line in self.append_buffer.drain(..) {
-            writeln!(file, "{line}")?;
-        }
+        self.flush_append_buffer()?;
         if self.unsynced_records > 0 {
-            file.sync_data()?;
+            self.backend.sync()?;
             self.unsynced_records = 0;
             self.last_sync_at = Instant::now();
         }
         Ok(())
     }
 
-    pub(crate) fn replay_records_with_stats(
+    /// Hand every record to `apply` in log order: the snapshot, the log,
+    /// then appends still buffered. Records are parsed as the backend
+    /// reads them, one line at a time, and `apply`'s first error stops
+    /// the replay.
+    pub(crate) fn replay_with_stats(
         &self,
-    ) -> Result<(Vec<PersistedRecord>, WalReplayStats), StoreError> {
-        let snapshot_records = self.replay_snapshot_records()?;
-        let mut wal_records = self.replay_wal_records()?;
-        if !self.append_buffer.is_empty() {
-            for line in &self.append_buffer {
-                wal_records.push(line_to_record(line)?);
-            }
-        }
-        let stats = WalReplayStats {
-            snapshot_records: snapshot_records.len(),
-            wal_records: wal_records.len(),
-        };
-
-        let mut out = snapshot_records;
-        out.extend(wal_records);
-        Ok((out, stats))
-    }
-
-    fn replay_snapshot_records(&self) -> Result<Vec<PersistedRecord>, StoreError> {
-        self.replay_snapshot_lines_raw()?
-            .into_iter()
-            .map(|line| line_to_record(&line))
-            .collect()
-    }
-
-    fn replay_snapshot_lines_raw(&self) -> Result<Vec<String>, StoreError> {
-        let snapshot_path = self.snapshot_path();
-        if !snapshot_path.exists() {
-            return Ok(Vec::new());
-        }
-        let file = OpenOptions::new().read(true).open(snapshot_path)?;
-        let reader = BufReader::new(file);
-        let mut lines = reader.lines();
-        let header = loop {
-            match lines.next() {
-                Some(line) => {
-                    let line = line?;
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    break line;
-                }
-                None => {
-                    return Err(StoreError::Parse("snapshot file is empty".to_string()));
+        mut apply: impl FnMut(PersistedRecord) -> Result<(), StoreError>,
+    ) -> Result<WalReplayStats, StoreError> {
+        let mut stats = WalReplayStats::default();
+        self.backend
+            .visit_snapshot(&mut |line| match line_to_record(line)? {
+                PersistedRecord::Checkpoint(_) => Ok(()),
+                record => {
+                    stats.snapshot_records += 1;
+                    apply(record)
                 }
-            }
-        };
-        if header != SNAPSHOT_HEADER {
-            return Err(StoreError::Parse(
-                "snapshot file has invalid header".to_string(),
-            ));
+            })?;
+        self.backend.visit_from(0, &mut |line| {
+            stats.wal_records += 1;
+            apply(line_to_record(line)?)
+        })?;
+        for line in &self.append_buffer {
+            stats.wal_records += 1;
+            apply(line_to_record(line)?)?;
         }
+        Ok(stats)
+    }
 
-        let mut out = Vec::new();
-        for line in lines {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
-            }
-            out.push(line);
+    /// Visit every snapshot line and then every WAL line, buffered ones
+    /// included, with whether the line is in the snapshot.
+    pub(crate) fn visit_lines(
+        &self,
+        mut visit: impl FnMut(&str, bool) -> Result<(), StoreError>,
+    ) -> Result<(), StoreError> {
+        self.backend.visit_snapshot(&mut |line| visit(line, true))?;
+        self.backend.visit_from(0, &mut |line| visit(line, false))?;
+        for line in &self.append_buffer {
+            visit(line, false)?;
         }
-        Ok(out)
+        Ok(())
     }
 
-    fn replay_wal_records(&self) -> Result<Vec<PersistedRecord>, StoreError> {
-        self.replay_wal_lines_raw()?
-            .into_iter()
-            .map(|line| line_to_record(&line))
-            .collect()
+    fn replay_snapshot_lines_raw(&self) -> Result<Vec<String>, StoreError> {
+        self.backend.read_snapshot()
     }
 
     fn replay_wal_lines_raw(&self) -> Result<Vec<String>, StoreError> {
-        let file = OpenOptions::new().read(true).open(&self.path)?;
-        let reader = BufReader::new(file);
-        let mut out = Vec::new();
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
-            }
-            out.push(line);
-        }
-        Ok(out)
+        self.backend.read_from(0)
     }
 
-    fn write_snapshot_records(&self, records: &[PersistedRecord]) -> Result<(), StoreError> {
-        self.write_snapshot_lines_raw(&records.iter().map(record_to_line).collect::<Vec<String>>())
+    fn write_snapshot_records<'a>(
+        &mut self,
+        records: impl IntoIterator<Item = &'a PersistedRecord>,
+    ) -> Result<(), StoreError> {
+        self.write_snapshot_lines_raw(
+            &records
+                .into_iter()
+                .map(record_to_line)
+                .collect::<Vec<String>>(),
+        )
     }
 
-    fn write_snapshot_lines_raw(&self, lines: &[String]) -> Result<(), StoreError> {
-        let snapshot_path = self.snapshot_path();
-        if let Some(parent) = snapshot_path.parent()
-            && !parent.as_os_str().is_empty()
-        {
-            create_dir_all(parent)?;
-        }
-
-        let mut tmp_path = snapshot_path.clone().into_os_string();
-        tmp_path.push(".tmp");
-        let tmp_path = PathBuf::from(tmp_path);
-
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&tmp_path)?;
-        writeln!(file, "{SNAPSHOT_HEADER}")?;
-        for line in lines {
-            writeln!(file, "{line}")?;
-        }
-        file.sync_all()?;
-        rename(tmp_path, snapshot_path)?;
-        Ok(())
+    fn write_snapshot_lines_raw(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.backend.write_snapshot(lines)
     }
 
-    fn write_wal_lines_raw(&self, lines: &[String]) -> Result<(), StoreError> {
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&self.path)?;
-        for line in lines {
-            writeln!(file, "{line}")?;
-        }
-        file.sync_data()?;
-        Ok(())
+    fn write_wal_lines_raw(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.backend.replace(lines)
     }
 
     fn truncate_wal(&mut self) -> Result<(), StoreError> {
         self.append_buffer.clear();
-        OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&self.path)?;
+        self.backend.truncate_before(self.wal_records)?;
         self.wal_records = 0;
         self.unsynced_records = 0;
         self.last_sync_at = Instant::now();
@@ -588,7 +850,10 @@ impl FileWal {
     ) -> Result<WalCheckpointStats, StoreError> {
         let truncated_wal_records = self.wal_records;
         self.flush_pending_sync()?;
-        self.write_snapshot_records(snapshot_records)?;
+        let checkpoint = PersistedRecord::Checkpoint(CheckpointRecord {
+            generation: self.checkpoint_generation()? + 1,
+        });
+        self.write_snapshot_records(std::iter::once(&checkpoint).chain(snapshot_records))?;
         self.truncate_wal()?;
         Ok(WalCheckpointStats {
             snapshot_records: snapshot_records.len(),
@@ -603,24 +868,41 @@ impl Drop for FileWal {
     }
 }
 
+/// Generation of the checkpoint that wrote `snapshot_lines`, from its
+/// leading [`CheckpointRecord`]; 0 when there is none.
+pub(crate) fn snapshot_checkpoint_generation(snapshot_lines: &[String]) -> Result<u64, StoreError> {
+    match snapshot_lines
+        .first()
+        .filter(|line| line.starts_with("H\t"))
+    {
+        Some(line) => match line_to_record(line)? {
+            PersistedRecord::Checkpoint(record) => Ok(record.generation),
+            _ => Ok(0),
+        },
+        None => Ok(0),
+    }
+}
 
+/// Records in `snapshot_lines`, not counting its leading
+/// [`CheckpointRecord`].
+pub(crate) fn snapshot_record_count(snapshot_lines: &[String]) -> usize {
+    let checkpoint = snapshot_lines
+        .first()
+        .is_some_and(|line| line.starts_with("H\t"));
+    snapshot_lines.len() - usize::from(checkpoint)
+}
 
-fn count_non_empty_lines(path: &Path) -> Result<usize, StoreError> {
-    let file = OpenOptions::new().read(true).open(path)?;
-    let reader = BufReader::new(file);
-    let mut count = 0usize;
-    for line in reader.lines() {
-        let line = line?;
-        if !line.trim().is_empty() {
-            count += 1;
-        }
-    }
-    Ok(count)
+pub(crate) fn sibling_tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.to_path_buf().into_os_string();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
 }
+
 pub(crate) fn record_to_line(record: &PersistedRecord) -> String {
     match record {
         PersistedRecord::Claim(c) => format!(
-            "C\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            migrations::CLAIM.header,
             escape_field(&c.claim_id),
             escape_field(&c.tenant_id),
             escape_field(&c.canonical_text),
@@ -628,7 +910,7 @@ pub(crate) fn record_to_line(record: &PersistedRecord) -> String {
             c.event_time_unix
                 .map(|v| v.to_string())
                 .unwrap_or_else(|| "null".to_string()),
-            pack_string_list(&c.entities),
+            pack_string_list(&entity_names(&c.entities)),
             pack_string_list(&c.embedding_ids),
             c.claim_type
                 .as_ref()
@@ -645,10 +927,12 @@ pub(crate) fn record_to_line(record: &PersistedRecord) -> String {
                 .unwrap_or_else(|| "null".to_string()),
             c.updated_at
                 .map(|v| v.to_string())
-                .unwrap_or_else(|| "null".to_string())
+                .unwrap_or_else(|| "null".to_string()),
+            claim_trailing_fields(c)
         ),
         PersistedRecord::Evidence(e) => format!(
-            "E\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            migrations::EVIDENCE.header,
             escape_field(&e.evidence_id),
             escape_field(&e.claim_id),
             escape_field(&e.source_id),
@@ -674,7 +958,12 @@ pub(crate) fn record_to_line(record: &PersistedRecord) -> String {
                 .unwrap_or_else(|| "null".to_string()),
             e.ingested_at
                 .map(|v| v.to_string())
-                .unwrap_or_else(|| "null".to_string())
+                .unwrap_or_else(|| "null".to_string()),
+            e.language.as_deref().map(escape_field).unwrap_or_default(),
+            e.stance_strength
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "null".to_string()),
+            u8::from(e.negated)
         ),
         PersistedRecord::Edge(edge) => format!(
             "G\t{}\t{}\t{}\t{}\t{}",
@@ -685,9 +974,15 @@ pub(crate) fn record_to_line(record: &PersistedRecord) -> String {
             edge.strength
         ),
         PersistedRecord::ClaimVector(record) => format!(
-            "V\t{}\t{}",
+            "{}\t{}\t{}\t{}",
+            migrations::CLAIM_VECTOR.header,
             escape_field(&record.claim_id),
-            pack_f32_list(&record.values)
+            pack_f32_list(&record.values),
+            record
+                .space
+                .as_deref()
+                .map(escape_field)
+                .unwrap_or_default()
         ),
         PersistedRecord::BatchCommit(record) => format!(
             "B\t{}\t{}\t{}\t{}",
@@ -696,21 +991,226 @@ pub(crate) fn record_to_line(record: &PersistedRecord) -> String {
             record.ts_unix_ms,
             pack_string_list(&record.claim_ids)
         ),
+        PersistedRecord::TenantVectorConfig(record) => {
+            let ann_tuning = match &record.config.ann_tuning {
+                Some(tuning) => format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    tuning.max_neighbors_base,
+                    tuning.max_neighbors_upper,
+                    tuning.search_expansion_factor,
+                    tuning.search_expansion_min,
+                    tuning.search_expansion_max,
+                    tuning.index_kind.encode(),
+                    tuning.exact_search_threshold,
+                    tuning.level_multiplier,
+                    tuning.level_seed
+                ),
+                // The tuning fields after a `null` are left empty.
+                None => format!(
+                    "null{}",
+                    "\t".repeat(migrations::TENANT_VECTOR_CONFIG.field_count() - 5)
+                ),
+            };
+            format!(
+                "{}\t{}\t{}\t{}\t{}",
+                migrations::TENANT_VECTOR_CONFIG.header,
+                escape_field(&record.tenant_id),
+                record.config.dimension,
+                record.config.metric.as_str(),
+                ann_tuning
+            )
+        }
+        PersistedRecord::Checkpoint(record) => format!("H\t{}", record.generation),
+        PersistedRecord::AnnGraphHeader(record) => format!(
+            "A\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            record.version,
+            escape_field(&record.tenant_id),
+            record.levels,
+            record.max_neighbors_base,
+            record.max_neighbors_upper,
+            record
+                .entry_point
+                .as_deref()
+                .map(escape_field)
+                .unwrap_or_else(|| "null".to_string()),
+            record.entry_level
+        ),
+        PersistedRecord::AnnGraphNode(record) => {
+            let mut line = format!(
+                "N\t{}\t{}\t{}",
+                escape_field(&record.tenant_id),
+                escape_field(&record.claim_id),
+                record.node_level
+            );
+            for level in &record.neighbors {
+                line.push('\t');
+                line.push_str(&pack_string_list(level));
+            }
+            line
+        }
+        PersistedRecord::TextAnalyzer(record) => format!(
+            "{}\t{}\t{}\t{}\t{}",
+            migrations::TEXT_ANALYZER.header,
+            escape_field(&record.tenant_id),
+            u8::from(record.analyzer.stopwords),
+            u8::from(record.analyzer.stemming),
+            record.analyzer.tokenizer.encode()
+        ),
+        PersistedRecord::VectorProjection(record) => format!(
+            "P\t{}\t{}\t{}\t{}",
+            escape_field(&record.tenant_id),
+            record.projection.input_dimension(),
+            record.projection.output_dimension(),
+            pack_f32_list(record.projection.matrix())
+        ),
+        PersistedRecord::SparseVector(record) => {
+            let (terms, weights): (Vec<String>, Vec<f32>) = record
+                .vector
+                .iter()
+                .map(|(term, weight)| (term.to_string(), weight))
+                .unzip();
+            format!(
+                "S\t{}\t{}\t{}",
+                escape_field(&record.claim_id),
+                pack_string_list(&terms),
+                pack_f32_list(&weights)
+            )
+        }
+        PersistedRecord::ClaimDelete(record) => format!(
+            "D\t{}\t{}",
+            escape_field(&record.tenant_id),
+            escape_field(&record.claim_id)
+        ),
+        PersistedRecord::ClaimMerge(record) => format!(
+            "M\t{}\t{}\t{}",
+            escape_field(&record.tenant_id),
+            escape_field(&record.primary_claim_id),
+            pack_string_list(&record.duplicate_claim_ids)
+        ),
+        PersistedRecord::ClaimArchive(record) => format!(
+            "R\t{}\t{}\t{}",
+            escape_field(&record.tenant_id),
+            escape_field(&record.claim_id),
+            if record.archived { "1" } else { "0" }
+        ),
+        PersistedRecord::Document(document) => format!(
+            "F\t{}\t{}\t{}\t{}\t{}\t{}",
+            escape_field(&document.doc_id),
+            escape_field(&document.tenant_id),
+            optional_escaped_field(document.uri.as_deref()),
+            optional_escaped_field(document.hash.as_deref()),
+            document
+                .ingested_at
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "null".to_string()),
+            escape_field(&document.text)
+        ),
+        PersistedRecord::Chunk(chunk) => format!(
+            "K\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            escape_field(&chunk.chunk_id),
+            escape_field(&chunk.doc_id),
+            escape_field(&chunk.tenant_id),
+            optional_escaped_field(chunk.uri.as_deref()),
+            optional_escaped_field(chunk.hash.as_deref()),
+            chunk
+                .ingested_at
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "null".to_string()),
+            escape_field(&chunk.text)
+        ),
+        PersistedRecord::Source(source) => format!(
+            "Q\t{}\t{}\t{}\t{}\t{}\t{}",
+            escape_field(&source.source_id),
+            escape_field(&source.tenant_id),
+            escape_field(&source.name),
+            optional_escaped_field(source.category.as_deref()),
+            source.default_quality,
+            source.trust_tier.as_str()
+        ),
+    }
+}
+
+/// Fields a record line can have before [`RecordFields`] spills to the
+/// heap; only ANN node records with many levels exceed it.
+const INLINE_RECORD_FIELDS: usize = migrations::CLAIM.field_count();
+
+/// The tab-separated fields of one record line, split in place. The
+/// spill vector stays empty, and unallocated, unless the line has more
+/// than [`INLINE_RECORD_FIELDS`] fields.
+struct RecordFields<'a> {
+    inline: [&'a str; INLINE_RECORD_FIELDS],
+    len: usize,
+    spilled: Vec<&'a str>,
+}
+
+impl<'a> RecordFields<'a> {
+    fn split(line: &'a str) -> Self {
+        let mut out = RecordFields {
+            inline: [""; INLINE_RECORD_FIELDS],
+            len: 0,
+            spilled: Vec::new(),
+        };
+        let mut fields = line.split('\t');
+        while out.len < INLINE_RECORD_FIELDS {
+            match fields.next() {
+                Some(field) => out.inline[out.len] = field,
+                None => return out,
+            }
+            out.len += 1;
+        }
+        if let Some(field) = fields.next() {
+            out.spilled = out.inline.to_vec();
+            out.spilled.push(field);
+            out.spilled.extend(fields);
+        }
+        out
+    }
+
+    fn extend(&mut self, fields: &[&'a str]) {
+        for field in fields {
+            if self.spilled.is_empty() && self.len < INLINE_RECORD_FIELDS {
+                self.inline[self.len] = field;
+                self.len += 1;
+            } else {
+                if self.spilled.is_empty() {
+                    self.spilled = self.inline.to_vec();
+                }
+                self.spilled.push(field);
+            }
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for RecordFields<'a> {
+    type Target = [&'a str];
+
+    fn deref(&self) -> &Self::Target {
+        if self.spilled.is_empty() {
+            &self.inline[..self.len]
+        } else {
+            &self.spilled
+        }
     }
 }
 
 pub(crate) fn line_to_record(line: &str) -> Result<PersistedRecord, StoreError> {
-    let parts: Vec<&str> = line.split('\t').collect();
-    if parts.is_empty() {
+    let mut fields = RecordFields::split(line);
+    if fields.is_empty() {
         return Err(StoreError::Parse("empty wal record".to_string()));
     }
-    match parts[0] {
+    let (kind, version) = migrations::parse_header(fields[0])?;
+    match migrations::layout(kind) {
+        Some(layout) => fields.extend(layout.upgrade(version, fields.len())?),
+        None if version.is_some() => {
+            return Err(StoreError::Parse(
+                "wal record kind has no layout versions".to_string(),
+            ));
+        }
+        None => {}
+    }
+    let parts: &[&str] = &fields;
+    match kind {
         "C" => {
-            if !(parts.len() == 6 || parts.len() == 8 || parts.len() == 13) {
-                return Err(StoreError::Parse(
-                    "claim record has invalid field count".to_string(),
-                ));
-            }
             let event_time_unix = if parts[5] == "null" {
                 None
             } else {
@@ -718,109 +1218,66 @@ pub(crate) fn line_to_record(line: &str) -> Result<PersistedRecord, StoreError>
                     StoreError::Parse("claim record has invalid event_time".to_string())
                 })?)
             };
-            let entities = if parts.len() >= 8 {
-                unpack_string_list(parts[6])?
-            } else {
-                Vec::new()
-            };
-            let embedding_ids = if parts.len() >= 8 {
-                unpack_string_list(parts[7])?
-            } else {
-                Vec::new()
-            };
-            let claim_type = if parts.len() >= 13 {
-                parse_optional_claim_type_field(parts[8])?
-            } else {
-                None
-            };
-            let valid_from = if parts.len() >= 13 {
-                parse_optional_i64_field(parts[9], "valid_from")?
-            } else {
-                None
-            };
-            let valid_to = if parts.len() >= 13 {
-                parse_optional_i64_field(parts[10], "valid_to")?
-            } else {
-                None
-            };
-            let created_at = if parts.len() >= 13 {
-                parse_optional_i64_field(parts[11], "created_at")?
-            } else {
-                None
-            };
-            let updated_at = if parts.len() >= 13 {
-                parse_optional_i64_field(parts[12], "updated_at")?
-            } else {
-                None
-            };
-            Ok(PersistedRecord::Claim(Claim {
-                claim_id: unescape_field(parts[1])?,
-                tenant_id: unescape_field(parts[2])?,
-                canonical_text: unescape_field(parts[3])?,
-                confidence: parts[4].parse::<f32>().map_err(|_| {
+            let mut entities: Vec<Entity> = unpack_string_list(parts[6])?
+                .into_iter()
+                .map(Entity::new)
+                .collect();
+            unpack_entity_details(parts[14], &mut entities)?;
+            let mut claim = Claim::new(
+                unescape_field(parts[1])?,
+                unescape_field(parts[2])?,
+                unescape_field(parts[3])?,
+                parts[4].parse::<f32>().map_err(|_| {
                     StoreError::Parse("claim record has invalid confidence".to_string())
                 })?,
-                event_time_unix,
-                entities,
-                embedding_ids,
-                claim_type,
-                valid_from,
-                valid_to,
-                created_at,
-                updated_at,
-            }))
+            );
+            claim.event_time_unix = event_time_unix;
+            claim.entities = entities;
+            claim.embedding_ids = unpack_string_list(parts[7])?;
+            claim.claim_type = parse_optional_claim_type_field(parts[8])?;
+            claim.valid_from = parse_optional_i64_field(parts[9], "valid_from")?;
+            claim.valid_to = parse_optional_i64_field(parts[10], "valid_to")?;
+            claim.created_at = parse_optional_i64_field(parts[11], "created_at")?;
+            claim.updated_at = parse_optional_i64_field(parts[12], "updated_at")?;
+            claim.metadata = unpack_metadata(parts[13])?;
+            claim.visibility_labels = unpack_string_list(&unescape_field(parts[15])?)?;
+            claim.language = parse_optional_nonempty_field(parts[16])?;
+            claim.collection = parse_optional_nonempty_field(parts[17])?;
+            Ok(PersistedRecord::Claim(claim))
         }
         "E" => {
-            if !(parts.len() == 6 || parts.len() == 9 || parts.len() == 12) {
-                return Err(StoreError::Parse(
-                    "evidence record has invalid field count".to_string(),
-                ));
-            }
-            let chunk_id = if parts.len() >= 9 {
-                parse_optional_escaped_field(parts[6])?
-            } else {
-                None
-            };
-            let span_start = if parts.len() >= 9 {
-                parse_optional_u32_field(parts[7], "span_start")?
-            } else {
-                None
-            };
-            let span_end = if parts.len() >= 9 {
-                parse_optional_u32_field(parts[8], "span_end")?
-            } else {
-                None
-            };
-            let doc_id = if parts.len() >= 12 {
-                parse_optional_escaped_field(parts[9])?
-            } else {
-                None
-            };
-            let extraction_model = if parts.len() >= 12 {
-                parse_optional_escaped_field(parts[10])?
-            } else {
-                None
-            };
-            let ingested_at = if parts.len() >= 12 {
-                parse_optional_i64_field(parts[11], "ingested_at")?
-            } else {
-                None
-            };
-            Ok(PersistedRecord::Evidence(Evidence {
-                evidence_id: unescape_field(parts[1])?,
-                claim_id: unescape_field(parts[2])?,
-                source_id: unescape_field(parts[3])?,
-                stance: str_to_stance(parts[4])?,
-                source_quality: parts[5].parse::<f32>().map_err(|_| {
+            let mut evidence = Evidence::new(
+                unescape_field(parts[1])?,
+                unescape_field(parts[2])?,
+                unescape_field(parts[3])?,
+                str_to_stance(parts[4])?,
+                parts[5].parse::<f32>().map_err(|_| {
                     StoreError::Parse("evidence record has invalid source_quality".to_string())
                 })?,
-                chunk_id,
-                span_start,
-                span_end,
-                doc_id,
-                extraction_model,
-                ingested_at,
-            }))
+            );
+            evidence.chunk_id = parse_optional_escaped_field(parts[6])?;
+            evidence.span_start = parse_optional_u32_field(parts[7], "span_start")?;
+            evidence.span_end = parse_optional_u32_field(parts[8], "span_end")?;
+            evidence.doc_id = parse_optional_escaped_field(parts[9])?;
+            evidence.extraction_model = parse_optional_escaped_field(parts[10])?;
+            evidence.ingested_at = parse_optional_i64_field(parts[11], "ingested_at")?;
+            evidence.language = parse_optional_nonempty_field(parts[12])?;
+            evidence.stance_strength = match parts[13] {
+                "null" => None,
+                raw => Some(raw.parse::<f32>().map_err(|_| {
+                    StoreError::Parse("evidence record has invalid stance_strength".to_string())
+                })?),
+            };
+            evidence.negated = match parts[14] {
+                "0" => false,
+                "1" => true,
+                _ => {
+                    return Err(StoreError::Parse(
+                        "evidence record has invalid negated flag".to_string(),
+                    ));
+                }
+            };
+            Ok(PersistedRecord::Evidence(evidence))
         }
         "G" => {
             if parts.len() != 6 {
@@ -828,29 +1285,21 @@ pub(crate) fn line_to_record(line: &str) -> Result<PersistedRecord, StoreError>
                     "edge record has invalid field count".to_string(),
                 ));
             }
-            Ok(PersistedRecord::Edge(ClaimEdge {
-                edge_id: unescape_field(parts[1])?,
-                from_claim_id: unescape_field(parts[2])?,
-                to_claim_id: unescape_field(parts[3])?,
-                relation: str_to_relation(parts[4])?,
-                strength: parts[5].parse::<f32>().map_err(|_| {
+            Ok(PersistedRecord::Edge(ClaimEdge::new(
+                unescape_field(parts[1])?,
+                unescape_field(parts[2])?,
+                unescape_field(parts[3])?,
+                str_to_relation(parts[4])?,
+                parts[5].parse::<f32>().map_err(|_| {
                     StoreError::Parse("edge record has invalid strength".to_string())
                 })?,
-                reason_codes: vec![],
-                created_at: None,
-            }))
-        }
-        "V" => {
-            if parts.len() != 3 {
-                return Err(StoreError::Parse(
-                    "vector record has invalid field count".to_string(),
-                ));
-            }
-            Ok(PersistedRecord::ClaimVector(ClaimVectorRecord {
-                claim_id: unescape_field(parts[1])?,
-                values: unpack_f32_list(parts[2])?,
-            }))
+            )))
         }
+        "V" => Ok(PersistedRecord::ClaimVector(ClaimVectorRecord {
+            claim_id: unescape_field(parts[1])?,
+            values: unpack_f32_list(parts[2])?,
+            space: parse_optional_nonempty_field(parts[3])?,
+        })),
         "B" => {
             if parts.len() != 5 {
                 return Err(StoreError::Parse(
@@ -867,29 +1316,401 @@ pub(crate) fn line_to_record(line: &str) -> Result<PersistedRecord, StoreError>
                 commit_id: unescape_field(parts[1])?,
                 batch_size,
                 ts_unix_ms,
-                claim_ids: unpack_string_list(parts[4])?,
+                claim_ids: unpack_string_list(parts[4])?
+                    .into_iter()
+                    .map(ClaimId::from)
+                    .collect(),
+            }))
+        }
+        "T" => {
+            let dimension = parts[2].parse::<usize>().map_err(|_| {
+                StoreError::Parse("tenant vector config record has invalid dimension".to_string())
+            })?;
+            let metric = DistanceMetric::parse(parts[3]).ok_or_else(|| {
+                StoreError::Parse("tenant vector config record has invalid metric".to_string())
+            })?;
+            let ann_tuning = if parts[4] != "null" {
+                let field = |idx: usize| {
+                    parts[idx].parse::<usize>().map_err(|_| {
+                        StoreError::Parse(
+                            "tenant vector config record has invalid ann tuning".to_string(),
+                        )
+                    })
+                };
+                Some(AnnTuningConfig {
+                    max_neighbors_base: field(4)?,
+                    max_neighbors_upper: field(5)?,
+                    search_expansion_factor: field(6)?,
+                    search_expansion_min: field(7)?,
+                    search_expansion_max: field(8)?,
+                    index_kind: AnnIndexKind::parse(parts[9]).ok_or_else(|| {
+                        StoreError::Parse(
+                            "tenant vector config record has invalid ann index kind".to_string(),
+                        )
+                    })?,
+                    exact_search_threshold: field(10)?,
+                    level_multiplier: parts[11]
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|value| value.is_finite() && *value >= 0.0)
+                        .ok_or_else(|| {
+                            StoreError::Parse(
+                                "tenant vector config record has invalid ann level multiplier"
+                                    .to_string(),
+                            )
+                        })?,
+                    level_seed: parts[12].parse::<u64>().map_err(|_| {
+                        StoreError::Parse(
+                            "tenant vector config record has invalid ann level seed".to_string(),
+                        )
+                    })?,
+                })
+            } else {
+                None
+            };
+            Ok(PersistedRecord::TenantVectorConfig(
+                TenantVectorConfigRecord {
+                    tenant_id: unescape_field(parts[1])?,
+                    config: TenantVectorConfig {
+                        dimension,
+                        metric,
+                        ann_tuning,
+                    },
+                },
+            ))
+        }
+        "H" => {
+            if parts.len() != 2 {
+                return Err(StoreError::Parse(
+                    "checkpoint record has invalid field count".to_string(),
+                ));
+            }
+            Ok(PersistedRecord::Checkpoint(CheckpointRecord {
+                generation: parts[1].parse::<u64>().map_err(|_| {
+                    StoreError::Parse("checkpoint record has invalid generation".to_string())
+                })?,
+            }))
+        }
+        "A" => {
+            if parts.len() != 8 {
+                return Err(StoreError::Parse(
+                    "ann graph header record has invalid field count".to_string(),
+                ));
+            }
+            let field = |idx: usize| {
+                parts[idx].parse::<usize>().map_err(|_| {
+                    StoreError::Parse("ann graph header record has invalid number".to_string())
+                })
+            };
+            Ok(PersistedRecord::AnnGraphHeader(AnnGraphHeaderRecord {
+                version: parts[1].parse::<u32>().map_err(|_| {
+                    StoreError::Parse("ann graph header record has invalid version".to_string())
+                })?,
+                tenant_id: unescape_field(parts[2])?,
+                levels: field(3)?,
+                max_neighbors_base: field(4)?,
+                max_neighbors_upper: field(5)?,
+                entry_point: parse_optional_escaped_field(parts[6])?,
+                entry_level: field(7)?,
+            }))
+        }
+        "N" => {
+            if parts.len() < 5 {
+                return Err(StoreError::Parse(
+                    "ann graph node record has invalid field count".to_string(),
+                ));
+            }
+            let node_level = parts[3].parse::<usize>().map_err(|_| {
+                StoreError::Parse("ann graph node record has invalid level".to_string())
+            })?;
+            if parts.len() != node_level + 5 {
+                return Err(StoreError::Parse(
+                    "ann graph node record does not match its level".to_string(),
+                ));
+            }
+            Ok(PersistedRecord::AnnGraphNode(AnnGraphNodeRecord {
+                tenant_id: unescape_field(parts[1])?,
+                claim_id: unescape_field(parts[2])?,
+                node_level,
+                neighbors: parts[4..]
+                    .iter()
+                    .map(|raw| unpack_string_list(raw))
+                    .collect::<Result<_, _>>()?,
+            }))
+        }
+        "L" => {
+            let flag = |idx: usize| match parts[idx] {
+                "0" => Ok(false),
+                "1" => Ok(true),
+                _ => Err(StoreError::Parse(
+                    "text analyzer record has invalid flag".to_string(),
+                )),
+            };
+            Ok(PersistedRecord::TextAnalyzer(TextAnalyzerRecord {
+                tenant_id: unescape_field(parts[1])?,
+                analyzer: TextAnalyzer {
+                    tokenizer: TokenizerKind::parse(parts[4]).ok_or_else(|| {
+                        StoreError::Parse("text analyzer record has invalid tokenizer".to_string())
+                    })?,
+                    stopwords: flag(2)?,
+                    stemming: flag(3)?,
+                },
+            }))
+        }
+        "P" => {
+            if parts.len() != 5 {
+                return Err(StoreError::Parse(
+                    "vector projection record has invalid field count".to_string(),
+                ));
+            }
+            let field = |idx: usize| {
+                parts[idx].parse::<usize>().map_err(|_| {
+                    StoreError::Parse("vector projection record has invalid dimension".to_string())
+                })
+            };
+            let projection =
+                VectorProjection::from_matrix(field(2)?, field(3)?, unpack_f32_list(parts[4])?)
+                    .map_err(|_| {
+                        StoreError::Parse("vector projection record has invalid matrix".to_string())
+                    })?;
+            Ok(PersistedRecord::VectorProjection(VectorProjectionRecord {
+                tenant_id: unescape_field(parts[1])?,
+                projection,
+            }))
+        }
+        "S" => {
+            if parts.len() != 4 {
+                return Err(StoreError::Parse(
+                    "sparse vector record has invalid field count".to_string(),
+                ));
+            }
+            let terms = unpack_string_list(parts[2])?;
+            let weights = unpack_f32_list(parts[3])?;
+            if terms.len() != weights.len() {
+                return Err(StoreError::Parse(
+                    "sparse vector record has mismatched terms and weights".to_string(),
+                ));
+            }
+            let vector = SparseVector::new(terms.into_iter().zip(weights)).map_err(|_| {
+                StoreError::Parse("sparse vector record has invalid entries".to_string())
+            })?;
+            Ok(PersistedRecord::SparseVector(SparseVectorRecord {
+                claim_id: unescape_field(parts[1])?,
+                vector,
             }))
         }
+        "D" => {
+            if parts.len() != 3 {
+                return Err(StoreError::Parse(
+                    "claim delete record has invalid field count".to_string(),
+                ));
+            }
+            Ok(PersistedRecord::ClaimDelete(ClaimDeleteRecord {
+                tenant_id: unescape_field(parts[1])?,
+                claim_id: unescape_field(parts[2])?,
+            }))
+        }
+        "M" => {
+            if parts.len() != 4 {
+                return Err(StoreError::Parse(
+                    "claim merge record has invalid field count".to_string(),
+                ));
+            }
+            Ok(PersistedRecord::ClaimMerge(ClaimMergeRecord {
+                tenant_id: unescape_field(parts[1])?,
+                primary_claim_id: unescape_field(parts[2])?.into(),
+                duplicate_claim_ids: unpack_string_list(parts[3])?
+                    .into_iter()
+                    .map(ClaimId::from)
+                    .collect(),
+            }))
+        }
+        "R" => {
+            if parts.len() != 4 {
+                return Err(StoreError::Parse(
+                    "claim archive record has invalid field count".to_string(),
+                ));
+            }
+            let archived = match parts[3] {
+                "1" => true,
+                "0" => false,
+                _ => {
+                    return Err(StoreError::Parse(
+                        "claim archive record has invalid flag".to_string(),
+                    ));
+                }
+            };
+            Ok(PersistedRecord::ClaimArchive(ClaimArchiveRecord {
+                tenant_id: unescape_field(parts[1])?,
+                claim_id: unescape_field(parts[2])?,
+                archived,
+            }))
+        }
+        "F" => {
+            if parts.len() != 7 {
+                return Err(StoreError::Parse(
+                    "document record has invalid field count".to_string(),
+                ));
+            }
+            let mut document = Document::new(
+                unescape_field(parts[1])?,
+                unescape_field(parts[2])?,
+                unescape_field(parts[6])?,
+            );
+            document.uri = parse_optional_escaped_field(parts[3])?;
+            document.hash = parse_optional_escaped_field(parts[4])?;
+            document.ingested_at = parse_optional_i64_field(parts[5], "ingested_at")?;
+            Ok(PersistedRecord::Document(document))
+        }
+        "K" => {
+            if parts.len() != 8 {
+                return Err(StoreError::Parse(
+                    "chunk record has invalid field count".to_string(),
+                ));
+            }
+            let mut chunk = Chunk::new(
+                unescape_field(parts[1])?,
+                unescape_field(parts[2])?,
+                unescape_field(parts[3])?,
+                unescape_field(parts[7])?,
+            );
+            chunk.uri = parse_optional_escaped_field(parts[4])?;
+            chunk.hash = parse_optional_escaped_field(parts[5])?;
+            chunk.ingested_at = parse_optional_i64_field(parts[6], "ingested_at")?;
+            Ok(PersistedRecord::Chunk(chunk))
+        }
+        "Q" => {
+            if parts.len() != 7 {
+                return Err(StoreError::Parse(
+                    "source record has invalid field count".to_string(),
+                ));
+            }
+            let mut source = Source::new(
+                unescape_field(parts[1])?,
+                unescape_field(parts[2])?,
+                unescape_field(parts[3])?,
+                parts[5].parse::<f32>().map_err(|_| {
+                    StoreError::Parse("source record has invalid default_quality".to_string())
+                })?,
+            );
+            source.category = parse_optional_escaped_field(parts[4])?;
+            source.trust_tier = TrustTier::parse(parts[6]).ok_or_else(|| {
+                StoreError::Parse("source record has invalid trust_tier".to_string())
+            })?;
+            Ok(PersistedRecord::Source(source))
+        }
         _ => Err(StoreError::Parse("unknown wal record kind".to_string())),
     }
 }
 
-fn escape_field(value: &str) -> String {
+pub(crate) fn escape_field(value: &str) -> String {
     value
         .replace('\\', "\\\\")
         .replace('\t', "\\t")
         .replace('\n', "\\n")
 }
 
-fn pack_string_list(values: &[String]) -> String {
+fn pack_string_list(values: &[impl AsRef<str>]) -> String {
     let mut out = String::new();
     for value in values {
+        let value = value.as_ref();
         out.push_str(&format!("{}:", value.len()));
         out.push_str(value);
     }
     out
 }
 
+/// Metadata, entity details, visibility labels, language, and
+/// collection, each written empty when unset.
+fn claim_trailing_fields(claim: &Claim) -> String {
+    let has_details = claim
+        .entities
+        .iter()
+        .any(|entity| !entity.entity_type.is_empty() || entity.canonical_name.is_some());
+    [
+        pack_metadata(&claim.metadata),
+        if has_details {
+            pack_entity_details(&claim.entities)
+        } else {
+            String::new()
+        },
+        escape_field(&pack_string_list(&claim.visibility_labels)),
+        claim
+            .language
+            .as_deref()
+            .map(escape_field)
+            .unwrap_or_default(),
+        claim
+            .collection
+            .as_deref()
+            .map(escape_field)
+            .unwrap_or_default(),
+    ]
+    .join("\t")
+}
+
+fn entity_names(entities: &[Entity]) -> Vec<String> {
+    entities.iter().map(|entity| entity.name.clone()).collect()
+}
+
+/// Each entity's type and canonical name, in the order of the names
+/// field, packed like metadata. An absent canonical name is written
+/// empty, which validation never lets through.
+fn pack_entity_details(entities: &[Entity]) -> String {
+    let flat: Vec<String> = entities
+        .iter()
+        .flat_map(|entity| {
+            [
+                entity.entity_type.clone(),
+                entity.canonical_name.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    escape_field(&pack_string_list(&flat))
+}
+
+fn unpack_entity_details(raw: &str, entities: &mut [Entity]) -> Result<(), StoreError> {
+    if raw.is_empty() {
+        return Ok(());
+    }
+    let flat = unpack_string_list(&unescape_field(raw)?)?;
+    if flat.len() != entities.len() * 2 {
+        return Err(StoreError::Parse(
+            "claim record entity details do not match its entities".to_string(),
+        ));
+    }
+    for (entity, details) in entities.iter_mut().zip(flat.chunks_exact(2)) {
+        entity.entity_type = details[0].clone();
+        entity.canonical_name = (!details[1].is_empty()).then(|| details[1].clone());
+    }
+    Ok(())
+}
+
+/// Keys and values alternate in one packed list, escaped as a whole
+/// since packed lists do not escape tabs or newlines.
+fn pack_metadata(metadata: &[(String, String)]) -> String {
+    let flat: Vec<String> = metadata
+        .iter()
+        .flat_map(|(key, value)| [key.clone(), value.clone()])
+        .collect();
+    escape_field(&pack_string_list(&flat))
+}
+
+fn unpack_metadata(raw: &str) -> Result<Vec<(String, String)>, StoreError> {
+    let flat = unpack_string_list(&unescape_field(raw)?)?;
+    if flat.len() % 2 != 0 {
+        return Err(StoreError::Parse(
+            "claim record has unpaired metadata".to_string(),
+        ));
+    }
+    let mut flat = flat.into_iter();
+    let mut metadata = Vec::with_capacity(flat.len() / 2);
+    while let (Some(key), Some(value)) = (flat.next(), flat.next()) {
+        metadata.push((key, value));
+    }
+    Ok(metadata)
+}
+
 fn unpack_string_list(raw: &str) -> Result<Vec<String>, StoreError> {
     if raw.is_empty() {
         return Ok(Vec::new());
@@ -954,6 +1775,12 @@ fn unpack_f32_list(raw: &str) -> Result<Vec<f32>, StoreError> {
     Ok(values)
 }
 
+fn optional_escaped_field(value: Option<&str>) -> String {
+    value
+        .map(escape_field)
+        .unwrap_or_else(|| "null".to_string())
+}
+
 fn parse_optional_escaped_field(raw: &str) -> Result<Option<String>, StoreError> {
     if raw == "null" {
         return Ok(None);
@@ -961,6 +1788,14 @@ fn parse_optional_escaped_field(raw: &str) -> Result<Option<String>, StoreError>
     Ok(Some(unescape_field(raw)?))
 }
 
+/// Versioned layouts write an absent trailing string as an empty field.
+fn parse_optional_nonempty_field(raw: &str) -> Result<Option<String>, StoreError> {
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(unescape_field(raw)?))
+}
+
 fn parse_optional_u32_field(raw: &str, field: &str) -> Result<Option<u32>, StoreError> {
     if raw == "null" {
         return Ok(None);
@@ -986,33 +1821,35 @@ fn parse_optional_claim_type_field(raw: &str) -> Result<Option<ClaimType>, Store
     Ok(Some(str_to_claim_type(raw)?))
 }
 
-fn unescape_field(value: &str) -> Result<String, StoreError> {
+/// Copies the runs between escapes whole; most fields have none and are
+/// copied in one go.
+pub(crate) fn unescape_field(value: &str) -> Result<String, StoreError> {
+    if !value.contains('\\') {
+        return Ok(value.to_string());
+    }
     let mut output = String::with_capacity(value.len());
-    let mut escaped = false;
-    for ch in value.chars() {
-        if escaped {
-            match ch {
-                '\\' => output.push('\\'),
-                't' => output.push('\t'),
-                'n' => output.push('\n'),
-                other => {
-                    return Err(StoreError::Parse(format!(
-                        "invalid escape sequence: \\{other}"
-                    )));
-                }
+    let mut rest = value;
+    while let Some(at) = rest.find('\\') {
+        output.push_str(&rest[..at]);
+        let mut escape = rest[at + 1..].chars();
+        match escape.next() {
+            Some('\\') => output.push('\\'),
+            Some('t') => output.push('\t'),
+            Some('n') => output.push('\n'),
+            Some(other) => {
+                return Err(StoreError::Parse(format!(
+                    "invalid escape sequence: \\{other}"
+                )));
+            }
+            None => {
+                return Err(StoreError::Parse(
+                    "unterminated escape sequence in wal field".to_string(),
+                ));
             }
-            escaped = false;
-        } else if ch == '\\' {
-            escaped = true;
-        } else {
-            output.push(ch);
         }
+        rest = escape.as_str();
     }
-    if escaped {
-        return Err(StoreError::Parse(
-            "unterminated escape sequence in wal field".to_string(),
-        ));
-    }
+    output.push_str(rest);
     Ok(output)
 }
 
@@ -1025,13 +1862,7 @@ fn stance_to_str(stance: &Stance) -> &'static str {
 }
 
 fn claim_type_to_str(value: &ClaimType) -> &'static str {
-    match value {
-        ClaimType::Factual => "factual",
-        ClaimType::Opinion => "opinion",
-        ClaimType::Prediction => "prediction",
-        ClaimType::Temporal => "temporal",
-        ClaimType::Causal => "causal",
-    }
+    value.as_str()
 }
 
 fn str_to_claim_type(value: &str) -> Result<ClaimType, StoreError> {
@@ -1057,13 +1888,7 @@ fn str_to_stance(raw: &str) -> Result<Stance, StoreError> {
 }
 
 fn relation_to_str(relation: &Relation) -> &'static str {
-    match relation {
-        Relation::Supports => "supports",
-        Relation::Contradicts => "contradicts",
-        Relation::Refines => "refines",
-        Relation::Duplicates => "duplicates",
-        Relation::DependsOn => "depends_on",
-    }
+    relation.as_str()
 }
 
 fn str_to_relation(raw: &str) -> Result<Relation, StoreError> {
@@ -1073,6 +1898,8 @@ fn str_to_relation(raw: &str) -> Result<Relation, StoreError> {
         "refines" => Ok(Relation::Refines),
         "duplicates" => Ok(Relation::Duplicates),
         "depends_on" => Ok(Relation::DependsOn),
+        "supersedes" => Ok(Relation::Supersedes),
+        "elaborates" => Ok(Relation::Elaborates),
         _ => Err(StoreError::Parse("invalid relation in wal".to_string())),
     }
 }
@@ -0,0 +1,546 @@
+//! Storage backends for the WAL.
+//!
+//! [`FileWal`](crate::FileWal) owns record encoding, append buffering, and
+//! the sync policy; a [`WalBackend`] owns where the records live. A
+//! backend stores an ordered log of record lines and the snapshot taken
+//! at the last checkpoint. [`FileWalBackend`] keeps them in a log file and
+//! a `<path>.snapshot` sibling and is what `FileWal::open` uses;
+//! [`MemoryWalBackend`] keeps them in memory, for tests and for embedding
+//! without a disk. Other backends, such as a remote log service, plug in
+//! through `FileWal::with_backend`.
+
+use std::fs::{File, OpenOptions, create_dir_all, rename};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::StoreError;
+use crate::wal::sibling_tmp_path;
+
+const SNAPSHOT_HEADER: &str = "SNAP\t1";
+
+/// Receives record lines from [`WalBackend::visit_from`] and
+/// [`WalBackend::visit_snapshot`].
+pub type LineVisitor<'a> = dyn FnMut(&str) -> Result<(), StoreError> + 'a;
+
+/// An ordered log of WAL record lines plus the snapshot of the last
+/// checkpoint. Offsets count records from the start of the log.
+pub trait WalBackend: Send {
+    /// Append `lines` after the last record. They need not be durable
+    /// until the next [`WalBackend::sync`].
+    fn append(&mut self, lines: &[String]) -> Result<(), StoreError>;
+
+    /// Make every appended record durable.
+    fn sync(&mut self) -> Result<(), StoreError>;
+
+    /// The records from `offset` on; empty past the end.
+    fn read_from(&self, offset: usize) -> Result<Vec<String>, StoreError>;
+
+    /// Call `visit` with each record from `offset` on, in order, stopping
+    /// at its first error. Replay uses this to parse records as they are
+    /// read; the default collects them with [`WalBackend::read_from`]
+    /// first, and backends that can stream should override it.
+    fn visit_from(&self, offset: usize, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        for line in self.read_from(offset)? {
+            visit(&line)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the records before `offset`, keeping the rest in order.
+    fn truncate_before(&mut self, offset: usize) -> Result<(), StoreError>;
+
+    /// Keep only the first `len` records, discarding a failed batch.
+    fn truncate_after(&mut self, len: usize) -> Result<(), StoreError>;
+
+    /// Replace every record with `lines`.
+    fn replace(&mut self, lines: &[String]) -> Result<(), StoreError>;
+
+    fn record_count(&self) -> Result<usize, StoreError>;
+
+    /// Stored size of the log, for checkpoint policies.
+    fn size_bytes(&self) -> Result<u64, StoreError>;
+
+    /// Snapshot record lines; empty when no snapshot was written.
+    fn read_snapshot(&self) -> Result<Vec<String>, StoreError>;
+
+    /// Call `visit` with each snapshot record, as [`WalBackend::visit_from`]
+    /// does for the log.
+    fn visit_snapshot(&self, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        for line in self.read_snapshot()? {
+            visit(&line)?;
+        }
+        Ok(())
+    }
+
+    /// The first snapshot record, if any. The default reads the whole
+    /// snapshot; backends that can stop after one record should override
+    /// it.
+    fn read_snapshot_head(&self) -> Result<Option<String>, StoreError> {
+        Ok(self.read_snapshot()?.into_iter().next())
+    }
+
+    fn write_snapshot(&mut self, lines: &[String]) -> Result<(), StoreError>;
+
+    /// Replace the snapshot and the log together. Backends that can swap
+    /// both at once should override this; the default writes the
+    /// snapshot first.
+    fn install(&mut self, snapshot_lines: &[String], lines: &[String]) -> Result<(), StoreError> {
+        self.write_snapshot(snapshot_lines)?;
+        self.replace(lines)
+    }
+
+    /// The log's file, for backends that have one.
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Discard the log and snapshot of a backend that is being replaced.
+    /// The default empties both; backends with files remove them.
+    fn retire(&mut self) -> Result<(), StoreError> {
+        self.install(&[], &[])
+    }
+}
+
+/// Records in a log file, one per line, with the snapshot in
+/// `<path>.snapshot`.
+#[derive(Debug, Clone)]
+pub struct FileWalBackend {
+    path: PathBuf,
+}
+
+impl FileWalBackend {
+    /// Use the log at `path`, creating it and its directory if missing.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            create_dir_all(parent)?;
+        }
+        let backend = Self { path };
+        if backend.install_marker_path().exists() {
+            backend.finish_install()?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&backend.path)?;
+        Ok(backend)
+    }
+
+    pub fn snapshot_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".snapshot");
+        PathBuf::from(path)
+    }
+
+    /// Marks a [`WalBackend::install`] whose files are staged and must
+    /// all be renamed into place, which `open` finishes after a crash.
+    fn install_marker_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".install");
+        PathBuf::from(path)
+    }
+
+    /// Write both files to temporary siblings, then the install marker.
+    /// Until the marker exists a crash leaves the current files in use.
+    pub(crate) fn stage_install(
+        &self,
+        snapshot_lines: &[String],
+        lines: &[String],
+    ) -> Result<(), StoreError> {
+        Self::write_lines(
+            &sibling_tmp_path(&self.snapshot_path()),
+            Some(SNAPSHOT_HEADER),
+            snapshot_lines,
+        )?;
+        Self::write_lines(&sibling_tmp_path(&self.path), None, lines)?;
+        Self::write_lines(&self.install_marker_path(), None, &[])
+    }
+
+    /// Rename whichever staged files are still pending, then drop the
+    /// marker. Safe to repeat after a crash part way through.
+    fn finish_install(&self) -> Result<(), StoreError> {
+        for path in [self.snapshot_path(), self.path.clone()] {
+            let tmp_path = sibling_tmp_path(&path);
+            if tmp_path.exists() {
+                rename(tmp_path, path)?;
+            }
+        }
+        std::fs::remove_file(self.install_marker_path())?;
+        Ok(())
+    }
+
+    fn write_lines(path: &Path, header: Option<&str>, lines: &[String]) -> Result<(), StoreError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        if let Some(header) = header {
+            writeln!(file, "{header}")?;
+        }
+        for line in lines {
+            writeln!(file, "{line}")?;
+        }
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+impl WalBackend for FileWalBackend {
+    fn append(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        for line in lines {
+            writeln!(file, "{line}")?;
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), StoreError> {
+        OpenOptions::new()
+            .append(true)
+            .open(&self.path)?
+            .sync_data()?;
+        Ok(())
+    }
+
+    fn read_from(&self, offset: usize) -> Result<Vec<String>, StoreError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut out = Vec::new();
+        let mut index = 0usize;
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if index >= offset {
+                out.push(line);
+            }
+            index += 1;
+        }
+        Ok(out)
+    }
+
+    /// Reads the log through one reused line buffer.
+    fn visit_from(&self, offset: usize, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        visit_lines(reader, |index, line| {
+            if index >= offset {
+                visit(line)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn truncate_before(&mut self, offset: usize) -> Result<(), StoreError> {
+        if offset == 0 {
+            return Ok(());
+        }
+        let kept = self.read_from(offset)?;
+        if kept.is_empty() {
+            OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&self.path)?;
+            return Ok(());
+        }
+        let tmp_path = sibling_tmp_path(&self.path);
+        Self::write_lines(&tmp_path, None, &kept)?;
+        rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    fn truncate_after(&mut self, len: usize) -> Result<(), StoreError> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut kept_bytes = 0u64;
+        let mut kept = 0usize;
+        let mut line = String::new();
+        while kept < len {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            kept_bytes += read as u64;
+            if !line.trim().is_empty() {
+                kept += 1;
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&self.path)?;
+        file.set_len(kept_bytes)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn replace(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        Self::write_lines(&self.path, None, lines)
+    }
+
+    fn record_count(&self) -> Result<usize, StoreError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut count = 0usize;
+        for line in reader.lines() {
+            if !line?.trim().is_empty() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn size_bytes(&self) -> Result<u64, StoreError> {
+        Ok(std::fs::metadata(&self.path)?.len())
+    }
+
+    fn read_snapshot(&self) -> Result<Vec<String>, StoreError> {
+        let snapshot_path = self.snapshot_path();
+        if !snapshot_path.exists() {
+            return Ok(Vec::new());
+        }
+        let reader = BufReader::new(File::open(snapshot_path)?);
+        let mut lines = reader.lines();
+        let header = loop {
+            match lines.next() {
+                Some(line) => {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    break line;
+                }
+                None => {
+                    return Err(StoreError::Parse("snapshot file is empty".to_string()));
+                }
+            }
+        };
+        if header != SNAPSHOT_HEADER {
+            return Err(StoreError::Parse(
+                "snapshot file has invalid header".to_string(),
+            ));
+        }
+
+        let mut out = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            out.push(line);
+        }
+        Ok(out)
+    }
+
+    fn visit_snapshot(&self, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        let snapshot_path = self.snapshot_path();
+        if !snapshot_path.exists() {
+            return Ok(());
+        }
+        let reader = BufReader::new(File::open(snapshot_path)?);
+        let mut saw_header = false;
+        visit_lines(reader, |index, line| {
+            if index > 0 {
+                return visit(line);
+            }
+            if line != SNAPSHOT_HEADER {
+                return Err(StoreError::Parse(
+                    "snapshot file has invalid header".to_string(),
+                ));
+            }
+            saw_header = true;
+            Ok(())
+        })?;
+        if !saw_header {
+            return Err(StoreError::Parse("snapshot file is empty".to_string()));
+        }
+        Ok(())
+    }
+
+    fn read_snapshot_head(&self) -> Result<Option<String>, StoreError> {
+        let snapshot_path = self.snapshot_path();
+        if !snapshot_path.exists() {
+            return Ok(None);
+        }
+        let mut lines = BufReader::new(File::open(snapshot_path)?)
+            .lines()
+            .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()));
+        match lines.next().transpose()? {
+            Some(header) if header == SNAPSHOT_HEADER => Ok(lines.next().transpose()?),
+            Some(_) => Err(StoreError::Parse(
+                "snapshot file has invalid header".to_string(),
+            )),
+            None => Err(StoreError::Parse("snapshot file is empty".to_string())),
+        }
+    }
+
+    fn write_snapshot(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        let snapshot_path = self.snapshot_path();
+        let tmp_path = sibling_tmp_path(&snapshot_path);
+        Self::write_lines(&tmp_path, Some(SNAPSHOT_HEADER), lines)?;
+        rename(tmp_path, snapshot_path)?;
+        Ok(())
+    }
+
+    /// Both files are fully written and synced to temporary siblings,
+    /// and an install marker is synced, before either is renamed into
+    /// place. A failure before the marker leaves the current files in
+    /// use; one after it is finished by the next `open`, so a reader
+    /// never pairs the new snapshot with the old log.
+    fn install(&mut self, snapshot_lines: &[String], lines: &[String]) -> Result<(), StoreError> {
+        self.stage_install(snapshot_lines, lines)?;
+        self.finish_install()
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn retire(&mut self) -> Result<(), StoreError> {
+        for path in [self.path.clone(), self.snapshot_path()] {
+            match std::fs::remove_file(&path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemoryWalState {
+    lines: Vec<String>,
+    snapshot: Vec<String>,
+    syncs: usize,
+}
+
+/// Records held in memory. Clones share the same log, so a test can
+/// reopen a [`FileWal`](crate::FileWal) on a clone to simulate a restart.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryWalBackend {
+    state: Arc<Mutex<MemoryWalState>>,
+}
+
+impl MemoryWalBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of [`WalBackend::sync`] calls, across clones.
+    pub fn sync_count(&self) -> usize {
+        self.lock().syncs
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryWalState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl WalBackend for MemoryWalBackend {
+    fn append(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.lock().lines.extend_from_slice(lines);
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), StoreError> {
+        self.lock().syncs += 1;
+        Ok(())
+    }
+
+    fn read_from(&self, offset: usize) -> Result<Vec<String>, StoreError> {
+        Ok(self.lock().lines.iter().skip(offset).cloned().collect())
+    }
+
+    fn visit_from(&self, offset: usize, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        self.lock()
+            .lines
+            .iter()
+            .skip(offset)
+            .try_for_each(|line| visit(line))
+    }
+
+    fn truncate_before(&mut self, offset: usize) -> Result<(), StoreError> {
+        let mut state = self.lock();
+        let offset = offset.min(state.lines.len());
+        state.lines.drain(..offset);
+        Ok(())
+    }
+
+    fn truncate_after(&mut self, len: usize) -> Result<(), StoreError> {
+        self.lock().lines.truncate(len);
+        Ok(())
+    }
+
+    fn replace(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.lock().lines = lines.to_vec();
+        Ok(())
+    }
+
+    fn record_count(&self) -> Result<usize, StoreError> {
+        Ok(self.lock().lines.len())
+    }
+
+    fn size_bytes(&self) -> Result<u64, StoreError> {
+        Ok(self
+            .lock()
+            .lines
+            .iter()
+            .map(|line| line.len() as u64 + 1)
+            .sum())
+    }
+
+    fn read_snapshot(&self) -> Result<Vec<String>, StoreError> {
+        Ok(self.lock().snapshot.clone())
+    }
+
+    fn read_snapshot_head(&self) -> Result<Option<String>, StoreError> {
+        Ok(self.lock().snapshot.first().cloned())
+    }
+
+    fn visit_snapshot(&self, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        self.lock().snapshot.iter().try_for_each(|line| visit(line))
+    }
+
+    fn write_snapshot(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.lock().snapshot = lines.to_vec();
+        Ok(())
+    }
+
+    fn install(&mut self, snapshot_lines: &[String], lines: &[String]) -> Result<(), StoreError> {
+        let mut state = self.lock();
+        state.snapshot = snapshot_lines.to_vec();
+        state.lines = lines.to_vec();
+        Ok(())
+    }
+}
+
+/// Call `visit` with the index and text of each non-blank line, without
+/// its line ending, reading every line into the same buffer.
+fn visit_lines(
+    mut reader: impl BufRead,
+    mut visit: impl FnMut(usize, &str) -> Result<(), StoreError>,
+) -> Result<(), StoreError> {
+    let mut buffer = String::new();
+    let mut index = 0usize;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+        let line = buffer.trim_end_matches(['\n', '\r']);
+        if line.trim().is_empty() {
+            continue;
+        }
+        visit(index, line)?;
+        index += 1;
+    }
+}
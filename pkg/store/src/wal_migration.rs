@@ -0,0 +1,319 @@
+//! Moving a WAL from one storage backend to another without downtime.
+//!
+//! A new backend is adopted in three steps. [`WalMigration::start`]
+//! copies the current log and snapshot into the new backend, and the WAL
+//! is reopened on [`WalMigration::dual_write_backend`], which writes every
+//! record, truncation, and checkpoint to both backends while reads keep
+//! coming from the old one. While both are written,
+//! [`WalMigration::verify`] replays each log into its own store and
+//! compares the claims, evidence, edges, and vectors they hold. Once the
+//! configured dual-write period has passed and the logs agree,
+//! [`WalMigration::cutover`] retires the old log and hands back the new
+//! backend to reopen the WAL on.
+//!
+//! Every backend stores the same record lines; only where they live
+//! differs. Changing the record encoding itself is not a migration this
+//! module performs.
+//!
+//! The old backend stays authoritative. A write the new backend fails is
+//! not retried and does not fail the WAL write, which would have the
+//! caller append the same records to the old log again; the new backend
+//! is marked diverged instead and gets no further writes. Verification
+//! reports the divergence and cutover refuses it until
+//! [`WalMigration::resync`] copies the old log over again.
+//!
+//! Backends are cloned into the dual-write backend, so both must be
+//! handles onto shared storage, as [`FileWalBackend`](crate::FileWalBackend)
+//! and [`MemoryWalBackend`](crate::MemoryWalBackend) are.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use schema::ClaimId;
+
+use crate::wal::snapshot_record_count;
+use crate::{FileWal, InMemoryStore, LineVisitor, StoreError, WalBackend, WalWritePolicy};
+
+/// Why the new backend stopped receiving writes, shared by a migration
+/// and its dual-write backends.
+#[derive(Debug, Clone, Default)]
+struct Divergence(Arc<Mutex<Option<String>>>);
+
+impl Divergence {
+    fn lock(&self) -> MutexGuard<'_, Option<String>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A [`WalBackend`] writing to an old and a new backend at once. Reads
+/// are served by the old backend.
+#[derive(Debug, Clone)]
+pub struct DualWriteWalBackend<P, S> {
+    primary: P,
+    secondary: S,
+    divergence: Divergence,
+}
+
+impl<P, S: WalBackend> DualWriteWalBackend<P, S> {
+    /// Why writes to the new backend stopped, if they did.
+    pub fn divergence(&self) -> Option<String> {
+        self.divergence.lock().clone()
+    }
+
+    /// Repeat a write the old backend took on the new one, unless it has
+    /// already diverged. A failure marks it diverged rather than failing
+    /// the write.
+    fn mirror(&mut self, write: impl FnOnce(&mut S) -> Result<(), StoreError>) {
+        let mut divergence = self.divergence.lock();
+        if divergence.is_none()
+            && let Err(err) = write(&mut self.secondary)
+        {
+            *divergence = Some(err.to_string());
+        }
+    }
+}
+
+impl<P: WalBackend, S: WalBackend> WalBackend for DualWriteWalBackend<P, S> {
+    fn append(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.primary.append(lines)?;
+        self.mirror(|secondary| secondary.append(lines));
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), StoreError> {
+        self.primary.sync()?;
+        self.mirror(|secondary| secondary.sync());
+        Ok(())
+    }
+
+    fn read_from(&self, offset: usize) -> Result<Vec<String>, StoreError> {
+        self.primary.read_from(offset)
+    }
+
+    fn visit_from(&self, offset: usize, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        self.primary.visit_from(offset, visit)
+    }
+
+    fn truncate_before(&mut self, offset: usize) -> Result<(), StoreError> {
+        self.primary.truncate_before(offset)?;
+        self.mirror(|secondary| secondary.truncate_before(offset));
+        Ok(())
+    }
+
+    fn truncate_after(&mut self, len: usize) -> Result<(), StoreError> {
+        self.primary.truncate_after(len)?;
+        self.mirror(|secondary| secondary.truncate_after(len));
+        Ok(())
+    }
+
+    fn replace(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.primary.replace(lines)?;
+        self.mirror(|secondary| secondary.replace(lines));
+        Ok(())
+    }
+
+    fn record_count(&self) -> Result<usize, StoreError> {
+        self.primary.record_count()
+    }
+
+    fn size_bytes(&self) -> Result<u64, StoreError> {
+        self.primary.size_bytes()
+    }
+
+    fn read_snapshot(&self) -> Result<Vec<String>, StoreError> {
+        self.primary.read_snapshot()
+    }
+
+    fn visit_snapshot(&self, visit: &mut LineVisitor<'_>) -> Result<(), StoreError> {
+        self.primary.visit_snapshot(visit)
+    }
+
+    fn read_snapshot_head(&self) -> Result<Option<String>, StoreError> {
+        self.primary.read_snapshot_head()
+    }
+
+    fn write_snapshot(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.primary.write_snapshot(lines)?;
+        self.mirror(|secondary| secondary.write_snapshot(lines));
+        Ok(())
+    }
+
+    fn install(&mut self, snapshot_lines: &[String], lines: &[String]) -> Result<(), StoreError> {
+        self.primary.install(snapshot_lines, lines)?;
+        self.mirror(|secondary| secondary.install(snapshot_lines, lines));
+        Ok(())
+    }
+
+    fn path(&self) -> Option<&Path> {
+        self.primary.path()
+    }
+}
+
+/// What [`WalMigration::verify`] found replaying both logs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DualWriteVerification {
+    /// Snapshot plus log records in the old and the new backend.
+    pub primary_records: usize,
+    pub secondary_records: usize,
+    /// Claims whose claim, evidence, edges, or vector differ between the
+    /// two replayed stores, sorted.
+    pub mismatched_claim_ids: Vec<ClaimId>,
+    /// Why writes to the new backend stopped, if they did; it then misses
+    /// records whether or not any claim differs yet.
+    pub divergence: Option<String>,
+}
+
+impl DualWriteVerification {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatched_claim_ids.is_empty() && self.divergence.is_none()
+    }
+}
+
+/// A WAL moving from the `primary` backend to the `secondary` one.
+#[derive(Debug, Clone)]
+pub struct WalMigration<P, S> {
+    primary: P,
+    secondary: S,
+    dual_write_period: Duration,
+    started_unix_ms: u64,
+    divergence: Divergence,
+}
+
+impl<P, S> WalMigration<P, S>
+where
+    P: WalBackend + Clone + 'static,
+    S: WalBackend + Clone + 'static,
+{
+    /// Begin migrating from `primary` to `secondary`, writing both for at
+    /// least `dual_write_period`. An empty `secondary` is seeded with
+    /// `primary`'s snapshot and log; one already holding records is taken
+    /// to be a migration in progress and left as it is.
+    pub fn start(
+        primary: P,
+        mut secondary: S,
+        dual_write_period: Duration,
+    ) -> Result<Self, StoreError> {
+        if secondary.record_count()? == 0 && secondary.read_snapshot()?.is_empty() {
+            secondary.install(&primary.read_snapshot()?, &primary.read_from(0)?)?;
+        }
+        Ok(Self {
+            primary,
+            secondary,
+            dual_write_period,
+            started_unix_ms: unix_ms_now(),
+            divergence: Divergence::default(),
+        })
+    }
+
+    /// Copy the old backend's snapshot and log over the new one again and
+    /// clear its divergence, restarting the dual-write period. Stop
+    /// writing through the dual-write backend first.
+    pub fn resync(&mut self) -> Result<(), StoreError> {
+        let mut divergence = self.divergence.lock();
+        self.secondary
+            .install(&self.primary.read_snapshot()?, &self.primary.read_from(0)?)?;
+        *divergence = None;
+        self.started_unix_ms = unix_ms_now();
+        Ok(())
+    }
+
+    /// When a resumed migration started dual-writing, so the period
+    /// counts from then rather than from this process's start.
+    pub fn with_started_unix_ms(mut self, started_unix_ms: u64) -> Self {
+        self.started_unix_ms = started_unix_ms;
+        self
+    }
+
+    pub fn started_unix_ms(&self) -> u64 {
+        self.started_unix_ms
+    }
+
+    pub fn dual_write_period_elapsed(&self) -> bool {
+        let period_ms = u64::try_from(self.dual_write_period.as_millis()).unwrap_or(u64::MAX);
+        unix_ms_now() >= self.started_unix_ms.saturating_add(period_ms)
+    }
+
+    /// The backend to open the WAL on while the migration runs.
+    pub fn dual_write_backend(&self) -> DualWriteWalBackend<P, S> {
+        DualWriteWalBackend {
+            primary: self.primary.clone(),
+            secondary: self.secondary.clone(),
+            divergence: self.divergence.clone(),
+        }
+    }
+
+    /// Replay both logs and compare what they hold. Records still in a
+    /// WAL's append buffer are not seen; flush the WAL first.
+    pub fn verify(&self) -> Result<DualWriteVerification, StoreError> {
+        let (primary, primary_records) = replay(self.primary.clone())?;
+        let (secondary, secondary_records) = replay(self.secondary.clone())?;
+        let claim_ids: BTreeSet<&ClaimId> = primary
+            .claims
+            .keys()
+            .chain(secondary.claims.keys())
+            .collect();
+        let mismatched_claim_ids = claim_ids
+            .into_iter()
+            .filter(|claim_id| {
+                primary.claims.get(claim_id.as_str()) != secondary.claims.get(claim_id.as_str())
+                    || primary.evidence_by_claim.get(claim_id.as_str())
+                        != secondary.evidence_by_claim.get(claim_id.as_str())
+                    || primary.edges_by_claim.get(claim_id.as_str())
+                        != secondary.edges_by_claim.get(claim_id.as_str())
+                    || primary.claim_vectors.get(claim_id.as_str())
+                        != secondary.claim_vectors.get(claim_id.as_str())
+            })
+            .cloned()
+            .collect();
+        Ok(DualWriteVerification {
+            primary_records,
+            secondary_records,
+            mismatched_claim_ids,
+            divergence: self.divergence.lock().clone(),
+        })
+    }
+
+    /// Retire the old log and return the new backend. Fails with
+    /// [`StoreError::Conflict`], leaving both logs in place, while the
+    /// dual-write period is running or when the logs disagree. Stop
+    /// writing through the dual-write backend before cutting over.
+    pub fn cutover(mut self) -> Result<S, StoreError> {
+        if !self.dual_write_period_elapsed() {
+            return Err(StoreError::Conflict(
+                "WAL dual-write period has not elapsed".to_string(),
+            ));
+        }
+        if let Some(reason) = self.divergence.lock().clone() {
+            return Err(StoreError::Conflict(format!(
+                "WAL dual-write backend diverged: {reason}"
+            )));
+        }
+        let verification = self.verify()?;
+        if !verification.is_consistent() {
+            return Err(StoreError::Conflict(format!(
+                "WAL dual-write logs disagree on {} claims",
+                verification.mismatched_claim_ids.len()
+            )));
+        }
+        self.primary.retire()?;
+        Ok(self.secondary)
+    }
+}
+
+/// Load a store from `backend`, returning it and how many snapshot and
+/// log records it holds.
+fn replay(backend: impl WalBackend + 'static) -> Result<(InMemoryStore, usize), StoreError> {
+    let records = snapshot_record_count(&backend.read_snapshot()?) + backend.record_count()?;
+    let wal = FileWal::with_backend(backend, WalWritePolicy::default())?;
+    Ok((InMemoryStore::load_from_wal(&wal)?, records))
+}
+
+fn unix_ms_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
@@ -0,0 +1,223 @@
+//! Read replicas that follow a leader's WAL.
+//!
+//! A [`WalFollower`] opens the log another process writes, builds its
+//! own [`InMemoryStore`] from the snapshot and the log, and then applies
+//! new records as [`FileWal::tail_from`] finds them, so reads can be
+//! spread over replicas while one leader takes writes. Offsets are
+//! record positions in the log, which a leader checkpoint rewrites: the
+//! follower remembers the last record it applied and rebuilds from the
+//! snapshot whenever that record is no longer where it left it, or,
+//! before it has applied any, whenever the leader checkpoints again.
+//!
+//! A new follower need not replay the leader's history either: the
+//! leader ships its current state with [`InMemoryStore::ship_snapshot`],
+//! tagged with the log offset and checkpoint generation it covers, and
+//! [`WalFollower::bootstrap`] loads that and tails on from the offset.
+//! The shipment is a header line, one record per line as in the WAL,
+//! and, when the log is not empty, the last log record the state covers
+//! so the follower can tell whether the log has since been rewritten.
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::wal::{record_to_line, snapshot_checkpoint_generation};
+use crate::{FileWal, InMemoryStore, StoreError};
+
+/// First field of a shipment's header line.
+const SHIPMENT_MAGIC: &str = "SHIP";
+const SHIPMENT_VERSION: &str = "2";
+
+/// What [`InMemoryStore::ship_snapshot`] wrote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotShipment {
+    /// Log offset the follower tails from.
+    pub start_offset: usize,
+    /// Checkpoint generation of the leader's snapshot at that offset.
+    pub checkpoint_generation: u64,
+    pub records: usize,
+}
+
+/// What one [`WalFollower::poll`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WalFollowOutcome {
+    /// Records applied, counting the whole snapshot and log on a resync.
+    pub applied: usize,
+    /// The store was rebuilt because the leader compacted its log.
+    pub resynced: bool,
+}
+
+pub struct WalFollower {
+    wal: FileWal,
+    store: InMemoryStore,
+    next_offset: usize,
+    /// The record at `next_offset - 1`, to notice the log being
+    /// rewritten under the follower.
+    last_line: Option<String>,
+    /// Checkpoint generation of the snapshot at the last resync.
+    checkpoint_generation: u64,
+}
+
+impl WalFollower {
+    /// Follow the log at `path`, loading what it holds so far.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let mut follower = Self {
+            wal: FileWal::open(path)?,
+            store: InMemoryStore::new(),
+            next_offset: 0,
+            last_line: None,
+            checkpoint_generation: 0,
+        };
+        follower.resync()?;
+        Ok(follower)
+    }
+
+    /// Follow the log at `path` from a shipment written by
+    /// [`InMemoryStore::ship_snapshot`], read from `shipment`.
+    pub fn bootstrap(path: impl AsRef<Path>, shipment: impl BufRead) -> Result<Self, StoreError> {
+        let wal = FileWal::open(path)?;
+        let mut lines = shipment.lines();
+        let header = lines
+            .next()
+            .transpose()?
+            .ok_or_else(|| StoreError::Parse("snapshot shipment is empty".to_string()))?;
+        let invalid_header =
+            || StoreError::Parse("snapshot shipment has invalid header".to_string());
+        let fields: Vec<&str> = header.split('\t').collect();
+        let [
+            SHIPMENT_MAGIC,
+            SHIPMENT_VERSION,
+            start_offset,
+            checkpoint_generation,
+            records,
+        ] = fields[..]
+        else {
+            return Err(invalid_header());
+        };
+        let start_offset: usize = start_offset.parse().map_err(|_| invalid_header())?;
+        let checkpoint_generation: u64 = checkpoint_generation
+            .parse()
+            .map_err(|_| invalid_header())?;
+        let records: usize = records.parse().map_err(|_| invalid_header())?;
+
+        let mut store = InMemoryStore::new();
+        for _ in 0..records {
+            let line = lines
+                .next()
+                .transpose()?
+                .ok_or_else(|| StoreError::Parse("snapshot shipment ended early".to_string()))?;
+            store.apply_persisted_record_line(&line)?;
+        }
+        store.finish_ann_graph_restore();
+        let last_line = if start_offset > 0 {
+            let line = lines.next().transpose()?.ok_or_else(|| {
+                StoreError::Parse("snapshot shipment has no log anchor".to_string())
+            })?;
+            Some(line)
+        } else {
+            None
+        };
+        Ok(Self {
+            wal,
+            store,
+            next_offset: start_offset,
+            last_line,
+            checkpoint_generation,
+        })
+    }
+
+    /// The replica, current as of the last poll.
+    pub fn store(&self) -> &InMemoryStore {
+        &self.store
+    }
+
+    /// Offset of the next record to apply.
+    pub fn next_offset(&self) -> usize {
+        self.next_offset
+    }
+
+    /// Wait up to `timeout` for new records and apply them. When a
+    /// record fails to apply, the records before it stay applied and the
+    /// next poll starts again from the failed one.
+    pub fn poll(&mut self, timeout: Duration) -> Result<WalFollowOutcome, StoreError> {
+        let delta = self.wal.tail_from(self.next_offset, timeout)?;
+        if delta.needs_resync || !self.last_applied_still_in_place()? {
+            return self.resync();
+        }
+        for line in &delta.wal_lines {
+            self.store.apply_persisted_record_line(line)?;
+            self.next_offset += 1;
+            self.last_line = Some(line.clone());
+        }
+        Ok(WalFollowOutcome {
+            applied: delta.wal_lines.len(),
+            resynced: false,
+        })
+    }
+
+    /// Whether the log still holds the last applied record where it was
+    /// applied from; a checkpoint since then moves or drops it.
+    fn last_applied_still_in_place(&self) -> Result<bool, StoreError> {
+        let Some(last_line) = self.last_line.as_ref() else {
+            let boundary = self.wal.replay_boundary()?;
+            return Ok(boundary.checkpoint_generation == self.checkpoint_generation);
+        };
+        let delta = self.wal.tail_from(self.next_offset - 1, Duration::ZERO)?;
+        Ok(!delta.needs_resync && delta.wal_lines.first() == Some(last_line))
+    }
+
+    /// Rebuild the store from the leader's snapshot and whole log.
+    fn resync(&mut self) -> Result<WalFollowOutcome, StoreError> {
+        let export = self.wal.replication_export()?;
+        let mut store = InMemoryStore::new();
+        for line in export.snapshot_lines.iter().chain(&export.wal_lines) {
+            store.apply_persisted_record_line(line)?;
+        }
+        store.finish_ann_graph_restore();
+        self.store = store;
+        self.next_offset = export.wal_lines.len();
+        self.last_line = export.wal_lines.last().cloned();
+        self.checkpoint_generation = snapshot_checkpoint_generation(&export.snapshot_lines)?;
+        Ok(WalFollowOutcome {
+            applied: export.snapshot_lines.len() + export.wal_lines.len(),
+            resynced: true,
+        })
+    }
+}
+
+impl InMemoryStore {
+    /// Write this store's state to `out` for [`WalFollower::bootstrap`].
+    /// The store must be the one writing `wal`, with every write so far
+    /// recorded there, so the state matches the log offset shipped.
+    pub fn ship_snapshot(
+        &self,
+        wal: &mut FileWal,
+        out: &mut impl Write,
+    ) -> Result<SnapshotShipment, StoreError> {
+        wal.flush_pending_sync()?;
+        let start_offset = wal.wal_record_count()?;
+        let checkpoint_generation = wal.checkpoint_generation()?;
+        let anchor = match start_offset.checked_sub(1) {
+            Some(last) => wal.replication_delta_from(last, 1)?.wal_lines.pop(),
+            None => None,
+        };
+        let records = self.snapshot_records();
+        writeln!(
+            out,
+            "{SHIPMENT_MAGIC}\t{SHIPMENT_VERSION}\t{start_offset}\t{checkpoint_generation}\t{}",
+            records.len()
+        )?;
+        for record in &records {
+            writeln!(out, "{}", record_to_line(record))?;
+        }
+        if let Some(anchor) = anchor {
+            writeln!(out, "{anchor}")?;
+        }
+        out.flush()?;
+        Ok(SnapshotShipment {
+            start_offset,
+            checkpoint_generation,
+            records: records.len(),
+        })
+    }
+}
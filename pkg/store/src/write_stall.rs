@@ -0,0 +1,115 @@
+//! Write flow control tied to the checkpoint backlog.
+//!
+//! A [`CheckpointPolicy`] asks for a checkpoint once the WAL passes a
+//! record or byte threshold. When checkpoints keep failing or cannot keep
+//! up, as on a slow or full disk, nothing else bounds the WAL, and every
+//! record in it is replayed on restart. The backlog is measured as the
+//! WAL's size over the threshold it is furthest past; once it reaches the
+//! policy's slowdown multiple, [`InMemoryStore::throttle_write`] asks
+//! each write to wait, longer the closer the backlog is to the stop
+//! multiple, and from the stop multiple on it rejects writes with
+//! [`StoreError::WriteStalled`] until a checkpoint brings the WAL back
+//! under it. A stopped write first attempts that checkpoint itself, so a
+//! disk that has recovered unblocks the next write.
+//!
+//! The delay is returned rather than slept so that a caller sharing the
+//! store behind a lock can wait it out after releasing the lock, instead
+//! of stalling every reader and writer along with the slowed one.
+
+use std::time::Duration;
+
+use crate::{CheckpointPolicy, FileWal, InMemoryStore, StoreError};
+
+/// Delay of a write just below the stop multiple, or of every slowed
+/// write when the policy has no stop multiple.
+pub(crate) const WRITE_SLOWDOWN_MAX_DELAY: Duration = Duration::from_millis(100);
+
+/// What [`CheckpointPolicy::write_stall`] asks of the next write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteStall {
+    /// The backlog is below the slowdown multiple.
+    Clear,
+    /// Delay the write by this long.
+    Slowdown(Duration),
+    /// Reject the write.
+    Stop,
+}
+
+impl CheckpointPolicy {
+    /// How many times the WAL holds its record or byte threshold,
+    /// whichever is larger; `0.0` when neither threshold is set.
+    pub fn checkpoint_backlog(&self, wal: &FileWal) -> Result<f64, StoreError> {
+        let mut backlog: f64 = 0.0;
+        if let Some(threshold) = self.max_wal_records.filter(|threshold| *threshold > 0) {
+            backlog = backlog.max(wal.wal_record_count()? as f64 / threshold as f64);
+        }
+        if let Some(threshold) = self.max_wal_bytes.filter(|threshold| *threshold > 0) {
+            backlog = backlog.max(wal.wal_size_bytes()? as f64 / threshold as f64);
+        }
+        Ok(backlog)
+    }
+
+    pub fn write_stall(&self, wal: &FileWal) -> Result<WriteStall, StoreError> {
+        Ok(self.write_stall_at(self.checkpoint_backlog(wal)?))
+    }
+
+    fn write_stall_at(&self, backlog: f64) -> WriteStall {
+        let stop = self.write_stop_multiple.filter(|multiple| *multiple > 0);
+        if stop.is_some_and(|stop| backlog >= f64::from(stop)) {
+            return WriteStall::Stop;
+        }
+        let Some(slowdown) = self
+            .write_slowdown_multiple
+            .filter(|multiple| *multiple > 0)
+        else {
+            return WriteStall::Clear;
+        };
+        let slowdown = f64::from(slowdown);
+        if backlog < slowdown {
+            return WriteStall::Clear;
+        }
+        let fraction = match stop.map(f64::from) {
+            Some(stop) if stop > slowdown => (backlog - slowdown) / (stop - slowdown),
+            _ => 1.0,
+        };
+        WriteStall::Slowdown(WRITE_SLOWDOWN_MAX_DELAY.mul_f64(fraction.clamp(0.0, 1.0)))
+    }
+}
+
+impl InMemoryStore {
+    /// Apply `policy`'s write flow control before a write to `wal`:
+    /// return how long a slowed write should wait, or, at the stop
+    /// multiple, checkpoint and fail with [`StoreError::WriteStalled`] if
+    /// the backlog is still there. Writes under a policy without write
+    /// multiples pass straight through with no delay.
+    pub fn throttle_write(
+        &self,
+        wal: &mut FileWal,
+        policy: &CheckpointPolicy,
+    ) -> Result<Option<Duration>, StoreError> {
+        match policy.write_stall(wal)? {
+            WriteStall::Clear => Ok(None),
+            WriteStall::Slowdown(delay) => {
+                self.metrics.record_write_slowdown(delay);
+                Ok(Some(delay))
+            }
+            WriteStall::Stop => {
+                let checkpoint_error = self.checkpoint_and_compact(wal).err();
+                let backlog = policy.checkpoint_backlog(wal)?;
+                if policy.write_stall_at(backlog) != WriteStall::Stop {
+                    return Ok(None);
+                }
+                self.metrics.record_write_stop();
+                let cause = match checkpoint_error {
+                    Some(err) => format!("checkpoint failed: {err}"),
+                    None => "checkpoint did not shrink it".to_string(),
+                };
+                Err(StoreError::WriteStalled(format!(
+                    "WAL backlog is {backlog:.1}x the checkpoint threshold, at or past the \
+                     stop multiple of {}; {cause}",
+                    policy.write_stop_multiple.unwrap_or_default()
+                )))
+            }
+        }
+    }
+}
@@ -18,14 +18,15 @@
 //! store crate.
 
 use schema::{
-    Claim, ClaimEdge, Evidence, Relation, RetrievalRequest, Stance, StanceMode,
+    Claim, ClaimEdge, ClaimEdgeBuilder, ClaimId, Evidence, EvidenceBuilder, Relation,
+    RetrievalRequest, Stance, StanceMode,
 };
 use store::{AnnTuningConfig, FileWal, InMemoryStore, WalWritePolicy};
 use tempfile::TempDir;
 fn make_claim(id: &str, tenant: &str, text: &str, confidence: f32) -> Claim {
     Claim {
-        claim_id: id.to_string(),
-        tenant_id: tenant.to_string(),
+        claim_id: id.into(),
+        tenant_id: tenant.into(),
         canonical_text: text.to_string(),
         confidence,
         event_time_unix: None,
@@ -36,41 +37,26 @@ fn make_claim(id: &str, tenant: &str, text: &str, confidence: f32) -> Claim {
         valid_to: None,
         created_at: None,
         updated_at: None,
+        metadata: Vec::new(),
+        visibility_labels: Vec::new(),
+        language: None,
+        collection: None,
     }
 }
 
-fn make_evidence(
-    id: &str,
-    claim_id: &str,
-    source: &str,
-    stance: Stance,
-    quality: f32,
-) -> Evidence {
-    Evidence {
-        evidence_id: id.to_string(),
-        claim_id: claim_id.to_string(),
-        source_id: source.to_string(),
-        stance,
-        source_quality: quality,
-        chunk_id: None,
-        span_start: None,
-        span_end: None,
-        doc_id: None,
-        extraction_model: None,
-        ingested_at: None,
-    }
+fn make_evidence(id: &str, claim_id: &str, source: &str, stance: Stance, quality: f32) -> Evidence {
+    EvidenceBuilder::new(id, claim_id, source)
+        .with_stance(stance)
+        .with_source_quality(quality)
+        .build()
+        .unwrap()
 }
 
 fn make_edge(id: &str, from: &str, to: &str, relation: Relation, strength: f32) -> ClaimEdge {
-    ClaimEdge {
-        edge_id: id.to_string(),
-        from_claim_id: from.to_string(),
-        to_claim_id: to.to_string(),
-        relation,
-        strength,
-        reason_codes: vec![],
-        created_at: None,
-    }
+    ClaimEdgeBuilder::new(id, from, to, relation)
+        .with_strength(strength)
+        .build()
+        .unwrap()
 }
 
 // ---------------------------------------------------------------------------
@@ -85,12 +71,11 @@ fn ingest_then_retrieve_returns_ingested_claim() {
 
     store.ingest_bundle(claim, vec![evidence], vec![]).unwrap();
 
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "Company X acquired Company Y".into(),
-        top_k: 5,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new(
+        "t1",
+        "Company X acquired Company Y",
+        5,
+    ));
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].claim_id, "c1");
     assert!(results[0].score > 0.0);
@@ -103,19 +88,14 @@ fn ingest_then_retrieve_returns_ingested_claim() {
 fn retrieve_returns_empty_for_unknown_tenant() {
     let mut store = InMemoryStore::new();
     store
-        .ingest_bundle(
-            make_claim("c1", "t1", "secret claim", 0.9),
-            vec![],
-            vec![],
-        )
+        .ingest_bundle(make_claim("c1", "t1", "secret claim", 0.9), vec![], vec![])
         .unwrap();
 
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "different-tenant".into(),
-        query: "secret claim".into(),
-        top_k: 5,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new(
+        "different-tenant",
+        "secret claim",
+        5,
+    ));
     assert!(results.is_empty(), "must not leak across tenants");
 }
 
@@ -137,18 +117,16 @@ fn cross_tenant_isolation_holds_for_ingest_and_retrieve() {
             .unwrap();
     }
 
-    let results_a = store.retrieve(&RetrievalRequest {
-        tenant_id: "tenant-a".into(),
-        query: "shared canonical text".into(),
-        top_k: 10,
-        stance_mode: StanceMode::Balanced,
-    });
-    let results_b = store.retrieve(&RetrievalRequest {
-        tenant_id: "tenant-b".into(),
-        query: "shared canonical text".into(),
-        top_k: 10,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results_a = store.retrieve(&RetrievalRequest::new(
+        "tenant-a",
+        "shared canonical text",
+        10,
+    ));
+    let results_b = store.retrieve(&RetrievalRequest::new(
+        "tenant-b",
+        "shared canonical text",
+        10,
+    ));
 
     assert_eq!(results_a.len(), 1);
     assert_eq!(results_b.len(), 1);
@@ -174,20 +152,24 @@ fn vector_search_is_tenant_isolated() {
         )
         .unwrap();
     store
-        .upsert_claim_vector("c-a", vec![1.0, 0.0, 0.0])
+        .upsert_claim_vector(&"c-a".into(), vec![1.0, 0.0, 0.0])
         .unwrap();
     store
-        .upsert_claim_vector("c-b", vec![0.0, 1.0, 0.0])
+        .upsert_claim_vector(&"c-b".into(), vec![0.0, 1.0, 0.0])
         .unwrap();
 
-    let hits_a = store.exact_vector_top_candidates("tenant-a", &[1.0, 0.0, 0.0], 5);
-    let hits_b = store.exact_vector_top_candidates("tenant-b", &[1.0, 0.0, 0.0], 5);
-    assert!(hits_a.contains(&"c-a".to_string()));
-    assert!(!hits_a.contains(&"c-b".to_string()),
-        "tenant A must not see tenant B vectors");
-    assert!(hits_b.contains(&"c-b".to_string()));
-    assert!(!hits_b.contains(&"c-a".to_string()),
-        "tenant B must not see tenant A vectors");
+    let hits_a = store.exact_vector_top_candidates(&"tenant-a".into(), &[1.0, 0.0, 0.0], 5);
+    let hits_b = store.exact_vector_top_candidates(&"tenant-b".into(), &[1.0, 0.0, 0.0], 5);
+    assert!(hits_a.contains(&"c-a".into()));
+    assert!(
+        !hits_a.contains(&"c-b".into()),
+        "tenant A must not see tenant B vectors"
+    );
+    assert!(hits_b.contains(&"c-b".into()));
+    assert!(
+        !hits_b.contains(&"c-a".into()),
+        "tenant B must not see tenant A vectors"
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -206,12 +188,7 @@ fn temporal_event_time_filter_excludes_older_claims() {
     store.ingest_bundle(recent, vec![], vec![]).unwrap();
 
     let results = store.retrieve_with_time_range(
-        &RetrievalRequest {
-            tenant_id: "t1".into(),
-            query: "claim".into(),
-            top_k: 10,
-            stance_mode: StanceMode::Balanced,
-        },
+        &RetrievalRequest::new("t1", "claim", 10),
         Some(150),
         Some(300),
     );
@@ -233,12 +210,7 @@ fn temporal_validity_window_inclusive() {
     store.ingest_bundle(outside, vec![], vec![]).unwrap();
 
     let results = store.retrieve_with_time_range(
-        &RetrievalRequest {
-            tenant_id: "t1".into(),
-            query: "claim".into(),
-            top_k: 10,
-            stance_mode: StanceMode::Balanced,
-        },
+        &RetrievalRequest::new("t1", "claim", 10),
         Some(120),
         Some(180),
     );
@@ -304,15 +276,19 @@ fn support_only_drops_claim_with_more_contradictions_than_supports() {
         )
         .unwrap();
 
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "claim".into(),
-        top_k: 10,
-        stance_mode: StanceMode::SupportOnly,
-    });
+    let results = store.retrieve(
+        &RetrievalRequest::new("t1", "claim", 10).with_stance_mode(StanceMode::SupportOnly),
+    );
     // The two contradicted claims should be filtered out; "clean" should remain
-    assert_eq!(results.len(), 1, "support-only must drop contradicted claims, got: {:?}",
-        results.iter().map(|r| (&r.claim_id, r.supports, r.contradicts)).collect::<Vec<_>>());
+    assert_eq!(
+        results.len(),
+        1,
+        "support-only must drop contradicted claims, got: {:?}",
+        results
+            .iter()
+            .map(|r| (&r.claim_id, r.supports, r.contradicts))
+            .collect::<Vec<_>>()
+    );
     assert_eq!(results[0].claim_id, "clean");
 }
 
@@ -339,12 +315,7 @@ fn balanced_mode_keeps_contradicted_claims_with_neutral_score() {
     }
     store.ingest_bundle(claim, evidence, vec![]).unwrap();
 
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "claim".into(),
-        top_k: 10,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new("t1", "claim", 10));
     // Balanced mode does NOT filter contradicted claims; the count is exposed
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].contradicts, 2);
@@ -362,13 +333,7 @@ fn edge_contradicts_evidence_increments_contradict_count() {
     store
         .ingest_bundle(
             make_claim("c1", "t1", "claim one", 0.9),
-            vec![make_evidence(
-                "e1",
-                "c1",
-                "src",
-                Stance::Supports,
-                0.9,
-            )],
+            vec![make_evidence("e1", "c1", "src", Stance::Supports, 0.9)],
             vec![],
         )
         .unwrap();
@@ -377,13 +342,7 @@ fn edge_contradicts_evidence_increments_contradict_count() {
     store
         .ingest_bundle(
             make_claim("c2", "t1", "claim two", 0.9),
-            vec![make_evidence(
-                "e2",
-                "c2",
-                "src",
-                Stance::Supports,
-                0.9,
-            )],
+            vec![make_evidence("e2", "c2", "src", Stance::Supports, 0.9)],
             vec![edge],
         )
         .unwrap();
@@ -391,14 +350,13 @@ fn edge_contradicts_evidence_increments_contradict_count() {
     // Edges contribute to a claim's contradiction count in scoring
     // (via the edge-summary path in the store). The top hit for "claim one"
     // is c1; c1 should have supports >= 1 from its evidence.
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "claim one".into(),
-        top_k: 10,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new("t1", "claim one", 10));
     let c1 = results.iter().find(|r| r.claim_id == "c1").unwrap();
-    assert!(c1.supports >= 1, "evidence supports must be counted, got {}", c1.supports);
+    assert!(
+        c1.supports >= 1,
+        "evidence supports must be counted, got {}",
+        c1.supports
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -411,21 +369,27 @@ fn ann_and_exact_return_consistent_top_hit() {
     for i in 0..20 {
         let id = format!("c{i}");
         let vector = vec![(i as f32) * 0.05, 1.0 - (i as f32) * 0.05, 0.5];
-        store.ingest_bundle(
-            make_claim(&id, "t1", &format!("claim {i} text"), 0.9),
-            vec![],
-            vec![],
-        ).unwrap();
-        store.upsert_claim_vector(&id, vector).unwrap();
+        store
+            .ingest_bundle(
+                make_claim(&id, "t1", &format!("claim {i} text"), 0.9),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .upsert_claim_vector(&ClaimId::from(&id), vector)
+            .unwrap();
     }
     let query = vec![0.0, 1.0, 0.5];
-    let exact = store.exact_vector_top_candidates("t1", &query, 5);
-    let ann = store.ann_vector_top_candidates("t1", &query, 5);
+    let exact = store.exact_vector_top_candidates(&"t1".into(), &query, 5);
+    let ann = store.ann_vector_top_candidates(&"t1".into(), &query, 5);
     assert!(!exact.is_empty());
     assert!(!ann.is_empty());
-    assert_eq!(exact[0], ann[0],
+    assert_eq!(
+        exact[0], ann[0],
         "top hit must match between ANN and exact: exact={:?} ann={:?}",
-        exact, ann);
+        exact, ann
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -442,7 +406,9 @@ fn retrieve_with_query_vector_prefers_aligned_claim() {
             vec![],
         )
         .unwrap();
-    store.upsert_claim_vector("aligned", vec![1.0, 0.0, 0.0]).unwrap();
+    store
+        .upsert_claim_vector(&"aligned".into(), vec![1.0, 0.0, 0.0])
+        .unwrap();
     store
         .ingest_bundle(
             make_claim("orthogonal", "t1", "this one does not", 0.9),
@@ -451,16 +417,11 @@ fn retrieve_with_query_vector_prefers_aligned_claim() {
         )
         .unwrap();
     store
-        .upsert_claim_vector("orthogonal", vec![0.0, 1.0, 0.0])
+        .upsert_claim_vector(&"orthogonal".into(), vec![0.0, 1.0, 0.0])
         .unwrap();
 
     let results = store.retrieve_with_time_range_and_query_vector(
-        &RetrievalRequest {
-            tenant_id: "t1".into(),
-            query: "matches".into(),
-            top_k: 5,
-            stance_mode: StanceMode::Balanced,
-        },
+        &RetrievalRequest::new("t1", "matches", 5),
         None,
         None,
         Some(&[1.0, 0.0, 0.0]),
@@ -479,13 +440,7 @@ fn higher_confidence_with_supports_ranks_above_lower_confidence_with_contradicts
     store
         .ingest_bundle(
             make_claim("strong", "t1", "strong evidence-backed claim", 0.95),
-            vec![make_evidence(
-                "es",
-                "strong",
-                "src",
-                Stance::Supports,
-                0.95,
-            )],
+            vec![make_evidence("es", "strong", "src", Stance::Supports, 0.95)],
             vec![],
         )
         .unwrap();
@@ -503,12 +458,7 @@ fn higher_confidence_with_supports_ranks_above_lower_confidence_with_contradicts
         )
         .unwrap();
 
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "claim".into(),
-        top_k: 5,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new("t1", "claim", 5));
     assert_eq!(results.len(), 2);
     assert_eq!(results[0].claim_id, "strong", "strong should rank first");
     assert!(results[0].score > results[1].score);
@@ -543,17 +493,10 @@ fn wal_persistence_and_replay_round_trip() {
 
     // Reopen and verify the claim + evidence round-tripped
     let wal2 = FileWal::open(&wal_path).unwrap();
-    let (store2, _stats) = InMemoryStore::load_from_wal_with_stats_and_ann_tuning(
-        &wal2,
-        AnnTuningConfig::default(),
-    )
-    .unwrap();
-    let results = store2.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "restart".into(),
-        top_k: 5,
-        stance_mode: StanceMode::Balanced,
-    });
+    let (store2, _stats) =
+        InMemoryStore::load_from_wal_with_stats_and_ann_tuning(&wal2, AnnTuningConfig::default())
+            .unwrap();
+    let results = store2.retrieve(&RetrievalRequest::new("t1", "restart", 5));
     assert_eq!(results.len(), 1, "WAL replay should restore the claim");
     assert_eq!(results[0].claim_id, "persistent");
     assert_eq!(results[0].supports, 1, "evidence should replay too");
@@ -577,23 +520,28 @@ fn wal_checkpoint_compacts_state() {
             .unwrap();
     }
     let stats = store.checkpoint_and_compact(&mut wal).unwrap();
-    assert!(stats.snapshot_records > 0, "snapshot should contain the 5 claims");
+    assert!(
+        stats.snapshot_records > 0,
+        "snapshot should contain the 5 claims"
+    );
     // After checkpoint the WAL is truncated; its record count drops to 0.
-    assert_eq!(wal.wal_record_count().unwrap(), 0,
-        "WAL record count should be 0 after checkpoint, got {}", wal.wal_record_count().unwrap());
+    assert_eq!(
+        wal.wal_record_count().unwrap(),
+        0,
+        "WAL record count should be 0 after checkpoint, got {}",
+        wal.wal_record_count().unwrap()
+    );
     drop(wal);
     drop(store);
 
     // Reopen and verify all 5 claims are present
     let wal2 = FileWal::open(&wal_path).unwrap();
-    let (store2, _) = InMemoryStore::load_from_wal_with_stats_and_ann_tuning(
-        &wal2,
-        AnnTuningConfig::default(),
-    )
-    .unwrap();
+    let (store2, _) =
+        InMemoryStore::load_from_wal_with_stats_and_ann_tuning(&wal2, AnnTuningConfig::default())
+            .unwrap();
     for i in 0..5 {
         let id = format!("c{i}");
-        let claim = store2.claim_by_id(&id);
+        let claim = store2.claim_by_id(&ClaimId::from(&id));
         assert!(claim.is_some(), "claim {id} must be in snapshot");
     }
 }
@@ -602,12 +550,7 @@ fn wal_checkpoint_compacts_state() {
 fn wal_with_custom_write_policy_does_not_lose_records() {
     let tmp = TempDir::new().unwrap();
     let wal_path = tmp.path().join("custom-policy.wal");
-    let policy = WalWritePolicy {
-        sync_every_records: 1,
-        append_buffer_max_records: 1,
-        sync_interval: None,
-        background_flush_only: false,
-    };
+    let policy = WalWritePolicy::new(1, 1);
     let mut wal = FileWal::open_with_policy(&wal_path, policy).unwrap();
     let mut store = InMemoryStore::new();
     for i in 0..3 {
@@ -624,13 +567,11 @@ fn wal_with_custom_write_policy_does_not_lose_records() {
     drop(store);
 
     let wal2 = FileWal::open(&wal_path).unwrap();
-    let (store2, _) = InMemoryStore::load_from_wal_with_stats_and_ann_tuning(
-        &wal2,
-        AnnTuningConfig::default(),
-    )
-    .unwrap();
+    let (store2, _) =
+        InMemoryStore::load_from_wal_with_stats_and_ann_tuning(&wal2, AnnTuningConfig::default())
+            .unwrap();
     for i in 0..3 {
-        assert!(store2.claim_by_id(&format!("c{i}")).is_some());
+        assert!(store2.claim_by_id(&format!("c{i}").into()).is_some());
     }
 }
 
@@ -641,12 +582,7 @@ fn wal_with_custom_write_policy_does_not_lose_records() {
 #[test]
 fn empty_store_returns_no_results() {
     let store = InMemoryStore::new();
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "any".into(),
-        query: "anything".into(),
-        top_k: 10,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new("any", "anything", 10));
     assert!(results.is_empty());
 }
 
@@ -662,13 +598,12 @@ fn empty_query_returns_all_tenant_claims() {
             )
             .unwrap();
     }
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "".into(),
-        top_k: 10,
-        stance_mode: StanceMode::Balanced,
-    });
-    assert_eq!(results.len(), 3, "empty query should fall back to all tenant claims");
+    let results = store.retrieve(&RetrievalRequest::new("t1", "", 10));
+    assert_eq!(
+        results.len(),
+        3,
+        "empty query should fall back to all tenant claims"
+    );
 }
 
 #[test]
@@ -683,12 +618,7 @@ fn top_k_limits_results() {
             )
             .unwrap();
     }
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: "t1".into(),
-        query: "claim".into(),
-        top_k: 3,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new("t1", "claim", 3));
     assert_eq!(results.len(), 3);
 }
 
@@ -696,39 +626,33 @@ fn top_k_limits_results() {
 fn claim_id_reuse_across_tenants_is_rejected() {
     let mut store = InMemoryStore::new();
     store
-        .ingest_bundle(
-            make_claim("c1", "t1", "first", 0.9),
-            vec![],
-            vec![],
-        )
+        .ingest_bundle(make_claim("c1", "t1", "first", 0.9), vec![], vec![])
         .unwrap();
     let err = store
-        .ingest_bundle(
-            make_claim("c1", "t2", "collision", 0.9),
-            vec![],
-            vec![],
-        )
+        .ingest_bundle(make_claim("c1", "t2", "collision", 0.9), vec![], vec![])
         .unwrap_err();
-    assert!(matches!(err, store::StoreError::Conflict(_)),
-        "claim_id reuse across tenants must be rejected, got {err:?}");
+    assert!(
+        matches!(err, store::StoreError::Conflict(_)),
+        "claim_id reuse across tenants must be rejected, got {err:?}"
+    );
 }
 
 #[test]
 fn dim_mismatch_on_vector_update_is_rejected() {
     let mut store = InMemoryStore::new();
     store
-        .ingest_bundle(
-            make_claim("c1", "t1", "claim", 0.9),
-            vec![],
-            vec![],
-        )
+        .ingest_bundle(make_claim("c1", "t1", "claim", 0.9), vec![], vec![])
+        .unwrap();
+    store
+        .upsert_claim_vector(&"c1".into(), vec![1.0, 0.0, 0.0])
         .unwrap();
-    store.upsert_claim_vector("c1", vec![1.0, 0.0, 0.0]).unwrap();
     let err = store
-        .upsert_claim_vector("c1", vec![1.0, 0.0])
+        .upsert_claim_vector(&"c1".into(), vec![1.0, 0.0])
         .unwrap_err();
-    assert!(matches!(err, store::StoreError::InvalidVector(_)),
-        "dimension mismatch must be rejected, got {err:?}");
+    assert!(
+        matches!(err, store::StoreError::InvalidVector(_)),
+        "dimension mismatch must be rejected, got {err:?}"
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -753,7 +677,6 @@ fn index_stats_reports_correct_tenant_count() {
     assert!(stats.temporal_buckets <= 1);
 }
 
-
 // ---------------------------------------------------------------------------
 // Semantic-first retrieval
 //
@@ -771,33 +694,35 @@ fn retrieve_semantic_ranks_aligned_claim_first() {
     let mut store = InMemoryStore::new();
     for (id, text, vec) in [
         ("c-aligned", "company acquired target", vec![1.0, 0.0, 0.0]),
-        ("c-orthogonal", "weather forecast sunny", vec![0.0, 1.0, 0.0]),
+        (
+            "c-orthogonal",
+            "weather forecast sunny",
+            vec![0.0, 1.0, 0.0],
+        ),
         ("c-tangential", "blue whale migration", vec![0.0, 0.0, 1.0]),
     ] {
         store
-            .ingest_bundle(
-                make_claim(id, "t1", text, 0.9),
-                vec![],
-                vec![],
-            )
+            .ingest_bundle(make_claim(id, "t1", text, 0.9), vec![], vec![])
             .unwrap();
-        store.upsert_claim_vector(id, vec).unwrap();
+        store.upsert_claim_vector(&ClaimId::from(id), vec).unwrap();
     }
 
     // A query vector aligned with c-aligned. The semantic-first path
     // must rank it first.
     let results = store.retrieve_semantic(
-        &RetrievalRequest {
-            tenant_id: "t1".into(),
-            query: "acquisition news".into(),
-            top_k: 3,
-            stance_mode: StanceMode::Balanced,
-        },
+        &RetrievalRequest::new("t1", "acquisition news", 3),
         &[1.0, 0.0, 0.0],
     );
-    assert_eq!(results.len(), 3, "semantic-first should still return all candidates");
-    assert_eq!(results[0].claim_id, "c-aligned",
-        "semantic-first must rank the aligned claim first, got {:?}", results);
+    assert_eq!(
+        results.len(),
+        3,
+        "semantic-first should still return all candidates"
+    );
+    assert_eq!(
+        results[0].claim_id, "c-aligned",
+        "semantic-first must rank the aligned claim first, got {:?}",
+        results
+    );
 }
 
 #[test]
@@ -815,7 +740,7 @@ fn retrieve_semantic_uses_dense_similarity_as_primary_signal() {
         )
         .unwrap();
     store
-        .upsert_claim_vector("lexical-only", vec![0.0, 1.0, 0.0])
+        .upsert_claim_vector(&"lexical-only".into(), vec![0.0, 1.0, 0.0])
         .unwrap();
     store
         .ingest_bundle(
@@ -825,26 +750,23 @@ fn retrieve_semantic_uses_dense_similarity_as_primary_signal() {
         )
         .unwrap();
     store
-        .upsert_claim_vector("semantic-only", vec![1.0, 0.0, 0.0])
+        .upsert_claim_vector(&"semantic-only".into(), vec![1.0, 0.0, 0.0])
         .unwrap();
 
     // Query: "acquisition target" (lexical match) + a vector aligned
     // with [1, 0, 0] (semantic match for "semantic-only").
     let results = store.retrieve_semantic(
-        &RetrievalRequest {
-            tenant_id: "t1".into(),
-            query: "acquisition target".into(),
-            top_k: 2,
-            stance_mode: StanceMode::Balanced,
-        },
+        &RetrievalRequest::new("t1", "acquisition target", 2),
         &[1.0, 0.0, 0.0],
     );
     // semantic-only is the dense-aligned claim; lexical-only is the
     // lexically-aligned one. With semantic-first scoring, semantic-only
     // must rank first.
-    assert_eq!(results[0].claim_id, "semantic-only",
+    assert_eq!(
+        results[0].claim_id, "semantic-only",
         "semantic-first must prefer dense alignment over lexical match, got {:?}",
-        results);
+        results
+    );
 }
 
 #[test]
@@ -862,22 +784,23 @@ fn retrieve_semantic_with_tenant_isolation_filters_other_tenants() {
                 )
                 .unwrap();
             store
-                .upsert_claim_vector(&id, vec![1.0, 0.0, 0.0])
+                .upsert_claim_vector(&ClaimId::from(&id), vec![1.0, 0.0, 0.0])
                 .unwrap();
         }
     }
     let results = store.retrieve_semantic(
-        &RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "claim".into(),
-            top_k: 10,
-            stance_mode: StanceMode::Balanced,
-        },
+        &RetrievalRequest::new("tenant-a", "claim", 10),
         &[1.0, 0.0, 0.0],
     );
-    assert!(results.iter().all(|r| r.claim_id.contains("tenant-a")),
-        "semantic-first must not leak across tenants, got {:?}", results);
-    assert!(!results.is_empty(), "should return at least one tenant-a result");
+    assert!(
+        results.iter().all(|r| r.claim_id.contains("tenant-a")),
+        "semantic-first must not leak across tenants, got {:?}",
+        results
+    );
+    assert!(
+        !results.is_empty(),
+        "should return at least one tenant-a result"
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -923,26 +846,20 @@ fn disk_persistence_round_trip() {
     //    should give us all 10 claims; the WAL tail (if any) is
     //    replayed over the snapshot.
     let mut wal2 = FileWal::open(&wal_path).unwrap();
-    let (store2, _stats) = InMemoryStore::load_from_disk_and_wal(
-        &disk_path,
-        &mut wal2,
-        AnnTuningConfig::default(),
-    )
-    .expect("disk + WAL cold-start should succeed");
+    let (store2, _stats) =
+        InMemoryStore::load_from_disk_and_wal(&disk_path, &mut wal2, AnnTuningConfig::default())
+            .expect("disk + WAL cold-start should succeed");
     assert_eq!(store2.claims_len(), 10, "all 10 claims must be loaded");
     for i in 0..10 {
         let id = format!("c{i}");
         let claim = store2
-            .claim_by_id(&id)
+            .claim_by_id(&ClaimId::from(&id))
             .unwrap_or_else(|| panic!("claim {id} should be in the snapshot"));
         assert_eq!(claim.canonical_text, format!("claim {i} text"));
     }
 
     // 3. Disk status is Available after a successful cold-start.
-    assert!(matches!(
-        store2.disk_status(),
-        store::DiskStatus::Available
-    ));
+    assert!(matches!(store2.disk_status(), store::DiskStatus::Available));
 }
 
 #[test]
@@ -1021,25 +938,25 @@ fn disk_tenant_set_membership_consistency() {
 
     // 2. Reopen and check tenant-set membership.
     let mut wal2 = FileWal::open(&wal_path).unwrap();
-    let (store2, _stats) = InMemoryStore::load_from_disk_and_wal(
-        &disk_path,
-        &mut wal2,
-        AnnTuningConfig::default(),
-    )
-    .expect("cold-start should succeed");
-
-    assert!(store2.claim_by_id("c-a-0").is_some());
-    assert!(store2.claim_by_id("c-a-1").is_some());
-    assert!(store2.claim_by_id("c-a-2").is_some());
-    assert!(store2.claim_by_id("c-b-0").is_some());
-    assert!(store2.claim_by_id("c-b-1").is_some());
+    let (store2, _stats) =
+        InMemoryStore::load_from_disk_and_wal(&disk_path, &mut wal2, AnnTuningConfig::default())
+            .expect("cold-start should succeed");
+
+    assert!(store2.claim_by_id(&"c-a-0".into()).is_some());
+    assert!(store2.claim_by_id(&"c-a-1".into()).is_some());
+    assert!(store2.claim_by_id(&"c-a-2".into()).is_some());
+    assert!(store2.claim_by_id(&"c-b-0".into()).is_some());
+    assert!(store2.claim_by_id(&"c-b-1".into()).is_some());
     // A claim from neither tenant must not be present.
-    assert!(store2.claim_by_id("c-c-0").is_none());
+    assert!(store2.claim_by_id(&"c-c-0".into()).is_none());
 
     // 3. The `tenant_ids` API must report both tenants (in
     //    sorted order) and only those tenants.
     let tenant_ids = store2.tenant_ids();
-    assert_eq!(tenant_ids, vec!["tenant-a".to_string(), "tenant-b".to_string()]);
+    assert_eq!(
+        tenant_ids,
+        vec!["tenant-a".to_string(), "tenant-b".to_string()]
+    );
 }
 
 #[test]
@@ -1075,7 +992,7 @@ fn disk_bulk_load_rebuilds_ann_index() {
             vec![angle.cos(), angle.sin(), 0.0, 0.0]
         };
         store
-            .upsert_claim_vector_persistent(&mut wal, &id, vector)
+            .upsert_claim_vector_persistent(&mut wal, &ClaimId::from(&id), vector)
             .unwrap();
     }
     drop(wal);
@@ -1084,19 +1001,19 @@ fn disk_bulk_load_rebuilds_ann_index() {
     // 2. Reopen via disk + WAL and verify ANN search returns the
     //    right top hit.
     let mut wal2 = FileWal::open(&wal_path).unwrap();
-    let (store2, _stats) = InMemoryStore::load_from_disk_and_wal(
-        &disk_path,
-        &mut wal2,
-        AnnTuningConfig::default(),
-    )
-    .expect("cold-start should succeed");
+    let (store2, _stats) =
+        InMemoryStore::load_from_disk_and_wal(&disk_path, &mut wal2, AnnTuningConfig::default())
+            .expect("cold-start should succeed");
 
     let query = [0.99, 0.01, 0.0, 0.0];
-    let ann = store2.ann_vector_top_candidates("t1", &query, 1);
-    let exact = store2.exact_vector_top_candidates("t1", &query, 1);
-    assert_eq!(ann.first().map(String::as_str), Some("c0"));
-    assert_eq!(exact.first().map(String::as_str), Some("c0"));
-    assert_eq!(ann, exact, "ANN and exact must agree after a disk cold-start");
+    let ann = store2.ann_vector_top_candidates(&"t1".into(), &query, 1);
+    let exact = store2.exact_vector_top_candidates(&"t1".into(), &query, 1);
+    assert_eq!(ann.first().map(ClaimId::as_str), Some("c0"));
+    assert_eq!(exact.first().map(ClaimId::as_str), Some("c0"));
+    assert_eq!(
+        ann, exact,
+        "ANN and exact must agree after a disk cold-start"
+    );
 }
 
 #[test]
@@ -1110,7 +1027,9 @@ fn disk_open_failure_does_not_crash_service() {
 
     // 1. `with_disk` must not panic.
     let mut store = InMemoryStore::new();
-    store = store.with_disk(&invalid_path).expect("with_disk returns Ok");
+    store = store
+        .with_disk(&invalid_path)
+        .expect("with_disk returns Ok");
     // 2. `disk_status()` must be `Unavailable` (not panic, not
     //    `Recovering`, not `Available`).
     assert!(
@@ -1188,15 +1107,12 @@ fn disk_clone_preserves_disk_handle_and_shares_storage() {
     //    present. This proves the clone's disk writes actually
     //    landed in the shared redb.
     let mut wal3 = FileWal::open(&wal_path).unwrap();
-    let (reloaded, _stats) = InMemoryStore::load_from_disk_and_wal(
-        &disk_path,
-        &mut wal3,
-        AnnTuningConfig::default(),
-    )
-    .expect("cold start should succeed");
+    let (reloaded, _stats) =
+        InMemoryStore::load_from_disk_and_wal(&disk_path, &mut wal3, AnnTuningConfig::default())
+            .expect("cold start should succeed");
     assert_eq!(reloaded.claims_len(), 2);
-    assert!(reloaded.claim_by_id("c-via-clone").is_some());
-    assert!(reloaded.claim_by_id("c-original").is_some());
+    assert!(reloaded.claim_by_id(&"c-via-clone".into()).is_some());
+    assert!(reloaded.claim_by_id(&"c-original".into()).is_some());
 }
 
 #[test]
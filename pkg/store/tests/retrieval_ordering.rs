@@ -0,0 +1,156 @@
+//! Property tests for the documented retrieval result order: score
+//! descending, then claim confidence descending, then claim id
+//! ascending.
+//!
+//! Each case builds a random corpus with deliberate score ties (repeated
+//! texts and confidences), ingests it into two stores in different
+//! orders, and checks that every retrieve path returns results sorted by
+//! that key and identical across both stores.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use schema::{Claim, RetrievalRequest};
+use store::InMemoryStore;
+
+const CASES: u64 = 48;
+const TEXTS: [&str; 3] = [
+    "Company X acquired Company Y",
+    "Company X opened an office",
+    "Regulators reviewed the acquisition",
+];
+const CONFIDENCES: [f32; 3] = [0.5, 0.7, 0.9];
+
+struct Corpus {
+    claims: Vec<Claim>,
+    vectors: Vec<(String, Vec<f32>)>,
+}
+
+fn random_corpus(rng: &mut StdRng) -> Corpus {
+    let count = rng.gen_range(2..40);
+    let mut claims = Vec::with_capacity(count);
+    let mut vectors = Vec::with_capacity(count);
+    for idx in 0..count {
+        let claim_id = format!("claim-{:03}", rng.gen_range(0..1000) * 100 + idx);
+        let text = TEXTS[rng.gen_range(0..TEXTS.len())];
+        let confidence = CONFIDENCES[rng.gen_range(0..CONFIDENCES.len())];
+        // Few distinct vectors, so dense scores tie too.
+        let vector = match rng.gen_range(0..3) {
+            0 => vec![1.0, 0.0, 0.0],
+            1 => vec![0.0, 1.0, 0.0],
+            _ => vec![0.6, 0.8, 0.0],
+        };
+        vectors.push((claim_id.clone(), vector));
+        claims.push(Claim {
+            claim_id: claim_id.into(),
+            tenant_id: "tenant-a".into(),
+            canonical_text: text.to_string(),
+            confidence,
+            event_time_unix: None,
+            entities: vec![],
+            embedding_ids: vec![],
+            claim_type: None,
+            valid_from: None,
+            valid_to: None,
+            created_at: None,
+            updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
+        });
+    }
+    Corpus { claims, vectors }
+}
+
+fn build_store(corpus: &Corpus, order: &[usize]) -> InMemoryStore {
+    let mut store = InMemoryStore::new();
+    for &idx in order {
+        store
+            .ingest_bundle(corpus.claims[idx].clone(), vec![], vec![])
+            .unwrap();
+    }
+    for &idx in order {
+        let (claim_id, vector) = &corpus.vectors[idx];
+        store
+            .upsert_claim_vector(&claim_id.into(), vector.clone())
+            .unwrap();
+    }
+    store
+}
+
+fn assert_canonical_order(store: &InMemoryStore, ranked: &[(String, f32)]) {
+    for pair in ranked.windows(2) {
+        let (a_id, a_score) = &pair[0];
+        let (b_id, b_score) = &pair[1];
+        let a_conf = store.claim_by_id(&a_id.into()).unwrap().confidence;
+        let b_conf = store.claim_by_id(&b_id.into()).unwrap().confidence;
+        let ordered = a_score > b_score
+            || (a_score == b_score && (a_conf > b_conf || (a_conf == b_conf && a_id < b_id)));
+        assert!(
+            ordered,
+            "{a_id} ({a_score}, {a_conf}) must rank before {b_id} ({b_score}, {b_conf})"
+        );
+    }
+}
+
+#[test]
+fn retrieve_paths_follow_canonical_order_and_ignore_insertion_order() {
+    for seed in 0..CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let corpus = random_corpus(&mut rng);
+        let mut order: Vec<usize> = (0..corpus.claims.len()).collect();
+        let forward = build_store(&corpus, &order);
+        order.shuffle(&mut rng);
+        let shuffled = build_store(&corpus, &order);
+
+        let req = RetrievalRequest::new(
+            "tenant-a",
+            "Company X acquisition",
+            rng.gen_range(1..=corpus.claims.len()),
+        );
+        let query_vector = [0.6, 0.8, 0.0];
+
+        for store in [&forward, &shuffled] {
+            let lexical: Vec<(String, f32)> = store
+                .retrieve(&req)
+                .into_iter()
+                .map(|result| (result.claim_id.into_string(), result.score))
+                .collect();
+            assert_canonical_order(store, &lexical);
+
+            let semantic: Vec<(String, f32)> = store
+                .retrieve_semantic(&req, &query_vector)
+                .into_iter()
+                .map(|result| (result.claim_id.into_string(), result.score))
+                .collect();
+            assert_canonical_order(store, &semantic);
+
+            let hits: Vec<(String, f32)> = store
+                .retrieve_hits(&req, None, None, Some(&query_vector))
+                .into_iter()
+                .map(|hit| (hit.claim_id.into_string(), hit.score))
+                .collect();
+            assert_eq!(hits, semantic, "seed {seed}");
+        }
+
+        let ids = |store: &InMemoryStore| -> Vec<String> {
+            store
+                .retrieve_semantic(&req, &query_vector)
+                .into_iter()
+                .map(|result| result.claim_id.into_string())
+                .collect()
+        };
+        assert_eq!(ids(&forward), ids(&shuffled), "seed {seed}");
+        assert_eq!(
+            forward.retrieve(&req),
+            shuffled.retrieve(&req),
+            "seed {seed}"
+        );
+        assert_eq!(
+            forward.exact_vector_top_candidates(&"tenant-a".into(), &query_vector, req.top_k),
+            shuffled.exact_vector_top_candidates(&"tenant-a".into(), &query_vector, req.top_k),
+            "seed {seed}"
+        );
+    }
+}
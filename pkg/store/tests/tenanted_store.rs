@@ -0,0 +1,208 @@
+//! Integration tests for the per-tenant isolated store façade.
+//!
+//! These exercise `TenantedStore` through the public API only: routing
+//! by tenant, per-tenant WAL replay on reopen, replay-failure
+//! isolation, per-tenant checkpoints, claim quotas, and lazy
+//! loading/unloading under a residency budget.
+
+use std::fs::{create_dir_all, write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use schema::{RetrievalRequest, claim_builder};
+use store::{StoreError, TenantedStore, TenantedStoreConfig};
+use tempfile::TempDir;
+
+fn request(tenant: &str, query: &str) -> RetrievalRequest {
+    RetrievalRequest::new(tenant, query, 5)
+}
+
+#[test]
+fn routes_by_tenant_and_replays_each_tenant_on_reopen() {
+    let tmp = TempDir::new().unwrap();
+    {
+        let mut store = TenantedStore::open(TenantedStoreConfig::new(tmp.path())).unwrap();
+        store
+            .ingest_bundle(
+                claim_builder("a1", "tenant-a", "alpha launch", 0.9),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .ingest_bundle(
+                claim_builder("b1", "tenant/b", "beta launch", 0.9),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        store
+            .upsert_claim_vector(&"tenant-a".into(), &"a1".into(), vec![1.0, 0.0])
+            .unwrap();
+        assert!(store.tenant_dir(&"tenant/b".into()).starts_with(tmp.path()));
+    }
+
+    let mut store = TenantedStore::open(TenantedStoreConfig::new(tmp.path())).unwrap();
+    assert_eq!(
+        store.tenant_ids(),
+        vec!["tenant-a".to_string(), "tenant/b".to_string()]
+    );
+    let results = store.retrieve(&request("tenant-a", "launch"));
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].claim_id, "a1");
+    assert!(
+        store
+            .claim_by_id(&"tenant-a".into(), &"b1".into())
+            .is_none()
+    );
+    assert_eq!(
+        store.load_tenant(&"tenant/b".into()).unwrap().claims_len(),
+        1
+    );
+}
+
+#[test]
+fn replay_failure_is_isolated_to_one_tenant() {
+    let tmp = TempDir::new().unwrap();
+    {
+        let mut store = TenantedStore::open(TenantedStoreConfig::new(tmp.path())).unwrap();
+        store
+            .ingest_bundle(
+                claim_builder("a1", "tenant-a", "alpha", 0.9),
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let broken = store.tenant_dir(&"tenant-broken".into());
+        create_dir_all(&broken).unwrap();
+        write(broken.join("wal.log"), "X\tgarbage\n").unwrap();
+    }
+
+    let mut store = TenantedStore::open(TenantedStoreConfig::new(tmp.path())).unwrap();
+    let Err(err) = store.load_tenant(&"tenant-broken".into()) else {
+        panic!("a tenant with a corrupt WAL should fail to load");
+    };
+    let StoreError::TenantLoad { tenant_id, source } = &err else {
+        panic!("expected a tenant load failure, got {err:?}");
+    };
+    assert_eq!(tenant_id, "tenant-broken");
+    assert!(Arc::ptr_eq(
+        source,
+        &store.failed_tenants()["tenant-broken"]
+    ));
+    let cause = std::error::Error::source(&err).expect("load failure should keep its cause");
+    assert_eq!(cause.to_string(), source.to_string());
+    assert_eq!(store.resident_tenant_ids(), Vec::<String>::new());
+    assert!(
+        store
+            .ingest_bundle(
+                claim_builder("x1", "tenant-broken", "x", 0.9),
+                vec![],
+                vec![]
+            )
+            .is_err()
+    );
+    assert_eq!(store.retrieve(&request("tenant-a", "alpha")).len(), 1);
+}
+
+#[test]
+fn quota_and_checkpoint_apply_per_tenant() {
+    let tmp = TempDir::new().unwrap();
+    let mut config = TenantedStoreConfig::new(tmp.path());
+    config.max_claims_per_tenant = Some(1);
+    let mut store = TenantedStore::open(config).unwrap();
+
+    store
+        .ingest_bundle(
+            claim_builder("a1", "tenant-a", "alpha", 0.9),
+            vec![],
+            vec![],
+        )
+        .unwrap();
+    // Updating an existing claim stays within budget.
+    store
+        .ingest_bundle(
+            claim_builder("a1", "tenant-a", "alpha v2", 0.9),
+            vec![],
+            vec![],
+        )
+        .unwrap();
+    let err = store
+        .ingest_bundle(
+            claim_builder("a2", "tenant-a", "alpha two", 0.9),
+            vec![],
+            vec![],
+        )
+        .unwrap_err();
+    assert!(matches!(err, StoreError::QuotaExceeded(_)));
+    // Another tenant has its own budget.
+    store
+        .ingest_bundle(claim_builder("b1", "tenant-b", "beta", 0.9), vec![], vec![])
+        .unwrap();
+
+    let stats = store.checkpoint_tenant(&"tenant-a".into()).unwrap();
+    assert_eq!(stats.snapshot_records, 1);
+    assert_eq!(stats.truncated_wal_records, 2);
+    assert!(matches!(
+        store.checkpoint_tenant(&"tenant-missing".into()),
+        Err(StoreError::UnknownTenant(_))
+    ));
+    let all = store.checkpoint_all();
+    assert_eq!(all.len(), 2);
+    assert!(all.values().all(Result::is_ok));
+}
+
+#[test]
+fn tenants_load_on_first_access_and_unload_under_budget() {
+    let tmp = TempDir::new().unwrap();
+    {
+        let mut store = TenantedStore::open(TenantedStoreConfig::new(tmp.path())).unwrap();
+        for tenant in ["t1", "t2", "t3"] {
+            store
+                .ingest_bundle(
+                    claim_builder(&format!("{tenant}-c"), tenant, "shared text", 0.9),
+                    vec![],
+                    vec![],
+                )
+                .unwrap();
+        }
+    }
+
+    let mut config = TenantedStoreConfig::new(tmp.path());
+    config.max_resident_tenants = Some(2);
+    let mut store = TenantedStore::open(config).unwrap();
+    assert_eq!(store.tenant_ids().len(), 3);
+    assert!(store.resident_tenant_ids().is_empty());
+
+    assert_eq!(store.retrieve(&request("t1", "shared")).len(), 1);
+    assert_eq!(store.retrieve(&request("t2", "shared")).len(), 1);
+    assert_eq!(
+        store.resident_tenant_ids(),
+        vec!["t1".to_string(), "t2".to_string()]
+    );
+    // Loading a third tenant evicts the least recently used one.
+    assert_eq!(store.retrieve(&request("t3", "shared")).len(), 1);
+    assert_eq!(
+        store.resident_tenant_ids(),
+        vec!["t2".to_string(), "t3".to_string()]
+    );
+    assert_eq!(store.resident_claim_count(), 2);
+
+    // Unloading checkpoints, so a reload replays from the snapshot.
+    assert!(store.unload_tenant(&"t2".into()).unwrap());
+    assert!(!store.unload_tenant(&"t2".into()).unwrap());
+    assert_eq!(
+        store
+            .claim_by_id(&"t2".into(), &"t2-c".into())
+            .unwrap()
+            .canonical_text,
+        "shared text"
+    );
+
+    let unloaded = store.unload_idle(Duration::ZERO).unwrap();
+    assert_eq!(unloaded.len(), 2);
+    assert!(store.resident_tenant_ids().is_empty());
+    // Unknown tenants are never created by reads.
+    assert!(store.retrieve(&request("t-unknown", "shared")).is_empty());
+    assert_eq!(store.tenant_ids().len(), 3);
+}
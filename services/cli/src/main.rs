@@ -0,0 +1,511 @@
+use std::{
+    env,
+    fs::{File, metadata},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use store::{FileWal, InMemoryStore, StoreError, parse_query, verify_backup};
+
+const DEFAULT_TOP_K: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Config {
+    wal_path: PathBuf,
+    command: Command,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Stats,
+    Checkpoint,
+    Verify {
+        backup: Option<PathBuf>,
+    },
+    Export {
+        tenant_id: String,
+        out: Option<PathBuf>,
+    },
+    Import {
+        input: Option<PathBuf>,
+    },
+    Query {
+        tenant_id: String,
+        top_k: usize,
+        query: String,
+    },
+    IndexStats,
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("dash-cli failed: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", usage_text());
+        return Ok(());
+    }
+    let config = config_from_inputs(args, |key| env::var(key).ok())?;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    execute(&config, &mut out)
+}
+
+fn usage_text() -> &'static str {
+    "Usage: dash-cli <command> [--wal PATH] [options]\n\
+Commands:\n\
+  stats                           WAL and snapshot record counts and sizes\n\
+  checkpoint                      fold the WAL into its snapshot and truncate it\n\
+  verify [--backup PATH]          replay every record, or check a backup archive\n\
+  export --tenant ID [--out PATH] write a tenant as JSONL (stdout by default)\n\
+  import [--in PATH]              load a tenant export (stdin by default)\n\
+  query --tenant ID [--top-k N] QUERY...\n\
+                                  retrieve using the query language\n\
+  index-stats                     in-memory index sizes after replay\n\
+Defaults:\n\
+  --wal from DASH_INGEST_WAL_PATH (fallback EME_INGEST_WAL_PATH)\n\
+Stop the ingestion service before running checkpoint or import against its WAL."
+}
+
+fn config_from_inputs<I, F>(args: I, env_lookup: F) -> Result<Config, String>
+where
+    I: IntoIterator<Item = String>,
+    F: Fn(&str) -> Option<String>,
+{
+    let mut args = args.into_iter();
+    let name = args
+        .next()
+        .ok_or_else(|| "a command is required".to_string())?;
+
+    let mut wal_override: Option<String> = None;
+    let mut tenant_id: Option<String> = None;
+    let mut top_k: Option<usize> = None;
+    let mut backup: Option<String> = None;
+    let mut out: Option<String> = None;
+    let mut input: Option<String> = None;
+    let mut words: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let slot = match flag.as_str() {
+            "--wal" => &mut wal_override,
+            "--tenant" => &mut tenant_id,
+            "--backup" => &mut backup,
+            "--out" => &mut out,
+            "--in" => &mut input,
+            "--top-k" => {
+                let raw = flag_value(&flag, inline_value, &mut args)?;
+                top_k = Some(parse_positive_usize_arg(&flag, &raw)?);
+                continue;
+            }
+            _ if flag.starts_with("--") => return Err(format!("unknown option '{arg}'")),
+            _ => {
+                words.push(arg);
+                continue;
+            }
+        };
+        *slot = Some(flag_value(&flag, inline_value, &mut args)?);
+    }
+
+    let command = match name.as_str() {
+        "stats" => Command::Stats,
+        "checkpoint" => Command::Checkpoint,
+        "verify" => Command::Verify {
+            backup: backup.take().map(PathBuf::from),
+        },
+        "export" => Command::Export {
+            tenant_id: required_tenant(tenant_id.take(), &name)?,
+            out: out.take().map(PathBuf::from),
+        },
+        "import" => Command::Import {
+            input: input.take().map(PathBuf::from),
+        },
+        "query" => {
+            let query = words.join(" ");
+            words.clear();
+            if query.trim().is_empty() {
+                return Err("query needs query text".to_string());
+            }
+            Command::Query {
+                tenant_id: required_tenant(tenant_id.take(), &name)?,
+                top_k: top_k.take().unwrap_or(DEFAULT_TOP_K),
+                query,
+            }
+        }
+        "index-stats" => Command::IndexStats,
+        _ => return Err(format!("unknown command '{name}'")),
+    };
+    if let Some(word) = words.first() {
+        return Err(format!("unexpected argument '{word}' for {name}"));
+    }
+    let unused = [
+        ("--tenant", tenant_id.is_some()),
+        ("--top-k", top_k.is_some()),
+        ("--backup", backup.is_some()),
+        ("--out", out.is_some()),
+        ("--in", input.is_some()),
+    ];
+    if let Some((flag, _)) = unused.iter().find(|(_, set)| *set) {
+        return Err(format!("{flag} does not apply to {name}"));
+    }
+
+    let wal_path = wal_override
+        .or_else(|| env_lookup("DASH_INGEST_WAL_PATH"))
+        .or_else(|| env_lookup("EME_INGEST_WAL_PATH"))
+        .ok_or_else(|| {
+            "WAL path is required (--wal or DASH_INGEST_WAL_PATH/EME_INGEST_WAL_PATH)".to_string()
+        })?;
+    if wal_path.trim().is_empty() {
+        return Err("WAL path is empty".to_string());
+    }
+
+    Ok(Config {
+        wal_path: PathBuf::from(wal_path),
+        command,
+    })
+}
+
+fn flag_value(
+    flag: &str,
+    inline_value: Option<&str>,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, String> {
+    match inline_value {
+        Some(value) => Ok(value.to_string()),
+        None => args
+            .next()
+            .ok_or_else(|| format!("{flag} requires a value")),
+    }
+}
+
+fn required_tenant(tenant_id: Option<String>, command: &str) -> Result<String, String> {
+    tenant_id
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| format!("{command} requires --tenant"))
+}
+
+fn parse_positive_usize_arg(flag: &str, raw: &str) -> Result<usize, String> {
+    match raw.parse::<usize>() {
+        Ok(value) if value > 0 => Ok(value),
+        _ => Err(format!("{flag} expects a positive integer, got '{raw}'")),
+    }
+}
+
+fn execute(config: &Config, out: &mut impl Write) -> Result<(), String> {
+    match &config.command {
+        Command::Stats => {
+            let wal = open_wal(&config.wal_path)?;
+            let boundary = wal.replay_boundary().map_err(store_error)?;
+            let line = serde_json::json!({
+                "wal_path": config.wal_path.to_string_lossy(),
+                "snapshot_records": boundary.snapshot_record_count,
+                "wal_records": boundary.wal_delta_record_count,
+                "total_records": boundary.total_replay_record_count,
+                "wal_bytes": wal.wal_size_bytes().map_err(store_error)?,
+                "snapshot_bytes": file_size(&wal.snapshot_path()),
+            });
+            write_line(out, &line)
+        }
+        Command::Checkpoint => {
+            let mut wal = open_wal(&config.wal_path)?;
+            let store = load_store(&wal)?;
+            let stats = store
+                .checkpoint_and_compact(&mut wal)
+                .map_err(store_error)?;
+            let line = serde_json::json!({
+                "snapshot_records": stats.snapshot_records,
+                "truncated_wal_records": stats.truncated_wal_records,
+            });
+            write_line(out, &line)
+        }
+        Command::Verify { backup: Some(path) } => {
+            let manifest = verify_backup(path).map_err(store_error)?;
+            let line = serde_json::json!({
+                "backup": path.to_string_lossy(),
+                "ok": true,
+                "snapshot_records": manifest.snapshot_records,
+                "wal_records": manifest.wal_records,
+                "created_unix_ms": manifest.created_unix_ms,
+            });
+            write_line(out, &line)
+        }
+        Command::Verify { backup: None } => {
+            let wal = open_wal(&config.wal_path)?;
+            let (store, stats) = InMemoryStore::load_from_wal_with_stats(&wal)
+                .map_err(|err| format!("replay failed: {}", store_error(err)))?;
+            let report = store.verify_integrity();
+            let stale_index_entries: Vec<String> = report
+                .stale_index_entries
+                .iter()
+                .map(|(tenant_id, claim_id)| format!("{tenant_id}/{claim_id}"))
+                .collect();
+            let line = serde_json::json!({
+                "ok": report.is_clean(),
+                "snapshot_records": stats.replay.snapshot_records,
+                "wal_records": stats.replay.wal_records,
+                "claims": report.claims_checked,
+                "evidence": stats.evidence_loaded,
+                "edges": stats.edges_loaded,
+                "vectors": stats.vectors_loaded,
+                "unindexed_claims": report.unindexed_claims,
+                "stale_index_entries": stale_index_entries,
+                "orphaned_attachments": report.orphaned_attachments,
+                "orphaned_vectors": report.orphaned_vectors,
+            });
+            write_line(out, &line)?;
+            if report.is_clean() {
+                Ok(())
+            } else {
+                Err("integrity check found inconsistencies".to_string())
+            }
+        }
+        Command::Export {
+            tenant_id,
+            out: path,
+        } => {
+            let wal = open_wal(&config.wal_path)?;
+            let store = load_store(&wal)?;
+            let stats = match path {
+                Some(path) => {
+                    let file = File::create(path)
+                        .map_err(|err| format!("cannot create {}: {err}", path.display()))?;
+                    store.export_tenant_jsonl(&tenant_id.into(), BufWriter::new(file))
+                }
+                None => store.export_tenant_jsonl(&tenant_id.into(), &mut *out),
+            }
+            .map_err(store_error)?;
+            let line = serde_json::json!({
+                "tenant_id": tenant_id,
+                "claims": stats.claims,
+                "evidence": stats.evidence,
+                "edges": stats.edges,
+                "vectors": stats.vectors,
+            });
+            eprintln!("{line}");
+            Ok(())
+        }
+        Command::Import { input } => {
+            let mut wal = open_wal(&config.wal_path)?;
+            let mut store = load_store(&wal)?;
+            let reader: Box<dyn BufRead> = match input {
+                Some(path) => {
+                    Box::new(BufReader::new(File::open(path).map_err(|err| {
+                        format!("cannot open {}: {err}", path.display())
+                    })?))
+                }
+                None => Box::new(io::stdin().lock()),
+            };
+            let stats = store
+                .import_tenant_jsonl_persistent(&mut wal, reader)
+                .map_err(store_error)?;
+            wal.flush_pending_sync().map_err(store_error)?;
+            let line = serde_json::json!({
+                "claims": stats.claims,
+                "evidence": stats.evidence,
+                "edges": stats.edges,
+                "vectors": stats.vectors,
+            });
+            write_line(out, &line)
+        }
+        Command::Query {
+            tenant_id,
+            top_k,
+            query,
+        } => {
+            let parsed = parse_query(query).map_err(store_error)?;
+            let wal = open_wal(&config.wal_path)?;
+            let store = load_store(&wal)?;
+            let results =
+                store.retrieve_with(&parsed.request(&tenant_id.into(), *top_k), &parsed.options);
+            for result in &results {
+                let line = serde_json::to_value(result).map_err(|err| err.to_string())?;
+                write_line(out, &line)?;
+            }
+            Ok(())
+        }
+        Command::IndexStats => {
+            let wal = open_wal(&config.wal_path)?;
+            let store = load_store(&wal)?;
+            let line = serde_json::to_value(store.index_stats()).map_err(|err| err.to_string())?;
+            write_line(out, &line)
+        }
+    }
+}
+
+fn open_wal(path: &Path) -> Result<FileWal, String> {
+    FileWal::open(path)
+        .map_err(|err| format!("cannot open WAL {}: {}", path.display(), store_error(err)))
+}
+
+fn load_store(wal: &FileWal) -> Result<InMemoryStore, String> {
+    InMemoryStore::load_from_wal(wal).map_err(|err| format!("replay failed: {}", store_error(err)))
+}
+
+fn file_size(path: &Path) -> u64 {
+    metadata(path).map(|value| value.len()).unwrap_or(0)
+}
+
+fn store_error(err: StoreError) -> String {
+    err.to_string()
+}
+
+fn write_line(out: &mut impl Write, line: &serde_json::Value) -> Result<(), String> {
+    writeln!(out, "{line}").map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schema::Claim;
+    use std::collections::HashMap;
+
+    fn env_lookup(values: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = values
+            .iter()
+            .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+            .collect();
+        move |key| map.get(key).cloned()
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| (*value).to_string()).collect()
+    }
+
+    fn temp_wal_path(label: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("dash-cli-{label}-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("log.snapshot"));
+        path
+    }
+
+    fn run_command(wal_path: &Path, command: Command) -> Result<String, String> {
+        let config = Config {
+            wal_path: wal_path.to_path_buf(),
+            command,
+        };
+        let mut out = Vec::new();
+        execute(&config, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn config_from_inputs_parses_query_words_and_wal_from_env() {
+        let env = env_lookup(&[("DASH_INGEST_WAL_PATH", "/tmp/dash.wal")]);
+        let config = config_from_inputs(
+            args(&[
+                "query",
+                "--tenant=tenant-a",
+                "entity:\"Company X\"",
+                "acquisition",
+            ]),
+            env,
+        )
+        .expect("query should parse");
+
+        assert_eq!(config.wal_path, PathBuf::from("/tmp/dash.wal"));
+        assert_eq!(
+            config.command,
+            Command::Query {
+                tenant_id: "tenant-a".to_string(),
+                top_k: DEFAULT_TOP_K,
+                query: "entity:\"Company X\" acquisition".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn config_from_inputs_rejects_misplaced_options() {
+        let env = env_lookup(&[]);
+        let err = config_from_inputs(args(&["stats", "--tenant", "tenant-a", "--wal", "x"]), &env)
+            .expect_err("stats takes no tenant");
+        assert!(err.contains("--tenant does not apply to stats"));
+        let err = config_from_inputs(args(&["export", "--wal", "x"]), &env)
+            .expect_err("export needs a tenant");
+        assert!(err.contains("export requires --tenant"));
+        let err = config_from_inputs(args(&["stats"]), &env).expect_err("missing WAL path");
+        assert!(err.contains("WAL path is required"));
+    }
+
+    #[test]
+    fn commands_operate_on_a_wal_data_directory() {
+        let wal_path = temp_wal_path("source");
+        {
+            let mut wal = FileWal::open(&wal_path).unwrap();
+            let mut store = InMemoryStore::new();
+            let claim = Claim {
+                claim_id: "c1".into(),
+                tenant_id: "tenant-a".into(),
+                canonical_text: "Company X acquired Company Y".to_string(),
+                confidence: 0.9,
+                event_time_unix: None,
+                entities: vec!["Company X".into()],
+                embedding_ids: vec![],
+                claim_type: None,
+                valid_from: None,
+                valid_to: None,
+                created_at: None,
+                updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
+            };
+            store
+                .ingest_bundle_persistent(&mut wal, claim, vec![], vec![])
+                .unwrap();
+            wal.flush_pending_sync().unwrap();
+        }
+
+        let hits = run_command(
+            &wal_path,
+            Command::Query {
+                tenant_id: "tenant-a".to_string(),
+                top_k: 5,
+                query: "entity:\"Company X\" acquired".to_string(),
+            },
+        )
+        .unwrap();
+        assert!(hits.contains("\"claim_id\":\"c1\""));
+        let verify = run_command(&wal_path, Command::Verify { backup: None }).unwrap();
+        assert!(verify.contains("\"ok\":true"));
+
+        let checkpoint = run_command(&wal_path, Command::Checkpoint).unwrap();
+        assert!(checkpoint.contains("\"snapshot_records\":1"));
+        let stats = run_command(&wal_path, Command::Stats).unwrap();
+        assert!(stats.contains("\"wal_records\":0"));
+
+        let export_path = temp_wal_path("export");
+        run_command(
+            &wal_path,
+            Command::Export {
+                tenant_id: "tenant-a".to_string(),
+                out: Some(export_path.clone()),
+            },
+        )
+        .unwrap();
+        let copy_path = temp_wal_path("copy");
+        let imported = run_command(
+            &copy_path,
+            Command::Import {
+                input: Some(export_path.clone()),
+            },
+        )
+        .unwrap();
+        assert!(imported.contains("\"claims\":1"));
+        let index_stats = run_command(&copy_path, Command::IndexStats).unwrap();
+        assert!(index_stats.contains("\"claim_count\":1"));
+
+        for path in [&wal_path, &export_path, &copy_path] {
+            let _ = std::fs::remove_file(path);
+            let _ = std::fs::remove_file(path.with_extension("log.snapshot"));
+        }
+    }
+}
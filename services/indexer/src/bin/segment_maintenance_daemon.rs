@@ -64,7 +64,7 @@ fn run() -> Result<(), String> {
 }
 
 fn run_tick(root_dir: &Path, min_stale_age: Duration) -> Result<SegmentMaintenanceStats, String> {
-    maintain_segment_root(root_dir, min_stale_age).map_err(|err| format!("{err:?}"))
+    maintain_segment_root(root_dir, min_stale_age).map_err(|err| err.to_string())
 }
 
 fn usage_text() -> &'static str {
@@ -0,0 +1,148 @@
+//! Layout versions of the segment manifest and segment files.
+//!
+//! Both formats are lines of tab-separated fields behind a header that
+//! names the format and its version: `DASHSEG-MANIFEST\t<version>` for a
+//! manifest, whose version covers the WAL position in its header and
+//! every row, and `DASHSEG\t<version>` for a segment file. As with the
+//! store's WAL records, [`Layout::upgrade`] appends the encoded defaults
+//! of the fields an older version predates, so the parsers always read
+//! the current layout at fixed positions. Adding a field means bumping
+//! the format's version and appending one field count and one default
+//! to its layout below. A version newer than this build knows is refused
+//! rather than misread.
+
+use crate::SegmentStoreError;
+
+/// The versions of one line layout.
+pub(crate) struct Layout {
+    /// Used in parse errors, as in "segment manifest row".
+    name: &'static str,
+    /// Field count of each version, oldest first, not counting the
+    /// format name and version.
+    field_counts: &'static [usize],
+    /// Encoded values of the fields added after the first version, in
+    /// field order.
+    defaults: &'static [&'static str],
+}
+
+/// Version written for new manifests.
+pub(crate) const MANIFEST_VERSION: u32 = 1;
+
+/// The WAL position trailing a manifest header, when recorded.
+pub(crate) const MANIFEST_POSITION: Layout = Layout {
+    name: "segment manifest WAL position",
+    field_counts: &[2],
+    defaults: &[],
+};
+
+pub(crate) const MANIFEST_ROW: Layout = Layout {
+    name: "segment manifest row",
+    field_counts: &[5],
+    defaults: &[],
+};
+
+/// Version written for new segment files.
+pub(crate) const SEGMENT_VERSION: u32 = 1;
+
+/// A segment file's header after the format name and version.
+pub(crate) const SEGMENT_HEADER: Layout = Layout {
+    name: "segment header",
+    field_counts: &[4],
+    defaults: &[],
+};
+
+impl Layout {
+    /// Checks `fields`, written at `version`, against that version's
+    /// layout and appends the defaults that bring them to the current one.
+    pub(crate) fn upgrade<'a>(
+        &self,
+        version: u32,
+        mut fields: Vec<&'a str>,
+    ) -> Result<Vec<&'a str>, SegmentStoreError> {
+        let index = self.version_index(version)?;
+        if fields.len() != self.field_counts[index] {
+            return Err(SegmentStoreError::Parse(format!(
+                "{} has {} fields, version {version} has {}",
+                self.name,
+                fields.len(),
+                self.field_counts[index]
+            )));
+        }
+        fields.extend_from_slice(&self.defaults[self.field_counts[index] - self.field_counts[0]..]);
+        Ok(fields)
+    }
+
+    fn version_index(&self, version: u32) -> Result<usize, SegmentStoreError> {
+        (version as usize)
+            .checked_sub(1)
+            .filter(|index| *index < self.field_counts.len())
+            .ok_or_else(|| {
+                SegmentStoreError::Parse(format!(
+                    "{} has unknown layout version {version}",
+                    self.name
+                ))
+            })
+    }
+}
+
+/// Parses the version after a format name, refusing versions this build
+/// does not know.
+pub(crate) fn parse_version(raw: &str, current: u32, name: &str) -> Result<u32, SegmentStoreError> {
+    match raw.parse::<u32>() {
+        Ok(version) if (1..=current).contains(&version) => Ok(version),
+        Ok(version) => Err(SegmentStoreError::Parse(format!(
+            "{name} version {version} is newer than this build supports ({current})"
+        ))),
+        Err(_) => Err(SegmentStoreError::Parse(format!(
+            "{name} version is invalid: {raw}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrade_appends_defaults_of_later_versions() {
+        let layout = Layout {
+            name: "test row",
+            field_counts: &[2, 3, 4],
+            defaults: &["third", "fourth"],
+        };
+        assert_eq!(
+            layout
+                .upgrade(1, vec!["a", "b"])
+                .expect("v1 should upgrade"),
+            vec!["a", "b", "third", "fourth"]
+        );
+        assert_eq!(
+            layout
+                .upgrade(2, vec!["a", "b", "c"])
+                .expect("v2 should upgrade"),
+            vec!["a", "b", "c", "fourth"]
+        );
+        assert_eq!(
+            layout
+                .upgrade(3, vec!["a", "b", "c", "d"])
+                .expect("v3 is current"),
+            vec!["a", "b", "c", "d"]
+        );
+        assert!(matches!(
+            layout.upgrade(2, vec!["a", "b"]),
+            Err(SegmentStoreError::Parse(_))
+        ));
+        assert!(matches!(
+            layout.upgrade(4, vec!["a", "b", "c", "d"]),
+            Err(SegmentStoreError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_versions_newer_than_current() {
+        assert_eq!(parse_version("1", 2, "test").expect("known version"), 1);
+        assert!(parse_version("3", 2, "test").is_err());
+        assert!(parse_version("0", 2, "test").is_err());
+        assert!(parse_version("x", 2, "test").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use schema::Claim;
+use schema::{Claim, ClaimId, TenantId};
 use std::{
     collections::{HashMap, HashSet},
     fs::{File, OpenOptions, create_dir_all, read_dir, remove_file, rename},
@@ -7,9 +7,17 @@ use std::{
     time::Duration,
 };
 
-use store::{InMemoryStore, StoreIndexStats};
+use store::{InMemoryStore, StoreIndexStats, WalReplayBoundary};
+
+mod layout;
+
+use layout::{
+    MANIFEST_POSITION, MANIFEST_ROW, MANIFEST_VERSION, SEGMENT_HEADER, SEGMENT_VERSION,
+    parse_version,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Tier {
     Hot,
     Warm,
@@ -17,12 +25,14 @@ pub enum Tier {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SegmentPlacement {
     pub claim_id: String,
     pub tier: Tier,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TierCounts {
     pub hot: usize,
     pub warm: usize,
@@ -30,6 +40,7 @@ pub struct TierCounts {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Segment {
     pub segment_id: String,
     pub tier: Tier,
@@ -37,6 +48,7 @@ pub struct Segment {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompactionPlan {
     pub tier: Tier,
     pub segments: Vec<Segment>,
@@ -59,6 +71,7 @@ impl Default for CompactionSchedulerConfig {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SegmentManifestEntry {
     pub segment_id: String,
     pub tier: Tier,
@@ -68,8 +81,78 @@ pub struct SegmentManifestEntry {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SegmentManifest {
     pub entries: Vec<SegmentManifestEntry>,
+    /// WAL position the segments were built from, when published through
+    /// [`persist_segments_at_wal_position`]. Manifests written before the
+    /// position was recorded load with `None`.
+    pub wal_position: Option<SegmentWalPosition>,
+}
+
+/// Point in the store's persistence the segments describe: how many
+/// records the active snapshot held and how many WAL records followed it.
+/// A checkpoint rewrites both, so the snapshot record count doubles as the
+/// snapshot's identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentWalPosition {
+    pub snapshot_records: usize,
+    pub wal_records: usize,
+}
+
+impl SegmentWalPosition {
+    pub fn from_boundary(boundary: &WalReplayBoundary) -> Self {
+        Self {
+            snapshot_records: boundary.snapshot_record_count,
+            wal_records: boundary.wal_delta_record_count,
+        }
+    }
+}
+
+/// How a manifest's recorded WAL position relates to the WAL on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentWalAlignment {
+    /// The manifest predates WAL positions; only claim-level checks apply.
+    Unrecorded,
+    Matches,
+    /// The WAL has records the segments were not built from. Expected when
+    /// other tenants write after this tenant last published.
+    Behind,
+    /// The segments were built from WAL records that are no longer on
+    /// disk, so they describe state the store cannot replay.
+    Ahead,
+    /// A checkpoint replaced the snapshot since the segments were
+    /// published; the positions are not comparable.
+    SnapshotChanged,
+}
+
+impl SegmentWalAlignment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unrecorded => "unrecorded",
+            Self::Matches => "matches",
+            Self::Behind => "behind",
+            Self::Ahead => "ahead",
+            Self::SnapshotChanged => "snapshot_changed",
+        }
+    }
+}
+
+impl SegmentManifest {
+    pub fn wal_alignment(&self, current: SegmentWalPosition) -> SegmentWalAlignment {
+        let Some(recorded) = self.wal_position else {
+            return SegmentWalAlignment::Unrecorded;
+        };
+        if recorded.snapshot_records != current.snapshot_records {
+            return SegmentWalAlignment::SnapshotChanged;
+        }
+        match recorded.wal_records.cmp(&current.wal_records) {
+            std::cmp::Ordering::Equal => SegmentWalAlignment::Matches,
+            std::cmp::Ordering::Less => SegmentWalAlignment::Behind,
+            std::cmp::Ordering::Greater => SegmentWalAlignment::Ahead,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -79,10 +162,38 @@ pub struct SegmentMaintenanceStats {
     pub pruned_file_count: usize,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Divergence between a tenant's active segments and the store, as found
+/// by [`reconcile_segments_with_store`]. Segments and the WAL are written
+/// independently, so a crash between the two can leave either side ahead.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SegmentReconciliationReport {
+    pub manifest_found: bool,
+    pub segment_claim_count: usize,
+    /// Claim ids referenced by an active segment that the store does not
+    /// hold for this tenant.
+    pub missing_from_store: Vec<String>,
+    /// Hot-tier claims held by the store that no active segment references.
+    pub missing_from_segments: Vec<String>,
+    /// Set when a manifest was found and the caller supplied the current
+    /// WAL position.
+    pub wal_alignment: Option<SegmentWalAlignment>,
+}
+
+impl SegmentReconciliationReport {
+    pub fn is_consistent(&self) -> bool {
+        self.missing_from_store.is_empty()
+            && self.missing_from_segments.is_empty()
+            && self.wal_alignment != Some(SegmentWalAlignment::Ahead)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum SegmentStoreError {
+    #[error("segment io error: {0}")]
     Io(String),
+    #[error("segment parse error: {0}")]
     Parse(String),
+    #[error("segment integrity error: {0}")]
     Integrity(String),
 }
 
@@ -93,9 +204,9 @@ impl From<std::io::Error> for SegmentStoreError {
 }
 
 const MANIFEST_FILE_NAME: &str = "segments.manifest";
-const MANIFEST_HEADER: &str = "DASHSEG-MANIFEST\t1";
+const MANIFEST_FORMAT: &str = "DASHSEG-MANIFEST";
 const SEGMENT_FILE_SUFFIX: &str = ".seg";
-const SEGMENT_HEADER: &str = "DASHSEG\t1";
+const SEGMENT_FORMAT: &str = "DASHSEG";
 
 pub fn classify_claim_tier(claim: &Claim) -> Tier {
     if claim.confidence >= 0.85 {
@@ -112,7 +223,7 @@ pub fn preview_segment_plan(store: &InMemoryStore, claims: &[Claim]) -> Vec<Segm
     claims
         .iter()
         .map(|c| SegmentPlacement {
-            claim_id: c.claim_id.clone(),
+            claim_id: c.claim_id.to_string(),
             tier: classify_claim_tier(c),
         })
         .collect()
@@ -137,7 +248,7 @@ pub fn build_segments(claims: &[Claim], max_segment_size: usize) -> Vec<Segment>
         buckets
             .entry(classify_claim_tier(claim))
             .or_default()
-            .push(claim.claim_id.clone());
+            .push(claim.claim_id.to_string());
     }
 
     let mut out = Vec::new();
@@ -228,6 +339,26 @@ pub fn apply_compaction_plan(segments: &[Segment], plan: &CompactionPlan) -> Vec
 pub fn persist_segments_atomic(
     root_dir: &Path,
     segments: &[Segment],
+) -> Result<SegmentManifest, SegmentStoreError> {
+    persist_segments(root_dir, segments, None)
+}
+
+/// Two-phase publish tied to the store's persistence: segment files are
+/// written first, then the manifest that activates them records the WAL
+/// position they were built from. Callers should sync the WAL before
+/// reading the position so the manifest never points past durable records.
+pub fn persist_segments_at_wal_position(
+    root_dir: &Path,
+    segments: &[Segment],
+    wal_position: SegmentWalPosition,
+) -> Result<SegmentManifest, SegmentStoreError> {
+    persist_segments(root_dir, segments, Some(wal_position))
+}
+
+fn persist_segments(
+    root_dir: &Path,
+    segments: &[Segment],
+    wal_position: Option<SegmentWalPosition>,
 ) -> Result<SegmentManifest, SegmentStoreError> {
     create_dir_all(root_dir)?;
     let mut entries = Vec::with_capacity(segments.len());
@@ -249,7 +380,10 @@ pub fn persist_segments_atomic(
             checksum,
         });
     }
-    let manifest = SegmentManifest { entries };
+    let manifest = SegmentManifest {
+        entries,
+        wal_position,
+    };
     write_manifest_atomic(root_dir, &manifest)?;
     Ok(manifest)
 }
@@ -368,11 +502,7 @@ pub fn load_manifest(root_dir: &Path) -> Result<Option<SegmentManifest>, Segment
             "segment manifest is empty".to_string(),
         ));
     }
-    if header.trim_end() != MANIFEST_HEADER {
-        return Err(SegmentStoreError::Parse(
-            "segment manifest header is invalid".to_string(),
-        ));
-    }
+    let (version, wal_position) = parse_manifest_header(header.trim_end())?;
 
     let mut entries = Vec::new();
     for line in reader.lines() {
@@ -380,12 +510,7 @@ pub fn load_manifest(root_dir: &Path) -> Result<Option<SegmentManifest>, Segment
         if line.trim().is_empty() {
             continue;
         }
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() != 5 {
-            return Err(SegmentStoreError::Parse(format!(
-                "segment manifest row is invalid: {line}"
-            )));
-        }
+        let parts = MANIFEST_ROW.upgrade(version, line.split('\t').collect())?;
         let tier = parse_tier(parts[1])?;
         let claim_count = parts[3].parse::<usize>().map_err(|_| {
             SegmentStoreError::Parse("segment manifest claim_count is invalid".to_string())
@@ -401,7 +526,28 @@ pub fn load_manifest(root_dir: &Path) -> Result<Option<SegmentManifest>, Segment
             checksum,
         });
     }
-    Ok(Some(SegmentManifest { entries }))
+    Ok(Some(SegmentManifest {
+        entries,
+        wal_position,
+    }))
+}
+
+/// Like [`load_segments_from_manifest`], but refuses a manifest whose
+/// recorded WAL position is past `current`: those segments were built from
+/// records the WAL no longer holds.
+pub fn load_segments_at_wal_position(
+    root_dir: &Path,
+    manifest: &SegmentManifest,
+    current: SegmentWalPosition,
+) -> Result<Vec<Segment>, SegmentStoreError> {
+    if manifest.wal_alignment(current) == SegmentWalAlignment::Ahead {
+        let recorded = manifest.wal_position.unwrap_or_default();
+        return Err(SegmentStoreError::Integrity(format!(
+            "segment manifest is ahead of the WAL: recorded wal_records={}, current={}",
+            recorded.wal_records, current.wal_records
+        )));
+    }
+    load_segments_from_manifest(root_dir, manifest)
 }
 
 pub fn load_segments_from_manifest(
@@ -442,6 +588,55 @@ pub fn load_segments_from_manifest(
     Ok(segments)
 }
 
+pub fn reconcile_segments_with_store(
+    tenant_dir: &Path,
+    store: &InMemoryStore,
+    tenant_id: &str,
+) -> Result<SegmentReconciliationReport, SegmentStoreError> {
+    reconcile_segments_with_store_at(tenant_dir, store, tenant_id, None)
+}
+
+/// [`reconcile_segments_with_store`] that also compares the manifest's
+/// recorded WAL position with `wal_position`. A manifest ahead of the WAL
+/// is reported as inconsistent even when the claim ids happen to agree.
+pub fn reconcile_segments_with_store_at(
+    tenant_dir: &Path,
+    store: &InMemoryStore,
+    tenant_id: &str,
+    wal_position: Option<SegmentWalPosition>,
+) -> Result<SegmentReconciliationReport, SegmentStoreError> {
+    let mut report = SegmentReconciliationReport::default();
+    let mut segment_ids: HashSet<String> = HashSet::new();
+    if let Some(manifest) = load_manifest(tenant_dir)? {
+        report.manifest_found = true;
+        report.wal_alignment = wal_position.map(|current| manifest.wal_alignment(current));
+        for segment in load_segments_from_manifest(tenant_dir, &manifest)? {
+            report.segment_claim_count += segment.claim_ids.len();
+            segment_ids.extend(segment.claim_ids);
+        }
+    }
+
+    let store_ids = store.claim_ids_for_tenant(&TenantId::from(tenant_id));
+    report.missing_from_store = segment_ids
+        .iter()
+        .filter(|claim_id| !store_ids.contains(claim_id.as_str()))
+        .cloned()
+        .collect();
+    report.missing_from_store.sort_unstable();
+    report.missing_from_segments = store_ids
+        .iter()
+        .filter(|claim_id| !segment_ids.contains(claim_id.as_str()))
+        .filter(|claim_id| {
+            store
+                .claim_by_id(claim_id)
+                .is_some_and(|claim| classify_claim_tier(claim) == Tier::Hot)
+        })
+        .map(ClaimId::to_string)
+        .collect();
+    report.missing_from_segments.sort_unstable();
+    Ok(report)
+}
+
 pub fn indexer_health_snapshot(
     store: &InMemoryStore,
     claims: &[Claim],
@@ -461,7 +656,14 @@ fn write_manifest_atomic(
             .truncate(true)
             .write(true)
             .open(&tmp_path)?;
-        writeln!(file, "{MANIFEST_HEADER}")?;
+        match manifest.wal_position {
+            Some(position) => writeln!(
+                file,
+                "{MANIFEST_FORMAT}\t{MANIFEST_VERSION}\t{}\t{}",
+                position.snapshot_records, position.wal_records
+            )?,
+            None => writeln!(file, "{MANIFEST_FORMAT}\t{MANIFEST_VERSION}")?,
+        }
         for entry in &manifest.entries {
             writeln!(
                 file,
@@ -479,6 +681,41 @@ fn write_manifest_atomic(
     Ok(())
 }
 
+/// Returns the manifest's layout version and, when the header carries the
+/// WAL position fields after the version, the recorded position.
+fn parse_manifest_header(
+    header: &str,
+) -> Result<(u32, Option<SegmentWalPosition>), SegmentStoreError> {
+    let mut fields = header.split('\t');
+    let (Some(MANIFEST_FORMAT), Some(version)) = (fields.next(), fields.next()) else {
+        return Err(SegmentStoreError::Parse(
+            "segment manifest header is invalid".to_string(),
+        ));
+    };
+    let version = parse_version(version, MANIFEST_VERSION, "segment manifest")?;
+    let fields: Vec<&str> = fields.collect();
+    if fields.is_empty() {
+        return Ok((version, None));
+    }
+    let fields = MANIFEST_POSITION.upgrade(version, fields)?;
+    let parsed: Vec<usize> = fields
+        .iter()
+        .filter_map(|field| field.parse::<usize>().ok())
+        .collect();
+    if parsed.len() != fields.len() {
+        return Err(SegmentStoreError::Parse(
+            "segment manifest WAL position is invalid".to_string(),
+        ));
+    }
+    Ok((
+        version,
+        Some(SegmentWalPosition {
+            snapshot_records: parsed[0],
+            wal_records: parsed[1],
+        }),
+    ))
+}
+
 fn write_segment_file_atomic(
     path: &Path,
     segment: &Segment,
@@ -493,7 +730,7 @@ fn write_segment_file_atomic(
             .open(&tmp_path)?;
         writeln!(
             file,
-            "{SEGMENT_HEADER}\t{}\t{}\t{}\t{}",
+            "{SEGMENT_FORMAT}\t{SEGMENT_VERSION}\t{}\t{}\t{}\t{}",
             escape_field(&segment.segment_id),
             format_tier(&segment.tier),
             segment.claim_ids.len(),
@@ -520,20 +757,22 @@ fn read_segment_file(path: &Path) -> Result<Segment, SegmentStoreError> {
         )));
     }
     let header = header.trim_end();
-    let parts: Vec<&str> = header.split('\t').collect();
-    if parts.len() != 6 || parts[0] != "DASHSEG" || parts[1] != "1" {
+    let mut fields = header.split('\t');
+    let (Some(SEGMENT_FORMAT), Some(version)) = (fields.next(), fields.next()) else {
         return Err(SegmentStoreError::Parse(format!(
             "segment file '{}' has invalid header",
             path.display()
         )));
-    }
+    };
+    let version = parse_version(version, SEGMENT_VERSION, "segment file")?;
+    let parts = SEGMENT_HEADER.upgrade(version, fields.collect())?;
 
-    let segment_id = unescape_field(parts[2])?;
-    let tier = parse_tier(parts[3])?;
-    let claim_count = parts[4]
+    let segment_id = unescape_field(parts[0])?;
+    let tier = parse_tier(parts[1])?;
+    let claim_count = parts[2]
         .parse::<usize>()
         .map_err(|_| SegmentStoreError::Parse("segment claim count is invalid".to_string()))?;
-    let expected_checksum = parts[5]
+    let expected_checksum = parts[3]
         .parse::<u64>()
         .map_err(|_| SegmentStoreError::Parse("segment checksum is invalid".to_string()))?;
 
@@ -688,6 +927,10 @@ mod tests {
             valid_to: None,
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         }
     }
 
@@ -793,6 +1036,51 @@ mod tests {
         let _ = fs::remove_dir_all(root);
     }
 
+    #[test]
+    fn refuses_segment_formats_newer_than_this_build() {
+        let root = temp_dir("segment-future-version");
+        let segments = vec![Segment {
+            segment_id: "hot-0".into(),
+            tier: Tier::Hot,
+            claim_ids: vec!["claim-1".into()],
+        }];
+        let manifest =
+            persist_segments_atomic(&root, &segments).expect("segment persist should succeed");
+        let manifest_path = root.join(MANIFEST_FILE_NAME);
+        let segment_path = root.join(&manifest.entries[0].file_name);
+        let bump = |content: String, format: &str, version: u32| {
+            content.replacen(
+                &format!("{format}\t{version}"),
+                &format!("{format}\t{}", version + 1),
+                1,
+            )
+        };
+
+        let original = fs::read_to_string(&segment_path).expect("segment should be readable");
+        fs::write(
+            &segment_path,
+            bump(original, SEGMENT_FORMAT, SEGMENT_VERSION),
+        )
+        .expect("segment should be writable");
+        assert!(matches!(
+            load_segments_from_manifest(&root, &manifest),
+            Err(SegmentStoreError::Parse(_))
+        ));
+
+        let original = fs::read_to_string(&manifest_path).expect("manifest should be readable");
+        fs::write(
+            &manifest_path,
+            bump(original, MANIFEST_FORMAT, MANIFEST_VERSION),
+        )
+        .expect("manifest should be writable");
+        assert!(matches!(
+            load_manifest(&root),
+            Err(SegmentStoreError::Parse(_))
+        ));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
     #[test]
     fn compaction_scheduler_plans_when_tier_exceeds_limit() {
         let claims = vec![
@@ -930,4 +1218,137 @@ mod tests {
 
         let _ = fs::remove_dir_all(root);
     }
+
+    #[test]
+    fn reconcile_segments_reports_divergence_in_both_directions() {
+        let root = temp_dir("segment-reconcile");
+        let tenant_dir = root.join("tenant-a");
+        let mut store = InMemoryStore::new();
+        for claim in [claim("c1", 0.9), claim("c2", 0.95), claim("c3", 0.4)] {
+            store
+                .ingest_bundle(claim, vec![], vec![])
+                .expect("ingest should succeed");
+        }
+
+        let report = reconcile_segments_with_store(&tenant_dir, &store, "tenant-a")
+            .expect("reconcile without manifest should succeed");
+        assert!(!report.manifest_found);
+        assert_eq!(report.missing_from_segments, vec!["c1", "c2"]);
+
+        persist_segments_atomic(
+            &tenant_dir,
+            &[Segment {
+                segment_id: "hot-0".into(),
+                tier: Tier::Hot,
+                claim_ids: vec!["c1".into(), "ghost".into()],
+            }],
+        )
+        .expect("segment persist should succeed");
+        let report = reconcile_segments_with_store(&tenant_dir, &store, "tenant-a")
+            .expect("reconcile should succeed");
+        assert!(report.manifest_found);
+        assert_eq!(report.segment_claim_count, 2);
+        assert_eq!(report.missing_from_store, vec!["ghost"]);
+        assert_eq!(report.missing_from_segments, vec!["c2"]);
+        assert!(!report.is_consistent());
+
+        let segments = build_segments(&store.claims_for_tenant(&"tenant-a".into()), 8);
+        persist_segments_atomic(&tenant_dir, &segments).expect("segment persist should succeed");
+        let report = reconcile_segments_with_store(&tenant_dir, &store, "tenant-a")
+            .expect("reconcile should succeed");
+        assert!(report.is_consistent());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn manifest_wal_position_round_trips_and_refuses_manifests_ahead_of_the_wal() {
+        let root = temp_dir("segment-wal-position");
+        let segments = vec![Segment {
+            segment_id: "hot-0".into(),
+            tier: Tier::Hot,
+            claim_ids: vec!["c1".into()],
+        }];
+        persist_segments_atomic(&root, &segments).expect("segment persist should succeed");
+        let manifest = load_manifest(&root)
+            .expect("manifest should load")
+            .expect("manifest should exist");
+        assert_eq!(manifest.wal_position, None);
+        assert_eq!(
+            manifest.wal_alignment(SegmentWalPosition::default()),
+            SegmentWalAlignment::Unrecorded
+        );
+
+        let recorded = SegmentWalPosition {
+            snapshot_records: 4,
+            wal_records: 7,
+        };
+        persist_segments_at_wal_position(&root, &segments, recorded)
+            .expect("segment persist should succeed");
+        let manifest = load_manifest(&root)
+            .expect("manifest should load")
+            .expect("manifest should exist");
+        assert_eq!(manifest.wal_position, Some(recorded));
+
+        let at = |snapshot_records, wal_records| SegmentWalPosition {
+            snapshot_records,
+            wal_records,
+        };
+        assert_eq!(
+            manifest.wal_alignment(at(4, 7)),
+            SegmentWalAlignment::Matches
+        );
+        assert_eq!(
+            manifest.wal_alignment(at(4, 9)),
+            SegmentWalAlignment::Behind
+        );
+        assert_eq!(manifest.wal_alignment(at(4, 5)), SegmentWalAlignment::Ahead);
+        assert_eq!(
+            manifest.wal_alignment(at(12, 0)),
+            SegmentWalAlignment::SnapshotChanged
+        );
+        assert_eq!(
+            load_segments_at_wal_position(&root, &manifest, at(4, 9))
+                .expect("segments behind the WAL should load"),
+            segments
+        );
+        assert!(matches!(
+            load_segments_at_wal_position(&root, &manifest, at(4, 5)),
+            Err(SegmentStoreError::Integrity(_))
+        ));
+
+        fs::write(
+            root.join(MANIFEST_FILE_NAME),
+            format!("{MANIFEST_FORMAT}\t{MANIFEST_VERSION}\t4\n"),
+        )
+        .expect("manifest should be writable");
+        assert!(matches!(
+            load_manifest(&root),
+            Err(SegmentStoreError::Parse(_))
+        ));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn segment_manifest_round_trips_through_json() {
+        let manifest = SegmentManifest {
+            entries: vec![SegmentManifestEntry {
+                segment_id: "hot-0".to_string(),
+                tier: Tier::Hot,
+                file_name: "hot-0.seg".to_string(),
+                claim_count: 2,
+                checksum: 42,
+            }],
+            wal_position: Some(SegmentWalPosition {
+                snapshot_records: 4,
+                wal_records: 9,
+            }),
+        };
+        let json = serde_json::to_string(&manifest).expect("manifest should serialize");
+        let decoded: SegmentManifest =
+            serde_json::from_str(&json).expect("manifest should deserialize");
+        assert_eq!(decoded, manifest);
+    }
 }
@@ -1,5 +1,6 @@
-use schema::{Claim, ClaimEdge, ClaimType, Evidence, Relation, Stance};
-use serde::{Deserialize, Serialize};
+use schema::{Claim, ClaimEdge, ClaimType, Entity, Evidence, Relation, Stance};
+use serde::{Deserialize, Deserializer, Serialize};
+use store::{ClaimInspection, ClaimPatch, OutboxEvent};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -125,12 +126,10 @@ impl IngestBatchApiRequestWire {
             let req = item.into_runtime()?;
             if let Some(tenant_id) = expected_tenant.as_deref() {
                 if tenant_id != req.claim.tenant_id {
-                    return Err(
-                        "all batch items must share the same claim.tenant_id".to_string(),
-                    );
+                    return Err("all batch items must share the same claim.tenant_id".to_string());
                 }
             } else {
-                expected_tenant = Some(req.claim.tenant_id.clone());
+                expected_tenant = Some(req.claim.tenant_id.to_string());
             }
             items.push(req);
         }
@@ -150,8 +149,9 @@ pub struct ClaimWire {
     pub confidence: f32,
     #[serde(default)]
     pub event_time_unix: Option<i64>,
+    /// Entity names or `{name, entity_type, canonical_name}` objects.
     #[serde(default)]
-    pub entities: Vec<String>,
+    pub entities: Vec<Entity>,
     #[serde(default)]
     pub embedding_ids: Vec<String>,
     #[serde(default)]
@@ -166,6 +166,15 @@ pub struct ClaimWire {
     pub updated_at: Option<i64>,
     #[serde(default)]
     pub embedding_vector: Option<Vec<f32>>,
+    /// `[key, value]` pairs.
+    #[serde(default)]
+    pub metadata: Vec<(String, String)>,
+    #[serde(default)]
+    pub visibility_labels: Vec<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub collection: Option<String>,
 }
 
 impl ClaimWire {
@@ -174,59 +183,192 @@ impl ClaimWire {
     /// happen in the hand-rolled parser, preserving the legacy error messages
     /// the test suite expects.
     pub fn into_runtime(self) -> Result<(Claim, Option<Vec<f32>>), String> {
-        let claim_type = match self.claim_type {
-            None => None,
-            Some(raw) => {
-                let normalized = raw.trim().to_ascii_lowercase();
-                Some(match normalized.as_str() {
-                    "factual" => ClaimType::Factual,
-                    "opinion" => ClaimType::Opinion,
-                    "prediction" => ClaimType::Prediction,
-                    "temporal" => ClaimType::Temporal,
-                    "causal" => ClaimType::Causal,
-                    _ => {
-                        return Err(
-                            "claim.claim_type must be one of: factual, opinion, prediction, temporal, causal"
-                                .to_string(),
-                        );
-                    }
-                })
-            }
-        };
+        let claim_type = self
+            .claim_type
+            .as_deref()
+            .map(parse_claim_type)
+            .transpose()?;
 
         if let Some(vector) = &self.embedding_vector {
             if vector.is_empty() {
-                return Err(
-                    "claim.embedding_vector must not be empty when provided".to_string(),
-                );
+                return Err("claim.embedding_vector must not be empty when provided".to_string());
             }
             if !vector.iter().all(|v| v.is_finite()) {
-                return Err(
-                    "claim.embedding_vector values must be finite numbers".to_string(),
-                );
+                return Err("claim.embedding_vector values must be finite numbers".to_string());
             }
         }
 
-        Ok((
-            Claim {
-                claim_id: self.claim_id,
-                tenant_id: self.tenant_id,
-                canonical_text: self.canonical_text,
-                confidence: self.confidence,
-                event_time_unix: self.event_time_unix,
-                entities: self.entities,
-                embedding_ids: self.embedding_ids,
-                claim_type,
-                valid_from: self.valid_from,
-                valid_to: self.valid_to,
-                created_at: self.created_at,
-                updated_at: self.updated_at,
-            },
-            self.embedding_vector,
-        ))
+        let mut claim = Claim::new(
+            self.claim_id,
+            self.tenant_id,
+            self.canonical_text,
+            self.confidence,
+        );
+        claim.event_time_unix = self.event_time_unix;
+        claim.entities = self.entities;
+        claim.embedding_ids = self.embedding_ids;
+        claim.claim_type = claim_type;
+        claim.valid_from = self.valid_from;
+        claim.valid_to = self.valid_to;
+        claim.created_at = self.created_at;
+        claim.updated_at = self.updated_at;
+        claim.metadata = self.metadata;
+        claim.visibility_labels = self.visibility_labels;
+        claim.language = self.language;
+        claim.collection = self.collection;
+        Ok((claim, self.embedding_vector))
+    }
+}
+
+fn parse_claim_type(raw: &str) -> Result<ClaimType, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "factual" => Ok(ClaimType::Factual),
+        "opinion" => Ok(ClaimType::Opinion),
+        "prediction" => Ok(ClaimType::Prediction),
+        "temporal" => Ok(ClaimType::Temporal),
+        "causal" => Ok(ClaimType::Causal),
+        _ => Err(
+            "claim.claim_type must be one of: factual, opinion, prediction, temporal, causal"
+                .to_string(),
+        ),
+    }
+}
+
+/// Deserialize a field that may be absent, `null`, or set: absence is
+/// `None` (through `#[serde(default)]`), `null` is `Some(None)`.
+fn present_or_null<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Wire-format body of `POST /v1/admin/claims/patch`. Fields left out
+/// are unchanged; `null` clears one of the optional fields.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdminClaimPatchWire {
+    #[serde(default)]
+    pub canonical_text: Option<String>,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    #[serde(default, deserialize_with = "present_or_null")]
+    pub claim_type: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present_or_null")]
+    pub event_time_unix: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present_or_null")]
+    pub valid_from: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present_or_null")]
+    pub valid_to: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present_or_null")]
+    pub updated_at: Option<Option<i64>>,
+    #[serde(default)]
+    pub entities: Option<Vec<Entity>>,
+    #[serde(default)]
+    pub embedding_ids: Option<Vec<String>>,
+    /// `[key, value]` pairs.
+    #[serde(default)]
+    pub metadata: Option<Vec<(String, String)>>,
+    #[serde(default)]
+    pub visibility_labels: Option<Vec<String>>,
+}
+
+impl AdminClaimPatchWire {
+    pub fn into_patch(self) -> Result<ClaimPatch, String> {
+        let claim_type = match self.claim_type {
+            None => None,
+            Some(None) => Some(None),
+            Some(Some(raw)) => Some(Some(parse_claim_type(&raw)?)),
+        };
+        let mut patch = ClaimPatch::default();
+        patch.canonical_text = self.canonical_text;
+        patch.confidence = self.confidence;
+        patch.claim_type = claim_type;
+        patch.event_time_unix = self.event_time_unix;
+        patch.valid_from = self.valid_from;
+        patch.valid_to = self.valid_to;
+        patch.updated_at = self.updated_at;
+        patch.entities = self.entities;
+        patch.embedding_ids = self.embedding_ids;
+        patch.metadata = self.metadata;
+        patch.visibility_labels = self.visibility_labels;
+        if patch.is_empty() {
+            return Err("patch must change at least one field".to_string());
+        }
+        Ok(patch)
+    }
+}
+
+/// Wire-format body of `POST /v1/admin/claims/entities`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdminClaimEntitiesWire {
+    pub entities: Vec<Entity>,
+}
+
+/// A claim with everything attached to it, as returned by the admin
+/// claim endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdminClaimResponse {
+    pub claim: Claim,
+    pub evidence: Vec<Evidence>,
+    pub edges: Vec<ClaimEdge>,
+    pub vector_dimension: Option<usize>,
+    pub vector_spaces: Vec<String>,
+    pub sparse_term_count: Option<usize>,
+}
+
+impl From<ClaimInspection> for AdminClaimResponse {
+    fn from(inspection: ClaimInspection) -> Self {
+        Self {
+            claim: inspection.claim,
+            evidence: inspection.evidence,
+            edges: inspection.edges,
+            vector_dimension: inspection.vector_dimension,
+            vector_spaces: inspection.vector_spaces,
+            sparse_term_count: inspection.sparse_term_count,
+        }
     }
 }
 
+/// One outbox event as served to integration consumers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutboxEventResponse {
+    pub lsn: u64,
+    pub op: &'static str,
+    pub tenant_id: String,
+    pub claim_id: String,
+}
+
+impl From<OutboxEvent> for OutboxEventResponse {
+    fn from(event: OutboxEvent) -> Self {
+        Self {
+            lsn: event.lsn,
+            op: event.op.as_str(),
+            tenant_id: event.tenant_id.into_string(),
+            claim_id: event.claim_id.into_string(),
+        }
+    }
+}
+
+/// A page of outbox events for one consumer. `acked_lsn` is the
+/// consumer's offset before this page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutboxPollResponse {
+    pub consumer: String,
+    pub acked_lsn: u64,
+    pub last_lsn: u64,
+    pub events: Vec<OutboxEventResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutboxAckResponse {
+    pub consumer: String,
+    pub acked_lsn: u64,
+    pub last_lsn: u64,
+}
+
 /// Wire-format `evidence` object. Mirrors `schema::Evidence` but accepts
 /// `stance` as a free-form string so the deserializer does not reject
 /// unknown values; the conversion validates the stance label.
@@ -250,6 +392,12 @@ pub struct EvidenceWire {
     pub extraction_model: Option<String>,
     #[serde(default)]
     pub ingested_at: Option<i64>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub stance_strength: Option<f32>,
+    #[serde(default)]
+    pub negated: bool,
 }
 
 impl EvidenceWire {
@@ -259,24 +407,26 @@ impl EvidenceWire {
             "contradicts" => Stance::Contradicts,
             "neutral" => Stance::Neutral,
             _ => {
-                return Err(
-                    "evidence.stance must be supports, contradicts, or neutral".to_string(),
-                );
+                return Err("evidence.stance must be supports, contradicts, or neutral".to_string());
             }
         };
-        Ok(Evidence {
-            evidence_id: self.evidence_id,
-            claim_id: self.claim_id,
-            source_id: self.source_id,
+        let mut evidence = Evidence::new(
+            self.evidence_id,
+            self.claim_id,
+            self.source_id,
             stance,
-            source_quality: self.source_quality,
-            chunk_id: self.chunk_id,
-            span_start: self.span_start,
-            span_end: self.span_end,
-            doc_id: self.doc_id,
-            extraction_model: self.extraction_model,
-            ingested_at: self.ingested_at,
-        })
+            self.source_quality,
+        );
+        evidence.chunk_id = self.chunk_id;
+        evidence.span_start = self.span_start;
+        evidence.span_end = self.span_end;
+        evidence.doc_id = self.doc_id;
+        evidence.extraction_model = self.extraction_model;
+        evidence.ingested_at = self.ingested_at;
+        evidence.language = self.language;
+        evidence.stance_strength = self.stance_strength;
+        evidence.negated = self.negated;
+        Ok(evidence)
     }
 }
 
@@ -305,22 +455,26 @@ impl ClaimEdgeWire {
             "refines" => Relation::Refines,
             "duplicates" => Relation::Duplicates,
             "depends_on" => Relation::DependsOn,
+            "supersedes" => Relation::Supersedes,
+            "elaborates" => Relation::Elaborates,
             _ => {
                 return Err(
-                    "edge.relation must be supports, contradicts, refines, duplicates, or depends_on"
+                    "edge.relation must be supports, contradicts, refines, duplicates, \
+                     depends_on, supersedes, or elaborates"
                         .to_string(),
                 );
             }
         };
-        Ok(ClaimEdge {
-            edge_id: self.edge_id,
-            from_claim_id: self.from_claim_id,
-            to_claim_id: self.to_claim_id,
+        let mut edge = ClaimEdge::new(
+            self.edge_id,
+            self.from_claim_id,
+            self.to_claim_id,
             relation,
-            strength: self.strength,
-            reason_codes: self.reason_codes,
-            created_at: self.created_at,
-        })
+            self.strength,
+        );
+        edge.reason_codes = self.reason_codes;
+        edge.created_at = self.created_at;
+        Ok(edge)
     }
 }
 
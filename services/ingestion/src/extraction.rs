@@ -1,7 +1,7 @@
 use crate::api::{
     IngestApiRequest, IngestBatchApiRequest, IngestDocumentApiRequest, IngestRawApiRequest,
 };
-use schema::{Claim, Evidence, Stance};
+use schema::{Claim, ClaimId, Evidence, Stance};
 use std::io::Write;
 use std::process::{Command, Stdio};
 
@@ -141,41 +141,33 @@ pub fn build_ingest_raw_output_from_request(
                 embedding_dimensions = Some(vector.len());
             }
         }
-        let claim_id = format!(
+        let claim_id = ClaimId::new(format!(
             "raw:{tenant_component}:{document_component}:c{:04}",
             index + 1
-        );
+        ));
         let evidence_id = format!(
             "raw:{tenant_component}:{document_component}:e{:04}",
             index + 1
         );
-        let claim = Claim {
-            claim_id: claim_id.clone(),
-            tenant_id: tenant_id.to_string(),
-            canonical_text: sentence.canonical_text,
-            confidence: claim_confidence,
-            event_time_unix: None,
-            entities: Vec::new(),
-            embedding_ids: embedding_model.clone().into_iter().collect(),
-            claim_type: None,
-            valid_from: None,
-            valid_to: None,
-            created_at: None,
-            updated_at: None,
-        };
-        let evidence = Evidence {
+        let claim = Claim::new(
+            claim_id.clone(),
+            tenant_id,
+            sentence.canonical_text,
+            claim_confidence,
+        )
+        .with_embedding_ids(embedding_model.clone());
+        let mut evidence = Evidence::new(
             evidence_id,
             claim_id,
-            source_id: source_id.to_string(),
-            stance: Stance::Supports,
+            source_id,
+            Stance::Supports,
             source_quality,
-            chunk_id: Some(format!("sentence-{}", index + 1)),
-            span_start: sentence.span_start,
-            span_end: sentence.span_end,
-            doc_id: Some(document_id.to_string()),
-            extraction_model: extraction_model.clone(),
-            ingested_at: None,
-        };
+        );
+        evidence.chunk_id = Some(format!("sentence-{}", index + 1));
+        evidence.span_start = sentence.span_start;
+        evidence.span_end = sentence.span_end;
+        evidence.doc_id = Some(document_id.to_string());
+        evidence.extraction_model = extraction_model.clone();
         items.push(IngestApiRequest {
             claim,
             claim_embedding,
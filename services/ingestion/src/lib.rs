@@ -78,6 +78,10 @@ mod tests {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             claim_embedding: None,
             evidence: vec![Evidence {
@@ -92,6 +96,9 @@ mod tests {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             edges: vec![],
         };
@@ -118,6 +125,10 @@ mod tests {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             claim_embedding: None,
             evidence: vec![],
@@ -125,10 +136,10 @@ mod tests {
         };
 
         let err = ingest_document(&mut store, input).unwrap_err();
-        assert_eq!(
+        assert!(matches!(
             err,
             StoreError::Validation(ValidationError::InvalidRange("confidence"))
-        );
+        ));
     }
 
     #[test]
@@ -152,6 +163,10 @@ mod tests {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             claim_embedding: None,
             evidence: vec![Evidence {
@@ -166,6 +181,9 @@ mod tests {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             edges: vec![],
         };
@@ -181,10 +199,8 @@ mod tests {
         let mut wal_path = std::env::temp_dir();
         wal_path.push(format!("eme-ingest-policy-{}.jsonl", std::process::id()));
         let mut wal = FileWal::open(&wal_path).unwrap();
-        let policy = CheckpointPolicy {
-            max_wal_records: Some(2),
-            max_wal_bytes: None,
-        };
+        let mut policy = CheckpointPolicy::default();
+        policy.max_wal_records = Some(2);
 
         let mut store = InMemoryStore::new();
         let input = IngestInput {
@@ -201,6 +217,10 @@ mod tests {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             claim_embedding: None,
             evidence: vec![Evidence {
@@ -215,6 +235,9 @@ mod tests {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             edges: vec![],
         };
@@ -246,6 +269,10 @@ mod tests {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             claim_embedding: Some(vec![0.1, 0.2, 0.3, 0.4]),
             evidence: vec![],
@@ -254,12 +281,7 @@ mod tests {
 
         ingest_document(&mut store, input).unwrap();
         let results = store.retrieve_with_time_range_and_query_vector(
-            &schema::RetrievalRequest {
-                tenant_id: "tenant-a".into(),
-                query: "vectorized claim".into(),
-                top_k: 1,
-                stance_mode: schema::StanceMode::Balanced,
-            },
+            &schema::RetrievalRequest::new("tenant-a", "vectorized claim", 1),
             None,
             None,
             Some(&[0.1, 0.2, 0.3, 0.4]),
@@ -284,6 +306,10 @@ mod tests {
                 valid_to: Some(260),
                 created_at: Some(1_771_620_000_000),
                 updated_at: Some(1_771_620_100_000),
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             claim_embedding: None,
             evidence: vec![],
@@ -292,7 +318,7 @@ mod tests {
 
         ingest_document(&mut store, input).unwrap();
         let claim = store
-            .claim_by_id("c-temporal")
+            .claim_by_id(&"c-temporal".into())
             .expect("claim should be persisted");
         assert_eq!(claim.claim_type, Some(schema::ClaimType::Temporal));
         assert_eq!(claim.valid_from, Some(120));
@@ -2,13 +2,20 @@ use ingestion::{
     IngestInput, ingest_document, ingest_document_persistent_with_policy,
     transport::IngestionRuntime, transport::serve_http_with_workers,
 };
-use schema::{Claim, Evidence, Stance};
-use store::{AnnTuningConfig, CheckpointPolicy, FileWal, InMemoryStore, WalWritePolicy};
+use metadata_router::TenantAliases;
+use schema::{Claim, Evidence, Stance, ValidationConfig};
+use store::{
+    AnnIndexKind, AnnTuningConfig, CheckpointPolicy, FileWal, InMemoryStore, Outbox, WalWritePolicy,
+};
 
 const SAFE_WAL_SYNC_EVERY_RECORDS_MAX: usize = 256;
 const SAFE_WAL_APPEND_BUFFER_RECORDS_MAX: usize = 256;
 const SAFE_WAL_SYNC_INTERVAL_MS_MAX: u64 = 5_000;
 const DEFAULT_ASYNC_WAL_FLUSH_INTERVAL_MS: u64 = 250;
+/// Checkpoint backlog, in multiples of the WAL threshold, at which
+/// ingest writes are slowed and then rejected; `0` turns either off.
+const DEFAULT_CHECKPOINT_WRITE_SLOWDOWN_MULTIPLE: u32 = 2;
+const DEFAULT_CHECKPOINT_WRITE_STOP_MULTIPLE: u32 = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum AsyncWalFlushSetting {
@@ -40,7 +47,26 @@ fn main() {
         .unwrap_or_else(|| "127.0.0.1:8081".to_string());
     let http_workers = parse_http_workers();
     let ann_tuning = parse_ann_tuning_config();
+    let validation_config = parse_validation_config();
     let segment_dir = env_with_fallback("DASH_INGEST_SEGMENT_DIR", "EME_INGEST_SEGMENT_DIR");
+    let tenant_aliases = match env_with_fallback("DASH_TENANT_ALIASES", "EME_TENANT_ALIASES")
+        .map(|raw| TenantAliases::parse(&raw))
+        .transpose()
+    {
+        Ok(aliases) => aliases.unwrap_or_default(),
+        Err(reason) => {
+            eprintln!("ingestion invalid DASH_TENANT_ALIASES: {reason}");
+            std::process::exit(2);
+        }
+    };
+    // One-shot data migration: move the WAL's data from old tenant ids to
+    // the ids they alias. Run it with the service stopped; the next start
+    // with `DASH_INGEST_SEGMENT_RECONCILE_ON_START=repair` rebuilds the
+    // segments.
+    if std::env::args().any(|arg| arg == "--migrate-tenant-aliases") {
+        migrate_tenant_aliases(&tenant_aliases);
+        return;
+    }
     let wal_sync_every_records = parse_env_with_fallback::<usize>(
         "DASH_INGEST_WAL_SYNC_EVERY_RECORDS",
         "EME_INGEST_WAL_SYNC_EVERY_RECORDS",
@@ -100,6 +126,10 @@ fn main() {
             valid_to: None,
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         },
         claim_embedding: None,
         evidence: vec![Evidence {
@@ -114,6 +144,9 @@ fn main() {
             doc_id: None,
             extraction_model: None,
             ingested_at: None,
+            language: None,
+            stance_strength: None,
+            negated: false,
         }],
         edges: vec![],
     };
@@ -154,16 +187,13 @@ fn main() {
 
         let mut wal = match FileWal::open_with_policy(
             &wal_path,
-            WalWritePolicy {
-                sync_every_records: wal_sync_every_records,
-                append_buffer_max_records: wal_append_buffer_records,
-                sync_interval: wal_sync_interval_ms.map(std::time::Duration::from_millis),
-                background_flush_only: wal_background_flush_only,
-            },
+            WalWritePolicy::new(wal_sync_every_records, wal_append_buffer_records)
+                .with_sync_interval(wal_sync_interval_ms.map(std::time::Duration::from_millis))
+                .with_background_flush_only(wal_background_flush_only),
         ) {
             Ok(wal) => wal,
             Err(err) => {
-                eprintln!("ingestion failed opening WAL '{wal_path}': {err:?}");
+                eprintln!("ingestion failed opening WAL '{wal_path}': {err}");
                 std::process::exit(1);
             }
         };
@@ -173,10 +203,11 @@ fn main() {
         ) {
             Ok(result) => result,
             Err(err) => {
-                eprintln!("ingestion failed replaying WAL '{wal_path}': {err:?}");
+                eprintln!("ingestion failed replaying WAL '{wal_path}': {err}");
                 std::process::exit(1);
             }
         };
+        store.set_validation_config(validation_config);
         // Default-on disk persistence (redb PR 2). The
         // `DASH_INGEST_PERSISTENCE_PATH` env var overrides the path;
         // setting `DASH_INGEST_PERSISTENCE_DISABLE=1` reverts to the
@@ -188,7 +219,7 @@ fn main() {
             "EME_INGEST_PERSISTENCE_DISABLE",
         )
         .as_deref()
-        == Some("1");
+            == Some("1");
         let disk_path = env_with_fallback(
             "DASH_INGEST_PERSISTENCE_PATH",
             "EME_INGEST_PERSISTENCE_PATH",
@@ -256,16 +287,25 @@ fn main() {
             wal.background_flush_only(),
             allow_unsafe_wal_durability
         );
-        let policy = CheckpointPolicy {
-            max_wal_records: parse_env_with_fallback::<usize>(
-                "DASH_CHECKPOINT_MAX_WAL_RECORDS",
-                "EME_CHECKPOINT_MAX_WAL_RECORDS",
-            ),
-            max_wal_bytes: parse_env_with_fallback::<u64>(
-                "DASH_CHECKPOINT_MAX_WAL_BYTES",
-                "EME_CHECKPOINT_MAX_WAL_BYTES",
-            ),
-        };
+        let mut policy = CheckpointPolicy::default();
+        policy.max_wal_records = parse_env_with_fallback::<usize>(
+            "DASH_CHECKPOINT_MAX_WAL_RECORDS",
+            "EME_CHECKPOINT_MAX_WAL_RECORDS",
+        );
+        policy.max_wal_bytes = parse_env_with_fallback::<u64>(
+            "DASH_CHECKPOINT_MAX_WAL_BYTES",
+            "EME_CHECKPOINT_MAX_WAL_BYTES",
+        );
+        policy.write_slowdown_multiple = parse_env_with_fallback::<u32>(
+            "DASH_CHECKPOINT_WRITE_SLOWDOWN_MULTIPLE",
+            "EME_CHECKPOINT_WRITE_SLOWDOWN_MULTIPLE",
+        )
+        .or(Some(DEFAULT_CHECKPOINT_WRITE_SLOWDOWN_MULTIPLE));
+        policy.write_stop_multiple = parse_env_with_fallback::<u32>(
+            "DASH_CHECKPOINT_WRITE_STOP_MULTIPLE",
+            "EME_CHECKPOINT_WRITE_STOP_MULTIPLE",
+        )
+        .or(Some(DEFAULT_CHECKPOINT_WRITE_STOP_MULTIPLE));
 
         if serve_mode {
             println!("ingestion transport listening on http://{bind_addr}");
@@ -285,7 +325,11 @@ fn main() {
             if let Some(segment_dir) = segment_dir.as_deref() {
                 println!("ingestion segment publish dir: {segment_dir}");
             }
-            let runtime = IngestionRuntime::persistent(store, wal, policy);
+            let mut runtime = IngestionRuntime::persistent(store, wal, policy);
+            if let Some(outbox) = open_outbox() {
+                runtime = runtime.with_outbox(outbox);
+            }
+            runtime.reconcile_segments_on_startup();
             if let Some(reason) = runtime.placement_routing_error() {
                 eprintln!("ingestion placement routing configuration error: {reason}");
                 std::process::exit(2);
@@ -315,11 +359,12 @@ fn main() {
                     store.claims_len(),
                     wal.path().display()
                 ),
-                Err(err) => eprintln!("ingestion failed: {err:?}"),
+                Err(err) => eprintln!("ingestion failed: {err}"),
             }
         }
     } else {
-        let store = InMemoryStore::new_with_ann_tuning(ann_tuning);
+        let mut store = InMemoryStore::new_with_ann_tuning(ann_tuning);
+        store.set_validation_config(validation_config);
         if serve_mode {
             println!("ingestion transport listening on http://{bind_addr}");
             println!("ingestion transport workers: {http_workers}");
@@ -338,7 +383,10 @@ fn main() {
             if let Some(segment_dir) = segment_dir.as_deref() {
                 println!("ingestion segment publish dir: {segment_dir}");
             }
-            let runtime = IngestionRuntime::in_memory(store);
+            let mut runtime = IngestionRuntime::in_memory(store);
+            if let Some(outbox) = open_outbox() {
+                runtime = runtime.with_outbox(outbox);
+            }
             if let Some(reason) = runtime.placement_routing_error() {
                 eprintln!("ingestion placement routing configuration error: {reason}");
                 std::process::exit(2);
@@ -354,18 +402,66 @@ fn main() {
                 std::process::exit(1);
             }
         } else {
-            let mut store = store;
             match ingest_document(&mut store, input) {
                 Ok(()) => println!(
                     "ingestion ready: claims={} (set DASH_INGEST_WAL_PATH for persistent mode)",
                     store.claims_len()
                 ),
-                Err(err) => eprintln!("ingestion failed: {err:?}"),
+                Err(err) => eprintln!("ingestion failed: {err}"),
             }
         }
     }
 }
 
+fn migrate_tenant_aliases(tenant_aliases: &TenantAliases) {
+    if tenant_aliases.is_empty() {
+        eprintln!("ingestion tenant migration needs DASH_TENANT_ALIASES");
+        std::process::exit(2);
+    }
+    let Some(wal_path) = env_with_fallback("DASH_INGEST_WAL_PATH", "EME_INGEST_WAL_PATH") else {
+        eprintln!("ingestion tenant migration needs DASH_INGEST_WAL_PATH");
+        std::process::exit(2);
+    };
+    let mut wal = match FileWal::open(&wal_path) {
+        Ok(wal) => wal,
+        Err(err) => {
+            eprintln!("ingestion failed opening WAL '{wal_path}': {err}");
+            std::process::exit(1);
+        }
+    };
+    match InMemoryStore::migrate_tenant_ids(&mut wal, &tenant_aliases.resolved_pairs()) {
+        Ok((_, stats)) => println!(
+            "ingestion tenant migration: claims_rewritten={}, tenant_settings_rewritten={}, ann_graphs_dropped={}, snapshot_records={}",
+            stats.claims_rewritten,
+            stats.tenant_settings_rewritten,
+            stats.ann_graphs_dropped,
+            stats.snapshot_records
+        ),
+        Err(err) => {
+            eprintln!("ingestion tenant migration failed for WAL '{wal_path}': {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn open_outbox() -> Option<Outbox> {
+    let path = env_with_fallback("DASH_INGEST_OUTBOX_PATH", "EME_INGEST_OUTBOX_PATH")?;
+    match Outbox::open(&path) {
+        Ok(outbox) => {
+            println!(
+                "ingestion outbox: path={path}, last_lsn={}, consumers={}",
+                outbox.last_lsn(),
+                outbox.consumers().len()
+            );
+            Some(outbox)
+        }
+        Err(err) => {
+            eprintln!("ingestion outbox open failed for '{path}': {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
 fn env_with_fallback(primary: &str, fallback: &str) -> Option<String> {
     std::env::var(primary)
         .ok()
@@ -562,9 +658,71 @@ fn parse_ann_tuning_config() -> AnnTuningConfig {
         ])
         .filter(|value| *value > 0)
         .unwrap_or(defaults.search_expansion_max),
+        index_kind: parse_env_first::<String>(&[
+            "DASH_INGEST_ANN_INDEX_KIND",
+            "DASH_ANN_INDEX_KIND",
+            "EME_INGEST_ANN_INDEX_KIND",
+            "EME_ANN_INDEX_KIND",
+        ])
+        .and_then(|value| AnnIndexKind::parse(&value))
+        .unwrap_or(defaults.index_kind),
+        exact_search_threshold: parse_env_first::<usize>(&[
+            "DASH_INGEST_ANN_EXACT_SEARCH_THRESHOLD",
+            "DASH_ANN_EXACT_SEARCH_THRESHOLD",
+            "EME_INGEST_ANN_EXACT_SEARCH_THRESHOLD",
+            "EME_ANN_EXACT_SEARCH_THRESHOLD",
+        ])
+        .unwrap_or(defaults.exact_search_threshold),
+        level_multiplier: parse_env_first::<f64>(&[
+            "DASH_INGEST_ANN_LEVEL_MULTIPLIER",
+            "DASH_ANN_LEVEL_MULTIPLIER",
+            "EME_INGEST_ANN_LEVEL_MULTIPLIER",
+            "EME_ANN_LEVEL_MULTIPLIER",
+        ])
+        .filter(|value| value.is_finite() && *value >= 0.0)
+        .unwrap_or(defaults.level_multiplier),
+        level_seed: parse_env_first::<u64>(&[
+            "DASH_INGEST_ANN_LEVEL_SEED",
+            "DASH_ANN_LEVEL_SEED",
+            "EME_INGEST_ANN_LEVEL_SEED",
+            "EME_ANN_LEVEL_SEED",
+        ])
+        .unwrap_or(defaults.level_seed),
     }
 }
 
+/// Ingest size limits; each unset variable keeps the store default and
+/// `0` lifts that limit.
+fn parse_validation_config() -> ValidationConfig {
+    let limit = |primary: &str, fallback: &str, default: usize| {
+        parse_env_with_fallback::<usize>(primary, fallback)
+            .map(|value| if value == 0 { usize::MAX } else { value })
+            .unwrap_or(default)
+    };
+    let mut config = ValidationConfig::default();
+    config.max_text_len = limit(
+        "DASH_INGEST_MAX_CLAIM_TEXT_LEN",
+        "EME_INGEST_MAX_CLAIM_TEXT_LEN",
+        config.max_text_len,
+    );
+    config.max_entities = limit(
+        "DASH_INGEST_MAX_CLAIM_ENTITIES",
+        "EME_INGEST_MAX_CLAIM_ENTITIES",
+        config.max_entities,
+    );
+    config.max_embedding_ids = limit(
+        "DASH_INGEST_MAX_CLAIM_EMBEDDING_IDS",
+        "EME_INGEST_MAX_CLAIM_EMBEDDING_IDS",
+        config.max_embedding_ids,
+    );
+    config.max_evidence_per_bundle = limit(
+        "DASH_INGEST_MAX_EVIDENCE_PER_BUNDLE",
+        "EME_INGEST_MAX_EVIDENCE_PER_BUNDLE",
+        config.max_evidence_per_bundle,
+    );
+    config
+}
+
 fn parse_env_first<T>(keys: &[&str]) -> Option<T>
 where
     T: std::str::FromStr,
@@ -9,6 +9,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+mod admin_routes;
 mod audit;
 mod authz;
 mod config;
@@ -16,6 +17,7 @@ mod document_parser_debug;
 mod http;
 mod ingest_routes;
 mod json;
+mod outbox_routes;
 mod payload;
 mod persistence;
 mod placement_debug;
@@ -26,12 +28,14 @@ mod request;
 mod routes;
 mod segment_runtime;
 mod server_runtime;
+mod storage_health;
 
 use audit::{AuditEvent, emit_audit_event};
-use authz::{AuthDecision, AuthPolicy, authorize_request_for_tenant};
+use authz::{AuthDecision, AuthPolicy, authorize_admin_request, authorize_request_for_tenant};
 use config::{
-    env_with_fallback, generate_batch_commit_id, parse_env_first_usize,
-    resolve_ingest_batch_max_items, resolve_wal_async_flush_interval, unix_timestamp_millis,
+    canonical_tenant_id, env_with_fallback, generate_batch_commit_id, parse_env_first_usize,
+    resolve_ingest_batch_max_items, resolve_wal_async_flush_interval, tenant_aliases_from_env,
+    unix_timestamp_millis,
 };
 use document_parser_debug::render_document_parser_debug_json;
 use http::{
@@ -57,21 +61,23 @@ use replication::{
     render_replication_export_frame, run_replication_pull_tick,
 };
 use request::{parse_query_usize, parse_request_line, read_http_request, split_target};
-use schema::Claim;
-use segment_runtime::SegmentRuntime;
+use schema::{Claim, ClaimId};
+use segment_runtime::{SegmentReconcileMode, SegmentRuntime};
+use storage_health::render_health_json;
 use store::{
-    CheckpointPolicy, FileWal, InMemoryStore, StoreError, WalReplicationDelta,
-    WalReplicationExport, batch_commit_payload_fingerprint,
+    CheckpointPolicy, ClaimInspection, ClaimPatch, FileWal, InMemoryStore, Outbox, OutboxOp,
+    StoreError, WalReplicationDelta, WalReplicationExport, batch_commit_payload_fingerprint,
 };
 
 use crate::{
     IngestInput,
     api::{
         IngestApiRequest, IngestApiResponse, IngestBatchApiRequest, IngestBatchApiResponse,
-        IngestDocumentApiResponse, IngestRawApiResponse, WriteConsistencyPolicy,
+        IngestDocumentApiResponse, IngestRawApiResponse, OutboxAckResponse, OutboxPollResponse,
+        WriteConsistencyPolicy,
     },
     extraction::{build_ingest_batch_from_document_request, build_ingest_raw_output_from_request},
-    ingest_document, ingest_document_persistent_with_policy,
+    ingest_document, ingest_document_persistent,
 };
 
 #[cfg(test)]
@@ -134,6 +140,11 @@ pub struct IngestionRuntime {
     replication_last_error: Option<String>,
     replication_commit_status: HashMap<String, ReplicationCommitStatus>,
     transport_backpressure: Option<Arc<TransportBackpressureMetrics>>,
+    outbox: Option<Outbox>,
+    outbox_events_total: u64,
+    /// Write slowdown owed by the last ingest, waited out by the handler
+    /// once it has released the runtime lock.
+    write_delay: Option<Duration>,
     started_at: Instant,
 }
 
@@ -241,6 +252,9 @@ impl IngestionRuntime {
             replication_last_error: None,
             replication_commit_status: HashMap::new(),
             transport_backpressure: None,
+            outbox: None,
+            outbox_events_total: 0,
+            write_delay: None,
             started_at: Instant::now(),
         }
     }
@@ -303,10 +317,20 @@ impl IngestionRuntime {
             replication_last_error: None,
             replication_commit_status: HashMap::new(),
             transport_backpressure: None,
+            outbox: None,
+            outbox_events_total: 0,
+            write_delay: None,
             started_at: Instant::now(),
         }
     }
 
+    /// Record every committed ingest in `outbox` for integration
+    /// consumers.
+    pub fn with_outbox(mut self, outbox: Outbox) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
+
     pub fn claims_len(&self) -> usize {
         self.store.claims_len()
     }
@@ -381,7 +405,7 @@ impl IngestionRuntime {
             })
             .ok_or_else(|| {
                 WriteRouteError::Placement(PlacementRouteError::PlacementNotFound {
-                    tenant_id: claim.tenant_id.clone(),
+                    tenant_id: claim.tenant_id.to_string(),
                     shard_id: routed.shard_id,
                 })
             })?;
@@ -432,11 +456,12 @@ impl IngestionRuntime {
             edges: request.edges,
         };
         let checkpoint_stats = self.ingest_input_internal(input)?;
+        self.append_outbox_events(&[(tenant_id.to_string(), ingested_claim_id.to_string())])?;
 
         self.successful_ingests += 1;
         self.publish_segments_for_tenant(&tenant_id);
         Ok(IngestApiResponse {
-            ingested_claim_id,
+            ingested_claim_id: ingested_claim_id.into(),
             claims_total: self.store.claims_len(),
             commit_epoch: None,
             ack_count: 1,
@@ -458,18 +483,20 @@ impl IngestionRuntime {
         let mut inputs = Vec::with_capacity(request.items.len());
         let mut ingested_claim_ids = Vec::with_capacity(request.items.len());
         let mut touched_tenants = HashSet::new();
+        let mut batch_claims = Vec::with_capacity(request.items.len());
 
         for item in request.items {
-            let tenant_id = item.claim.tenant_id.clone();
-            let claim_id = item.claim.claim_id.clone();
+            let tenant_id = item.claim.tenant_id.to_string();
+            let claim_id = item.claim.claim_id.to_string();
             inputs.push(IngestInput {
                 claim: item.claim,
                 claim_embedding: item.claim_embedding,
                 evidence: item.evidence,
                 edges: item.edges,
             });
+            batch_claims.push((tenant_id.clone(), claim_id.clone()));
             touched_tenants.insert(tenant_id);
-            ingested_claim_ids.push(claim_id);
+            ingested_claim_ids.push(ClaimId::from(claim_id));
         }
 
         if let Some(existing) = self.store.batch_commit_metadata(&commit_id) {
@@ -489,7 +516,10 @@ impl IngestionRuntime {
                 commit_id,
                 idempotent_replay: true,
                 batch_size: ingested_claim_ids.len(),
-                ingested_claim_ids,
+                ingested_claim_ids: ingested_claim_ids
+                    .into_iter()
+                    .map(ClaimId::into_string)
+                    .collect(),
                 claims_total: self.store.claims_len(),
                 commit_epoch: None,
                 ack_count: 1,
@@ -501,7 +531,8 @@ impl IngestionRuntime {
             });
         }
 
-        let mut staged_store = self.store.clone();
+        self.throttle_write()?;
+        let mut staged_store = self.store.staged();
         for input in &inputs {
             ingest_document(&mut staged_store, input.clone())?;
         }
@@ -523,14 +554,15 @@ impl IngestionRuntime {
             })();
             if let Err(err) = append_result {
                 if let Err(rollback_err) = wal.rollback_to(rollback_point) {
-                    eprintln!("batch rollback failed after WAL append error: {rollback_err:?}");
+                    eprintln!("batch rollback failed after WAL append error: {rollback_err}");
                 }
                 return Err(err);
             }
             self.batch_commit_total = self.batch_commit_total.saturating_add(1);
         }
 
-        self.store = staged_store;
+        self.store.commit_staged(staged_store);
+        self.append_outbox_events(&batch_claims)?;
         self.successful_ingests = self
             .successful_ingests
             .saturating_add(ingested_claim_ids.len() as u64);
@@ -548,7 +580,7 @@ impl IngestionRuntime {
             match self.store.checkpoint_and_compact(wal) {
                 Ok(stats) => checkpoint_stats = Some(stats),
                 Err(err) => {
-                    eprintln!("ingestion batch checkpoint failed after commit: {err:?}");
+                    eprintln!("ingestion batch checkpoint failed after commit: {err}");
                 }
             }
         }
@@ -563,7 +595,10 @@ impl IngestionRuntime {
             commit_id,
             idempotent_replay: false,
             batch_size: ingested_claim_ids.len(),
-            ingested_claim_ids,
+            ingested_claim_ids: ingested_claim_ids
+                .into_iter()
+                .map(ClaimId::into_string)
+                .collect(),
             claims_total: self.store.claims_len(),
             commit_epoch: None,
             ack_count: 1,
@@ -581,13 +616,14 @@ impl IngestionRuntime {
         &mut self,
         input: IngestInput,
     ) -> Result<Option<store::WalCheckpointStats>, StoreError> {
+        self.throttle_write()?;
         let checkpoint_stats = if let Some(wal) = self.wal.as_mut() {
-            ingest_document_persistent_with_policy(
-                &mut self.store,
-                wal,
-                &self.checkpoint_policy,
-                input,
-            )?
+            ingest_document_persistent(&mut self.store, wal, input)?;
+            if should_checkpoint_now(&self.checkpoint_policy, wal)? {
+                Some(self.store.checkpoint_and_compact(wal)?)
+            } else {
+                None
+            }
         } else {
             ingest_document(&mut self.store, input)?;
             None
@@ -595,9 +631,113 @@ impl IngestionRuntime {
         Ok(checkpoint_stats)
     }
 
+    /// Apply the checkpoint policy's write flow control, keeping any
+    /// slowdown for [`Self::take_write_delay`] rather than sleeping with
+    /// the runtime locked.
+    fn throttle_write(&mut self) -> Result<(), StoreError> {
+        self.write_delay = match self.wal.as_mut() {
+            Some(wal) => self.store.throttle_write(wal, &self.checkpoint_policy)?,
+            None => None,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn take_write_delay(&mut self) -> Option<Duration> {
+        self.write_delay.take()
+    }
+
+    /// Events are appended once the write has committed, so a failure
+    /// here is reported to the client, whose retry records them again.
+    fn append_outbox_events(&mut self, claims: &[(String, String)]) -> Result<(), StoreError> {
+        let Some(outbox) = self.outbox.as_mut() else {
+            return Ok(());
+        };
+        outbox.append(claims.iter().map(|(tenant_id, claim_id)| {
+            (tenant_id.as_str(), claim_id.as_str(), OutboxOp::Ingest)
+        }))?;
+        self.outbox_events_total = self.outbox_events_total.saturating_add(claims.len() as u64);
+        Ok(())
+    }
+
+    /// The next `limit` events for `consumer`. `None` when no outbox is
+    /// configured.
+    fn outbox_poll(
+        &self,
+        consumer: &str,
+        limit: usize,
+    ) -> Option<Result<OutboxPollResponse, StoreError>> {
+        let outbox = self.outbox.as_ref()?;
+        let result = outbox
+            .poll(consumer, limit)
+            .map(|events| OutboxPollResponse {
+                consumer: consumer.to_string(),
+                acked_lsn: outbox.acked_lsn(consumer),
+                last_lsn: outbox.last_lsn(),
+                events: events.into_iter().map(Into::into).collect(),
+            });
+        Some(result)
+    }
+
+    /// Acknowledge `lsn` for `consumer` and drop the events every
+    /// consumer is done with.
+    fn outbox_ack(
+        &mut self,
+        consumer: &str,
+        lsn: u64,
+    ) -> Option<Result<OutboxAckResponse, StoreError>> {
+        let outbox = self.outbox.as_mut()?;
+        let result = outbox.ack(consumer, lsn).and_then(|acked_lsn| {
+            outbox.prune_acknowledged()?;
+            Ok(OutboxAckResponse {
+                consumer: consumer.to_string(),
+                acked_lsn,
+                last_lsn: outbox.last_lsn(),
+            })
+        });
+        Some(result)
+    }
+
+    fn admin_inspect_claim(&self, tenant_id: &str, claim_id: &str) -> Option<ClaimInspection> {
+        self.store
+            .inspect_claim(&tenant_id.into(), &claim_id.into())
+    }
+
+    fn admin_patch_claim(
+        &mut self,
+        tenant_id: &str,
+        claim_id: &str,
+        patch: &ClaimPatch,
+    ) -> Result<ClaimInspection, StoreError> {
+        match self.wal.as_mut() {
+            Some(wal) => self.store.patch_claim_persistent(
+                wal,
+                &tenant_id.into(),
+                &claim_id.into(),
+                patch,
+            )?,
+            None => self
+                .store
+                .patch_claim(&tenant_id.into(), &claim_id.into(), patch)?,
+        };
+        self.publish_segments_for_tenant(tenant_id);
+        self.admin_inspect_claim(tenant_id, claim_id)
+            .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string()))
+    }
+
+    fn admin_reindex_claim(
+        &mut self,
+        tenant_id: &str,
+        claim_id: &str,
+    ) -> Result<ClaimInspection, StoreError> {
+        self.store
+            .reindex_claim(&tenant_id.into(), &claim_id.into())?;
+        self.admin_inspect_claim(tenant_id, claim_id)
+            .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string()))
+    }
+
     fn publish_segments_for_tenant(&mut self, tenant_id: &str) {
         if let Some(segment_runtime) = self.segment_runtime.as_ref() {
-            match segment_runtime.publish_for_tenant(&self.store, tenant_id) {
+            match segment_runtime.publish_for_tenant(&self.store, self.wal.as_mut(), tenant_id) {
                 Ok(stats) => {
                     self.segment_publish_success_total =
                         self.segment_publish_success_total.saturating_add(1);
@@ -610,7 +750,7 @@ impl IngestionRuntime {
                     self.segment_publish_failure_total =
                         self.segment_publish_failure_total.saturating_add(1);
                     eprintln!(
-                        "ingestion segment publish failed for tenant '{}': {err:?}",
+                        "ingestion segment publish failed for tenant '{}': {err}",
                         tenant_id
                     );
                 }
@@ -772,7 +912,7 @@ impl IngestionRuntime {
             Err(err) => {
                 self.wal_flush_due_total += 1;
                 self.wal_flush_failure_total += 1;
-                eprintln!("ingestion WAL interval flush failed: {err:?}");
+                eprintln!("ingestion WAL interval flush failed: {err}");
             }
         }
     }
@@ -794,7 +934,7 @@ impl IngestionRuntime {
             Err(err) => {
                 self.wal_flush_due_total += 1;
                 self.wal_flush_failure_total += 1;
-                eprintln!("ingestion WAL async flush failed: {err:?}");
+                eprintln!("ingestion WAL async flush failed: {err}");
             }
         }
     }
@@ -848,7 +988,48 @@ impl IngestionRuntime {
             Err(err) => {
                 self.segment_maintenance_failure_total =
                     self.segment_maintenance_failure_total.saturating_add(1);
-                eprintln!("ingestion segment maintenance tick failed: {err:?}");
+                eprintln!("ingestion segment maintenance tick failed: {err}");
+            }
+        }
+    }
+
+    /// Boot-time check that active segments and the replayed store agree.
+    /// Divergence is logged per tenant and, when the segment runtime is
+    /// configured with `DASH_INGEST_SEGMENT_RECONCILE_ON_START=repair`,
+    /// fixed by republishing the tenant's segments from the store.
+    pub fn reconcile_segments_on_startup(&mut self) {
+        let Some(segment_runtime) = self.segment_runtime.as_ref() else {
+            return;
+        };
+        let repair = match segment_runtime.startup_reconcile {
+            SegmentReconcileMode::Off => return,
+            SegmentReconcileMode::Report => false,
+            SegmentReconcileMode::Repair => true,
+        };
+        match segment_runtime.reconcile_with_store(&self.store, self.wal.as_mut(), repair) {
+            Ok(stats) => {
+                for (tenant_dir, report) in &stats.divergent {
+                    eprintln!(
+                        "ingestion segment reconcile: tenant_dir='{}' missing_from_store={} missing_from_segments={} wal_alignment={} repaired={}",
+                        tenant_dir,
+                        report.missing_from_store.len(),
+                        report.missing_from_segments.len(),
+                        report
+                            .wal_alignment
+                            .map(|alignment| alignment.as_str())
+                            .unwrap_or("none"),
+                        repair
+                    );
+                }
+                println!(
+                    "ingestion segment reconcile: tenants_checked={}, divergent={}, repaired={}",
+                    stats.tenants_checked,
+                    stats.divergent.len(),
+                    stats.repaired_tenants
+                );
+            }
+            Err(err) => {
+                eprintln!("ingestion segment reconcile failed: {err}");
             }
         }
     }
@@ -859,14 +1040,18 @@ impl IngestionRuntime {
         max_records: usize,
     ) -> Result<WalReplicationDelta, StoreError> {
         let wal = self.wal.as_mut().ok_or_else(|| {
-            StoreError::Io("replication source requires persistent WAL mode".to_string())
+            StoreError::Io(std::io::Error::other(
+                "replication source requires persistent WAL mode",
+            ))
         })?;
         wal.replication_delta_from(from_offset, max_records)
     }
 
     fn replication_export_for_followers(&mut self) -> Result<WalReplicationExport, StoreError> {
         let wal = self.wal.as_mut().ok_or_else(|| {
-            StoreError::Io("replication source requires persistent WAL mode".to_string())
+            StoreError::Io(std::io::Error::other(
+                "replication source requires persistent WAL mode",
+            ))
         })?;
         wal.replication_export()
     }
@@ -884,7 +1069,7 @@ impl IngestionRuntime {
             return Ok(());
         }
 
-        let mut staged_store = self.store.clone();
+        let mut staged_store = self.store.staged();
         for line in wal_lines {
             staged_store.apply_persisted_record_line(line)?;
         }
@@ -899,15 +1084,13 @@ impl IngestionRuntime {
             })();
             if let Err(err) = append_result {
                 if let Err(rollback_err) = wal.rollback_to(rollback_point) {
-                    eprintln!(
-                        "replication rollback failed after WAL append error: {rollback_err:?}"
-                    );
+                    eprintln!("replication rollback failed after WAL append error: {rollback_err}");
                 }
                 return Err(err);
             }
         }
 
-        self.store = staged_store;
+        self.store.commit_staged(staged_store);
         for tenant_id in self.store.tenant_ids() {
             self.publish_segments_for_tenant(&tenant_id);
         }
@@ -1015,6 +1198,12 @@ impl IngestionRuntime {
         } else {
             0.0
         };
+        let wal_checkpoint_backlog = self
+            .wal
+            .as_ref()
+            .and_then(|wal| self.checkpoint_policy.checkpoint_backlog(wal).ok())
+            .unwrap_or(0.0);
+        let store_metrics = self.store.metrics_snapshot();
         let transport_queue_capacity = self
             .transport_backpressure
             .as_ref()
@@ -1141,6 +1330,14 @@ dash_ingest_wal_async_flush_interval_ms {}\n\
 dash_ingest_wal_async_flush_tick_total {}\n\
 # TYPE dash_ingest_wal_background_flush_only gauge\n\
 dash_ingest_wal_background_flush_only {}\n\
+# TYPE dash_ingest_wal_checkpoint_backlog gauge\n\
+dash_ingest_wal_checkpoint_backlog {:.4}\n\
+# TYPE dash_ingest_write_slowdown_total counter\n\
+dash_ingest_write_slowdown_total {}\n\
+# TYPE dash_ingest_write_slowdown_micros_total counter\n\
+dash_ingest_write_slowdown_micros_total {}\n\
+# TYPE dash_ingest_write_stall_reject_total counter\n\
+dash_ingest_write_stall_reject_total {}\n\
 # TYPE dash_ingest_transport_queue_capacity gauge\n\
 dash_ingest_transport_queue_capacity {}\n\
 # TYPE dash_ingest_transport_queue_depth gauge\n\
@@ -1159,6 +1356,10 @@ dash_ingest_replication_resync_total {}\n\
 dash_ingest_replication_last_offset {}\n\
 # TYPE dash_ingest_replication_last_error gauge\n\
 dash_ingest_replication_last_error {}\n\
+# TYPE dash_ingest_outbox_events_total counter\n\
+dash_ingest_outbox_events_total {}\n\
+# TYPE dash_ingest_outbox_last_lsn gauge\n\
+dash_ingest_outbox_last_lsn {}\n\
 # TYPE dash_ingest_claims_total gauge\n\
 dash_ingest_claims_total {}\n\
 # TYPE dash_ingest_uptime_seconds gauge\n\
@@ -1218,6 +1419,10 @@ dash_ingest_uptime_seconds {:.4}\n",
             wal_async_flush_interval_ms,
             self.wal_async_flush_tick_total,
             wal_background_flush_only,
+            wal_checkpoint_backlog,
+            store_metrics.write_slowdowns,
+            store_metrics.write_slowdown_micros_total,
+            store_metrics.write_stops,
             transport_queue_capacity,
             transport_queue_depth,
             transport_queue_full_reject_total,
@@ -1227,6 +1432,8 @@ dash_ingest_uptime_seconds {:.4}\n",
             self.replication_resync_total,
             self.replication_last_offset,
             self.replication_last_error.is_some() as usize,
+            self.outbox_events_total,
+            self.outbox.as_ref().map_or(0, Outbox::last_lsn),
             self.store.claims_len(),
             self.started_at.elapsed().as_secs_f64()
         )
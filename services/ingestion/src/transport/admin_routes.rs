@@ -0,0 +1,147 @@
+use super::ingest_routes::{observe_auth_failure, observe_auth_success, observe_authz_denied};
+use super::payload::{
+    build_admin_claim_entities_from_json, build_admin_claim_patch_from_json,
+    render_admin_claim_response_json,
+};
+use super::*;
+use crate::api::AdminClaimResponse;
+
+/// The admin routes for curating a single claim. Each takes the claim
+/// through `tenant_id` and `claim_id` query parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum AdminClaimAction {
+    /// `GET /v1/admin/claims`: the claim with its evidence, edges, and
+    /// vector summary.
+    Inspect,
+    /// `POST /v1/admin/claims/patch`: change some of the claim's fields.
+    Patch,
+    /// `POST /v1/admin/claims/entities`: replace the claim's entities.
+    ReassignEntities,
+    /// `POST /v1/admin/claims/reindex`: rebuild the claim's index entries.
+    Reindex,
+}
+
+impl AdminClaimAction {
+    fn audit_action(self) -> &'static str {
+        match self {
+            Self::Inspect => "admin_claim_inspect",
+            Self::Patch => "admin_claim_patch",
+            Self::ReassignEntities => "admin_claim_entities",
+            Self::Reindex => "admin_claim_reindex",
+        }
+    }
+}
+
+enum AdminClaimOperation {
+    Inspect,
+    Reindex,
+    Patch(ClaimPatch),
+}
+
+pub(super) fn handle_admin_claim_request(
+    runtime: &SharedRuntime,
+    request: &HttpRequest,
+    query: &HashMap<String, String>,
+    action: AdminClaimAction,
+    audit_log_path: Option<&str>,
+) -> HttpResponse {
+    let tenant_id = match query.get("tenant_id").map(|value| value.trim()) {
+        Some(value) if !value.is_empty() => canonical_tenant_id(value.to_string()),
+        _ => return HttpResponse::bad_request("tenant_id query parameter is required"),
+    };
+    let tenant_id = tenant_id.as_str();
+    let claim_id = match query.get("claim_id").map(|value| value.trim()) {
+        Some(value) if !value.is_empty() => value,
+        _ => return HttpResponse::bad_request("claim_id query parameter is required"),
+    };
+    let audit = |status: u16, outcome: &str, reason: &str| {
+        emit_audit_event(
+            runtime,
+            audit_log_path,
+            AuditEvent {
+                action: action.audit_action(),
+                tenant_id: Some(tenant_id),
+                claim_id: Some(claim_id),
+                status,
+                outcome,
+                reason,
+            },
+        );
+    };
+
+    match authorize_admin_request(request) {
+        AuthDecision::Unauthorized(reason) => {
+            observe_auth_failure(runtime);
+            audit(401, "denied", reason);
+            return HttpResponse::unauthorized(reason);
+        }
+        AuthDecision::Forbidden(reason) => {
+            observe_authz_denied(runtime);
+            audit(403, "denied", reason);
+            return HttpResponse::forbidden(reason);
+        }
+        AuthDecision::Allowed => observe_auth_success(runtime),
+    }
+
+    let operation = match action {
+        AdminClaimAction::Inspect => AdminClaimOperation::Inspect,
+        AdminClaimAction::Reindex => AdminClaimOperation::Reindex,
+        AdminClaimAction::Patch | AdminClaimAction::ReassignEntities => {
+            let body = match std::str::from_utf8(&request.body) {
+                Ok(body) => body,
+                Err(_) => return HttpResponse::bad_request("request body must be valid UTF-8"),
+            };
+            let patch = if action == AdminClaimAction::Patch {
+                build_admin_claim_patch_from_json(body)
+            } else {
+                build_admin_claim_entities_from_json(body).map(|entities| {
+                    let mut patch = ClaimPatch::default();
+                    patch.entities = Some(entities);
+                    patch
+                })
+            };
+            match patch {
+                Ok(patch) => AdminClaimOperation::Patch(patch),
+                Err(reason) => return HttpResponse::bad_request(&reason),
+            }
+        }
+    };
+
+    let mut guard = match runtime.lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            return HttpResponse::internal_server_error("failed to acquire ingestion runtime lock");
+        }
+    };
+    let result = match operation {
+        AdminClaimOperation::Inspect => guard
+            .admin_inspect_claim(tenant_id, claim_id)
+            .ok_or_else(|| StoreError::MissingClaim(claim_id.to_string())),
+        AdminClaimOperation::Reindex => guard.admin_reindex_claim(tenant_id, claim_id),
+        AdminClaimOperation::Patch(patch) => {
+            guard.flush_wal_if_due();
+            guard.admin_patch_claim(tenant_id, claim_id, &patch)
+        }
+    };
+    drop(guard);
+
+    match result {
+        Ok(inspection) => {
+            audit(200, "success", "admin claim request completed");
+            HttpResponse::ok_json(render_admin_claim_response_json(&AdminClaimResponse::from(
+                inspection,
+            )))
+        }
+        Err(StoreError::MissingClaim(_)) => {
+            audit(404, "error", "unknown claim");
+            HttpResponse::not_found(&format!(
+                "unknown claim '{claim_id}' for tenant '{tenant_id}'"
+            ))
+        }
+        Err(err) => {
+            let (status, message) = map_store_error(&err);
+            audit(status, "error", &message);
+            HttpResponse::error_with_status(status, &message)
+        }
+    }
+}
@@ -4,8 +4,9 @@ use std::{
 };
 
 use auth::{JwtValidationConfig, JwtValidationError, verify_hs256_token_for_tenant};
+use metadata_router::TenantAliases;
 
-use super::{HttpRequest, env_with_fallback};
+use super::{HttpRequest, env_with_fallback, tenant_aliases_from_env};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(super) enum AuthDecision {
@@ -22,6 +23,7 @@ pub(super) struct AuthPolicy {
     jwt_validation: Option<JwtValidationConfig>,
     rate_limiter: Option<TenantRateLimiter>,
     revocation_list: Option<RevocationList>,
+    tenant_aliases: TenantAliases,
 }
 
 impl AuthPolicy {
@@ -66,6 +68,7 @@ impl AuthPolicy {
                 "DASH_INGEST_REVOKED_KEYS_PATH",
                 "EME_INGEST_REVOKED_KEYS_PATH",
             )),
+            tenant_aliases: tenant_aliases_from_env(),
         }
     }
 }
@@ -83,6 +86,10 @@ impl TenantScope {
             Self::Set(tenants) => tenants.contains(tenant_id),
         }
     }
+
+    fn allows_any(&self, tenant_ids: &[&str]) -> bool {
+        tenant_ids.iter().any(|tenant_id| self.allows(tenant_id))
+    }
 }
 
 pub(super) fn authorize_request_for_tenant(
@@ -90,13 +97,24 @@ pub(super) fn authorize_request_for_tenant(
     tenant_id: &str,
     policy: &AuthPolicy,
 ) -> AuthDecision {
+    // Grants made to a tenant's old ids carry over to the id they now
+    // resolve to.
+    let mut tenant_ids = vec![tenant_id];
+    tenant_ids.extend(policy.tenant_aliases.aliases_of(tenant_id));
+
     if let Some(jwt_config) = policy.jwt_validation.as_ref()
         && let Some(token) = presented_bearer_token(request)
         && bearer_looks_like_jwt(token)
     {
-        return match verify_hs256_token_for_tenant(token, tenant_id, jwt_config, unix_now_secs()) {
+        let now = unix_now_secs();
+        let verified = tenant_ids
+            .iter()
+            .map(|tenant_id| verify_hs256_token_for_tenant(token, tenant_id, jwt_config, now))
+            .find(Result::is_ok)
+            .unwrap_or_else(|| verify_hs256_token_for_tenant(token, tenant_id, jwt_config, now));
+        return match verified {
             Ok(()) => {
-                if !policy.allowed_tenants.allows(tenant_id) {
+                if !policy.allowed_tenants.allows_any(&tenant_ids) {
                     AuthDecision::Forbidden("tenant is not allowed by service policy")
                 } else {
                     AuthDecision::Allowed
@@ -129,7 +147,7 @@ pub(super) fn authorize_request_for_tenant(
             return AuthDecision::Unauthorized("missing or invalid API key");
         };
         if let Some(scope) = policy.scoped_api_keys.get(api_key) {
-            if !scope.allows(tenant_id) {
+            if !scope.allows_any(&tenant_ids) {
                 return AuthDecision::Forbidden("tenant is not allowed for this API key");
             }
         } else if policy.required_api_keys.is_empty() || !policy.required_api_keys.contains(api_key)
@@ -142,7 +160,7 @@ pub(super) fn authorize_request_for_tenant(
         return AuthDecision::Unauthorized("missing or invalid API key");
     }
 
-    if !policy.allowed_tenants.allows(tenant_id) {
+    if !policy.allowed_tenants.allows_any(&tenant_ids) {
         return AuthDecision::Forbidden("tenant is not allowed by service policy");
     }
     if let Some(ref limiter) = policy.rate_limiter
@@ -157,6 +175,34 @@ pub(super) fn authorize_request_for_tenant(
     AuthDecision::Allowed
 }
 
+/// Admin routes take only the keys in `DASH_INGEST_ADMIN_API_KEY(S)`;
+/// tenant API keys and JWTs do not grant admin access. The routes stay
+/// closed until an admin key is configured.
+pub(super) fn authorize_admin_request(request: &HttpRequest) -> AuthDecision {
+    let admin_api_keys = parse_api_key_set(
+        env_with_fallback("DASH_INGEST_ADMIN_API_KEY", "EME_INGEST_ADMIN_API_KEY").as_deref(),
+        env_with_fallback("DASH_INGEST_ADMIN_API_KEYS", "EME_INGEST_ADMIN_API_KEYS").as_deref(),
+    );
+    if admin_api_keys.is_empty() {
+        return AuthDecision::Forbidden("admin API is disabled");
+    }
+    let revoked_api_keys = parse_api_key_set(
+        None,
+        env_with_fallback(
+            "DASH_INGEST_REVOKED_API_KEYS",
+            "EME_INGEST_REVOKED_API_KEYS",
+        )
+        .as_deref(),
+    );
+    match presented_api_key(request) {
+        Some(key) if revoked_api_keys.contains(key) => {
+            AuthDecision::Unauthorized("API key revoked")
+        }
+        Some(key) if admin_api_keys.contains(key) => AuthDecision::Allowed,
+        _ => AuthDecision::Unauthorized("missing or invalid admin API key"),
+    }
+}
+
 fn presented_api_key(request: &HttpRequest) -> Option<&str> {
     if let Some(value) = request.headers.get("x-api-key") {
         return Some(value.as_str());
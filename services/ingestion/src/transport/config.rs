@@ -3,6 +3,7 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use metadata_router::TenantAliases;
 use store::FileWal;
 
 static BATCH_COMMIT_COUNTER: AtomicU64 = AtomicU64::new(1);
@@ -29,6 +30,24 @@ pub(super) fn env_with_fallback(primary: &str, fallback: &str) -> Option<String>
         .or_else(|| std::env::var(fallback).ok())
 }
 
+/// Tenant aliases from `DASH_TENANT_ALIASES` (`old=new,...`). Startup
+/// rejects an invalid value, so a parse failure here means no aliases.
+pub(super) fn tenant_aliases_from_env() -> TenantAliases {
+    env_with_fallback("DASH_TENANT_ALIASES", "EME_TENANT_ALIASES")
+        .and_then(|raw| TenantAliases::parse(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// The id a request's tenant id resolves to through the configured
+/// aliases.
+pub(super) fn canonical_tenant_id(tenant_id: String) -> String {
+    let aliases = tenant_aliases_from_env();
+    match aliases.resolve(tenant_id.trim()) {
+        resolved if resolved == tenant_id.trim() => tenant_id,
+        resolved => resolved.to_string(),
+    }
+}
+
 pub(super) fn parse_env_first_usize(keys: &[&str]) -> Option<usize> {
     for key in keys {
         if let Ok(value) = std::env::var(key)
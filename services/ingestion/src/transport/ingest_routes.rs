@@ -133,7 +133,7 @@ pub(super) fn handle_ingest_post(
                         HttpResponse::error_with_status(status, &message)
                     }
                 };
-            drop(guard);
+            release_after_write(guard);
             emit_audit_event(
                 runtime,
                 audit_log_path,
@@ -350,7 +350,7 @@ pub(super) fn handle_ingest_raw_post(
                     HttpResponse::error_with_status(status, &message)
                 }
             };
-            drop(guard);
+            release_after_write(guard);
             emit_audit_event(
                 runtime,
                 audit_log_path,
@@ -519,7 +519,7 @@ pub(super) fn handle_ingest_batch_post(
                     HttpResponse::error_with_status(status, &message)
                 }
             };
-            drop(guard);
+            release_after_write(guard);
             emit_audit_event(
                 runtime,
                 audit_log_path,
@@ -743,7 +743,7 @@ pub(super) fn handle_ingest_document_post(
                     HttpResponse::error_with_status(status, &message)
                 }
             };
-            drop(guard);
+            release_after_write(guard);
             emit_audit_event(
                 runtime,
                 audit_log_path,
@@ -810,19 +810,37 @@ fn commit_status_for_progress(ack_count: usize, required_acks: usize) -> &'stati
     }
 }
 
-fn observe_auth_success(runtime: &SharedRuntime) {
+/// Unlock the runtime, then wait out any write slowdown the ingest owes,
+/// so a stalled writer does not hold up other requests.
+fn release_after_write(guard: std::sync::MutexGuard<'_, IngestionRuntime>) {
+    release_after_write_with(guard, std::thread::sleep);
+}
+
+/// [`release_after_write`] waiting with `wait` instead of sleeping.
+pub(super) fn release_after_write_with(
+    mut guard: std::sync::MutexGuard<'_, IngestionRuntime>,
+    wait: impl FnOnce(Duration),
+) {
+    let delay = guard.take_write_delay();
+    drop(guard);
+    if let Some(delay) = delay {
+        wait(delay);
+    }
+}
+
+pub(super) fn observe_auth_success(runtime: &SharedRuntime) {
     if let Ok(mut guard) = runtime.lock() {
         guard.observe_auth_success();
     }
 }
 
-fn observe_auth_failure(runtime: &SharedRuntime) {
+pub(super) fn observe_auth_failure(runtime: &SharedRuntime) {
     if let Ok(mut guard) = runtime.lock() {
         guard.observe_auth_failure();
     }
 }
 
-fn observe_authz_denied(runtime: &SharedRuntime) {
+pub(super) fn observe_authz_denied(runtime: &SharedRuntime) {
     if let Ok(mut guard) = runtime.lock() {
         guard.observe_authz_denied();
     }
@@ -0,0 +1,119 @@
+use super::ingest_routes::{observe_auth_failure, observe_auth_success, observe_authz_denied};
+use super::payload::{render_outbox_ack_response_json, render_outbox_poll_response_json};
+use super::*;
+
+const DEFAULT_OUTBOX_POLL_LIMIT: usize = 100;
+const MAX_OUTBOX_POLL_LIMIT: usize = 10_000;
+
+/// The outbox routes for integration consumers. Both take the consumer
+/// through the `consumer` query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum OutboxAction {
+    /// `GET /v1/outbox`: the events after the consumer's offset, up to
+    /// `limit`.
+    Poll,
+    /// `POST /v1/outbox/ack`: advance the consumer's offset to `lsn`.
+    Ack,
+}
+
+impl OutboxAction {
+    fn audit_action(self) -> &'static str {
+        match self {
+            Self::Poll => "outbox_poll",
+            Self::Ack => "outbox_ack",
+        }
+    }
+}
+
+pub(super) fn handle_outbox_request(
+    runtime: &SharedRuntime,
+    request: &HttpRequest,
+    query: &HashMap<String, String>,
+    action: OutboxAction,
+    audit_log_path: Option<&str>,
+) -> HttpResponse {
+    let consumer = match query.get("consumer").map(|value| value.trim()) {
+        Some(value) if !value.is_empty() => value,
+        _ => return HttpResponse::bad_request("consumer query parameter is required"),
+    };
+    let audit = |status: u16, outcome: &str, reason: &str| {
+        emit_audit_event(
+            runtime,
+            audit_log_path,
+            AuditEvent {
+                action: action.audit_action(),
+                tenant_id: None,
+                claim_id: None,
+                status,
+                outcome,
+                reason,
+            },
+        );
+    };
+
+    match authorize_admin_request(request) {
+        AuthDecision::Unauthorized(reason) => {
+            observe_auth_failure(runtime);
+            audit(401, "denied", reason);
+            return HttpResponse::unauthorized(reason);
+        }
+        AuthDecision::Forbidden(reason) => {
+            observe_authz_denied(runtime);
+            audit(403, "denied", reason);
+            return HttpResponse::forbidden(reason);
+        }
+        AuthDecision::Allowed => observe_auth_success(runtime),
+    }
+
+    let limit = match parse_query_usize(query, "limit") {
+        Ok(limit) => limit
+            .unwrap_or(DEFAULT_OUTBOX_POLL_LIMIT)
+            .clamp(1, MAX_OUTBOX_POLL_LIMIT),
+        Err(reason) => return HttpResponse::bad_request(&reason),
+    };
+    let lsn = match (
+        action,
+        query.get("lsn").map(|value| value.trim().parse::<u64>()),
+    ) {
+        (OutboxAction::Poll, _) => 0,
+        (OutboxAction::Ack, Some(Ok(lsn))) => lsn,
+        (OutboxAction::Ack, Some(Err(_))) => {
+            return HttpResponse::bad_request("query parameter 'lsn' must be a positive integer");
+        }
+        (OutboxAction::Ack, None) => {
+            return HttpResponse::bad_request("lsn query parameter is required");
+        }
+    };
+
+    let mut guard = match runtime.lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            return HttpResponse::internal_server_error("failed to acquire ingestion runtime lock");
+        }
+    };
+    let result = match action {
+        OutboxAction::Poll => guard
+            .outbox_poll(consumer, limit)
+            .map(|result| result.map(|resp| render_outbox_poll_response_json(&resp))),
+        OutboxAction::Ack => guard
+            .outbox_ack(consumer, lsn)
+            .map(|result| result.map(|resp| render_outbox_ack_response_json(&resp))),
+    };
+    drop(guard);
+
+    match result {
+        None => {
+            audit(404, "error", "outbox is not configured");
+            HttpResponse::not_found("outbox is not configured")
+        }
+        Some(Ok(body)) => {
+            audit(200, "success", "outbox request completed");
+            HttpResponse::ok_json(body)
+        }
+        Some(Err(err)) => {
+            let (status, message) = map_store_error(&err);
+            audit(status, "error", &message);
+            HttpResponse::error_with_status(status, &message)
+        }
+    }
+}
@@ -1,12 +1,19 @@
+use schema::Entity;
+use store::ClaimPatch;
+
+use super::canonical_tenant_id;
+
 use crate::api::{
-    IngestApiRequest, IngestApiRequestWire, IngestApiResponse, IngestBatchApiRequest,
-    IngestBatchApiRequestWire, IngestBatchApiResponse, IngestDocumentApiRequest,
-    IngestDocumentApiResponse, IngestRawApiRequest, IngestRawApiResponse,
+    AdminClaimEntitiesWire, AdminClaimPatchWire, AdminClaimResponse, IngestApiRequest,
+    IngestApiRequestWire, IngestApiResponse, IngestBatchApiRequest, IngestBatchApiRequestWire,
+    IngestBatchApiResponse, IngestDocumentApiRequest, IngestDocumentApiResponse,
+    IngestRawApiRequest, IngestRawApiResponse, OutboxAckResponse, OutboxPollResponse,
 };
 
 pub(super) fn build_ingest_request_from_json(body: &str) -> Result<IngestApiRequest, String> {
-    let wire: IngestApiRequestWire =
+    let mut wire: IngestApiRequestWire =
         serde_json::from_str(body).map_err(|err| err.to_string())?;
+    wire.claim.tenant_id = canonical_tenant_id(wire.claim.tenant_id);
     wire.into_runtime()
 }
 
@@ -14,16 +21,19 @@ pub(super) fn build_ingest_batch_request_from_json(
     body: &str,
     max_items: usize,
 ) -> Result<IngestBatchApiRequest, String> {
-    let wire: IngestBatchApiRequestWire =
+    let mut wire: IngestBatchApiRequestWire =
         serde_json::from_str(body).map_err(|err| err.to_string())?;
+    for item in &mut wire.items {
+        item.claim.tenant_id = canonical_tenant_id(std::mem::take(&mut item.claim.tenant_id));
+    }
     wire.into_runtime(max_items)
 }
 
 pub(super) fn build_ingest_raw_request_from_json(
     body: &str,
 ) -> Result<IngestRawApiRequest, String> {
-    let mut req: IngestRawApiRequest =
-        serde_json::from_str(body).map_err(|err| err.to_string())?;
+    let mut req: IngestRawApiRequest = serde_json::from_str(body).map_err(|err| err.to_string())?;
+    req.tenant_id = canonical_tenant_id(req.tenant_id);
     validate_raw_request_ranges(&mut req)?;
     normalize_optional_string(&mut req.extraction_model);
     normalize_optional_string(&mut req.embedding_model);
@@ -35,6 +45,7 @@ pub(super) fn build_ingest_document_request_from_json(
 ) -> Result<IngestDocumentApiRequest, String> {
     let mut req: IngestDocumentApiRequest =
         serde_json::from_str(body).map_err(|err| err.to_string())?;
+    req.tenant_id = canonical_tenant_id(req.tenant_id);
     validate_document_request_ranges(&mut req)?;
     validate_document_request_content(&req)?;
     normalize_optional_string(&mut req.extraction_model);
@@ -42,6 +53,28 @@ pub(super) fn build_ingest_document_request_from_json(
     Ok(req)
 }
 
+pub(super) fn build_admin_claim_patch_from_json(body: &str) -> Result<ClaimPatch, String> {
+    let wire: AdminClaimPatchWire = serde_json::from_str(body).map_err(|err| err.to_string())?;
+    wire.into_patch()
+}
+
+pub(super) fn build_admin_claim_entities_from_json(body: &str) -> Result<Vec<Entity>, String> {
+    let wire: AdminClaimEntitiesWire = serde_json::from_str(body).map_err(|err| err.to_string())?;
+    Ok(wire.entities)
+}
+
+pub(super) fn render_admin_claim_response_json(resp: &AdminClaimResponse) -> String {
+    serde_json::to_string(resp).expect("AdminClaimResponse is always serializable")
+}
+
+pub(super) fn render_outbox_poll_response_json(resp: &OutboxPollResponse) -> String {
+    serde_json::to_string(resp).expect("OutboxPollResponse is always serializable")
+}
+
+pub(super) fn render_outbox_ack_response_json(resp: &OutboxAckResponse) -> String {
+    serde_json::to_string(resp).expect("OutboxAckResponse is always serializable")
+}
+
 pub(super) fn render_ingest_response_json(resp: &IngestApiResponse) -> String {
     serde_json::to_string(resp).expect("IngestApiResponse is always serializable")
 }
@@ -4,13 +4,18 @@ use crate::IngestInput;
 
 pub(super) fn map_store_error(error: &StoreError) -> (u16, String) {
     match error {
-        StoreError::Validation(err) => (400, format!("validation error: {err:?}")),
+        StoreError::Validation(err) => (400, format!("validation error: {err}")),
         StoreError::MissingClaim(claim_id) => (400, format!("missing claim: {claim_id}")),
+        StoreError::MissingDocument(doc_id) => (400, format!("missing document: {doc_id}")),
         StoreError::Conflict(message) => (409, format!("state conflict: {message}")),
         StoreError::InvalidVector(message) => (400, format!("invalid vector: {message}")),
-        StoreError::Io(message) | StoreError::Parse(message) => {
-            (500, format!("internal persistence error: {message}"))
-        }
+        StoreError::QuotaExceeded(message) => (429, format!("quota exceeded: {message}")),
+        StoreError::UnknownTenant(tenant_id) => (404, format!("unknown tenant: {tenant_id}")),
+        StoreError::Forbidden(message) => (403, format!("forbidden: {message}")),
+        StoreError::WriteStalled(message) => (503, format!("write stalled: {message}")),
+        StoreError::Io(err) => (500, format!("internal persistence error: {err}")),
+        StoreError::Parse(message) => (500, format!("internal persistence error: {message}")),
+        other => (500, format!("internal store error: {other}")),
     }
 }
 
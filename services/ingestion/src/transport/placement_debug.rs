@@ -142,7 +142,7 @@ fn build_write_route_probe_json(
             "{{\"status\":\"rejected\",\"tenant_id\":\"{}\",\"entity_key\":\"{}\",\"reason\":\"{}\"}}",
             json_escape(tenant_id),
             json_escape(entity_key),
-            json_escape(&format!("placement route error: {err:?}"))
+            json_escape(&format!("placement route error: {err}"))
         ),
     }
 }
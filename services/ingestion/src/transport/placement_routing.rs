@@ -7,7 +7,7 @@ use metadata_router::{
     PlacementRouteError, ReplicaHealth, ReplicaRole, RouterConfig, ShardPlacement,
     load_shard_placements_from_source, shard_ids_from_placements,
 };
-use schema::Claim;
+use schema::{Claim, Entity};
 
 use super::config::{env_with_fallback, parse_env_first_u64, parse_env_first_usize};
 use crate::api::WriteConsistencyPolicy;
@@ -297,8 +297,8 @@ pub(super) fn write_entity_key_for_claim(claim: &Claim) -> &str {
     claim
         .entities
         .iter()
+        .map(Entity::index_name)
         .find(|value| !value.trim().is_empty())
-        .map(String::as_str)
         .unwrap_or(claim.claim_id.as_str())
 }
 
@@ -361,7 +361,11 @@ pub(super) fn run_replication_pull_tick(runtime: &SharedRuntime, config: &Replic
         };
         let result = runtime
             .lock()
-            .map_err(|_| StoreError::Io("replication runtime lock unavailable".to_string()))
+            .map_err(|_| {
+                StoreError::Io(std::io::Error::other(
+                    "replication runtime lock unavailable",
+                ))
+            })
             .and_then(|mut guard| {
                 guard.apply_replication_export(WalReplicationExport {
                     snapshot_lines: export_frame.snapshot_lines,
@@ -369,7 +373,7 @@ pub(super) fn run_replication_pull_tick(runtime: &SharedRuntime, config: &Replic
                 })
             });
         if let Err(err) = result {
-            let message = format!("replication resync apply failed: {err:?}");
+            let message = format!("replication resync apply failed: {err}");
             if let Ok(mut guard) = runtime.lock() {
                 guard.observe_replication_pull_failure(message.clone());
             }
@@ -396,12 +400,16 @@ pub(super) fn run_replication_pull_tick(runtime: &SharedRuntime, config: &Replic
     };
     let result = runtime
         .lock()
-        .map_err(|_| StoreError::Io("replication runtime lock unavailable".to_string()))
+        .map_err(|_| {
+            StoreError::Io(std::io::Error::other(
+                "replication runtime lock unavailable",
+            ))
+        })
         .and_then(|mut guard| {
             guard.apply_replication_delta_lines(&delta_frame.wal_lines, delta_frame.next_offset)
         });
     if let Err(err) = result {
-        let message = format!("replication delta apply failed: {err:?}");
+        let message = format!("replication delta apply failed: {err}");
         if let Ok(mut guard) = runtime.lock() {
             guard.observe_replication_pull_failure(message.clone());
         }
@@ -840,7 +848,7 @@ mod tests {
                     "commit-diverge-1",
                     1,
                     1_700_000_000_000,
-                    &["c-existing".to_string()],
+                    &["c-existing".into()],
                 )
                 .expect("initial batch commit metadata should seed runtime");
         }
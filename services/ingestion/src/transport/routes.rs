@@ -1,3 +1,5 @@
+use super::admin_routes::AdminClaimAction;
+use super::outbox_routes::OutboxAction;
 use super::*;
 
 pub(super) fn handle_request(runtime: &SharedRuntime, request: &HttpRequest) -> HttpResponse {
@@ -15,6 +17,20 @@ pub(super) fn handle_request(runtime: &SharedRuntime, request: &HttpRequest) ->
     let audit_log_path =
         env_with_fallback("DASH_INGEST_AUDIT_LOG_PATH", "EME_INGEST_AUDIT_LOG_PATH");
     match (request.method.as_str(), path.as_str()) {
+        ("GET", "/v1/admin/claims") => admin_routes::handle_admin_claim_request(
+            runtime,
+            request,
+            &query,
+            AdminClaimAction::Inspect,
+            audit_log_path.as_deref(),
+        ),
+        ("GET", "/v1/outbox") => outbox_routes::handle_outbox_request(
+            runtime,
+            request,
+            &query,
+            OutboxAction::Poll,
+            audit_log_path.as_deref(),
+        ),
         ("GET", _) => read_routes::handle_get_request(runtime, request, &path, &query),
         ("POST", "/v1/ingest") => ingest_routes::handle_ingest_post(
             runtime,
@@ -44,6 +60,34 @@ pub(super) fn handle_request(runtime: &SharedRuntime, request: &HttpRequest) ->
             &auth_policy,
             audit_log_path.as_deref(),
         ),
+        ("POST", "/v1/admin/claims/patch") => admin_routes::handle_admin_claim_request(
+            runtime,
+            request,
+            &query,
+            AdminClaimAction::Patch,
+            audit_log_path.as_deref(),
+        ),
+        ("POST", "/v1/admin/claims/entities") => admin_routes::handle_admin_claim_request(
+            runtime,
+            request,
+            &query,
+            AdminClaimAction::ReassignEntities,
+            audit_log_path.as_deref(),
+        ),
+        ("POST", "/v1/admin/claims/reindex") => admin_routes::handle_admin_claim_request(
+            runtime,
+            request,
+            &query,
+            AdminClaimAction::Reindex,
+            audit_log_path.as_deref(),
+        ),
+        ("POST", "/v1/outbox/ack") => outbox_routes::handle_outbox_request(
+            runtime,
+            request,
+            &query,
+            OutboxAction::Ack,
+            audit_log_path.as_deref(),
+        ),
         ("POST", "/internal/replication/ack") => {
             read_routes::handle_replication_ack_post(runtime, request, &query)
         }
@@ -51,8 +95,14 @@ pub(super) fn handle_request(runtime: &SharedRuntime, request: &HttpRequest) ->
         (_, "/v1/ingest/raw") => HttpResponse::method_not_allowed("only POST is supported"),
         (_, "/v1/ingest/document") => HttpResponse::method_not_allowed("only POST is supported"),
         (_, "/v1/ingest/batch") => HttpResponse::method_not_allowed("only POST is supported"),
+        (_, "/v1/admin/claims/patch")
+        | (_, "/v1/admin/claims/entities")
+        | (_, "/v1/admin/claims/reindex")
+        | (_, "/v1/outbox/ack") => HttpResponse::method_not_allowed("only POST is supported"),
         (_, "/health")
         | (_, "/metrics")
+        | (_, "/v1/admin/claims")
+        | (_, "/v1/outbox")
         | (_, "/debug/placement")
         | (_, "/debug/document-parser")
         | (_, "/internal/replication/wal")
@@ -1,11 +1,13 @@
-use std::{path::PathBuf, time::Duration};
+use std::{collections::BTreeMap, fs::read_dir, path::PathBuf, time::Duration};
 
 use indexer::{
-    CompactionSchedulerConfig, SegmentMaintenanceStats, SegmentStoreError, apply_compaction_plan,
-    build_segments, load_manifest, maintain_segment_root, persist_segments_atomic,
-    plan_compaction_round, prune_unreferenced_segment_files,
+    CompactionSchedulerConfig, SegmentMaintenanceStats, SegmentReconciliationReport,
+    SegmentStoreError, SegmentWalPosition, apply_compaction_plan, build_segments, load_manifest,
+    maintain_segment_root, persist_segments_at_wal_position, persist_segments_atomic,
+    plan_compaction_round, prune_unreferenced_segment_files, reconcile_segments_with_store_at,
 };
-use store::InMemoryStore;
+use schema::TenantId;
+use store::{FileWal, InMemoryStore};
 
 use super::{
     DEFAULT_SEGMENT_GC_MIN_STALE_AGE_MS, DEFAULT_SEGMENT_MAINTENANCE_INTERVAL_MS,
@@ -21,6 +23,36 @@ pub(super) struct SegmentRuntime {
     pub(super) scheduler: CompactionSchedulerConfig,
     pub(super) maintenance_interval: Option<Duration>,
     pub(super) maintenance_min_stale_age: Duration,
+    pub(super) startup_reconcile: SegmentReconcileMode,
+}
+
+/// What to do at boot when a tenant's active segments and the store
+/// disagree about which claims exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SegmentReconcileMode {
+    Off,
+    Report,
+    Repair,
+}
+
+impl SegmentReconcileMode {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" | "0" => Some(Self::Off),
+            "report" => Some(Self::Report),
+            "repair" => Some(Self::Repair),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct SegmentReconcileStats {
+    pub(super) tenants_checked: usize,
+    /// Reports for tenants whose segments diverged from the store, keyed by
+    /// segment directory name.
+    pub(super) divergent: BTreeMap<String, SegmentReconciliationReport>,
+    pub(super) repaired_tenants: usize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -72,6 +104,12 @@ impl SegmentRuntime {
             ])
             .unwrap_or(DEFAULT_SEGMENT_GC_MIN_STALE_AGE_MS),
         );
+        let startup_reconcile = env_with_fallback(
+            "DASH_INGEST_SEGMENT_RECONCILE_ON_START",
+            "EME_INGEST_SEGMENT_RECONCILE_ON_START",
+        )
+        .and_then(|raw| SegmentReconcileMode::parse(&raw))
+        .unwrap_or(SegmentReconcileMode::Report);
         Some(Self {
             root_dir: PathBuf::from(root_dir),
             max_segment_size,
@@ -81,15 +119,21 @@ impl SegmentRuntime {
             },
             maintenance_interval,
             maintenance_min_stale_age,
+            startup_reconcile,
         })
     }
 
+    /// Rebuild and publish a tenant's segments. With a WAL, pending records
+    /// are synced first and the manifest records the resulting position, so
+    /// a crash can never leave a manifest describing records the WAL lost.
     pub(super) fn publish_for_tenant(
         &self,
         store: &InMemoryStore,
+        wal: Option<&mut FileWal>,
         tenant_id: &str,
     ) -> Result<SegmentPublishStats, SegmentStoreError> {
-        let claims = store.claims_for_tenant(tenant_id);
+        let wal_position = wal.map(durable_wal_position).transpose()?;
+        let claims = store.claims_for_tenant(&TenantId::from(tenant_id));
         let claim_count = claims.len();
         let mut segments = build_segments(&claims, self.max_segment_size);
         let plans = plan_compaction_round(&segments, &self.scheduler);
@@ -98,7 +142,10 @@ impl SegmentRuntime {
         }
         let tenant_dir = self.tenant_segment_dir(tenant_id);
         let previous_manifest = load_manifest(&tenant_dir)?;
-        let manifest = persist_segments_atomic(&tenant_dir, &segments)?;
+        let manifest = match wal_position {
+            Some(position) => persist_segments_at_wal_position(&tenant_dir, &segments, position)?,
+            None => persist_segments_atomic(&tenant_dir, &segments)?,
+        };
         let stale_file_pruned_count =
             prune_unreferenced_segment_files(&tenant_dir, &manifest, previous_manifest.as_ref())?;
         Ok(SegmentPublishStats {
@@ -118,4 +165,62 @@ impl SegmentRuntime {
     ) -> Result<SegmentMaintenanceStats, SegmentStoreError> {
         maintain_segment_root(&self.root_dir, self.maintenance_min_stale_age)
     }
+
+    /// Compare every tenant's active segments with the store. Tenants come
+    /// from both the store and the segment root, so a directory whose
+    /// tenant no longer has any claims is reported too. With a WAL, a
+    /// manifest recorded past the WAL's current position is divergent as
+    /// well. With `repair`, divergent tenants are republished from the
+    /// store, which is the source of truth after WAL replay.
+    pub(super) fn reconcile_with_store(
+        &self,
+        store: &InMemoryStore,
+        mut wal: Option<&mut FileWal>,
+        repair: bool,
+    ) -> Result<SegmentReconcileStats, SegmentStoreError> {
+        let wal_position = wal.as_deref_mut().map(durable_wal_position).transpose()?;
+        let mut tenants: BTreeMap<String, String> = store
+            .tenant_ids()
+            .into_iter()
+            .map(|tenant_id| (sanitize_path_component(&tenant_id), tenant_id.into_string()))
+            .collect();
+        if self.root_dir.is_dir() {
+            for entry in read_dir(&self.root_dir)? {
+                let entry = entry?;
+                if !entry.path().is_dir() {
+                    continue;
+                }
+                if let Some(dir_name) = entry.file_name().to_str() {
+                    tenants
+                        .entry(dir_name.to_string())
+                        .or_insert_with(|| dir_name.to_string());
+                }
+            }
+        }
+
+        let mut stats = SegmentReconcileStats::default();
+        for (dir_name, tenant_id) in tenants {
+            stats.tenants_checked += 1;
+            let tenant_dir = self.root_dir.join(&dir_name);
+            let report =
+                reconcile_segments_with_store_at(&tenant_dir, store, &tenant_id, wal_position)?;
+            if report.is_consistent() {
+                continue;
+            }
+            if repair {
+                self.publish_for_tenant(store, wal.as_deref_mut(), &tenant_id)?;
+                stats.repaired_tenants += 1;
+            }
+            stats.divergent.insert(dir_name, report);
+        }
+        Ok(stats)
+    }
+}
+
+fn durable_wal_position(wal: &mut FileWal) -> Result<SegmentWalPosition, SegmentStoreError> {
+    let boundary = wal
+        .flush_pending_sync()
+        .and_then(|()| wal.replay_boundary())
+        .map_err(|err| SegmentStoreError::Io(err.to_string()))?;
+    Ok(SegmentWalPosition::from_boundary(&boundary))
 }
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use store::{StorageThresholds, storage_report};
+
+use super::config::{env_with_fallback, parse_env_first_u64};
+use super::json::json_escape;
+
+/// The data directory and thresholds the health endpoint checks, from
+/// `DASH_INGEST_STORAGE_DIR` and the `DASH_INGEST_STORAGE_WARN_*_BYTES`
+/// limits; `None` without a directory.
+fn storage_health_config_from_env() -> Option<(PathBuf, StorageThresholds)> {
+    let data_dir = env_with_fallback("DASH_INGEST_STORAGE_DIR", "EME_INGEST_STORAGE_DIR")?;
+    let thresholds = StorageThresholds {
+        max_total_bytes: parse_env_first_u64(&[
+            "DASH_INGEST_STORAGE_WARN_TOTAL_BYTES",
+            "EME_INGEST_STORAGE_WARN_TOTAL_BYTES",
+        ]),
+        max_wal_bytes: parse_env_first_u64(&[
+            "DASH_INGEST_STORAGE_WARN_WAL_BYTES",
+            "EME_INGEST_STORAGE_WARN_WAL_BYTES",
+        ]),
+        max_tenant_bytes: parse_env_first_u64(&[
+            "DASH_INGEST_STORAGE_WARN_TENANT_BYTES",
+            "EME_INGEST_STORAGE_WARN_TENANT_BYTES",
+        ]),
+    };
+    Some((PathBuf::from(data_dir), thresholds))
+}
+
+/// Health body: `ok`, or `warn` with the storage thresholds exceeded.
+/// Warnings do not fail the probe; they are there to act on before the
+/// disk fills.
+pub(super) fn render_health_json() -> String {
+    let Some((data_dir, thresholds)) = storage_health_config_from_env() else {
+        return "{\"status\":\"ok\"}".to_string();
+    };
+    let alerts = match storage_report(&data_dir) {
+        Ok(report) => report.alerts(&thresholds),
+        Err(err) => {
+            return format!(
+                "{{\"status\":\"warn\",\"storage_error\":\"{}\"}}",
+                json_escape(&err.to_string())
+            );
+        }
+    };
+    if alerts.is_empty() {
+        return "{\"status\":\"ok\"}".to_string();
+    }
+    let warnings: Vec<String> = alerts
+        .iter()
+        .map(|alert| {
+            let tenant_id = alert
+                .tenant_id
+                .as_deref()
+                .map(|tenant_id| format!("\"{}\"", json_escape(tenant_id)))
+                .unwrap_or_else(|| "null".to_string());
+            format!(
+                "{{\"kind\":\"{}\",\"tenant_id\":{tenant_id},\"used_bytes\":{},\
+                 \"threshold_bytes\":{}}}",
+                alert.kind.as_str(),
+                alert.used_bytes,
+                alert.threshold_bytes
+            )
+        })
+        .collect();
+    format!(
+        "{{\"status\":\"warn\",\"storage_warnings\":[{}]}}",
+        warnings.join(",")
+    )
+}
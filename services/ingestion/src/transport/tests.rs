@@ -100,7 +100,14 @@ fn build_ingest_request_from_json_accepts_metadata_fields() {
                 "tenant_id": "tenant-a",
                 "canonical_text": "Company X acquired Company Y",
                 "confidence": 0.9,
-                "entities": ["Company X", "Company Y"],
+                "entities": [
+                    "Company X",
+                    {
+                        "name": "Company Y Inc.",
+                        "entity_type": "organization",
+                        "canonical_name": "Company Y"
+                    }
+                ],
                 "embedding_ids": ["emb://1"]
             },
             "evidence": [
@@ -118,7 +125,15 @@ fn build_ingest_request_from_json_accepts_metadata_fields() {
         }"#;
 
     let req = build_ingest_request_from_json(body).unwrap();
-    assert_eq!(req.claim.entities, vec!["Company X", "Company Y"]);
+    assert_eq!(
+        req.claim.entities,
+        vec![
+            schema::Entity::new("Company X"),
+            schema::Entity::new("Company Y Inc.")
+                .with_type("organization")
+                .with_canonical_name("Company Y"),
+        ]
+    );
     assert_eq!(req.claim.embedding_ids, vec!["emb://1"]);
     assert_eq!(req.evidence[0].chunk_id.as_deref(), Some("chunk-10"));
     assert_eq!(req.evidence[0].span_start, Some(12));
@@ -931,6 +946,116 @@ fn handle_request_post_batch_is_atomic_on_validation_failure() {
     let _ = std::fs::remove_file(PathBuf::from(snapshot_path));
 }
 
+/// Log whose appends fail while `fail_appends` is set.
+#[derive(Clone, Default)]
+struct FlakyWalBackend {
+    log: store::MemoryWalBackend,
+    fail_appends: Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl store::WalBackend for FlakyWalBackend {
+    fn append(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        if self.fail_appends.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err(StoreError::Io(std::io::Error::other(
+                "injected append failure",
+            )));
+        }
+        self.log.append(lines)
+    }
+
+    fn sync(&mut self) -> Result<(), StoreError> {
+        self.log.sync()
+    }
+
+    fn read_from(&self, offset: usize) -> Result<Vec<String>, StoreError> {
+        self.log.read_from(offset)
+    }
+
+    fn truncate_before(&mut self, offset: usize) -> Result<(), StoreError> {
+        self.log.truncate_before(offset)
+    }
+
+    fn truncate_after(&mut self, len: usize) -> Result<(), StoreError> {
+        self.log.truncate_after(len)
+    }
+
+    fn replace(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.log.replace(lines)
+    }
+
+    fn record_count(&self) -> Result<usize, StoreError> {
+        self.log.record_count()
+    }
+
+    fn size_bytes(&self) -> Result<u64, StoreError> {
+        self.log.size_bytes()
+    }
+
+    fn read_snapshot(&self) -> Result<Vec<String>, StoreError> {
+        self.log.read_snapshot()
+    }
+
+    fn write_snapshot(&mut self, lines: &[String]) -> Result<(), StoreError> {
+        self.log.write_snapshot(lines)
+    }
+}
+
+#[test]
+fn handle_request_post_batch_publishes_changes_and_metrics_only_after_wal_commit() {
+    let backend = FlakyWalBackend::default();
+    let wal = FileWal::with_backend(backend.clone(), store::WalWritePolicy::default())
+        .expect("wal should open");
+    let store = InMemoryStore::new();
+    let changes = store.subscribe();
+    let runtime = Arc::new(Mutex::new(IngestionRuntime::persistent(
+        store,
+        wal,
+        CheckpointPolicy::default(),
+    )));
+    let batch = |claim_id: &str| HttpRequest {
+        method: "POST".to_string(),
+        target: "/v1/ingest/batch".to_string(),
+        headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+        body: serde_json::json!({
+            "items": [{
+                "claim": {
+                    "claim_id": claim_id,
+                    "tenant_id": "tenant-a",
+                    "canonical_text": "Batch commit gate",
+                    "confidence": 0.9
+                }
+            }]
+        })
+        .to_string()
+        .into_bytes(),
+    };
+
+    backend
+        .fail_appends
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    let response = handle_request(&runtime, &batch("c-rolled-back"));
+    assert_eq!(response.status, 500, "{}", response.body);
+    assert!(changes.drain().is_empty());
+    let guard = runtime.lock().expect("runtime lock should be available");
+    assert_eq!(guard.claims_len(), 0);
+    assert_eq!(guard.store.metrics_snapshot().claims_ingested, 0);
+    drop(guard);
+
+    backend
+        .fail_appends
+        .store(false, std::sync::atomic::Ordering::Relaxed);
+    let response = handle_request(&runtime, &batch("c-committed"));
+    assert_eq!(response.status, 200, "{}", response.body);
+    let events = changes.drain();
+    assert!(matches!(
+        &events[0].record,
+        store::ChangeRecord::Claim(claim) if claim.claim_id == "c-committed"
+    ));
+    assert_eq!(events[0].sequence, 0);
+    let guard = runtime.lock().expect("runtime lock should be available");
+    assert_eq!(guard.store.metrics_snapshot().claims_ingested, 1);
+}
+
 #[test]
 fn handle_request_internal_replication_wal_returns_delta_payload() {
     let _guard = env_lock().lock().expect("env lock should be available");
@@ -1093,6 +1218,45 @@ fn auth_policy_scoped_key_rejects_other_tenants() {
     );
 }
 
+#[test]
+fn tenant_aliases_canonicalize_requests_and_carry_over_key_scopes() {
+    let _guard = env_lock().lock().expect("env lock should be available");
+    let previous_aliases = std::env::var_os("DASH_TENANT_ALIASES");
+    set_env_var_for_tests("DASH_TENANT_ALIASES", "acme-old=acme,acme-eu=acme-old");
+
+    let body = r#"{
+            "items": [
+                {"claim": {"claim_id": "c1", "tenant_id": "acme-eu",
+                           "canonical_text": "Acme opened a Berlin office", "confidence": 0.9}},
+                {"claim": {"claim_id": "c2", "tenant_id": "acme",
+                           "canonical_text": "Acme hired a CFO", "confidence": 0.8}}
+            ]
+        }"#;
+    let batch = build_ingest_batch_request_from_json(body, 8).expect("batch should parse");
+    let request = HttpRequest {
+        method: "POST".to_string(),
+        target: "/v1/ingest".to_string(),
+        headers: HashMap::from([("x-api-key".to_string(), "scope-a".to_string())]),
+        body: Vec::new(),
+    };
+    let policy = AuthPolicy::from_env(None, None, None, None, Some("scope-a:acme-old".to_string()));
+    let decision = authorize_request_for_tenant(&request, "acme", &policy);
+    let other_decision = authorize_request_for_tenant(&request, "globex", &policy);
+
+    restore_env_var_for_tests("DASH_TENANT_ALIASES", previous_aliases.as_deref());
+    assert!(
+        batch
+            .items
+            .iter()
+            .all(|item| item.claim.tenant_id == "acme")
+    );
+    assert_eq!(decision, AuthDecision::Allowed);
+    assert_eq!(
+        other_decision,
+        AuthDecision::Forbidden("tenant is not allowed for this API key")
+    );
+}
+
 #[test]
 fn auth_policy_scoped_key_rejects_unknown_key_when_required_keys_are_unset() {
     let request = HttpRequest {
@@ -1329,16 +1493,8 @@ fn write_backpressure_response_returns_http_503_payload() {
 #[test]
 fn persistent_runtime_enables_async_flush_for_batched_wal_by_default() {
     let wal_path = temp_wal_path();
-    let wal = FileWal::open_with_policy(
-        &wal_path,
-        store::WalWritePolicy {
-            sync_every_records: 64,
-            append_buffer_max_records: 64,
-            sync_interval: None,
-            background_flush_only: false,
-        },
-    )
-    .expect("wal should open");
+    let wal = FileWal::open_with_policy(&wal_path, store::WalWritePolicy::new(64, 64))
+        .expect("wal should open");
     let runtime =
         IngestionRuntime::persistent(InMemoryStore::new(), wal, CheckpointPolicy::default());
     assert_eq!(
@@ -1357,12 +1513,7 @@ fn persistent_runtime_enables_async_flush_for_background_only_wal() {
     let wal_path = temp_wal_path();
     let wal = FileWal::open_with_policy(
         &wal_path,
-        store::WalWritePolicy {
-            sync_every_records: 1,
-            append_buffer_max_records: 1,
-            sync_interval: None,
-            background_flush_only: true,
-        },
+        store::WalWritePolicy::new(1, 1).with_background_flush_only(true),
     )
     .expect("wal should open");
     let runtime =
@@ -1383,12 +1534,9 @@ fn background_only_mode_skips_request_thread_interval_flush() {
     let wal_path = temp_wal_path();
     let wal = FileWal::open_with_policy(
         &wal_path,
-        store::WalWritePolicy {
-            sync_every_records: 1,
-            append_buffer_max_records: 1,
-            sync_interval: Some(Duration::from_millis(1)),
-            background_flush_only: true,
-        },
+        store::WalWritePolicy::new(1, 1)
+            .with_sync_interval(Some(Duration::from_millis(1)))
+            .with_background_flush_only(true),
     )
     .expect("wal should open");
     let mut runtime =
@@ -1418,18 +1566,59 @@ fn background_only_mode_skips_request_thread_interval_flush() {
 }
 
 #[test]
-fn async_flush_tick_forces_sync_of_unsynced_wal_records() {
+fn write_slowdown_is_left_for_the_handler_instead_of_slept_under_the_lock() {
     let wal_path = temp_wal_path();
-    let wal = FileWal::open_with_policy(
-        &wal_path,
-        store::WalWritePolicy {
-            sync_every_records: 64,
-            append_buffer_max_records: 64,
-            sync_interval: None,
-            background_flush_only: false,
-        },
+    let mut wal = FileWal::open(&wal_path).expect("wal should open");
+    wal.append_claim(&schema::claim_builder(
+        "c-backlog",
+        "tenant-a",
+        "Backlog claim",
+        0.9,
+    ))
+    .expect("append should succeed");
+    let mut policy = CheckpointPolicy::default();
+    policy.max_wal_records = Some(1);
+    policy.write_slowdown_multiple = Some(1);
+    let mut runtime = IngestionRuntime::persistent(InMemoryStore::new(), wal, policy);
+    runtime.throttle_write().expect("throttle should succeed");
+    assert_eq!(runtime.take_write_delay(), Some(Duration::from_millis(100)));
+    assert_eq!(runtime.take_write_delay(), None);
+
+    let request = build_ingest_request_from_json(
+        r#"{"claim":{"claim_id":"c-slow","tenant_id":"tenant-a","canonical_text":"Slowed claim","confidence":0.9}}"#,
     )
-    .expect("wal should open");
+    .expect("request should parse");
+    runtime.ingest(request).expect("ingest should succeed");
+
+    // The handler unlocks the runtime before it waits out the delay, so
+    // other requests can take the lock while the writer is stalled.
+    let runtime = Mutex::new(runtime);
+    let mut waited = None;
+    ingest_routes::release_after_write_with(
+        runtime.lock().expect("runtime lock should be available"),
+        |delay| waited = Some((delay, runtime.try_lock().is_ok())),
+    );
+    assert_eq!(waited, Some((Duration::from_millis(100), true)));
+    assert_eq!(
+        runtime
+            .lock()
+            .expect("runtime lock should be available")
+            .take_write_delay(),
+        None
+    );
+
+    drop(runtime);
+    let _ = std::fs::remove_file(&wal_path);
+    let mut snapshot = wal_path.into_os_string();
+    snapshot.push(".snapshot");
+    let _ = std::fs::remove_file(PathBuf::from(snapshot));
+}
+
+#[test]
+fn async_flush_tick_forces_sync_of_unsynced_wal_records() {
+    let wal_path = temp_wal_path();
+    let wal = FileWal::open_with_policy(&wal_path, store::WalWritePolicy::new(64, 64))
+        .expect("wal should open");
     let mut runtime =
         IngestionRuntime::persistent(InMemoryStore::new(), wal, CheckpointPolicy::default());
     let request = build_ingest_request_from_json(
@@ -1930,6 +2119,7 @@ fn segment_publish_writes_manifest_and_metrics() {
                 },
                 maintenance_interval: None,
                 maintenance_min_stale_age: Duration::from_millis(0),
+                startup_reconcile: SegmentReconcileMode::Off,
             },
         )),
     ));
@@ -2005,6 +2195,7 @@ fn segment_maintenance_prunes_orphan_segment_files() {
             },
             maintenance_interval: Some(Duration::from_millis(1)),
             maintenance_min_stale_age: Duration::from_millis(0),
+            startup_reconcile: SegmentReconcileMode::Off,
         }));
     runtime.run_segment_maintenance_tick();
 
@@ -2020,6 +2211,138 @@ fn segment_maintenance_prunes_orphan_segment_files() {
     let _ = std::fs::remove_dir_all(root_dir);
 }
 
+#[test]
+fn segment_reconcile_on_startup_repairs_divergent_tenants() {
+    let mut root_dir = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be monotonic")
+        .as_nanos();
+    root_dir.push(format!(
+        "dash-ingest-segment-reconcile-test-{}-{}",
+        std::process::id(),
+        nanos
+    ));
+
+    persist_segments_atomic(
+        &root_dir.join("tenant-a"),
+        &[Segment {
+            segment_id: "hot-0".to_string(),
+            tier: Tier::Hot,
+            claim_ids: vec!["ghost".to_string()],
+        }],
+    )
+    .expect("segment persist should succeed");
+
+    let mut store = InMemoryStore::new();
+    store
+        .ingest_bundle(
+            schema::claim_builder("c1", "tenant-a", "Company X acquired Company Y", 0.95),
+            vec![],
+            vec![],
+        )
+        .expect("ingest should succeed");
+    let segment_runtime = SegmentRuntime {
+        root_dir: root_dir.clone(),
+        max_segment_size: 8,
+        scheduler: CompactionSchedulerConfig::default(),
+        maintenance_interval: None,
+        maintenance_min_stale_age: Duration::from_millis(0),
+        startup_reconcile: SegmentReconcileMode::Repair,
+    };
+
+    let stats = segment_runtime
+        .reconcile_with_store(&store, None, false)
+        .expect("reconcile report should succeed");
+    assert_eq!(stats.tenants_checked, 1);
+    assert_eq!(stats.repaired_tenants, 0);
+    let report = &stats.divergent["tenant-a"];
+    assert_eq!(report.missing_from_store, vec!["ghost"]);
+    assert_eq!(report.missing_from_segments, vec!["c1"]);
+
+    let mut runtime =
+        IngestionRuntime::in_memory(store).with_segment_runtime_for_tests(Some(segment_runtime));
+    runtime.reconcile_segments_on_startup();
+
+    let report = indexer::reconcile_segments_with_store(
+        &root_dir.join("tenant-a"),
+        &runtime.store,
+        "tenant-a",
+    )
+    .expect("reconcile after repair should succeed");
+    assert!(report.is_consistent());
+
+    let _ = std::fs::remove_dir_all(root_dir);
+}
+
+#[test]
+fn segment_publish_records_wal_position_and_repairs_manifest_ahead_of_wal() {
+    let mut root_dir = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be monotonic")
+        .as_nanos();
+    root_dir.push(format!(
+        "dash-ingest-segment-wal-position-test-{}-{}",
+        std::process::id(),
+        nanos
+    ));
+    let tenant_dir = root_dir.join("tenant-a");
+    let wal_path = temp_wal_path();
+    let mut wal = FileWal::open(&wal_path).expect("wal should open");
+    let mut store = InMemoryStore::new();
+    store
+        .ingest_bundle_persistent(
+            &mut wal,
+            schema::claim_builder("c1", "tenant-a", "Company X acquired Company Y", 0.95),
+            vec![],
+            vec![],
+        )
+        .expect("ingest should succeed");
+    let segment_runtime = SegmentRuntime {
+        root_dir: root_dir.clone(),
+        max_segment_size: 8,
+        scheduler: CompactionSchedulerConfig::default(),
+        maintenance_interval: None,
+        maintenance_min_stale_age: Duration::from_millis(0),
+        startup_reconcile: SegmentReconcileMode::Repair,
+    };
+
+    segment_runtime
+        .publish_for_tenant(&store, Some(&mut wal), "tenant-a")
+        .expect("segment publish should succeed");
+    let current = indexer::SegmentWalPosition::from_boundary(
+        &wal.replay_boundary().expect("replay boundary should load"),
+    );
+    let manifest = indexer::load_manifest(&tenant_dir)
+        .expect("manifest should load")
+        .expect("manifest should exist");
+    assert_eq!(manifest.wal_position, Some(current));
+
+    // Simulate a crash that kept the manifest but lost the WAL tail.
+    let ahead = indexer::SegmentWalPosition {
+        wal_records: current.wal_records + 3,
+        ..current
+    };
+    let segments =
+        indexer::load_segments_from_manifest(&tenant_dir, &manifest).expect("segments should load");
+    indexer::persist_segments_at_wal_position(&tenant_dir, &segments, ahead)
+        .expect("segment persist should succeed");
+
+    let mut runtime = IngestionRuntime::persistent(store, wal, CheckpointPolicy::default())
+        .with_segment_runtime_for_tests(Some(segment_runtime));
+    runtime.reconcile_segments_on_startup();
+
+    let manifest = indexer::load_manifest(&tenant_dir)
+        .expect("manifest should load")
+        .expect("manifest should exist");
+    assert_eq!(manifest.wal_position, Some(current));
+
+    drop(runtime);
+    let _ = std::fs::remove_file(&wal_path);
+    let _ = std::fs::remove_dir_all(root_dir);
+}
+
 #[test]
 fn append_audit_record_writes_chained_hash_and_seq() {
     let mut audit_path = std::env::temp_dir();
@@ -2088,3 +2411,248 @@ fn append_audit_record_writes_chained_hash_and_seq() {
 
     let _ = std::fs::remove_file(audit_path);
 }
+
+#[test]
+fn admin_claim_routes_are_disabled_without_an_admin_key() {
+    let _guard = env_lock().lock().expect("env lock should be available");
+    let previous_key = std::env::var_os("DASH_INGEST_ADMIN_API_KEY");
+    let previous_keys = std::env::var_os("DASH_INGEST_ADMIN_API_KEYS");
+    restore_env_var_for_tests("DASH_INGEST_ADMIN_API_KEY", None);
+    restore_env_var_for_tests("DASH_INGEST_ADMIN_API_KEYS", None);
+
+    let runtime = sample_runtime();
+    let request = HttpRequest {
+        method: "GET".to_string(),
+        target: "/v1/admin/claims?tenant_id=tenant-a&claim_id=c1".to_string(),
+        headers: HashMap::from([("x-api-key".to_string(), "anything".to_string())]),
+        body: Vec::new(),
+    };
+    let response = handle_request(&runtime, &request);
+    assert_eq!(response.status, 403);
+    assert!(response.body.contains("admin API is disabled"));
+
+    restore_env_var_for_tests("DASH_INGEST_ADMIN_API_KEY", previous_key.as_deref());
+    restore_env_var_for_tests("DASH_INGEST_ADMIN_API_KEYS", previous_keys.as_deref());
+}
+
+#[test]
+fn admin_claim_routes_inspect_patch_and_reassign_entities() {
+    let _guard = env_lock().lock().expect("env lock should be available");
+    let previous_key = std::env::var_os("DASH_INGEST_ADMIN_API_KEY");
+    set_env_var_for_tests("DASH_INGEST_ADMIN_API_KEY", "admin-secret");
+
+    let runtime = sample_runtime();
+    let ingest = HttpRequest {
+        method: "POST".to_string(),
+        target: "/v1/ingest".to_string(),
+        headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+        body: br#"{
+                "claim": {
+                    "claim_id": "c1",
+                    "tenant_id": "tenant-a",
+                    "canonical_text": "Company X acquired Company Y",
+                    "confidence": 0.9,
+                    "entities": ["Company X"]
+                },
+                "evidence": [
+                    {
+                        "evidence_id": "e1",
+                        "claim_id": "c1",
+                        "source_id": "source://doc",
+                        "stance": "supports",
+                        "source_quality": 0.95
+                    }
+                ]
+            }"#
+        .to_vec(),
+    };
+    assert_eq!(handle_request(&runtime, &ingest).status, 200);
+
+    let admin_request = |method: &str, target: &str, key: &str, body: &[u8]| HttpRequest {
+        method: method.to_string(),
+        target: target.to_string(),
+        headers: HashMap::from([
+            ("x-api-key".to_string(), key.to_string()),
+            ("content-type".to_string(), "application/json".to_string()),
+        ]),
+        body: body.to_vec(),
+    };
+
+    let wrong_key = admin_request(
+        "GET",
+        "/v1/admin/claims?tenant_id=tenant-a&claim_id=c1",
+        "not-admin",
+        b"",
+    );
+    assert_eq!(handle_request(&runtime, &wrong_key).status, 401);
+
+    let inspect = admin_request(
+        "GET",
+        "/v1/admin/claims?tenant_id=tenant-a&claim_id=c1",
+        "admin-secret",
+        b"",
+    );
+    let response = handle_request(&runtime, &inspect);
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"evidence_id\":\"e1\""));
+
+    let patch = admin_request(
+        "POST",
+        "/v1/admin/claims/patch?tenant_id=tenant-a&claim_id=c1",
+        "admin-secret",
+        br#"{"confidence": 0.4, "claim_type": "opinion"}"#,
+    );
+    let response = handle_request(&runtime, &patch);
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"confidence\":0.4"));
+
+    let empty_patch = admin_request(
+        "POST",
+        "/v1/admin/claims/patch?tenant_id=tenant-a&claim_id=c1",
+        "admin-secret",
+        b"{}",
+    );
+    assert_eq!(handle_request(&runtime, &empty_patch).status, 400);
+
+    let entities = admin_request(
+        "POST",
+        "/v1/admin/claims/entities?tenant_id=tenant-a&claim_id=c1",
+        "admin-secret",
+        br#"{"entities": ["Company Z"]}"#,
+    );
+    let response = handle_request(&runtime, &entities);
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"Company Z\""));
+    {
+        let guard = runtime.lock().expect("runtime lock should be available");
+        assert!(
+            guard
+                .store
+                .claim_ids_for_entity(&"tenant-a".into(), "company z")
+                .contains("c1")
+        );
+    }
+
+    let reindex = admin_request(
+        "POST",
+        "/v1/admin/claims/reindex?tenant_id=tenant-a&claim_id=c1",
+        "admin-secret",
+        b"",
+    );
+    assert_eq!(handle_request(&runtime, &reindex).status, 200);
+
+    let missing = admin_request(
+        "GET",
+        "/v1/admin/claims?tenant_id=tenant-b&claim_id=c1",
+        "admin-secret",
+        b"",
+    );
+    assert_eq!(handle_request(&runtime, &missing).status, 404);
+
+    restore_env_var_for_tests("DASH_INGEST_ADMIN_API_KEY", previous_key.as_deref());
+}
+
+#[test]
+fn outbox_routes_serve_ingest_events_and_track_consumer_offsets() {
+    let _guard = env_lock().lock().expect("env lock should be available");
+    let previous_key = std::env::var_os("DASH_INGEST_ADMIN_API_KEY");
+    set_env_var_for_tests("DASH_INGEST_ADMIN_API_KEY", "admin-secret");
+
+    let outbox_path = temp_wal_path().with_extension("outbox");
+    let outbox = Outbox::open(&outbox_path).expect("outbox should open");
+    let offsets_path = outbox.offsets_path();
+    let runtime = Arc::new(Mutex::new(
+        IngestionRuntime::in_memory(InMemoryStore::new()).with_outbox(outbox),
+    ));
+    let ingest = |claim_id: &str| HttpRequest {
+        method: "POST".to_string(),
+        target: "/v1/ingest".to_string(),
+        headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+        body: format!(
+            r#"{{"claim": {{"claim_id": "{claim_id}", "tenant_id": "tenant-a",
+                "canonical_text": "Company X acquired Company Y", "confidence": 0.9}}}}"#
+        )
+        .into_bytes(),
+    };
+    assert_eq!(handle_request(&runtime, &ingest("c1")).status, 200);
+    assert_eq!(handle_request(&runtime, &ingest("c2")).status, 200);
+
+    let outbox_request = |method: &str, target: &str| HttpRequest {
+        method: method.to_string(),
+        target: target.to_string(),
+        headers: HashMap::from([("x-api-key".to_string(), "admin-secret".to_string())]),
+        body: Vec::new(),
+    };
+    let poll = outbox_request("GET", "/v1/outbox?consumer=kafka&limit=1");
+    let response = handle_request(&runtime, &poll);
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"last_lsn\":2"));
+    assert!(response.body.contains("\"lsn\":1"));
+    assert!(response.body.contains("\"op\":\"ingest\""));
+    assert!(response.body.contains("\"claim_id\":\"c1\""));
+    assert!(!response.body.contains("\"claim_id\":\"c2\""));
+
+    let ack = outbox_request("POST", "/v1/outbox/ack?consumer=kafka&lsn=1");
+    let response = handle_request(&runtime, &ack);
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"acked_lsn\":1"));
+    let response = handle_request(
+        &runtime,
+        &outbox_request("GET", "/v1/outbox?consumer=kafka"),
+    );
+    assert!(response.body.contains("\"claim_id\":\"c2\""));
+    assert!(!response.body.contains("\"claim_id\":\"c1\""));
+
+    let past_end = outbox_request("POST", "/v1/outbox/ack?consumer=kafka&lsn=9");
+    assert_eq!(handle_request(&runtime, &past_end).status, 409);
+    let mut unauthenticated = outbox_request("GET", "/v1/outbox?consumer=kafka");
+    unauthenticated.headers.clear();
+    assert_eq!(handle_request(&runtime, &unauthenticated).status, 401);
+    let unconfigured = handle_request(&sample_runtime(), &poll);
+    assert_eq!(unconfigured.status, 404);
+
+    restore_env_var_for_tests("DASH_INGEST_ADMIN_API_KEY", previous_key.as_deref());
+    let _ = std::fs::remove_file(&outbox_path);
+    let _ = std::fs::remove_file(&offsets_path);
+}
+
+#[test]
+fn handle_request_health_warns_when_storage_thresholds_are_exceeded() {
+    let _guard = env_lock().lock().expect("env lock should be available");
+    let previous_dir = std::env::var_os("DASH_INGEST_STORAGE_DIR");
+    let previous_wal = std::env::var_os("DASH_INGEST_STORAGE_WARN_WAL_BYTES");
+    let data_dir = temp_wal_path().with_extension("storage");
+    std::fs::create_dir_all(&data_dir).expect("storage dir should be created");
+    std::fs::write(data_dir.join("wal.log"), vec![b'x'; 64]).expect("wal should be written");
+    set_env_var_for_tests("DASH_INGEST_STORAGE_DIR", data_dir.to_str().unwrap());
+
+    let runtime = sample_runtime();
+    let health = || {
+        handle_request(
+            &runtime,
+            &HttpRequest {
+                method: "GET".to_string(),
+                target: "/v1/health".to_string(),
+                headers: HashMap::new(),
+                body: Vec::new(),
+            },
+        )
+    };
+    set_env_var_for_tests("DASH_INGEST_STORAGE_WARN_WAL_BYTES", "1024");
+    assert_eq!(health().body, "{\"status\":\"ok\"}");
+
+    set_env_var_for_tests("DASH_INGEST_STORAGE_WARN_WAL_BYTES", "32");
+    let response = health();
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"status\":\"warn\""));
+    assert!(response.body.contains(
+        "{\"kind\":\"wal\",\"tenant_id\":null,\"used_bytes\":64,\"threshold_bytes\":32}"
+    ));
+
+    restore_env_var_for_tests("DASH_INGEST_STORAGE_DIR", previous_dir.as_deref());
+    restore_env_var_for_tests(
+        "DASH_INGEST_STORAGE_WARN_WAL_BYTES",
+        previous_wal.as_deref(),
+    );
+    let _ = std::fs::remove_dir_all(&data_dir);
+}
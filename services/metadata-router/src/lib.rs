@@ -7,6 +7,7 @@ use std::{
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShardAssignment {
     pub tenant_id: String,
     pub entity_key: String,
@@ -14,12 +15,14 @@ pub struct ShardAssignment {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RoutingPlan {
     pub primary: ShardAssignment,
     pub replicas: Vec<ShardAssignment>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RouterConfig {
     pub shard_ids: Vec<u32>,
     pub virtual_nodes_per_shard: u32,
@@ -27,12 +30,14 @@ pub struct RouterConfig {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReplicaRole {
     Leader,
     Follower,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReplicaHealth {
     Healthy,
     Degraded,
@@ -40,6 +45,7 @@ pub enum ReplicaHealth {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReplicaPlacement {
     pub node_id: String,
     pub role: ReplicaRole,
@@ -47,6 +53,7 @@ pub struct ReplicaPlacement {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShardPlacement {
     pub tenant_id: String,
     pub shard_id: u32,
@@ -55,6 +62,7 @@ pub struct ShardPlacement {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReadPreference {
     LeaderOnly,
     PreferFollower,
@@ -62,6 +70,7 @@ pub enum ReadPreference {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RoutedReplica {
     pub tenant_id: String,
     pub entity_key: String,
@@ -71,15 +80,29 @@ pub struct RoutedReplica {
     pub role: ReplicaRole,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum PlacementRouteError {
+    #[error("no placement for tenant '{tenant_id}' shard {shard_id}")]
     PlacementNotFound { tenant_id: String, shard_id: u32 },
+    #[error("no writable leader for tenant '{tenant_id}' shard {shard_id}")]
     NoWritableLeader { tenant_id: String, shard_id: u32 },
+    #[error("no readable replica for tenant '{tenant_id}' shard {shard_id}")]
     NoReadableReplica { tenant_id: String, shard_id: u32 },
+    #[error("replica '{node_id}' not found")]
     ReplicaNotFound { node_id: String },
+    #[error("replica '{node_id}' is unhealthy")]
     ReplicaUnhealthy { node_id: String },
 }
 
+/// Old tenant ids mapped to the ids that replaced them, for org renames
+/// and consolidations. Services resolve a request's tenant id through
+/// these before authorizing, routing, or reading it, so clients can keep
+/// sending the old id. Aliases may chain: `a=b,b=c` resolves `a` to `c`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantAliases {
+    aliases: BTreeMap<String, String>,
+}
+
 impl Default for RouterConfig {
     fn default() -> Self {
         Self {
@@ -445,6 +468,95 @@ pub fn parse_shard_placements_csv(input: &str) -> Result<Vec<ShardPlacement>, St
     Ok(placements)
 }
 
+impl TenantAliases {
+    /// Builds aliases from `(old, new)` pairs. Empty ids, an id aliased to
+    /// itself, an id given two targets, and cycles are rejected.
+    pub fn new<I, S>(pairs: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = (S, S)>,
+        S: AsRef<str>,
+    {
+        let mut aliases = BTreeMap::new();
+        for (old, new) in pairs {
+            let (old, new) = (old.as_ref().trim(), new.as_ref().trim());
+            if old.is_empty() || new.is_empty() {
+                return Err("tenant alias ids must not be empty".to_string());
+            }
+            if old == new {
+                return Err(format!("tenant '{old}' cannot alias itself"));
+            }
+            if let Some(existing) = aliases.insert(old.to_string(), new.to_string())
+                && existing != new
+            {
+                return Err(format!(
+                    "tenant '{old}' is aliased to both '{existing}' and '{new}'"
+                ));
+            }
+        }
+        let parsed = Self { aliases };
+        for old in parsed.aliases.keys() {
+            let mut current = old.as_str();
+            for _ in 0..=parsed.aliases.len() {
+                match parsed.aliases.get(current) {
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+            if parsed.aliases.contains_key(current) {
+                return Err(format!("tenant alias '{old}' is part of a cycle"));
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Parses `old=new` pairs separated by commas.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut pairs = Vec::new();
+        for entry in raw
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (old, new) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("tenant alias '{entry}' must be old=new"))?;
+            pairs.push((old, new));
+        }
+        Self::new(pairs)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// The id `tenant_id` resolves to; ids without an alias resolve to
+    /// themselves.
+    pub fn resolve<'a>(&'a self, tenant_id: &'a str) -> &'a str {
+        let mut current = tenant_id;
+        while let Some(next) = self.aliases.get(current) {
+            current = next;
+        }
+        current
+    }
+
+    /// Old ids that resolve to `tenant_id`, sorted.
+    pub fn aliases_of(&self, tenant_id: &str) -> Vec<&str> {
+        self.aliases
+            .keys()
+            .filter(|old| self.resolve(old) == tenant_id)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Every old id with the id it finally resolves to.
+    pub fn resolved_pairs(&self) -> BTreeMap<String, String> {
+        self.aliases
+            .keys()
+            .map(|old| (old.clone(), self.resolve(old).to_string()))
+            .collect()
+    }
+}
+
 pub fn shard_ids_from_placements(placements: &[ShardPlacement]) -> Vec<u32> {
     let mut shard_ids = BTreeSet::new();
     for placement in placements {
@@ -639,6 +751,10 @@ mod tests {
         let err = route_write_with_placement("tenant-a", "entity-x", &config, &[placement])
             .expect_err("write route should fail");
         assert!(matches!(err, PlacementRouteError::NoWritableLeader { .. }));
+        assert_eq!(
+            err.to_string(),
+            "no writable leader for tenant 'tenant-a' shard 5"
+        );
     }
 
     #[test]
@@ -749,4 +865,38 @@ mod tests {
         assert_eq!(authority, "127.0.0.1:8090");
         assert_eq!(path, "/v1/control-plane/placement?format=csv");
     }
+
+    #[test]
+    fn tenant_aliases_resolve_chains_and_reject_cycles() {
+        let aliases = TenantAliases::parse("acme-old=acme, acme-eu = acme-old,globex=initech")
+            .expect("aliases should parse");
+        assert_eq!(aliases.resolve("acme-eu"), "acme");
+        assert_eq!(aliases.resolve("acme-old"), "acme");
+        assert_eq!(aliases.resolve("acme"), "acme");
+        assert_eq!(aliases.resolve("unknown"), "unknown");
+        assert_eq!(aliases.aliases_of("acme"), vec!["acme-eu", "acme-old"]);
+        assert_eq!(
+            aliases.resolved_pairs().get("acme-eu").map(String::as_str),
+            Some("acme")
+        );
+
+        let err = TenantAliases::parse("a=b,b=c,c=a").expect_err("cycle should fail");
+        assert!(err.contains("cycle"));
+        let err = TenantAliases::parse("a=b,a=c").expect_err("two targets should fail");
+        assert!(err.contains("both"));
+        assert!(TenantAliases::parse("a=a").is_err());
+        assert!(TenantAliases::parse("a").is_err());
+        assert!(TenantAliases::parse("").expect("empty is fine").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn shard_placement_round_trips_through_json() {
+        let placement = sample_placement();
+        let json = serde_json::to_string(&placement).expect("placement should serialize");
+        assert!(json.contains("\"role\":\"Leader\""));
+        let decoded: ShardPlacement =
+            serde_json::from_str(&json).expect("placement should deserialize");
+        assert_eq!(decoded, placement);
+    }
 }
@@ -2,7 +2,10 @@ use graph::{
     GraphReasoningConfig, NodeReasoningSignals, compute_node_reasoning_with_config,
     traverse_edges_multi_hop,
 };
-use schema::{Claim, ClaimType, RetrievalRequest, Stance, StanceMode};
+use schema::{
+    CertaintyBand, Claim, ClaimId, ClaimType, RetrievalRequest, RetrievalResult, Stance,
+    StanceMode, TenantId,
+};
 mod result_projection;
 mod segment_storage;
 #[cfg(test)]
@@ -13,7 +16,11 @@ use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 #[cfg(test)]
 use std::time::Duration;
-use store::InMemoryStore;
+use std::time::Instant;
+use store::{
+    AnnSearchOverrides, ConfidenceRange, InMemoryStore, MetadataFilter, ResultFields,
+    RetrievalOptions, StageBreakerState,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TimeRange {
@@ -32,6 +39,35 @@ pub struct RetrieveApiRequest {
     pub stance_mode: StanceMode,
     pub return_graph: bool,
     pub time_range: Option<TimeRange>,
+    /// Per-query ANN expansion budget (`ef_search`); `None` keeps the
+    /// tenant's tuning.
+    pub ann_expansion_budget: Option<usize>,
+    /// Claim metadata filters; a claim must match all of them.
+    pub metadata_filters: Vec<MetadataFilter>,
+    /// Bounds on claim confidence, applied before candidates are scored.
+    pub confidence_range: Option<ConfidenceRange>,
+    /// Claim types to keep; a claim must have one of them. Empty keeps
+    /// every claim, typed or not.
+    pub claim_types: Vec<ClaimType>,
+    /// Collections to keep; a claim must be in one of them. Empty keeps
+    /// every claim, in a collection or not.
+    pub collections: Vec<String>,
+    /// Keep only claims whose validity window covers this timestamp.
+    pub as_of_unix: Option<i64>,
+    /// Which result fields to hydrate. Left-out fields are returned
+    /// empty, so the response shape does not change.
+    pub result_fields: ResultFields,
+    /// Score cold-tier segment claims even when hot and warm claims fill
+    /// `top_k`. Without it they are only consulted as a fallback.
+    pub include_cold: bool,
+    /// When the caller stops waiting, usually derived from the transport's
+    /// request timeout. Candidate scoring stops there and the best results
+    /// so far are returned.
+    pub deadline: Option<Instant>,
+    /// The caller's visibility labels, taken from its verified token and
+    /// never from the request itself. Claims labeled for none of them are
+    /// left out of results and the graph; `None` does not filter.
+    pub visibility_labels: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,6 +76,8 @@ pub struct CitationNode {
     pub source_id: String,
     pub stance: String,
     pub source_quality: f32,
+    pub negated: bool,
+    pub stance_strength: Option<f32>,
     pub chunk_id: Option<String>,
     pub span_start: Option<u32>,
     pub span_end: Option<u32>,
@@ -57,6 +95,9 @@ pub struct EvidenceNode {
     pub confidence_band: Option<String>,
     pub dominant_stance: Option<String>,
     pub contradiction_risk: Option<f32>,
+    /// `corroborated`, `contested` or `unverified`; unset for graph nodes
+    /// that are not results.
+    pub certainty: Option<String>,
     pub graph_score: Option<f32>,
     pub support_path_count: Option<usize>,
     pub contradiction_chain_depth: Option<usize>,
@@ -91,6 +132,12 @@ pub struct EvidenceGraph {
 pub struct RetrieveApiResponse {
     pub results: Vec<EvidenceNode>,
     pub graph: Option<EvidenceGraph>,
+    /// Cold-tier claims that were eligible candidates; zero when the
+    /// query was answered from hot and warm claims alone.
+    pub cold_claims_considered: usize,
+    /// The request deadline cut candidate scoring or the cold fallback
+    /// short, so a better match may be missing.
+    pub truncated: bool,
 }
 
 pub const STORAGE_MERGE_MODEL: &str = "immutable_segment_base_plus_mutable_wal_delta";
@@ -135,6 +182,8 @@ pub struct RetrievePlannerDebugSnapshot {
     pub has_filtering: bool,
     pub metadata_prefilter_count: usize,
     pub segment_base_count: usize,
+    /// Segment claims in the cold tier.
+    pub segment_cold_count: usize,
     pub wal_delta_count: usize,
     pub storage_visible_count: usize,
     pub allowed_claim_ids_active: bool,
@@ -142,30 +191,74 @@ pub struct RetrievePlannerDebugSnapshot {
     pub short_circuit_empty: bool,
     pub ann_candidate_count: usize,
     pub planner_candidate_count: usize,
+    /// Pipeline stages currently skipped for the tenant by their
+    /// circuit breaker.
+    pub open_stage_breakers: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct PlannerContext {
-    tenant_id: String,
+    tenant_id: TenantId,
     from_unix: Option<i64>,
     to_unix: Option<i64>,
     entity_filters: Vec<String>,
     embedding_filters: Vec<String>,
     metadata_allowed_claim_ids: Option<HashSet<String>>,
     segment_base_claim_ids: Option<HashSet<String>>,
+    /// Cold-tier subset of `segment_base_claim_ids`.
+    segment_cold_claim_ids: HashSet<String>,
     wal_delta_claim_ids: Option<HashSet<String>>,
     storage_visible_claim_ids: Option<HashSet<String>>,
-    allowed_claim_ids: Option<HashSet<String>>,
+    allowed_claim_ids: Option<HashSet<ClaimId>>,
     has_filtering: bool,
     short_circuit_empty: bool,
 }
 
+impl PlannerContext {
+    /// This plan with cold-tier claims removed from the visible and
+    /// allowed sets, or `None` when there are none to remove.
+    fn without_cold_claims(&self) -> Option<Self> {
+        if self.segment_cold_claim_ids.is_empty() {
+            return None;
+        }
+        let drop_cold = |ids: &HashSet<String>| -> HashSet<String> {
+            ids.difference(&self.segment_cold_claim_ids)
+                .cloned()
+                .collect()
+        };
+        let drop_cold_allowed = |ids: &HashSet<ClaimId>| -> HashSet<ClaimId> {
+            ids.iter()
+                .filter(|claim_id| !self.segment_cold_claim_ids.contains(claim_id.as_str()))
+                .cloned()
+                .collect()
+        };
+        Some(Self {
+            storage_visible_claim_ids: self.storage_visible_claim_ids.as_ref().map(drop_cold),
+            allowed_claim_ids: self.allowed_claim_ids.as_ref().map(drop_cold_allowed),
+            ..self.clone()
+        })
+    }
+
+    /// Cold-tier claims that pass the plan's filters.
+    fn cold_candidate_count(&self) -> usize {
+        match &self.allowed_claim_ids {
+            Some(allowed) => self
+                .segment_cold_claim_ids
+                .iter()
+                .filter(|claim_id| allowed.contains(claim_id.as_str()))
+                .count(),
+            None => self.segment_cold_claim_ids.len(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct EvidenceNodeSignals {
     score: f32,
     supports: usize,
     contradicts: usize,
     citations: Vec<CitationNode>,
+    certainty: Option<CertaintyBand>,
 }
 
 const DEFAULT_GRAPH_REASONING_MAX_HOPS: usize = 3;
@@ -206,61 +299,62 @@ pub fn execute_api_query_with_storage_snapshot(
                 } else {
                     None
                 },
+                cold_claims_considered: 0,
+                truncated: false,
             },
             merge_snapshot,
         );
     }
 
-    let retrieval_request = RetrievalRequest {
-        tenant_id: planner.tenant_id.clone(),
-        query: req.query,
-        top_k: req.top_k,
-        stance_mode: req.stance_mode,
+    let retrieval_request =
+        RetrievalRequest::new(planner.tenant_id.clone(), req.query.clone(), req.top_k)
+            .with_stance_mode(req.stance_mode.clone());
+    // Hot and warm claims are scored first; cold-tier claims only join
+    // when the request opts in or the first pass comes up short.
+    let hot_planner = if req.include_cold {
+        None
+    } else {
+        planner.without_cold_claims()
     };
-    let disk_native_segment_execution_active = resolve_disk_native_segment_execution_enabled()
-        && planner.segment_base_claim_ids.is_some()
-        && planner.storage_visible_claim_ids.is_some();
-    let (results, execution_mode, execution_candidate_count) =
-        if disk_native_segment_execution_active {
-            let candidate_claim_ids = planner
-                .storage_visible_claim_ids
-                .clone()
-                .unwrap_or_default();
-            let candidate_count = candidate_claim_ids.len();
-            (
-                store.retrieve_with_time_range_query_vector_and_explicit_candidate_claim_ids(
-                    &retrieval_request,
-                    planner.from_unix,
-                    planner.to_unix,
-                    req.query_embedding.as_deref(),
-                    &candidate_claim_ids,
-                    planner.allowed_claim_ids.as_ref(),
-                ),
-                STORAGE_EXECUTION_MODE_SEGMENT_DISK_BASE,
-                candidate_count,
-            )
+    let mut cold_consulted = hot_planner.is_none();
+    let (mut results, mut execution_mode, mut execution_candidate_count, mut truncated) =
+        execute_retrieval_pass(
+            store,
+            &req,
+            &retrieval_request,
+            hot_planner.as_ref().unwrap_or(&planner),
+        );
+    if !cold_consulted && results.len() < req.top_k {
+        // A shortfall past the deadline keeps the hot results rather than
+        // scanning cold claims for a client that has given up.
+        if req
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            truncated = true;
         } else {
-            let candidate_count = store.candidate_count_with_query_vector_and_allowed_claim_ids(
-                &retrieval_request,
-                req.query_embedding.as_deref(),
-                (planner.from_unix, planner.to_unix),
-                planner.allowed_claim_ids.as_ref(),
-            );
             (
-                store.retrieve_with_time_range_query_vector_and_allowed_claim_ids(
-                    &retrieval_request,
-                    planner.from_unix,
-                    planner.to_unix,
-                    req.query_embedding.as_deref(),
-                    planner.allowed_claim_ids.as_ref(),
-                ),
-                STORAGE_EXECUTION_MODE_MEMORY_INDEX,
-                candidate_count,
-            )
-        };
+                results,
+                execution_mode,
+                execution_candidate_count,
+                truncated,
+            ) = execute_retrieval_pass(store, &req, &retrieval_request, &planner);
+            cold_consulted = true;
+        }
+    }
+    let cold_claims_considered = if cold_consulted {
+        planner.cold_candidate_count()
+    } else {
+        0
+    };
 
-    let tenant_claims = store.claims_for_tenant(&planner.tenant_id);
-    let tenant_claim_by_id: HashMap<String, Claim> = tenant_claims
+    let mut tenant_claims = store.claims_for_tenant(&planner.tenant_id);
+    if let Some(labels) = &req.visibility_labels
+        && store.has_labelled_claims(&planner.tenant_id)
+    {
+        tenant_claims.retain(|claim| claim.is_visible_to(labels));
+    }
+    let tenant_claim_by_id: HashMap<ClaimId, Claim> = tenant_claims
         .iter()
         .cloned()
         .map(|claim| (claim.claim_id.clone(), claim))
@@ -270,7 +364,7 @@ pub fn execute_api_query_with_storage_snapshot(
         .iter()
         .map(|r| {
             evidence_node_from_parts(
-                r.claim_id.clone(),
+                r.claim_id.to_string(),
                 r.canonical_text.clone(),
                 EvidenceNodeSignals {
                     score: r.score,
@@ -280,10 +374,12 @@ pub fn execute_api_query_with_storage_snapshot(
                         .citations
                         .iter()
                         .map(|citation| CitationNode {
-                            evidence_id: citation.evidence_id.clone(),
+                            evidence_id: citation.evidence_id.to_string(),
                             source_id: citation.source_id.clone(),
                             stance: stance_to_str(&citation.stance).to_string(),
                             source_quality: citation.source_quality,
+                            negated: citation.negated,
+                            stance_strength: citation.stance_strength,
                             chunk_id: citation.chunk_id.clone(),
                             span_start: citation.span_start,
                             span_end: citation.span_end,
@@ -292,6 +388,7 @@ pub fn execute_api_query_with_storage_snapshot(
                             ingested_at: citation.ingested_at,
                         })
                         .collect(),
+                    certainty: Some(r.certainty),
                 },
                 tenant_claim_by_id.get(&r.claim_id),
                 planner.from_unix,
@@ -301,6 +398,13 @@ pub fn execute_api_query_with_storage_snapshot(
         .collect();
 
     let graph = if req.return_graph {
+        let node_text = |claim: &Claim| {
+            if req.result_fields.includes_text() {
+                claim.canonical_text.clone()
+            } else {
+                String::new()
+            }
+        };
         let graph_reasoning_config = graph_reasoning_config_from_env();
         let selected: std::collections::HashSet<String> =
             nodes.iter().map(|n| n.claim_id.clone()).collect();
@@ -310,6 +414,13 @@ pub fn execute_api_query_with_storage_snapshot(
         for claim in &tenant_claims {
             all_edges.extend(store.edges_for_claim(&claim.claim_id));
         }
+        // Edges into claims the caller may not see would name them.
+        if req.visibility_labels.is_some() {
+            all_edges.retain(|edge| {
+                tenant_claim_by_id.contains_key(edge.from_claim_id.as_str())
+                    && tenant_claim_by_id.contains_key(edge.to_claim_id.as_str())
+            });
+        }
 
         let traversed =
             traverse_edges_multi_hop(&start_ids, &all_edges, graph_reasoning_config.max_hops);
@@ -325,19 +436,20 @@ pub fn execute_api_query_with_storage_snapshot(
             .collect();
         let mut edges = Vec::new();
         for edge in traversed {
-            if let Some(claim) = tenant_claim_by_id.get(&edge.from_claim_id)
-                && !node_map.contains_key(&edge.from_claim_id)
+            if let Some(claim) = tenant_claim_by_id.get(edge.from_claim_id.as_str())
+                && !node_map.contains_key(edge.from_claim_id.as_str())
             {
                 node_map.insert(
-                    edge.from_claim_id.clone(),
+                    edge.from_claim_id.to_string(),
                     evidence_node_from_parts(
-                        edge.from_claim_id.clone(),
-                        claim.canonical_text.clone(),
+                        edge.from_claim_id.to_string(),
+                        node_text(claim),
                         EvidenceNodeSignals {
                             score: 0.0,
                             supports: 0,
                             contradicts: 0,
                             citations: Vec::new(),
+                            certainty: None,
                         },
                         Some(claim),
                         planner.from_unix,
@@ -345,19 +457,20 @@ pub fn execute_api_query_with_storage_snapshot(
                     ),
                 );
             }
-            if let Some(claim) = tenant_claim_by_id.get(&edge.to_claim_id)
-                && !node_map.contains_key(&edge.to_claim_id)
+            if let Some(claim) = tenant_claim_by_id.get(edge.to_claim_id.as_str())
+                && !node_map.contains_key(edge.to_claim_id.as_str())
             {
                 node_map.insert(
-                    edge.to_claim_id.clone(),
+                    edge.to_claim_id.to_string(),
                     evidence_node_from_parts(
-                        edge.to_claim_id.clone(),
-                        claim.canonical_text.clone(),
+                        edge.to_claim_id.to_string(),
+                        node_text(claim),
                         EvidenceNodeSignals {
                             score: 0.0,
                             supports: 0,
                             contradicts: 0,
                             citations: Vec::new(),
+                            certainty: None,
                         },
                         Some(claim),
                         planner.from_unix,
@@ -367,8 +480,8 @@ pub fn execute_api_query_with_storage_snapshot(
             }
 
             edges.push(EvidenceGraphEdge {
-                from_claim_id: edge.from_claim_id,
-                to_claim_id: edge.to_claim_id,
+                from_claim_id: edge.from_claim_id.into(),
+                to_claim_id: edge.to_claim_id.into(),
                 relation: format!("{:?}", edge.relation).to_ascii_lowercase(),
                 strength: edge.strength,
             });
@@ -393,6 +506,8 @@ pub fn execute_api_query_with_storage_snapshot(
         RetrieveApiResponse {
             results: nodes,
             graph,
+            cold_claims_considered,
+            truncated,
         },
         merge_snapshot,
     )
@@ -411,12 +526,9 @@ pub fn build_retrieve_planner_debug_snapshot(
     req: &RetrieveApiRequest,
 ) -> RetrievePlannerDebugSnapshot {
     let planner = build_planner_context(store, req);
-    let diagnostics_req = RetrievalRequest {
-        tenant_id: planner.tenant_id.clone(),
-        query: req.query.clone(),
-        top_k: req.top_k,
-        stance_mode: req.stance_mode.clone(),
-    };
+    let diagnostics_req =
+        RetrievalRequest::new(planner.tenant_id.clone(), req.query.clone(), req.top_k)
+            .with_stance_mode(req.stance_mode.clone());
     let ann_candidate_count = req
         .query_embedding
         .as_ref()
@@ -427,16 +539,26 @@ pub fn build_retrieve_planner_debug_snapshot(
     let planner_candidate_count = if planner.short_circuit_empty {
         0
     } else {
-        store.candidate_count_with_query_vector_and_allowed_claim_ids(
+        store.candidate_count_with_ann_overrides(
             &diagnostics_req,
             req.query_embedding.as_deref(),
             (planner.from_unix, planner.to_unix),
             planner.allowed_claim_ids.as_ref(),
+            AnnSearchOverrides {
+                expansion_budget: req.ann_expansion_budget,
+            },
         )
     };
 
+    let open_stage_breakers = store
+        .pipeline_stage_breakers(&planner.tenant_id)
+        .into_iter()
+        .filter(|status| status.state == StageBreakerState::Open)
+        .map(|status| status.stage.to_string())
+        .collect();
+
     RetrievePlannerDebugSnapshot {
-        tenant_id: planner.tenant_id,
+        tenant_id: planner.tenant_id.into_string(),
         top_k: req.top_k,
         stance_mode: stance_mode_to_str(req.stance_mode.clone()).to_string(),
         has_query_embedding: req.query_embedding.as_ref().is_some_and(|v| !v.is_empty()),
@@ -451,6 +573,7 @@ pub fn build_retrieve_planner_debug_snapshot(
             .segment_base_claim_ids
             .as_ref()
             .map_or(0, HashSet::len),
+        segment_cold_count: planner.segment_cold_claim_ids.len(),
         wal_delta_count: planner.wal_delta_claim_ids.as_ref().map_or(0, HashSet::len),
         storage_visible_count: planner
             .storage_visible_claim_ids
@@ -461,6 +584,7 @@ pub fn build_retrieve_planner_debug_snapshot(
         short_circuit_empty: planner.short_circuit_empty,
         ann_candidate_count,
         planner_candidate_count,
+        open_stage_breakers,
     }
 }
 
@@ -488,8 +612,77 @@ fn contradiction_risk_for_counts(supports: usize, contradicts: usize) -> Option<
     result_projection::contradiction_risk_for_counts(supports, contradicts)
 }
 
+/// Score the request against one plan, returning the results, the
+/// execution mode used, and how many candidates it considered.
+fn execute_retrieval_pass(
+    store: &InMemoryStore,
+    req: &RetrieveApiRequest,
+    retrieval_request: &RetrievalRequest,
+    planner: &PlannerContext,
+) -> (Vec<RetrievalResult>, &'static str, usize, bool) {
+    let time_range = (planner.from_unix, planner.to_unix);
+    let base_options = || {
+        let options = RetrievalOptions::new()
+            .with_time_range(time_range.0, time_range.1)
+            .with_query_vector(req.query_embedding.as_deref())
+            .with_allowed_claim_ids(planner.allowed_claim_ids.as_ref())
+            .with_fields(req.result_fields);
+        let options = match &req.visibility_labels {
+            Some(labels) => options.with_visibility_labels(labels.iter().cloned()),
+            None => options,
+        };
+        match req.deadline {
+            Some(deadline) => options.with_deadline(deadline),
+            None => options,
+        }
+    };
+    let disk_native_segment_execution_active = resolve_disk_native_segment_execution_enabled()
+        && planner.segment_base_claim_ids.is_some()
+        && planner.storage_visible_claim_ids.is_some();
+    if disk_native_segment_execution_active {
+        let candidate_claim_ids: HashSet<ClaimId> = planner
+            .storage_visible_claim_ids
+            .iter()
+            .flatten()
+            .map(ClaimId::from)
+            .collect();
+        let candidate_count = candidate_claim_ids.len();
+        let outcome = store.retrieve_with_outcome(
+            retrieval_request,
+            &base_options().with_candidate_claim_ids(&candidate_claim_ids),
+        );
+        (
+            outcome.results,
+            STORAGE_EXECUTION_MODE_SEGMENT_DISK_BASE,
+            candidate_count,
+            outcome.truncated,
+        )
+    } else {
+        let ann_overrides = AnnSearchOverrides {
+            expansion_budget: req.ann_expansion_budget,
+        };
+        let candidate_count = store.candidate_count_with_ann_overrides(
+            retrieval_request,
+            req.query_embedding.as_deref(),
+            time_range,
+            planner.allowed_claim_ids.as_ref(),
+            ann_overrides,
+        );
+        let outcome = store.retrieve_with_outcome(
+            retrieval_request,
+            &base_options().with_ann_overrides(ann_overrides),
+        );
+        (
+            outcome.results,
+            STORAGE_EXECUTION_MODE_MEMORY_INDEX,
+            candidate_count,
+            outcome.truncated,
+        )
+    }
+}
+
 fn build_planner_context(store: &InMemoryStore, req: &RetrieveApiRequest) -> PlannerContext {
-    let tenant_id = req.tenant_id.clone();
+    let tenant_id = TenantId::from(&req.tenant_id);
     let (from_unix, to_unix) = req
         .time_range
         .as_ref()
@@ -511,9 +704,19 @@ fn build_planner_context(store: &InMemoryStore, req: &RetrieveApiRequest) -> Pla
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty())
         .collect();
-    let metadata_allowed_claim_ids =
-        build_metadata_prefilter_claim_ids(store, &tenant_id, &entity_filters, &embedding_filters);
-    let segment_base_claim_ids = build_segment_prefilter_claim_ids(&tenant_id);
+    let metadata_allowed_claim_ids = build_metadata_prefilter_claim_ids(
+        store,
+        &tenant_id,
+        &entity_filters,
+        &embedding_filters,
+        req,
+    );
+    let segment_prefilter = build_segment_prefilter(&tenant_id);
+    let segment_cold_claim_ids = segment_prefilter
+        .as_ref()
+        .map(|prefilter| prefilter.cold_claim_ids.clone())
+        .unwrap_or_default();
+    let segment_base_claim_ids = segment_prefilter.map(|prefilter| prefilter.claim_ids);
     let wal_delta_claim_ids =
         build_wal_delta_claim_ids(store, &tenant_id, segment_base_claim_ids.as_ref());
     let storage_visible_claim_ids = merge_segment_base_with_wal_delta_claim_ids(
@@ -522,6 +725,11 @@ fn build_planner_context(store: &InMemoryStore, req: &RetrieveApiRequest) -> Pla
     );
     let has_filtering = !entity_filters.is_empty()
         || !embedding_filters.is_empty()
+        || !req.metadata_filters.is_empty()
+        || req.confidence_range.is_some()
+        || !req.claim_types.is_empty()
+        || !req.collections.is_empty()
+        || req.as_of_unix.is_some()
         || storage_visible_claim_ids.is_some();
     let allowed_claim_ids = merge_allowed_claim_ids(
         metadata_allowed_claim_ids.as_ref(),
@@ -541,6 +749,7 @@ fn build_planner_context(store: &InMemoryStore, req: &RetrieveApiRequest) -> Pla
         embedding_filters,
         metadata_allowed_claim_ids,
         segment_base_claim_ids,
+        segment_cold_claim_ids,
         wal_delta_claim_ids,
         storage_visible_claim_ids,
         allowed_claim_ids,
@@ -634,9 +843,10 @@ fn resolve_storage_promotion_boundary(planner: &PlannerContext) -> (&'static str
 
 fn build_metadata_prefilter_claim_ids(
     store: &InMemoryStore,
-    tenant_id: &str,
+    tenant_id: &TenantId,
     entity_filters: &[String],
     embedding_filters: &[String],
+    req: &RetrieveApiRequest,
 ) -> Option<std::collections::HashSet<String>> {
     let entity_candidates = if entity_filters.is_empty() {
         None
@@ -657,16 +867,36 @@ fn build_metadata_prefilter_claim_ids(
         Some(ids)
     };
 
-    match (entity_candidates, embedding_candidates) {
-        (None, None) => None,
-        (Some(entity), None) => Some(entity),
-        (None, Some(embedding)) => Some(embedding),
-        (Some(entity), Some(embedding)) => Some(entity.intersection(&embedding).cloned().collect()),
-    }
+    let label_candidates = (!req.metadata_filters.is_empty())
+        .then(|| store.claim_ids_matching_metadata(tenant_id, &req.metadata_filters));
+    let confidence_candidates = req
+        .confidence_range
+        .map(|range| store.claim_ids_in_confidence_range(tenant_id, range));
+    let type_candidates = (!req.claim_types.is_empty())
+        .then(|| store.claim_ids_for_claim_types(tenant_id, &req.claim_types));
+    let collection_candidates = (!req.collections.is_empty())
+        .then(|| store.claim_ids_in_collections(tenant_id, &req.collections));
+    let validity_candidates = req
+        .as_of_unix
+        .map(|as_of| store.claim_ids_valid_at(tenant_id, as_of));
+
+    [
+        entity_candidates,
+        embedding_candidates,
+        label_candidates,
+        confidence_candidates,
+        type_candidates,
+        collection_candidates,
+        validity_candidates,
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(|left, right| left.intersection(&right).cloned().collect())
+    .map(|ids| ids.into_iter().map(ClaimId::into_string).collect())
 }
 
-fn build_segment_prefilter_claim_ids(tenant_id: &str) -> Option<HashSet<String>> {
-    segment_storage::build_segment_prefilter_claim_ids(tenant_id)
+fn build_segment_prefilter(tenant_id: &str) -> Option<segment_storage::SegmentPrefilter> {
+    segment_storage::build_segment_prefilter(tenant_id)
 }
 
 #[cfg(test)]
@@ -679,7 +909,7 @@ fn build_segment_prefilter_claim_ids_from_root(
 
 fn build_wal_delta_claim_ids(
     store: &InMemoryStore,
-    tenant_id: &str,
+    tenant_id: &TenantId,
     segment_base_claim_ids: Option<&HashSet<String>>,
 ) -> Option<HashSet<String>> {
     segment_storage::build_wal_delta_claim_ids(store, tenant_id, segment_base_claim_ids)
@@ -695,8 +925,9 @@ fn merge_segment_base_with_wal_delta_claim_ids(
 fn merge_allowed_claim_ids(
     metadata: Option<&HashSet<String>>,
     segment: Option<&HashSet<String>>,
-) -> Option<HashSet<String>> {
+) -> Option<HashSet<ClaimId>> {
     segment_storage::merge_allowed_claim_ids(metadata, segment)
+        .map(|allowed| allowed.into_iter().map(ClaimId::from).collect())
 }
 
 fn env_with_fallback(primary: &str, fallback: &str) -> Option<String> {
@@ -780,10 +1011,7 @@ fn stance_to_str(stance: &Stance) -> &'static str {
 }
 
 fn stance_mode_to_str(mode: StanceMode) -> &'static str {
-    match mode {
-        StanceMode::Balanced => "balanced",
-        StanceMode::SupportOnly => "support_only",
-    }
+    mode.as_str()
 }
 
 #[cfg(test)]
@@ -901,6 +1129,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e1".into(),
@@ -914,6 +1146,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![ClaimEdge {
                     edge_id: "edge1".into(),
@@ -941,6 +1176,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e2".into(),
@@ -954,6 +1193,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -971,6 +1213,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: true,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1011,6 +1263,10 @@ mod tests {
                     valid_to: Some(1_735_776_000),
                     created_at: Some(1_735_603_200_000),
                     updated_at: Some(1_735_689_600_000),
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e1".into(),
@@ -1024,6 +1280,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![ClaimEdge {
                     edge_id: "edge1".into(),
@@ -1051,6 +1310,10 @@ mod tests {
                     valid_to: Some(1_735_862_400),
                     created_at: Some(1_735_603_200_000),
                     updated_at: Some(1_735_692_000_000),
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1069,6 +1332,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: true,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1079,6 +1352,7 @@ mod tests {
         assert_eq!(node.confidence_band.as_deref(), Some("high"));
         assert_eq!(node.dominant_stance.as_deref(), Some("supports"));
         assert_eq!(node.contradiction_risk, Some(0.0));
+        assert_eq!(node.certainty.as_deref(), Some("unverified"));
         assert!(node.graph_score.is_some());
         assert_eq!(node.support_path_count, Some(1));
         assert_eq!(node.contradiction_chain_depth, Some(0));
@@ -1101,6 +1375,7 @@ mod tests {
         assert_eq!(c2_graph_node.confidence_band.as_deref(), Some("high"));
         assert_eq!(c2_graph_node.dominant_stance, None);
         assert_eq!(c2_graph_node.contradiction_risk, None);
+        assert_eq!(c2_graph_node.certainty, None);
         assert!(c2_graph_node.graph_score.is_some());
         assert_eq!(c2_graph_node.support_path_count, Some(1));
         assert_eq!(c2_graph_node.contradiction_chain_depth, Some(0));
@@ -1147,6 +1422,10 @@ mod tests {
             valid_to: None,
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         };
         let event_annotation =
             temporal_annotation_for_claim(Some(&claim_event_only), Some(90), Some(110));
@@ -1171,6 +1450,10 @@ mod tests {
             valid_to: Some(120),
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         };
         let window_annotation =
             temporal_annotation_for_claim(Some(&claim_window_only), Some(90), Some(110));
@@ -1195,6 +1478,10 @@ mod tests {
             valid_to: Some(80),
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         };
         let both_annotation = temporal_annotation_for_claim(Some(&claim_both), Some(90), Some(110));
         assert_eq!(
@@ -1218,6 +1505,10 @@ mod tests {
             valid_to: None,
             created_at: None,
             updated_at: None,
+            metadata: Vec::new(),
+            visibility_labels: Vec::new(),
+            language: None,
+            collection: None,
         };
         let none_annotation =
             temporal_annotation_for_claim(Some(&missing_temporal), Some(90), Some(110));
@@ -1258,6 +1549,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-old".into(),
@@ -1271,6 +1566,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -1290,6 +1588,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e-new".into(),
@@ -1303,6 +1605,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -1323,6 +1628,16 @@ mod tests {
                     from_unix: Some(150),
                     to_unix: Some(250),
                 }),
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1353,6 +1668,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1373,6 +1692,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1391,6 +1714,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1416,6 +1749,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1434,6 +1771,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: true,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1488,6 +1835,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1508,16 +1859,20 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
             )
             .expect("ingest c2 should succeed");
         store
-            .upsert_claim_vector("c1", vec![0.8, 0.2, 0.1, 0.9])
+            .upsert_claim_vector(&"c1".into(), vec![0.8, 0.2, 0.1, 0.9])
             .expect("upsert vector c1 should work");
         store
-            .upsert_claim_vector("c2", vec![0.1, 0.9, 0.8, 0.2])
+            .upsert_claim_vector(&"c2".into(), vec![0.1, 0.9, 0.8, 0.2])
             .expect("upsert vector c2 should work");
 
         let snapshot = build_retrieve_planner_debug_snapshot(
@@ -1532,6 +1887,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1564,6 +1929,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1582,6 +1951,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1628,6 +2007,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1648,6 +2031,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1672,6 +2059,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1718,6 +2115,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1738,6 +2139,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1762,6 +2167,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1820,6 +2235,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1843,6 +2262,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -1907,6 +2336,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -1934,6 +2367,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
         assert_eq!(snapshot.execution_mode, STORAGE_EXECUTION_MODE_MEMORY_INDEX);
@@ -1978,6 +2421,10 @@ mod tests {
                         valid_to: None,
                         created_at: None,
                         updated_at: None,
+                        metadata: Vec::new(),
+                        visibility_labels: Vec::new(),
+                        language: None,
+                        collection: None,
                     },
                     vec![],
                     vec![],
@@ -1988,20 +2435,22 @@ mod tests {
         let segment_base_before: HashSet<String> =
             ["claim-segment".to_string()].into_iter().collect();
         let wal_delta_before =
-            build_wal_delta_claim_ids(&store, tenant, Some(&segment_base_before)).unwrap();
+            build_wal_delta_claim_ids(&store, &TenantId::from(tenant), Some(&segment_base_before))
+                .unwrap();
         let storage_visible_before = merge_segment_base_with_wal_delta_claim_ids(
             Some(&segment_base_before),
             Some(&wal_delta_before),
         )
         .unwrap();
         let planner_before = PlannerContext {
-            tenant_id: tenant.to_string(),
+            tenant_id: tenant.into(),
             from_unix: None,
             to_unix: None,
             entity_filters: vec![],
             embedding_filters: vec![],
             metadata_allowed_claim_ids: None,
             segment_base_claim_ids: Some(segment_base_before),
+            segment_cold_claim_ids: HashSet::new(),
             wal_delta_claim_ids: Some(wal_delta_before),
             storage_visible_claim_ids: Some(storage_visible_before),
             allowed_claim_ids: None,
@@ -2026,20 +2475,22 @@ mod tests {
                 .into_iter()
                 .collect();
         let wal_delta_after =
-            build_wal_delta_claim_ids(&store, tenant, Some(&segment_base_after)).unwrap();
+            build_wal_delta_claim_ids(&store, &TenantId::from(tenant), Some(&segment_base_after))
+                .unwrap();
         let storage_visible_after = merge_segment_base_with_wal_delta_claim_ids(
             Some(&segment_base_after),
             Some(&wal_delta_after),
         )
         .unwrap();
         let planner_after = PlannerContext {
-            tenant_id: tenant.to_string(),
+            tenant_id: tenant.into(),
             from_unix: None,
             to_unix: None,
             entity_filters: vec![],
             embedding_filters: vec![],
             metadata_allowed_claim_ids: None,
             segment_base_claim_ids: Some(segment_base_after),
+            segment_cold_claim_ids: HashSet::new(),
             wal_delta_claim_ids: Some(wal_delta_after),
             storage_visible_claim_ids: Some(storage_visible_after),
             allowed_claim_ids: None,
@@ -2108,9 +2559,13 @@ mod tests {
                         valid_to: None,
                         created_at: None,
                         updated_at: None,
+                        metadata: Vec::new(),
+                        visibility_labels: Vec::new(),
+                        language: None,
+                        collection: None,
                     },
                     vec![Evidence {
-                        evidence_id: format!("e-{claim_id}"),
+                        evidence_id: format!("e-{claim_id}").into(),
                         claim_id: claim_id.into(),
                         source_id: format!("source://{claim_id}"),
                         stance: Stance::Supports,
@@ -2121,6 +2576,9 @@ mod tests {
                         doc_id: None,
                         extraction_model: None,
                         ingested_at: None,
+                        language: None,
+                        stance_strength: None,
+                        negated: false,
                     }],
                     vec![],
                 )
@@ -2137,6 +2595,16 @@ mod tests {
             stance_mode: StanceMode::Balanced,
             return_graph: false,
             time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
         };
 
         let segment_assisted_response = {
@@ -2203,6 +2671,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -2223,6 +2695,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![],
                 vec![],
@@ -2247,6 +2723,16 @@ mod tests {
                 stance_mode: StanceMode::Balanced,
                 return_graph: false,
                 time_range: None,
+                ann_expansion_budget: None,
+                metadata_filters: Vec::new(),
+                confidence_range: None,
+                claim_types: Vec::new(),
+                collections: Vec::new(),
+                as_of_unix: None,
+                result_fields: ResultFields::Full,
+                include_cold: false,
+                deadline: None,
+                visibility_labels: None,
             },
         );
 
@@ -2519,4 +3005,337 @@ mod tests {
         let _ = std::fs::remove_dir_all(root);
         clear_segment_cache_for_tests();
     }
+
+    #[test]
+    fn execute_api_query_pushes_confidence_range_into_candidate_selection() {
+        let mut store = InMemoryStore::new();
+        for (claim_id, confidence) in [("c-low", 0.2), ("c-mid", 0.6), ("c-high", 0.95)] {
+            store
+                .ingest_bundle(
+                    schema::claim_builder(
+                        claim_id,
+                        "tenant-a",
+                        "company x acquired company y",
+                        confidence,
+                    ),
+                    vec![],
+                    vec![],
+                )
+                .unwrap();
+        }
+        let request = |confidence_range| RetrieveApiRequest {
+            tenant_id: "tenant-a".into(),
+            query: "company x acquired".into(),
+            query_embedding: None,
+            entity_filters: vec![],
+            embedding_id_filters: vec![],
+            top_k: 5,
+            stance_mode: StanceMode::Balanced,
+            return_graph: false,
+            time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
+        };
+
+        let response = execute_api_query(&store, request(Some(ConfidenceRange::at_least(0.5))));
+        let mut ids: Vec<&str> = response
+            .results
+            .iter()
+            .map(|node| node.claim_id.as_str())
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec!["c-high", "c-mid"]);
+
+        let response = execute_api_query(
+            &store,
+            request(Some(ConfidenceRange::new(Some(0.1), Some(0.5)))),
+        );
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].claim_id, "c-low");
+
+        let response = execute_api_query(&store, request(Some(ConfidenceRange::at_least(0.99))));
+        assert!(response.results.is_empty());
+    }
+
+    #[test]
+    fn execute_api_query_keeps_only_requested_claim_types() {
+        let mut store = InMemoryStore::new();
+        for (claim_id, claim_type) in [
+            ("c-fact", Some(ClaimType::Factual)),
+            ("c-opinion", Some(ClaimType::Opinion)),
+            ("c-untyped", None),
+        ] {
+            let mut claim =
+                schema::claim_builder(claim_id, "tenant-a", "company x acquired company y", 0.8);
+            claim.claim_type = claim_type;
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        let request = |claim_types| RetrieveApiRequest {
+            tenant_id: "tenant-a".into(),
+            query: "company x acquired".into(),
+            query_embedding: None,
+            entity_filters: vec![],
+            embedding_id_filters: vec![],
+            top_k: 5,
+            stance_mode: StanceMode::Balanced,
+            return_graph: false,
+            time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types,
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
+        };
+
+        let response = execute_api_query(&store, request(vec![ClaimType::Factual]));
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].claim_id, "c-fact");
+        assert_eq!(response.results[0].claim_type.as_deref(), Some("factual"));
+
+        let response = execute_api_query(
+            &store,
+            request(vec![ClaimType::Factual, ClaimType::Opinion]),
+        );
+        assert_eq!(response.results.len(), 2);
+
+        let response = execute_api_query(&store, request(vec![ClaimType::Causal]));
+        assert!(response.results.is_empty());
+
+        assert_eq!(
+            execute_api_query(&store, request(Vec::new())).results.len(),
+            3
+        );
+    }
+
+    #[test]
+    fn execute_api_query_targets_requested_collections() {
+        let mut store = InMemoryStore::new();
+        for (claim_id, collection) in [
+            ("c-ticket", Some("support-tickets")),
+            ("c-contract", Some("contracts")),
+            ("c-default", None),
+        ] {
+            let mut claim =
+                schema::claim_builder(claim_id, "tenant-a", "company x renewed its plan", 0.8);
+            claim.collection = collection.map(str::to_string);
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        let request = |collections: &[&str]| RetrieveApiRequest {
+            tenant_id: "tenant-a".into(),
+            query: "company x renewed".into(),
+            query_embedding: None,
+            entity_filters: vec![],
+            embedding_id_filters: vec![],
+            top_k: 5,
+            stance_mode: StanceMode::Balanced,
+            return_graph: false,
+            time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: collections.iter().map(|c| c.to_string()).collect(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
+        };
+
+        let response = execute_api_query(&store, request(&["contracts"]));
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].claim_id, "c-contract");
+        let response = execute_api_query(&store, request(&["contracts", "support-tickets"]));
+        assert_eq!(response.results.len(), 2);
+        assert!(
+            execute_api_query(&store, request(&["legal"]))
+                .results
+                .is_empty()
+        );
+        assert_eq!(execute_api_query(&store, request(&[])).results.len(), 3);
+    }
+
+    #[test]
+    fn execute_api_query_as_of_keeps_claims_valid_at_the_timestamp() {
+        let mut store = InMemoryStore::new();
+        for (claim_id, valid_from, valid_to) in [
+            ("c-ceo-old", Some(100), Some(199)),
+            ("c-ceo-new", Some(200), None),
+            ("c-undated", None, None),
+        ] {
+            let mut claim =
+                schema::claim_builder(claim_id, "tenant-a", "the ceo of company x", 0.8);
+            claim.valid_from = valid_from;
+            claim.valid_to = valid_to;
+            store.ingest_bundle(claim, vec![], vec![]).unwrap();
+        }
+        let request = |as_of_unix| RetrieveApiRequest {
+            tenant_id: "tenant-a".into(),
+            query: "ceo of company x".into(),
+            query_embedding: None,
+            entity_filters: vec![],
+            embedding_id_filters: vec![],
+            top_k: 5,
+            stance_mode: StanceMode::Balanced,
+            return_graph: false,
+            time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
+        };
+
+        let response = execute_api_query(&store, request(Some(150)));
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].claim_id, "c-ceo-old");
+
+        let response = execute_api_query(&store, request(Some(5_000)));
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].claim_id, "c-ceo-new");
+
+        assert!(
+            execute_api_query(&store, request(Some(50)))
+                .results
+                .is_empty()
+        );
+        assert_eq!(execute_api_query(&store, request(None)).results.len(), 3);
+    }
+
+    #[test]
+    fn cold_tier_claims_are_consulted_only_on_opt_in_or_shortfall() {
+        let _env_lock = env_lock().lock().expect("env lock should be available");
+        let _lock = segment_cache_test_lock()
+            .lock()
+            .expect("segment cache test lock should be available");
+        clear_segment_cache_for_tests();
+        let root = temp_dir("cold-tier-opt-in");
+        persist_segments_atomic(
+            &root.join("tenant-a"),
+            &[
+                Segment {
+                    segment_id: "hot-0".into(),
+                    tier: Tier::Hot,
+                    claim_ids: vec!["claim-hot".into()],
+                },
+                Segment {
+                    segment_id: "cold-0".into(),
+                    tier: Tier::Cold,
+                    claim_ids: vec!["claim-cold-1".into(), "claim-cold-2".into()],
+                },
+            ],
+        )
+        .expect("segment persist should succeed");
+
+        let mut store = InMemoryStore::new();
+        for (claim_id, confidence) in [
+            ("claim-hot", 0.9),
+            ("claim-cold-1", 0.4),
+            ("claim-cold-2", 0.3),
+        ] {
+            store
+                .ingest_bundle(
+                    Claim {
+                        claim_id: claim_id.into(),
+                        tenant_id: "tenant-a".into(),
+                        canonical_text: format!("Project Alpha status {claim_id}"),
+                        confidence,
+                        event_time_unix: None,
+                        entities: vec![],
+                        embedding_ids: vec![],
+                        claim_type: None,
+                        valid_from: None,
+                        valid_to: None,
+                        created_at: None,
+                        updated_at: None,
+                        metadata: Vec::new(),
+                        visibility_labels: Vec::new(),
+                        language: None,
+                        collection: None,
+                    },
+                    vec![],
+                    vec![],
+                )
+                .expect("ingest should succeed");
+        }
+        let request = |top_k, include_cold| RetrieveApiRequest {
+            tenant_id: "tenant-a".into(),
+            query: "project alpha status".into(),
+            query_embedding: None,
+            entity_filters: vec![],
+            embedding_id_filters: vec![],
+            top_k,
+            stance_mode: StanceMode::Balanced,
+            return_graph: false,
+            time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold,
+            deadline: None,
+            visibility_labels: None,
+        };
+        let ids = |response: &RetrieveApiResponse| {
+            response
+                .results
+                .iter()
+                .map(|node| node.claim_id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let _segment_dir_env = EnvVarGuard::set("DASH_RETRIEVAL_SEGMENT_DIR", root.as_os_str());
+        let hot_only = execute_api_query(&store, request(1, false));
+        assert_eq!(ids(&hot_only), vec!["claim-hot"]);
+        assert_eq!(hot_only.cold_claims_considered, 0);
+
+        let opted_in = execute_api_query(&store, request(1, true));
+        assert_eq!(ids(&opted_in), vec!["claim-hot"]);
+        assert_eq!(opted_in.cold_claims_considered, 2);
+
+        let shortfall = execute_api_query(&store, request(3, false));
+        assert_eq!(shortfall.results.len(), 3);
+        assert_eq!(shortfall.cold_claims_considered, 2);
+        assert!(!shortfall.truncated);
+
+        // A request whose deadline has passed stops scoring and skips the
+        // cold fallback instead of doing more work for a departed client.
+        let expired = execute_api_query(
+            &store,
+            RetrieveApiRequest {
+                deadline: Some(Instant::now()),
+                ..request(3, false)
+            },
+        );
+        assert!(expired.truncated);
+        assert_eq!(expired.cold_claims_considered, 0);
+
+        let debug = build_retrieve_planner_debug_snapshot(&store, &request(1, false));
+        assert_eq!(debug.segment_base_count, 3);
+        assert_eq!(debug.segment_cold_count, 2);
+
+        let _ = std::fs::remove_dir_all(root);
+        clear_segment_cache_for_tests();
+    }
 }
@@ -25,6 +25,7 @@ pub(super) fn evidence_node_from_parts(
         dominant_stance: dominant_stance_for_counts(signals.supports, signals.contradicts)
             .map(str::to_string),
         contradiction_risk: contradiction_risk_for_counts(signals.supports, signals.contradicts),
+        certainty: signals.certainty.map(|band| band.as_str().to_string()),
         graph_score: None,
         support_path_count: None,
         contradiction_chain_depth: None,
@@ -90,13 +91,7 @@ pub(super) fn temporal_annotation_for_claim(
 }
 
 pub(super) fn claim_type_to_str(value: &ClaimType) -> &'static str {
-    match value {
-        ClaimType::Factual => "factual",
-        ClaimType::Opinion => "opinion",
-        ClaimType::Prediction => "prediction",
-        ClaimType::Temporal => "temporal",
-        ClaimType::Causal => "causal",
-    }
+    value.as_str()
 }
 
 pub(super) fn confidence_band_for_claim_confidence(value: f32) -> &'static str {
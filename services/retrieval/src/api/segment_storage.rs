@@ -1,4 +1,5 @@
-use indexer::{load_manifest, load_segments_from_manifest};
+use indexer::{Tier, load_manifest, load_segments_from_manifest};
+use schema::{ClaimId, TenantId};
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
@@ -27,9 +28,17 @@ impl SegmentCacheKey {
     }
 }
 
+/// A tenant's segment claim ids. The cold-tier subset is kept apart so
+/// retrieval can leave it out unless a request asks for it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(super) struct SegmentPrefilter {
+    pub(super) claim_ids: HashSet<String>,
+    pub(super) cold_claim_ids: HashSet<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct SegmentCacheEntry {
-    claim_ids: Option<HashSet<String>>,
+    prefilter: Option<SegmentPrefilter>,
     fallback_reason: Option<SegmentFallbackReason>,
     next_refresh_instant: std::time::Instant,
 }
@@ -43,7 +52,7 @@ enum SegmentFallbackReason {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct SegmentPrefilterLoadResult {
-    claim_ids: Option<HashSet<String>>,
+    prefilter: Option<SegmentPrefilter>,
     fallback_reason: Option<SegmentFallbackReason>,
 }
 
@@ -65,16 +74,24 @@ static SEGMENT_PREFILTER_CACHE: OnceLock<RwLock<HashMap<SegmentCacheKey, Segment
 static SEGMENT_PREFILTER_CACHE_METRICS: OnceLock<SegmentPrefilterCacheMetricAtoms> =
     OnceLock::new();
 
-pub(super) fn build_segment_prefilter_claim_ids(tenant_id: &str) -> Option<HashSet<String>> {
+pub(super) fn build_segment_prefilter(tenant_id: &str) -> Option<SegmentPrefilter> {
     let segment_root =
         env_with_fallback("DASH_RETRIEVAL_SEGMENT_DIR", "EME_RETRIEVAL_SEGMENT_DIR")?;
-    build_segment_prefilter_claim_ids_from_root(tenant_id, PathBuf::from(segment_root))
+    build_segment_prefilter_from_root(tenant_id, PathBuf::from(segment_root))
 }
 
+#[cfg(test)]
 pub(super) fn build_segment_prefilter_claim_ids_from_root(
     tenant_id: &str,
     segment_root: PathBuf,
 ) -> Option<HashSet<String>> {
+    build_segment_prefilter_from_root(tenant_id, segment_root).map(|prefilter| prefilter.claim_ids)
+}
+
+pub(super) fn build_segment_prefilter_from_root(
+    tenant_id: &str,
+    segment_root: PathBuf,
+) -> Option<SegmentPrefilter> {
     let cache_key = SegmentCacheKey::new(&segment_root, tenant_id);
     let segment_tenant_path = segment_root.join(sanitize_path_component(tenant_id));
     let now = std::time::Instant::now();
@@ -85,10 +102,10 @@ pub(super) fn build_segment_prefilter_claim_ids_from_root(
         segment_prefilter_cache_metric_atoms()
             .cache_hits
             .fetch_add(1, Ordering::Relaxed);
-        if entry.claim_ids.is_none() {
+        if entry.prefilter.is_none() {
             observe_segment_fallback_activation(entry.fallback_reason);
         }
-        return entry.claim_ids.clone();
+        return entry.prefilter.clone();
     }
 
     segment_prefilter_cache_metric_atoms()
@@ -105,7 +122,7 @@ pub(super) fn build_segment_prefilter_claim_ids_from_root(
     segment_prefilter_cache_metric_atoms()
         .refresh_load_micros
         .fetch_add(elapsed_micros_u64, Ordering::Relaxed);
-    if load_result.claim_ids.is_some() {
+    if load_result.prefilter.is_some() {
         segment_prefilter_cache_metric_atoms()
             .refresh_successes
             .fetch_add(1, Ordering::Relaxed);
@@ -120,13 +137,13 @@ pub(super) fn build_segment_prefilter_claim_ids_from_root(
         cache.insert(
             cache_key,
             SegmentCacheEntry {
-                claim_ids: load_result.claim_ids.clone(),
+                prefilter: load_result.prefilter.clone(),
                 fallback_reason: load_result.fallback_reason,
                 next_refresh_instant,
             },
         );
     }
-    load_result.claim_ids
+    load_result.prefilter
 }
 
 fn load_segment_prefilter_claim_ids(segment_tenant_path: &Path) -> SegmentPrefilterLoadResult {
@@ -134,13 +151,13 @@ fn load_segment_prefilter_claim_ids(segment_tenant_path: &Path) -> SegmentPrefil
         Ok(Some(value)) => value,
         Ok(None) => {
             return SegmentPrefilterLoadResult {
-                claim_ids: None,
+                prefilter: None,
                 fallback_reason: Some(SegmentFallbackReason::MissingManifest),
             };
         }
         Err(_) => {
             return SegmentPrefilterLoadResult {
-                claim_ids: None,
+                prefilter: None,
                 fallback_reason: Some(SegmentFallbackReason::ManifestError),
             };
         }
@@ -149,17 +166,22 @@ fn load_segment_prefilter_claim_ids(segment_tenant_path: &Path) -> SegmentPrefil
         Ok(value) => value,
         Err(_) => {
             return SegmentPrefilterLoadResult {
-                claim_ids: None,
+                prefilter: None,
                 fallback_reason: Some(SegmentFallbackReason::SegmentError),
             };
         }
     };
-    let mut ids = HashSet::new();
+    let mut prefilter = SegmentPrefilter::default();
     for segment in segments {
-        ids.extend(segment.claim_ids);
+        if segment.tier == Tier::Cold {
+            prefilter
+                .cold_claim_ids
+                .extend(segment.claim_ids.iter().cloned());
+        }
+        prefilter.claim_ids.extend(segment.claim_ids);
     }
     SegmentPrefilterLoadResult {
-        claim_ids: Some(ids),
+        prefilter: Some(prefilter),
         fallback_reason: None,
     }
 }
@@ -189,15 +211,16 @@ fn observe_segment_fallback_activation(reason: Option<SegmentFallbackReason>) {
 
 pub(super) fn build_wal_delta_claim_ids(
     store: &InMemoryStore,
-    tenant_id: &str,
+    tenant_id: &TenantId,
     segment_base_claim_ids: Option<&HashSet<String>>,
 ) -> Option<HashSet<String>> {
     let segment_base_claim_ids = segment_base_claim_ids?;
     let tenant_claim_ids = store.claim_ids_for_tenant(tenant_id);
     Some(
         tenant_claim_ids
-            .difference(segment_base_claim_ids)
-            .cloned()
+            .into_iter()
+            .filter(|claim_id| !segment_base_claim_ids.contains(claim_id.as_str()))
+            .map(ClaimId::into_string)
             .collect(),
     )
 }
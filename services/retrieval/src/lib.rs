@@ -22,7 +22,7 @@ pub fn retrieve_for_rag_with_time_range(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use schema::{Claim, Evidence, Stance, StanceMode};
+    use schema::{Claim, Evidence, Stance};
 
     #[test]
     fn retrieve_for_rag_returns_ranked_results_with_citations() {
@@ -42,6 +42,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e1".into(),
@@ -55,6 +59,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -62,12 +69,7 @@ mod tests {
 
         let results = retrieve_for_rag(
             &store,
-            RetrievalRequest {
-                tenant_id: "tenant-a".into(),
-                query: "Did company x acquire company y?".into(),
-                top_k: 1,
-                stance_mode: StanceMode::Balanced,
-            },
+            RetrievalRequest::new("tenant-a", "Did company x acquire company y?", 1),
         );
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].claim_id, "c1");
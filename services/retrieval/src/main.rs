@@ -1,6 +1,7 @@
+use metadata_router::TenantAliases;
 use retrieval::{retrieve_for_rag, transport::serve_http_with_workers};
-use schema::{Claim, Evidence, RetrievalRequest, Stance, StanceMode};
-use store::{AnnTuningConfig, FileWal, InMemoryStore};
+use schema::{Claim, Evidence, RetrievalRequest, Stance};
+use store::{AnnIndexKind, AnnTuningConfig, FileWal, InMemoryStore};
 
 fn main() {
     // Default to serve mode (this is a server binary; the CLI
@@ -16,6 +17,12 @@ fn main() {
     let http_workers = parse_http_workers();
     let ann_tuning = parse_ann_tuning_config();
     let segment_dir = env_with_fallback("DASH_RETRIEVAL_SEGMENT_DIR", "EME_RETRIEVAL_SEGMENT_DIR");
+    if let Some(raw) = env_with_fallback("DASH_TENANT_ALIASES", "EME_TENANT_ALIASES")
+        && let Err(reason) = TenantAliases::parse(&raw)
+    {
+        eprintln!("retrieval invalid DASH_TENANT_ALIASES: {reason}");
+        std::process::exit(2);
+    }
 
     let store = if let Some(wal_path) =
         env_with_fallback("DASH_RETRIEVAL_WAL_PATH", "EME_RETRIEVAL_WAL_PATH")
@@ -23,7 +30,7 @@ fn main() {
         let wal = match FileWal::open(&wal_path) {
             Ok(wal) => wal,
             Err(err) => {
-                eprintln!("retrieval failed opening WAL '{wal_path}': {err:?}");
+                eprintln!("retrieval failed opening WAL '{wal_path}': {err}");
                 std::process::exit(1);
             }
         };
@@ -33,7 +40,7 @@ fn main() {
         ) {
             Ok(result) => result,
             Err(err) => {
-                eprintln!("retrieval failed replaying WAL '{wal_path}': {err:?}");
+                eprintln!("retrieval failed replaying WAL '{wal_path}': {err}");
                 std::process::exit(1);
             }
         };
@@ -54,7 +61,7 @@ fn main() {
             "EME_RETRIEVAL_PERSISTENCE_DISABLE",
         )
         .as_deref()
-        == Some("1");
+            == Some("1");
         let disk_path = env_with_fallback(
             "DASH_RETRIEVAL_PERSISTENCE_PATH",
             "EME_RETRIEVAL_PERSISTENCE_PATH",
@@ -109,6 +116,10 @@ fn main() {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "sample-evidence".into(),
@@ -122,6 +133,9 @@ fn main() {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -129,12 +143,7 @@ fn main() {
 
         let results = retrieve_for_rag(
             &store,
-            RetrievalRequest {
-                tenant_id: "sample-tenant".into(),
-                query: "retrieval initialized".into(),
-                top_k: 5,
-                stance_mode: StanceMode::Balanced,
-            },
+            RetrievalRequest::new("sample-tenant", "retrieval initialized", 5),
         );
         println!("retrieval ready: results={}", results.len());
         store
@@ -259,6 +268,36 @@ fn parse_ann_tuning_config() -> AnnTuningConfig {
         ])
         .filter(|value| *value > 0)
         .unwrap_or(defaults.search_expansion_max),
+        index_kind: parse_env_first::<String>(&[
+            "DASH_RETRIEVAL_ANN_INDEX_KIND",
+            "DASH_ANN_INDEX_KIND",
+            "EME_RETRIEVAL_ANN_INDEX_KIND",
+            "EME_ANN_INDEX_KIND",
+        ])
+        .and_then(|value| AnnIndexKind::parse(&value))
+        .unwrap_or(defaults.index_kind),
+        exact_search_threshold: parse_env_first::<usize>(&[
+            "DASH_RETRIEVAL_ANN_EXACT_SEARCH_THRESHOLD",
+            "DASH_ANN_EXACT_SEARCH_THRESHOLD",
+            "EME_RETRIEVAL_ANN_EXACT_SEARCH_THRESHOLD",
+            "EME_ANN_EXACT_SEARCH_THRESHOLD",
+        ])
+        .unwrap_or(defaults.exact_search_threshold),
+        level_multiplier: parse_env_first::<f64>(&[
+            "DASH_RETRIEVAL_ANN_LEVEL_MULTIPLIER",
+            "DASH_ANN_LEVEL_MULTIPLIER",
+            "EME_RETRIEVAL_ANN_LEVEL_MULTIPLIER",
+            "EME_ANN_LEVEL_MULTIPLIER",
+        ])
+        .filter(|value| value.is_finite() && *value >= 0.0)
+        .unwrap_or(defaults.level_multiplier),
+        level_seed: parse_env_first::<u64>(&[
+            "DASH_RETRIEVAL_ANN_LEVEL_SEED",
+            "DASH_ANN_LEVEL_SEED",
+            "EME_RETRIEVAL_ANN_LEVEL_SEED",
+            "EME_ANN_LEVEL_SEED",
+        ])
+        .unwrap_or(defaults.level_seed),
     }
 }
 
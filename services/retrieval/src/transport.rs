@@ -13,11 +13,14 @@ use std::{
 
 use metadata_router::{
     PlacementRouteError, ReadPreference, ReplicaHealth, ReplicaRole, RoutedReplica, RouterConfig,
-    ShardPlacement, load_shard_placements_from_source, route_read_with_placement,
+    ShardPlacement, TenantAliases, load_shard_placements_from_source, route_read_with_placement,
     shard_ids_from_placements,
 };
-use schema::StanceMode;
-use store::InMemoryStore;
+use schema::{ClaimId, ClaimType, StanceMode};
+use store::{
+    ConfidenceRange, InMemoryStore, MetadataFilter, ParsedQuery, ResultFields, RetrievalOptions,
+    StoreError, parse_query,
+};
 
 #[cfg(test)]
 use crate::api::STORAGE_MERGE_MODEL;
@@ -33,20 +36,25 @@ use crate::api::{
 };
 mod audit;
 mod authz;
+mod concurrency;
 mod debug_render;
 mod http;
 mod payload;
 use audit::{AuditEvent, append_audit_record};
 #[cfg(test)]
 use audit::{audit_chain_states, is_sha256_hex};
-use authz::{AuthDecision, AuthPolicy, authorize_request_for_tenant};
+use authz::{
+    AuthDecision, AuthPolicy, authorize_request_for_tenant, visibility_labels_for_request,
+};
+use concurrency::{QueryConcurrencyLimiter, query_limiter};
 use debug_render::{
     evaluate_storage_divergence_warning, promotion_boundary_state_metric_value,
     render_placement_debug_json, render_planner_debug_json, render_storage_visibility_debug_json,
     resolve_storage_divergence_warn_delta_count, resolve_storage_divergence_warn_ratio,
 };
 use http::{
-    parse_request_line, read_http_request, render_response_text, split_target, write_response,
+    parse_request_line, read_http_request, render_response_text, request_deadline, split_target,
+    write_response,
 };
 #[cfg(test)]
 use payload::build_retrieve_request_from_json;
@@ -242,6 +250,13 @@ pub(crate) struct TransportMetrics {
     retrieve_last_result_count: usize,
     retrieve_latency_ms_window: VecDeque<f64>,
     ingest_to_visible_lag_ms_window: VecDeque<f64>,
+    /// Time retrievals spent waiting for a concurrency limiter slot.
+    query_queue_wait_ms_window: VecDeque<f64>,
+    query_queue_timeout_total: u64,
+    /// Retrievals whose request deadline passed before they started.
+    retrieve_deadline_exceeded_total: u64,
+    /// Retrievals the request deadline cut short.
+    retrieve_truncated_total: u64,
     storage_last_segment_base_count: usize,
     storage_last_wal_delta_count: usize,
     storage_last_storage_visible_count: usize,
@@ -298,6 +313,10 @@ impl Default for TransportMetrics {
             retrieve_last_result_count: 0,
             retrieve_latency_ms_window: VecDeque::with_capacity(METRICS_WINDOW_SIZE),
             ingest_to_visible_lag_ms_window: VecDeque::with_capacity(METRICS_WINDOW_SIZE),
+            query_queue_wait_ms_window: VecDeque::with_capacity(METRICS_WINDOW_SIZE),
+            query_queue_timeout_total: 0,
+            retrieve_deadline_exceeded_total: 0,
+            retrieve_truncated_total: 0,
             storage_last_segment_base_count: 0,
             storage_last_wal_delta_count: 0,
             storage_last_storage_visible_count: 0,
@@ -372,6 +391,24 @@ impl TransportMetrics {
         }
     }
 
+    fn observe_query_queue_wait(&mut self, wait: Duration, timed_out: bool) {
+        Self::push_window(
+            &mut self.query_queue_wait_ms_window,
+            wait.as_secs_f64() * 1000.0,
+        );
+        if timed_out {
+            self.query_queue_timeout_total += 1;
+        }
+    }
+
+    fn observe_retrieve_deadline_exceeded(&mut self) {
+        self.retrieve_deadline_exceeded_total += 1;
+    }
+
+    fn observe_retrieve_truncated(&mut self) {
+        self.retrieve_truncated_total += 1;
+    }
+
     fn observe_auth_success(&mut self) {
         self.auth_success_total += 1;
     }
@@ -518,6 +555,11 @@ impl TransportMetrics {
         let retrieve_latency_p99 = Self::quantile(&self.retrieve_latency_ms_window, 0.99);
         let visibility_lag_p50 = Self::quantile(&self.ingest_to_visible_lag_ms_window, 0.50);
         let visibility_lag_p95 = Self::quantile(&self.ingest_to_visible_lag_ms_window, 0.95);
+        let query_queue_wait_p50 = Self::quantile(&self.query_queue_wait_ms_window, 0.50);
+        let query_queue_wait_p95 = Self::quantile(&self.query_queue_wait_ms_window, 0.95);
+        let query_limiter = query_limiter()
+            .map(QueryConcurrencyLimiter::snapshot)
+            .unwrap_or_default();
         let segment_cache_metrics = segment_prefilter_cache_metrics_snapshot();
         let uptime_seconds = self.started_at.elapsed().as_secs_f64();
         let placement_enabled = placement_routing.map(|_| 1).unwrap_or(0);
@@ -625,6 +667,22 @@ dash_retrieve_latency_ms_p99 {:.4}\n\
 dash_ingest_to_visible_lag_ms_p50 {:.4}\n\
 # TYPE dash_ingest_to_visible_lag_ms_p95 gauge\n\
 dash_ingest_to_visible_lag_ms_p95 {:.4}\n\
+# TYPE dash_retrieve_query_max_in_flight gauge\n\
+dash_retrieve_query_max_in_flight {}\n\
+# TYPE dash_retrieve_query_in_flight gauge\n\
+dash_retrieve_query_in_flight {}\n\
+# TYPE dash_retrieve_query_queued gauge\n\
+dash_retrieve_query_queued {}\n\
+# TYPE dash_retrieve_query_queue_wait_ms_p50 gauge\n\
+dash_retrieve_query_queue_wait_ms_p50 {:.4}\n\
+# TYPE dash_retrieve_query_queue_wait_ms_p95 gauge\n\
+dash_retrieve_query_queue_wait_ms_p95 {:.4}\n\
+# TYPE dash_retrieve_query_queue_timeout_total counter\n\
+dash_retrieve_query_queue_timeout_total {}\n\
+# TYPE dash_retrieve_deadline_exceeded_total counter\n\
+dash_retrieve_deadline_exceeded_total {}\n\
+# TYPE dash_retrieve_truncated_total counter\n\
+dash_retrieve_truncated_total {}\n\
 # TYPE dash_retrieve_storage_last_segment_base_count gauge\n\
 dash_retrieve_storage_last_segment_base_count {}\n\
 # TYPE dash_retrieve_storage_last_wal_delta_count gauge\n\
@@ -730,6 +788,14 @@ dash_transport_uptime_seconds {:.4}\n",
             retrieve_latency_p99,
             visibility_lag_p50,
             visibility_lag_p95,
+            query_limiter.max_in_flight,
+            query_limiter.in_flight,
+            query_limiter.queued,
+            query_queue_wait_p50,
+            query_queue_wait_p95,
+            self.query_queue_timeout_total,
+            self.retrieve_deadline_exceeded_total,
+            self.retrieve_truncated_total,
             self.storage_last_segment_base_count,
             self.storage_last_wal_delta_count,
             self.storage_last_storage_visible_count,
@@ -861,7 +927,9 @@ pub fn serve_http_with_workers(
         // accept() calls with shutdown-flag polling. The 50ms
         // sleep caps shutdown latency at ~50ms p99 and bounds
         // CPU usage in the idle case.
-        listener.set_nonblocking(true).expect("set listener non-blocking");
+        listener
+            .set_nonblocking(true)
+            .expect("set listener non-blocking");
         loop {
             if shutdown.is_triggered() {
                 eprintln!("retrieval: shutdown signal received, draining in-flight requests");
@@ -1098,6 +1166,7 @@ fn handle_request_with_metrics_and_reload(
     placement_routing: Option<&PlacementRoutingRuntime>,
     placement_reload: Option<&PlacementReloadSnapshot>,
 ) -> HttpResponse {
+    let deadline = request_deadline(&request.headers, Instant::now());
     let (path, query) = split_target(&request.target);
     let auth_policy = AuthPolicy::from_env(
         env_with_fallback("DASH_RETRIEVAL_API_KEY", "EME_RETRIEVAL_API_KEY"),
@@ -1146,9 +1215,7 @@ fn handle_request_with_metrics_and_reload(
             // mutex is poisoned, something else is very wrong.
             match metrics.lock() {
                 Ok(_) => HttpResponse::ok_json("{\"status\":\"ready\"}".to_string()),
-                Err(_) => HttpResponse::internal_server_error(
-                    "metrics mutex poisoned",
-                ),
+                Err(_) => HttpResponse::internal_server_error("metrics mutex poisoned"),
             }
         }
         ("GET", "/metrics") => {
@@ -1285,7 +1352,10 @@ fn handle_request_with_metrics_and_reload(
         },
         ("GET", "/v1/retrieve") => match build_retrieve_transport_request_from_query(&query) {
             Ok(transport_req) => {
-                let req = transport_req.request;
+                let req = RetrieveApiRequest {
+                    deadline,
+                    ..transport_req.request
+                };
                 let tenant_id = req.tenant_id.clone();
                 match authorize_request_for_tenant(request, &tenant_id, &auth_policy) {
                     AuthDecision::Unauthorized(reason) => {
@@ -1322,12 +1392,21 @@ fn handle_request_with_metrics_and_reload(
                     }
                     AuthDecision::Allowed => {
                         observe_auth_success(metrics);
+                        let req = RetrieveApiRequest {
+                            visibility_labels: Some(visibility_labels_for_request(
+                                request,
+                                &tenant_id,
+                                &auth_policy,
+                            )),
+                            ..req
+                        };
                         let response = execute_retrieve_and_observe(
                             store,
                             req,
                             transport_req.read_consistency,
                             metrics,
                             placement_routing,
+                            query_limiter(),
                         );
                         let (outcome, reason) = if response.status < 400 {
                             ("success", "retrieve accepted")
@@ -1379,7 +1458,10 @@ fn handle_request_with_metrics_and_reload(
             };
             match build_retrieve_transport_request_from_json(body) {
                 Ok(transport_req) => {
-                    let req = transport_req.request;
+                    let req = RetrieveApiRequest {
+                        deadline,
+                        ..transport_req.request
+                    };
                     let tenant_id = req.tenant_id.clone();
                     match authorize_request_for_tenant(request, &tenant_id, &auth_policy) {
                         AuthDecision::Unauthorized(reason) => {
@@ -1416,12 +1498,21 @@ fn handle_request_with_metrics_and_reload(
                         }
                         AuthDecision::Allowed => {
                             observe_auth_success(metrics);
+                            let req = RetrieveApiRequest {
+                                visibility_labels: Some(visibility_labels_for_request(
+                                    request,
+                                    &tenant_id,
+                                    &auth_policy,
+                                )),
+                                ..req
+                            };
                             let response = execute_retrieve_and_observe(
                                 store,
                                 req,
                                 transport_req.read_consistency,
                                 metrics,
                                 placement_routing,
+                                query_limiter(),
                             );
                             let (outcome, reason) = if response.status < 400 {
                                 ("success", "retrieve accepted")
@@ -1463,9 +1554,7 @@ fn handle_request_with_metrics_and_reload(
             // vectors are semantically meaningful.
             let body = match std::str::from_utf8(&request.body) {
                 Ok(text) => text,
-                Err(_) => {
-                    return HttpResponse::bad_request("request body must be valid UTF-8")
-                }
+                Err(_) => return HttpResponse::bad_request("request body must be valid UTF-8"),
             };
             let provider = crate::openai_embeddings::select_provider_from_env();
             match crate::openai_embeddings::handle_openai_embeddings_with_provider(
@@ -1473,8 +1562,7 @@ fn handle_request_with_metrics_and_reload(
                 provider.as_ref(),
             ) {
                 Ok(resp) => {
-                    let body = serde_json::to_string(&resp)
-                        .unwrap_or_else(|_| "{}".to_string());
+                    let body = serde_json::to_string(&resp).unwrap_or_else(|_| "{}".to_string());
                     HttpResponse::ok_json(body)
                 }
                 Err(err) => {
@@ -1507,6 +1595,24 @@ fn env_with_fallback(primary: &str, fallback: &str) -> Option<String> {
         .or_else(|| std::env::var(fallback).ok())
 }
 
+/// Tenant aliases from `DASH_TENANT_ALIASES` (`old=new,...`). Startup
+/// rejects an invalid value, so a parse failure here means no aliases.
+fn tenant_aliases_from_env() -> TenantAliases {
+    env_with_fallback("DASH_TENANT_ALIASES", "EME_TENANT_ALIASES")
+        .and_then(|raw| TenantAliases::parse(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// The id a request's tenant id resolves to through the configured
+/// aliases.
+fn canonical_tenant_id(tenant_id: String) -> String {
+    let aliases = tenant_aliases_from_env();
+    match aliases.resolve(tenant_id.trim()) {
+        resolved if resolved == tenant_id.trim() => tenant_id,
+        resolved => resolved.to_string(),
+    }
+}
+
 fn observe_auth_success(metrics: &Arc<Mutex<TransportMetrics>>) {
     if let Ok(mut guard) = metrics.lock() {
         guard.observe_auth_success();
@@ -1568,6 +1674,7 @@ fn execute_retrieve_and_observe(
     read_consistency: ReadConsistencyPolicy,
     metrics: &Arc<Mutex<TransportMetrics>>,
     placement_routing: Option<&PlacementRoutingRuntime>,
+    query_limiter: Option<&QueryConcurrencyLimiter>,
 ) -> HttpResponse {
     let mut serving_replica: Option<String> = None;
     if let Some(routing) = placement_routing {
@@ -1589,8 +1696,40 @@ fn execute_retrieve_and_observe(
         }
     }
 
-    let started_at = Instant::now();
     let tenant_id = req.tenant_id.clone();
+    let _permit = match query_limiter.map(|limiter| limiter.acquire(&tenant_id, req.deadline)) {
+        Some(Ok(permit)) => {
+            if let Ok(mut guard) = metrics.lock() {
+                guard.observe_query_queue_wait(permit.queue_wait(), false);
+            }
+            Some(permit)
+        }
+        Some(Err(waited)) => {
+            if let Ok(mut guard) = metrics.lock() {
+                guard.observe_query_queue_wait(waited, true);
+                guard.observe_retrieve(503, 0.0, 0, None);
+            }
+            return HttpResponse::service_unavailable(&format!(
+                "service unavailable: retrieval concurrency limit reached after waiting {} ms",
+                waited.as_millis()
+            ));
+        }
+        None => None,
+    };
+    if req
+        .deadline
+        .is_some_and(|deadline| Instant::now() >= deadline)
+    {
+        if let Ok(mut guard) = metrics.lock() {
+            guard.observe_retrieve_deadline_exceeded();
+            guard.observe_retrieve(504, 0.0, 0, None);
+        }
+        return HttpResponse::gateway_timeout(
+            "request timeout elapsed before the retrieval could start",
+        );
+    }
+
+    let started_at = Instant::now();
     let (response, merge_snapshot) = execute_api_query_with_storage_snapshot(store, req);
     let latency_ms = started_at.elapsed().as_secs_f64() * 1000.0;
     let result_count = response.results.len();
@@ -1598,6 +1737,9 @@ fn execute_retrieve_and_observe(
         estimate_ingest_to_visible_lag_ms(store, &tenant_id, &response.results);
 
     if let Ok(mut guard) = metrics.lock() {
+        if response.truncated {
+            guard.observe_retrieve_truncated();
+        }
         guard.observe_retrieve(200, latency_ms, result_count, ingest_to_visible_lag_ms);
         guard.observe_storage_merge_execution(&merge_snapshot);
     }
@@ -1975,18 +2117,15 @@ fn estimate_ingest_to_visible_lag_ms(
         return None;
     }
     let now_unix = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs() as i64;
-    let claims = store.claims_for_tenant(tenant_id);
-    let event_time_by_claim_id: HashMap<String, i64> = claims
-        .into_iter()
-        .filter_map(|claim| claim.event_time_unix.map(|ts| (claim.claim_id, ts)))
-        .collect();
-
     let mut lag_values: Vec<f64> = Vec::new();
     for node in results {
-        if let Some(event_unix) = event_time_by_claim_id.get(&node.claim_id)
-            && now_unix >= *event_unix
+        if let Some(event_unix) = store
+            .claim_by_id(&ClaimId::from(&node.claim_id))
+            .filter(|claim| claim.tenant_id == tenant_id)
+            .and_then(|claim| claim.event_time_unix)
+            && now_unix >= event_unix
         {
-            lag_values.push((now_unix - *event_unix) as f64 * 1000.0);
+            lag_values.push((now_unix - event_unix) as f64 * 1000.0);
         }
     }
     if lag_values.is_empty() {
@@ -2083,6 +2222,14 @@ impl HttpResponse {
         }
     }
 
+    fn gateway_timeout(message: &str) -> Self {
+        Self {
+            status: 504,
+            content_type: "application/json",
+            body: format!("{{\"error\":\"{}\"}}", json_escape(message)),
+        }
+    }
+
     fn error_with_status(status: u16, message: &str) -> Self {
         match status {
             400 => Self::bad_request(message),
@@ -2131,6 +2278,10 @@ mod tests {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e1".into(),
@@ -2144,6 +2295,9 @@ mod tests {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -2215,6 +2369,7 @@ mod tests {
         assert!(req.query_embedding.is_none());
         assert!(req.entity_filters.is_empty());
         assert!(req.embedding_id_filters.is_empty());
+        assert!(req.ann_expansion_budget.is_none());
     }
 
     #[test]
@@ -2228,7 +2383,8 @@ mod tests {
             "top_k": 3,
             "stance_mode": "support_only",
             "return_graph": true,
-            "time_range": {"from_unix": 10, "to_unix": 20}
+            "time_range": {"from_unix": 10, "to_unix": 20},
+            "ann_expansion_budget": 256
         }"#;
 
         let req = build_retrieve_request_from_json(body).unwrap();
@@ -2239,6 +2395,7 @@ mod tests {
         assert_eq!(req.query_embedding, Some(vec![0.1, 0.2, 0.3]));
         assert_eq!(req.entity_filters, vec!["company x"]);
         assert_eq!(req.embedding_id_filters, vec!["emb://1"]);
+        assert_eq!(req.ann_expansion_budget, Some(256));
     }
 
     #[test]
@@ -2256,6 +2413,213 @@ mod tests {
         assert_eq!(req.embedding_id_filters, vec!["emb://1", "emb://2"]);
     }
 
+    #[test]
+    fn build_retrieve_request_compiles_q_query_language_from_query_and_json() {
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "tenant-a".into());
+        params.insert(
+            "q".into(),
+            r#"entity:"Company X" type:factual after:1970-01-02 acquisition"#.into(),
+        );
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.query, "acquisition");
+        assert_eq!(req.entity_filters, vec!["Company X"]);
+        assert_eq!(req.claim_types, vec![ClaimType::Factual]);
+        assert_eq!(
+            req.time_range,
+            Some(TimeRange {
+                from_unix: Some(86_400),
+                to_unix: None,
+            })
+        );
+
+        params.insert("query".into(), "acquisition".into());
+        assert!(build_retrieve_request_from_query(&params).is_err());
+
+        let body = r#"{
+            "tenant_id": "tenant-a",
+            "q": "meta:project=apollo min_confidence:0.5 launch",
+            "min_confidence": 0.2
+        }"#;
+        assert!(build_retrieve_request_from_json(body).is_err());
+        let body = r#"{
+            "tenant_id": "tenant-a",
+            "q": "meta:project=apollo min_confidence:0.5 launch",
+            "metadata_filters": {"label": "urgent"}
+        }"#;
+        let req = build_retrieve_request_from_json(body).unwrap();
+        assert_eq!(req.query, "launch");
+        assert_eq!(req.metadata_filters.len(), 2);
+        assert_eq!(req.confidence_range.unwrap().min, Some(0.5));
+        let body = r#"{"tenant_id": "tenant-a", "q": "type:bogus launch"}"#;
+        assert!(build_retrieve_request_from_json(body).is_err());
+    }
+
+    #[test]
+    fn build_retrieve_request_parses_metadata_filters_from_query_and_json() {
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "tenant-a".into());
+        params.insert("query".into(), "company x".into());
+        params.insert(
+            "metadata_filters".into(),
+            "project:apollo,label:urgent|blocked".into(),
+        );
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(
+            req.metadata_filters,
+            vec![
+                MetadataFilter::equals("project", "apollo"),
+                MetadataFilter::any_of("label", vec!["urgent".into(), "blocked".into()]),
+            ]
+        );
+
+        let body = r#"{
+            "tenant_id": "tenant-a",
+            "query": "company x",
+            "metadata_filters": {"source": "crm", "label": ["urgent", "blocked"]}
+        }"#;
+        let req = build_retrieve_request_from_json(body).unwrap();
+        assert_eq!(
+            req.metadata_filters,
+            vec![
+                MetadataFilter::any_of("label", vec!["urgent".into(), "blocked".into()]),
+                MetadataFilter::equals("source", "crm"),
+            ]
+        );
+
+        params.insert("metadata_filters".into(), "project".into());
+        let err = build_retrieve_request_from_query(&params).unwrap_err();
+        assert!(err.contains("key:value"));
+    }
+
+    #[test]
+    fn build_retrieve_request_parses_confidence_bounds_from_query_and_json() {
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "tenant-a".into());
+        params.insert("query".into(), "company x".into());
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.confidence_range, None);
+
+        params.insert("min_confidence".into(), "0.5".into());
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.confidence_range, Some(ConfidenceRange::at_least(0.5)));
+
+        params.insert("max_confidence".into(), "0.4".into());
+        let err = build_retrieve_request_from_query(&params).unwrap_err();
+        assert!(err.contains("min_confidence must be <= max_confidence"));
+
+        params.insert("max_confidence".into(), "1.5".into());
+        let err = build_retrieve_request_from_query(&params).unwrap_err();
+        assert!(err.contains("max_confidence must be a number between 0 and 1"));
+
+        let body = r#"{
+            "tenant_id": "tenant-a",
+            "query": "company x",
+            "min_confidence": 0.25,
+            "max_confidence": 0.75
+        }"#;
+        let req = build_retrieve_request_from_json(body).unwrap();
+        assert_eq!(
+            req.confidence_range,
+            Some(ConfidenceRange::new(Some(0.25), Some(0.75)))
+        );
+    }
+
+    #[test]
+    fn build_retrieve_request_resolves_tenant_aliases() {
+        let _guard = env_lock().lock().expect("env lock should be available");
+        let previous_aliases = std::env::var_os("DASH_TENANT_ALIASES");
+        set_env_var_for_tests("DASH_TENANT_ALIASES", "acme-old=acme");
+
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "acme-old".into());
+        params.insert("query".into(), "company x".into());
+        let from_query = build_retrieve_request_from_query(&params).unwrap();
+        let body = r#"{"tenant_id": "acme-old", "query": "company x"}"#;
+        let from_json = build_retrieve_request_from_json(body).unwrap();
+        params.insert("tenant_id".into(), "globex".into());
+        let unaliased = build_retrieve_request_from_query(&params).unwrap();
+
+        restore_env_var_for_tests("DASH_TENANT_ALIASES", previous_aliases.as_deref());
+        assert_eq!(from_query.tenant_id, "acme");
+        assert_eq!(from_json.tenant_id, "acme");
+        assert_eq!(unaliased.tenant_id, "globex");
+    }
+
+    #[test]
+    fn build_retrieve_request_parses_claim_types_from_query_and_json() {
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "tenant-a".into());
+        params.insert("query".into(), "company x".into());
+        params.insert("claim_types".into(), "factual, Causal".into());
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.claim_types, vec![ClaimType::Factual, ClaimType::Causal]);
+
+        params.insert("claim_types".into(), "rumor".into());
+        let err = build_retrieve_request_from_query(&params).unwrap_err();
+        assert!(err.contains("claim_types values must be"));
+
+        let body = r#"{
+            "tenant_id": "tenant-a",
+            "query": "company x",
+            "claim_types": ["opinion"]
+        }"#;
+        let req = build_retrieve_request_from_json(body).unwrap();
+        assert_eq!(req.claim_types, vec![ClaimType::Opinion]);
+    }
+
+    #[test]
+    fn build_retrieve_request_parses_collections_from_query_json_and_q() {
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "tenant-a".into());
+        params.insert("query".into(), "renewal terms".into());
+        params.insert("collections".into(), "contracts, support-tickets".into());
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.collections, vec!["contracts", "support-tickets"]);
+
+        let body = r#"{
+            "tenant_id": "tenant-a",
+            "q": "collection:contracts collection:legal renewal terms",
+            "collections": ["contracts"]
+        }"#;
+        let req = build_retrieve_request_from_json(body).unwrap();
+        assert_eq!(req.query, "renewal terms");
+        assert_eq!(req.collections, vec!["contracts", "legal"]);
+    }
+
+    #[test]
+    fn build_retrieve_request_parses_result_fields_from_query_and_json() {
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "tenant-a".into());
+        params.insert("query".into(), "company x".into());
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.result_fields, ResultFields::Full);
+        params.insert("fields".into(), "ids_only".into());
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.result_fields, ResultFields::IdsOnly);
+
+        let body = r#"{"tenant_id": "tenant-a", "query": "company x", "fields": "no_citations"}"#;
+        let req = build_retrieve_request_from_json(body).unwrap();
+        assert_eq!(req.result_fields, ResultFields::NoCitations);
+        let body = r#"{"tenant_id": "tenant-a", "query": "company x", "fields": "everything"}"#;
+        let err = build_retrieve_request_from_json(body).unwrap_err();
+        assert!(err.contains("fields must be full, no_citations, or ids_only"));
+    }
+
+    #[test]
+    fn build_retrieve_request_parses_as_of_from_query_and_json() {
+        let mut params = HashMap::new();
+        params.insert("tenant_id".into(), "tenant-a".into());
+        params.insert("query".into(), "company x".into());
+        params.insert("as_of_unix".into(), "1700000000".into());
+        let req = build_retrieve_request_from_query(&params).unwrap();
+        assert_eq!(req.as_of_unix, Some(1_700_000_000));
+
+        let body = r#"{"tenant_id": "tenant-a", "query": "company x", "as_of_unix": "soon"}"#;
+        let err = build_retrieve_request_from_json(body).unwrap_err();
+        assert!(err.contains("as_of_unix must be an i64 timestamp"));
+    }
+
     #[test]
     fn build_retrieve_request_from_query_rejects_invalid_time_range() {
         let mut params = HashMap::new();
@@ -2346,6 +2710,143 @@ mod tests {
         assert!(response.body.contains("\"evidence_id\":\"e1\""));
     }
 
+    #[test]
+    fn query_limiter_hands_freed_slots_to_waiting_tenants_in_turn() {
+        let limiter = QueryConcurrencyLimiter::new(1, Duration::from_secs(5));
+        let order = Mutex::new(Vec::new());
+        let held = limiter
+            .acquire("noisy", None)
+            .expect("first query should not wait");
+        std::thread::scope(|scope| {
+            for (queued, tenant_id) in ["noisy", "noisy", "noisy", "quiet"].into_iter().enumerate()
+            {
+                let limiter = &limiter;
+                let order = &order;
+                scope.spawn(move || {
+                    let permit = limiter
+                        .acquire(tenant_id, None)
+                        .expect("query should get a slot");
+                    assert!(permit.queue_wait() > Duration::ZERO);
+                    order.lock().expect("order lock").push(tenant_id);
+                });
+                while limiter.snapshot().queued <= queued {
+                    std::thread::yield_now();
+                }
+            }
+            assert_eq!(limiter.snapshot().in_flight, 1);
+            drop(held);
+        });
+        assert_eq!(
+            order.into_inner().expect("order lock"),
+            vec!["noisy", "quiet", "noisy", "noisy"]
+        );
+        let snapshot = limiter.snapshot();
+        assert_eq!((snapshot.in_flight, snapshot.queued), (0, 0));
+    }
+
+    #[test]
+    fn execute_retrieve_rejects_queries_that_time_out_in_the_limiter_queue() {
+        let store = sample_store();
+        let metrics = Arc::new(Mutex::new(TransportMetrics::default()));
+        let limiter = QueryConcurrencyLimiter::new(1, Duration::from_millis(20));
+        let request = build_retrieve_request_from_query(&HashMap::from([
+            ("tenant_id".to_string(), "tenant-a".to_string()),
+            ("query".to_string(), "company x".to_string()),
+        ]))
+        .expect("request should parse");
+
+        let held = limiter
+            .acquire("tenant-b", None)
+            .expect("first query should not wait");
+        let response = execute_retrieve_and_observe(
+            &store,
+            request.clone(),
+            ReadConsistencyPolicy::One,
+            &metrics,
+            None,
+            Some(&limiter),
+        );
+        assert_eq!(response.status, 503);
+        assert!(response.body.contains("concurrency limit"));
+        assert_eq!(limiter.snapshot().timeout_total, 1);
+        assert_eq!(limiter.snapshot().queued, 0);
+
+        drop(held);
+        let response = execute_retrieve_and_observe(
+            &store,
+            request,
+            ReadConsistencyPolicy::One,
+            &metrics,
+            None,
+            Some(&limiter),
+        );
+        assert_eq!(response.status, 200);
+        let rendered = metrics
+            .lock()
+            .expect("metrics lock")
+            .render_prometheus(None);
+        assert!(rendered.contains("dash_retrieve_query_queue_timeout_total 1\n"));
+        assert!(rendered.contains("dash_retrieve_query_queue_wait_ms_p95 "));
+    }
+
+    #[test]
+    fn request_timeout_headers_map_to_a_retrieval_deadline() {
+        let headers =
+            |name: &str, value: &str| HashMap::from([(name.to_string(), value.to_string())]);
+        assert_eq!(
+            http::request_timeout(&headers("grpc-timeout", "250m")),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            http::request_timeout(&headers("grpc-timeout", "2S")),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(http::request_timeout(&headers("grpc-timeout", "5x")), None);
+        assert_eq!(http::request_timeout(&headers("grpc-timeout", "0m")), None);
+        assert_eq!(
+            http::request_timeout(&headers("x-request-timeout-ms", " 40 ")),
+            Some(Duration::from_millis(40))
+        );
+        assert_eq!(
+            http::request_timeout(&headers("x-envoy-expected-rq-timeout-ms", "15000")),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(http::request_timeout(&HashMap::new()), None);
+
+        let store = sample_store();
+        let metrics = Arc::new(Mutex::new(TransportMetrics::default()));
+        let mut request = build_retrieve_request_from_query(&HashMap::from([
+            ("tenant_id".to_string(), "tenant-a".to_string()),
+            ("query".to_string(), "company x".to_string()),
+        ]))
+        .expect("request should parse");
+        request.deadline = Some(Instant::now());
+        let response = execute_retrieve_and_observe(
+            &store,
+            request,
+            ReadConsistencyPolicy::One,
+            &metrics,
+            None,
+            None,
+        );
+        assert_eq!(response.status, 504);
+
+        let request = HttpRequest {
+            method: "GET".to_string(),
+            target: "/v1/retrieve?tenant_id=tenant-a&query=company+x&top_k=1".to_string(),
+            headers: headers("x-request-timeout-ms", "60000"),
+            body: Vec::new(),
+        };
+        let response = handle_request_with_metrics(&store, &request, &metrics);
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("\"truncated\":false"));
+        let rendered = metrics
+            .lock()
+            .expect("metrics lock")
+            .render_prometheus(None);
+        assert!(rendered.contains("dash_retrieve_deadline_exceeded_total 1\n"));
+    }
+
     #[test]
     fn handle_request_rejects_when_local_node_is_not_selected_replica() {
         let store = sample_store();
@@ -2778,7 +3279,7 @@ tenant-a,0,12,node-a,follower,healthy\n",
     fn debug_planner_endpoint_returns_stage_counts() {
         let mut store = sample_store();
         store
-            .upsert_claim_vector("c1", vec![0.8, 0.2, 0.1, 0.9])
+            .upsert_claim_vector(&"c1".into(), vec![0.8, 0.2, 0.1, 0.9])
             .expect("vector upsert should succeed");
         let request = HttpRequest {
             method: "GET".to_string(),
@@ -2864,6 +3365,10 @@ tenant-a,0,12,node-a,follower,healthy\n",
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
                     evidence_id: "e2".into(),
@@ -2877,6 +3382,9 @@ tenant-a,0,12,node-a,follower,healthy\n",
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3,9 +3,13 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use auth::{JwtValidationConfig, JwtValidationError, verify_hs256_token_for_tenant};
+use auth::{
+    JwtValidationConfig, JwtValidationError, verify_hs256_token_for_tenant,
+    verify_hs256_token_scopes_for_tenant,
+};
+use metadata_router::TenantAliases;
 
-use super::{HttpRequest, env_with_fallback};
+use super::{HttpRequest, env_with_fallback, tenant_aliases_from_env};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(super) enum AuthDecision {
@@ -22,6 +26,7 @@ pub(super) struct AuthPolicy {
     jwt_validation: Option<JwtValidationConfig>,
     rate_limiter: Option<TenantRateLimiter>,
     revocation_list: Option<RevocationList>,
+    tenant_aliases: TenantAliases,
 }
 
 impl AuthPolicy {
@@ -72,6 +77,7 @@ impl AuthPolicy {
                 "DASH_RETRIEVAL_REVOKED_KEYS_PATH",
                 "EME_RETRIEVAL_REVOKED_KEYS_PATH",
             )),
+            tenant_aliases: tenant_aliases_from_env(),
         }
     }
 }
@@ -89,6 +95,10 @@ impl TenantScope {
             Self::Set(tenants) => tenants.contains(tenant_id),
         }
     }
+
+    fn allows_any(&self, tenant_ids: &[&str]) -> bool {
+        tenant_ids.iter().any(|tenant_id| self.allows(tenant_id))
+    }
 }
 
 pub(super) fn authorize_request_for_tenant(
@@ -96,13 +106,24 @@ pub(super) fn authorize_request_for_tenant(
     tenant_id: &str,
     policy: &AuthPolicy,
 ) -> AuthDecision {
+    // Grants made to a tenant's old ids carry over to the id they now
+    // resolve to.
+    let mut tenant_ids = vec![tenant_id];
+    tenant_ids.extend(policy.tenant_aliases.aliases_of(tenant_id));
+
     if let Some(jwt_config) = policy.jwt_validation.as_ref()
         && let Some(token) = presented_bearer_token(request)
         && bearer_looks_like_jwt(token)
     {
-        return match verify_hs256_token_for_tenant(token, tenant_id, jwt_config, unix_now_secs()) {
+        let now = unix_now_secs();
+        let verified = tenant_ids
+            .iter()
+            .map(|tenant_id| verify_hs256_token_for_tenant(token, tenant_id, jwt_config, now))
+            .find(Result::is_ok)
+            .unwrap_or_else(|| verify_hs256_token_for_tenant(token, tenant_id, jwt_config, now));
+        return match verified {
             Ok(()) => {
-                if !policy.allowed_tenants.allows(tenant_id) {
+                if !policy.allowed_tenants.allows_any(&tenant_ids) {
                     AuthDecision::Forbidden("tenant is not allowed by service policy")
                 } else {
                     AuthDecision::Allowed
@@ -135,7 +156,7 @@ pub(super) fn authorize_request_for_tenant(
             return AuthDecision::Unauthorized("missing or invalid API key");
         };
         if let Some(scope) = policy.scoped_api_keys.get(api_key) {
-            if !scope.allows(tenant_id) {
+            if !scope.allows_any(&tenant_ids) {
                 return AuthDecision::Forbidden("tenant is not allowed for this API key");
             }
         } else if policy.required_api_keys.is_empty() || !policy.required_api_keys.contains(api_key)
@@ -148,7 +169,7 @@ pub(super) fn authorize_request_for_tenant(
         return AuthDecision::Unauthorized("missing or invalid API key");
     }
 
-    if !policy.allowed_tenants.allows(tenant_id) {
+    if !policy.allowed_tenants.allows_any(&tenant_ids) {
         return AuthDecision::Forbidden("tenant is not allowed by service policy");
     }
     if let Some(ref limiter) = policy.rate_limiter
@@ -163,6 +184,30 @@ pub(super) fn authorize_request_for_tenant(
     AuthDecision::Allowed
 }
 
+/// Visibility labels of a caller already authorized for `tenant_id`: the
+/// scopes of its JWT. Callers without one, such as API key holders, hold
+/// no labels and only see unlabeled claims.
+pub(super) fn visibility_labels_for_request(
+    request: &HttpRequest,
+    tenant_id: &str,
+    policy: &AuthPolicy,
+) -> Vec<String> {
+    let Some(jwt_config) = policy.jwt_validation.as_ref() else {
+        return Vec::new();
+    };
+    let Some(token) = presented_bearer_token(request).filter(|token| bearer_looks_like_jwt(token))
+    else {
+        return Vec::new();
+    };
+    let now = unix_now_secs();
+    std::iter::once(tenant_id)
+        .chain(policy.tenant_aliases.aliases_of(tenant_id))
+        .find_map(|tenant_id| {
+            verify_hs256_token_scopes_for_tenant(token, tenant_id, jwt_config, now).ok()
+        })
+        .unwrap_or_default()
+}
+
 fn presented_api_key(request: &HttpRequest) -> Option<&str> {
     if let Some(value) = request.headers.get("x-api-key") {
         return Some(value.as_str());
@@ -0,0 +1,198 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Condvar, Mutex, MutexGuard, OnceLock},
+    time::{Duration, Instant},
+};
+
+use super::{parse_env_first_u64, parse_env_first_usize};
+
+const DEFAULT_QUERY_QUEUE_TIMEOUT_MS: u64 = 1_000;
+
+/// Bounds how many retrievals execute at once. Queries past the bound
+/// wait in a queue per tenant, and freed slots go to the waiting tenants
+/// in turn, so a tenant with a burst of expensive queries waits behind
+/// its own queries rather than everyone else's.
+pub(super) struct QueryConcurrencyLimiter {
+    max_in_flight: usize,
+    queue_timeout: Duration,
+    state: Mutex<LimiterState>,
+    granted_cv: Condvar,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    in_flight: usize,
+    next_ticket: u64,
+    /// Tenants with waiting queries, in the order they get the next slot.
+    rotation: VecDeque<String>,
+    waiting: HashMap<String, VecDeque<u64>>,
+    granted: HashSet<u64>,
+    timeout_total: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) struct QueryLimiterSnapshot {
+    pub(super) max_in_flight: usize,
+    pub(super) in_flight: usize,
+    pub(super) queued: usize,
+    pub(super) timeout_total: u64,
+}
+
+/// A slot held for one query; released on drop.
+pub(super) struct QueryPermit<'a> {
+    limiter: &'a QueryConcurrencyLimiter,
+    queue_wait: Duration,
+}
+
+impl QueryPermit<'_> {
+    pub(super) fn queue_wait(&self) -> Duration {
+        self.queue_wait
+    }
+}
+
+impl Drop for QueryPermit<'_> {
+    fn drop(&mut self) {
+        let mut state = self.limiter.lock_state();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        self.limiter.grant_waiting(&mut state);
+    }
+}
+
+impl QueryConcurrencyLimiter {
+    pub(super) fn new(max_in_flight: usize, queue_timeout: Duration) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            queue_timeout,
+            state: Mutex::new(LimiterState::default()),
+            granted_cv: Condvar::new(),
+        }
+    }
+
+    fn from_env() -> Option<Self> {
+        let max_in_flight = parse_env_first_usize(&[
+            "DASH_RETRIEVAL_MAX_CONCURRENT_QUERIES",
+            "EME_RETRIEVAL_MAX_CONCURRENT_QUERIES",
+        ])
+        .filter(|value| *value > 0)?;
+        let queue_timeout_ms = parse_env_first_u64(&[
+            "DASH_RETRIEVAL_QUERY_QUEUE_TIMEOUT_MS",
+            "EME_RETRIEVAL_QUERY_QUEUE_TIMEOUT_MS",
+        ])
+        .unwrap_or(DEFAULT_QUERY_QUEUE_TIMEOUT_MS);
+        Some(Self::new(
+            max_in_flight,
+            Duration::from_millis(queue_timeout_ms),
+        ))
+    }
+
+    /// Wait for a slot for a query of `tenant_id`, at most the queue
+    /// timeout and never past the request's `deadline`. Returns how long
+    /// the query waited when no slot was granted in time.
+    pub(super) fn acquire(
+        &self,
+        tenant_id: &str,
+        deadline: Option<Instant>,
+    ) -> Result<QueryPermit<'_>, Duration> {
+        let started_at = Instant::now();
+        let mut state = self.lock_state();
+        if state.in_flight < self.max_in_flight && state.rotation.is_empty() {
+            state.in_flight += 1;
+            return Ok(self.permit(started_at));
+        }
+
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        let queue = state.waiting.entry(tenant_id.to_string()).or_default();
+        queue.push_back(ticket);
+        if queue.len() == 1 {
+            state.rotation.push_back(tenant_id.to_string());
+        }
+
+        let queue_deadline = started_at + self.queue_timeout;
+        let deadline = deadline.map_or(queue_deadline, |deadline| deadline.min(queue_deadline));
+        loop {
+            if state.granted.remove(&ticket) {
+                return Ok(self.permit(started_at));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            state = match self.granted_cv.wait_timeout(state, deadline - now) {
+                Ok((guard, _)) => guard,
+                Err(poisoned) => poisoned.into_inner().0,
+            };
+        }
+
+        if let Some(queue) = state.waiting.get_mut(tenant_id) {
+            queue.retain(|waiting| *waiting != ticket);
+            if queue.is_empty() {
+                state.waiting.remove(tenant_id);
+                state.rotation.retain(|tenant| tenant != tenant_id);
+            }
+        }
+        state.timeout_total += 1;
+        Err(started_at.elapsed())
+    }
+
+    pub(super) fn snapshot(&self) -> QueryLimiterSnapshot {
+        let state = self.lock_state();
+        QueryLimiterSnapshot {
+            max_in_flight: self.max_in_flight,
+            in_flight: state.in_flight,
+            queued: state.waiting.values().map(VecDeque::len).sum(),
+            timeout_total: state.timeout_total,
+        }
+    }
+
+    fn permit(&self, started_at: Instant) -> QueryPermit<'_> {
+        QueryPermit {
+            limiter: self,
+            queue_wait: started_at.elapsed(),
+        }
+    }
+
+    /// Hand free slots to waiting queries, one tenant at a time.
+    fn grant_waiting(&self, state: &mut LimiterState) {
+        let mut granted_any = false;
+        while state.in_flight < self.max_in_flight {
+            let Some(tenant_id) = state.rotation.pop_front() else {
+                break;
+            };
+            let Some(queue) = state.waiting.get_mut(&tenant_id) else {
+                continue;
+            };
+            let Some(ticket) = queue.pop_front() else {
+                state.waiting.remove(&tenant_id);
+                continue;
+            };
+            if queue.is_empty() {
+                state.waiting.remove(&tenant_id);
+            } else {
+                state.rotation.push_back(tenant_id);
+            }
+            state.granted.insert(ticket);
+            state.in_flight += 1;
+            granted_any = true;
+        }
+        if granted_any {
+            self.granted_cv.notify_all();
+        }
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, LimiterState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// The process-wide limiter, configured from
+/// `DASH_RETRIEVAL_MAX_CONCURRENT_QUERIES`; `None` when unset.
+pub(super) fn query_limiter() -> Option<&'static QueryConcurrencyLimiter> {
+    static LIMITER: OnceLock<Option<QueryConcurrencyLimiter>> = OnceLock::new();
+    LIMITER
+        .get_or_init(QueryConcurrencyLimiter::from_env)
+        .as_ref()
+}
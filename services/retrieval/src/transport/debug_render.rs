@@ -17,7 +17,7 @@ use crate::api::{
 
 pub(super) fn render_planner_debug_json(snapshot: &RetrievePlannerDebugSnapshot) -> String {
     format!(
-        "{{\"tenant_id\":\"{}\",\"top_k\":{},\"stance_mode\":\"{}\",\"has_query_embedding\":{},\"entity_filter_count\":{},\"embedding_filter_count\":{},\"has_filtering\":{},\"metadata_prefilter_count\":{},\"segment_base_count\":{},\"wal_delta_count\":{},\"storage_visible_count\":{},\"allowed_claim_ids_active\":{},\"allowed_claim_ids_count\":{},\"short_circuit_empty\":{},\"ann_candidate_count\":{},\"planner_candidate_count\":{}}}",
+        "{{\"tenant_id\":\"{}\",\"top_k\":{},\"stance_mode\":\"{}\",\"has_query_embedding\":{},\"entity_filter_count\":{},\"embedding_filter_count\":{},\"has_filtering\":{},\"metadata_prefilter_count\":{},\"segment_base_count\":{},\"segment_cold_count\":{},\"wal_delta_count\":{},\"storage_visible_count\":{},\"allowed_claim_ids_active\":{},\"allowed_claim_ids_count\":{},\"short_circuit_empty\":{},\"ann_candidate_count\":{},\"planner_candidate_count\":{},\"open_stage_breakers\":[{}]}}",
         json_escape(&snapshot.tenant_id),
         snapshot.top_k,
         snapshot.stance_mode,
@@ -27,6 +27,7 @@ pub(super) fn render_planner_debug_json(snapshot: &RetrievePlannerDebugSnapshot)
         snapshot.has_filtering,
         snapshot.metadata_prefilter_count,
         snapshot.segment_base_count,
+        snapshot.segment_cold_count,
         snapshot.wal_delta_count,
         snapshot.storage_visible_count,
         snapshot.allowed_claim_ids_active,
@@ -34,6 +35,12 @@ pub(super) fn render_planner_debug_json(snapshot: &RetrievePlannerDebugSnapshot)
         snapshot.short_circuit_empty,
         snapshot.ann_candidate_count,
         snapshot.planner_candidate_count,
+        snapshot
+            .open_stage_breakers
+            .iter()
+            .map(|stage| format!("\"{}\"", json_escape(stage)))
+            .collect::<Vec<_>>()
+            .join(","),
     )
 }
 
@@ -264,7 +271,7 @@ fn build_read_route_probe_json(
             "{{\"status\":\"rejected\",\"tenant_id\":\"{}\",\"entity_key\":\"{}\",\"reason\":\"{}\"}}",
             json_escape(tenant_id),
             json_escape(entity_key),
-            json_escape(&format!("placement route error: {err:?}"))
+            json_escape(&format!("placement route error: {err}"))
         ),
     }
 }
@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     io::{BufRead, BufReader, Read, Write},
     net::TcpStream,
+    time::{Duration, Instant},
 };
 
 use super::{HttpRequest, HttpResponse, MAX_HTTP_BODY_BYTES};
@@ -122,6 +123,7 @@ pub(super) fn render_response_text(response: &HttpResponse) -> String {
         404 => "404 Not Found",
         405 => "405 Method Not Allowed",
         503 => "503 Service Unavailable",
+        504 => "504 Gateway Timeout",
         _ => "500 Internal Server Error",
     };
     let body_len = response.body.len();
@@ -131,6 +133,50 @@ pub(super) fn render_response_text(response: &HttpResponse) -> String {
     )
 }
 
+/// How long the client is prepared to wait, from the first timeout header
+/// present: `grpc-timeout` as set by gRPC clients and gateways (`<value>`
+/// plus one of `H`, `M`, `S`, `m`, `u`, `n`), `x-request-timeout-ms`, or
+/// the Envoy `x-envoy-expected-rq-timeout-ms`. Malformed values are
+/// ignored rather than rejected.
+pub(super) fn request_timeout(headers: &HashMap<String, String>) -> Option<Duration> {
+    if let Some(raw) = headers.get("grpc-timeout") {
+        return parse_grpc_timeout(raw.trim());
+    }
+    ["x-request-timeout-ms", "x-envoy-expected-rq-timeout-ms"]
+        .iter()
+        .find_map(|name| headers.get(*name))
+        .and_then(|raw| raw.trim().parse::<u64>().ok())
+        .filter(|millis| *millis > 0)
+        .map(Duration::from_millis)
+}
+
+/// The deadline for a request received at `received_at`; see
+/// [`request_timeout`].
+pub(super) fn request_deadline(
+    headers: &HashMap<String, String>,
+    received_at: Instant,
+) -> Option<Instant> {
+    request_timeout(headers).and_then(|timeout| received_at.checked_add(timeout))
+}
+
+fn parse_grpc_timeout(raw: &str) -> Option<Duration> {
+    if raw.len() < 2 || raw.len() > 9 {
+        return None;
+    }
+    let (value, unit) = raw.split_at(raw.len() - 1);
+    let value = value.parse::<u64>().ok()?;
+    let timeout = match unit {
+        "H" => Duration::from_secs(value.checked_mul(3_600)?),
+        "M" => Duration::from_secs(value.checked_mul(60)?),
+        "S" => Duration::from_secs(value),
+        "m" => Duration::from_millis(value),
+        "u" => Duration::from_micros(value),
+        "n" => Duration::from_nanos(value),
+        _ => return None,
+    };
+    (!timeout.is_zero()).then_some(timeout)
+}
+
 fn url_decode(raw: &str) -> Result<String, String> {
     let bytes = raw.as_bytes();
     let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
@@ -11,12 +11,15 @@ pub(super) fn build_retrieve_transport_request_from_query(
     if tenant_id.is_empty() {
         return Err("tenant_id cannot be empty".to_string());
     }
-
-    let request_query = query
-        .get("query")
-        .ok_or_else(|| "query is required".to_string())?
-        .trim()
-        .to_string();
+    let tenant_id = canonical_tenant_id(tenant_id);
+
+    let dsl = query.get("q").map(|raw| parse_query_dsl(raw)).transpose()?;
+    let request_query = match (&dsl, query.get("query")) {
+        (Some(_), Some(_)) => return Err("use either query or q, not both".to_string()),
+        (Some(parsed), None) => parsed.text.clone(),
+        (None, Some(raw)) => raw.trim().to_string(),
+        (None, None) => return Err("query is required".to_string()),
+    };
     if request_query.is_empty() {
         return Err("query cannot be empty".to_string());
     }
@@ -35,6 +38,39 @@ pub(super) fn build_retrieve_transport_request_from_query(
         .map(|value| parse_csv_string_list(value, "embedding_id_filters"))
         .transpose()?
         .unwrap_or_default();
+    let metadata_filters = query
+        .get("metadata_filters")
+        .map(|value| parse_metadata_filters_csv(value, "metadata_filters"))
+        .transpose()?
+        .unwrap_or_default();
+    let min_confidence = query
+        .get("min_confidence")
+        .map(|value| parse_confidence(value, "min_confidence"))
+        .transpose()?;
+    let max_confidence = query
+        .get("max_confidence")
+        .map(|value| parse_confidence(value, "max_confidence"))
+        .transpose()?;
+    let confidence_range = confidence_range(min_confidence, max_confidence)?;
+    let claim_types = query
+        .get("claim_types")
+        .map(|value| parse_claim_types_csv(value, "claim_types"))
+        .transpose()?
+        .unwrap_or_default();
+    let collections = query
+        .get("collections")
+        .map(|value| parse_csv_string_list(value, "collections"))
+        .transpose()?
+        .unwrap_or_default();
+    let as_of_unix = query
+        .get("as_of_unix")
+        .map(|value| parse_i64(value, "as_of_unix"))
+        .transpose()?;
+    let result_fields = query
+        .get("fields")
+        .map(|value| parse_result_fields(value))
+        .transpose()?
+        .unwrap_or_default();
 
     let top_k = match query.get("top_k") {
         Some(value) => parse_positive_usize(value, "top_k")?,
@@ -52,8 +88,17 @@ pub(super) fn build_retrieve_transport_request_from_query(
         Some("false") | None => false,
         Some(_) => return Err("return_graph must be true or false".to_string()),
     };
+    let include_cold = match query.get("include_cold").map(|s| s.as_str()) {
+        Some("true") => true,
+        Some("false") | None => false,
+        Some(_) => return Err("include_cold must be true or false".to_string()),
+    };
     let read_consistency =
         ReadConsistencyPolicy::from_raw(query.get("read_consistency").map(String::as_str))?;
+    let ann_expansion_budget = query
+        .get("ann_expansion_budget")
+        .map(|value| parse_positive_usize(value, "ann_expansion_budget"))
+        .transpose()?;
 
     let from_unix = query
         .get("from_unix")
@@ -77,18 +122,33 @@ pub(super) fn build_retrieve_transport_request_from_query(
         return Err("time range is invalid: from_unix must be <= to_unix".to_string());
     }
 
+    let mut request = RetrieveApiRequest {
+        tenant_id,
+        query: request_query,
+        query_embedding,
+        entity_filters,
+        embedding_id_filters,
+        top_k,
+        stance_mode,
+        return_graph,
+        time_range,
+        ann_expansion_budget,
+        metadata_filters,
+        confidence_range,
+        claim_types,
+        collections,
+        as_of_unix,
+        result_fields,
+        include_cold,
+        deadline: None,
+        visibility_labels: None,
+    };
+    if let Some(parsed) = dsl {
+        merge_query_dsl_filters(&mut request, parsed.options)?;
+    }
+
     Ok(RetrieveTransportRequest {
-        request: RetrieveApiRequest {
-            tenant_id,
-            query: request_query,
-            query_embedding,
-            entity_filters,
-            embedding_id_filters,
-            top_k,
-            stance_mode,
-            return_graph,
-            time_range,
-        },
+        request,
         read_consistency,
     })
 }
@@ -112,8 +172,20 @@ pub(super) fn build_retrieve_transport_request_from_json(
     if tenant_id.trim().is_empty() {
         return Err("tenant_id cannot be empty".to_string());
     }
+    let tenant_id = canonical_tenant_id(tenant_id);
 
-    let query = require_string(&object, "query")?;
+    let dsl = match object.get("q") {
+        Some(JsonValue::String(raw)) => Some(parse_query_dsl(raw)?),
+        Some(JsonValue::Null) | None => None,
+        Some(_) => return Err("q must be a string".to_string()),
+    };
+    let query = match &dsl {
+        Some(_) if object.contains_key("query") => {
+            return Err("use either query or q, not both".to_string());
+        }
+        Some(parsed) => parsed.text.clone(),
+        None => require_string(&object, "query")?,
+    };
     if query.trim().is_empty() {
         return Err("query cannot be empty".to_string());
     }
@@ -126,6 +198,28 @@ pub(super) fn build_retrieve_transport_request_from_json(
     let embedding_id_filters =
         parse_optional_string_array(object.get("embedding_id_filters"), "embedding_id_filters")?
             .unwrap_or_default();
+    let metadata_filters =
+        parse_optional_metadata_filters(object.get("metadata_filters"), "metadata_filters")?;
+    let min_confidence = parse_optional_confidence(object.get("min_confidence"), "min_confidence")?;
+    let max_confidence = parse_optional_confidence(object.get("max_confidence"), "max_confidence")?;
+    let confidence_range = confidence_range(min_confidence, max_confidence)?;
+    let claim_types = parse_optional_string_array(object.get("claim_types"), "claim_types")?
+        .unwrap_or_default()
+        .iter()
+        .map(|value| parse_claim_type(value, "claim_types"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let collections =
+        parse_optional_string_array(object.get("collections"), "collections")?.unwrap_or_default();
+    let as_of_unix = match object.get("as_of_unix") {
+        Some(JsonValue::Number(raw)) => Some(parse_i64(raw, "as_of_unix")?),
+        Some(JsonValue::Null) | None => None,
+        Some(_) => return Err("as_of_unix must be an i64 timestamp".to_string()),
+    };
+    let result_fields = match object.get("fields") {
+        Some(JsonValue::String(raw)) => parse_result_fields(raw)?,
+        Some(JsonValue::Null) | None => ResultFields::Full,
+        Some(_) => return Err("fields must be a string".to_string()),
+    };
 
     let top_k = match object.get("top_k") {
         Some(JsonValue::Number(raw)) => parse_positive_usize(raw, "top_k")?,
@@ -144,6 +238,16 @@ pub(super) fn build_retrieve_transport_request_from_json(
         Some(_) => return Err("return_graph must be a boolean".to_string()),
         None => false,
     };
+    let include_cold = match object.get("include_cold") {
+        Some(JsonValue::Bool(flag)) => *flag,
+        Some(JsonValue::Null) | None => false,
+        Some(_) => return Err("include_cold must be a boolean".to_string()),
+    };
+    let ann_expansion_budget = match object.get("ann_expansion_budget") {
+        Some(JsonValue::Number(raw)) => Some(parse_positive_usize(raw, "ann_expansion_budget")?),
+        Some(JsonValue::Null) | None => None,
+        Some(_) => return Err("ann_expansion_budget must be a positive integer".to_string()),
+    };
     let read_consistency = match object.get("read_consistency") {
         Some(JsonValue::String(value)) => ReadConsistencyPolicy::from_raw(Some(value))?,
         Some(JsonValue::Null) | None => ReadConsistencyPolicy::One,
@@ -185,18 +289,33 @@ pub(super) fn build_retrieve_transport_request_from_json(
         return Err("time range is invalid: from_unix must be <= to_unix".to_string());
     }
 
+    let mut request = RetrieveApiRequest {
+        tenant_id,
+        query,
+        query_embedding,
+        entity_filters,
+        embedding_id_filters,
+        top_k,
+        stance_mode,
+        return_graph,
+        time_range,
+        ann_expansion_budget,
+        metadata_filters,
+        confidence_range,
+        claim_types,
+        collections,
+        as_of_unix,
+        result_fields,
+        include_cold,
+        deadline: None,
+        visibility_labels: None,
+    };
+    if let Some(parsed) = dsl {
+        merge_query_dsl_filters(&mut request, parsed.options)?;
+    }
+
     Ok(RetrieveTransportRequest {
-        request: RetrieveApiRequest {
-            tenant_id,
-            query,
-            query_embedding,
-            entity_filters,
-            embedding_id_filters,
-            top_k,
-            stance_mode,
-            return_graph,
-            time_range,
-        },
+        request,
         read_consistency,
     })
 }
@@ -206,6 +325,48 @@ pub(super) fn build_retrieve_request_from_json(body: &str) -> Result<RetrieveApi
     build_retrieve_transport_request_from_json(body).map(|value| value.request)
 }
 
+/// Parse a `q` parameter written in the store's query language.
+fn parse_query_dsl(raw: &str) -> Result<ParsedQuery, String> {
+    parse_query(raw).map_err(|err| match err {
+        StoreError::Parse(message) => message,
+        other => format!("invalid q: {other:?}"),
+    })
+}
+
+/// Add the filters of a `q` parameter to those given as separate fields.
+/// List filters are extended; ranges may only come from one place.
+fn merge_query_dsl_filters(
+    request: &mut RetrieveApiRequest,
+    options: RetrievalOptions<'_>,
+) -> Result<(), String> {
+    request.entity_filters.extend(options.entities);
+    for claim_type in options.claim_types {
+        if !request.claim_types.contains(&claim_type) {
+            request.claim_types.push(claim_type);
+        }
+    }
+    for collection in options.collections {
+        if !request.collections.contains(&collection) {
+            request.collections.push(collection);
+        }
+    }
+    request.metadata_filters.extend(options.metadata);
+    if !options.confidence.is_unbounded() {
+        if request.confidence_range.is_some() {
+            return Err("confidence bounds must be given either in q or as fields".to_string());
+        }
+        request.confidence_range = Some(options.confidence);
+    }
+    let (from_unix, to_unix) = options.time_range;
+    if from_unix.is_some() || to_unix.is_some() {
+        if request.time_range.is_some() {
+            return Err("time bounds must be given either in q or as fields".to_string());
+        }
+        request.time_range = Some(TimeRange { from_unix, to_unix });
+    }
+    Ok(())
+}
+
 fn parse_stance_mode(raw: &str) -> Result<StanceMode, String> {
     match raw {
         "balanced" => Ok(StanceMode::Balanced),
@@ -265,6 +426,62 @@ fn parse_query_embedding_csv(raw: &str, field_name: &str) -> Result<Vec<f32>, St
     Ok(out)
 }
 
+fn parse_confidence(raw: &str, field_name: &str) -> Result<f32, String> {
+    match raw.trim().parse::<f32>() {
+        Ok(value) if (0.0..=1.0).contains(&value) => Ok(value),
+        _ => Err(format!("{field_name} must be a number between 0 and 1")),
+    }
+}
+
+fn parse_optional_confidence(
+    value: Option<&JsonValue>,
+    field_name: &str,
+) -> Result<Option<f32>, String> {
+    match value {
+        Some(JsonValue::Number(raw)) => parse_confidence(raw, field_name).map(Some),
+        Some(JsonValue::Null) | None => Ok(None),
+        Some(_) => Err(format!("{field_name} must be a number between 0 and 1")),
+    }
+}
+
+fn confidence_range(
+    min_confidence: Option<f32>,
+    max_confidence: Option<f32>,
+) -> Result<Option<ConfidenceRange>, String> {
+    if let (Some(min), Some(max)) = (min_confidence, max_confidence)
+        && min > max
+    {
+        return Err("min_confidence must be <= max_confidence".to_string());
+    }
+    let range = ConfidenceRange::new(min_confidence, max_confidence);
+    Ok((!range.is_unbounded()).then_some(range))
+}
+
+fn parse_claim_types_csv(raw: &str, field_name: &str) -> Result<Vec<ClaimType>, String> {
+    parse_csv_string_list(raw, field_name)?
+        .iter()
+        .map(|value| parse_claim_type(value, field_name))
+        .collect()
+}
+
+fn parse_claim_type(raw: &str, field_name: &str) -> Result<ClaimType, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "factual" => Ok(ClaimType::Factual),
+        "opinion" => Ok(ClaimType::Opinion),
+        "prediction" => Ok(ClaimType::Prediction),
+        "temporal" => Ok(ClaimType::Temporal),
+        "causal" => Ok(ClaimType::Causal),
+        _ => Err(format!(
+            "{field_name} values must be factual, opinion, prediction, temporal, or causal"
+        )),
+    }
+}
+
+fn parse_result_fields(raw: &str) -> Result<ResultFields, String> {
+    ResultFields::parse(raw)
+        .ok_or_else(|| "fields must be full, no_citations, or ids_only".to_string())
+}
+
 fn parse_csv_string_list(raw: &str, field_name: &str) -> Result<Vec<String>, String> {
     let out: Vec<String> = raw
         .split(',')
@@ -278,6 +495,72 @@ fn parse_csv_string_list(raw: &str, field_name: &str) -> Result<Vec<String>, Str
     Ok(out)
 }
 
+/// `key:value` pairs separated by commas, with `|` between alternative
+/// values: `project:apollo,label:urgent|blocked`.
+fn parse_metadata_filters_csv(raw: &str, field_name: &str) -> Result<Vec<MetadataFilter>, String> {
+    let mut out = Vec::new();
+    for part in parse_csv_string_list(raw, field_name)? {
+        let Some((key, values)) = part.split_once(':') else {
+            return Err(format!("{field_name} entries must be key:value"));
+        };
+        let values: Vec<String> = values
+            .split('|')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+            .collect();
+        out.push(metadata_filter(key, values, field_name)?);
+    }
+    Ok(out)
+}
+
+/// An object from key to a value or an array of alternative values.
+fn parse_optional_metadata_filters(
+    value: Option<&JsonValue>,
+    field_name: &str,
+) -> Result<Vec<MetadataFilter>, String> {
+    let object = match value {
+        None | Some(JsonValue::Null) => return Ok(Vec::new()),
+        Some(JsonValue::Object(object)) => object,
+        Some(_) => return Err(format!("{field_name} must be an object or null")),
+    };
+    let mut keys: Vec<&String> = object.keys().collect();
+    keys.sort_unstable();
+    let mut out = Vec::with_capacity(keys.len());
+    for key in keys {
+        let values = match &object[key] {
+            JsonValue::String(value) => vec![value.trim().to_string()],
+            JsonValue::Array(_) => {
+                parse_optional_string_array(Some(&object[key]), field_name)?.unwrap_or_default()
+            }
+            _ => {
+                return Err(format!(
+                    "{field_name} values must be strings or arrays of strings"
+                ));
+            }
+        };
+        out.push(metadata_filter(key, values, field_name)?);
+    }
+    Ok(out)
+}
+
+fn metadata_filter(
+    key: &str,
+    mut values: Vec<String>,
+    field_name: &str,
+) -> Result<MetadataFilter, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("{field_name} keys must not be empty"));
+    }
+    values.retain(|value| !value.is_empty());
+    match values.len() {
+        0 => Err(format!("{field_name}.{key} must have a value")),
+        1 => Ok(MetadataFilter::equals(key, values.remove(0))),
+        _ => Ok(MetadataFilter::any_of(key, values)),
+    }
+}
+
 fn parse_optional_f32_array(
     value: Option<&JsonValue>,
     field_name: &str,
@@ -640,6 +923,10 @@ pub(super) fn render_retrieve_response_json(
         out.push_str("null");
     }
 
+    out.push_str(",\"cold_claims_considered\":");
+    out.push_str(&resp.cold_claims_considered.to_string());
+    out.push_str(",\"truncated\":");
+    out.push_str(if resp.truncated { "true" } else { "false" });
     out.push_str(",\"read_policy\":\"");
     out.push_str(&json_escape(read_policy));
     out.push('"');
@@ -667,6 +954,8 @@ fn render_evidence_node_json(out: &mut String, node: &crate::api::EvidenceNode)
     render_optional_string(out, node.dominant_stance.as_deref());
     out.push_str(",\"contradiction_risk\":");
     render_optional_f32(out, node.contradiction_risk);
+    out.push_str(",\"certainty\":");
+    render_optional_string(out, node.certainty.as_deref());
     out.push_str(",\"graph_score\":");
     render_optional_f32(out, node.graph_score);
     out.push_str(",\"support_path_count\":");
@@ -756,6 +1045,10 @@ fn render_citations_json(citations: &[CitationNode]) -> String {
         out.push_str(&json_escape(&citation.stance));
         out.push_str("\",\"source_quality\":");
         out.push_str(&format!("{:.6}", citation.source_quality));
+        out.push_str(",\"negated\":");
+        out.push_str(if citation.negated { "true" } else { "false" });
+        out.push_str(",\"stance_strength\":");
+        render_optional_f32(&mut out, citation.stance_strength);
         out.push_str(",\"chunk_id\":");
         if let Some(chunk_id) = &citation.chunk_id {
             out.push('"');
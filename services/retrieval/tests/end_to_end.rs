@@ -1,5 +1,5 @@
 use retrieval::retrieve_for_rag;
-use schema::{Claim, ClaimEdge, Relation, RetrievalRequest, Stance, StanceMode};
+use schema::{Claim, ClaimEdge, Relation, RetrievalRequest, Stance};
 use store::InMemoryStore;
 
 #[test]
@@ -21,6 +21,10 @@ fn end_to_end_retrieval_returns_citations_and_support_counts() {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![schema::Evidence {
                 evidence_id: "ev1".into(),
@@ -34,6 +38,9 @@ fn end_to_end_retrieval_returns_citations_and_support_counts() {
                 doc_id: Some("doc://press-release".into()),
                 extraction_model: Some("extractor-v5".into()),
                 ingested_at: Some(1_736_035_200_000),
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![ClaimEdge {
                 edge_id: "edge1".into(),
@@ -49,12 +56,7 @@ fn end_to_end_retrieval_returns_citations_and_support_counts() {
 
     let results = retrieve_for_rag(
         &store,
-        RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "Did company x acquire company y in 2025?".into(),
-            top_k: 3,
-            stance_mode: StanceMode::Balanced,
-        },
+        RetrievalRequest::new("tenant-a", "Did company x acquire company y in 2025?", 3),
     );
 
     assert_eq!(results.len(), 1);
@@ -28,6 +28,10 @@ fn sample_store() -> InMemoryStore {
                 valid_to: Some(1_735_862_400),
                 created_at: Some(1_735_603_200_000),
                 updated_at: Some(1_735_689_600_000),
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
                 evidence_id: "ev-http".into(),
@@ -41,6 +45,9 @@ fn sample_store() -> InMemoryStore {
                 doc_id: Some("doc://transport-http".into()),
                 extraction_model: Some("extractor-v5".into()),
                 ingested_at: Some(1_735_689_700_000),
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
@@ -291,6 +298,66 @@ fn transport_denies_cross_tenant_retrieval_for_jwt_claim_scope() {
     assert!(response.contains("tenant is not allowed for this JWT"));
 }
 
+#[test]
+fn transport_filters_labeled_claims_by_jwt_scopes() {
+    let _guard = env_lock().lock().expect("env lock should be available");
+    let _jwt_secret = EnvVarGuard::set("DASH_RETRIEVAL_JWT_HS256_SECRET", OsStr::new("jwt-secret"));
+    let _jwt_issuer = EnvVarGuard::set("DASH_RETRIEVAL_JWT_ISSUER", OsStr::new("dash"));
+    let _jwt_audience = EnvVarGuard::set("DASH_RETRIEVAL_JWT_AUDIENCE", OsStr::new("retrieval"));
+    let mut store = sample_store();
+    store
+        .ingest_bundle(
+            Claim {
+                claim_id: "claim-finance".into(),
+                tenant_id: "tenant-http".into(),
+                canonical_text: "Company X paid for Company Y in cash".into(),
+                confidence: 0.9,
+                event_time_unix: None,
+                entities: vec![],
+                embedding_ids: vec![],
+                claim_type: None,
+                valid_from: None,
+                valid_to: None,
+                created_at: None,
+                updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: vec!["team:finance".into()],
+                language: None,
+                collection: None,
+            },
+            vec![],
+            vec![],
+        )
+        .expect("labeled ingest should succeed");
+    let exp = now_unix_secs() + 300;
+    let retrieve_with_scope = |scope: &str| {
+        let token = encode_hs256_token(
+            &format!(
+                "{{\"tenant_id\":\"tenant-http\",\"iss\":\"dash\",\"aud\":\"retrieval\",\"exp\":{exp},\"scope\":\"{scope}\"}}"
+            ),
+            "jwt-secret",
+        )
+        .expect("token should encode");
+        let request = format!(
+            "GET /v1/retrieve?tenant_id=tenant-http&query=company+x&top_k=5 HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\nConnection: close\r\n\r\n",
+            token
+        );
+        let response = retrieval::transport::handle_http_request_bytes(&store, request.as_bytes())
+            .expect("request should parse and return response");
+        String::from_utf8(response).expect("response should be UTF-8")
+    };
+
+    let finance = retrieve_with_scope("read team:finance");
+    assert!(finance.starts_with("HTTP/1.1 200"));
+    assert!(finance.contains("claim-finance"));
+    assert!(finance.contains("claim-http"));
+
+    let legal = retrieve_with_scope("team:legal");
+    assert!(legal.starts_with("HTTP/1.1 200"));
+    assert!(!legal.contains("claim-finance"));
+    assert!(legal.contains("claim-http"));
+}
+
 #[test]
 fn transport_denies_expired_retrieval_jwt() {
     let _guard = env_lock().lock().expect("env lock should be available");
@@ -33,8 +33,8 @@
 
 use std::time::Duration;
 
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use schema::{Claim, Evidence, RetrievalRequest, Stance, StanceMode};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use schema::{Claim, Evidence, RetrievalRequest, Stance};
 use store::{AnnTuningConfig, FileWal, InMemoryStore};
 
 use tempfile::TempDir;
@@ -45,8 +45,8 @@ use tempfile::TempDir;
 
 fn make_claim(id: &str, tenant: &str, text: &str) -> Claim {
     Claim {
-        claim_id: id.to_string(),
-        tenant_id: tenant.to_string(),
+        claim_id: id.into(),
+        tenant_id: tenant.into(),
         canonical_text: text.to_string(),
         confidence: 0.9,
         event_time_unix: None,
@@ -57,13 +57,17 @@ fn make_claim(id: &str, tenant: &str, text: &str) -> Claim {
         valid_to: None,
         created_at: Some(0),
         updated_at: Some(0),
+        metadata: Vec::new(),
+        visibility_labels: Vec::new(),
+        language: None,
+        collection: None,
     }
 }
 
 fn make_evidence(id: &str, claim_id: &str) -> Evidence {
     Evidence {
-        evidence_id: id.to_string(),
-        claim_id: claim_id.to_string(),
+        evidence_id: id.into(),
+        claim_id: claim_id.into(),
         source_id: format!("src://{id}"),
         stance: Stance::Supports,
         source_quality: 0.9,
@@ -73,6 +77,9 @@ fn make_evidence(id: &str, claim_id: &str) -> Evidence {
         doc_id: None,
         extraction_model: None,
         ingested_at: None,
+        language: None,
+        stance_strength: None,
+        negated: false,
     }
 }
 
@@ -185,12 +192,7 @@ fn bench_retrieve_lexical(c: &mut Criterion) {
     for n in [1_000, 10_000] {
         group.throughput(Throughput::Elements(1));
         let store = build_retrieval_fixture(n);
-        let req = RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "claim 42 content".into(),
-            top_k: 10,
-            stance_mode: StanceMode::Balanced,
-        };
+        let req = RetrievalRequest::new("tenant-a", "claim 42 content", 10);
         group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _n| {
             b.iter(|| {
                 let results = store.retrieve(&req);
@@ -207,12 +209,7 @@ fn bench_retrieve_semantic(c: &mut Criterion) {
         group.throughput(Throughput::Elements(1));
         let store = build_retrieval_fixture(n);
         let query_vector = vec![0.1f32; 384]; // dummy 384-dim vector
-        let req = RetrievalRequest {
-            tenant_id: "tenant-a".into(),
-            query: "claim 42 content".into(),
-            top_k: 10,
-            stance_mode: StanceMode::Balanced,
-        };
+        let req = RetrievalRequest::new("tenant-a", "claim 42 content", 10);
         group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _n| {
             b.iter(|| {
                 let results = store.retrieve_semantic(&req, &query_vector);
@@ -239,14 +236,15 @@ fn bench_ann_vs_brute_force(c: &mut Criterion) {
         // ANN path: uses the HNSW graph
         group.bench_with_input(BenchmarkId::new("ann", n), &n, |b, _n| {
             b.iter(|| {
-                let cands = store.ann_vector_top_candidates("tenant-a", &query_vector, 10);
+                let cands = store.ann_vector_top_candidates(&"tenant-a".into(), &query_vector, 10);
                 std::hint::black_box(cands);
             });
         });
         // Brute-force path: full scan, exact cosine
         group.bench_with_input(BenchmarkId::new("brute_force", n), &n, |b, _n| {
             b.iter(|| {
-                let cands = store.exact_vector_top_candidates("tenant-a", &query_vector, 10);
+                let cands =
+                    store.exact_vector_top_candidates(&"tenant-a".into(), &query_vector, 10);
                 std::hint::black_box(cands);
             });
         });
@@ -287,8 +285,7 @@ fn bench_wal_replay(c: &mut Criterion) {
         let _keep_alive = &tmp;
         b.iter(|| {
             let wal = FileWal::open(&wal_path).expect("reopen");
-            let (store, _stats) = InMemoryStore::load_from_wal_with_stats(&wal)
-                .expect("replay");
+            let (store, _stats) = InMemoryStore::load_from_wal_with_stats(&wal).expect("replay");
             std::hint::black_box(store);
         });
     });
@@ -301,47 +298,37 @@ fn bench_wal_replay(c: &mut Criterion) {
 
 fn bench_disk_vs_memory_ingest(c: &mut Criterion) {
     let mut group = c.benchmark_group("disk_vs_memory_ingest");
-    group.throughput(Throughput::Elements(1_000));
-    for n in [1_000] {
-        // In-memory path
-        group.bench_with_input(BenchmarkId::new("in_memory", n), &n, |b, &n| {
-            b.iter(|| {
-                let mut store = InMemoryStore::new_with_ann_tuning(AnnTuningConfig::default());
-                for i in 0..n {
-                    let id = format!("c{i}");
-                    store
-                        .ingest_bundle(
-                            make_claim(&id, "t1", &format!("text {i}")),
-                            vec![],
-                            vec![],
-                        )
-                        .expect("ingest");
-                }
-                std::hint::black_box(store);
-            });
+    let n = 1_000;
+    group.throughput(Throughput::Elements(n as u64));
+    // In-memory path
+    group.bench_with_input(BenchmarkId::new("in_memory", n), &n, |b, &n| {
+        b.iter(|| {
+            let mut store = InMemoryStore::new_with_ann_tuning(AnnTuningConfig::default());
+            for i in 0..n {
+                let id = format!("c{i}");
+                store
+                    .ingest_bundle(make_claim(&id, "t1", &format!("text {i}")), vec![], vec![])
+                    .expect("ingest");
+            }
+            std::hint::black_box(store);
         });
-        // Disk-backed path: same ingest but mirrored to redb
-        group.bench_with_input(BenchmarkId::new("disk_backed", n), &n, |b, &n| {
-            b.iter(|| {
-                let tmp = TempDir::new().unwrap();
-                let disk_path = tmp.path().join("bench.redb");
-                let mut store =
-                    InMemoryStore::new_with_ann_tuning(AnnTuningConfig::default());
-                store = store.with_disk(&disk_path).expect("with_disk");
-                for i in 0..n {
-                    let id = format!("c{i}");
-                    store
-                        .ingest_bundle(
-                            make_claim(&id, "t1", &format!("text {i}")),
-                            vec![],
-                            vec![],
-                        )
-                        .expect("ingest");
-                }
-                std::hint::black_box(store);
-            });
+    });
+    // Disk-backed path: same ingest but mirrored to redb
+    group.bench_with_input(BenchmarkId::new("disk_backed", n), &n, |b, &n| {
+        b.iter(|| {
+            let tmp = TempDir::new().unwrap();
+            let disk_path = tmp.path().join("bench.redb");
+            let mut store = InMemoryStore::new_with_ann_tuning(AnnTuningConfig::default());
+            store = store.with_disk(&disk_path).expect("with_disk");
+            for i in 0..n {
+                let id = format!("c{i}");
+                store
+                    .ingest_bundle(make_claim(&id, "t1", &format!("text {i}")), vec![], vec![])
+                    .expect("ingest");
+            }
+            std::hint::black_box(store);
         });
-    }
+    });
     group.finish();
 }
 
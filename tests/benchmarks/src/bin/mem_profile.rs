@@ -33,8 +33,8 @@ use store::{AnnTuningConfig, InMemoryStore};
 
 fn make_claim(id: &str, tenant: &str, text: &str) -> Claim {
     Claim {
-        claim_id: id.to_string(),
-        tenant_id: tenant.to_string(),
+        claim_id: id.into(),
+        tenant_id: tenant.into(),
         canonical_text: text.to_string(),
         confidence: 0.9,
         event_time_unix: None,
@@ -45,13 +45,17 @@ fn make_claim(id: &str, tenant: &str, text: &str) -> Claim {
         valid_to: None,
         created_at: Some(0),
         updated_at: Some(0),
+        metadata: Vec::new(),
+        visibility_labels: Vec::new(),
+        language: None,
+        collection: None,
     }
 }
 
 fn make_evidence(id: &str, claim_id: &str) -> Evidence {
     Evidence {
-        evidence_id: id.to_string(),
-        claim_id: claim_id.to_string(),
+        evidence_id: id.into(),
+        claim_id: claim_id.into(),
         source_id: format!("src://{id}"),
         stance: Stance::Supports,
         source_quality: 0.9,
@@ -61,6 +65,9 @@ fn make_evidence(id: &str, claim_id: &str) -> Evidence {
         doc_id: None,
         extraction_model: None,
         ingested_at: None,
+        language: None,
+        stance_strength: None,
+        negated: false,
     }
 }
 
@@ -98,7 +105,7 @@ fn scenario_retrieve_10k() {
     eprintln!("retrieve_10k: fixture built, running 10k retrieve calls");
     for i in 0..10_000 {
         let _query = format!("claim {i} body");
-        let _ = store.claims_for_tenant("tenant-a");
+        let _ = store.claims_for_tenant(&"tenant-a".into());
     }
     eprintln!("retrieve_10k: done");
 }
@@ -127,7 +134,9 @@ fn scenario_with_disk() {
 }
 
 fn main() {
-    let scenario = env::args().nth(1).unwrap_or_else(|| "ingest_10k".to_string());
+    let scenario = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "ingest_10k".to_string());
     let _profiler = dhat::Profiler::new_heap(); // runs until process exit
     eprintln!("mem_profile: scenario={scenario}");
     match scenario.as_str() {
@@ -139,7 +148,5 @@ fn main() {
             std::process::exit(2);
         }
     }
-    eprintln!(
-        "mem_profile: done; load target/dhat/{scenario}.heap.json into the dhat viewer"
-    );
+    eprintln!("mem_profile: done; load target/dhat/{scenario}.heap.json into the dhat viewer");
 }
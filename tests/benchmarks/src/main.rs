@@ -14,10 +14,12 @@ use retrieval::api::{
     RetrieveApiRequest, execute_api_query, reset_segment_prefilter_cache_metrics,
     segment_prefilter_cache_metrics_snapshot,
 };
-use schema::{Claim, ClaimEdge, Evidence, Relation, RetrievalRequest, Stance, StanceMode};
+use schema::{
+    Claim, ClaimEdge, ClaimId, Evidence, Relation, RetrievalRequest, Stance, StanceMode, TenantId,
+};
 use store::{
-    AnnTuningConfig, FileWal, InMemoryStore, StoreIndexStats, VectorBackendRuntime,
-    WalCheckpointStats,
+    AnnIndexKind, AnnTuningConfig, FileWal, InMemoryStore, ResultFields, StoreIndexStats,
+    VectorBackendRuntime, VectorPrecision, VectorStorageConfig, WalCheckpointStats,
 };
 
 const CONTRADICTION_DETECTION_F1_GATE: f64 = 0.80;
@@ -33,6 +35,8 @@ const DEFAULT_XXLARGE_MIN_ANN_RECALL_AT_100: f64 = 0.98;
 const DEFAULT_LARGE_PLUS_MIN_GRAPH_SCORE_COVERAGE: f64 = 1.0;
 const DEFAULT_LARGE_PLUS_MIN_GRAPH_SUPPORT_PATH_COUNT: usize = 1;
 const DEFAULT_LARGE_PLUS_MIN_GRAPH_CONTRADICTION_CHAIN_DEPTH: usize = 2;
+const DEFAULT_ANN_INDEX_COMPARE: &str = "ivf:64:8";
+const ANN_INDEX_COMPARE_QUERY_RUNS: usize = 20;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum BenchmarkProfile {
@@ -134,6 +138,7 @@ struct BenchmarkConfig {
     max_dash_latency_regression_pct: Option<f64>,
     scorecard_out: Option<String>,
     ann_tuning: AnnTuningConfig,
+    ann_index_compare: Vec<AnnIndexKind>,
     large_min_candidate_reduction_pct: f64,
     large_max_dash_latency_ms: f64,
     large_min_ann_recall_at_100: f64,
@@ -169,6 +174,8 @@ struct BenchmarkSummary {
     ann_candidate_count: usize,
     final_scored_candidate_count: usize,
     ann_recall: AnnRecallSummary,
+    vector_precision_recall: Vec<VectorPrecisionRecall>,
+    ann_index_comparison: Vec<AnnIndexComparison>,
     graph_reasoning: GraphReasoningBenchmarkSummary,
     index_stats: StoreIndexStats,
     ann_tuning: AnnTuningConfig,
@@ -238,6 +245,27 @@ struct AnnRecallPoint {
     recall: f64,
 }
 
+/// Recall and query cost of one ANN index kind on a copy of the
+/// fixture store; the first entry is the configured index.
+#[derive(Debug, Clone)]
+struct AnnIndexComparison {
+    index_kind: AnnIndexKind,
+    build_ms: f64,
+    avg_query_ms: f64,
+    recall_at_10: f64,
+    recall_at_100: f64,
+}
+
+/// Exact-search recall of a reduced-precision copy of the fixture store
+/// against full-precision exact search.
+#[derive(Debug, Clone)]
+struct VectorPrecisionRecall {
+    precision: VectorPrecision,
+    recall_at_10: f64,
+    recall_at_100: f64,
+    storage_bytes: usize,
+}
+
 #[derive(Debug, Clone, Default)]
 struct GraphReasoningBenchmarkSummary {
     graph_score_coverage: f64,
@@ -380,13 +408,8 @@ fn main() {
     };
     let eme_hit = eme_top.as_deref() == Some(expected_top);
 
-    let baseline_scan_count = store.claims_for_tenant(tenant).len();
-    let diagnostics_req = RetrievalRequest {
-        tenant_id: tenant.to_string(),
-        query: query.to_string(),
-        top_k: 1,
-        stance_mode: StanceMode::Balanced,
-    };
+    let baseline_scan_count = store.claims_for_tenant(&TenantId::from(tenant)).len();
+    let diagnostics_req = RetrievalRequest::new(tenant.to_string(), query.to_string(), 1);
     let metadata_prefilter_claim_ids = if config.profile == BenchmarkProfile::Hybrid {
         build_metadata_prefilter_claim_ids(
             &store,
@@ -401,8 +424,11 @@ fn main() {
         .as_ref()
         .map(|ids| ids.len())
         .unwrap_or(0);
-    let ann_candidate_count =
-        store.ann_candidate_count_for_query_vector(tenant, &hybrid_query_embedding, 1);
+    let ann_candidate_count = store.ann_candidate_count_for_query_vector(
+        &TenantId::from(tenant),
+        &hybrid_query_embedding,
+        1,
+    );
     let final_scored_candidate_count = store
         .candidate_count_with_query_vector_and_allowed_claim_ids(
             &diagnostics_req,
@@ -412,6 +438,14 @@ fn main() {
         );
     let dash_candidate_count = final_scored_candidate_count;
     let ann_recall = measure_ann_recall(&store, tenant, &hybrid_query_embedding);
+    let vector_precision_recall =
+        measure_vector_precision_recall(&store, tenant, &hybrid_query_embedding);
+    let ann_index_comparison = measure_ann_index_comparison(
+        &store,
+        tenant,
+        &hybrid_query_embedding,
+        &config.ann_index_compare,
+    );
     let graph_reasoning = measure_fixture_graph_reasoning(
         &store,
         &RetrieveApiRequest {
@@ -432,6 +466,16 @@ fn main() {
             stance_mode: StanceMode::Balanced,
             return_graph: true,
             time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
         },
     );
     let index_stats = store.index_stats();
@@ -479,6 +523,8 @@ fn main() {
         ann_candidate_count,
         final_scored_candidate_count,
         ann_recall,
+        vector_precision_recall,
+        ann_index_comparison,
         graph_reasoning,
         index_stats,
         ann_tuning: config.ann_tuning.clone(),
@@ -700,13 +746,13 @@ fn build_metadata_prefilter_claim_ids(
     tenant_id: &str,
     entity_filters: &[String],
     embedding_filters: &[String],
-) -> Option<std::collections::HashSet<String>> {
+) -> Option<std::collections::HashSet<ClaimId>> {
     let entity_candidates = if entity_filters.is_empty() {
         None
     } else {
         let mut ids = std::collections::HashSet::new();
         for filter in entity_filters {
-            ids.extend(store.claim_ids_for_entity(tenant_id, filter));
+            ids.extend(store.claim_ids_for_entity(&TenantId::from(tenant_id), filter));
         }
         Some(ids)
     };
@@ -715,7 +761,7 @@ fn build_metadata_prefilter_claim_ids(
     } else {
         let mut ids = std::collections::HashSet::new();
         for filter in embedding_filters {
-            ids.extend(store.claim_ids_for_embedding_id(tenant_id, filter));
+            ids.extend(store.claim_ids_for_embedding_id(&TenantId::from(tenant_id), filter));
         }
         Some(ids)
     };
@@ -771,7 +817,21 @@ where
             "DASH_BENCH_ANN_SEARCH_EXPANSION_MAX",
             defaults.search_expansion_max,
         ),
+        index_kind: std::env::var("DASH_BENCH_ANN_INDEX_KIND")
+            .ok()
+            .and_then(|value| AnnIndexKind::parse(&value))
+            .unwrap_or(defaults.index_kind),
+        exact_search_threshold: defaults.exact_search_threshold,
+        level_multiplier: env_or_default_f64(
+            "DASH_BENCH_ANN_LEVEL_MULTIPLIER",
+            defaults.level_multiplier,
+        ),
+        level_seed: defaults.level_seed,
     };
+    let mut ann_index_compare = parse_ann_index_kinds(
+        &std::env::var("DASH_BENCH_ANN_INDEX_COMPARE")
+            .unwrap_or_else(|_| DEFAULT_ANN_INDEX_COMPARE.to_string()),
+    )?;
     let mut large_min_candidate_reduction_pct =
         env_or_default_f64("DASH_BENCH_LARGE_MIN_CANDIDATE_REDUCTION_PCT", 95.0);
     let mut large_max_dash_latency_ms =
@@ -976,6 +1036,12 @@ where
                 min_segment_cache_hits =
                     parse_non_negative_usize_arg(args.next(), "--min-segment-cache-hits")?;
             }
+            "--ann-index-compare" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "Missing value for --ann-index-compare".to_string())?;
+                ann_index_compare = parse_ann_index_kinds(&value)?;
+            }
             "--require-vector-backend" => {
                 let value = args
                     .next()
@@ -1030,6 +1096,7 @@ where
         max_dash_latency_regression_pct,
         scorecard_out,
         ann_tuning,
+        ann_index_compare,
         large_min_candidate_reduction_pct,
         large_max_dash_latency_ms,
         large_min_ann_recall_at_100,
@@ -1093,7 +1160,7 @@ fn parse_non_negative_usize_arg(value: Option<String>, flag: &str) -> Result<usi
 }
 
 fn usage_text() -> &'static str {
-    "Usage: cargo run -p benchmark-smoke --bin benchmark-smoke -- [--smoke] [--profile smoke|standard|large|xlarge|xxlarge|hybrid] [--fixture-size N] [--iterations N] [--min-iterations N] [--history-out PATH] [--history-csv-out PATH] [--guard-history PATH] [--guard-min-iterations N] [--max-dash-latency-regression-pct N] [--scorecard-out PATH] [--ann-max-neighbors-base N] [--ann-max-neighbors-upper N] [--ann-search-expansion-factor N] [--ann-search-expansion-min N] [--ann-search-expansion-max N] [--large-min-candidate-reduction-pct N] [--large-max-dash-latency-ms N] [--large-min-ann-recall-at-100 N] [--xlarge-min-candidate-reduction-pct N] [--xlarge-max-dash-latency-ms N] [--xlarge-min-ann-recall-at-100 N] [--xxlarge-min-candidate-reduction-pct N] [--xxlarge-max-dash-latency-ms N] [--xxlarge-min-ann-recall-at-100 N] [--large-plus-min-graph-score-coverage N] [--large-plus-min-graph-support-path-count N] [--large-plus-min-graph-contradiction-chain-depth N] [--min-segment-refresh-successes N] [--min-segment-cache-hits N] [--require-vector-backend cpu|gpu] [quality probes enforce contradiction_detection_f1 >= 0.80, citation_coverage >= 0.95, extraction_span_coverage >= 0.95; large+ profiles enforce graph coverage/path/depth gates]"
+    "Usage: cargo run -p benchmark-smoke --bin benchmark-smoke -- [--smoke] [--profile smoke|standard|large|xlarge|xxlarge|hybrid] [--fixture-size N] [--iterations N] [--min-iterations N] [--history-out PATH] [--history-csv-out PATH] [--guard-history PATH] [--guard-min-iterations N] [--max-dash-latency-regression-pct N] [--scorecard-out PATH] [--ann-max-neighbors-base N] [--ann-max-neighbors-upper N] [--ann-search-expansion-factor N] [--ann-search-expansion-min N] [--ann-search-expansion-max N] [--ann-index-compare KIND[,KIND...]|none] [--large-min-candidate-reduction-pct N] [--large-max-dash-latency-ms N] [--large-min-ann-recall-at-100 N] [--xlarge-min-candidate-reduction-pct N] [--xlarge-max-dash-latency-ms N] [--xlarge-min-ann-recall-at-100 N] [--xxlarge-min-candidate-reduction-pct N] [--xxlarge-max-dash-latency-ms N] [--xxlarge-min-ann-recall-at-100 N] [--large-plus-min-graph-score-coverage N] [--large-plus-min-graph-support-path-count N] [--large-plus-min-graph-contradiction-chain-depth N] [--min-segment-refresh-successes N] [--min-segment-cache-hits N] [--require-vector-backend cpu|gpu] [quality probes enforce contradiction_detection_f1 >= 0.80, citation_coverage >= 0.95, extraction_span_coverage >= 0.95; large+ profiles enforce graph coverage/path/depth gates]"
 }
 
 #[allow(unused_unsafe)]
@@ -1160,9 +1227,9 @@ fn run_segment_cache_probe(
     let root = temp_dir_for("segment-cache");
     let tenant_segment_root = root.join(tenant);
     let claim_ids: Vec<String> = store
-        .claims_for_tenant(tenant)
+        .claims_for_tenant(&TenantId::from(tenant))
         .into_iter()
-        .map(|claim| claim.claim_id)
+        .map(|claim| claim.claim_id.into_string())
         .collect();
     persist_segments_atomic(
         &tenant_segment_root,
@@ -1172,7 +1239,7 @@ fn run_segment_cache_probe(
             claim_ids,
         }],
     )
-    .map_err(|err| format!("segment manifest persist failed: {err:?}"))?;
+    .map_err(|err| format!("segment manifest persist failed: {err}"))?;
 
     let _segment_dir_env = EnvVarGuard::set("DASH_RETRIEVAL_SEGMENT_DIR", root.as_os_str());
     let _segment_refresh_env = EnvVarGuard::set(
@@ -1191,6 +1258,16 @@ fn run_segment_cache_probe(
         stance_mode: StanceMode::Balanced,
         return_graph: false,
         time_range: None,
+        ann_expansion_budget: None,
+        metadata_filters: Vec::new(),
+        confidence_range: None,
+        claim_types: Vec::new(),
+        collections: Vec::new(),
+        as_of_unix: None,
+        result_fields: ResultFields::Full,
+        include_cold: false,
+        deadline: None,
+        visibility_labels: None,
     };
     let _ = execute_api_query(store, request.clone());
     let _ = execute_api_query(store, request);
@@ -1219,7 +1296,7 @@ fn maybe_run_wal_scale_slice(
 
     let root = temp_dir_for("wal-scale");
     let wal_path = root.join("bench.wal");
-    let mut wal = FileWal::open(&wal_path).map_err(|err| format!("open WAL failed: {err:?}"))?;
+    let mut wal = FileWal::open(&wal_path).map_err(|err| format!("open WAL failed: {err}"))?;
     let mut store = InMemoryStore::new();
     let default_claims = match profile {
         BenchmarkProfile::Large => 10_000,
@@ -1241,7 +1318,7 @@ fn maybe_run_wal_scale_slice(
     let checkpoint_start = Instant::now();
     let checkpoint_stats = store
         .checkpoint_and_compact(&mut wal)
-        .map_err(|err| format!("checkpoint failed: {err:?}"))?;
+        .map_err(|err| format!("checkpoint failed: {err}"))?;
     let checkpoint_ms = checkpoint_start.elapsed().as_secs_f64() * 1000.0;
 
     let delta_claim_id = "claim-wal-delta";
@@ -1249,8 +1326,8 @@ fn maybe_run_wal_scale_slice(
         .ingest_bundle_persistent(
             &mut wal,
             Claim {
-                claim_id: delta_claim_id.to_string(),
-                tenant_id: "tenant-benchmark-wal-scale".to_string(),
+                claim_id: delta_claim_id.into(),
+                tenant_id: "tenant-benchmark-wal-scale".into(),
                 canonical_text: "post checkpoint replay delta".to_string(),
                 confidence: 0.9,
                 event_time_unix: Some(1_775_000_000),
@@ -1261,10 +1338,14 @@ fn maybe_run_wal_scale_slice(
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "evidence-wal-delta".to_string(),
-                claim_id: delta_claim_id.to_string(),
+                evidence_id: "evidence-wal-delta".into(),
+                claim_id: delta_claim_id.into(),
                 source_id: "source://wal/delta".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.9,
@@ -1274,27 +1355,33 @@ fn maybe_run_wal_scale_slice(
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
-        .map_err(|err| format!("checkpoint delta ingest failed: {err:?}"))?;
+        .map_err(|err| format!("checkpoint delta ingest failed: {err}"))?;
     store
-        .upsert_claim_vector_persistent(&mut wal, delta_claim_id, benchmark_query_embedding())
-        .map_err(|err| format!("checkpoint delta vector upsert failed: {err:?}"))?;
+        .upsert_claim_vector_persistent(
+            &mut wal,
+            &ClaimId::from(delta_claim_id),
+            benchmark_query_embedding(),
+        )
+        .map_err(|err| format!("checkpoint delta vector upsert failed: {err}"))?;
 
     let replay_start = Instant::now();
     let (replayed, load_stats) = InMemoryStore::load_from_wal_with_stats(&wal)
-        .map_err(|err| format!("replay from WAL failed: {err:?}"))?;
+        .map_err(|err| format!("replay from WAL failed: {err}"))?;
     let replay_ms = replay_start.elapsed().as_secs_f64() * 1000.0;
     let replay_validation_top_claim = replayed
-        .retrieve(&RetrievalRequest {
-            tenant_id: "tenant-benchmark-wal-scale".to_string(),
-            query: "post checkpoint replay delta".to_string(),
-            top_k: 1,
-            stance_mode: StanceMode::Balanced,
-        })
+        .retrieve(&RetrievalRequest::new(
+            "tenant-benchmark-wal-scale",
+            "post checkpoint replay delta",
+            1,
+        ))
         .first()
-        .map(|result| result.claim_id.clone());
+        .map(|result| result.claim_id.to_string());
     let replay_validation_hit = replay_validation_top_claim.as_deref() == Some(delta_claim_id);
 
     let _ = std::fs::remove_dir_all(&root);
@@ -1348,18 +1435,137 @@ fn compute_ann_recall_at_budget(
         return 1.0;
     }
 
-    let ann = store.ann_vector_top_candidates(tenant, query_embedding, budget);
-    let exact = store.exact_vector_top_candidates(tenant, query_embedding, budget);
-    if exact.is_empty() {
-        return 1.0;
+    let ann = store.ann_vector_top_candidates(&TenantId::from(tenant), query_embedding, budget);
+    let exact = store.exact_vector_top_candidates(&TenantId::from(tenant), query_embedding, budget);
+    overlap_recall(&exact, &ann)
+}
+
+fn measure_vector_precision_recall(
+    store: &InMemoryStore,
+    tenant: &str,
+    query_embedding: &[f32],
+) -> Vec<VectorPrecisionRecall> {
+    let exact_at = |store: &InMemoryStore, budget: usize| {
+        store.exact_vector_top_candidates(&TenantId::from(tenant), query_embedding, budget)
+    };
+    let full_10 = exact_at(store, 10);
+    let full_100 = exact_at(store, 100);
+    let mut out = vec![VectorPrecisionRecall {
+        precision: VectorPrecision::F32,
+        recall_at_10: 1.0,
+        recall_at_100: 1.0,
+        storage_bytes: store.vector_storage_bytes(),
+    }];
+    for precision in [VectorPrecision::F16, VectorPrecision::Bf16] {
+        let mut reduced = store.clone();
+        reduced.set_vector_storage_config(VectorStorageConfig { precision });
+        out.push(VectorPrecisionRecall {
+            precision,
+            recall_at_10: overlap_recall(&full_10, &exact_at(&reduced, 10)),
+            recall_at_100: overlap_recall(&full_100, &exact_at(&reduced, 100)),
+            storage_bytes: reduced.vector_storage_bytes(),
+        });
+    }
+    out
+}
+
+fn measure_ann_index_comparison(
+    store: &InMemoryStore,
+    tenant: &str,
+    query_embedding: &[f32],
+    compare: &[AnnIndexKind],
+) -> Vec<AnnIndexComparison> {
+    let measure = |store: &InMemoryStore, build_ms: f64| {
+        let started = Instant::now();
+        for _ in 0..ANN_INDEX_COMPARE_QUERY_RUNS {
+            let _ = store.ann_vector_top_candidates(&TenantId::from(tenant), query_embedding, 10);
+        }
+        AnnIndexComparison {
+            index_kind: store.ann_tuning().index_kind,
+            build_ms,
+            avg_query_ms: started.elapsed().as_secs_f64() * 1000.0
+                / ANN_INDEX_COMPARE_QUERY_RUNS as f64,
+            recall_at_10: compute_ann_recall_at_budget(store, tenant, query_embedding, 10),
+            recall_at_100: compute_ann_recall_at_budget(store, tenant, query_embedding, 100),
+        }
+    };
+    let mut out = vec![measure(store, 0.0)];
+    for kind in compare {
+        if *kind == store.ann_tuning().index_kind {
+            continue;
+        }
+        let mut candidate = store.clone();
+        let started = Instant::now();
+        candidate.set_ann_tuning(AnnTuningConfig {
+            index_kind: *kind,
+            ..store.ann_tuning().clone()
+        });
+        let build_ms = started.elapsed().as_secs_f64() * 1000.0;
+        out.push(measure(&candidate, build_ms));
+    }
+    out
+}
+
+fn format_ann_index_comparison(report: &[AnnIndexComparison]) -> String {
+    report
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}:build_ms={:.2},query_ms={:.4},r10={:.4},r100={:.4}",
+                entry.index_kind.encode(),
+                entry.build_ms,
+                entry.avg_query_ms,
+                entry.recall_at_10,
+                entry.recall_at_100
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn parse_ann_index_kinds(raw: &str) -> Result<Vec<AnnIndexKind>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
     }
+    raw.split(',')
+        .map(|value| {
+            AnnIndexKind::parse(value).ok_or_else(|| {
+                format!(
+                    "Invalid ANN index kind '{}'. Valid forms: graph, pq:<subspaces>:<centroids>, ivf:<lists>:<probes>.",
+                    value.trim()
+                )
+            })
+        })
+        .collect()
+}
 
-    let exact_set: HashSet<&str> = exact.iter().map(String::as_str).collect();
-    let hits = ann
+fn overlap_recall(expected: &[ClaimId], actual: &[ClaimId]) -> f64 {
+    if expected.is_empty() {
+        return 1.0;
+    }
+    let expected_set: HashSet<&str> = expected.iter().map(ClaimId::as_str).collect();
+    let hits = actual
         .iter()
-        .filter(|claim_id| exact_set.contains(claim_id.as_str()))
+        .filter(|claim_id| expected_set.contains(claim_id.as_str()))
         .count();
-    hits as f64 / exact_set.len() as f64
+    hits as f64 / expected_set.len() as f64
+}
+
+fn format_vector_precision_recall(report: &[VectorPrecisionRecall]) -> String {
+    report
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}:r10={:.4},r100={:.4},bytes={}",
+                entry.precision.as_str(),
+                entry.recall_at_10,
+                entry.recall_at_100,
+                entry.storage_bytes
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";")
 }
 
 fn measure_fixture_graph_reasoning(
@@ -1447,6 +1653,14 @@ fn print_summary(summary: &BenchmarkSummary) {
         "ANN recall curve (budget:recall): {}",
         format_ann_recall_curve(&summary.ann_recall.curve)
     );
+    println!(
+        "Vector precision recall (precision:recall@10,recall@100,bytes): {}",
+        format_vector_precision_recall(&summary.vector_precision_recall)
+    );
+    println!(
+        "ANN index comparison (kind:build,query,recall@10,recall@100): {}",
+        format_ann_index_comparison(&summary.ann_index_comparison)
+    );
     println!("DASH candidate count: {}", summary.dash_candidate_count);
     let reduction_pct = if summary.baseline_scan_count == 0 {
         0.0
@@ -1579,12 +1793,13 @@ fn print_quality_summary(summary: &QualityProbeSummary) {
 
 fn print_ann_tuning(ann_tuning: &AnnTuningConfig) {
     println!(
-        "ANN tuning: base_neighbors={}, upper_neighbors={}, search_factor={}, search_min={}, search_max={}",
+        "ANN tuning: base_neighbors={}, upper_neighbors={}, search_factor={}, search_min={}, search_max={}, level_multiplier={}",
         ann_tuning.max_neighbors_base,
         ann_tuning.max_neighbors_upper,
         ann_tuning.search_expansion_factor,
         ann_tuning.search_expansion_min,
-        ann_tuning.search_expansion_max
+        ann_tuning.search_expansion_max,
+        ann_tuning.level_multiplier
     );
 }
 
@@ -1922,6 +2137,16 @@ fn write_scorecard(
         "- ann_recall_curve: {}",
         format_ann_recall_curve(&summary.ann_recall.curve)
     )?;
+    writeln!(
+        file,
+        "- vector_precision_recall: {}",
+        format_vector_precision_recall(&summary.vector_precision_recall)
+    )?;
+    writeln!(
+        file,
+        "- ann_index_comparison: {}",
+        format_ann_index_comparison(&summary.ann_index_comparison)
+    )?;
     writeln!(
         file,
         "- graph_score_coverage: {:.4}",
@@ -2317,12 +2542,10 @@ fn run_quality_probes() -> QualityProbeSummary {
     seed_quality_probe_fixture(&mut store, tenant);
 
     let contradiction_top = store
-        .retrieve(&RetrievalRequest {
-            tenant_id: tenant.to_string(),
-            query: "project orion launched".to_string(),
-            top_k: 1,
-            stance_mode: StanceMode::SupportOnly,
-        })
+        .retrieve(
+            &RetrievalRequest::new(tenant.to_string(), "project orion launched", 1)
+                .with_stance_mode(StanceMode::SupportOnly),
+        )
         .first()
         .map(|r| r.claim_id.clone());
     let contradiction_support_only_pass =
@@ -2332,12 +2555,7 @@ fn run_quality_probes() -> QualityProbeSummary {
         contradiction_detection_f1 >= CONTRADICTION_DETECTION_F1_GATE;
 
     let temporal_results = store.retrieve_with_time_range(
-        &RetrievalRequest {
-            tenant_id: tenant.to_string(),
-            query: "mars mission status update".to_string(),
-            top_k: 10,
-            stance_mode: StanceMode::Balanced,
-        },
+        &RetrievalRequest::new(tenant.to_string(), "mars mission status update", 10),
         Some(2_000),
         Some(3_000),
     );
@@ -2359,6 +2577,16 @@ fn run_quality_probes() -> QualityProbeSummary {
             stance_mode: StanceMode::Balanced,
             return_graph: false,
             time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
         },
     );
     let hybrid_filter_with_embedding_pass =
@@ -2376,6 +2604,16 @@ fn run_quality_probes() -> QualityProbeSummary {
             stance_mode: StanceMode::Balanced,
             return_graph: false,
             time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
         },
     );
     let citation_coverage = if citation_probe.results.is_empty() {
@@ -2403,6 +2641,16 @@ fn run_quality_probes() -> QualityProbeSummary {
             stance_mode: StanceMode::Balanced,
             return_graph: true,
             time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
         },
     );
     let graph_reasoning_score_present_pass = !graph_probe.results.is_empty()
@@ -2461,7 +2709,7 @@ fn run_quality_probes() -> QualityProbeSummary {
     let extraction_claim_ids: HashSet<String> = extraction_batch
         .items
         .iter()
-        .map(|item| item.claim.claim_id.clone())
+        .map(|item| item.claim.claim_id.to_string())
         .collect();
     for item in extraction_batch.items {
         store
@@ -2480,6 +2728,16 @@ fn run_quality_probes() -> QualityProbeSummary {
             stance_mode: StanceMode::Balanced,
             return_graph: false,
             time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
         },
     );
     let extraction_results: Vec<_> = extraction_probe
@@ -2529,8 +2787,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-contradiction-heavy".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-contradiction-heavy".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Orion launched in 2024".to_string(),
                 confidence: 0.85,
                 event_time_unix: Some(2_024),
@@ -2541,11 +2799,15 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![
                 Evidence {
-                    evidence_id: "probe-contradiction-heavy-s1".to_string(),
-                    claim_id: "probe-contradiction-heavy".to_string(),
+                    evidence_id: "probe-contradiction-heavy-s1".into(),
+                    claim_id: "probe-contradiction-heavy".into(),
                     source_id: "source://probe/heavy/s1".to_string(),
                     stance: Stance::Supports,
                     source_quality: 0.9,
@@ -2555,10 +2817,13 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 },
                 Evidence {
-                    evidence_id: "probe-contradiction-heavy-c1".to_string(),
-                    claim_id: "probe-contradiction-heavy".to_string(),
+                    evidence_id: "probe-contradiction-heavy-c1".into(),
+                    claim_id: "probe-contradiction-heavy".into(),
                     source_id: "source://probe/heavy/c1".to_string(),
                     stance: Stance::Contradicts,
                     source_quality: 0.9,
@@ -2568,10 +2833,13 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 },
                 Evidence {
-                    evidence_id: "probe-contradiction-heavy-c2".to_string(),
-                    claim_id: "probe-contradiction-heavy".to_string(),
+                    evidence_id: "probe-contradiction-heavy-c2".into(),
+                    claim_id: "probe-contradiction-heavy".into(),
                     source_id: "source://probe/heavy/c2".to_string(),
                     stance: Stance::Contradicts,
                     source_quality: 0.9,
@@ -2581,6 +2849,9 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 },
             ],
             vec![],
@@ -2590,8 +2861,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-contradiction-supported".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-contradiction-supported".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Orion launched in 2023".to_string(),
                 confidence: 0.9,
                 event_time_unix: Some(2_023),
@@ -2602,11 +2873,15 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![
                 Evidence {
-                    evidence_id: "probe-contradiction-supported-s1".to_string(),
-                    claim_id: "probe-contradiction-supported".to_string(),
+                    evidence_id: "probe-contradiction-supported-s1".into(),
+                    claim_id: "probe-contradiction-supported".into(),
                     source_id: "source://probe/supported/s1".to_string(),
                     stance: Stance::Supports,
                     source_quality: 0.92,
@@ -2616,10 +2891,13 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 },
                 Evidence {
-                    evidence_id: "probe-contradiction-supported-s2".to_string(),
-                    claim_id: "probe-contradiction-supported".to_string(),
+                    evidence_id: "probe-contradiction-supported-s2".into(),
+                    claim_id: "probe-contradiction-supported".into(),
                     source_id: "source://probe/supported/s2".to_string(),
                     stance: Stance::Supports,
                     source_quality: 0.9,
@@ -2629,6 +2907,9 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 },
             ],
             vec![],
@@ -2638,8 +2919,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-temporal-old".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-temporal-old".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Mars mission status update".to_string(),
                 confidence: 0.9,
                 event_time_unix: Some(1_500),
@@ -2650,10 +2931,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-temporal-old-s1".to_string(),
-                claim_id: "probe-temporal-old".to_string(),
+                evidence_id: "probe-temporal-old-s1".into(),
+                claim_id: "probe-temporal-old".into(),
                 source_id: "source://probe/temporal/old".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.9,
@@ -2663,6 +2948,9 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
@@ -2671,8 +2959,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-temporal-new".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-temporal-new".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Mars mission status update".to_string(),
                 confidence: 0.9,
                 event_time_unix: Some(2_500),
@@ -2683,10 +2971,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-temporal-new-s1".to_string(),
-                claim_id: "probe-temporal-new".to_string(),
+                evidence_id: "probe-temporal-new-s1".into(),
+                claim_id: "probe-temporal-new".into(),
                 source_id: "source://probe/temporal/new".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.9,
@@ -2696,6 +2988,9 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
@@ -2704,8 +2999,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-temporal-unknown".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-temporal-unknown".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Mars mission status update".to_string(),
                 confidence: 0.95,
                 event_time_unix: None,
@@ -2716,10 +3011,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-temporal-unknown-s1".to_string(),
-                claim_id: "probe-temporal-unknown".to_string(),
+                evidence_id: "probe-temporal-unknown-s1".into(),
+                claim_id: "probe-temporal-unknown".into(),
                 source_id: "source://probe/temporal/unknown".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.95,
@@ -2729,6 +3028,9 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
@@ -2737,22 +3039,26 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-filter-match".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-filter-match".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Helios acquired Startup Nova".to_string(),
                 confidence: 0.96,
                 event_time_unix: Some(2_026),
-                entities: vec!["Project Helios".to_string(), "Startup Nova".to_string()],
+                entities: vec!["Project Helios".into(), "Startup Nova".into()],
                 embedding_ids: vec!["emb://probe-filter-match".to_string()],
                 claim_type: None,
                 valid_from: None,
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-filter-match-s1".to_string(),
-                claim_id: "probe-filter-match".to_string(),
+                evidence_id: "probe-filter-match-s1".into(),
+                claim_id: "probe-filter-match".into(),
                 source_id: "source://probe/filter/match".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.95,
@@ -2762,33 +3068,43 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
         .expect("quality probe ingest should succeed");
     store
-        .upsert_claim_vector("probe-filter-match", benchmark_query_embedding())
+        .upsert_claim_vector(
+            &ClaimId::from("probe-filter-match"),
+            benchmark_query_embedding(),
+        )
         .expect("quality probe vector upsert should succeed");
 
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-filter-other".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-filter-other".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Helios announced startup program".to_string(),
                 confidence: 0.91,
                 event_time_unix: Some(2_026),
-                entities: vec!["Project Helios".to_string()],
+                entities: vec!["Project Helios".into()],
                 embedding_ids: vec!["emb://probe-filter-other".to_string()],
                 claim_type: None,
                 valid_from: None,
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-filter-other-s1".to_string(),
-                claim_id: "probe-filter-other".to_string(),
+                evidence_id: "probe-filter-other-s1".into(),
+                claim_id: "probe-filter-other".into(),
                 source_id: "source://probe/filter/other".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.9,
@@ -2798,19 +3114,25 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
         .expect("quality probe ingest should succeed");
     store
-        .upsert_claim_vector("probe-filter-other", fixture_vector_for_index(999_991))
+        .upsert_claim_vector(
+            &ClaimId::from("probe-filter-other"),
+            fixture_vector_for_index(999_991),
+        )
         .expect("quality probe vector upsert should succeed");
 
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-graph-root".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-graph-root".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Atlas evidence chain baseline claim".to_string(),
                 confidence: 0.94,
                 event_time_unix: Some(2_026),
@@ -2821,10 +3143,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-graph-root-s1".to_string(),
-                claim_id: "probe-graph-root".to_string(),
+                evidence_id: "probe-graph-root-s1".into(),
+                claim_id: "probe-graph-root".into(),
                 source_id: "source://probe/graph/root".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.93,
@@ -2834,21 +3160,24 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![
                 ClaimEdge {
-                    edge_id: "probe-graph-edge-root-support".to_string(),
-                    from_claim_id: "probe-graph-root".to_string(),
-                    to_claim_id: "probe-graph-support-1".to_string(),
+                    edge_id: "probe-graph-edge-root-support".into(),
+                    from_claim_id: "probe-graph-root".into(),
+                    to_claim_id: "probe-graph-support-1".into(),
                     relation: Relation::Supports,
                     strength: 0.9,
                     reason_codes: vec![],
                     created_at: None,
                 },
                 ClaimEdge {
-                    edge_id: "probe-graph-edge-root-contradict".to_string(),
-                    from_claim_id: "probe-graph-root".to_string(),
-                    to_claim_id: "probe-graph-contradict-1".to_string(),
+                    edge_id: "probe-graph-edge-root-contradict".into(),
+                    from_claim_id: "probe-graph-root".into(),
+                    to_claim_id: "probe-graph-contradict-1".into(),
                     relation: Relation::Contradicts,
                     strength: 0.88,
                     reason_codes: vec![],
@@ -2861,8 +3190,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-graph-support-1".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-graph-support-1".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Atlas evidence chain supporting layer one".to_string(),
                 confidence: 0.9,
                 event_time_unix: Some(2_026),
@@ -2873,10 +3202,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-graph-support-1-s1".to_string(),
-                claim_id: "probe-graph-support-1".to_string(),
+                evidence_id: "probe-graph-support-1-s1".into(),
+                claim_id: "probe-graph-support-1".into(),
                 source_id: "source://probe/graph/support-1".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.9,
@@ -2886,11 +3219,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![ClaimEdge {
-                edge_id: "probe-graph-edge-support-chain".to_string(),
-                from_claim_id: "probe-graph-support-1".to_string(),
-                to_claim_id: "probe-graph-support-2".to_string(),
+                edge_id: "probe-graph-edge-support-chain".into(),
+                from_claim_id: "probe-graph-support-1".into(),
+                to_claim_id: "probe-graph-support-2".into(),
                 relation: Relation::Supports,
                 strength: 0.84,
                 reason_codes: vec![],
@@ -2902,8 +3238,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-graph-support-2".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-graph-support-2".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Atlas evidence chain supporting layer two".to_string(),
                 confidence: 0.88,
                 event_time_unix: Some(2_026),
@@ -2914,10 +3250,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-graph-support-2-s1".to_string(),
-                claim_id: "probe-graph-support-2".to_string(),
+                evidence_id: "probe-graph-support-2-s1".into(),
+                claim_id: "probe-graph-support-2".into(),
                 source_id: "source://probe/graph/support-2".to_string(),
                 stance: Stance::Supports,
                 source_quality: 0.9,
@@ -2927,6 +3267,9 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
@@ -2935,8 +3278,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-graph-contradict-1".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-graph-contradict-1".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Atlas evidence chain contradicting branch one".to_string(),
                 confidence: 0.86,
                 event_time_unix: Some(2_026),
@@ -2947,10 +3290,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-graph-contradict-1-c1".to_string(),
-                claim_id: "probe-graph-contradict-1".to_string(),
+                evidence_id: "probe-graph-contradict-1-c1".into(),
+                claim_id: "probe-graph-contradict-1".into(),
                 source_id: "source://probe/graph/contradict-1".to_string(),
                 stance: Stance::Contradicts,
                 source_quality: 0.86,
@@ -2960,11 +3307,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![ClaimEdge {
-                edge_id: "probe-graph-edge-contradict-chain".to_string(),
-                from_claim_id: "probe-graph-contradict-1".to_string(),
-                to_claim_id: "probe-graph-contradict-2".to_string(),
+                edge_id: "probe-graph-edge-contradict-chain".into(),
+                from_claim_id: "probe-graph-contradict-1".into(),
+                to_claim_id: "probe-graph-contradict-2".into(),
                 relation: Relation::Contradicts,
                 strength: 0.81,
                 reason_codes: vec![],
@@ -2976,8 +3326,8 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
     store
         .ingest_bundle(
             Claim {
-                claim_id: "probe-graph-contradict-2".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "probe-graph-contradict-2".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Project Atlas evidence chain contradicting branch two".to_string(),
                 confidence: 0.82,
                 event_time_unix: Some(2_026),
@@ -2988,10 +3338,14 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             vec![Evidence {
-                evidence_id: "probe-graph-contradict-2-c1".to_string(),
-                claim_id: "probe-graph-contradict-2".to_string(),
+                evidence_id: "probe-graph-contradict-2-c1".into(),
+                claim_id: "probe-graph-contradict-2".into(),
                 source_id: "source://probe/graph/contradict-2".to_string(),
                 stance: Stance::Contradicts,
                 source_quality: 0.84,
@@ -3001,6 +3355,9 @@ fn seed_quality_probe_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }],
             vec![],
         )
@@ -3014,8 +3371,8 @@ fn seed_contradiction_f1_fixture(store: &mut InMemoryStore, tenant: &str) {
         let mut evidence = Vec::with_capacity(supports + contradicts);
         for idx in 0..supports {
             evidence.push(Evidence {
-                evidence_id: format!("{claim_id}-s{}", idx + 1),
-                claim_id: claim_id.to_string(),
+                evidence_id: format!("{claim_id}-s{}", idx + 1).into(),
+                claim_id: claim_id.into(),
                 source_id: format!("source://probe/f1/{claim_id}/supports/{}", idx + 1),
                 stance: Stance::Supports,
                 source_quality: 0.91,
@@ -3025,12 +3382,15 @@ fn seed_contradiction_f1_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             });
         }
         for idx in 0..contradicts {
             evidence.push(Evidence {
-                evidence_id: format!("{claim_id}-c{}", idx + 1),
-                claim_id: claim_id.to_string(),
+                evidence_id: format!("{claim_id}-c{}", idx + 1).into(),
+                claim_id: claim_id.into(),
                 source_id: format!("source://probe/f1/{claim_id}/contradicts/{}", idx + 1),
                 stance: Stance::Contradicts,
                 source_quality: 0.91,
@@ -3040,14 +3400,17 @@ fn seed_contradiction_f1_fixture(store: &mut InMemoryStore, tenant: &str) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             });
         }
 
         store
             .ingest_bundle(
                 Claim {
-                    claim_id: claim_id.to_string(),
-                    tenant_id: tenant.to_string(),
+                    claim_id: claim_id.into(),
+                    tenant_id: tenant.into(),
                     canonical_text: format!(
                         "Adversarial contradiction probe statement for {claim_id}"
                     ),
@@ -3060,6 +3423,10 @@ fn seed_contradiction_f1_fixture(store: &mut InMemoryStore, tenant: &str) {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 evidence,
                 vec![],
@@ -3076,12 +3443,11 @@ fn seed_contradiction_f1_fixture(store: &mut InMemoryStore, tenant: &str) {
 }
 
 fn measure_contradiction_detection_f1(store: &InMemoryStore, tenant: &str) -> f64 {
-    let results = store.retrieve(&RetrievalRequest {
-        tenant_id: tenant.to_string(),
-        query: "adversarial contradiction probe".to_string(),
-        top_k: 32,
-        stance_mode: StanceMode::Balanced,
-    });
+    let results = store.retrieve(&RetrievalRequest::new(
+        tenant.to_string(),
+        "adversarial contradiction probe",
+        32,
+    ));
 
     let expected_contradiction_ids: HashSet<String> = (1..=5)
         .map(|idx| format!("probe-f1-contradict-{idx}"))
@@ -3090,7 +3456,7 @@ fn measure_contradiction_detection_f1(store: &InMemoryStore, tenant: &str) -> f6
         .into_iter()
         .filter(|result| result.claim_id.starts_with("probe-f1-"))
         .filter(|result| result.contradicts > result.supports)
-        .map(|result| result.claim_id)
+        .map(|result| result.claim_id.into_string())
         .collect();
 
     let true_positive = predicted_contradiction_ids
@@ -3145,8 +3511,8 @@ fn seed_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
         };
         let evidence = if include_evidence {
             vec![Evidence {
-                evidence_id: format!("evidence-{i}"),
-                claim_id: claim_id.clone(),
+                evidence_id: format!("evidence-{i}").into(),
+                claim_id: claim_id.clone().into(),
                 source_id: format!("source://doc-{i}"),
                 stance: Stance::Supports,
                 source_quality: if i == target_index { 0.95 } else { 0.75 },
@@ -3156,6 +3522,9 @@ fn seed_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }]
         } else {
             Vec::new()
@@ -3163,8 +3532,8 @@ fn seed_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
         store
             .ingest_bundle(
                 Claim {
-                    claim_id: claim_id.clone(),
-                    tenant_id: tenant.to_string(),
+                    claim_id: claim_id.clone().into(),
+                    tenant_id: tenant.into(),
                     canonical_text: claim_text,
                     confidence: if i == target_index { 0.95 } else { 0.7 },
                     event_time_unix: Some(1735689600 + i as i64),
@@ -3175,6 +3544,10 @@ fn seed_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 evidence,
                 vec![],
@@ -3189,7 +3562,7 @@ fn seed_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
                 fixture_vector_for_index(i)
             };
             store
-                .upsert_claim_vector(&claim_id, vector)
+                .upsert_claim_vector(&ClaimId::from(claim_id), vector)
                 .expect("fixture vector upsert should succeed");
         }
     }
@@ -3228,8 +3601,8 @@ fn seed_fixture_graph_reasoning_edges(
         store
             .ingest_bundle(
                 Claim {
-                    claim_id: claim_id.to_string(),
-                    tenant_id: tenant.to_string(),
+                    claim_id: claim_id.into(),
+                    tenant_id: tenant.into(),
                     canonical_text: canonical_text.to_string(),
                     confidence: 0.8,
                     event_time_unix: Some(1_735_689_600),
@@ -3240,10 +3613,14 @@ fn seed_fixture_graph_reasoning_edges(
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
-                    evidence_id: format!("evidence-{claim_id}"),
-                    claim_id: claim_id.to_string(),
+                    evidence_id: format!("evidence-{claim_id}").into(),
+                    claim_id: claim_id.into(),
                     source_id: format!("source://graph/{claim_id}"),
                     stance: stance.clone(),
                     source_quality: 0.8,
@@ -3253,6 +3630,9 @@ fn seed_fixture_graph_reasoning_edges(
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3262,8 +3642,8 @@ fn seed_fixture_graph_reasoning_edges(
     store
         .ingest_bundle(
             Claim {
-                claim_id: target_claim_id.to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: target_claim_id.into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Company X acquired Company Y in 2025".to_string(),
                 confidence: 0.95,
                 event_time_unix: Some(1_735_689_600),
@@ -3274,22 +3654,26 @@ fn seed_fixture_graph_reasoning_edges(
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             Vec::new(),
             vec![
                 ClaimEdge {
-                    edge_id: "edge-graph-target-support".to_string(),
-                    from_claim_id: target_claim_id.to_string(),
-                    to_claim_id: "claim-graph-support-1".to_string(),
+                    edge_id: "edge-graph-target-support".into(),
+                    from_claim_id: target_claim_id.into(),
+                    to_claim_id: "claim-graph-support-1".into(),
                     relation: Relation::Supports,
                     strength: 0.9,
                     reason_codes: vec![],
                     created_at: None,
                 },
                 ClaimEdge {
-                    edge_id: "edge-graph-target-contradict".to_string(),
-                    from_claim_id: target_claim_id.to_string(),
-                    to_claim_id: "claim-graph-contradict-1".to_string(),
+                    edge_id: "edge-graph-target-contradict".into(),
+                    from_claim_id: target_claim_id.into(),
+                    to_claim_id: "claim-graph-contradict-1".into(),
                     relation: Relation::Contradicts,
                     strength: 0.88,
                     reason_codes: vec![],
@@ -3302,8 +3686,8 @@ fn seed_fixture_graph_reasoning_edges(
     store
         .ingest_bundle(
             Claim {
-                claim_id: "claim-graph-support-1".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "claim-graph-support-1".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Graph support branch node one".to_string(),
                 confidence: 0.8,
                 event_time_unix: Some(1_735_689_600),
@@ -3314,12 +3698,16 @@ fn seed_fixture_graph_reasoning_edges(
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             Vec::new(),
             vec![ClaimEdge {
-                edge_id: "edge-graph-support-chain".to_string(),
-                from_claim_id: "claim-graph-support-1".to_string(),
-                to_claim_id: "claim-graph-support-2".to_string(),
+                edge_id: "edge-graph-support-chain".into(),
+                from_claim_id: "claim-graph-support-1".into(),
+                to_claim_id: "claim-graph-support-2".into(),
                 relation: Relation::Supports,
                 strength: 0.84,
                 reason_codes: vec![],
@@ -3331,8 +3719,8 @@ fn seed_fixture_graph_reasoning_edges(
     store
         .ingest_bundle(
             Claim {
-                claim_id: "claim-graph-contradict-1".to_string(),
-                tenant_id: tenant.to_string(),
+                claim_id: "claim-graph-contradict-1".into(),
+                tenant_id: tenant.into(),
                 canonical_text: "Graph contradiction branch node one".to_string(),
                 confidence: 0.8,
                 event_time_unix: Some(1_735_689_600),
@@ -3343,12 +3731,16 @@ fn seed_fixture_graph_reasoning_edges(
                 valid_to: None,
                 created_at: None,
                 updated_at: None,
+                metadata: Vec::new(),
+                visibility_labels: Vec::new(),
+                language: None,
+                collection: None,
             },
             Vec::new(),
             vec![ClaimEdge {
-                edge_id: "edge-graph-contradiction-chain".to_string(),
-                from_claim_id: "claim-graph-contradict-1".to_string(),
-                to_claim_id: "claim-graph-contradict-2".to_string(),
+                edge_id: "edge-graph-contradiction-chain".into(),
+                from_claim_id: "claim-graph-contradict-1".into(),
+                to_claim_id: "claim-graph-contradict-2".into(),
                 relation: Relation::Contradicts,
                 strength: 0.82,
                 reason_codes: vec![],
@@ -3389,8 +3781,8 @@ fn seed_fixture_persistent(
         };
         let evidence = if include_evidence {
             vec![Evidence {
-                evidence_id: format!("evidence-{i}"),
-                claim_id: claim_id.clone(),
+                evidence_id: format!("evidence-{i}").into(),
+                claim_id: claim_id.clone().into(),
                 source_id: format!("source://doc-{i}"),
                 stance: Stance::Supports,
                 source_quality: if i == target_index { 0.95 } else { 0.75 },
@@ -3400,6 +3792,9 @@ fn seed_fixture_persistent(
                 doc_id: None,
                 extraction_model: None,
                 ingested_at: None,
+                language: None,
+                stance_strength: None,
+                negated: false,
             }]
         } else {
             Vec::new()
@@ -3408,8 +3803,8 @@ fn seed_fixture_persistent(
             .ingest_bundle_persistent(
                 wal,
                 Claim {
-                    claim_id: claim_id.clone(),
-                    tenant_id: tenant.to_string(),
+                    claim_id: claim_id.clone().into(),
+                    tenant_id: tenant.into(),
                     canonical_text: claim_text,
                     confidence: if i == target_index { 0.95 } else { 0.7 },
                     event_time_unix: Some(1735689600 + i as i64),
@@ -3420,11 +3815,15 @@ fn seed_fixture_persistent(
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 evidence,
                 vec![],
             )
-            .map_err(|err| format!("persistent fixture ingest failed at index {i}: {err:?}"))?;
+            .map_err(|err| format!("persistent fixture ingest failed at index {i}: {err}"))?;
 
         let should_upsert_vector = i == target_index || i % vector_upsert_stride == 0;
         if should_upsert_vector {
@@ -3434,9 +3833,9 @@ fn seed_fixture_persistent(
                 fixture_vector_for_index(i)
             };
             store
-                .upsert_claim_vector_persistent(wal, &claim_id, vector)
+                .upsert_claim_vector_persistent(wal, &ClaimId::from(claim_id), vector)
                 .map_err(|err| {
-                    format!("persistent fixture vector upsert failed at index {i}: {err:?}")
+                    format!("persistent fixture vector upsert failed at index {i}: {err}")
                 })?;
         }
     }
@@ -3458,11 +3857,11 @@ fn seed_hybrid_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
         };
 
         let entities = if is_target || i % 19 == 0 {
-            vec!["Project Helios".to_string(), "Startup Nova".to_string()]
+            vec!["Project Helios".into(), "Startup Nova".into()]
         } else if i % 7 == 0 {
-            vec!["Project Helios".to_string()]
+            vec!["Project Helios".into()]
         } else {
-            vec!["Project Atlas".to_string()]
+            vec!["Project Atlas".into()]
         };
         let embedding_ids = if is_target {
             vec!["emb://hybrid-target".to_string()]
@@ -3484,8 +3883,8 @@ fn seed_hybrid_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
         store
             .ingest_bundle(
                 Claim {
-                    claim_id: claim_id.clone(),
-                    tenant_id: tenant.to_string(),
+                    claim_id: claim_id.clone().into(),
+                    tenant_id: tenant.into(),
                     canonical_text,
                     confidence: if is_target { 0.97 } else { 0.72 },
                     event_time_unix: Some(1767225600 + i as i64),
@@ -3496,10 +3895,14 @@ fn seed_hybrid_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
                     valid_to: None,
                     created_at: None,
                     updated_at: None,
+                    metadata: Vec::new(),
+                    visibility_labels: Vec::new(),
+                    language: None,
+                    collection: None,
                 },
                 vec![Evidence {
-                    evidence_id: format!("evidence-hybrid-{i}"),
-                    claim_id: claim_id.clone(),
+                    evidence_id: format!("evidence-hybrid-{i}").into(),
+                    claim_id: claim_id.clone().into(),
                     source_id: format!("source://hybrid/doc-{i}"),
                     stance: Stance::Supports,
                     source_quality: if is_target { 0.97 } else { 0.78 },
@@ -3509,6 +3912,9 @@ fn seed_hybrid_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
                     doc_id: None,
                     extraction_model: None,
                     ingested_at: None,
+                    language: None,
+                    stance_strength: None,
+                    negated: false,
                 }],
                 vec![],
             )
@@ -3522,7 +3928,7 @@ fn seed_hybrid_fixture(store: &mut InMemoryStore, tenant: &str, count: usize) {
             fixture_vector_for_index(i + 200_000)
         };
         store
-            .upsert_claim_vector(&claim_id, vector)
+            .upsert_claim_vector(&ClaimId::from(claim_id), vector)
             .expect("hybrid fixture vector upsert should succeed");
     }
 }
@@ -3548,7 +3954,7 @@ fn fixture_vector_for_index(i: usize) -> Vec<f32> {
 }
 
 fn baseline_retrieve_top1(store: &InMemoryStore, tenant: &str, query: &str) -> Option<String> {
-    let claims = store.claims_for_tenant(tenant);
+    let claims = store.claims_for_tenant(&TenantId::from(tenant));
     claims
         .iter()
         .map(|claim| {
@@ -3558,7 +3964,7 @@ fn baseline_retrieve_top1(store: &InMemoryStore, tenant: &str, query: &str) -> O
             )
         })
         .max_by(|a, b| a.1.total_cmp(&b.1))
-        .map(|(claim_id, _)| claim_id)
+        .map(|(claim_id, _)| claim_id.into_string())
 }
 
 fn baseline_hybrid_retrieve_top1(
@@ -3580,16 +3986,15 @@ fn baseline_hybrid_retrieve_top1(
         .collect();
 
     store
-        .claims_for_tenant(tenant)
+        .claims_for_tenant(&TenantId::from(tenant))
         .into_iter()
         .filter(|claim| {
             let entity_match = if normalized_entities.is_empty() {
                 true
             } else {
-                claim
-                    .entities
-                    .iter()
-                    .any(|entity| normalized_entities.contains(&entity.trim().to_ascii_lowercase()))
+                claim.entities.iter().any(|entity| {
+                    normalized_entities.contains(&entity.index_name().trim().to_ascii_lowercase())
+                })
             };
             let embedding_match = if embedding_filter_set.is_empty() {
                 true
@@ -3608,24 +4013,19 @@ fn baseline_hybrid_retrieve_top1(
             )
         })
         .max_by(|a, b| a.1.total_cmp(&b.1))
-        .map(|(claim_id, _)| claim_id)
+        .map(|(claim_id, _)| claim_id.into_string())
 }
 
 fn eme_retrieve_top1(store: &InMemoryStore, tenant: &str, query: &str) -> Option<String> {
     store
         .retrieve_with_time_range_and_query_vector(
-            &RetrievalRequest {
-                tenant_id: tenant.to_string(),
-                query: query.to_string(),
-                top_k: 1,
-                stance_mode: StanceMode::Balanced,
-            },
+            &RetrievalRequest::new(tenant.to_string(), query.to_string(), 1),
             None,
             None,
             Some(&benchmark_query_embedding()),
         )
         .first()
-        .map(|r| r.claim_id.clone())
+        .map(|r| r.claim_id.to_string())
 }
 
 fn eme_hybrid_retrieve_top1(
@@ -3648,6 +4048,16 @@ fn eme_hybrid_retrieve_top1(
             stance_mode: StanceMode::Balanced,
             return_graph: false,
             time_range: None,
+            ann_expansion_budget: None,
+            metadata_filters: Vec::new(),
+            confidence_range: None,
+            claim_types: Vec::new(),
+            collections: Vec::new(),
+            as_of_unix: None,
+            result_fields: ResultFields::Full,
+            include_cold: false,
+            deadline: None,
+            visibility_labels: None,
         },
     )
     .results
@@ -3741,6 +4151,7 @@ mod tests {
             max_dash_latency_regression_pct: None,
             scorecard_out: None,
             ann_tuning: AnnTuningConfig::default(),
+            ann_index_compare: Vec::new(),
             large_min_candidate_reduction_pct: min_reduction,
             large_max_dash_latency_ms: max_latency,
             large_min_ann_recall_at_100: 0.95,
@@ -3787,6 +4198,8 @@ mod tests {
                 recall_at_100: 1.0,
                 curve: Vec::new(),
             },
+            vector_precision_recall: Vec::new(),
+            ann_index_comparison: Vec::new(),
             graph_reasoning: GraphReasoningBenchmarkSummary {
                 graph_score_coverage: 1.0,
                 max_support_path_count: 1,
@@ -4015,6 +4428,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_args_reads_ann_index_compare_list() {
+        let config = parse_args(
+            [
+                "--profile",
+                "smoke",
+                "--ann-index-compare",
+                "ivf:32:4,pq:8:16",
+            ]
+            .into_iter()
+            .map(str::to_string),
+        )
+        .expect("parse should succeed");
+        assert_eq!(
+            config.ann_index_compare,
+            vec![
+                AnnIndexKind::Ivf {
+                    lists: 32,
+                    probes: 4
+                },
+                AnnIndexKind::ProductQuantization {
+                    subspaces: 8,
+                    centroids: 16
+                },
+            ]
+        );
+        let err = parse_args(
+            ["--ann-index-compare", "ivf:32"]
+                .into_iter()
+                .map(str::to_string),
+        )
+        .expect_err("parse should fail");
+        assert!(err.contains("Invalid ANN index kind"));
+    }
+
     #[test]
     fn parse_args_rejects_invalid_require_vector_backend_value() {
         let err = parse_args(
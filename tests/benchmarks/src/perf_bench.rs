@@ -22,10 +22,11 @@ use std::{
     collections::BTreeMap,
     env, fmt, fs,
     path::PathBuf,
-    process, time::{Instant, SystemTime, UNIX_EPOCH},
+    process,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
-use schema::{Claim, Evidence, RetrievalRequest, Stance, StanceMode};
+use schema::{Claim, ClaimId, Evidence, RetrievalRequest, Stance, TenantId};
 use serde::Serialize;
 use store::{AnnTuningConfig, FileWal, InMemoryStore};
 
@@ -116,10 +117,7 @@ where
             "--all" => out.all = true,
             "--help" | "-h" => out.help = true,
             other => {
-                return Err(format!(
-                    "Unknown argument '{other}'.\n\n{}",
-                    usage_text()
-                ));
+                return Err(format!("Unknown argument '{other}'.\n\n{}", usage_text()));
             }
         }
     }
@@ -273,8 +271,8 @@ impl From<&BenchResult> for BenchResultJson {
 
 fn make_claim(claim_id: &str, tenant_id: &str, text: &str, confidence: f32) -> Claim {
     Claim {
-        claim_id: claim_id.to_string(),
-        tenant_id: tenant_id.to_string(),
+        claim_id: claim_id.into(),
+        tenant_id: tenant_id.into(),
         canonical_text: text.to_string(),
         confidence,
         event_time_unix: None,
@@ -285,13 +283,17 @@ fn make_claim(claim_id: &str, tenant_id: &str, text: &str, confidence: f32) -> C
         valid_to: None,
         created_at: None,
         updated_at: None,
+        metadata: Vec::new(),
+        visibility_labels: Vec::new(),
+        language: None,
+        collection: None,
     }
 }
 
 fn make_evidence(evidence_id: &str, claim_id: &str) -> Evidence {
     Evidence {
-        evidence_id: evidence_id.to_string(),
-        claim_id: claim_id.to_string(),
+        evidence_id: evidence_id.into(),
+        claim_id: claim_id.into(),
         source_id: format!("source://perf/{evidence_id}"),
         stance: Stance::Supports,
         source_quality: 0.9,
@@ -301,12 +303,17 @@ fn make_evidence(evidence_id: &str, claim_id: &str) -> Evidence {
         doc_id: None,
         extraction_model: None,
         ingested_at: None,
+        language: None,
+        stance_strength: None,
+        negated: false,
     }
 }
 
 fn fixture_vector(seed: usize, dim: usize) -> Vec<f32> {
     let mut out = Vec::with_capacity(dim);
-    let mut state = (seed as u64).wrapping_mul(6364136223846793005).wrapping_add(1);
+    let mut state = (seed as u64)
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1);
     for _ in 0..dim {
         state = state
             .wrapping_mul(2862933555777941757)
@@ -345,12 +352,7 @@ fn ingest_one_in_memory(store: &mut InMemoryStore, tenant: &str, index: usize) {
         .expect("in-memory ingest should succeed");
 }
 
-fn ingest_one_persistent(
-    store: &mut InMemoryStore,
-    wal: &mut FileWal,
-    tenant: &str,
-    index: usize,
-) {
+fn ingest_one_persistent(store: &mut InMemoryStore, wal: &mut FileWal, tenant: &str, index: usize) {
     let id = format!("claim-perf-pw-{index}");
     let claim = make_claim(&id, tenant, "perf persistent ingest claim", 0.9);
     let evidence = make_evidence(&format!("evd-perf-pw-{index}"), &id);
@@ -401,7 +403,7 @@ fn scenario_ingest_throughput_sequential(
         let tmp = temp_dir("ingest-wal");
         fs::create_dir_all(&tmp).map_err(|e| format!("mkdir temp: {e}"))?;
         let wal_path = tmp.join("bench.wal");
-        let mut wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e:?}"))?;
+        let mut wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e}"))?;
         let mut store = InMemoryStore::new();
         let mut counter: usize = 0;
         for _ in 0..warmup {
@@ -449,15 +451,10 @@ fn scenario_retrieve_throughput_lexical(
         let evidence = make_evidence(&format!("evd-rl-{i}"), &id);
         store
             .ingest_bundle(claim, vec![evidence], vec![])
-            .map_err(|e| format!("fixture ingest at {i}: {e:?}"))?;
+            .map_err(|e| format!("fixture ingest at {i}: {e}"))?;
     }
 
-    let req = RetrievalRequest {
-        tenant_id: LEX_TENANT.to_string(),
-        query: "alice operation status".to_string(),
-        top_k: 10,
-        stance_mode: StanceMode::Balanced,
-    };
+    let req = RetrievalRequest::new(LEX_TENANT.to_string(), "alice operation status", 10);
 
     for _ in 0..warmup {
         let _ = store.retrieve(&req);
@@ -496,19 +493,18 @@ fn scenario_retrieve_throughput_semantic(
         let evidence = make_evidence(&format!("evd-rs-{i}"), &id);
         store
             .ingest_bundle(claim, vec![evidence], vec![])
-            .map_err(|e| format!("fixture ingest at {i}: {e:?}"))?;
+            .map_err(|e| format!("fixture ingest at {i}: {e}"))?;
         store
-            .upsert_claim_vector(&id, fixture_vector(i, SEMANTIC_DIM))
-            .map_err(|e| format!("vector upsert at {i}: {e:?}"))?;
+            .upsert_claim_vector(&ClaimId::from(id), fixture_vector(i, SEMANTIC_DIM))
+            .map_err(|e| format!("vector upsert at {i}: {e}"))?;
     }
 
     let query_vector = fixture_vector(0, SEMANTIC_DIM);
-    let req = RetrievalRequest {
-        tenant_id: SEM_TENANT.to_string(),
-        query: "alice operation status".to_string(),
-        top_k: SEMANTIC_TOP_K,
-        stance_mode: StanceMode::Balanced,
-    };
+    let req = RetrievalRequest::new(
+        SEM_TENANT.to_string(),
+        "alice operation status",
+        SEMANTIC_TOP_K,
+    );
 
     for _ in 0..warmup {
         let _ = store.retrieve_semantic(&req, &query_vector);
@@ -544,17 +540,17 @@ fn scenario_ann_search_throughput_at_scale(
         let claim = make_claim(&id, ANN_TENANT, &format!("ann fixture text {i}"), 0.9);
         store
             .ingest_bundle(claim, vec![], vec![])
-            .map_err(|e| format!("ann fixture ingest at {i}: {e:?}"))?;
+            .map_err(|e| format!("ann fixture ingest at {i}: {e}"))?;
         store
-            .upsert_claim_vector(&id, fixture_vector(i + 17, ANN_DIM))
-            .map_err(|e| format!("ann vector upsert at {i}: {e:?}"))?;
+            .upsert_claim_vector(&ClaimId::from(id), fixture_vector(i + 17, ANN_DIM))
+            .map_err(|e| format!("ann vector upsert at {i}: {e}"))?;
     }
 
     let warmup_queries: Vec<Vec<f32>> = (0..warmup)
         .map(|i| fixture_vector(2_000_000 + i, ANN_DIM))
         .collect();
     for q in &warmup_queries {
-        let _ = store.ann_vector_top_candidates(ANN_TENANT, q, ANN_TOP_N);
+        let _ = store.ann_vector_top_candidates(&TenantId::from(ANN_TENANT), q, ANN_TOP_N);
     }
 
     let queries: Vec<Vec<f32>> = (0..iterations)
@@ -563,7 +559,7 @@ fn scenario_ann_search_throughput_at_scale(
     let mut latencies = Vec::with_capacity(iterations);
     for q in &queries {
         let start = Instant::now();
-        let _ = store.ann_vector_top_candidates(ANN_TENANT, q, ANN_TOP_N);
+        let _ = store.ann_vector_top_candidates(&TenantId::from(ANN_TENANT), q, ANN_TOP_N);
         latencies.push(start.elapsed().as_micros() as u64);
     }
 
@@ -572,7 +568,10 @@ fn scenario_ann_search_throughput_at_scale(
             .with_extra("fixture_size", ANN_VECTORS.to_string())
             .with_extra("vector_dim", ANN_DIM.to_string())
             .with_extra("top_n", ANN_TOP_N.to_string())
-            .with_extra("note", "ANN graph build is O(N^2); scale reduced from 100k spec for build feasibility"),
+            .with_extra(
+                "note",
+                "ANN graph build is O(N^2); scale reduced from 100k spec for build feasibility",
+            ),
     ])
 }
 
@@ -587,7 +586,7 @@ fn scenario_wal_replay_throughput(
     // Setup: ingest WAL_REPLAY_FIXTURE_CLAIMS claims into a FileWal with
     // sync_every_records=1 (the default). Then drop everything.
     {
-        let mut wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e:?}"))?;
+        let mut wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e}"))?;
         let mut store = InMemoryStore::new();
         for i in 0..WAL_REPLAY_FIXTURE_CLAIMS {
             let id = format!("claim-wr-{i}");
@@ -595,7 +594,7 @@ fn scenario_wal_replay_throughput(
             let evidence = make_evidence(&format!("evd-wr-{i}"), &id);
             store
                 .ingest_bundle_persistent(&mut wal, claim, vec![evidence], vec![])
-                .map_err(|e| format!("wal fixture ingest at {i}: {e:?}"))?;
+                .map_err(|e| format!("wal fixture ingest at {i}: {e}"))?;
         }
         // Drop store and wal so the file is closed and the OS page cache
         // doesn't pre-warm the replay read path.
@@ -603,25 +602,25 @@ fn scenario_wal_replay_throughput(
 
     // Warmup replays.
     for _ in 0..warmup {
-        let wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e:?}"))?;
+        let wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e}"))?;
         let _ = InMemoryStore::load_from_wal_with_stats_and_ann_tuning(
             &wal,
             AnnTuningConfig::default(),
         )
-        .map_err(|e| format!("warmup replay: {e:?}"))?;
+        .map_err(|e| format!("warmup replay: {e}"))?;
     }
 
     // Measured replays.
     let mut latencies = Vec::with_capacity(iterations);
     let mut last_record_count: usize = 0;
     for _ in 0..iterations {
-        let wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e:?}"))?;
+        let wal = FileWal::open(&wal_path).map_err(|e| format!("open wal: {e}"))?;
         let start = Instant::now();
         let (_, stats) = InMemoryStore::load_from_wal_with_stats_and_ann_tuning(
             &wal,
             AnnTuningConfig::default(),
         )
-        .map_err(|e| format!("replay: {e:?}"))?;
+        .map_err(|e| format!("replay: {e}"))?;
         latencies.push(start.elapsed().as_micros() as u64);
         last_record_count = stats.replay.wal_records + stats.replay.snapshot_records;
     }
@@ -675,10 +674,7 @@ fn main() {
 
     let selected: Vec<String> = if let Some(scenario) = args.scenario.as_deref() {
         if !ALL_SCENARIOS.contains(&scenario) {
-            eprintln!(
-                "Unknown scenario '{scenario}'. Valid: {:?}",
-                ALL_SCENARIOS
-            );
+            eprintln!("Unknown scenario '{scenario}'. Valid: {:?}", ALL_SCENARIOS);
             process::exit(2);
         }
         vec![scenario.to_string()]